@@ -6,6 +6,7 @@
 use std::collections::HashMap;
 use std::time::{Duration, Instant};
 use crate::errors::ConsensusError;
+use crate::metrics::{self, ConsensusMetrics};
 use crate::reputation::{NodeReputation, ReputationConfig, DoubleSignEvidence};
 use serde::{Deserialize, Serialize};
 
@@ -119,6 +120,12 @@ pub struct CommitRevealConsensus {
     reputation: NodeReputation,
     current_round: Option<RoundState>,
     node_id: String,
+    /// When the active round started (round duration metric)
+    round_started_at: Instant,
+    /// Whether the active round's outcome has been counted
+    round_resolved: bool,
+    rounds_finalized: u64,
+    rounds_failed: u64,
 }
 
 impl CommitRevealConsensus {
@@ -131,15 +138,25 @@ impl CommitRevealConsensus {
             reputation,
             current_round: None,
             node_id,
+            round_started_at: Instant::now(),
+            round_resolved: true,
+            rounds_finalized: 0,
+            rounds_failed: 0,
         }
     }
     
     /// Start new consensus round
     pub fn start_round(&mut self, participants: Vec<String>) -> Result<u64, ConsensusError> {
         if participants.len() < self.config.min_participants {
+            println!("[CONSENSUS] ❌ Round failed to start: {}/{} participants", 
+                     participants.len(), self.config.min_participants);
+            self.count_round(false, 0.0);
             return Err(ConsensusError::InsufficientNodes);
         }
         
+        // A round replaced before finalizing never produced a leader
+        self.resolve_round(false);
+        
         let round_number = self.current_round
             .as_ref()
             .map(|r| r.round_number + 1)
@@ -156,9 +173,45 @@ impl CommitRevealConsensus {
         };
         
         self.current_round = Some(round_state);
+        self.round_started_at = Instant::now();
+        self.round_resolved = false;
+        self.metrics().publish();
         Ok(round_number)
     }
     
+    /// Current round, phase, participation and round outcome counters
+    pub fn metrics(&self) -> ConsensusMetrics {
+        let round = self.current_round.as_ref();
+        ConsensusMetrics {
+            current_round: round.map_or(0, |r| r.round_number),
+            phase: round.map(|r| r.phase.clone()),
+            commits_received: round.map_or(0, |r| r.commits.len()),
+            reveals_received: round.map_or(0, |r| r.reveals.len()),
+            rounds_finalized_total: self.rounds_finalized,
+            rounds_failed_total: self.rounds_failed,
+        }
+    }
+    
+    /// Count the active round as finalized or failed, once per round
+    fn resolve_round(&mut self, finalized: bool) {
+        if self.current_round.is_none() || self.round_resolved {
+            return;
+        }
+        self.round_resolved = true;
+        self.count_round(finalized, self.round_started_at.elapsed().as_secs_f64());
+    }
+    
+    fn count_round(&mut self, finalized: bool, duration_secs: f64) {
+        if finalized {
+            self.rounds_finalized += 1;
+            metrics::record_successful_round(duration_secs);
+        } else {
+            self.rounds_failed += 1;
+            metrics::record_failed_round(duration_secs);
+        }
+        self.metrics().publish();
+    }
+    
     /// Process commit from validator (simplified version)
     pub async fn process_commit(&mut self, commit: Commit) -> Result<(), ConsensusError> {
         // Validate signature (simplified) - do this before any borrows
@@ -209,6 +262,7 @@ impl CommitRevealConsensus {
             state.phase_duration = self.config.reveal_phase_duration;
         }
         
+        self.metrics().publish();
         Ok(())
     }
     
@@ -271,6 +325,7 @@ impl CommitRevealConsensus {
         let state = self.current_round.as_mut().ok_or(ConsensusError::NoActiveRound)?;
         state.reveals.insert(reveal.node_id.clone(), reveal);  // Store full Reveal with nonce
         
+        self.metrics().publish();
         Ok(())
     }
     
@@ -278,27 +333,42 @@ impl CommitRevealConsensus {
     pub fn advance_phase(&mut self) -> Result<ConsensusPhase, ConsensusError> {
         let state = self.current_round.as_mut().ok_or(ConsensusError::NoActiveRound)?;
         
-        match state.phase {
+        let next = match state.phase {
             ConsensusPhase::Commit => {
                 state.phase = ConsensusPhase::Reveal;
                 state.phase_start = Instant::now();
                 state.phase_duration = self.config.reveal_phase_duration;
-                Ok(ConsensusPhase::Reveal)
+                ConsensusPhase::Reveal
             }
             ConsensusPhase::Reveal => {
                 state.phase = ConsensusPhase::Finalize;
                 state.phase_start = Instant::now();
-                Ok(ConsensusPhase::Finalize)
+                ConsensusPhase::Finalize
             }
             ConsensusPhase::Finalize => {
+                // Leaving a round that never selected a leader
+                self.resolve_round(false);
                 self.current_round = None;
-                Ok(ConsensusPhase::Commit) // Ready for next round
+                ConsensusPhase::Commit // Ready for next round
             }
-        }
+        };
+        self.metrics().publish();
+        Ok(next)
     }
     
     /// PRODUCTION: Finalize round with Byzantine safety requirements
     pub fn finalize_round(&mut self) -> Result<String, ConsensusError> {
+        let result = self.try_finalize_round();
+        match &result {
+            Ok(_) => self.resolve_round(true),
+            // Calling again after finalizing is a phase error, not a failed round
+            Err(ConsensusError::InvalidPhase(_)) | Err(ConsensusError::NoActiveRound) => {}
+            Err(_) => self.resolve_round(false),
+        }
+        result
+    }
+    
+    fn try_finalize_round(&mut self) -> Result<String, ConsensusError> {
         // First get the leader without mutable borrow
         let leader = {
             let state = self.current_round.as_ref().ok_or(ConsensusError::NoActiveRound)?;
@@ -782,4 +852,33 @@ impl CommitRevealConsensus {
         self.submit_reveal(reveal)
     }
 
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn participants(count: usize) -> Vec<String> {
+        (0..count).map(|i| format!("node_{}", i)).collect()
+    }
+
+    #[test]
+    fn test_round_below_min_participants_counts_as_failed() {
+        let mut consensus = CommitRevealConsensus::new("node_0".to_string(), ConsensusConfig::default());
+        assert_eq!(consensus.metrics(), ConsensusMetrics::default());
+
+        assert!(matches!(consensus.start_round(participants(2)), Err(ConsensusError::InsufficientNodes)));
+        let metrics = consensus.metrics();
+        assert_eq!(metrics.rounds_failed_total, 1);
+        assert_eq!(metrics.rounds_finalized_total, 0);
+        assert_eq!(metrics.phase, None);
+
+        // A started round that reaches finalize without reveals fails once
+        let round = consensus.start_round(participants(3)).unwrap();
+        let metrics = consensus.metrics();
+        assert_eq!((metrics.current_round, metrics.phase), (round, Some(ConsensusPhase::Commit)));
+        consensus.advance_phase().unwrap();
+        assert!(consensus.finalize_round().is_err());
+        assert!(consensus.finalize_round().is_err());
+        assert_eq!(consensus.metrics().rounds_failed_total, 2);
+    }
+}
@@ -4,6 +4,9 @@
 //! Pool 1: Dynamic base emission with sharp drop halving
 //! Pool 2: Transaction fees (70% Super, 30% Full, 0% Light)
 //! Pool 3: Activation pool (ONLY in Phase 2)
+//!
+//! Every pool is split with integer arithmetic only (`distribute_largest_remainder`),
+//! so all nodes compute identical per-node amounts that sum exactly to the pool.
 
 use std::collections::HashMap;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
@@ -13,6 +16,9 @@ use crate::errors::ConsensusError;
 /// Minimum claim amount (1 QNC in nanoQNC) to prevent spam
 const MIN_CLAIM_AMOUNT: u64 = 1_000_000_000; // 1 QNC = 10^9 nanoQNC
 
+/// Reward window length (4 hours)
+const REWARD_WINDOW_SECS: u64 = 4 * 60 * 60;
+
 /// QNet economic phases
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum QNetPhase {
@@ -28,6 +34,60 @@ pub enum NodeType {
     Super,
 }
 
+/// Reward pool a share is paid from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PoolSource {
+    /// Pool 1: scheduled base emission (halving), minted fresh every window
+    BaseEmission,
+    /// Pool 2: transaction fees, paid to Full and Super nodes only
+    TransactionFees,
+    /// Pool 3: QNC spent on activations, distributed in Phase 2 only
+    ActivationPool,
+}
+
+impl PoolSource {
+    pub const ALL: [PoolSource; 3] = [Self::BaseEmission, Self::TransactionFees, Self::ActivationPool];
+
+    /// Whether a node of `node_type` receives a share of this pool in `phase`
+    pub fn is_eligible(&self, node_type: &NodeType, phase: &QNetPhase) -> bool {
+        match self {
+            Self::BaseEmission => true,
+            Self::TransactionFees => *node_type != NodeType::Light,
+            Self::ActivationPool => *phase == QNetPhase::Phase2,
+        }
+    }
+}
+
+/// Amount per reward pool in nanoQNC
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PoolBalances {
+    pub base_emission: u64,
+    pub transaction_fees: u64,
+    pub activation_pool: u64,
+}
+
+impl PoolBalances {
+    pub fn get(&self, source: PoolSource) -> u64 {
+        match source {
+            PoolSource::BaseEmission => self.base_emission,
+            PoolSource::TransactionFees => self.transaction_fees,
+            PoolSource::ActivationPool => self.activation_pool,
+        }
+    }
+
+    fn get_mut(&mut self, source: PoolSource) -> &mut u64 {
+        match source {
+            PoolSource::BaseEmission => &mut self.base_emission,
+            PoolSource::TransactionFees => &mut self.transaction_fees,
+            PoolSource::ActivationPool => &mut self.activation_pool,
+        }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.base_emission + self.transaction_fees + self.activation_pool
+    }
+}
+
 /// Ping success requirements for different node types
 #[derive(Debug, Clone)]
 pub struct PingRequirements {
@@ -58,6 +118,92 @@ impl PingRequirements {
     }
 }
 
+/// Relative Pool 1 / Pool 3 share per node type
+/// Distribution is deterministic, so every node must use identical multipliers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RewardMultipliers {
+    pub light: f64,
+    pub full: f64,
+    pub super_: f64,
+}
+
+/// Fixed-point precision for multiplier weights (avoids float drift between nodes)
+const MULTIPLIER_PRECISION: f64 = 1_000_000.0;
+
+impl Default for RewardMultipliers {
+    fn default() -> Self {
+        // Equal shares for all node types
+        Self { light: 1.0, full: 1.0, super_: 1.0 }
+    }
+}
+
+impl RewardMultipliers {
+    /// Parse `light,full,super`, e.g. "1.0,1.5,2.0"
+    pub fn parse(value: &str) -> Result<Self, ConsensusError> {
+        let parts: Vec<f64> = value.split(',')
+            .map(|part| part.trim().parse::<f64>())
+            .collect::<Result<_, _>>()
+            .map_err(|e| ConsensusError::InvalidOperation(format!("Invalid reward multipliers '{}': {}", value, e)))?;
+        if parts.len() != 3 {
+            return Err(ConsensusError::InvalidOperation(format!("Expected light,full,super multipliers, got '{}'", value)));
+        }
+        let multipliers = Self { light: parts[0], full: parts[1], super_: parts[2] };
+        multipliers.validate()?;
+        Ok(multipliers)
+    }
+    
+    /// All multipliers must be finite and positive
+    pub fn validate(&self) -> Result<(), ConsensusError> {
+        for (name, value) in [("light", self.light), ("full", self.full), ("super", self.super_)] {
+            if !value.is_finite() || value <= 0.0 {
+                return Err(ConsensusError::InvalidOperation(format!("Reward multiplier for {} must be positive, got {}", name, value)));
+            }
+        }
+        Ok(())
+    }
+    
+    /// Integer weight for a node type
+    fn weight(&self, node_type: &NodeType) -> u128 {
+        let multiplier = match node_type {
+            NodeType::Light => self.light,
+            NodeType::Full => self.full,
+            NodeType::Super => self.super_,
+        };
+        (multiplier * MULTIPLIER_PRECISION).round() as u128
+    }
+}
+
+/// Split `total` in proportion to `weights` so the parts sum exactly to `total`
+/// (largest-remainder method, integer only):
+/// 1. each part gets `floor(total * weight / total_weight)`
+/// 2. the units left over go one each to the parts with the largest
+///    remainders `(total * weight) % total_weight`
+/// 3. equal remainders are broken by position, so callers must pass weights in
+///    a canonical order (rewards use node_id order)
+///
+/// Zero-weight entries always get 0; if every weight is 0 nothing is distributed.
+pub fn distribute_largest_remainder(total: u64, weights: &[u128]) -> Vec<u64> {
+    let total_weight: u128 = weights.iter().sum();
+    if total_weight == 0 {
+        return vec![0; weights.len()];
+    }
+    
+    let mut parts = Vec::with_capacity(weights.len());
+    let mut remainders = Vec::with_capacity(weights.len());
+    for (index, weight) in weights.iter().enumerate() {
+        let scaled = total as u128 * weight;
+        parts.push((scaled / total_weight) as u64);
+        remainders.push((scaled % total_weight, index));
+    }
+    
+    let leftover = total - parts.iter().sum::<u64>();
+    remainders.sort_by(|(a, ia), (b, ib)| b.cmp(a).then(ia.cmp(ib)));
+    for &(_, index) in remainders.iter().take(leftover as usize) {
+        parts[index] += 1;
+    }
+    parts
+}
+
 /// Ping attempt record
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PingAttempt {
@@ -142,6 +288,19 @@ pub struct PhaseAwareReward {
     pub total_reward: u64,
 }
 
+/// Outcome of processing a single reward window
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowProcessingResult {
+    pub window_start: u64,
+    pub eligible_nodes: u32,
+    /// Rewards assigned in THIS window only (never includes earlier unclaimed rewards)
+    pub window_emission: u64,
+    /// `window_emission` split by the pool it was paid from
+    pub pool_emission: PoolBalances,
+    /// True if the window had already been processed and was skipped
+    pub already_processed: bool,
+}
+
 /// Reward claim result
 #[derive(Debug, Clone)]
 pub struct RewardClaimResult {
@@ -151,12 +310,23 @@ pub struct RewardClaimResult {
     pub next_claim_time: u64,
 }
 
+/// What a node can currently claim, for operator queries
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RewardClaimStatus {
+    pub pending: u64,
+    /// Start of the most recently processed reward window
+    pub last_window_processed: u64,
+    pub claimable_now: bool,
+    pub phase: u8,
+}
+
 /// Phase-aware three-pool reward manager
 pub struct PhaseAwareRewardManager {
     /// Genesis timestamp (when blockchain started)
     genesis_timestamp: u64,
     
-    /// Current reward window (4 hours)
+    /// Window cursor: start of the reward window currently open (4 hours).
+    /// Every window before the cursor has been processed exactly once.
     current_window_start: u64,
     
     /// Node ping histories by node_id
@@ -174,17 +344,21 @@ pub struct PhaseAwareRewardManager {
     /// Storage handler path for RocksDB persistence
     storage_path: Option<String>,
     
-    /// Pool 2: Transaction fees
-    pool2_transaction_fees: u64,
+    /// Undistributed balances of the accumulating pools (Pool 2 fees, Pool 3 activations).
+    /// Pool 1 is minted per window and never holds a balance here.
+    pool_balances: PoolBalances,
     
-    /// Pool 3: Activation pool (only works in Phase 2)
-    pool3_activation_pool: u64,
+    /// Everything paid out so far, per pool
+    pool_distributed: PoolBalances,
     
     /// Phase transition parameters
     dev_burn_percentage: f64,  // Current 1DEV burn percentage
     
     /// Minimum claim interval (prevent spam)
     min_claim_interval: Duration,
+    
+    /// Per-node-type weights for Pool 1 and Pool 3 distribution
+    reward_multipliers: RewardMultipliers,
 }
 
 impl PhaseAwareRewardManager {
@@ -200,11 +374,12 @@ impl PhaseAwareRewardManager {
             pending_rewards: HashMap::new(),
             last_claim_time: HashMap::new(),
             storage_path: None,
-            pool2_transaction_fees: 0,
-            pool3_activation_pool: 0,
+            pool_balances: PoolBalances::default(),
+            pool_distributed: PoolBalances::default(),
             dev_burn_percentage: 0.0,
 
             min_claim_interval: Duration::from_secs(3600), // 1 hour minimum
+            reward_multipliers: RewardMultipliers::default(),
         }
     }
     
@@ -216,7 +391,7 @@ impl PhaseAwareRewardManager {
             .as_secs();
             
         // Round down to nearest 4-hour boundary
-        now - (now % (4 * 60 * 60))
+        now - (now % REWARD_WINDOW_SECS)
     }
     
     /// Calculate years since genesis timestamp
@@ -278,6 +453,18 @@ impl PhaseAwareRewardManager {
         // in get_current_phase() and get_reward_stats(), so this parameter is ignored
     }
     
+    /// Set per-node-type reward multipliers (must match across all nodes)
+    pub fn set_reward_multipliers(&mut self, multipliers: RewardMultipliers) -> Result<(), ConsensusError> {
+        multipliers.validate()?;
+        self.reward_multipliers = multipliers;
+        Ok(())
+    }
+    
+    /// Current per-node-type reward multipliers
+    pub fn get_reward_multipliers(&self) -> &RewardMultipliers {
+        &self.reward_multipliers
+    }
+    
     /// FIXED: Register node with wallet address for reward ownership
     /// Set storage path for RocksDB persistence (for scalability)
     pub fn set_storage_path(&mut self, path: String) {
@@ -289,9 +476,11 @@ impl PhaseAwareRewardManager {
         let window_start = Self::get_current_window_start();
         
         // Check if we need to start a new reward window
+        // Goes through the cursor so the closed window is never processed twice
         if window_start > self.current_window_start {
-            self.process_reward_window()?;
-            self.current_window_start = window_start;
+            let closed_window = self.current_window_start;
+            self.process_window_at(closed_window)?;
+            self.current_window_start = self.current_window_start.max(window_start);
         }
         
         // FIXED: Store wallet ownership for reward claims
@@ -322,8 +511,11 @@ impl PhaseAwareRewardManager {
         Ok(())
     }
     
-    /// Process current reward window and calculate rewards
-    fn process_reward_window(&mut self) -> Result<(), ConsensusError> {
+    /// Process the open reward window (at the cursor), calculate rewards and
+    /// advance the cursor to the next window
+    fn process_reward_window(&mut self) -> Result<WindowProcessingResult, ConsensusError> {
+        let window_start = self.current_window_start;
+        self.current_window_start = window_start + REWARD_WINDOW_SECS;
         let current_phase = self.get_current_phase();
         
         // Count eligible nodes (those who met ping requirements)
@@ -346,116 +538,173 @@ impl PhaseAwareRewardManager {
         if total_eligible_nodes == 0 {
             // No eligible nodes, skip reward distribution
             self.ping_histories.clear();
-            return Ok(());
+            return Ok(WindowProcessingResult {
+                window_start,
+                eligible_nodes: 0,
+                window_emission: 0,
+                pool_emission: PoolBalances::default(),
+                already_processed: false,
+            });
         }
         
-        // Calculate rewards for each eligible node
-        for (node_id, ping_history) in &self.ping_histories {
-            if ping_history.meets_requirements() {
-                let reward = self.calculate_node_reward(
-                    &ping_history.node_type,
-                    &current_phase,
-                    total_eligible_nodes,
-                    eligible_full_nodes,
-                    eligible_super_nodes,
-                );
-                
-                self.pending_rewards.insert(node_id.clone(), reward);
-            }
+        // Canonical node_id order: remainder ties must resolve the same on every node
+        let mut eligible: Vec<(String, NodeType)> = self.ping_histories.iter()
+            .filter(|(_, history)| history.meets_requirements())
+            .map(|(node_id, history)| (node_id.clone(), history.node_type.clone()))
+            .collect();
+        eligible.sort_by(|(a, _), (b, _)| a.cmp(b));
+        
+        let weights_for = |source: PoolSource| -> Vec<u128> {
+            eligible.iter()
+                .map(|(_, node_type)| if source.is_eligible(node_type, &current_phase) {
+                    self.reward_multipliers.weight(node_type)
+                } else {
+                    0
+                })
+                .collect()
+        };
+        let type_weights = |wanted: NodeType| -> Vec<u128> {
+            eligible.iter().map(|(_, node_type)| u128::from(*node_type == wanted)).collect()
+        };
+        
+        // Pool 1: Dynamic base emission (weighted share for all eligible nodes)
+        let pool1 = distribute_largest_remainder(self.calculate_pool1_base_emission(), &weights_for(PoolSource::BaseEmission));
+        
+        // Pool 2: Transaction fees - 30% equally to Full nodes, the rest equally to Super nodes
+        let fees = self.pool_balances.transaction_fees;
+        let full_fees = (u128::from(fees) * 30 / 100) as u64;
+        let pool2_full = distribute_largest_remainder(full_fees, &type_weights(NodeType::Full));
+        let pool2_super = distribute_largest_remainder(fees - full_fees, &type_weights(NodeType::Super));
+        
+        // Pool 3: Activation pool (ONLY in Phase 2, weighted share for all eligible nodes)
+        let pool3 = distribute_largest_remainder(self.pool_balances.activation_pool, &weights_for(PoolSource::ActivationPool));
+        
+        let mut pool_emission = PoolBalances::default();
+        for (i, (node_id, _)) in eligible.into_iter().enumerate() {
+            let pool2_transaction_fees = pool2_full[i] + pool2_super[i];
+            pool_emission.base_emission += pool1[i];
+            pool_emission.transaction_fees += pool2_transaction_fees;
+            pool_emission.activation_pool += pool3[i];
+            self.pending_rewards.insert(node_id, PhaseAwareReward {
+                current_phase: current_phase.clone(),
+                pool1_base_emission: pool1[i],
+                pool2_transaction_fees,
+                pool3_activation_bonus: pool3[i],
+                total_reward: pool1[i] + pool2_transaction_fees + pool3[i],
+            });
         }
         
         // Clear ping histories for next window
         self.ping_histories.clear();
         
-        // Reset transaction fees (they're distributed)
-        self.pool2_transaction_fees = 0;
-        
-        // Reset Pool 3 if Phase 2 (it's distributed)
-        if current_phase == QNetPhase::Phase2 {
-            self.pool3_activation_pool = 0;
+        // Debit what was paid out; a share with no eligible recipient stays in its pool
+        for source in PoolSource::ALL {
+            let paid = pool_emission.get(source);
+            if source != PoolSource::BaseEmission {
+                *self.pool_balances.get_mut(source) -= paid;
+            }
+            *self.pool_distributed.get_mut(source) += paid;
         }
         
-        Ok(())
+        Ok(WindowProcessingResult {
+            window_start,
+            eligible_nodes: total_eligible_nodes,
+            window_emission: pool_emission.total(),
+            pool_emission,
+            already_processed: false,
+        })
     }
     
-    /// Calculate reward for a single node
-    fn calculate_node_reward(
-        &self,
-        node_type: &NodeType,
-        current_phase: &QNetPhase,
-        total_eligible_nodes: u32,
-        eligible_full_nodes: u32,
-        eligible_super_nodes: u32,
-    ) -> PhaseAwareReward {
-        // Pool 1: Dynamic base emission (equal share for all eligible nodes)
-        let pool1_base_emission = if total_eligible_nodes > 0 {
-            self.calculate_pool1_base_emission() / total_eligible_nodes as u64
-        } else {
-            0
-        };
+    /// Process the reward window starting at `window_start` exactly once.
+    ///
+    /// Every window advances the cursor, including windows with no pings or no
+    /// eligible nodes: those are recorded as processed with zero emission. Empty
+    /// windows are NOT carried forward, so the next active window emits only its
+    /// own single-window amount rather than a backlog. Windows before the cursor
+    /// are skipped, which prevents double emission on repeated calls.
+    pub fn process_window_at(&mut self, window_start: u64) -> Result<WindowProcessingResult, ConsensusError> {
+        if self.is_window_processed(window_start) {
+            return Ok(WindowProcessingResult {
+                window_start,
+                eligible_nodes: 0,
+                window_emission: 0,
+                pool_emission: PoolBalances::default(),
+                already_processed: true,
+            });
+        }
         
-        // Pool 2: Transaction fees (only Full and Super nodes)
-        let pool2_transaction_fees = match node_type {
-            NodeType::Light => 0,
-            NodeType::Full => {
-                if eligible_full_nodes > 0 {
-                    (self.pool2_transaction_fees * 30 / 100) / eligible_full_nodes as u64
-                } else {
-                    0
-                }
+        // Windows skipped between the cursor and `window_start` emitted nothing
+        self.current_window_start = window_start;
+        self.process_reward_window()
+    }
+    
+    /// Check if the reward window starting at `window_start` was already processed
+    pub fn is_window_processed(&self, window_start: u64) -> bool {
+        window_start < self.current_window_start
+    }
+    
+    /// Get the window cursor (start of the open reward window) for persistence
+    pub fn get_window_cursor(&self) -> u64 {
+        self.current_window_start
+    }
+    
+    /// Restore the window cursor from storage (for node restart recovery)
+    /// The cursor never moves backwards, so a restored cursor can't reopen a processed window
+    pub fn restore_window_cursor(&mut self, cursor: u64) {
+        self.current_window_start = self.current_window_start.max(cursor);
+    }
+    
+    /// Credit `amount` to an accumulating pool for distribution in the next window
+    pub fn credit_pool(&mut self, source: PoolSource, amount: u64) -> Result<(), ConsensusError> {
+        match (source, self.get_current_phase()) {
+            (PoolSource::BaseEmission, _) => {
+                return Err(ConsensusError::InvalidOperation("Pool 1 is minted by the emission schedule, not credited".to_string()));
             },
-            NodeType::Super => {
-                if eligible_super_nodes > 0 {
-                    (self.pool2_transaction_fees * 70 / 100) / eligible_super_nodes as u64
-                } else {
-                    0
-                }
+            (PoolSource::ActivationPool, QNetPhase::Phase1) => {
+                return Err(ConsensusError::InvalidOperation("Pool 3 disabled in Phase 1. Use 1DEV burn instead.".to_string()));
             },
-        };
-        
-        // Pool 3: Activation pool (ONLY in Phase 2, equal share for all eligible nodes)
-        let pool3_activation_bonus = match current_phase {
-            QNetPhase::Phase1 => 0, // Pool 3 DISABLED in Phase 1
-            QNetPhase::Phase2 => {
-                if total_eligible_nodes > 0 {
-                    self.pool3_activation_pool / total_eligible_nodes as u64
-                } else {
-                    0
-                }
-            }
-        };
-        
-        let total_reward = pool1_base_emission + pool2_transaction_fees + pool3_activation_bonus;
-        
-        PhaseAwareReward {
-            current_phase: current_phase.clone(),
-            pool1_base_emission,
-            pool2_transaction_fees,
-            pool3_activation_bonus,
-            total_reward,
+            _ => {}
         }
+        let balance = self.pool_balances.get_mut(source);
+        *balance = balance.checked_add(amount)
+            .ok_or_else(|| ConsensusError::InvalidOperation(format!(
+                "{:?} pool overflow: {} + {}", source, balance, amount
+            )))?;
+        Ok(())
     }
     
     /// Add transaction fees to Pool 2
-    pub fn add_transaction_fees(&mut self, amount: u64) {
-        self.pool2_transaction_fees += amount;
+    pub fn add_transaction_fees(&mut self, amount: u64) -> Result<(), ConsensusError> {
+        self.credit_pool(PoolSource::TransactionFees, amount)
     }
     
     /// Add activation QNC to Pool 3 (ONLY works in Phase 2)
     pub fn add_activation_qnc(&mut self, amount: u64) -> Result<(), ConsensusError> {
-        match self.get_current_phase() {
-            QNetPhase::Phase1 => {
-                // Pool 3 disabled in Phase 1
-                Err(ConsensusError::InvalidOperation("Pool 3 disabled in Phase 1. Use 1DEV burn instead.".to_string()))
-            },
-            QNetPhase::Phase2 => {
-                // Pool 3 enabled in Phase 2
-                self.pool3_activation_pool += amount;
-                Ok(())
-            }
+        self.credit_pool(PoolSource::ActivationPool, amount)
+    }
+    
+    /// Amount available from `source` in the open window
+    /// (Pool 1: this window's scheduled emission; Pools 2 and 3: undistributed balance)
+    pub fn pool_balance(&self, source: PoolSource) -> u64 {
+        match source {
+            PoolSource::BaseEmission => self.calculate_pool1_base_emission(),
+            _ => self.pool_balances.get(source),
         }
     }
     
+    /// Amounts available from every pool in the open window
+    pub fn pool_balances(&self) -> PoolBalances {
+        PoolBalances {
+            base_emission: self.calculate_pool1_base_emission(),
+            ..self.pool_balances
+        }
+    }
+    
+    /// Total paid out per pool since this manager started
+    pub fn pool_distributed(&self) -> PoolBalances {
+        self.pool_distributed
+    }
+    
     /// FIXED: Claim rewards for a node - ONLY the owning wallet can claim
     pub fn claim_rewards(&mut self, node_id: &str, claimant_wallet: &str) -> RewardClaimResult {
         let current_time = SystemTime::now()
@@ -538,6 +787,31 @@ impl PhaseAwareRewardManager {
         }
     }
     
+    /// Claim status of a node: pending amount and whether `claim_rewards` would succeed now
+    pub fn get_claim_status(&self, node_id: &str) -> RewardClaimStatus {
+        let current_time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        self.claim_status_at(node_id, current_time)
+    }
+    
+    fn claim_status_at(&self, node_id: &str, current_time: u64) -> RewardClaimStatus {
+        let pending = self.pending_rewards.get(node_id).map(|r| r.total_reward).unwrap_or(0);
+        let interval_elapsed = self.last_claim_time.get(node_id)
+            .is_none_or(|last_claim| current_time.saturating_sub(*last_claim) >= self.min_claim_interval.as_secs());
+        
+        RewardClaimStatus {
+            pending,
+            last_window_processed: self.current_window_start.saturating_sub(REWARD_WINDOW_SECS),
+            claimable_now: self.node_ownership.contains_key(node_id) && pending >= MIN_CLAIM_AMOUNT && interval_elapsed,
+            phase: match self.get_current_phase() {
+                QNetPhase::Phase1 => 1,
+                QNetPhase::Phase2 => 2,
+            },
+        }
+    }
+    
     /// Get pending reward for a node
     pub fn get_pending_reward(&self, node_id: &str) -> Option<&PhaseAwareReward> {
         self.pending_rewards.get(node_id)
@@ -565,7 +839,7 @@ impl PhaseAwareRewardManager {
     
     /// Get Pool #2 transaction fees accumulated
     pub fn get_pool2_fees(&self) -> u64 {
-        self.pool2_transaction_fees
+        self.pool_balances.transaction_fees
     }
     
     /// Get current Pool 1 base emission (PUBLIC for validation)
@@ -577,7 +851,7 @@ impl PhaseAwareRewardManager {
     
     /// Reset Pool #2 fees after distribution
     pub fn reset_pool2_fees(&mut self) {
-        self.pool2_transaction_fees = 0;
+        self.pool_balances.transaction_fees = 0;
     }
     
     /// Get years since genesis timestamp
@@ -599,8 +873,9 @@ impl PhaseAwareRewardManager {
             current_phase,
             current_window_start: self.current_window_start,
             pool1_current_emission,
-            pool2_transaction_fees: self.pool2_transaction_fees,
-            pool3_activation_pool: self.pool3_activation_pool,
+            pool2_transaction_fees: self.pool_balances.transaction_fees,
+            pool3_activation_pool: self.pool_balances.activation_pool,
+            pool_distributed: self.pool_distributed,
             total_pending_rewards: total_pending,
             nodes_with_pending_rewards: self.pending_rewards.len(),
             active_ping_histories: self.ping_histories.len(),
@@ -610,7 +885,7 @@ impl PhaseAwareRewardManager {
     
     /// Force process current reward window (for testing)
     pub fn force_process_window(&mut self) -> Result<(), ConsensusError> {
-        self.process_reward_window()
+        self.process_reward_window().map(|_| ())
     }
     
     /// Get all pending rewards for automatic distribution
@@ -626,6 +901,16 @@ impl PhaseAwareRewardManager {
         self.node_ownership.get(node_id).cloned()
     }
     
+    /// Get node ids owned by a wallet address (sorted for stable output)
+    pub fn get_nodes_owned_by(&self, wallet_address: &str) -> Vec<String> {
+        let mut nodes: Vec<String> = self.node_ownership.iter()
+            .filter(|(_, owner)| owner.as_str() == wallet_address)
+            .map(|(node_id, _)| node_id.clone())
+            .collect();
+        nodes.sort();
+        nodes
+    }
+    
     /// Get all registered nodes with their types
     pub fn get_all_registered_nodes(&self) -> Vec<(String, NodeType)> {
         self.ping_histories.iter()
@@ -647,6 +932,7 @@ pub struct PhaseAwareRewardStats {
     pub pool1_current_emission: u64,
     pub pool2_transaction_fees: u64,
     pub pool3_activation_pool: u64,
+    pub pool_distributed: PoolBalances,
     pub total_pending_rewards: u64,
     pub nodes_with_pending_rewards: usize,
     pub active_ping_histories: usize,
@@ -656,4 +942,250 @@ pub struct PhaseAwareRewardStats {
 /// Production initialization
 pub fn create_production_phase_aware_rewards(genesis_timestamp: u64) -> PhaseAwareRewardManager {
     PhaseAwareRewardManager::new(genesis_timestamp)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fixed window far enough ahead that `register_node` never sees a wall-clock rollover
+    const BASE_WINDOW: u64 = 4_102_444_800; // 2100-01-01, aligned to a 4-hour boundary
+
+    fn recent_genesis() -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() - 24 * 60 * 60
+    }
+
+    fn manager_at(window_start: u64) -> PhaseAwareRewardManager {
+        let mut manager = PhaseAwareRewardManager::new(recent_genesis());
+        manager.restore_window_cursor(window_start);
+        manager
+    }
+
+    fn add_eligible_node(manager: &mut PhaseAwareRewardManager, node_id: &str, node_type: NodeType) {
+        manager.register_node(node_id.to_string(), node_type, format!("wallet_{}", node_id)).unwrap();
+        for _ in 0..10 {
+            manager.record_ping_attempt(node_id, true, 50).unwrap();
+        }
+    }
+
+    fn add_eligible_full_node(manager: &mut PhaseAwareRewardManager, node_id: &str) {
+        add_eligible_node(manager, node_id, NodeType::Full);
+    }
+
+    #[test]
+    fn test_empty_windows_advance_cursor_without_backlog() {
+        let mut manager = manager_at(BASE_WINDOW);
+        let single_window_emission = manager.get_pool1_base_emission();
+
+        // Two consecutive windows with no pings at all
+        for i in 0..2 {
+            let window = BASE_WINDOW + i * REWARD_WINDOW_SECS;
+            let result = manager.process_window_at(window).unwrap();
+            assert!(!result.already_processed);
+            assert_eq!(result.eligible_nodes, 0);
+            assert_eq!(result.window_emission, 0);
+            assert!(manager.is_window_processed(window));
+            assert_eq!(manager.get_window_cursor(), window + REWARD_WINDOW_SECS);
+        }
+
+        // Active window emits exactly one window's amount
+        let active = BASE_WINDOW + 2 * REWARD_WINDOW_SECS;
+        add_eligible_full_node(&mut manager, "full_node");
+        let result = manager.process_window_at(active).unwrap();
+        assert_eq!(result.eligible_nodes, 1);
+        assert_eq!(result.window_emission, single_window_emission);
+
+        // Re-processing the same window must not emit again
+        let repeat = manager.process_window_at(active).unwrap();
+        assert!(repeat.already_processed);
+        assert_eq!(repeat.window_emission, 0);
+    }
+
+    #[test]
+    fn test_unclaimed_reward_is_not_re_emitted() {
+        let mut manager = manager_at(BASE_WINDOW);
+        let single_window_emission = manager.get_pool1_base_emission();
+
+        add_eligible_full_node(&mut manager, "node_a");
+        assert_eq!(manager.process_window_at(BASE_WINDOW).unwrap().window_emission, single_window_emission);
+
+        // node_a leaves its reward unclaimed; only node_b is active in the next window
+        add_eligible_full_node(&mut manager, "node_b");
+        let result = manager.process_window_at(BASE_WINDOW + REWARD_WINDOW_SECS).unwrap();
+        assert_eq!(result.window_emission, single_window_emission);
+
+        let total_pending: u64 = manager.get_all_pending_rewards().iter().map(|(_, amount)| amount).sum();
+        assert_eq!(total_pending, 2 * single_window_emission);
+    }
+
+    #[test]
+    fn test_restored_cursor_prevents_emission_after_restart() {
+        let mut manager = manager_at(BASE_WINDOW);
+        add_eligible_full_node(&mut manager, "node_a");
+        manager.process_window_at(BASE_WINDOW).unwrap();
+        let persisted_cursor = manager.get_window_cursor();
+
+        // Restart inside the same window: a fresh manager restored from storage
+        let mut restarted = PhaseAwareRewardManager::new(recent_genesis());
+        restarted.restore_window_cursor(persisted_cursor);
+        assert!(restarted.is_window_processed(BASE_WINDOW));
+        add_eligible_full_node(&mut restarted, "node_a");
+        let result = restarted.process_window_at(BASE_WINDOW).unwrap();
+        assert!(result.already_processed);
+        assert_eq!(result.window_emission, 0);
+
+        // Restoring an older cursor never reopens a processed window
+        restarted.restore_window_cursor(BASE_WINDOW);
+        assert_eq!(restarted.get_window_cursor(), persisted_cursor);
+    }
+
+    fn window_rewards(multipliers: RewardMultipliers) -> (u64, u64, u64) {
+        let mut manager = manager_at(BASE_WINDOW);
+        manager.set_reward_multipliers(multipliers).unwrap();
+        add_eligible_node(&mut manager, "full_node", NodeType::Full);
+        add_eligible_node(&mut manager, "super_node", NodeType::Super);
+        let result = manager.process_window_at(BASE_WINDOW).unwrap();
+
+        let full = manager.get_pending_reward("full_node").unwrap().pool1_base_emission;
+        let super_ = manager.get_pending_reward("super_node").unwrap().pool1_base_emission;
+        (full, super_, result.window_emission)
+    }
+
+    #[test]
+    fn test_super_multiplier_scales_super_share_at_constant_emission() {
+        let single_window_emission = manager_at(BASE_WINDOW).get_pool1_base_emission();
+
+        let (full_equal, super_equal, total_equal) = window_rewards(RewardMultipliers::default());
+        assert_eq!(full_equal, super_equal);
+
+        // Super weight 3x Full: Super gets 3/4, Full 1/4 of the same Pool 1 emission
+        let (full_tuned, super_tuned, total_tuned) =
+            window_rewards(RewardMultipliers { light: 1.0, full: 1.0, super_: 3.0 });
+        assert!(super_tuned - single_window_emission * 3 / 4 <= 1);
+        assert!(full_tuned - single_window_emission / 4 <= 1);
+
+        // Rounding never loses or creates a nanoQNC
+        assert_eq!(full_equal + super_equal, single_window_emission);
+        assert_eq!(full_tuned + super_tuned, single_window_emission);
+        for total in [total_equal, total_tuned] {
+            assert_eq!(total, single_window_emission);
+        }
+
+        let invalid = RewardMultipliers { light: 1.0, full: 0.0, super_: 1.0 };
+        assert!(manager_at(BASE_WINDOW).set_reward_multipliers(invalid).is_err());
+        assert!(RewardMultipliers::parse("1,1.5,-2").is_err());
+        assert_eq!(RewardMultipliers::parse("1, 1.5, 2").unwrap().super_, 2.0);
+    }
+
+    #[test]
+    fn test_largest_remainder_sums_exactly_and_deterministically() {
+        // 100 over weights 1:1:1 -> 34, 33, 33 (tie broken by position)
+        assert_eq!(distribute_largest_remainder(100, &[1, 1, 1]), vec![34, 33, 33]);
+        // 10 over 1:2:4 -> floors 1, 2, 5 (remainders 3, 6, 5 of 7); leftover 2 -> 2nd and 3rd
+        assert_eq!(distribute_largest_remainder(10, &[1, 2, 4]), vec![1, 3, 6]);
+        assert_eq!(distribute_largest_remainder(7, &[0, 3, 0]), vec![0, 7, 0]);
+        assert_eq!(distribute_largest_remainder(7, &[0, 0]), vec![0, 0]);
+
+        // A window emission that doesn't divide evenly across seven mixed nodes
+        let run = || {
+            let mut manager = manager_at(BASE_WINDOW);
+            manager.set_reward_multipliers(RewardMultipliers { light: 1.0, full: 1.5, super_: 2.25 }).unwrap();
+            manager.add_transaction_fees(1_000_000_007).unwrap();
+            for (i, node_type) in [NodeType::Light, NodeType::Full, NodeType::Super].iter().cycle().take(7).enumerate() {
+                add_eligible_node(&mut manager, &format!("node_{}", i), node_type.clone());
+            }
+            let result = manager.process_window_at(BASE_WINDOW).unwrap();
+            let mut rewards = manager.get_all_pending_rewards();
+            rewards.sort();
+            (manager.get_pool1_base_emission(), result.window_emission, rewards)
+        };
+
+        let (emission, window_emission, rewards) = run();
+        assert_eq!(window_emission, emission + 1_000_000_007);
+        assert_eq!(rewards.iter().map(|(_, amount)| amount).sum::<u64>(), window_emission);
+        for _ in 0..5 {
+            assert_eq!(run().2, rewards);
+        }
+    }
+
+    #[test]
+    fn test_force_process_window_advances_cursor() {
+        let mut manager = manager_at(BASE_WINDOW);
+        add_eligible_full_node(&mut manager, "node_a");
+        manager.force_process_window().unwrap();
+        assert!(manager.is_window_processed(BASE_WINDOW));
+        assert!(manager.process_window_at(BASE_WINDOW).unwrap().already_processed);
+    }
+
+    #[test]
+    fn test_claim_status_reports_pending_and_claimability() {
+        let mut manager = manager_at(BASE_WINDOW);
+        add_eligible_full_node(&mut manager, "full_node");
+        manager.process_window_at(BASE_WINDOW).unwrap();
+
+        let status = manager.get_claim_status("full_node");
+        assert!(status.pending >= MIN_CLAIM_AMOUNT);
+        assert!(status.claimable_now);
+        assert_eq!(status.last_window_processed, BASE_WINDOW);
+        assert_eq!(status.phase, 1);
+
+        // After claiming, nothing is pending and the claim interval applies
+        assert!(manager.claim_rewards("full_node", "wallet_full_node").success);
+        let status = manager.get_claim_status("full_node");
+        assert_eq!(status.pending, 0);
+        assert!(!status.claimable_now);
+
+        // Unknown node: nothing to claim
+        let unknown = manager.get_claim_status("missing_node");
+        assert_eq!(unknown.pending, 0);
+        assert!(!unknown.claimable_now);
+    }
+
+    #[test]
+    fn test_fees_distributed_from_fee_pool_separately_from_emission() {
+        let mut manager = manager_at(BASE_WINDOW);
+        let single_window_emission = manager.get_pool1_base_emission();
+
+        manager.add_transaction_fees(1_500).unwrap();
+        manager.add_transaction_fees(500).unwrap();
+        assert_eq!(manager.pool_balance(PoolSource::TransactionFees), 2_000);
+        assert_eq!(manager.pool_balances(), PoolBalances {
+            base_emission: single_window_emission,
+            transaction_fees: 2_000,
+            activation_pool: 0,
+        });
+        // Pool 1 is never credited; Pool 3 is closed in Phase 1
+        assert!(manager.credit_pool(PoolSource::BaseEmission, 1).is_err());
+        assert!(manager.add_activation_qnc(1).is_err());
+
+        manager.register_node("light_node".to_string(), NodeType::Light, "wallet_light_node".to_string()).unwrap();
+        manager.record_ping_attempt("light_node", true, 50).unwrap();
+        add_eligible_node(&mut manager, "full_node", NodeType::Full);
+        add_eligible_node(&mut manager, "super_node", NodeType::Super);
+        let result = manager.process_window_at(BASE_WINDOW).unwrap();
+
+        assert_eq!(result.pool_emission, PoolBalances {
+            base_emission: single_window_emission,
+            transaction_fees: 2_000,
+            activation_pool: 0,
+        });
+        assert_eq!(result.window_emission, single_window_emission + 2_000);
+
+        // Light nodes share base emission but not fees (30% Full, 70% Super)
+        let fees_of = |node_id: &str| manager.get_pending_reward(node_id).unwrap().pool2_transaction_fees;
+        assert_eq!((fees_of("light_node"), fees_of("full_node"), fees_of("super_node")), (0, 600, 1_400));
+        assert!(manager.get_pending_reward("light_node").unwrap().pool1_base_emission > 0);
+
+        assert_eq!(manager.pool_balance(PoolSource::TransactionFees), 0);
+        assert_eq!(manager.pool_distributed(), result.pool_emission);
+
+        // Fees with no eligible recipient stay in the fee pool for a later window
+        manager.add_transaction_fees(700).unwrap();
+        manager.register_node("light_node".to_string(), NodeType::Light, "wallet_light_node".to_string()).unwrap();
+        manager.record_ping_attempt("light_node", true, 50).unwrap();
+        let result = manager.process_window_at(BASE_WINDOW + REWARD_WINDOW_SECS).unwrap();
+        assert_eq!(result.pool_emission.transaction_fees, 0);
+        assert_eq!(result.window_emission, single_window_emission);
+        assert_eq!(manager.pool_balance(PoolSource::TransactionFees), 700);
+    }
+}
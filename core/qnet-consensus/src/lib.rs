@@ -17,15 +17,17 @@ pub mod dynamic_timing;
 pub mod errors;
 pub mod reputation;
 pub mod kademlia;
+pub mod metrics;
 
 // Re-export main types for public API
-pub use lazy_rewards::{PhaseAwareRewardManager, PhaseAwareReward, RewardClaimResult};
+pub use lazy_rewards::{PhaseAwareRewardManager, PhaseAwareReward, PoolBalances, PoolSource, RewardClaimResult, RewardClaimStatus, RewardMultipliers, WindowProcessingResult, distribute_largest_remainder};
 pub use reward_integration::{RewardIntegrationManager, RewardInfo};
 pub use batch_operations::{
     BatchOperationsManager, BatchRewardClaimRequest, BatchRewardClaimResult,
     BatchNodeActivationRequest, BatchNodeActivationResult, BatchTransferRequest, BatchTransferResult
 };
 pub use commit_reveal::{CommitRevealConsensus, ConsensusConfig};
+pub use metrics::ConsensusMetrics;
 pub use errors::ConsensusError;
 pub use reputation::{NodeReputation, ReputationConfig, MaliciousBehavior};
 pub use kademlia::{KademliaDht, KademliaNode, generate_node_id};
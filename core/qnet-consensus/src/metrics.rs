@@ -1,11 +1,12 @@
 //! Metrics collection for consensus
 
 use prometheus::{
-    register_counter_vec, register_gauge_vec, register_histogram_vec,
-    CounterVec, GaugeVec, HistogramVec,
+    register_counter_vec, register_gauge, register_gauge_vec, register_histogram_vec,
+    CounterVec, Gauge, GaugeVec, HistogramVec,
 };
 use lazy_static::lazy_static;
-use std::sync::{Arc, RwLock};
+use serde::Serialize;
+use crate::commit_reveal::ConsensusPhase;
 
 lazy_static! {
     /// Counter for consensus rounds
@@ -36,6 +37,18 @@ lazy_static! {
         &["type"]
     ).unwrap();
     
+    /// Gauge for the active consensus round number
+    pub static ref CONSENSUS_CURRENT_ROUND: Gauge = register_gauge!(
+        "qnet_consensus_current_round",
+        "Round number of the active consensus round"
+    ).unwrap();
+    
+    /// Gauge for the active round phase (0 idle, 1 commit, 2 reveal, 3 finalize)
+    pub static ref CONSENSUS_PHASE: Gauge = register_gauge!(
+        "qnet_consensus_phase",
+        "Phase of the active consensus round: 0 idle, 1 commit, 2 reveal, 3 finalize"
+    ).unwrap();
+    
     /// Gauge for active participants
     pub static ref CONSENSUS_PARTICIPANTS: GaugeVec = register_gauge_vec!(
         "qnet_consensus_participants",
@@ -67,15 +80,6 @@ lazy_static! {
     ).unwrap();
 }
 
-/// Metrics data
-#[derive(Default)]
-pub struct Metrics {
-    /// Total rounds
-    pub total_rounds: u64,
-    /// Successful rounds
-    pub successful_rounds: u64,
-}
-
 /// Record a successful consensus round
 pub fn record_successful_round(duration_secs: f64) {
     CONSENSUS_ROUNDS.with_label_values(&["success"]).inc();
@@ -130,16 +134,36 @@ pub fn record_reputation_update(update_type: &str) {
     REPUTATION_UPDATES.with_label_values(&[update_type]).inc();
 }
 
-/// Consensus metrics wrapper
+/// Commit-reveal progress snapshot
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
 pub struct ConsensusMetrics {
-    inner: Arc<RwLock<Metrics>>,
+    /// Round number of the active round (0 before the first round)
+    pub current_round: u64,
+    /// Phase of the active round, None between rounds
+    pub phase: Option<ConsensusPhase>,
+    /// Commits stored for the active round
+    pub commits_received: usize,
+    /// Reveals stored for the active round
+    pub reveals_received: usize,
+    /// Rounds that selected a leader
+    pub rounds_finalized_total: u64,
+    /// Rounds that could not start or finalize (a rising count is a health warning)
+    pub rounds_failed_total: u64,
 }
 
 impl ConsensusMetrics {
-    /// Create new metrics
-    pub fn new() -> Self {
-        Self {
-            inner: Arc::new(RwLock::new(Metrics::default())),
-        }
+    /// Update the Prometheus gauges from this snapshot
+    /// Round outcome counters are incremented as they happen (`record_*_round`)
+    pub fn publish(&self) {
+        CONSENSUS_CURRENT_ROUND.set(self.current_round as f64);
+        let phase = match self.phase {
+            None => 0.0,
+            Some(ConsensusPhase::Commit) => 1.0,
+            Some(ConsensusPhase::Reveal) => 2.0,
+            Some(ConsensusPhase::Finalize) => 3.0,
+        };
+        CONSENSUS_PHASE.set(phase);
+        update_participants("commit", self.commits_received);
+        update_participants("reveal", self.reveals_received);
     }
-} 
\ No newline at end of file
+} 
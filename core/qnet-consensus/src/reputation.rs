@@ -532,7 +532,7 @@ impl NodeReputation {
         let behavior = match evidence.evidence_type.as_str() {
             "double_sign" => MaliciousBehavior::DoubleSign,
             "invalid_block" => MaliciousBehavior::InvalidBlock,
-            "time_manipulation" => MaliciousBehavior::TimeManipulation,
+            "time_manipulation" | "time_manipulation_future" | "time_manipulation_past" => MaliciousBehavior::TimeManipulation,
             "network_flooding" => MaliciousBehavior::NetworkFlooding,
             "invalid_consensus" => MaliciousBehavior::InvalidConsensus,
             _ => MaliciousBehavior::ProtocolViolation,
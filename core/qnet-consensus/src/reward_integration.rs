@@ -99,7 +99,10 @@ impl RewardIntegrationManager {
     /// Process transaction and extract fees for Pool 2
     pub fn process_transaction_fee(&mut self, tx_hash: String, amount: u64, gas_used: u64, gas_price: u64) -> Result<(), ConsensusError> {
         // Calculate total fee
-        let fee_amount = gas_used * gas_price;
+        let fee_amount = gas_used.checked_mul(gas_price)
+            .ok_or_else(|| ConsensusError::InvalidOperation(format!(
+                "Fee overflow: {} * {}", gas_used, gas_price
+            )))?;
         
         if fee_amount == 0 {
             return Ok(()); // No fee to process
@@ -120,14 +123,14 @@ impl RewardIntegrationManager {
         // Add fee to Pool 2
         {
             let mut reward_manager = self.reward_manager.write().unwrap();
-            reward_manager.add_transaction_fees(fee_amount);
+            reward_manager.add_transaction_fees(fee_amount)?;
         }
         
         // Track processed fee
         self.processed_fees.insert(tx_hash, fee);
         
         // Update statistics
-        self.pool_stats.pool2_total_fees += fee_amount;
+        self.pool_stats.pool2_total_fees = self.pool_stats.pool2_total_fees.saturating_add(fee_amount);
         self.pool_stats.total_transactions_processed += 1;
         
         println!("[RewardIntegration] ✅ Transaction fee processed: {} QNC → Pool 2", fee_amount);
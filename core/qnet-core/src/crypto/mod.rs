@@ -6,7 +6,8 @@ pub mod rust;
 // Re-export main crypto functions
 pub use rust::{
     ProductionCrypto, DilithiumParams, SphincsParams, CryptoError, CryptoErrorKind,
-    generate_keypair, sign as rust_sign, verify as rust_verify, merkle
+    generate_keypair, sign as rust_sign, verify as rust_verify, merkle,
+    SignatureAlgorithm, SignatureEnvelope
 };
 
 // Convenience functions
@@ -3,12 +3,14 @@
 pub mod production_crypto;
 pub mod utils;
 pub mod merkle;
+pub mod signature_envelope;
 
 // Re-export main types for backward compatibility
 pub use production_crypto::{
     ProductionCrypto, DilithiumParams, SphincsParams, CryptoErrorWithKind as CryptoError, 
     CryptoErrorKind, default_dilithium_params, default_sphincs_params
 };
+pub use signature_envelope::{SignatureAlgorithm, SignatureEnvelope};
 
 // Simplified interface for backward compatibility
 pub fn generate_keypair() -> Result<(Vec<u8>, Vec<u8>), CryptoError> {
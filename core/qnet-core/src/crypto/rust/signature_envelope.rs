@@ -0,0 +1,268 @@
+//! Self-describing signature envelope
+//!
+//! Serialized signatures carry a 1-byte algorithm id followed by the raw
+//! signature bytes, so verifiers never need out-of-band knowledge of the
+//! scheme. Signatures produced before the envelope existed carry no tag
+//! and are treated as Dilithium3.
+
+use std::fmt;
+use serde::{Serialize, Deserialize};
+use pqcrypto_traits::sign::{PublicKey as PQPublicKey, DetachedSignature as PQDetachedSignature, SignedMessage as PQSignedMessage};
+use pqcrypto_dilithium::dilithium3;
+use pqcrypto_dilithium::dilithium5;
+use pqcrypto_falcon::falcon512;
+use pqcrypto_falcon::falcon1024;
+
+use super::production_crypto::{CryptoErrorKind, CryptoErrorWithKind as CryptoError, CryptoResult};
+
+/// String prefix marking an enveloped signature (hex of `[id][signature]`)
+pub const SIGNATURE_ENVELOPE_PREFIX: &str = "sigv1:";
+
+/// Algorithm assumed for signatures without an envelope tag
+pub const LEGACY_SIGNATURE_ALGORITHM: SignatureAlgorithm = SignatureAlgorithm::Dilithium3;
+
+/// Signature algorithms that can appear in an envelope
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum SignatureAlgorithm {
+    Ed25519 = 0x01,
+    Dilithium3 = 0x03,
+    Dilithium5 = 0x05,
+    Falcon512 = 0x10,
+    Falcon1024 = 0x11,
+}
+
+impl SignatureAlgorithm {
+    /// Envelope id byte
+    pub fn id(self) -> u8 {
+        self as u8
+    }
+
+    /// Resolve an envelope id byte
+    pub fn from_id(id: u8) -> CryptoResult<Self> {
+        match id {
+            0x01 => Ok(Self::Ed25519),
+            0x03 => Ok(Self::Dilithium3),
+            0x05 => Ok(Self::Dilithium5),
+            0x10 => Ok(Self::Falcon512),
+            0x11 => Ok(Self::Falcon1024),
+            other => Err(CryptoError {
+                kind: CryptoErrorKind::InvalidParameters,
+                message: format!("Unknown signature algorithm id: 0x{:02x}", other),
+            }),
+        }
+    }
+}
+
+impl fmt::Display for SignatureAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ed25519 => write!(f, "Ed25519"),
+            Self::Dilithium3 => write!(f, "Dilithium3"),
+            Self::Dilithium5 => write!(f, "Dilithium5"),
+            Self::Falcon512 => write!(f, "Falcon512"),
+            Self::Falcon1024 => write!(f, "Falcon1024"),
+        }
+    }
+}
+
+/// Signature tagged with the algorithm that produced it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureEnvelope {
+    pub algorithm: SignatureAlgorithm,
+    pub signature: Vec<u8>,
+}
+
+impl SignatureEnvelope {
+    /// Wrap raw signature bytes
+    pub fn new(algorithm: SignatureAlgorithm, signature: Vec<u8>) -> Self {
+        Self { algorithm, signature }
+    }
+
+    /// Binary form: `[algorithm id][signature bytes]`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(1 + self.signature.len());
+        bytes.push(self.algorithm.id());
+        bytes.extend_from_slice(&self.signature);
+        bytes
+    }
+
+    /// Parse the binary form
+    pub fn from_bytes(bytes: &[u8]) -> CryptoResult<Self> {
+        let (&id, signature) = bytes.split_first().ok_or_else(|| CryptoError {
+            kind: CryptoErrorKind::InvalidSignature,
+            message: "Empty signature envelope".to_string(),
+        })?;
+        if signature.is_empty() {
+            return Err(CryptoError {
+                kind: CryptoErrorKind::InvalidSignature,
+                message: "Signature envelope has no signature bytes".to_string(),
+            });
+        }
+        Ok(Self::new(SignatureAlgorithm::from_id(id)?, signature.to_vec()))
+    }
+
+    /// String form stored in transactions
+    pub fn encode(&self) -> String {
+        format!("{}{}", SIGNATURE_ENVELOPE_PREFIX, hex::encode(self.to_bytes()))
+    }
+
+    /// Check whether a stored signature string carries an envelope tag
+    pub fn is_enveloped(encoded: &str) -> bool {
+        encoded.starts_with(SIGNATURE_ENVELOPE_PREFIX)
+    }
+
+    /// Parse a stored signature string.
+    ///
+    /// Enveloped strings are decoded by their tag; legacy un-tagged hex
+    /// signatures are migrated as `LEGACY_SIGNATURE_ALGORITHM`.
+    pub fn parse(encoded: &str) -> CryptoResult<Self> {
+        let (payload, enveloped) = match encoded.strip_prefix(SIGNATURE_ENVELOPE_PREFIX) {
+            Some(payload) => (payload, true),
+            None => (encoded, false),
+        };
+        let bytes = hex::decode(payload).map_err(|e| CryptoError {
+            kind: CryptoErrorKind::InvalidSignature,
+            message: format!("Invalid signature hex: {}", e),
+        })?;
+        if enveloped {
+            Self::from_bytes(&bytes)
+        } else if bytes.is_empty() {
+            Err(CryptoError {
+                kind: CryptoErrorKind::InvalidSignature,
+                message: "Empty legacy signature".to_string(),
+            })
+        } else {
+            Ok(Self::new(LEGACY_SIGNATURE_ALGORITHM, bytes))
+        }
+    }
+
+    /// Verify the signature with the verifier selected by the envelope tag.
+    /// Dilithium accepts both detached signatures and signed messages.
+    pub fn verify(&self, message: &[u8], public_key: &[u8]) -> CryptoResult<bool> {
+        let invalid_key = |algorithm: SignatureAlgorithm| CryptoError {
+            kind: CryptoErrorKind::InvalidKey,
+            message: format!("Failed to parse {} public key", algorithm),
+        };
+
+        match self.algorithm {
+            SignatureAlgorithm::Ed25519 => {
+                use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+                let key_bytes: [u8; 32] = public_key.try_into()
+                    .map_err(|_| invalid_key(self.algorithm))?;
+                let verifying_key = VerifyingKey::from_bytes(&key_bytes)
+                    .map_err(|_| invalid_key(self.algorithm))?;
+                let sig_bytes: [u8; 64] = match self.signature.as_slice().try_into() {
+                    Ok(bytes) => bytes,
+                    Err(_) => return Ok(false),
+                };
+                Ok(verifying_key.verify(message, &Signature::from_bytes(&sig_bytes)).is_ok())
+            }
+            SignatureAlgorithm::Dilithium3 => {
+                let pk = dilithium3::PublicKey::from_bytes(public_key)
+                    .map_err(|_| invalid_key(self.algorithm))?;
+                if let Ok(sig) = dilithium3::DetachedSignature::from_bytes(&self.signature) {
+                    return Ok(dilithium3::verify_detached_signature(&sig, message, &pk).is_ok());
+                }
+                match dilithium3::SignedMessage::from_bytes(&self.signature) {
+                    Ok(signed) => Ok(dilithium3::open(&signed, &pk).is_ok_and(|m| m == message)),
+                    Err(_) => Ok(false),
+                }
+            }
+            SignatureAlgorithm::Dilithium5 => {
+                let pk = dilithium5::PublicKey::from_bytes(public_key)
+                    .map_err(|_| invalid_key(self.algorithm))?;
+                if let Ok(sig) = dilithium5::DetachedSignature::from_bytes(&self.signature) {
+                    return Ok(dilithium5::verify_detached_signature(&sig, message, &pk).is_ok());
+                }
+                match dilithium5::SignedMessage::from_bytes(&self.signature) {
+                    Ok(signed) => Ok(dilithium5::open(&signed, &pk).is_ok_and(|m| m == message)),
+                    Err(_) => Ok(false),
+                }
+            }
+            SignatureAlgorithm::Falcon512 => {
+                let pk = falcon512::PublicKey::from_bytes(public_key)
+                    .map_err(|_| invalid_key(self.algorithm))?;
+                match falcon512::DetachedSignature::from_bytes(&self.signature) {
+                    Ok(sig) => Ok(falcon512::verify_detached_signature(&sig, message, &pk).is_ok()),
+                    Err(_) => Ok(false),
+                }
+            }
+            SignatureAlgorithm::Falcon1024 => {
+                let pk = falcon1024::PublicKey::from_bytes(public_key)
+                    .map_err(|_| invalid_key(self.algorithm))?;
+                match falcon1024::DetachedSignature::from_bytes(&self.signature) {
+                    Ok(sig) => Ok(falcon1024::verify_detached_signature(&sig, message, &pk).is_ok()),
+                    Err(_) => Ok(false),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pqcrypto_traits::sign::DetachedSignature as _;
+
+    const MESSAGE: &[u8] = b"transfer:alice:bob:1000:10:21000";
+
+    /// Sign MESSAGE with every supported algorithm, returning (envelope, public key)
+    fn signed_envelopes() -> Vec<(SignatureEnvelope, Vec<u8>)> {
+        use ed25519_dalek::{Signer, SigningKey};
+        let ed_key = SigningKey::from_bytes(&[7u8; 32]);
+        let (d3_pk, d3_sk) = dilithium3::keypair();
+        let (d5_pk, d5_sk) = dilithium5::keypair();
+        let (f512_pk, f512_sk) = falcon512::keypair();
+        let (f1024_pk, f1024_sk) = falcon1024::keypair();
+
+        vec![
+            (SignatureEnvelope::new(SignatureAlgorithm::Ed25519, ed_key.sign(MESSAGE).to_bytes().to_vec()),
+             ed_key.verifying_key().to_bytes().to_vec()),
+            (SignatureEnvelope::new(SignatureAlgorithm::Dilithium3, dilithium3::detached_sign(MESSAGE, &d3_sk).as_bytes().to_vec()),
+             d3_pk.as_bytes().to_vec()),
+            (SignatureEnvelope::new(SignatureAlgorithm::Dilithium5, dilithium5::detached_sign(MESSAGE, &d5_sk).as_bytes().to_vec()),
+             d5_pk.as_bytes().to_vec()),
+            (SignatureEnvelope::new(SignatureAlgorithm::Falcon512, falcon512::detached_sign(MESSAGE, &f512_sk).as_bytes().to_vec()),
+             f512_pk.as_bytes().to_vec()),
+            (SignatureEnvelope::new(SignatureAlgorithm::Falcon1024, falcon1024::detached_sign(MESSAGE, &f1024_sk).as_bytes().to_vec()),
+             f1024_pk.as_bytes().to_vec()),
+        ]
+    }
+
+    #[test]
+    fn test_envelope_round_trip_all_algorithms() {
+        for (envelope, public_key) in signed_envelopes() {
+            let encoded = envelope.encode();
+            assert!(SignatureEnvelope::is_enveloped(&encoded));
+
+            let parsed = SignatureEnvelope::parse(&encoded).unwrap();
+            assert_eq!(parsed, envelope, "round trip failed for {}", envelope.algorithm);
+            assert!(parsed.verify(MESSAGE, &public_key).unwrap(), "{} did not verify", envelope.algorithm);
+            assert!(!parsed.verify(b"tampered", &public_key).unwrap(), "{} accepted a tampered message", envelope.algorithm);
+        }
+    }
+
+    #[test]
+    fn test_legacy_signature_verifies_as_dilithium3() {
+        let (pk, sk) = dilithium3::keypair();
+        let legacy = hex::encode(dilithium3::detached_sign(MESSAGE, &sk).as_bytes());
+        assert!(!SignatureEnvelope::is_enveloped(&legacy));
+
+        let parsed = SignatureEnvelope::parse(&legacy).unwrap();
+        assert_eq!(parsed.algorithm, SignatureAlgorithm::Dilithium3);
+        assert!(parsed.verify(MESSAGE, pk.as_bytes()).unwrap());
+
+        // Legacy signed-message form produced by ProductionCrypto
+        let signed = dilithium3::sign(MESSAGE, &sk);
+        let parsed = SignatureEnvelope::parse(&hex::encode(signed.as_bytes())).unwrap();
+        assert!(parsed.verify(MESSAGE, pk.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn test_unknown_algorithm_rejected() {
+        let encoded = format!("{}{}", SIGNATURE_ENVELOPE_PREFIX, hex::encode([0x7f, 1, 2, 3]));
+        assert!(SignatureEnvelope::parse(&encoded).is_err());
+        assert!(SignatureEnvelope::from_bytes(&[SignatureAlgorithm::Falcon512.id()]).is_err());
+    }
+}
@@ -7,6 +7,7 @@
 
 pub mod crypto;
 pub mod security;
+pub mod storage;
 
 // Re-export main crypto functions
 pub use crypto::{hash, sign, verify, KeyPair};
@@ -0,0 +1,181 @@
+//! Background compaction scheduler
+//!
+//! Checks storage stats every interval and compacts when enough SST files
+//! have accumulated, optionally only inside an off-peak window. Overlapping
+//! compactions are prevented by the storage itself (`try_optimize`).
+
+use std::sync::Arc;
+use std::time::Duration;
+use async_trait::async_trait;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+use super::{QNetStorage, StorageError, StorageStats};
+
+/// When the scheduler compacts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompactionSchedule {
+    /// Time between stats checks
+    pub interval: Duration,
+    /// Only compact between these UTC hours (`start..end`, may wrap past midnight)
+    pub off_peak_hours: Option<(u8, u8)>,
+    /// Compact once this many SST files are waiting to be merged
+    pub sst_file_threshold: usize,
+}
+
+impl Default for CompactionSchedule {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(600),
+            off_peak_hours: None,
+            sst_file_threshold: 8,
+        }
+    }
+}
+
+impl CompactionSchedule {
+    /// Whether `hour` (UTC, 0-23) falls in the off-peak window
+    pub fn in_window(&self, hour: u8) -> bool {
+        match self.off_peak_hours {
+            None => true,
+            Some((start, end)) if start <= end => hour >= start && hour < end,
+            Some((start, end)) => hour >= start || hour < end,
+        }
+    }
+
+    /// Whether stats taken at `hour` make a compaction worthwhile
+    pub fn should_compact(&self, stats: &StorageStats, hour: u8) -> bool {
+        self.in_window(hour) && stats.sst_file_count >= self.sst_file_threshold
+    }
+}
+
+/// Storage the scheduler can inspect and compact
+#[async_trait]
+pub trait CompactionTarget: Send + Sync + 'static {
+    async fn compaction_stats(&self) -> StorageStats;
+
+    /// Compact, returning false if a compaction was already running
+    async fn try_compact(&self) -> Result<bool, StorageError>;
+}
+
+#[async_trait]
+impl CompactionTarget for QNetStorage {
+    async fn compaction_stats(&self) -> StorageStats {
+        self.get_stats().await
+    }
+
+    async fn try_compact(&self) -> Result<bool, StorageError> {
+        self.try_optimize().await
+    }
+}
+
+/// Running scheduler task; stop it with `shutdown`
+pub struct CompactionScheduler {
+    stop: watch::Sender<bool>,
+    task: JoinHandle<()>,
+}
+
+impl CompactionScheduler {
+    /// Start checking `target` on the schedule
+    pub fn spawn<T: CompactionTarget>(target: Arc<T>, schedule: CompactionSchedule) -> Self {
+        let (stop, mut stopped) = watch::channel(false);
+        let task = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(schedule.interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {}
+                    _ = stopped.changed() => break,
+                }
+
+                let stats = target.compaction_stats().await;
+                if !schedule.should_compact(&stats, current_utc_hour()) {
+                    continue;
+                }
+                match target.try_compact().await {
+                    Ok(true) => println!("[STORAGE] 🗜️ Scheduled compaction done ({} SST files before)", stats.sst_file_count),
+                    Ok(false) => println!("[STORAGE] ⏭️ Compaction already running - skipped scheduled run"),
+                    Err(e) => println!("[STORAGE] ⚠️ Scheduled compaction failed: {}", e),
+                }
+            }
+        });
+        Self { stop, task }
+    }
+
+    /// Stop the scheduler and wait for a running check to finish
+    pub async fn shutdown(self) {
+        let _ = self.stop.send(true);
+        let _ = self.task.await;
+    }
+}
+
+fn current_utc_hour() -> u8 {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    ((secs / 3600) % 24) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+    /// Stats stub reporting a settable SST file count
+    #[derive(Default)]
+    struct StubStorage {
+        sst_files: AtomicUsize,
+        compactions: AtomicU64,
+    }
+
+    #[async_trait]
+    impl CompactionTarget for StubStorage {
+        async fn compaction_stats(&self) -> StorageStats {
+            StorageStats { sst_file_count: self.sst_files.load(Ordering::SeqCst), ..StorageStats::default() }
+        }
+
+        async fn try_compact(&self) -> Result<bool, StorageError> {
+            self.compactions.fetch_add(1, Ordering::SeqCst);
+            self.sst_files.store(1, Ordering::SeqCst);
+            Ok(true)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_compacts_past_threshold() {
+        let storage = Arc::new(StubStorage::default());
+        storage.sst_files.store(3, Ordering::SeqCst);
+        let schedule = CompactionSchedule {
+            interval: Duration::from_millis(10),
+            off_peak_hours: None,
+            sst_file_threshold: 8,
+        };
+        let scheduler = CompactionScheduler::spawn(storage.clone(), schedule);
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert_eq!(storage.compactions.load(Ordering::SeqCst), 0);
+
+        storage.sst_files.store(12, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert_eq!(storage.compactions.load(Ordering::SeqCst), 1);
+
+        // No further runs after shutdown
+        scheduler.shutdown().await;
+        storage.sst_files.store(12, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        assert_eq!(storage.compactions.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_off_peak_window() {
+        let schedule = CompactionSchedule { off_peak_hours: Some((22, 4)), ..CompactionSchedule::default() };
+        assert!(schedule.in_window(23) && schedule.in_window(0) && schedule.in_window(3));
+        assert!(!schedule.in_window(4) && !schedule.in_window(12));
+
+        let stats = StorageStats { sst_file_count: 20, ..StorageStats::default() };
+        assert!(schedule.should_compact(&stats, 1));
+        assert!(!schedule.should_compact(&stats, 12));
+        assert!(CompactionSchedule::default().should_compact(&stats, 12));
+    }
+}
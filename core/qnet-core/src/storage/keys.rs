@@ -0,0 +1,127 @@
+//! Key layout for `QNetStorage`
+//!
+//! Every key is an ASCII prefix followed by a fixed-width suffix:
+//!
+//! | Key | Layout |
+//! |---|---|
+//! | block by height | `block_` + height, 8 bytes **big-endian** |
+//! | block height by hash | `block_hash_` + 32-byte hash |
+//! | transaction | `tx_` + 32-byte hash |
+//! | transaction block height | `tx_block_` + 32-byte hash |
+//! | node | `node_` + 32-byte node id |
+//! | account | `account_` + 32-byte address |
+//! | state-layer block record | `state_block_` + 32-byte hash |
+//! | state-layer account record | `state_account_` + 32-byte address digest |
+//! | latest height | `latest_height` |
+//!
+//! Heights inside keys are big-endian so byte order is numeric order: a range scan
+//! over `block_` walks blocks by ascending height. Heights stored as *values* (block
+//! hash and transaction mappings, latest height) are little-endian, since they are
+//! only read back whole; encode them with [`height_value`] and never use it for keys.
+//!
+//! `block_hash_` keys share the `block_` prefix. A scan over block heights must
+//! skip keys whose length is not [`BLOCK_KEY_LEN`].
+
+const BLOCK_PREFIX: &[u8] = b"block_";
+const BLOCK_HASH_PREFIX: &[u8] = b"block_hash_";
+const TX_PREFIX: &[u8] = b"tx_";
+const TX_BLOCK_PREFIX: &[u8] = b"tx_block_";
+const NODE_PREFIX: &[u8] = b"node_";
+const ACCOUNT_PREFIX: &[u8] = b"account_";
+const STATE_BLOCK_PREFIX: &[u8] = b"state_block_";
+const STATE_ACCOUNT_PREFIX: &[u8] = b"state_account_";
+
+/// Key holding the latest stored block height
+pub const LATEST_HEIGHT: &[u8] = b"latest_height";
+
+/// Length of a block-by-height key
+pub const BLOCK_KEY_LEN: usize = BLOCK_PREFIX.len() + 8;
+
+fn prefixed(prefix: &[u8], suffix: &[u8]) -> Vec<u8> {
+    let mut key = Vec::with_capacity(prefix.len() + suffix.len());
+    key.extend_from_slice(prefix);
+    key.extend_from_slice(suffix);
+    key
+}
+
+/// Block by height (big-endian, sorts by height)
+pub fn block(height: u64) -> Vec<u8> {
+    prefixed(BLOCK_PREFIX, &height.to_be_bytes())
+}
+
+/// Block height by block hash
+pub fn block_hash(hash: &[u8; 32]) -> Vec<u8> {
+    prefixed(BLOCK_HASH_PREFIX, hash)
+}
+
+/// Transaction by hash
+pub fn transaction(hash: &[u8; 32]) -> Vec<u8> {
+    prefixed(TX_PREFIX, hash)
+}
+
+/// Height of the block containing a transaction
+pub fn transaction_block(hash: &[u8; 32]) -> Vec<u8> {
+    prefixed(TX_BLOCK_PREFIX, hash)
+}
+
+/// Node by id
+pub fn node(node_id: &[u8; 32]) -> Vec<u8> {
+    prefixed(NODE_PREFIX, node_id)
+}
+
+/// Account state by address
+pub fn account(address: &[u8; 32]) -> Vec<u8> {
+    prefixed(ACCOUNT_PREFIX, address)
+}
+
+/// Opaque state-layer block record by block hash
+pub fn state_block(hash: &[u8; 32]) -> Vec<u8> {
+    prefixed(STATE_BLOCK_PREFIX, hash)
+}
+
+/// Opaque state-layer account record by address digest
+pub fn state_account(address: &[u8; 32]) -> Vec<u8> {
+    prefixed(STATE_ACCOUNT_PREFIX, address)
+}
+
+/// Height stored as a value (little-endian); not for keys
+pub fn height_value(height: u64) -> [u8; 8] {
+    height.to_le_bytes()
+}
+
+/// Decode a [`height_value`], `None` if it is not 8 bytes
+pub fn parse_height_value(bytes: &[u8]) -> Option<u64> {
+    bytes.try_into().ok().map(u64::from_le_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_keys_sort_by_height() {
+        let heights: Vec<u64> = (1..=300).chain([u32::MAX as u64, u64::MAX - 1, u64::MAX]).collect();
+        let mut keys: Vec<Vec<u8>> = heights.iter().rev().map(|&h| block(h)).collect();
+        keys.sort();
+
+        let sorted: Vec<u64> = keys.iter()
+            .map(|key| {
+                assert_eq!(key.len(), BLOCK_KEY_LEN);
+                u64::from_be_bytes(key[BLOCK_PREFIX.len()..].try_into().unwrap())
+            })
+            .collect();
+        assert_eq!(sorted, heights);
+
+        // Little-endian keys would put 256 before 2
+        assert!(block(2) < block(256));
+    }
+
+    #[test]
+    fn test_height_values_round_trip() {
+        for height in [0, 1, 300, u64::MAX] {
+            assert_eq!(parse_height_value(&height_value(height)), Some(height));
+        }
+        assert_eq!(height_value(1), [1, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(parse_height_value(&[1, 2, 3]), None);
+    }
+}
@@ -3,6 +3,8 @@
 //! Provides optimized storage solutions for production deployment
 
 pub mod optimized_storage;
+pub mod compaction;
+pub mod keys;
 
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -10,8 +12,9 @@ use serde::{Serialize, Deserialize};
 
 pub use optimized_storage::{
     OptimizedStorage, StorageConfig, StorageStats, StorageError,
-    LSMConfig, CompressionConfig, CompressionType, ShardFunction
+    LSMConfig, CompressionConfig, CompressionType, ShardFunction, CompactionRecord
 };
+pub use compaction::{CompactionSchedule, CompactionScheduler, CompactionTarget};
 
 /// Main storage interface for QNet blockchain
 pub struct QNetStorage {
@@ -130,14 +133,14 @@ impl QNetStorage {
     /// Store block with all related data
     pub async fn store_block(&self, block: &BlockData) -> Result<(), StorageError> {
         // Store block data
-        let block_key = self.block_key(block.height);
+        let block_key = keys::block(block.height);
         let block_bytes = bincode::serialize(block)
             .map_err(|e| StorageError::Serialization(e.to_string()))?;
         self.backend.put(&block_key, &block_bytes).await?;
         
         // Store block hash mapping
-        let hash_key = self.block_hash_key(&block.hash);
-        let height_bytes = block.height.to_le_bytes();
+        let hash_key = keys::block_hash(&block.hash);
+        let height_bytes = keys::height_value(block.height);
         self.backend.put(&hash_key, &height_bytes).await?;
         
         // Store transactions
@@ -146,15 +149,14 @@ impl QNetStorage {
         }
         
         // Update latest height
-        let latest_key = b"latest_height";
-        self.backend.put(latest_key, &height_bytes).await?;
+        self.backend.put(keys::LATEST_HEIGHT, &height_bytes).await?;
         
         Ok(())
     }
     
     /// Get block by height
     pub async fn get_block(&self, height: u64) -> Result<Option<BlockData>, StorageError> {
-        let key = self.block_key(height);
+        let key = keys::block(height);
         match self.backend.get(&key).await? {
             Some(bytes) => {
                 let block: BlockData = bincode::deserialize(&bytes)
@@ -167,33 +169,41 @@ impl QNetStorage {
     
     /// Get block by hash
     pub async fn get_block_by_hash(&self, hash: &[u8; 32]) -> Result<Option<BlockData>, StorageError> {
-        let hash_key = self.block_hash_key(hash);
+        let hash_key = keys::block_hash(hash);
         match self.backend.get(&hash_key).await? {
-            Some(height_bytes) => {
-                if height_bytes.len() == 8 {
-                    let height = u64::from_le_bytes([
-                        height_bytes[0], height_bytes[1], height_bytes[2], height_bytes[3],
-                        height_bytes[4], height_bytes[5], height_bytes[6], height_bytes[7],
-                    ]);
-                    self.get_block(height).await
-                } else {
-                    Ok(None)
-                }
-            }
+            Some(height_bytes) => match keys::parse_height_value(&height_bytes) {
+                Some(height) => self.get_block(height).await,
+                None => Ok(None),
+            },
             None => Ok(None),
         }
     }
     
+    /// Whether a block is stored at `height`, without deserializing it
+    pub async fn has_block(&self, height: u64) -> Result<bool, StorageError> {
+        self.backend.contains(&keys::block(height)).await
+    }
+    
+    /// Whether a block with this hash is stored, without deserializing it
+    pub async fn has_block_hash(&self, hash: &[u8; 32]) -> Result<bool, StorageError> {
+        self.backend.contains(&keys::block_hash(hash)).await
+    }
+    
+    /// Whether a transaction is stored, without deserializing it
+    pub async fn has_transaction(&self, hash: &[u8; 32]) -> Result<bool, StorageError> {
+        self.backend.contains(&keys::transaction(hash)).await
+    }
+    
     /// Store transaction
     pub async fn store_transaction(&self, tx: &TransactionData, block_height: u64) -> Result<(), StorageError> {
-        let tx_key = self.transaction_key(&tx.hash);
+        let tx_key = keys::transaction(&tx.hash);
         let tx_bytes = bincode::serialize(tx)
             .map_err(|e| StorageError::Serialization(e.to_string()))?;
         self.backend.put(&tx_key, &tx_bytes).await?;
         
         // Store transaction-to-block mapping
-        let tx_block_key = self.transaction_block_key(&tx.hash);
-        let block_bytes = block_height.to_le_bytes();
+        let tx_block_key = keys::transaction_block(&tx.hash);
+        let block_bytes = keys::height_value(block_height);
         self.backend.put(&tx_block_key, &block_bytes).await?;
         
         Ok(())
@@ -201,7 +211,7 @@ impl QNetStorage {
     
     /// Get transaction by hash
     pub async fn get_transaction(&self, hash: &[u8; 32]) -> Result<Option<TransactionData>, StorageError> {
-        let key = self.transaction_key(hash);
+        let key = keys::transaction(hash);
         match self.backend.get(&key).await? {
             Some(bytes) => {
                 let tx: TransactionData = bincode::deserialize(&bytes)
@@ -214,7 +224,7 @@ impl QNetStorage {
     
     /// Store node data
     pub async fn store_node(&self, node: &NodeData) -> Result<(), StorageError> {
-        let node_key = self.node_key(&node.node_id);
+        let node_key = keys::node(&node.node_id);
         let node_bytes = bincode::serialize(node)
             .map_err(|e| StorageError::Serialization(e.to_string()))?;
         self.backend.put(&node_key, &node_bytes).await?;
@@ -223,7 +233,7 @@ impl QNetStorage {
     
     /// Get node by ID
     pub async fn get_node(&self, node_id: &[u8; 32]) -> Result<Option<NodeData>, StorageError> {
-        let key = self.node_key(node_id);
+        let key = keys::node(node_id);
         match self.backend.get(&key).await? {
             Some(bytes) => {
                 let node: NodeData = bincode::deserialize(&bytes)
@@ -236,7 +246,7 @@ impl QNetStorage {
     
     /// Store account state
     pub async fn store_account(&self, account: &AccountState) -> Result<(), StorageError> {
-        let account_key = self.account_key(&account.address);
+        let account_key = keys::account(&account.address);
         let account_bytes = bincode::serialize(account)
             .map_err(|e| StorageError::Serialization(e.to_string()))?;
         self.backend.put(&account_key, &account_bytes).await?;
@@ -245,7 +255,7 @@ impl QNetStorage {
     
     /// Get account state
     pub async fn get_account(&self, address: &[u8; 32]) -> Result<Option<AccountState>, StorageError> {
-        let key = self.account_key(address);
+        let key = keys::account(address);
         match self.backend.get(&key).await? {
             Some(bytes) => {
                 let account: AccountState = bincode::deserialize(&bytes)
@@ -256,17 +266,35 @@ impl QNetStorage {
         }
     }
     
+    /// Store an opaque state-layer block record keyed by block hash
+    pub async fn store_block_record(&self, hash: &[u8; 32], bytes: &[u8]) -> Result<(), StorageError> {
+        let key = keys::state_block(hash);
+        self.backend.put(&key, bytes).await
+    }
+    
+    /// Get a state-layer block record by block hash
+    pub async fn get_block_record(&self, hash: &[u8; 32]) -> Result<Option<Vec<u8>>, StorageError> {
+        let key = keys::state_block(hash);
+        self.backend.get(&key).await
+    }
+    
+    /// Store an opaque state-layer account record keyed by address digest
+    pub async fn store_account_record(&self, address: &[u8; 32], bytes: &[u8]) -> Result<(), StorageError> {
+        let key = keys::state_account(address);
+        self.backend.put(&key, bytes).await
+    }
+    
+    /// Get a state-layer account record by address digest
+    pub async fn get_account_record(&self, address: &[u8; 32]) -> Result<Option<Vec<u8>>, StorageError> {
+        let key = keys::state_account(address);
+        self.backend.get(&key).await
+    }
+    
     /// Get latest block height
     pub async fn get_latest_height(&self) -> Result<u64, StorageError> {
-        let key = b"latest_height";
-        match self.backend.get(key).await? {
-            Some(bytes) if bytes.len() == 8 => {
-                Ok(u64::from_le_bytes([
-                    bytes[0], bytes[1], bytes[2], bytes[3],
-                    bytes[4], bytes[5], bytes[6], bytes[7],
-                ]))
-            }
-            _ => Ok(0),
+        match self.backend.get(keys::LATEST_HEIGHT).await? {
+            Some(bytes) => Ok(keys::parse_height_value(&bytes).unwrap_or(0)),
+            None => Ok(0),
         }
     }
     
@@ -275,14 +303,14 @@ impl QNetStorage {
         let mut operations = Vec::new();
         
         for block in blocks {
-            let block_key = self.block_key(block.height);
+            let block_key = keys::block(block.height);
             let block_bytes = bincode::serialize(&block)
                 .map_err(|e| StorageError::Serialization(e.to_string()))?;
             operations.push((block_key, block_bytes));
             
             // Add hash mapping
-            let hash_key = self.block_hash_key(&block.hash);
-            let height_bytes = block.height.to_le_bytes().to_vec();
+            let hash_key = keys::block_hash(&block.hash);
+            let height_bytes = keys::height_value(block.height).to_vec();
             operations.push((hash_key, height_bytes));
         }
         
@@ -300,41 +328,9 @@ impl QNetStorage {
         self.backend.optimize().await
     }
     
-    // Key generation helpers
-    fn block_key(&self, height: u64) -> Vec<u8> {
-        let mut key = b"block_".to_vec();
-        key.extend_from_slice(&height.to_be_bytes());
-        key
-    }
-    
-    fn block_hash_key(&self, hash: &[u8; 32]) -> Vec<u8> {
-        let mut key = b"block_hash_".to_vec();
-        key.extend_from_slice(hash);
-        key
-    }
-    
-    fn transaction_key(&self, hash: &[u8; 32]) -> Vec<u8> {
-        let mut key = b"tx_".to_vec();
-        key.extend_from_slice(hash);
-        key
-    }
-    
-    fn transaction_block_key(&self, hash: &[u8; 32]) -> Vec<u8> {
-        let mut key = b"tx_block_".to_vec();
-        key.extend_from_slice(hash);
-        key
-    }
-    
-    fn node_key(&self, node_id: &[u8; 32]) -> Vec<u8> {
-        let mut key = b"node_".to_vec();
-        key.extend_from_slice(node_id);
-        key
-    }
-    
-    fn account_key(&self, address: &[u8; 32]) -> Vec<u8> {
-        let mut key = b"account_".to_vec();
-        key.extend_from_slice(address);
-        key
+    /// Compact unless a compaction is already running (false if skipped)
+    pub async fn try_optimize(&self) -> Result<bool, StorageError> {
+        self.backend.try_optimize().await
     }
 }
 
@@ -381,6 +377,67 @@ impl Default for StorageConfig {
                 level: 3,
                 min_size: 1024,                     // Compress files > 1KB
             },
+            enable_encryption: false,           // Blockchain data is public
+        }
+    }
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn test_storage() -> QNetStorage {
+        let config = StorageConfig {
+            bloom_filter_size: 10_000,
+            cache_size: 1024 * 1024,
+            lsm_config: LSMConfig {
+                memtable_size: 1024 * 1024,
+                ..StorageConfig::default().lsm_config
+            },
+            ..StorageConfig::default()
+        };
+        QNetStorage::new(config).await.expect("storage should initialize")
+    }
+
+    fn test_block(height: u64) -> BlockData {
+        BlockData {
+            height,
+            hash: [height as u8; 32],
+            parent_hash: [0; 32],
+            timestamp: 1_700_000_000 + height,
+            transactions: vec![TransactionData {
+                hash: [0xAA; 32],
+                sender: [1; 32],
+                receiver: [2; 32],
+                amount: 10,
+                nonce: 1,
+                signature: vec![],
+                transaction_type: TransactionType::Transfer,
+            }],
+            proposer: [3; 32],
+            signature: vec![],
         }
     }
-} 
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_has_checks_skip_value_reads() {
+        let storage = test_storage().await;
+        storage.store_block(&test_block(5)).await.unwrap();
+
+        assert!(storage.has_block(5).await.unwrap());
+        assert!(!storage.has_block(6).await.unwrap());
+        assert!(storage.has_block_hash(&[5; 32]).await.unwrap());
+        assert!(!storage.has_block_hash(&[6; 32]).await.unwrap());
+        assert!(storage.has_transaction(&[0xAA; 32]).await.unwrap());
+        assert!(!storage.has_transaction(&[0xBB; 32]).await.unwrap());
+
+        // An undecodable value still exists: the check never touches the bytes
+        storage.backend.put(&keys::block(9), b"not a block").await.unwrap();
+        assert!(storage.has_block(9).await.unwrap());
+        assert!(storage.get_block(9).await.is_err());
+
+        let stats = storage.get_stats().await;
+        assert_eq!(stats.existence_checks, 7);
+        assert_eq!(stats.reads, 1, "only get_block read a value");
+    }
+}
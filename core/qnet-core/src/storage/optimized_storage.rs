@@ -11,6 +11,7 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use tokio::sync::RwLock;
 use serde::{Serialize, Deserialize};
 use sha2::{Sha256, Digest};
@@ -39,42 +40,20 @@ pub struct OptimizedStorage {
     
     /// File encryption for physical disk protection
     file_encryption: Arc<FileEncryption>,
-}
-
-/// File encryption system for protecting data at rest
-/// NOTE: This encrypts only the file storage, not the data content
-/// All blockchain data remains publicly queryable via APIs
-pub struct FileEncryption {
-    /// Master encryption key for file protection
-    master_key: Arc<Key<Aes256Gcm>>,
     
-    /// Cipher instance for AES-256-GCM
-    cipher: Arc<Aes256Gcm>,
-    
-    /// Key derivation salt
-    salt: [u8; 32],
+    /// Set while a compaction runs, so compactions never overlap
+    compacting: AtomicBool,
     
-    /// Encryption enabled flag
-    enabled: bool,
+    /// Last completed compaction
+    last_compaction: Arc<RwLock<Option<CompactionRecord>>>,
 }
 
-/// Encrypted file header for integrity verification
-#[derive(Clone)]
-pub struct EncryptedFileHeader {
-    /// File format version
-    version: u8,
-    
-    /// AES-GCM nonce (96 bits)
-    nonce: [u8; 12],
-    
-    /// Authentication tag (128 bits)  
-    tag: [u8; 16],
-    
-    /// File checksum for integrity
-    checksum: [u8; 32],
-    
-    /// Encryption timestamp
-    timestamp: u64,
+/// Timing of a completed compaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompactionRecord {
+    /// Unix time the compaction finished
+    pub finished_at: u64,
+    pub duration_ms: u64,
 }
 
 /// LSM-tree storage engine
@@ -96,6 +75,7 @@ pub struct LSMEngine {
 }
 
 /// Bloom filter for fast existence checks
+#[derive(Clone)]
 pub struct BloomFilter {
     /// Bit array
     bits: Vec<bool>,
@@ -240,13 +220,13 @@ pub enum CompactionStrategy {
 #[derive(Clone)]
 pub struct CompressionConfig {
     /// Compression algorithm
-    algorithm: CompressionType,
+    pub algorithm: CompressionType,
     
     /// Compression level
-    level: u8,
+    pub level: u8,
     
     /// Minimum size to compress
-    min_size: usize,
+    pub min_size: usize,
 }
 
 #[derive(Clone)]
@@ -299,6 +279,9 @@ pub struct StorageMetrics {
     /// Cache misses
     pub cache_misses: u64,
     
+    /// Key existence checks (no value read)
+    pub existence_checks: u64,
+    
     /// Compaction count
     pub compactions: u64,
     
@@ -348,6 +331,8 @@ impl OptimizedStorage {
             cache,
             metrics,
             file_encryption,
+            compacting: AtomicBool::new(false),
+            last_compaction: Arc::new(RwLock::new(None)),
         })
     }
     
@@ -423,6 +408,20 @@ impl OptimizedStorage {
         }
     }
     
+    /// Whether a key is stored, without reading or decompressing its value
+    pub async fn contains(&self, key: &[u8]) -> Result<bool, StorageError> {
+        self.metrics.write().await.existence_checks += 1;
+        
+        // Bloom filter answers most misses definitively
+        if !self.bloom_filter.read().await.might_contain(key) {
+            return Ok(false);
+        }
+        if self.cache.read().await.contains(key) {
+            return Ok(true);
+        }
+        self.lsm_engine.contains(key).await
+    }
+    
     /// Batch operations for efficiency
     pub async fn batch_put(&self, operations: Vec<(Vec<u8>, Vec<u8>)>) -> Result<(), StorageError> {
         // Group by shard for efficiency
@@ -445,7 +444,8 @@ impl OptimizedStorage {
         
         // Wait for all batches
         for task in tasks {
-            task.await??;
+            task.await
+                .map_err(|e| StorageError::Internal(format!("Batch task failed: {}", e)))??;
         }
         
         Ok(())
@@ -453,7 +453,30 @@ impl OptimizedStorage {
     
     /// Optimize storage (trigger compaction)
     pub async fn optimize(&self) -> Result<(), StorageError> {
-        self.lsm_engine.trigger_compaction().await
+        self.try_optimize().await.map(|_| ())
+    }
+    
+    /// Compact unless another compaction is already running
+    /// Returns false when skipped because one was in progress
+    pub async fn try_optimize(&self) -> Result<bool, StorageError> {
+        if self.compacting.swap(true, Ordering::AcqRel) {
+            return Ok(false);
+        }
+        
+        let started = std::time::Instant::now();
+        let result = self.lsm_engine.trigger_compaction().await;
+        if result.is_ok() {
+            let finished_at = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            *self.last_compaction.write().await = Some(CompactionRecord {
+                finished_at,
+                duration_ms: started.elapsed().as_millis() as u64,
+            });
+        }
+        self.compacting.store(false, Ordering::Release);
+        result.map(|_| true)
     }
     
     /// Get storage statistics
@@ -465,6 +488,7 @@ impl OptimizedStorage {
         StorageStats {
             reads: metrics.reads,
             writes: metrics.writes,
+            existence_checks: metrics.existence_checks,
             cache_hit_rate: if metrics.reads > 0 {
                 metrics.cache_hits as f64 / metrics.reads as f64
             } else { 0.0 },
@@ -473,17 +497,19 @@ impl OptimizedStorage {
             storage_size_gb: metrics.storage_size_bytes as f64 / 1_073_741_824.0,
             cache_size_mb: cache_stats.size_mb,
             compaction_count: lsm_stats.compaction_count,
+            sst_file_count: lsm_stats.sst_file_count,
+            last_compaction: *self.last_compaction.read().await,
         }
     }
     
     /// Compress value if it's beneficial
     fn compress_if_beneficial(&self, value: &[u8]) -> Result<Vec<u8>, StorageError> {
         if value.len() < self.compression.min_size {
-            return Ok(value.to_vec());
+            return Ok(Self::uncompressed(value));
         }
         
         match self.compression.algorithm {
-            CompressionType::None => Ok(value.to_vec()),
+            CompressionType::None => Ok(Self::uncompressed(value)),
             CompressionType::LZ4 => {
                 // In production, would use actual LZ4 compression
                 // For now, return as-is with compression marker
@@ -506,6 +532,14 @@ impl OptimizedStorage {
         }
     }
     
+    /// Tag a value as stored without compression so reads never strip payload bytes
+    fn uncompressed(value: &[u8]) -> Vec<u8> {
+        let mut tagged = Vec::with_capacity(value.len() + 1);
+        tagged.push(0); // No compression marker
+        tagged.extend_from_slice(value);
+        tagged
+    }
+    
     /// Decompress value if needed
     fn decompress_if_needed(&self, data: &[u8]) -> Result<Vec<u8>, StorageError> {
         if data.is_empty() {
@@ -654,15 +688,21 @@ pub struct LSMConfig {
 }
 
 // Statistics structures
+#[derive(Debug, Clone, Default)]
 pub struct StorageStats {
     pub reads: u64,
     pub writes: u64,
+    /// `contains` lookups, which never read values
+    pub existence_checks: u64,
     pub cache_hit_rate: f64,
     pub avg_read_latency_ms: f64,
     pub avg_write_latency_ms: f64,
     pub storage_size_gb: f64,
     pub cache_size_mb: f64,
     pub compaction_count: u64,
+    /// Unmerged SST files; compaction is worthwhile as this grows
+    pub sst_file_count: usize,
+    pub last_compaction: Option<CompactionRecord>,
 }
 
 pub struct CacheStats {
@@ -820,6 +860,39 @@ impl LSMEngine {
         Ok(None)
     }
     
+    /// Same lookup order as `get` without cloning the value
+    async fn contains(&self, key: &[u8]) -> Result<bool, StorageError> {
+        {
+            let memtable = self.memtable.read().await;
+            if let Some(entry) = memtable.data.get(key) {
+                return Ok(matches!(entry.operation, Operation::Put));
+            }
+        }
+        
+        {
+            let immutable_tables = self.immutable_tables.read().await;
+            for table in immutable_tables.iter().rev() {
+                if let Some(entry) = table.data.get(key) {
+                    return Ok(matches!(entry.operation, Operation::Put));
+                }
+            }
+        }
+        
+        {
+            let sst_files = self.sst_files.read().await;
+            for sst_file in sst_files.iter().rev() {
+                if self.key_in_range(key, &sst_file.key_range)
+                    && sst_file.index.bloom.might_contain(key)
+                    && self.search_sst_file(sst_file, key).await?.is_some()
+                {
+                    return Ok(true);
+                }
+            }
+        }
+        
+        Ok(false)
+    }
+    
     async fn batch_put(&self, operations: Vec<(Vec<u8>, Vec<u8>)>) -> Result<(), StorageError> {
         for (key, value) in operations {
             self.put(&key, &value).await?;
@@ -899,10 +972,11 @@ impl LSMEngine {
         // Move current memtable to immutable
         let old_memtable = {
             let mut memtable = self.memtable.write().await;
+            let max_size = memtable.max_size;
             let old = std::mem::replace(&mut *memtable, MemTable {
                 data: HashMap::new(),
                 size: 0,
-                max_size: memtable.max_size,
+                max_size,
                 created_at: std::time::SystemTime::now()
                     .duration_since(std::time::UNIX_EPOCH)
                     .unwrap()
@@ -1182,6 +1256,10 @@ impl LRUCache {
         self.current_size += entry_size;
     }
     
+    fn contains(&self, key: &[u8]) -> bool {
+        self.entries.contains_key(key)
+    }
+    
     fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
         if let Some(entry) = self.entries.get(key) {
             // In production, would update access time and order
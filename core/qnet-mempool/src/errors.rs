@@ -18,6 +18,14 @@ pub enum MempoolError {
     #[error("Nonce too low: expected >= {expected}, got {got}")]
     NonceTooLow { expected: u64, got: u64 },
     
+    /// Nonce further ahead of the account than pending transactions may queue
+    #[error("Nonce too far ahead: maximum {max}, got {got}")]
+    NonceTooFarAhead { max: u64, got: u64 },
+    
+    /// Sender cannot pay for this transaction on top of its pending ones
+    #[error("Insufficient balance: need {required}, have {balance}")]
+    InsufficientBalance { required: u64, balance: u64 },
+    
     /// Nonce gap detected
     #[error("Nonce gap: expected {expected}, got {got}")]
     NonceGap { expected: u64, got: u64 },
@@ -26,6 +34,10 @@ pub enum MempoolError {
     #[error("Gas price too low: minimum {min}, got {got}")]
     GasPriceTooLow { min: u64, got: u64 },
     
+    /// Replacement for a pending transaction at the same nonce does not outbid it
+    #[error("Replacement underpriced: minimum gas price {min}, got {got}")]
+    ReplacementUnderpriced { min: u64, got: u64 },
+    
     /// Mempool is full
     #[error("Mempool is full: capacity {capacity}")]
     MempoolFull { capacity: usize },
@@ -34,6 +46,30 @@ pub enum MempoolError {
     #[error("Account transaction limit exceeded: {limit}")]
     AccountLimitExceeded { limit: usize },
     
+    /// Transfer below the dust threshold
+    #[error("Dust transaction: minimum amount {min}, got {got}")]
+    DustTransaction { min: u64, got: u64 },
+    
+    /// Sender exceeded its admission rate
+    #[error("Sender {sender} rate limited: more than {limit} transactions in {window_secs}s")]
+    SenderRateLimited { sender: String, limit: usize, window_secs: u64 },
+    
+    /// Fee does not cover the transaction size at current mempool load
+    #[error("Fee per byte too low: minimum {min}, got {got}")]
+    FeePerByteTooLow { min: u64, got: u64 },
+    
+    /// Transaction or its data payload exceeds the network size cap
+    #[error("Transaction too large: {what} is {size} bytes, maximum {max}")]
+    TransactionTooLarge { what: &'static str, size: usize, max: usize },
+    
+    /// Transaction was signed for a different network
+    #[error("Chain id mismatch: expected {expected}, got {got}")]
+    ChainIdMismatch { expected: u64, got: u64 },
+    
+    /// Transaction past its `valid_until`
+    #[error("Transaction no longer valid: valid until {valid_until:?}, block height {height} at {timestamp}")]
+    ValidityExpired { valid_until: qnet_state::transaction::ValidUntil, height: u64, timestamp: u64 },
+    
     /// Transaction expired
     #[error("Transaction expired: age {age_secs}s > max {max_age_secs}s")]
     TransactionExpired { age_secs: u64, max_age_secs: u64 },
@@ -23,8 +23,9 @@ pub use config::MempoolConfig;
 pub use errors::{MempoolError, MempoolResult};
 pub use mempool::Mempool;
 pub use priority::TxPriority;
-pub use validation::SimpleValidator;
-pub use simple_mempool::{SimpleMempool, SimpleMempoolConfig};
+pub use validation::{SimpleValidator, AdmissionConfig, AccountView};
+pub use simple_mempool::{SimpleMempool, SimpleMempoolConfig, EvictionPolicy, GasFloorConfig, MempoolChanges};
+pub use metrics::MempoolMetrics;
 pub use mev_protection::{TxBundle, MevProtectedMempool, BundleAllocationConfig};
 
 /// Prelude for common imports
@@ -3,7 +3,7 @@
 use crate::{
     errors::{MempoolError, MempoolResult},
     priority::{TxPriority, PriorityCalculator, DefaultPriorityCalculator},
-    validation::{TxValidator, DefaultValidator, SimpleValidator},
+    validation::{TxValidator, DefaultValidator, SimpleValidator, AccountView},
     eviction::EvictionPolicy,
 };
 use qnet_state::{StateDB, transaction::{Transaction, TxHash}};
@@ -137,6 +137,13 @@ impl Mempool {
         }
     }
     
+    /// Simple mempool checking balances and nonces against `accounts` at admission
+    pub fn new_simple_with_accounts(config: MempoolConfig, accounts: Arc<dyn AccountView>) -> Self {
+        let mut mempool = Self::new_simple(config);
+        mempool.validator = Arc::new(SimpleValidator::new(mempool.config.min_gas_price).with_account_view(accounts));
+        mempool
+    }
+    
     /// Create simple mempool without StateDB (for Python bindings)
     pub fn new_simple(config: MempoolConfig) -> Self {
         let validator = Arc::new(SimpleValidator::new(config.min_gas_price));
@@ -153,6 +160,11 @@ impl Mempool {
         }
     }
     
+    /// Fraction of capacity in use (0.0 - 1.0)
+    fn pool_fill(&self) -> f64 {
+        self.transactions.len() as f64 / self.config.max_size.max(1) as f64
+    }
+    
     /// Add transaction to mempool
     pub async fn add_transaction(&self, tx: Transaction) -> MempoolResult<()> {
         // Check if already exists
@@ -171,8 +183,29 @@ impl Mempool {
             ));
         }
         
+        // Dust, rate limit and fee-per-byte rules
+        self.validator.check_admission(&tx, self.pool_fill())?;
+        
+        // Balance and nonce, counting the sender's queued transactions
+        let pending: Vec<Transaction> = self.by_sender.get(&tx.from)
+            .map(|nonces| nonces.values().filter_map(|hash| self.transactions.get(hash).map(|entry| entry.tx.clone())).collect())
+            .unwrap_or_default();
+        self.validator.check_account(&tx, &pending)?;
+        
+        // Replace-by-fee: a transaction at a pending nonce must outbid the pending one,
+        // which it then evicts (this is how cancellations take effect)
+        let replaces = self.by_sender.get(&tx.from).and_then(|nonces| nonces.get(&tx.nonce).cloned());
+        if let Some(pending_hash) = &replaces {
+            if let Some(pending_tx) = self.transactions.get(pending_hash) {
+                let min = crate::validation::replacement_min_gas_price(pending_tx.tx.gas_price);
+                if tx.gas_price < min {
+                    return Err(MempoolError::ReplacementUnderpriced { min, got: tx.gas_price });
+                }
+            }
+        }
+        
         // Check mempool capacity
-        if self.transactions.len() >= self.config.max_size {
+        if replaces.is_none() && self.transactions.len() >= self.config.max_size {
             // Try eviction
             self.evict_transactions(1);
             
@@ -187,7 +220,7 @@ impl Mempool {
         // Check per-account limit
         let sender_txs = self.by_sender.get(&tx.from);
         if let Some(sender_txs) = sender_txs {
-            if sender_txs.len() >= self.config.max_per_account {
+            if replaces.is_none() && sender_txs.len() >= self.config.max_per_account {
                 return Err(MempoolError::AccountLimitExceeded {
                     limit: self.config.max_per_account,
                 });
@@ -204,6 +237,11 @@ impl Mempool {
             }
         }
         
+        if let Some(pending_hash) = replaces {
+            self.remove_transaction(&pending_hash);
+            info!("Transaction {} replaced by {}", format_hash_for_log(&pending_hash), format_hash_for_log(&tx.hash));
+        }
+        
         // Calculate priority
         let priority = self.priority_calc.calculate_priority(&tx);
         
@@ -302,6 +340,10 @@ impl Mempool {
                 continue;
             }
             
+            if self.validator.check_admission(&tx, self.pool_fill()).is_err() {
+                continue;
+            }
+            
             // Check capacity
             if self.transactions.len() >= self.config.max_size {
                 self.evict_transactions(1);
@@ -367,12 +409,16 @@ impl Mempool {
         }
     }
     
-    /// Get top transactions by priority
+    /// Get top transactions by priority (class first, then gas price)
     pub fn get_top_transactions(&self, limit: usize) -> Vec<Transaction> {
         let queue = self.priority_queue.read();
         let mut txs = Vec::with_capacity(limit.min(queue.len()));
         
-        for (hash, _priority) in queue.iter().take(limit) {
+        // Queue iteration is unordered - sort so System transactions are never cut off by the limit
+        let mut ranked: Vec<_> = queue.iter().collect();
+        ranked.sort_by(|a, b| b.1.cmp(a.1));
+        
+        for (hash, _priority) in ranked.into_iter().take(limit) {
             if let Some(tx) = self.get_transaction(hash) {
                 txs.push(tx);
             }
@@ -449,9 +495,9 @@ impl Mempool {
         if to_remove.len() < count {
             let queue = self.priority_queue.read();
             let mut priorities: Vec<_> = queue.iter()
-                .map(|(hash, priority)| (hash.clone(), priority.score))
+                .map(|(hash, priority)| (hash.clone(), priority.clone()))
                 .collect();
-            priorities.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+            priorities.sort_by(|a, b| a.1.cmp(&b.1));
             
             for (hash, _) in priorities.into_iter().take(count - to_remove.len()) {
                 to_remove.push(hash);
@@ -543,6 +589,8 @@ impl Mempool {
                 amount: 0,
             },
             data: None,
+            chain_id: qnet_state::transaction::UNBOUND_CHAIN_ID,
+            valid_until: None,
         };
         
         let priority = self.priority_calc.calculate_priority(&tx);
@@ -1,10 +1,11 @@
 //! Mempool metrics
 
 use prometheus::{
-    register_counter_vec, register_gauge_vec, register_histogram_vec,
-    CounterVec, GaugeVec, HistogramVec,
+    register_counter_vec, register_gauge, register_gauge_vec, register_histogram_vec,
+    CounterVec, Gauge, GaugeVec, HistogramVec,
 };
 use lazy_static::lazy_static;
+use serde::Serialize;
 
 lazy_static! {
     /// Transaction operations counter
@@ -43,6 +44,43 @@ lazy_static! {
         "Total number of evictions",
         &["reason"]
     ).unwrap();
+    
+    /// Age of the oldest pending transaction
+    pub static ref OLDEST_TX_AGE: Gauge = register_gauge!(
+        "qnet_mempool_oldest_tx_age_seconds",
+        "Age of the oldest transaction in mempool"
+    ).unwrap();
+    
+    /// Average gas price of pending transactions
+    pub static ref AVERAGE_GAS_PRICE: Gauge = register_gauge!(
+        "qnet_mempool_average_gas_price",
+        "Average gas price of transactions in mempool"
+    ).unwrap();
+}
+
+/// Snapshot of mempool depth, evictions and age
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MempoolMetrics {
+    /// Transactions currently pending
+    pub size: usize,
+    /// Maximum pending transactions
+    pub capacity: usize,
+    /// Transactions evicted since start
+    pub evictions: u64,
+    /// Age of the oldest pending transaction
+    pub oldest_entry_age_secs: u64,
+    /// Average gas price of pending transactions
+    pub average_gas_price: u64,
+}
+
+impl MempoolMetrics {
+    /// Update the Prometheus gauges from this snapshot
+    pub fn publish(&self) {
+        MEMPOOL_SIZE.with_label_values(&["total"]).set(self.size as f64);
+        MEMPOOL_SIZE.with_label_values(&["capacity"]).set(self.capacity as f64);
+        OLDEST_TX_AGE.set(self.oldest_entry_age_secs as f64);
+        AVERAGE_GAS_PRICE.set(self.average_gas_price as f64);
+    }
 }
 
 /// Record transaction operation
@@ -1,13 +1,41 @@
 ﻿//! Transaction priority calculation
 
-use qnet_state::transaction::Transaction;
+use qnet_state::transaction::{Transaction, TransactionType};
 use std::cmp::Ordering;
 use std::time::Instant;
 
+/// Inclusion class, compared before gas price
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PriorityClass {
+    /// User transactions - ordered by fee
+    Normal = 0,
+    /// Node activations - included regardless of fee
+    High = 1,
+    /// Emission and ping commitments - never crowded out by user fees
+    System = 2,
+}
+
+impl PriorityClass {
+    /// Derive class from transaction type
+    pub fn from_transaction_type(tx_type: &TransactionType) -> Self {
+        match tx_type {
+            TransactionType::RewardDistribution
+            | TransactionType::PingAttestation { .. }
+            | TransactionType::PingCommitmentWithSampling { .. } => Self::System,
+            TransactionType::NodeActivation { .. }
+            | TransactionType::BatchNodeActivations { .. } => Self::High,
+            _ => Self::Normal,
+        }
+    }
+}
+
 /// Transaction priority information
 #[derive(Debug, Clone)]
 pub struct TxPriority {
-    /// Gas price (primary factor)
+    /// Priority class (primary factor)
+    pub class: PriorityClass,
+    
+    /// Gas price (ordering within a class)
     pub gas_price: u64,
     
     /// Time when transaction was added
@@ -28,6 +56,7 @@ impl TxPriority {
     pub fn new(tx: &Transaction, is_priority: bool) -> Self {
         let size = bincode::serialize(tx).unwrap().len();
         let mut priority = Self {
+            class: PriorityClass::from_transaction_type(&tx.tx_type),
             gas_price: tx.gas_price,
             timestamp: Instant::now(),
             size,
@@ -68,7 +97,7 @@ impl TxPriority {
 
 impl PartialEq for TxPriority {
     fn eq(&self, other: &Self) -> bool {
-        self.score == other.score
+        self.class == other.class && self.score == other.score
     }
 }
 
@@ -76,8 +105,11 @@ impl Eq for TxPriority {}
 
 impl PartialOrd for TxPriority {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        // Higher score = higher priority
-        self.score.partial_cmp(&other.score)
+        // Higher class first, then higher score within the class
+        match self.class.cmp(&other.class) {
+            Ordering::Equal => self.score.partial_cmp(&other.score),
+            ordering => Some(ordering),
+        }
     }
 }
 
@@ -136,3 +168,59 @@ impl PriorityCalculator for DefaultPriorityCalculator {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tx(tx_type: TransactionType, gas_price: u64) -> Transaction {
+        Transaction::new(
+            "sender".to_string(), Some("receiver".to_string()), 1_000, 0,
+            gas_price, 10_000, 1_700_000_000, None, tx_type, None,
+        )
+    }
+
+    fn transfer() -> TransactionType {
+        TransactionType::Transfer { from: "sender".to_string(), to: "receiver".to_string(), amount: 1_000 }
+    }
+
+    #[test]
+    fn test_class_from_transaction_type() {
+        assert_eq!(PriorityClass::from_transaction_type(&TransactionType::RewardDistribution), PriorityClass::System);
+        assert_eq!(PriorityClass::from_transaction_type(&transfer()), PriorityClass::Normal);
+        assert_eq!(PriorityClass::from_transaction_type(&TransactionType::ContractCall), PriorityClass::Normal);
+    }
+
+    #[test]
+    fn test_low_fee_system_outranks_high_fee_transfer() {
+        let system = TxPriority::new(&tx(TransactionType::RewardDistribution, 1), false);
+        let transfer_high = TxPriority::new(&tx(transfer(), 1_000_000), true);
+        let transfer_low = TxPriority::new(&tx(transfer(), 100), false);
+
+        assert!(system > transfer_high);
+        // Gas price still orders transactions within a class
+        assert!(transfer_high > transfer_low);
+    }
+
+    #[tokio::test]
+    async fn test_mempool_selects_system_transaction_first() {
+        let config = crate::mempool::MempoolConfig::default();
+        let min_gas_price = config.min_gas_price;
+        let mempool = crate::mempool::Mempool::new_simple(config);
+
+        for i in 0..5u64 {
+            let mut user_tx = tx(transfer(), min_gas_price * (100 + i));
+            user_tx.from = format!("user_{}", i);
+            user_tx.hash = user_tx.calculate_hash();
+            mempool.add_transaction(user_tx).await.unwrap();
+        }
+        let mut emission = tx(TransactionType::RewardDistribution, min_gas_price);
+        emission.from = "system_emission".to_string();
+        emission.hash = emission.calculate_hash();
+        mempool.add_transaction(emission.clone()).await.unwrap();
+
+        let selected = mempool.get_top_transactions(2);
+        assert_eq!(selected.len(), 2);
+        assert_eq!(selected[0].hash, emission.hash);
+        assert_eq!(selected[1].gas_price, min_gas_price * 104);
+    }
+}
@@ -25,6 +25,7 @@ impl PyMempoolConfig {
             inner: SimpleMempoolConfig {
                 max_size,
                 min_gas_price,
+                ..SimpleMempoolConfig::default()
             },
         }
     }
@@ -2,18 +2,123 @@
 
 use dashmap::DashMap;
 use std::sync::Arc;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use std::collections::{VecDeque, BTreeMap};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::Instant;
 use serde::{Serialize, Deserialize};
 use bincode;
 use hex;
 use sha3::{Sha3_256, Digest};
+use qnet_state::transaction::Transaction;
+use crate::metrics::{self, MempoolMetrics};
+use crate::priority::PriorityClass;
+use crate::validation::replacement_min_gas_price;
+
+/// Which transaction a full pool drops to make room
+///
+/// Victims always come from the lowest priority class present, and never from a
+/// class above the incoming transaction's: system and node activation transactions
+/// are not evicted for user transfers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EvictionPolicy {
+    /// Cheapest transaction, oldest first within a gas price; same-class victims
+    /// must pay less than the incoming transaction
+    #[default]
+    LowestFee,
+    /// Earliest admitted transaction (scans the pool)
+    Oldest,
+    /// Largest stored transaction, cheapest first within a size (scans the pool)
+    LargestSize,
+}
+
+impl std::str::FromStr for EvictionPolicy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "lowest_fee" | "lowestfee" => Ok(Self::LowestFee),
+            "oldest" => Ok(Self::Oldest),
+            "largest_size" | "largestsize" | "largest" => Ok(Self::LargestSize),
+            other => Err(format!("Unknown eviction policy '{}': expected lowest_fee, oldest or largest_size", other)),
+        }
+    }
+}
+
+impl EvictionPolicy {
+    /// Policy from `QNET_MEMPOOL_EVICTION`, `LowestFee` if unset or invalid
+    pub fn from_env() -> Self {
+        match std::env::var("QNET_MEMPOOL_EVICTION") {
+            Ok(value) => value.parse().unwrap_or_else(|e| {
+                println!("[MEMPOOL] ⚠️ {} - using lowest_fee", e);
+                Self::LowestFee
+            }),
+            Err(_) => Self::LowestFee,
+        }
+    }
+}
+
+/// Congestion pricing for admission
+///
+/// Below `start_pct` fullness the floor is `min_gas_price`; every further
+/// `step_pct` of fullness raises it by `increase_pct` percent of that base.
+/// System transactions are never priced out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GasFloorConfig {
+    /// Pool fullness (percent) at which the floor starts rising
+    pub start_pct: u64,
+    /// Fullness (percent) per increase step
+    pub step_pct: u64,
+    /// Floor increase per step, as a percent of `min_gas_price` (0 keeps it static)
+    pub increase_pct: u64,
+}
+
+impl Default for GasFloorConfig {
+    fn default() -> Self {
+        Self {
+            start_pct: 50,
+            step_pct: 10,
+            increase_pct: 50,
+        }
+    }
+}
+
+impl GasFloorConfig {
+    /// Config with `QNET_MEMPOOL_FLOOR_START_PCT` / `QNET_MEMPOOL_FLOOR_STEP_PCT` /
+    /// `QNET_MEMPOOL_FLOOR_INCREASE_PCT` overrides
+    pub fn from_env() -> Self {
+        let var = |name: &str| std::env::var(name).ok().and_then(|v| v.trim().parse::<u64>().ok());
+        let defaults = Self::default();
+        Self {
+            start_pct: var("QNET_MEMPOOL_FLOOR_START_PCT").filter(|&v| v <= 100).unwrap_or(defaults.start_pct),
+            step_pct: var("QNET_MEMPOOL_FLOOR_STEP_PCT").filter(|&v| v > 0).unwrap_or(defaults.step_pct),
+            increase_pct: var("QNET_MEMPOOL_FLOOR_INCREASE_PCT").unwrap_or(defaults.increase_pct),
+        }
+    }
+
+    /// Minimum gas price for a pool holding `size` of `capacity` transactions
+    pub fn floor(&self, min_gas_price: u64, size: usize, capacity: usize) -> u64 {
+        let fullness_pct = (size as u64 * 100) / capacity.max(1) as u64;
+        if fullness_pct < self.start_pct {
+            return min_gas_price;
+        }
+        let steps = (fullness_pct - self.start_pct) / self.step_pct.max(1);
+        let percent = 100 + steps as u128 * self.increase_pct as u128;
+        // Round up so small base prices still rise
+        let floor = (min_gas_price as u128 * percent).div_ceil(100);
+        floor.min(u64::MAX as u128) as u64
+    }
+}
 
 /// Simple mempool configuration
 #[derive(Debug, Clone)]
 pub struct SimpleMempoolConfig {
     pub max_size: usize,
     pub min_gas_price: u64,
+    /// Victim selection when the pool is full
+    pub eviction_policy: EvictionPolicy,
+    /// Congestion-scaled admission floor
+    pub gas_floor: GasFloorConfig,
 }
 
 impl Default for SimpleMempoolConfig {
@@ -21,6 +126,8 @@ impl Default for SimpleMempoolConfig {
         Self {
             max_size: 500_000, // Production default: 500k transactions
             min_gas_price: 100_000, // PRODUCTION: 0.0001 QNC (BASE_FEE_NANO_QNC from qnet-state)
+            eviction_policy: EvictionPolicy::default(),
+            gas_floor: GasFloorConfig::default(),
         }
     }
 }
@@ -32,6 +139,69 @@ enum TxStorage {
     Binary(Vec<u8>),
 }
 
+impl TxStorage {
+    fn bytes(&self) -> &[u8] {
+        match self {
+            TxStorage::Json(json) => json.as_bytes(),
+            TxStorage::Binary(bytes) => bytes,
+        }
+    }
+}
+
+/// Per-transaction bookkeeping for eviction and metrics
+#[derive(Debug, Clone, Copy)]
+struct EntryInfo {
+    gas_price: u64,
+    added_at: Instant,
+    /// Admission order (ties in `added_at` are possible)
+    seq: u64,
+    size: usize,
+    class: PriorityClass,
+}
+
+/// Changes kept for `changes_since` before pollers must resync
+pub const CHANGE_LOG_CAPACITY: usize = 10_000;
+
+/// One addition or removal, numbered by the pool's change sequence
+#[derive(Debug, Clone)]
+struct ChangeEntry {
+    seq: u64,
+    /// Pool key
+    key: String,
+    /// Transaction's own hash (pool key if undecodable)
+    tx_hash: String,
+    added: bool,
+}
+
+/// Bounded log of pool changes
+#[derive(Debug, Default)]
+struct ChangeLog {
+    /// Sequence of the latest change
+    sequence: u64,
+    entries: VecDeque<ChangeEntry>,
+    /// Changes up to this sequence are no longer in the log
+    truncated_through: u64,
+}
+
+/// Pool changes after a client's last seen sequence
+#[derive(Debug, Clone, Serialize)]
+pub struct MempoolChanges {
+    /// Current sequence, to pass as `since` on the next poll
+    pub sequence: u64,
+    /// `since` predates the change log (or is from the future): refetch the full pool
+    pub full_resync: bool,
+    /// Transactions added since `since` and still pending
+    pub added: Vec<Transaction>,
+    /// Hashes of transactions removed since `since`
+    pub removed: Vec<String>,
+}
+
+/// Transaction from stored bytes (JSON or bincode)
+fn decode(bytes: &[u8]) -> Option<Transaction> {
+    serde_json::from_slice::<Transaction>(bytes).ok()
+        .or_else(|| bincode::deserialize::<Transaction>(bytes).ok())
+}
+
 /// Optimized mempool implementation with binary support and priority queue
 /// ARCHITECTURE: Priority-based transaction ordering for spam protection
 pub struct SimpleMempool {
@@ -40,6 +210,19 @@ pub struct SimpleMempool {
     // PRODUCTION: Priority queue (BTreeMap) sorted by gas_price descending
     // Key: gas_price (u64), Value: FIFO queue of tx hashes at that price
     by_gas_price: Arc<RwLock<BTreeMap<u64, VecDeque<String>>>>,
+    // hash -> gas price, age, size and class for eviction and metrics
+    entry_info: Arc<DashMap<String, EntryInfo>>,
+    // Transaction's own hash -> pool key (pool keys hash the stored bytes)
+    by_tx_hash: Arc<DashMap<String, String>>,
+    // Sender -> nonce -> pool key, for balance and nonce checks on queued transactions
+    by_sender: Arc<DashMap<String, BTreeMap<u64, String>>>,
+    // Entries per PriorityClass, to find the lowest class present without a scan
+    class_counts: Arc<[AtomicUsize; 3]>,
+    next_seq: Arc<AtomicU64>,
+    evictions: Arc<AtomicU64>,
+    // Additions and removals for diff polling
+    changes: Arc<Mutex<ChangeLog>>,
+    change_log_capacity: usize,
     use_binary: bool, // Toggle for binary storage
 }
 
@@ -53,6 +236,14 @@ impl SimpleMempool {
             config,
             transactions: Arc::new(DashMap::new()),
             by_gas_price: Arc::new(RwLock::new(BTreeMap::new())),
+            entry_info: Arc::new(DashMap::new()),
+            by_tx_hash: Arc::new(DashMap::new()),
+            by_sender: Arc::new(DashMap::new()),
+            class_counts: Arc::new(Default::default()),
+            next_seq: Arc::new(AtomicU64::new(0)),
+            evictions: Arc::new(AtomicU64::new(0)),
+            changes: Arc::new(Mutex::new(ChangeLog::default())),
+            change_log_capacity: CHANGE_LOG_CAPACITY,
             use_binary,
         }
     }
@@ -61,10 +252,6 @@ impl SimpleMempool {
     /// PRODUCTION: Priority-based insertion for spam protection
     /// gas_price: Transaction gas price for priority sorting (higher = earlier processing)
     pub fn add_raw_transaction(&self, tx_json: String, hash: String, gas_price: u64) -> bool {
-        if self.transactions.len() >= self.config.max_size {
-            return false;
-        }
-        
         if self.transactions.contains_key(&hash) {
             return false;
         }
@@ -83,27 +270,13 @@ impl SimpleMempool {
             TxStorage::Json(tx_json)
         };
         
-        self.transactions.insert(hash.clone(), storage);
-        
-        // PRODUCTION: Add to priority queue (sorted by gas_price descending)
-        // FIFO order within same gas_price (fair for same-price transactions)
-        let mut priority_queue = self.by_gas_price.write();
-        priority_queue
-            .entry(gas_price)
-            .or_insert_with(VecDeque::new)
-            .push_back(hash);
-        
-        true
+        self.insert(hash, storage, gas_price)
     }
     
     /// Add binary transaction directly with priority
     /// PRODUCTION: Priority-based insertion for spam protection
     /// gas_price: Transaction gas price for priority sorting (higher = earlier processing)
     pub fn add_binary_transaction(&self, tx_bytes: Vec<u8>, hash: String, gas_price: u64) -> bool {
-        if self.transactions.len() >= self.config.max_size {
-            return false;
-        }
-        
         if self.transactions.contains_key(&hash) {
             return false;
         }
@@ -115,9 +288,58 @@ impl SimpleMempool {
             return false; // Reject tampered data
         }
         
-        self.transactions.insert(hash.clone(), TxStorage::Binary(tx_bytes));
+        self.insert(hash, TxStorage::Binary(tx_bytes), gas_price)
+    }
+    
+    /// Store a verified transaction, evicting one per the eviction policy if the pool is full
+    ///
+    /// A transaction at a sender and nonce already pooled replaces the pooled one if it
+    /// pays at least `replacement_min_gas_price`, and is rejected otherwise.
+    fn insert(&self, hash: String, storage: TxStorage, gas_price: u64) -> bool {
+        let size = storage.bytes().len();
+        // Unparseable data is Normal
+        let tx = decode(storage.bytes());
+        let class = tx.as_ref()
+            .map(|tx| PriorityClass::from_transaction_type(&tx.tx_type))
+            .unwrap_or(PriorityClass::Normal);
+        if class != PriorityClass::System && gas_price < self.effective_min_gas_price() {
+            return false;
+        }
+        let replaces = tx.as_ref().and_then(|tx| self.pooled_at(&tx.from, tx.nonce));
+        if let Some(pending) = &replaces {
+            let min = self.entry_info.get(pending).map_or(0, |info| replacement_min_gas_price(info.gas_price));
+            if gas_price < min {
+                return false;
+            }
+        }
+        let info = EntryInfo {
+            gas_price,
+            added_at: Instant::now(),
+            seq: self.next_seq.fetch_add(1, Ordering::Relaxed),
+            size,
+            class,
+        };
+        if replaces.is_none() && self.transactions.len() >= self.config.max_size && !self.evict_for(&info) {
+            return false;
+        }
+        if let Some(pending) = replaces {
+            self.remove_transaction(&pending);
+        }
+        
+        self.transactions.insert(hash.clone(), storage);
+        self.entry_info.insert(hash.clone(), info);
+        if let Some(tx) = &tx {
+            self.by_sender.entry(tx.from.clone()).or_default().insert(tx.nonce, hash.clone());
+        }
+        let tx_hash = tx.map(|tx| tx.hash).filter(|tx_hash| !tx_hash.is_empty()).unwrap_or_else(|| hash.clone());
+        self.record_change(&hash, tx_hash.clone(), true);
+        if tx_hash != hash {
+            self.by_tx_hash.insert(tx_hash, hash.clone());
+        }
+        self.class_counts[class as usize].fetch_add(1, Ordering::Relaxed);
         
         // PRODUCTION: Add to priority queue (sorted by gas_price descending)
+        // FIFO order within same gas_price (fair for same-price transactions)
         let mut priority_queue = self.by_gas_price.write();
         priority_queue
             .entry(gas_price)
@@ -127,6 +349,69 @@ impl SimpleMempool {
         true
     }
     
+    /// Evict one transaction to make room for `incoming`, false if nothing may be evicted
+    fn evict_for(&self, incoming: &EntryInfo) -> bool {
+        let Some(hash) = self.select_victim(incoming) else { return false };
+        let Some((_, info)) = self.entry_info.remove(&hash) else { return false };
+        if let Some((_, storage)) = self.transactions.remove(&hash) {
+            self.unindex(&hash, &storage);
+        }
+        self.class_counts[info.class as usize].fetch_sub(1, Ordering::Relaxed);
+        
+        let mut priority_queue = self.by_gas_price.write();
+        if let Some(hashes) = priority_queue.get_mut(&info.gas_price) {
+            hashes.retain(|h| *h != hash);
+            if hashes.is_empty() {
+                priority_queue.remove(&info.gas_price);
+            }
+        }
+        
+        metrics::record_tx_age(info.added_at.elapsed().as_secs_f64(), "evicted");
+        self.evictions.fetch_add(1, Ordering::Relaxed);
+        metrics::record_eviction("capacity");
+        true
+    }
+    
+    /// Victim for `incoming` under the configured policy
+    /// SECURITY: Only the lowest class present is evictable, and only if it does not
+    /// outrank the incoming transaction
+    fn select_victim(&self, incoming: &EntryInfo) -> Option<String> {
+        let victim_class = [PriorityClass::Normal, PriorityClass::High, PriorityClass::System]
+            .into_iter()
+            .find(|class| self.class_counts[*class as usize].load(Ordering::Relaxed) > 0)?;
+        if victim_class > incoming.class {
+            return None;
+        }
+        
+        let in_class = |info: &EntryInfo| info.class == victim_class;
+        match self.config.eviction_policy {
+            EvictionPolicy::LowestFee => {
+                // A full pool only admits transactions that outbid its cheapest same-class entry
+                let outbid_required = victim_class == incoming.class;
+                let priority_queue = self.by_gas_price.read();
+                for (gas_price, hashes) in priority_queue.iter() {
+                    if outbid_required && *gas_price >= incoming.gas_price {
+                        return None;
+                    }
+                    let victim = hashes.iter()
+                        .find(|hash| self.entry_info.get(*hash).is_some_and(|info| in_class(&info)));
+                    if let Some(hash) = victim {
+                        return Some(hash.clone());
+                    }
+                }
+                None
+            }
+            EvictionPolicy::Oldest => self.entry_info.iter()
+                .filter(|entry| in_class(entry.value()))
+                .min_by_key(|entry| entry.value().seq)
+                .map(|entry| entry.key().clone()),
+            EvictionPolicy::LargestSize => self.entry_info.iter()
+                .filter(|entry| in_class(entry.value()))
+                .max_by_key(|entry| (entry.value().size, std::cmp::Reverse(entry.value().gas_price)))
+                .map(|entry| entry.key().clone()),
+        }
+    }
+    
     /// Get raw transaction (handles both formats)
     pub fn get_raw_transaction(&self, hash: &str) -> Option<String> {
         self.transactions.get(hash).and_then(|entry| {
@@ -157,6 +442,114 @@ impl SimpleMempool {
         })
     }
     
+    /// Pending transaction by pool key or by the transaction's own hash
+    pub fn get_by_hash(&self, hash: &str) -> Option<Transaction> {
+        let key = match self.by_tx_hash.get(hash) {
+            Some(key) if !self.transactions.contains_key(hash) => key.clone(),
+            _ => hash.to_string(),
+        };
+        self.transactions.get(&key).and_then(|entry| decode(entry.value().bytes()))
+    }
+    
+    /// Pending transactions from `sender`, in nonce order
+    pub fn pending_from(&self, sender: &str) -> Vec<Transaction> {
+        let Some(nonces) = self.by_sender.get(sender) else { return Vec::new() };
+        nonces.values()
+            .filter_map(|key| self.transactions.get(key).and_then(|entry| decode(entry.value().bytes())))
+            .collect()
+    }
+    
+    /// Pool key of `sender`'s pending transaction at `nonce`
+    fn pooled_at(&self, sender: &str, nonce: u64) -> Option<String> {
+        self.by_sender.get(sender).and_then(|nonces| nonces.get(&nonce).cloned())
+    }
+    
+    /// Gas price a transaction from `sender` at `nonce` must pay to replace the one
+    /// pending there, `None` if nothing is pending at that nonce
+    pub fn replacement_min_gas_price(&self, sender: &str, nonce: u64) -> Option<u64> {
+        let pending = self.pooled_at(sender, nonce)?;
+        self.entry_info.get(&pending).map(|info| replacement_min_gas_price(info.gas_price))
+    }
+    
+    /// Drop the index entries of a removed transaction and log the removal
+    fn unindex(&self, key: &str, storage: &TxStorage) {
+        let tx = decode(storage.bytes());
+        if let Some(tx) = &tx {
+            if let dashmap::mapref::entry::Entry::Occupied(mut nonces) = self.by_sender.entry(tx.from.clone()) {
+                if nonces.get().get(&tx.nonce).is_some_and(|k| k == key) {
+                    nonces.get_mut().remove(&tx.nonce);
+                }
+                if nonces.get().is_empty() {
+                    nonces.remove();
+                }
+            }
+        }
+        let tx_hash = tx
+            .map(|tx| tx.hash)
+            .filter(|tx_hash| !tx_hash.is_empty())
+            .unwrap_or_else(|| key.to_string());
+        self.by_tx_hash.remove(&tx_hash);
+        self.record_change(key, tx_hash, false);
+    }
+    
+    /// Append a change under the next sequence, dropping the oldest past capacity
+    fn record_change(&self, key: &str, tx_hash: String, added: bool) {
+        let mut log = self.changes.lock();
+        log.sequence += 1;
+        let seq = log.sequence;
+        log.entries.push_back(ChangeEntry { seq, key: key.to_string(), tx_hash, added });
+        while log.entries.len() > self.change_log_capacity {
+            if let Some(dropped) = log.entries.pop_front() {
+                log.truncated_through = dropped.seq;
+            }
+        }
+    }
+    
+    /// Current change sequence
+    pub fn sequence(&self) -> u64 {
+        self.changes.lock().sequence
+    }
+    
+    /// Net additions and removals after sequence `since`
+    ///
+    /// A transaction added and removed again within the window is omitted; one removed
+    /// and re-added is reported as added. `full_resync` is set, with no changes, when
+    /// `since` is older than the change log or newer than the current sequence.
+    pub fn changes_since(&self, since: u64) -> MempoolChanges {
+        let log = self.changes.lock();
+        if since < log.truncated_through || since > log.sequence {
+            return MempoolChanges { sequence: log.sequence, full_resync: true, added: Vec::new(), removed: Vec::new() };
+        }
+        
+        // First and last change per pool key, in first-seen order
+        let start = log.entries.partition_point(|entry| entry.seq <= since);
+        let mut order: Vec<&ChangeEntry> = Vec::new();
+        let mut net: std::collections::HashMap<&str, (bool, &ChangeEntry)> = std::collections::HashMap::new();
+        for entry in log.entries.range(start..) {
+            match net.get_mut(entry.key.as_str()) {
+                Some((_, last)) => *last = entry,
+                None => {
+                    net.insert(&entry.key, (entry.added, entry));
+                    order.push(entry);
+                }
+            }
+        }
+        
+        let mut added = Vec::new();
+        let mut removed = Vec::new();
+        for first in order {
+            let (first_added, last) = net[first.key.as_str()];
+            if last.added {
+                if let Some(tx) = self.transactions.get(&last.key).and_then(|entry| decode(entry.value().bytes())) {
+                    added.push(tx);
+                }
+            } else if !first_added {
+                removed.push(last.tx_hash.clone());
+            }
+        }
+        MempoolChanges { sequence: log.sequence, full_resync: false, added, removed }
+    }
+    
     /// Get pending transactions (PRIORITY ORDER: highest gas_price first)
     /// PRODUCTION: Anti-spam protection - high-paying transactions processed first
     /// ARCHITECTURE: Prevents spam attacks from blocking legitimate high-value transactions
@@ -176,7 +569,12 @@ impl SimpleMempool {
     /// Remove transaction (must remove from both transactions map AND priority queue)
     /// CRITICAL: Maintains consistency between storage and priority queue
     pub fn remove_transaction(&self, hash: &str) -> bool {
-        if self.transactions.remove(hash).is_some() {
+        if let Some((_, storage)) = self.transactions.remove(hash) {
+            self.unindex(hash, &storage);
+            if let Some((_, info)) = self.entry_info.remove(hash) {
+                self.class_counts[info.class as usize].fetch_sub(1, Ordering::Relaxed);
+                metrics::record_tx_age(info.added_at.elapsed().as_secs_f64(), "removed");
+            }
             // CRITICAL: Also remove from priority queue
             // Iterate all gas_price levels to find and remove this hash
             let mut priority_queue = self.by_gas_price.write();
@@ -193,9 +591,21 @@ impl SimpleMempool {
     
     /// Clear all transactions (both storage and priority queue)
     /// CRITICAL: Clears both data structures to maintain consistency
+    /// Pollers must resync afterwards: the change log is truncated rather than filled with removals
     pub fn clear(&self) {
+        let mut log = self.changes.lock();
+        log.sequence += 1;
+        log.truncated_through = log.sequence;
+        log.entries.clear();
+        drop(log);
         self.transactions.clear();
         self.by_gas_price.write().clear();
+        self.entry_info.clear();
+        self.by_tx_hash.clear();
+        self.by_sender.clear();
+        for count in self.class_counts.iter() {
+            count.store(0, Ordering::Relaxed);
+        }
     }
     
     /// Get mempool size
@@ -207,4 +617,306 @@ impl SimpleMempool {
     pub fn get_min_gas_price(&self) -> u64 {
         self.config.min_gas_price
     }
+    
+    /// Gas price a non-system transaction must pay to be admitted right now
+    pub fn effective_min_gas_price(&self) -> u64 {
+        self.config.gas_floor.floor(self.config.min_gas_price, self.transactions.len(), self.config.max_size)
+    }
+    
+    /// Current depth, eviction and age metrics (also published to Prometheus)
+    pub fn metrics(&self) -> MempoolMetrics {
+        let mut oldest_entry_age_secs = 0;
+        let mut gas_price_total: u128 = 0;
+        for entry in self.entry_info.iter() {
+            let info = entry.value();
+            gas_price_total += info.gas_price as u128;
+            oldest_entry_age_secs = oldest_entry_age_secs.max(info.added_at.elapsed().as_secs());
+        }
+        let count = self.entry_info.len();
+        
+        let snapshot = MempoolMetrics {
+            size: self.transactions.len(),
+            capacity: self.config.max_size,
+            evictions: self.evictions.load(Ordering::Relaxed),
+            oldest_entry_age_secs,
+            average_gas_price: if count > 0 { (gas_price_total / count as u128) as u64 } else { 0 },
+        };
+        snapshot.publish();
+        snapshot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use qnet_state::transaction::TransactionType;
+
+    fn tx(nonce: u64) -> (String, String) {
+        let json = format!("{{\"nonce\":{}}}", nonce);
+        let hash = format!("{:x}", Sha3_256::digest(json.as_bytes()));
+        (json, hash)
+    }
+
+    #[test]
+    fn test_full_pool_evicts_cheapest_and_stays_capped() {
+        let mempool = SimpleMempool::new(SimpleMempoolConfig { max_size: 10, min_gas_price: 1, ..SimpleMempoolConfig::default() });
+        for nonce in 0..10 {
+            let (json, hash) = tx(nonce);
+            assert!(mempool.add_raw_transaction(json, hash, 100 + nonce));
+        }
+        assert_eq!(mempool.metrics().evictions, 0);
+
+        // Higher-paying transactions push out the cheapest ones
+        for nonce in 10..15 {
+            let (json, hash) = tx(nonce);
+            assert!(mempool.add_raw_transaction(json, hash, 1_000 + nonce));
+        }
+        // A transaction that does not outbid the cheapest entry is rejected
+        let (json, hash) = tx(99);
+        assert!(!mempool.add_raw_transaction(json, hash, 1));
+
+        let metrics = mempool.metrics();
+        assert_eq!(metrics.size, 10);
+        assert_eq!(metrics.capacity, 10);
+        assert_eq!(metrics.evictions, 5);
+        assert!(mempool.get_raw_transaction(&tx(0).1).is_none());
+        assert!(mempool.get_raw_transaction(&tx(5).1).is_some());
+        // Remaining: 105..=109 and 1010..=1014
+        assert_eq!(metrics.average_gas_price, (105..=109).chain(1010..=1014).sum::<u64>() / 10);
+        assert_eq!(mempool.get_pending_transactions(10).len(), 10);
+    }
+
+    #[test]
+    fn test_gas_floor_rises_with_congestion() {
+        let mempool = SimpleMempool::new(SimpleMempoolConfig { max_size: 10, min_gas_price: 100, ..SimpleMempoolConfig::default() });
+        assert_eq!(mempool.effective_min_gas_price(), 100);
+        let (json, hash) = tx(0);
+        assert!(!mempool.add_raw_transaction(json, hash, 99));
+
+        // Up to 50% fullness the floor stays at the base price
+        for nonce in 0..5 {
+            let (json, hash) = tx(nonce);
+            assert!(mempool.add_raw_transaction(json, hash, 100));
+        }
+        assert_eq!(mempool.effective_min_gas_price(), 100);
+
+        for nonce in 5..9 {
+            let (json, hash) = tx(nonce);
+            assert!(mempool.add_raw_transaction(json, hash, 1_000));
+        }
+        // 90% full: four steps of +50%
+        assert_eq!(mempool.effective_min_gas_price(), 300);
+        let (json, hash) = tx(100);
+        assert!(!mempool.add_raw_transaction(json.clone(), hash.clone(), 100));
+        assert!(mempool.add_raw_transaction(json, hash, 300));
+
+        // System transactions are not priced out
+        let (json, hash) = typed_tx(101, TransactionType::RewardDistribution, 10);
+        assert!(mempool.add_raw_transaction(json, hash, 0));
+    }
+
+    /// Serialized transaction of the given type; `memo` pads the size
+    fn typed_tx(nonce: u64, tx_type: TransactionType, memo: usize) -> (String, String) {
+        let mut tx = Transaction::new(
+            format!("sender_{}", nonce), Some("receiver".to_string()), 5_000, nonce, 100_000, 10_000,
+            1_700_000_000, None, tx_type, None,
+        );
+        tx.data = Some("m".repeat(memo));
+        let json = serde_json::to_string(&tx).unwrap();
+        let hash = format!("{:x}", Sha3_256::digest(json.as_bytes()));
+        (json, hash)
+    }
+
+    fn transfer(nonce: u64, memo: usize) -> (String, String) {
+        typed_tx(nonce, TransactionType::Transfer { from: "a".to_string(), to: "b".to_string(), amount: 5_000 }, memo)
+    }
+
+    fn pool(policy: EvictionPolicy) -> SimpleMempool {
+        // Static floor: these tests are about eviction, not congestion pricing
+        let gas_floor = GasFloorConfig { increase_pct: 0, ..GasFloorConfig::default() };
+        SimpleMempool::new(SimpleMempoolConfig { max_size: 3, min_gas_price: 1, eviction_policy: policy, gas_floor })
+    }
+
+    /// Fill a 3-slot pool: cheap-large-middle, expensive-small-oldest, mid-fee-small-newest
+    fn filled(policy: EvictionPolicy) -> (SimpleMempool, [String; 3]) {
+        let mempool = pool(policy);
+        let entries = [(transfer(1, 10), 200), (transfer(0, 2_000), 100), (transfer(2, 10), 300)];
+        let mut hashes = Vec::new();
+        for ((json, hash), gas_price) in entries {
+            assert!(mempool.add_raw_transaction(json, hash.clone(), gas_price));
+            hashes.push(hash);
+        }
+        (mempool, hashes.try_into().unwrap())
+    }
+
+    #[test]
+    fn test_each_policy_evicts_expected_victim() {
+        let [oldest, largest_cheapest, newest] = filled(EvictionPolicy::LowestFee).1;
+        for (policy, victim) in [
+            (EvictionPolicy::LowestFee, &largest_cheapest),
+            (EvictionPolicy::Oldest, &oldest),
+            (EvictionPolicy::LargestSize, &largest_cheapest),
+        ] {
+            let (mempool, _) = filled(policy);
+            let (json, hash) = transfer(9, 4_000);
+            assert!(mempool.add_raw_transaction(json, hash.clone(), 150), "{:?}", policy);
+            assert_eq!(mempool.size(), 3);
+            assert!(mempool.get_raw_transaction(victim).is_none(), "{:?} kept its victim", policy);
+            assert!(mempool.get_raw_transaction(&newest).is_some());
+            assert!(mempool.get_raw_transaction(&hash).is_some());
+        }
+
+        // Largest-size picks the new 4k transaction next, unlike lowest-fee
+        let (mempool, _) = filled(EvictionPolicy::LargestSize);
+        let (big, big_hash) = transfer(9, 4_000);
+        assert!(mempool.add_raw_transaction(big, big_hash.clone(), 150));
+        let (small, small_hash) = transfer(10, 10);
+        assert!(mempool.add_raw_transaction(small, small_hash, 50));
+        assert!(mempool.get_raw_transaction(&big_hash).is_none());
+
+        // Lowest-fee still requires outbidding the cheapest entry
+        let (mempool, _) = filled(EvictionPolicy::LowestFee);
+        let (json, hash) = transfer(11, 10);
+        assert!(!mempool.add_raw_transaction(json, hash, 100));
+
+        assert_eq!("oldest".parse::<EvictionPolicy>(), Ok(EvictionPolicy::Oldest));
+        assert_eq!("largest_size".parse::<EvictionPolicy>(), Ok(EvictionPolicy::LargestSize));
+        assert!("newest".parse::<EvictionPolicy>().is_err());
+    }
+
+    #[test]
+    fn test_eviction_never_drops_higher_class_for_normal() {
+        let activation = || TransactionType::NodeActivation {
+            node_type: qnet_state::account::NodeType::Light,
+            amount: 0,
+            phase: qnet_state::account::ActivationPhase::Phase1,
+        };
+        for policy in [EvictionPolicy::LowestFee, EvictionPolicy::Oldest, EvictionPolicy::LargestSize] {
+            let mempool = pool(policy);
+            let system = typed_tx(0, TransactionType::RewardDistribution, 5_000);
+            let high = typed_tx(1, activation(), 5_000);
+            let normal = transfer(2, 10);
+            assert!(mempool.add_raw_transaction(system.0, system.1.clone(), 0));
+            assert!(mempool.add_raw_transaction(high.0, high.1.clone(), 1));
+            assert!(mempool.add_raw_transaction(normal.0, normal.1.clone(), 1_000));
+
+            // A high-priority transaction displaces the normal one, even one paying more
+            let high2 = typed_tx(3, activation(), 10);
+            assert!(mempool.add_raw_transaction(high2.0, high2.1.clone(), 1), "{:?}", policy);
+            assert!(mempool.get_raw_transaction(&normal.1).is_none());
+
+            // With no normal transactions left, normal ones are turned away at any fee
+            let rich = transfer(4, 10_000);
+            assert!(!mempool.add_raw_transaction(rich.0, rich.1, u64::MAX), "{:?}", policy);
+            for hash in [&system.1, &high.1, &high2.1] {
+                assert!(mempool.get_raw_transaction(hash).is_some());
+            }
+            assert_eq!(mempool.metrics().evictions, 1);
+        }
+    }
+
+    #[test]
+    fn test_get_by_hash_finds_pool_and_transaction_hashes() {
+        let mempool = pool(EvictionPolicy::LowestFee);
+        let (json, key) = transfer(7, 10);
+        let tx: Transaction = serde_json::from_str(&json).unwrap();
+        assert_ne!(tx.hash, key);
+        assert!(mempool.add_raw_transaction(json, key.clone(), 100));
+
+        assert_eq!(mempool.get_by_hash(&key).unwrap().nonce, 7);
+        assert_eq!(mempool.get_by_hash(&tx.hash).unwrap().hash, tx.hash);
+        assert!(mempool.get_by_hash("unknown").is_none());
+
+        assert!(mempool.remove_transaction(&key));
+        assert!(mempool.get_by_hash(&tx.hash).is_none());
+        assert!(mempool.by_tx_hash.is_empty());
+    }
+
+    #[test]
+    fn test_changes_since_reports_exact_diff() {
+        let mut mempool = SimpleMempool::new(SimpleMempoolConfig { max_size: 10, min_gas_price: 1, ..SimpleMempoolConfig::default() });
+        let entries: Vec<_> = (0..4).map(|nonce| transfer(nonce, 10)).collect();
+        let tx_hash = |json: &str| serde_json::from_str::<Transaction>(json).unwrap().hash;
+        for (json, key) in &entries[..2] {
+            assert!(mempool.add_raw_transaction(json.clone(), key.clone(), 100));
+        }
+        let since = mempool.sequence();
+        assert_eq!(since, 2);
+
+        // Add two, remove one old and one new: only the survivors and the old removal show
+        for (json, key) in &entries[2..] {
+            assert!(mempool.add_raw_transaction(json.clone(), key.clone(), 100));
+        }
+        assert!(mempool.remove_transaction(&entries[0].1));
+        assert!(mempool.remove_transaction(&entries[3].1));
+
+        let diff = mempool.changes_since(since);
+        assert!(!diff.full_resync);
+        assert_eq!(diff.sequence, 6);
+        assert_eq!(diff.added.iter().map(|tx| tx.nonce).collect::<Vec<_>>(), vec![2]);
+        assert_eq!(diff.removed, vec![tx_hash(&entries[0].0)]);
+
+        let current = mempool.changes_since(diff.sequence);
+        assert!(!current.full_resync && current.added.is_empty() && current.removed.is_empty());
+        assert!(mempool.changes_since(diff.sequence + 1).full_resync);
+
+        // Polls older than the retained log must resync
+        mempool.change_log_capacity = 2;
+        let (json, key) = transfer(9, 10);
+        assert!(mempool.add_raw_transaction(json, key, 100));
+        assert!(mempool.changes_since(since).full_resync);
+        let recent = mempool.changes_since(5);
+        assert!(!recent.full_resync);
+        assert_eq!(recent.added.iter().map(|tx| tx.nonce).collect::<Vec<_>>(), vec![9]);
+        assert_eq!(recent.removed, vec![tx_hash(&entries[3].0)]);
+
+        mempool.clear();
+        assert!(mempool.changes_since(recent.sequence).full_resync);
+        assert!(!mempool.changes_since(mempool.sequence()).full_resync);
+    }
+
+    #[test]
+    fn test_cancellation_replaces_pending_transaction() {
+        use qnet_state::{Account, TransactionBuilder};
+        use std::collections::HashMap;
+
+        let mempool = SimpleMempool::new(SimpleMempoolConfig { max_size: 10, min_gas_price: 1, ..SimpleMempoolConfig::default() });
+        let add = |tx: &Transaction| {
+            let json = serde_json::to_string(tx).unwrap();
+            let key = format!("{:x}", Sha3_256::digest(json.as_bytes()));
+            (mempool.add_raw_transaction(json, key.clone(), tx.gas_price), key)
+        };
+        let original = TransactionBuilder::new().transfer("alice", "bob", 5_000).nonce(1).gas(100, 10_000).build().unwrap();
+        let (added, original_key) = add(&original);
+        assert!(added);
+        assert_eq!(mempool.replacement_min_gas_price("alice", 1), Some(110));
+
+        // Not enough of a bump: the original stays
+        let cheap = TransactionBuilder::cancel("alice", 1).gas(105, 10_000).build().unwrap();
+        assert!(!add(&cheap).0);
+
+        let cancel = TransactionBuilder::cancel("alice", 1).gas(110, 10_000).build().unwrap();
+        assert!(cancel.is_cancellation());
+        assert!(add(&cancel).0);
+        assert!(mempool.get_raw_transaction(&original_key).is_none());
+        assert!(mempool.get_by_hash(&original.hash).is_none());
+        assert_eq!(mempool.size(), 1);
+        assert_eq!(mempool.pending_from("alice").iter().map(|tx| &tx.hash).collect::<Vec<_>>(), vec![&cancel.hash]);
+
+        // The block producer picks up the cancellation: alice pays the fee, bob gets nothing
+        let mined: Vec<Transaction> = mempool.get_pending_transactions(10).iter()
+            .map(|json| serde_json::from_str(json).unwrap())
+            .collect();
+        assert_eq!(mined.len(), 1);
+        let mut accounts = HashMap::new();
+        let mut alice = Account::new("alice".to_string());
+        alice.balance = 10_000_000;
+        accounts.insert("alice".to_string(), alice);
+        let receipt = mined[0].execute(&mut accounts).unwrap();
+        assert_eq!(receipt.tx_hash, cancel.hash);
+        assert!(receipt.is_success());
+        assert_eq!(accounts["alice"].nonce, 1);
+        assert_eq!(accounts["alice"].balance, 10_000_000 - receipt.fee);
+        assert!(!accounts.contains_key("bob"));
+    }
 } 
\ No newline at end of file
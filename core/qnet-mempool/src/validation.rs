@@ -1,10 +1,13 @@
 ﻿//! Transaction validation for mempool
 
 use crate::errors::{MempoolError, MempoolResult};
+use crate::priority::PriorityClass;
 use qnet_state::{StateDB, transaction::Transaction};
 use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 /// Validation result
 #[derive(Debug, Clone)]
@@ -58,6 +61,16 @@ pub trait TxValidator: Send + Sync {
     
     /// Quick validation (without state checks)
     fn validate_basic(&self, tx: &Transaction) -> ValidationResult;
+    
+    /// Dust/spam admission rules, given how full the pool is (0.0 - 1.0)
+    fn check_admission(&self, _tx: &Transaction, _pool_fill: f64) -> MempoolResult<()> {
+        Ok(())
+    }
+    
+    /// Balance and nonce against committed state, given the sender's `pending` transactions
+    fn check_account(&self, _tx: &Transaction, _pending: &[Transaction]) -> MempoolResult<()> {
+        Ok(())
+    }
 }
 
 /// Default transaction validator
@@ -108,7 +121,13 @@ impl TxValidator for DefaultValidator {
                 }
                 
                 // Check if transaction can pay for itself
-                let total_cost = tx.value() + (tx.gas_price * tx.gas_limit);
+                let total_cost = match tx.max_cost() {
+                    Ok(cost) => cost,
+                    Err(e) => {
+                        result.add_error(e.to_string());
+                        return Ok(result);
+                    }
+                };
                 if total_cost > 0 {
                     result.add_error("New account has insufficient balance".to_string());
                     result.account_balance = Some(0);
@@ -131,7 +150,13 @@ impl TxValidator for DefaultValidator {
         }
         
         // Check balance
-        let total_cost = tx.value() + (tx.gas_price * tx.gas_limit);
+        let total_cost = match tx.max_cost() {
+            Ok(cost) => cost,
+            Err(e) => {
+                result.add_error(e.to_string());
+                return Ok(result);
+            }
+        };
         if account_state.balance < total_cost {
             result.add_error(format!(
                 "Insufficient balance: need {}, have {}",
@@ -210,14 +235,398 @@ impl TxValidator for DefaultValidator {
     }
 }
 
+/// Designated sender of emission (`RewardDistribution`) transactions
+pub const SYSTEM_EMISSION_SENDER: &str = "system_emission";
+
+/// Designated sender of reward claim payouts (`RewardDistribution`)
+pub const SYSTEM_REWARDS_POOL_SENDER: &str = "system_rewards_pool";
+
+/// Designated sender of `PingCommitmentWithSampling` transactions
+pub const SYSTEM_PING_COMMITMENT_SENDER: &str = "system_ping_commitment";
+
+/// Prefix reserved for system senders
+const SYSTEM_SENDER_PREFIX: &str = "system_";
+
+/// Maximum contract code size (24KB)
+const MAX_CONTRACT_CODE_SIZE: usize = 24_576;
+
+fn is_hex_hash(value: &str) -> bool {
+    value.len() == 64 && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Check the invariants specific to each transaction type
+/// System types are only accepted from their designated senders, and user
+/// types may not claim a system sender
+pub fn validate_tx_type(tx: &Transaction) -> Result<(), String> {
+    use qnet_state::transaction::TransactionType;
+    
+    let is_system_sender = tx.from.starts_with(SYSTEM_SENDER_PREFIX);
+    let has_recipient = tx.to.as_ref().is_some_and(|to| !to.is_empty());
+    
+    match &tx.tx_type {
+        TransactionType::RewardDistribution => {
+            if tx.from != SYSTEM_EMISSION_SENDER && tx.from != SYSTEM_REWARDS_POOL_SENDER {
+                return Err(format!("Reward distribution must come from {} or {}, got {}",
+                                   SYSTEM_EMISSION_SENDER, SYSTEM_REWARDS_POOL_SENDER, tx.from));
+            }
+            if !has_recipient {
+                return Err("Reward distribution without recipient".to_string());
+            }
+        }
+        TransactionType::PingCommitmentWithSampling {
+            window_start_height, window_end_height, merkle_root,
+            total_ping_count, successful_ping_count, sample_seed, ..
+        } => {
+            if tx.from != SYSTEM_PING_COMMITMENT_SENDER {
+                return Err(format!("Ping commitment must come from {}, got {}", SYSTEM_PING_COMMITMENT_SENDER, tx.from));
+            }
+            if !is_hex_hash(merkle_root) {
+                return Err("Ping commitment merkle_root must be a 32-byte hex hash".to_string());
+            }
+            if !is_hex_hash(sample_seed) {
+                return Err("Ping commitment sample_seed must be a 32-byte hex hash".to_string());
+            }
+            if window_end_height < window_start_height {
+                return Err("Ping commitment window ends before it starts".to_string());
+            }
+            if successful_ping_count > total_ping_count {
+                return Err("Ping commitment has more successful pings than total".to_string());
+            }
+        }
+        TransactionType::PingAttestation { from_node, to_node, .. } => {
+            if !is_system_sender {
+                return Err(format!("Ping attestation must come from a system sender, got {}", tx.from));
+            }
+            if from_node.is_empty() || to_node.is_empty() {
+                return Err("Ping attestation without node ids".to_string());
+            }
+        }
+        _ if is_system_sender => {
+            return Err(format!("System sender {} cannot submit user transactions", tx.from));
+        }
+        TransactionType::Transfer { to, .. } => {
+            if to.is_empty() || !has_recipient {
+                return Err("Transfer without recipient".to_string());
+            }
+        }
+        TransactionType::ContractCall => {
+            if !has_recipient {
+                return Err("Contract call without contract address".to_string());
+            }
+        }
+        TransactionType::ContractDeploy => {
+            match &tx.data {
+                None => return Err("Contract deploy without code".to_string()),
+                Some(code) if code.len() > MAX_CONTRACT_CODE_SIZE => return Err("Contract code too large".to_string()),
+                Some(_) => {}
+            }
+        }
+        TransactionType::CreateAccount { address, .. } => {
+            if address.is_empty() {
+                return Err("Create account without address".to_string());
+            }
+        }
+        TransactionType::NodeActivation { .. } => {}
+        TransactionType::BatchRewardClaims { node_ids, batch_id } => {
+            if node_ids.is_empty() || batch_id.is_empty() {
+                return Err("Batch reward claim without nodes or batch id".to_string());
+            }
+        }
+        TransactionType::BatchNodeActivations { activation_data, batch_id } => {
+            if activation_data.is_empty() || batch_id.is_empty() {
+                return Err("Batch node activation without entries or batch id".to_string());
+            }
+        }
+        TransactionType::BatchTransfers { transfers, batch_id } => {
+            if transfers.is_empty() || batch_id.is_empty() {
+                return Err("Batch transfer without entries or batch id".to_string());
+            }
+            if transfers.iter().any(|t| t.to_address.is_empty()) {
+                return Err("Batch transfer entry without recipient".to_string());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Reject transactions signed for another network (replay protection)
+/// System operations are produced locally and carry no chain id
+pub fn validate_chain_id(tx: &Transaction, expected: u64) -> MempoolResult<()> {
+    if PriorityClass::from_transaction_type(&tx.tx_type) == PriorityClass::System || tx.chain_id == expected {
+        return Ok(());
+    }
+    Err(MempoolError::ChainIdMismatch { expected, got: tx.chain_id })
+}
+
+/// Reject transactions past their `valid_until` for a block at `height` with `timestamp`
+/// Admission checks against the next block and the local clock; block validation uses
+/// the block's own height and timestamp, so every node reaches the same verdict
+pub fn validate_valid_until(tx: &Transaction, height: u64, timestamp: u64) -> MempoolResult<()> {
+    match tx.valid_until {
+        Some(valid_until) if !valid_until.allows(height, timestamp) => {
+            Err(MempoolError::ValidityExpired { valid_until, height, timestamp })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Read access to committed account state for admission checks
+pub trait AccountView: Send + Sync {
+    /// Committed account, `None` if it does not exist yet
+    fn account(&self, address: &str) -> Option<qnet_state::Account>;
+}
+
+impl AccountView for qnet_state::State {
+    fn account(&self, address: &str) -> Option<qnet_state::Account> {
+        self.get_account(address)
+    }
+}
+
+/// Default number of nonces a sender may queue past its committed nonce
+pub const DEFAULT_MAX_NONCE_LOOKAHEAD: u64 = 64;
+
+/// Reject a transaction that cannot execute against committed state
+///
+/// `account.nonce` is the sender's last used nonce, so the next transaction uses
+/// `nonce + 1`. Nonces up to `max_lookahead` past that are admitted, so a sender can
+/// queue a chain of transactions before the first is mined. The balance must cover
+/// this transaction plus the sender's `pending` transactions that execute before it
+/// (lower nonces); one pending at the same nonce is being replaced and not counted.
+pub fn validate_account_state(
+    tx: &Transaction,
+    account: Option<&qnet_state::Account>,
+    pending: &[Transaction],
+    max_lookahead: u64,
+) -> MempoolResult<()> {
+    let last_nonce = account.map_or(0, |account| account.nonce);
+    let balance = account.map_or(0, |account| account.balance);
+    
+    if tx.nonce <= last_nonce {
+        return Err(MempoolError::NonceTooLow { expected: last_nonce + 1, got: tx.nonce });
+    }
+    let max_nonce = last_nonce.saturating_add(max_lookahead.max(1));
+    if tx.nonce > max_nonce {
+        return Err(MempoolError::NonceTooFarAhead { max: max_nonce, got: tx.nonce });
+    }
+    
+    let mut required = tx.max_cost().map_err(|e| MempoolError::InvalidTransaction(e.to_string()))?;
+    for queued in pending.iter().filter(|p| p.from == tx.from && p.nonce > last_nonce && p.nonce < tx.nonce) {
+        let cost = queued.max_cost().map_err(|e| MempoolError::InvalidTransaction(e.to_string()))?;
+        required = required.saturating_add(cost);
+    }
+    if required > balance {
+        return Err(MempoolError::InsufficientBalance { required, balance });
+    }
+    Ok(())
+}
+
+/// Gas price increase, in percent, a same-nonce replacement must pay
+pub const MIN_REPLACEMENT_BUMP_PCT: u64 = 10;
+
+/// Lowest gas price that replaces a pending transaction paying `gas_price`
+pub fn replacement_min_gas_price(gas_price: u64) -> u64 {
+    gas_price.saturating_add((gas_price.saturating_mul(MIN_REPLACEMENT_BUMP_PCT) / 100).max(1))
+}
+
+/// Default cap on a transaction's serialized (bincode) size
+pub const DEFAULT_MAX_TX_BYTES: usize = 64 * 1024;
+
+/// Default cap on a transaction's `data` payload, room for the largest contract
+pub const DEFAULT_MAX_TX_DATA_BYTES: usize = 32 * 1024;
+
+/// Transaction size caps; part of consensus, so every node on a network uses the same values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TxSizeLimits {
+    /// Maximum serialized (bincode) size of a whole transaction
+    pub max_tx_bytes: usize,
+    /// Maximum length of the `data` field
+    pub max_tx_data_bytes: usize,
+}
+
+impl Default for TxSizeLimits {
+    fn default() -> Self {
+        Self {
+            max_tx_bytes: DEFAULT_MAX_TX_BYTES,
+            max_tx_data_bytes: DEFAULT_MAX_TX_DATA_BYTES,
+        }
+    }
+}
+
+/// Reject user transactions over the size caps
+/// System operations are exempt: ping commitments grow with the network and are checked by consensus
+pub fn validate_tx_size(tx: &Transaction, limits: &TxSizeLimits) -> MempoolResult<()> {
+    if PriorityClass::from_transaction_type(&tx.tx_type) == PriorityClass::System {
+        return Ok(());
+    }
+    let data_bytes = tx.data.as_ref().map_or(0, |data| data.len());
+    if data_bytes > limits.max_tx_data_bytes {
+        return Err(MempoolError::TransactionTooLarge { what: "data", size: data_bytes, max: limits.max_tx_data_bytes });
+    }
+    let tx_bytes = bincode::serialized_size(tx)
+        .map_err(|e| MempoolError::InvalidTransaction(format!("Unserializable transaction: {}", e)))? as usize;
+    if tx_bytes > limits.max_tx_bytes {
+        return Err(MempoolError::TransactionTooLarge { what: "transaction", size: tx_bytes, max: limits.max_tx_bytes });
+    }
+    Ok(())
+}
+
+/// Dust and spam admission rules
+#[derive(Debug, Clone)]
+pub struct AdmissionConfig {
+    /// Smallest amount a transfer (or batch transfer entry) may move
+    pub min_transfer_amount: u64,
+    
+    /// Transactions accepted per sender within `rate_limit_window`
+    pub max_txs_per_sender: usize,
+    
+    /// Sliding window for the per-sender rate limit
+    pub rate_limit_window: Duration,
+    
+    /// Minimum fee (gas_price * gas_limit) per serialized byte on an empty pool
+    pub min_fee_per_byte: u64,
+    
+    /// Factor applied to `min_fee_per_byte` when the pool is full (scales linearly)
+    pub full_pool_fee_multiplier: u64,
+    
+    /// Nonces a sender may queue past its committed nonce
+    pub max_nonce_lookahead: u64,
+}
+
+impl Default for AdmissionConfig {
+    fn default() -> Self {
+        Self {
+            min_transfer_amount: 1_000, // 0.000001 QNC
+            max_txs_per_sender: 1_000,
+            rate_limit_window: Duration::from_secs(1),
+            min_fee_per_byte: 1_000,
+            full_pool_fee_multiplier: 10,
+            max_nonce_lookahead: DEFAULT_MAX_NONCE_LOOKAHEAD,
+        }
+    }
+}
+
+impl AdmissionConfig {
+    /// Fee per byte required at `pool_fill` (0.0 empty - 1.0 full)
+    pub fn required_fee_per_byte(&self, pool_fill: f64) -> u64 {
+        let extra = self.full_pool_fee_multiplier.saturating_sub(1) as f64 * pool_fill.clamp(0.0, 1.0);
+        (self.min_fee_per_byte as f64 * (1.0 + extra)) as u64
+    }
+}
+
+/// Senders tracked before stale rate limit entries are swept
+const RATE_LIMIT_SWEEP_THRESHOLD: usize = 10_000;
+
 /// Simple validator for Python bindings (no StateDB)
 pub struct SimpleValidator {
     min_gas_price: u64,
+    admission: AdmissionConfig,
+    /// Network chain id transactions must be bound to (unchecked if None)
+    chain_id: Option<u64>,
+    /// Network size caps
+    size_limits: TxSizeLimits,
+    /// Admission times per sender inside the rate limit window
+    recent_by_sender: Mutex<HashMap<String, VecDeque<Instant>>>,
+    /// Committed state for balance and nonce checks (unchecked if None)
+    accounts: Option<Arc<dyn AccountView>>,
 }
 
 impl SimpleValidator {
     pub fn new(min_gas_price: u64) -> Self {
-        Self { min_gas_price }
+        Self {
+            min_gas_price,
+            admission: AdmissionConfig::default(),
+            chain_id: None,
+            size_limits: TxSizeLimits::default(),
+            recent_by_sender: Mutex::new(HashMap::new()),
+            accounts: None,
+        }
+    }
+    
+    /// Check balance and nonce against `accounts` at admission
+    pub fn with_account_view(mut self, accounts: Arc<dyn AccountView>) -> Self {
+        self.accounts = Some(accounts);
+        self
+    }
+    
+    /// Replace the default admission rules
+    pub fn with_admission(mut self, admission: AdmissionConfig) -> Self {
+        self.admission = admission;
+        self
+    }
+    
+    /// Only admit transactions bound to `chain_id`
+    pub fn with_chain_id(mut self, chain_id: u64) -> Self {
+        self.chain_id = Some(chain_id);
+        self
+    }
+    
+    /// Enforce the network's size caps instead of the defaults
+    pub fn with_size_limits(mut self, size_limits: TxSizeLimits) -> Self {
+        self.size_limits = size_limits;
+        self
+    }
+    
+    /// `check_admission` at an explicit time
+    pub fn check_admission_at(&self, tx: &Transaction, pool_fill: f64, now: Instant) -> MempoolResult<()> {
+        use qnet_state::transaction::TransactionType;
+        
+        // System operations are produced by the protocol and never throttled
+        let class = PriorityClass::from_transaction_type(&tx.tx_type);
+        if class == PriorityClass::System {
+            return Ok(());
+        }
+        
+        if let Some(chain_id) = self.chain_id {
+            validate_chain_id(tx, chain_id)?;
+        }
+        validate_tx_size(tx, &self.size_limits)?;
+        
+        // Amount floor applies to transfers only (node activations are High class and
+        // exempt, as are zero-value cancellations)
+        if class != PriorityClass::High && !tx.is_cancellation() {
+            let smallest = match &tx.tx_type {
+                TransactionType::Transfer { .. } => Some(tx.amount),
+                TransactionType::BatchTransfers { transfers, .. } => transfers.iter().map(|t| t.amount).min(),
+                _ => None,
+            };
+            if let Some(amount) = smallest {
+                if amount < self.admission.min_transfer_amount {
+                    return Err(MempoolError::DustTransaction {
+                        min: self.admission.min_transfer_amount,
+                        got: amount,
+                    });
+                }
+            }
+        }
+        
+        // Fee per byte, rising as the pool fills up
+        let size = bincode::serialized_size(tx).unwrap_or(0).max(1);
+        let fee_per_byte = tx.gas_price.saturating_mul(tx.gas_limit) / size;
+        let required = self.admission.required_fee_per_byte(pool_fill);
+        if fee_per_byte < required {
+            return Err(MempoolError::FeePerByteTooLow { min: required, got: fee_per_byte });
+        }
+        
+        // Per-sender sliding window
+        let window = self.admission.rate_limit_window;
+        let mut recent = self.recent_by_sender.lock();
+        if recent.len() > RATE_LIMIT_SWEEP_THRESHOLD {
+            recent.retain(|_, times| times.back().is_some_and(|t| now.duration_since(*t) < window));
+        }
+        let times = recent.entry(tx.from.clone()).or_default();
+        while times.front().is_some_and(|t| now.duration_since(*t) >= window) {
+            times.pop_front();
+        }
+        if times.len() >= self.admission.max_txs_per_sender {
+            return Err(MempoolError::SenderRateLimited {
+                sender: tx.from.clone(),
+                limit: self.admission.max_txs_per_sender,
+                window_secs: window.as_secs(),
+            });
+        }
+        times.push_back(now);
+        
+        Ok(())
     }
 }
 
@@ -230,6 +639,17 @@ impl TxValidator for SimpleValidator {
     fn validate_basic(&self, tx: &Transaction) -> ValidationResult {
         let mut result = ValidationResult::success();
         
+        // Type-specific payload checks
+        if let Err(e) = validate_tx_type(tx) {
+            result.add_error(e);
+            return result;
+        }
+        
+        // System operations are free (gas = 0)
+        if PriorityClass::from_transaction_type(&tx.tx_type) == PriorityClass::System {
+            return result;
+        }
+        
         // Basic checks only
         if tx.gas_price < self.min_gas_price {
             result.add_error(format!(
@@ -244,5 +664,325 @@ impl TxValidator for SimpleValidator {
         
         result
     }
+    
+    fn check_admission(&self, tx: &Transaction, pool_fill: f64) -> MempoolResult<()> {
+        self.check_admission_at(tx, pool_fill, Instant::now())
+    }
+    
+    fn check_account(&self, tx: &Transaction, pending: &[Transaction]) -> MempoolResult<()> {
+        // System operations are checked by consensus, not against sender balance
+        let Some(accounts) = &self.accounts else { return Ok(()) };
+        if PriorityClass::from_transaction_type(&tx.tx_type) == PriorityClass::System {
+            return Ok(());
+        }
+        validate_account_state(tx, accounts.account(&tx.from).as_ref(), pending, self.admission.max_nonce_lookahead)
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use qnet_state::transaction::{BatchTransferData, TransactionType};
+
+    const MIN_GAS_PRICE: u64 = 100_000;
+
+    fn tx(from: &str, to: Option<&str>, tx_type: TransactionType) -> Transaction {
+        let gas_price = if from.starts_with(SYSTEM_SENDER_PREFIX) { 0 } else { MIN_GAS_PRICE };
+        let gas_limit = if from.starts_with(SYSTEM_SENDER_PREFIX) { 0 } else { 10_000 };
+        Transaction::new(
+            from.to_string(), to.map(str::to_string), 1_000, 1,
+            gas_price, gas_limit, 1_700_000_000, None, tx_type, None,
+        )
+    }
+
+    fn ping_commitment(merkle_root: &str) -> TransactionType {
+        TransactionType::PingCommitmentWithSampling {
+            window_start_height: 14_400,
+            window_end_height: 28_799,
+            merkle_root: merkle_root.to_string(),
+            total_ping_count: 10,
+            successful_ping_count: 9,
+            sample_seed: "ab".repeat(32),
+            ping_samples: Vec::new(),
+        }
+    }
+
+    fn assert_valid(tx: &Transaction) {
+        let result = SimpleValidator::new(MIN_GAS_PRICE).validate_basic(tx);
+        assert!(result.is_valid, "expected valid, got {:?}", result.errors);
+    }
+
+    fn assert_rejected(tx: &Transaction, reason: &str) {
+        let result = SimpleValidator::new(MIN_GAS_PRICE).validate_basic(tx);
+        assert!(!result.is_valid, "expected rejection for {}", reason);
+        assert!(result.errors[0].contains(reason), "unexpected errors: {:?}", result.errors);
+    }
+
+    #[test]
+    fn test_user_transaction_types() {
+        let transfer = |to: &str| TransactionType::Transfer { from: "alice".to_string(), to: to.to_string(), amount: 1_000 };
+        assert_valid(&tx("alice", Some("bob"), transfer("bob")));
+        assert_rejected(&tx("alice", Some("bob"), transfer("")), "Transfer without recipient");
+
+        assert_valid(&tx("alice", Some("contract_1"), TransactionType::ContractCall));
+        assert_rejected(&tx("alice", None, TransactionType::ContractCall), "without contract address");
+
+        let batch = |to: &str| TransactionType::BatchTransfers {
+            transfers: vec![BatchTransferData { to_address: to.to_string(), amount: 5, memo: None }],
+            batch_id: "batch_1".to_string(),
+        };
+        assert_valid(&tx("alice", None, batch("bob")));
+        assert_rejected(&tx("alice", None, batch("")), "entry without recipient");
+
+        // User types cannot be submitted under a system identity
+        assert_rejected(&tx("system_emission", Some("bob"), transfer("bob")), "cannot submit user transactions");
+    }
+
+    #[test]
+    fn test_system_transaction_types() {
+        let merkle_root = "cd".repeat(32);
+        assert_valid(&tx(SYSTEM_EMISSION_SENDER, Some("system_rewards_pool"), TransactionType::RewardDistribution));
+        assert_valid(&tx(SYSTEM_REWARDS_POOL_SENDER, Some("alice"), TransactionType::RewardDistribution));
+        assert_rejected(&tx("alice", Some("alice"), TransactionType::RewardDistribution), "must come from system_emission");
+        assert_rejected(&tx(SYSTEM_EMISSION_SENDER, None, TransactionType::RewardDistribution), "without recipient");
+
+        assert_valid(&tx(SYSTEM_PING_COMMITMENT_SENDER, None, ping_commitment(&merkle_root)));
+        assert_rejected(&tx(SYSTEM_PING_COMMITMENT_SENDER, None, ping_commitment("")), "merkle_root");
+        assert_rejected(&tx("alice", None, ping_commitment(&merkle_root)), "must come from system_ping_commitment");
+
+        let attestation = |from_node: &str| TransactionType::PingAttestation {
+            from_node: from_node.to_string(),
+            to_node: "node_2".to_string(),
+            response_time_ms: 40,
+            success: true,
+        };
+        assert_valid(&tx("system_ping", None, attestation("node_1")));
+        assert_rejected(&tx("system_ping", None, attestation("")), "without node ids");
+        assert_rejected(&tx("node_1", None, attestation("node_1")), "system sender");
+    }
+
+    fn transfer_of(from: &str, amount: u64) -> Transaction {
+        let mut t = tx(from, Some("bob"), TransactionType::Transfer { from: from.to_string(), to: "bob".to_string(), amount });
+        t.amount = amount;
+        t
+    }
+
+    #[test]
+    fn test_dust_rejected_small_transfer_accepted() {
+        let validator = SimpleValidator::new(MIN_GAS_PRICE);
+        let now = Instant::now();
+
+        assert!(matches!(
+            validator.check_admission_at(&transfer_of("alice", 1), 0.0, now),
+            Err(MempoolError::DustTransaction { min: 1_000, got: 1 })
+        ));
+        assert!(validator.check_admission_at(&transfer_of("alice", 1_000), 0.0, now).is_ok());
+
+        // Node activation and system payouts are not held to the transfer floor
+        let activation = TransactionType::NodeActivation {
+            node_type: qnet_state::account::NodeType::Light, amount: 0, phase: qnet_state::account::ActivationPhase::Phase1,
+        };
+        let mut activate = tx("carol", None, activation);
+        activate.amount = 0;
+        assert!(validator.check_admission_at(&activate, 0.0, now).is_ok());
+        let mut reward = tx(SYSTEM_REWARDS_POOL_SENDER, Some("alice"), TransactionType::RewardDistribution);
+        reward.amount = 1;
+        assert!(validator.check_admission_at(&reward, 1.0, now).is_ok());
+    }
+
+    #[test]
+    fn test_fee_per_byte_rises_with_fullness() {
+        let validator = SimpleValidator::new(MIN_GAS_PRICE).with_admission(AdmissionConfig {
+            min_fee_per_byte: 1_000_000,
+            ..AdmissionConfig::default()
+        });
+        let transfer = transfer_of("alice", 5_000);
+        let size = bincode::serialized_size(&transfer).unwrap();
+        let fee_per_byte = transfer.gas_price * transfer.gas_limit / size;
+        assert!(fee_per_byte > 1_000_000 && fee_per_byte < 10_000_000, "test assumes ~300 byte tx");
+
+        assert!(validator.check_admission_at(&transfer, 0.0, Instant::now()).is_ok());
+        assert!(matches!(
+            validator.check_admission_at(&transfer, 1.0, Instant::now()),
+            Err(MempoolError::FeePerByteTooLow { min: 10_000_000, .. })
+        ));
+    }
+
+    #[test]
+    fn test_sender_flood_throttled() {
+        let validator = SimpleValidator::new(MIN_GAS_PRICE).with_admission(AdmissionConfig {
+            max_txs_per_sender: 5,
+            rate_limit_window: Duration::from_secs(10),
+            ..AdmissionConfig::default()
+        });
+        let start = Instant::now();
+        let transfer = transfer_of("spammer", 5_000);
+
+        for _ in 0..5 {
+            assert!(validator.check_admission_at(&transfer, 0.0, start).is_ok());
+        }
+        assert!(matches!(
+            validator.check_admission_at(&transfer, 0.0, start + Duration::from_secs(1)),
+            Err(MempoolError::SenderRateLimited { limit: 5, window_secs: 10, .. })
+        ));
+
+        // Other senders are unaffected, and the window slides
+        assert!(validator.check_admission_at(&transfer_of("alice", 5_000), 0.0, start).is_ok());
+        assert!(validator.check_admission_at(&transfer, 0.0, start + Duration::from_secs(10)).is_ok());
+    }
+
+    #[test]
+    fn test_transaction_for_other_chain_rejected() {
+        let testnet_tx = transfer_of("alice", 5_000).with_chain_id(1337);
+        let mainnet = SimpleValidator::new(MIN_GAS_PRICE).with_chain_id(1);
+        let testnet = SimpleValidator::new(MIN_GAS_PRICE).with_chain_id(1337);
+
+        assert!(matches!(
+            mainnet.check_admission_at(&testnet_tx, 0.0, Instant::now()),
+            Err(MempoolError::ChainIdMismatch { expected: 1, got: 1337 })
+        ));
+        assert!(testnet.check_admission_at(&testnet_tx, 0.0, Instant::now()).is_ok());
+
+        // Unbound user transactions are replayable and rejected too; system payouts are exempt
+        assert!(matches!(
+            validate_chain_id(&transfer_of("alice", 5_000), 1),
+            Err(MempoolError::ChainIdMismatch { expected: 1, got: 0 })
+        ));
+        let reward = tx(SYSTEM_REWARDS_POOL_SENDER, Some("alice"), TransactionType::RewardDistribution);
+        assert!(validate_chain_id(&reward, 1).is_ok());
+    }
+
+    #[test]
+    fn test_oversized_transaction_rejected_at_admission() {
+        let transfer = transfer_of("alice", 5_000);
+        let base = bincode::serialized_size(&transfer).unwrap() as usize;
+        let with_data = |len: usize| {
+            let mut t = transfer.clone();
+            t.data = Some("d".repeat(len));
+            t
+        };
+        // `Some` plus the string length prefix
+        let overhead = bincode::serialized_size(&with_data(0)).unwrap() as usize;
+        assert!(overhead > base);
+
+        let limits = TxSizeLimits { max_tx_bytes: overhead + 1_000, max_tx_data_bytes: 800 };
+        let validator = SimpleValidator::new(MIN_GAS_PRICE).with_size_limits(limits);
+        let admit = |t: &Transaction| validator.check_admission_at(t, 0.0, Instant::now());
+
+        assert!(admit(&with_data(800)).is_ok());
+        assert!(matches!(
+            admit(&with_data(801)),
+            Err(MempoolError::TransactionTooLarge { what: "data", size: 801, max: 800 })
+        ));
+
+        // Whole-transaction cap, with a data limit that no longer binds
+        let validator = SimpleValidator::new(MIN_GAS_PRICE)
+            .with_size_limits(TxSizeLimits { max_tx_data_bytes: 2_000, ..limits });
+        assert!(validator.check_admission_at(&with_data(1_000), 0.0, Instant::now()).is_ok());
+        assert!(matches!(
+            validator.check_admission_at(&with_data(1_001), 0.0, Instant::now()),
+            Err(MempoolError::TransactionTooLarge { what: "transaction", .. })
+        ));
+
+        // System operations are exempt
+        let mut reward = tx(SYSTEM_REWARDS_POOL_SENDER, Some("alice"), TransactionType::RewardDistribution);
+        reward.data = Some("d".repeat(5_000));
+        assert!(validate_tx_size(&reward, &limits).is_ok());
+    }
+
+    #[test]
+    fn test_transaction_rejected_past_valid_until() {
+        use qnet_state::transaction::ValidUntil;
+
+        let by_height = transfer_of("alice", 5_000).with_valid_until(ValidUntil::Height(100));
+        assert!(validate_valid_until(&by_height, 99, 1_700_000_000).is_ok());
+        assert!(validate_valid_until(&by_height, 100, 1_700_000_000).is_ok());
+        assert!(matches!(
+            validate_valid_until(&by_height, 101, 1_700_000_000),
+            Err(MempoolError::ValidityExpired { valid_until: ValidUntil::Height(100), height: 101, .. })
+        ));
+
+        let by_time = transfer_of("alice", 5_000).with_valid_until(ValidUntil::Timestamp(1_700_000_060));
+        assert!(validate_valid_until(&by_time, 1_000, 1_700_000_060).is_ok());
+        assert!(validate_valid_until(&by_time, 1_000, 1_700_000_061).is_err());
+
+        // No expiry: valid at any height
+        assert!(validate_valid_until(&transfer_of("alice", 5_000), u64::MAX, u64::MAX).is_ok());
+    }
+
+    const FEE: u64 = MIN_GAS_PRICE * 10_000;
+
+    fn alice_transfer(nonce: u64, amount: u64) -> Transaction {
+        let mut t = transfer_of("alice", amount);
+        t.nonce = nonce;
+        t
+    }
+
+    /// Validator reading alice's committed state: last nonce 4, room for three 5_000 transfers
+    fn validator_with_alice() -> SimpleValidator {
+        let state = qnet_state::State::new();
+        let mut alice = qnet_state::Account::new("alice".to_string());
+        alice.nonce = 4;
+        alice.balance = 3 * (5_000 + FEE);
+        state.update_account("alice".to_string(), alice);
+        SimpleValidator::new(MIN_GAS_PRICE).with_account_view(Arc::new(state))
+    }
+
+    #[test]
+    fn test_insufficient_balance_rejected() {
+        let validator = validator_with_alice();
+        assert!(matches!(
+            validator.check_account(&alice_transfer(5, 2 * (5_000 + FEE) + 5_001), &[]),
+            Err(MempoolError::InsufficientBalance { .. })
+        ));
+
+        // Unknown accounts have nothing to spend
+        let mut bob = transfer_of("bob", 5_000);
+        bob.nonce = 1;
+        assert!(matches!(
+            validator.check_account(&bob, &[]),
+            Err(MempoolError::InsufficientBalance { required, balance: 0 }) if required == 5_000 + FEE
+        ));
+    }
+
+    #[test]
+    fn test_stale_nonce_rejected() {
+        let validator = validator_with_alice();
+        assert!(matches!(
+            validator.check_account(&alice_transfer(4, 5_000), &[]),
+            Err(MempoolError::NonceTooLow { expected: 5, got: 4 })
+        ));
+        assert!(matches!(
+            validator.check_account(&alice_transfer(1, 5_000), &[]),
+            Err(MempoolError::NonceTooLow { expected: 5, got: 1 })
+        ));
+        assert!(matches!(
+            validator.check_account(&alice_transfer(5 + DEFAULT_MAX_NONCE_LOOKAHEAD, 5_000), &[]),
+            Err(MempoolError::NonceTooFarAhead { max: 68, .. })
+        ));
+    }
+
+    #[test]
+    fn test_pending_chain_from_same_sender_accepted() {
+        let validator = validator_with_alice();
+        let mut pending = Vec::new();
+        for nonce in 5..=7 {
+            let next = alice_transfer(nonce, 5_000);
+            assert!(validator.check_account(&next, &pending).is_ok(), "nonce {} rejected", nonce);
+            pending.push(next);
+        }
+
+        // A fourth would overdraw once the queued three are paid for
+        assert!(matches!(
+            validator.check_account(&alice_transfer(8, 5_000), &pending),
+            Err(MempoolError::InsufficientBalance { .. })
+        ));
+
+        // Replacing a queued transaction does not pay for it twice; later nonces are not reserved
+        assert!(validator.check_account(&alice_transfer(7, 5_000), &pending).is_ok());
+        assert!(validator.check_account(&alice_transfer(5, 5_000), &pending).is_ok());
+
+        // Without an account view nothing is checked
+        assert!(SimpleValidator::new(MIN_GAS_PRICE).check_account(&alice_transfer(1, 5_000), &[]).is_ok());
+    }
+}
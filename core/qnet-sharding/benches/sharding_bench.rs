@@ -15,7 +15,7 @@ fn benchmark_shard_assignment(c: &mut Criterion) {
 }
 
 fn benchmark_parallel_validation(c: &mut Criterion) {
-    let validator = ParallelValidator::new(8);
+    let validator = ParallelValidator::new(8).unwrap();
     let txs = vec![vec![1, 2, 3]; 10000];
     
     c.bench_function("parallel_validation_10k", |b| {
@@ -7,7 +7,9 @@
 //! Target: Support 1 Million TPS through intelligent sharding
 
 use std::sync::Arc;
-use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
 use dashmap::DashMap;
 use tokio::sync::RwLock;
 use blake3;
@@ -20,6 +22,8 @@ pub const MIN_SHARDS: u32 = 1;       // Single shard for small networks (< 1000
 pub const MAX_SHARDS: u32 = 256;     // Maximum for 1M+ TPS capacity
 pub const MAX_CROSS_SHARD_TXS: usize = 1000;
 pub const REBALANCE_THRESHOLD: f64 = 1.5; // 50% load difference triggers rebalance
+pub const DEFAULT_MAX_MOVES_PER_SHARD: usize = 5;
+pub const DEFAULT_MAX_PREPARES_PER_ACCOUNT: usize = 16;
 
 /// Get optimal shard count based on network size
 /// PRODUCTION: Gradual scaling to avoid over-sharding on small networks
@@ -36,6 +40,36 @@ pub fn get_optimal_shard_count(network_size: usize) -> u32 {
     }
 }
 
+/// Length of an EON address: {19 hex}eon{19 hex}
+pub const EON_ADDRESS_LEN: usize = 41;
+
+/// Check canonical EON form: 41 characters, lowercase hex around an `eon` marker at position 19
+/// The checksum is not verified here, legacy Genesis addresses have none
+pub fn validate_eon_address(address: &str) -> ShardResult<()> {
+    if address.len() != EON_ADDRESS_LEN || !address.is_ascii() {
+        return Err(ShardingError::InvalidAddress(format!("expected {} characters, got {}", EON_ADDRESS_LEN, address.len())));
+    }
+    if &address[19..22] != "eon" {
+        return Err(ShardingError::InvalidAddress("missing 'eon' marker at position 19".to_string()));
+    }
+    let is_hex = |s: &str| s.chars().all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c));
+    if !is_hex(&address[0..19]) || !is_hex(&address[22..]) {
+        return Err(ShardingError::InvalidAddress("non-hex characters".to_string()));
+    }
+    Ok(())
+}
+
+/// Canonical form of an EON address: trimmed, `0x` prefix removed, lowercase
+pub fn normalize_address(address: &str) -> ShardResult<String> {
+    let trimmed = address.trim();
+    let unprefixed = trimmed.strip_prefix("0x")
+        .or_else(|| trimmed.strip_prefix("0X"))
+        .unwrap_or(trimmed);
+    let normalized = unprefixed.to_ascii_lowercase();
+    validate_eon_address(&normalized)?;
+    Ok(normalized)
+}
+
 /// Shard coordinator for managing cross-shard transactions
 pub struct ShardCoordinator {
     /// Dynamic shard count (using atomic for lock-free reads)
@@ -44,14 +78,41 @@ pub struct ShardCoordinator {
     /// Shard assignments
     shard_map: Arc<DashMap<String, u32>>,
     
-    /// Cross-shard transaction queue
-    cross_shard_queue: Arc<RwLock<Vec<CrossShardTx>>>,
+    /// Cross-shard transaction queue, highest priority drained first
+    cross_shard_queue: Arc<RwLock<CrossShardQueue>>,
     
     /// Shard load statistics
     shard_loads: Arc<DashMap<u32, ShardLoad>>,
     
     /// Hot accounts for rebalancing
     hot_accounts: Arc<DashMap<String, HotAccountStats>>,
+    
+    /// Rebalancing parameters
+    rebalance_config: RebalanceConfig,
+    
+    /// Set while `rebalance_shards` runs
+    rebalancing: Arc<AtomicBool>,
+    
+    /// Cross-shard debits prepared but not yet committed or aborted
+    prepares: Arc<RwLock<PrepareLedger>>,
+}
+
+/// Parameters for `ShardCoordinator::rebalance_shards`
+#[derive(Clone, Debug)]
+pub struct RebalanceConfig {
+    /// Load ratio against the average that marks a shard over/underloaded
+    pub threshold: f64,
+    /// Hottest accounts moved out of each overloaded shard per call
+    pub max_moves_per_shard: usize,
+}
+
+impl Default for RebalanceConfig {
+    fn default() -> Self {
+        Self {
+            threshold: REBALANCE_THRESHOLD,
+            max_moves_per_shard: DEFAULT_MAX_MOVES_PER_SHARD,
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -63,6 +124,161 @@ pub struct CrossShardTx {
     pub timestamp: u64,
 }
 
+/// Queue ordering: larger amount first, then older, then hash for a total order
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct CrossShardPriority {
+    amount: u64,
+    age: Reverse<u64>,
+    tx_hash: String,
+}
+
+impl CrossShardPriority {
+    fn of(tx: &CrossShardTx) -> Self {
+        Self {
+            amount: tx.amount,
+            age: Reverse(tx.timestamp),
+            tx_hash: tx.tx_hash.clone(),
+        }
+    }
+}
+
+/// Bounded cross-shard queue ordered by priority
+/// When full, a higher priority transaction displaces the lowest pending one
+#[derive(Debug)]
+pub struct CrossShardQueue {
+    entries: BTreeMap<CrossShardPriority, CrossShardTx>,
+    capacity: usize,
+}
+
+impl CrossShardQueue {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: BTreeMap::new(),
+            capacity,
+        }
+    }
+    
+    /// Insert a transaction; returns the displaced transaction if the queue was full
+    pub fn push(&mut self, tx: CrossShardTx) -> ShardResult<Option<CrossShardTx>> {
+        let priority = CrossShardPriority::of(&tx);
+        let mut displaced = None;
+        
+        if self.entries.len() >= self.capacity {
+            match self.entries.first_key_value() {
+                Some((lowest, _)) if *lowest < priority => {
+                    displaced = self.entries.pop_first().map(|(_, tx)| tx);
+                }
+                _ => return Err(ShardingError::QueueFull(self.capacity)),
+            }
+        }
+        
+        self.entries.insert(priority, tx);
+        Ok(displaced)
+    }
+    
+    /// Remove up to `max` highest priority transactions
+    pub fn drain_batch(&mut self, max: usize) -> Vec<CrossShardTx> {
+        let mut batch = Vec::with_capacity(max.min(self.entries.len()));
+        while batch.len() < max {
+            match self.entries.pop_last() {
+                Some((_, tx)) => batch.push(tx),
+                None => break,
+            }
+        }
+        batch
+    }
+    
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+    
+    /// Lowest shard id `>= first_shard` with pending transactions, and how many touch it
+    fn pending_for_shards_from(&self, first_shard: u32) -> Option<(u32, usize)> {
+        let shard = self.entries.values()
+            .flat_map(|tx| [tx.from_shard, tx.to_shard])
+            .filter(|&shard| shard >= first_shard)
+            .min()?;
+        let pending = self.entries.values()
+            .filter(|tx| tx.from_shard == shard || tx.to_shard == shard)
+            .count();
+        Some((shard, pending))
+    }
+}
+
+/// Prepared cross-shard debits per sending account
+/// Each prepare reserves its amount until committed or aborted; an account may not
+/// hold more than `max_per_account` prepares, nor reserve more than its balance
+#[derive(Debug)]
+pub struct PrepareLedger {
+    max_per_account: usize,
+    /// account -> tx hash -> reserved amount
+    by_account: HashMap<String, BTreeMap<String, u64>>,
+    /// tx hash -> account
+    accounts: HashMap<String, String>,
+}
+
+impl Default for PrepareLedger {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_PREPARES_PER_ACCOUNT)
+    }
+}
+
+impl PrepareLedger {
+    pub fn new(max_per_account: usize) -> Self {
+        Self {
+            max_per_account,
+            by_account: HashMap::new(),
+            accounts: HashMap::new(),
+        }
+    }
+    
+    /// Reserve `amount` from `account` for `tx_hash` against its committed `balance`
+    pub fn prepare(&mut self, account: &str, tx_hash: &str, amount: u64, balance: u64) -> ShardResult<()> {
+        if self.accounts.contains_key(tx_hash) {
+            return Err(ShardingError::AlreadyPrepared(tx_hash.to_string()));
+        }
+        let count = self.prepared_count(account);
+        if count >= self.max_per_account {
+            return Err(ShardingError::TooManyPrepares { account: account.to_string(), max: self.max_per_account });
+        }
+        let prepared = self.prepared_amount(account);
+        if prepared.checked_add(amount).map_or(true, |total| total > balance) {
+            return Err(ShardingError::PrepareOverCommit { account: account.to_string(), prepared, amount, balance });
+        }
+        
+        self.by_account.entry(account.to_string()).or_default().insert(tx_hash.to_string(), amount);
+        self.accounts.insert(tx_hash.to_string(), account.to_string());
+        Ok(())
+    }
+    
+    /// Drop the reservation for `tx_hash`, returning the account and amount it held
+    /// Used for both commit (the debit is now in the committed balance) and abort
+    pub fn release(&mut self, tx_hash: &str) -> ShardResult<(String, u64)> {
+        let account = self.accounts.remove(tx_hash)
+            .ok_or_else(|| ShardingError::PrepareNotFound(tx_hash.to_string()))?;
+        let prepares = self.by_account.get_mut(&account).expect("indexed account has prepares");
+        let amount = prepares.remove(tx_hash).unwrap_or(0);
+        if prepares.is_empty() {
+            self.by_account.remove(&account);
+        }
+        Ok((account, amount))
+    }
+    
+    /// Prepares held by `account`
+    pub fn prepared_count(&self, account: &str) -> usize {
+        self.by_account.get(account).map_or(0, |prepares| prepares.len())
+    }
+    
+    /// Total amount reserved by `account`'s prepares
+    pub fn prepared_amount(&self, account: &str) -> u64 {
+        self.by_account.get(account).map_or(0, |prepares| prepares.values().sum())
+    }
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct ShardLoad {
     pub transactions_per_second: f64,
@@ -92,53 +308,210 @@ impl ShardCoordinator {
         Self {
             total_shards: Arc::new(AtomicU32::new(shard_count)),
             shard_map: Arc::new(DashMap::new()),
-            cross_shard_queue: Arc::new(RwLock::new(Vec::new())),
+            cross_shard_queue: Arc::new(RwLock::new(CrossShardQueue::new(MAX_CROSS_SHARD_TXS))),
             shard_loads: Arc::new(DashMap::new()),
             hot_accounts: Arc::new(DashMap::new()),
+            rebalance_config: RebalanceConfig::default(),
+            rebalancing: Arc::new(AtomicBool::new(false)),
+            prepares: Arc::new(RwLock::new(PrepareLedger::default())),
         }
     }
     
+    /// Use custom rebalancing parameters
+    pub fn with_rebalance_config(mut self, config: RebalanceConfig) -> Self {
+        self.rebalance_config = config;
+        self
+    }
+    
+    /// Limit concurrent uncommitted cross-shard prepares per sending account
+    pub fn with_max_prepares_per_account(mut self, max: usize) -> Self {
+        self.prepares = Arc::new(RwLock::new(PrepareLedger::new(max)));
+        self
+    }
+    
     /// Dynamically adjust shard count based on network growth
-    pub fn adjust_shard_count(&self, network_size: usize) {
+    /// A shrinking network goes through `downscale_shards`, which migrates accounts first
+    pub async fn adjust_shard_count(&self, network_size: usize) -> ShardResult<()> {
         let optimal = get_optimal_shard_count(network_size);
         let current = self.total_shards.load(Ordering::Relaxed);
-        if current != optimal {
+        if optimal < current {
+            self.downscale_shards(optimal).await?;
+        } else if optimal > current {
             println!("[SHARDING] Adjusting shards: {} -> {} for {} nodes", current, optimal, network_size);
             self.total_shards.store(optimal, Ordering::Relaxed);
             // Note: In production, this would trigger shard rebalancing
         }
+        Ok(())
+    }
+    
+    /// Remove shards `new_count..total`, moving their accounts to the remaining ones
+    ///
+    /// `new_count` must divide the current count, so an account's default shard
+    /// (`hash % total`) is unchanged on kept shards and becomes `shard % new_count`
+    /// on removed ones. Reassigned (`shard_map`) and hot accounts on a removed shard
+    /// are moved to that same target, then `total_shards` is lowered. Refused while
+    /// a rebalance runs or a cross-shard transaction is pending for a removed shard:
+    /// drain those through two-phase commit first.
+    pub async fn downscale_shards(&self, new_count: u32) -> ShardResult<DownscaleResult> {
+        let current = self.total_shards.load(Ordering::Relaxed);
+        if new_count < MIN_SHARDS || new_count >= current || current % new_count != 0 {
+            return Err(ShardingError::InvalidShardCount { requested: new_count, current });
+        }
+        if self.rebalancing.compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire).is_err() {
+            return Err(ShardingError::RebalanceInProgress);
+        }
+        
+        // Holding the queue keeps new cross-shard transactions out until the count is lowered
+        let queue = self.cross_shard_queue.write().await;
+        let result = match queue.pending_for_shards_from(new_count) {
+            Some((shard, pending)) => Err(ShardingError::CrossShardInFlight { shard, pending }),
+            None => Ok(self.migrate_removed_shards(current, new_count)),
+        };
+        self.rebalancing.store(false, Ordering::Release);
+        
+        if let Ok(result) = &result {
+            println!("[SHARDING] Downscaled shards: {} -> {}, {} accounts migrated",
+                     current, new_count, result.moved_accounts.len());
+        }
+        result
+    }
+    
+    /// Move accounts and load off shards `new_count..current`, then lower the count
+    fn migrate_removed_shards(&self, current: u32, new_count: u32) -> DownscaleResult {
+        let mut moved_accounts = Vec::new();
+        
+        for mut entry in self.shard_map.iter_mut() {
+            if *entry.value() >= new_count {
+                let from_shard = *entry.value();
+                *entry.value_mut() = from_shard % new_count;
+                moved_accounts.push(AccountMove {
+                    address: entry.key().clone(),
+                    from_shard,
+                    to_shard: from_shard % new_count,
+                    tx_count: self.hot_accounts.get(entry.key()).map_or(0, |hot| hot.tx_count_last_hour),
+                });
+            }
+        }
+        for mut entry in self.hot_accounts.iter_mut() {
+            let from_shard = entry.current_shard;
+            if from_shard >= new_count {
+                entry.current_shard = from_shard % new_count;
+                if !self.shard_map.contains_key(entry.key()) {
+                    moved_accounts.push(AccountMove {
+                        address: entry.key().clone(),
+                        from_shard,
+                        to_shard: from_shard % new_count,
+                        tx_count: entry.tx_count_last_hour,
+                    });
+                }
+            }
+        }
+        
+        // Removed shards' load now lands on their targets
+        for shard in new_count..current {
+            if let Some((_, load)) = self.shard_loads.remove(&shard) {
+                let mut target = self.shard_loads.entry(shard % new_count).or_insert_with(ShardLoad::default);
+                target.transactions_per_second += load.transactions_per_second;
+                target.pending_txs += load.pending_txs;
+            }
+        }
+        
+        self.total_shards.store(new_count, Ordering::Release);
+        moved_accounts.sort_by(|a, b| a.address.cmp(&b.address));
+        DownscaleResult {
+            from_shards: current,
+            to_shards: new_count,
+            moved_accounts,
+        }
     }
     
     /// Get shard for an address (synchronous for compatibility)
+    /// EON addresses are normalized first; other identifiers are hashed as given
     pub fn get_shard(&self, address: &str) -> u32 {
+        match normalize_address(address) {
+            Ok(normalized) => self.shard_for_key(&normalized),
+            Err(_) => self.shard_for_key(address),
+        }
+    }
+    
+    /// Get shard for an EON address, rejecting invalid addresses
+    pub fn try_get_shard(&self, address: &str) -> ShardResult<u32> {
+        normalize_address(address).map(|normalized| self.shard_for_key(&normalized))
+    }
+    
+    fn shard_for_key(&self, key: &str) -> u32 {
         // Check if account has been reassigned
-        if let Some(entry) = self.shard_map.get(address) {
+        if let Some(entry) = self.shard_map.get(key) {
             return *entry;
         }
         
         // Calculate default shard with dynamic total (lock-free read)
         let total = self.total_shards.load(Ordering::Relaxed);
-        let hash = blake3::hash(address.as_bytes());
+        let hash = blake3::hash(key.as_bytes());
         let shard = u32::from_le_bytes(hash.as_bytes()[0..4].try_into().unwrap());
         shard % total
     }
     
     /// Process cross-shard transaction
-    pub async fn process_cross_shard_tx(&self, tx: CrossShardTx) -> Result<(), String> {
+    /// A full queue drops its lowest priority entry for a higher priority newcomer
+    pub async fn process_cross_shard_tx(&self, tx: CrossShardTx) -> ShardResult<()> {
         let mut queue = self.cross_shard_queue.write().await;
+        // Read under the queue lock: a downscale lowers the count while holding it
+        let total = self.total_shards.load(Ordering::Acquire);
+        for shard in [tx.from_shard, tx.to_shard] {
+            if shard >= total {
+                return Err(ShardingError::InvalidShard { shard, total });
+            }
+        }
         
-        if queue.len() >= MAX_CROSS_SHARD_TXS {
-            return Err("Cross-shard queue full".to_string());
+        let (from_shard, to_shard) = (tx.from_shard, tx.to_shard);
+        if let Some(displaced) = queue.push(tx)? {
+            println!("[SHARDING] Cross-shard queue full, dropped lower priority tx {} ({} nanoQNC)",
+                     displaced.tx_hash, displaced.amount);
         }
         
         // Update shard loads
-        self.update_shard_load(tx.from_shard, 1.0).await;
-        self.update_shard_load(tx.to_shard, 0.5).await; // Receiving shard has less work
+        self.update_shard_load(from_shard, 1.0).await;
+        self.update_shard_load(to_shard, 0.5).await; // Receiving shard has less work
         
-        queue.push(tx);
         Ok(())
     }
     
+    /// Pull the highest priority cross-shard transactions for two-phase commit
+    pub async fn drain_batch(&self, max: usize) -> Vec<CrossShardTx> {
+        self.cross_shard_queue.write().await.drain_batch(max)
+    }
+    
+    /// Number of pending cross-shard transactions
+    pub async fn pending_cross_shard_count(&self) -> usize {
+        self.cross_shard_queue.read().await.len()
+    }
+    
+    /// Two-phase commit, prepare: reserve `tx.amount` from `from_address`
+    /// `balance` is the account's committed balance on the source shard; the prepare is
+    /// rejected if it would exceed the per-account limit or, together with the
+    /// account's other prepares, reserve more than that balance
+    pub async fn prepare_cross_shard_tx(&self, from_address: &str, tx: &CrossShardTx, balance: u64) -> ShardResult<()> {
+        self.prepares.write().await.prepare(from_address, &tx.tx_hash, tx.amount, balance)
+    }
+    
+    /// Two-phase commit, commit: the debit is applied, release its reservation
+    pub async fn commit_prepared(&self, tx_hash: &str) -> ShardResult<u64> {
+        self.prepares.write().await.release(tx_hash).map(|(_, amount)| amount)
+    }
+    
+    /// Two-phase commit, abort: drop the reservation, funds are available again
+    pub async fn abort_prepared(&self, tx_hash: &str) -> ShardResult<u64> {
+        let (account, amount) = self.prepares.write().await.release(tx_hash)?;
+        println!("[SHARDING] Aborted cross-shard prepare {} ({} nanoQNC released for {})", tx_hash, amount, account);
+        Ok(amount)
+    }
+    
+    /// Total amount reserved by an account's uncommitted prepares
+    pub async fn prepared_amount(&self, account: &str) -> u64 {
+        self.prepares.read().await.prepared_amount(account)
+    }
+    
     /// Update shard load statistics
     async fn update_shard_load(&self, shard_id: u32, tx_weight: f64) {
         let mut load = self.shard_loads.entry(shard_id).or_insert_with(ShardLoad::default);
@@ -156,15 +529,29 @@ impl ShardCoordinator {
     }
     
     /// Rebalance shards based on load
-    pub async fn rebalance_shards(&self) -> Result<RebalanceResult, String> {
-        let loads: Vec<_> = self.shard_loads.iter().map(|entry| (*entry.key(), entry.value().clone())).collect();
+    /// Deterministic given the same load snapshot: shards are visited by id and
+    /// equally hot accounts are ordered by address, independent of map iteration order
+    pub async fn rebalance_shards(&self) -> ShardResult<RebalanceResult> {
+        if self.rebalancing.compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire).is_err() {
+            return Err(ShardingError::RebalanceInProgress);
+        }
+        let result = self.rebalance_snapshot();
+        self.rebalancing.store(false, Ordering::Release);
+        Ok(result)
+    }
+    
+    /// Move hot accounts for the current load snapshot
+    fn rebalance_snapshot(&self) -> RebalanceResult {
+        let mut loads: Vec<_> = self.shard_loads.iter().map(|entry| (*entry.key(), entry.value().clone())).collect();
+        loads.sort_by_key(|(shard_id, _)| *shard_id);
+        let threshold = self.rebalance_config.threshold;
         
         if loads.is_empty() {
-            return Ok(RebalanceResult {
+            return RebalanceResult {
                 rebalanced_accounts: 0,
                 moved_accounts: Vec::new(),
                 performance_improvement: 0.0,
-            });
+            };
         }
         
         // Find overloaded and underloaded shards
@@ -174,19 +561,19 @@ impl ShardCoordinator {
         let mut underloaded_shards = Vec::new();
         
         for (shard_id, load) in &loads {
-            if load.transactions_per_second > avg_load * REBALANCE_THRESHOLD {
+            if load.transactions_per_second > avg_load * threshold {
                 overloaded_shards.push(*shard_id);
-            } else if load.transactions_per_second < avg_load / REBALANCE_THRESHOLD {
+            } else if load.transactions_per_second < avg_load / threshold {
                 underloaded_shards.push(*shard_id);
             }
         }
         
         if overloaded_shards.is_empty() || underloaded_shards.is_empty() {
-            return Ok(RebalanceResult {
+            return RebalanceResult {
                 rebalanced_accounts: 0,
                 moved_accounts: Vec::new(),
                 performance_improvement: 0.0,
-            });
+            };
         }
         
         // Move hot accounts from overloaded to underloaded shards
@@ -200,12 +587,14 @@ impl ShardCoordinator {
                 .map(|entry| (entry.key().clone(), entry.value().clone()))
                 .collect();
             
-            // Sort by transaction count (move hottest accounts first)
+            // Sort by transaction count (move hottest accounts first), ties by address
             let mut sorted_accounts = hot_accounts_in_shard;
-            sorted_accounts.sort_by(|a, b| b.1.tx_count_last_hour.cmp(&a.1.tx_count_last_hour));
+            sorted_accounts.sort_by(|a, b| {
+                b.1.tx_count_last_hour.cmp(&a.1.tx_count_last_hour).then_with(|| a.0.cmp(&b.0))
+            });
             
             // Move top accounts to underloaded shards
-            for (account_addr, account_stats) in sorted_accounts.iter().take(5) {
+            for (account_addr, account_stats) in sorted_accounts.iter().take(self.rebalance_config.max_moves_per_shard) {
                 if let Some(target_shard) = underloaded_shards.first() {
                     // Reassign account to new shard
                     self.shard_map.insert(account_addr.clone(), *target_shard);
@@ -234,24 +623,25 @@ impl ShardCoordinator {
             0.0
         };
         
-        Ok(RebalanceResult {
+        RebalanceResult {
             rebalanced_accounts: rebalanced_count,
             moved_accounts,
             performance_improvement,
-        })
+        }
     }
     
-    /// Track hot account activity
-    pub fn track_account_activity(&self, address: &str, tx_size: u64) {
+    /// Track hot account activity, keyed by the normalized address
+    pub fn track_account_activity(&self, address: &str, tx_size: u64) -> ShardResult<()> {
+        let address = normalize_address(address)?;
         let current_time = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
             .as_secs();
         
-        let mut hot_account = self.hot_accounts.entry(address.to_string()).or_insert_with(|| {
+        let mut hot_account = self.hot_accounts.entry(address.clone()).or_insert_with(|| {
             HotAccountStats {
-                address: address.to_string(),
-                current_shard: self.get_shard(address),
+                address: address.clone(),
+                current_shard: self.shard_for_key(&address),
                 tx_count_last_hour: 0,
                 avg_tx_size: 0,
                 last_activity: current_time,
@@ -266,6 +656,7 @@ impl ShardCoordinator {
         hot_account.tx_count_last_hour += 1;
         hot_account.avg_tx_size = (hot_account.avg_tx_size + tx_size) / 2;
         hot_account.last_activity = current_time;
+        Ok(())
     }
     
     /// Get comprehensive shard statistics
@@ -294,29 +685,86 @@ impl ShardCoordinator {
     }
 }
 
+/// Upper bound on validator threads, anything above is a misconfiguration
+pub const MAX_VALIDATOR_THREADS: usize = 1024;
+
+/// Sharding errors
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ShardingError {
+    #[error("Invalid validator thread count {0}: expected 1..={max}", max = MAX_VALIDATOR_THREADS)]
+    InvalidThreadCount(usize),
+    #[error("Failed to build validator thread pool: {0}")]
+    ThreadPool(String),
+    #[error("Cross-shard queue full ({0} pending)")]
+    QueueFull(usize),
+    #[error("Invalid shard {shard}: network has {total} shards")]
+    InvalidShard { shard: u32, total: u32 },
+    #[error("Invalid EON address: {0}")]
+    InvalidAddress(String),
+    #[error("Account not found: {0}")]
+    AccountNotFound(String),
+    #[error("Shard rebalance already in progress")]
+    RebalanceInProgress,
+    #[error("Cannot downscale from {current} to {requested} shards: the new count must be smaller and divide the current one")]
+    InvalidShardCount { requested: u32, current: u32 },
+    #[error("{pending} cross-shard transactions pending for shard {shard}")]
+    CrossShardInFlight { shard: u32, pending: usize },
+    #[error("Account {account} already has {max} cross-shard prepares in flight")]
+    TooManyPrepares { account: String, max: usize },
+    #[error("Prepare of {amount} would over-commit {account}: {prepared} already prepared against balance {balance}")]
+    PrepareOverCommit { account: String, prepared: u64, amount: u64, balance: u64 },
+    #[error("Cross-shard transaction {0} already prepared")]
+    AlreadyPrepared(String),
+    #[error("No cross-shard prepare for transaction {0}")]
+    PrepareNotFound(String),
+}
+
+/// Result type for sharding operations
+pub type ShardResult<T> = Result<T, ShardingError>;
+
 /// Parallel transaction validator using Rayon
+/// Without a thread pool it validates sequentially with identical results
 pub struct ParallelValidator {
-    thread_pool: rayon::ThreadPool,
+    thread_pool: Option<rayon::ThreadPool>,
 }
 
 impl ParallelValidator {
-    pub fn new(num_threads: usize) -> Self {
+    /// Validator backed by a pool of `num_threads` threads
+    pub fn new(num_threads: usize) -> Result<Self, ShardingError> {
+        if num_threads == 0 || num_threads > MAX_VALIDATOR_THREADS {
+            return Err(ShardingError::InvalidThreadCount(num_threads));
+        }
         let thread_pool = rayon::ThreadPoolBuilder::new()
             .num_threads(num_threads)
             .build()
-            .unwrap();
+            .map_err(|e| ShardingError::ThreadPool(e.to_string()))?;
             
-        Self { thread_pool }
+        Ok(Self { thread_pool: Some(thread_pool) })
+    }
+    
+    /// Validator that runs on the calling thread (fallback when no pool can be built)
+    pub fn sequential() -> Self {
+        Self { thread_pool: None }
+    }
+    
+    pub fn is_parallel(&self) -> bool {
+        self.thread_pool.is_some()
     }
     
     /// Validate transactions in parallel with full cryptographic verification
     pub fn validate_batch(&self, transactions: Vec<TransactionData>) -> Vec<ValidationResult> {
-        self.thread_pool.install(|| {
-            transactions
-                .par_iter()
+        match &self.thread_pool {
+            Some(thread_pool) => thread_pool.install(|| {
+                transactions
+                    .par_iter()
+                    .map(|tx| self.validate_single_transaction(tx))
+                    .collect()
+            }),
+            None => transactions
+                .iter()
                 .map(|tx| self.validate_single_transaction(tx))
-                .collect()
-        })
+                .collect(),
+        }
     }
     
     /// Validate single transaction with comprehensive checks
@@ -405,6 +853,15 @@ pub struct RebalanceResult {
     pub performance_improvement: f64,
 }
 
+/// Outcome of `ShardCoordinator::downscale_shards`
+#[derive(Clone, Debug)]
+pub struct DownscaleResult {
+    pub from_shards: u32,
+    pub to_shards: u32,
+    /// Reassigned and hot accounts moved off removed shards, by address
+    pub moved_accounts: Vec<AccountMove>,
+}
+
 #[derive(Clone, Debug)]
 pub struct AccountMove {
     pub address: String,
@@ -425,3 +882,264 @@ pub struct ShardStatistics {
     pub cross_shard_tx_count: u64,
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cross_tx(hash: &str, amount: u64, timestamp: u64) -> CrossShardTx {
+        CrossShardTx {
+            tx_hash: hash.to_string(),
+            from_shard: 0,
+            to_shard: 1,
+            amount,
+            timestamp,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cross_shard_drain_by_priority() {
+        let coordinator = ShardCoordinator::new();
+        for (hash, amount, timestamp) in [("small", 10, 1), ("large", 5_000, 2), ("medium", 300, 3), ("medium_old", 300, 0)] {
+            coordinator.process_cross_shard_tx(cross_tx(hash, amount, timestamp)).await.unwrap();
+        }
+
+        let batch: Vec<String> = coordinator.drain_batch(3).await.into_iter().map(|tx| tx.tx_hash).collect();
+        assert_eq!(batch, vec!["large", "medium_old", "medium"]);
+        assert_eq!(coordinator.pending_cross_shard_count().await, 1);
+        assert_eq!(coordinator.drain_batch(10).await[0].tx_hash, "small");
+        assert!(coordinator.drain_batch(10).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_typed_errors_for_bad_shard_and_concurrent_rebalance() {
+        let coordinator = ShardCoordinator::with_shard_count(2);
+        let mut tx = cross_tx("stray", 10, 0);
+        tx.to_shard = 2;
+        assert_eq!(
+            coordinator.process_cross_shard_tx(tx).await,
+            Err(ShardingError::InvalidShard { shard: 2, total: 2 })
+        );
+        assert_eq!(coordinator.pending_cross_shard_count().await, 0);
+
+        coordinator.rebalancing.store(true, Ordering::Release);
+        assert!(matches!(coordinator.rebalance_shards().await, Err(ShardingError::RebalanceInProgress)));
+        coordinator.rebalancing.store(false, Ordering::Release);
+        assert!(coordinator.rebalance_shards().await.is_ok());
+        assert!(!coordinator.rebalancing.load(Ordering::Acquire));
+    }
+
+    #[tokio::test]
+    async fn test_stacked_prepares_cannot_over_commit_account() {
+        let coordinator = ShardCoordinator::new().with_max_prepares_per_account(3);
+        let account = "alice";
+        let balance = 100;
+        coordinator.prepare_cross_shard_tx(account, &cross_tx("a", 40, 0), balance).await.unwrap();
+        coordinator.prepare_cross_shard_tx(account, &cross_tx("b", 40, 1), balance).await.unwrap();
+
+        // 80 already reserved: another 30 would exceed the balance
+        assert_eq!(
+            coordinator.prepare_cross_shard_tx(account, &cross_tx("c", 30, 2), balance).await,
+            Err(ShardingError::PrepareOverCommit { account: account.to_string(), prepared: 80, amount: 30, balance })
+        );
+        assert_eq!(
+            coordinator.prepare_cross_shard_tx(account, &cross_tx("a", 1, 3), balance).await,
+            Err(ShardingError::AlreadyPrepared("a".to_string()))
+        );
+
+        // Committing one frees its reservation; the debit is now in the committed balance
+        assert_eq!(coordinator.commit_prepared("a").await, Ok(40));
+        let balance = balance - 40;
+        coordinator.prepare_cross_shard_tx(account, &cross_tx("c", 20, 2), balance).await.unwrap();
+        assert_eq!(coordinator.prepared_amount(account).await, 60);
+
+        // Count limit: a third in-flight prepare fits, a fourth does not
+        coordinator.prepare_cross_shard_tx(account, &cross_tx("d", 0, 4), balance).await.unwrap();
+        assert_eq!(
+            coordinator.prepare_cross_shard_tx(account, &cross_tx("e", 0, 5), 1_000).await,
+            Err(ShardingError::TooManyPrepares { account: account.to_string(), max: 3 })
+        );
+
+        // Aborting one frees both a slot and its funds
+        assert_eq!(coordinator.abort_prepared("b").await, Ok(40));
+        coordinator.prepare_cross_shard_tx(account, &cross_tx("e", 40, 5), balance).await.unwrap();
+        assert_eq!(coordinator.prepared_amount(account).await, 60);
+        assert_eq!(coordinator.abort_prepared("b").await, Err(ShardingError::PrepareNotFound("b".to_string())));
+
+        // Other accounts are unaffected
+        coordinator.prepare_cross_shard_tx("bob", &cross_tx("f", 100, 6), 100).await.unwrap();
+    }
+
+    #[test]
+    fn test_full_cross_shard_queue_displaces_lowest() {
+        let mut queue = CrossShardQueue::new(3);
+        for (i, amount) in [100u64, 20, 300].into_iter().enumerate() {
+            assert!(queue.push(cross_tx(&format!("tx_{}", i), amount, i as u64)).unwrap().is_none());
+        }
+
+        // Lower than everything pending: rejected, queue unchanged
+        assert_eq!(queue.push(cross_tx("dust", 5, 10)).unwrap_err(), ShardingError::QueueFull(3));
+        assert_eq!(queue.len(), 3);
+
+        // Higher priority newcomer evicts the 20 nanoQNC transfer
+        let displaced = queue.push(cross_tx("whale", 1_000_000, 11)).unwrap().unwrap();
+        assert_eq!(displaced.tx_hash, "tx_1");
+        assert_eq!(queue.len(), 3);
+
+        let amounts: Vec<u64> = queue.drain_batch(3).iter().map(|tx| tx.amount).collect();
+        assert_eq!(amounts, vec![1_000_000, 300, 100]);
+    }
+
+    fn equal_hot_accounts_coordinator(config: RebalanceConfig) -> ShardCoordinator {
+        let coordinator = ShardCoordinator::with_shard_count(3).with_rebalance_config(config);
+        for (shard_id, tps) in [(0u32, 100.0), (1, 10.0), (2, 10.0)] {
+            coordinator.shard_loads.insert(shard_id, ShardLoad { transactions_per_second: tps, ..Default::default() });
+        }
+        for i in 0..40 {
+            let address = format!("account_{:02}", i);
+            coordinator.hot_accounts.insert(address.clone(), HotAccountStats {
+                address,
+                current_shard: 0,
+                tx_count_last_hour: 7,
+                avg_tx_size: 250,
+                last_activity: 0,
+            });
+        }
+        coordinator
+    }
+
+    #[tokio::test]
+    async fn test_rebalance_equal_counts_is_deterministic() {
+        let moves = |config: RebalanceConfig| async move {
+            let result = equal_hot_accounts_coordinator(config).rebalance_shards().await.unwrap();
+            result.moved_accounts.into_iter()
+                .map(|m| (m.address, m.from_shard, m.to_shard))
+                .collect::<Vec<_>>()
+        };
+
+        let first = moves(RebalanceConfig::default()).await;
+        let expected: Vec<_> = (0..DEFAULT_MAX_MOVES_PER_SHARD)
+            .map(|i| (format!("account_{:02}", i), 0, 1))
+            .collect();
+        assert_eq!(first, expected);
+        for _ in 0..10 {
+            assert_eq!(moves(RebalanceConfig::default()).await, first);
+        }
+
+        let config = RebalanceConfig { max_moves_per_shard: 12, ..Default::default() };
+        let larger = moves(config).await;
+        assert_eq!(larger.len(), 12);
+        assert_eq!(larger[..DEFAULT_MAX_MOVES_PER_SHARD], first[..]);
+    }
+
+    #[tokio::test]
+    async fn test_downscale_migrates_accounts_off_removed_shards() {
+        let coordinator = ShardCoordinator::with_shard_count(8);
+        let addresses: Vec<String> = (0..64u64).map(|i| format!("{:019x}eon{:019x}", i, i * 7919)).collect();
+        for address in &addresses {
+            coordinator.track_account_activity(address, 100).unwrap();
+        }
+        // Rebalanced onto a removed shard, and onto a kept one
+        for (address, shard) in [(&addresses[0], 6), (&addresses[1], 2)] {
+            coordinator.shard_map.insert(address.clone(), shard);
+            coordinator.hot_accounts.get_mut(address.as_str()).unwrap().current_shard = shard;
+        }
+        let before: Vec<u32> = addresses.iter().map(|a| coordinator.get_shard(a)).collect();
+        assert!(before.iter().any(|&shard| shard >= 4));
+
+        // Refused with a cross-shard transaction pending for a removed shard, or during a rebalance
+        let mut pending = cross_tx("pending", 10, 0);
+        pending.to_shard = 5;
+        coordinator.process_cross_shard_tx(pending).await.unwrap();
+        assert_eq!(
+            coordinator.downscale_shards(4).await.unwrap_err(),
+            ShardingError::CrossShardInFlight { shard: 5, pending: 1 }
+        );
+        assert_eq!(coordinator.drain_batch(10).await.len(), 1);
+        coordinator.rebalancing.store(true, Ordering::Release);
+        assert_eq!(coordinator.downscale_shards(4).await.unwrap_err(), ShardingError::RebalanceInProgress);
+        coordinator.rebalancing.store(false, Ordering::Release);
+        assert!(matches!(coordinator.downscale_shards(3).await, Err(ShardingError::InvalidShardCount { requested: 3, current: 8 })));
+        assert_eq!(coordinator.get_shard_statistics().total_shards, 8);
+
+        let result = coordinator.downscale_shards(4).await.unwrap();
+        assert_eq!((result.from_shards, result.to_shards), (8, 4));
+        assert_eq!(coordinator.total_shards.load(Ordering::Acquire), 4);
+        for (address, &old_shard) in addresses.iter().zip(&before) {
+            let shard = coordinator.get_shard(address);
+            assert!(shard < 4, "{} still on shard {}", address, shard);
+            // Accounts on kept shards stay put
+            if old_shard < 4 {
+                assert_eq!(shard, old_shard);
+            }
+            assert_eq!(coordinator.hot_accounts.get(address.as_str()).unwrap().current_shard, shard);
+        }
+        assert!(coordinator.shard_map.iter().all(|entry| *entry.value() < 4));
+        assert_eq!(coordinator.get_shard(&addresses[0]), 2);
+        assert_eq!(result.moved_accounts.len(), before.iter().filter(|&&shard| shard >= 4).count());
+
+        // Cross-shard transactions for removed shards are now rejected
+        let mut stale = cross_tx("stale", 10, 0);
+        stale.from_shard = 7;
+        assert_eq!(coordinator.process_cross_shard_tx(stale).await, Err(ShardingError::InvalidShard { shard: 7, total: 4 }));
+    }
+
+    #[test]
+    fn test_equivalent_addresses_share_shard_and_stats() {
+        let coordinator = ShardCoordinator::with_shard_count(MAX_SHARDS);
+        let canonical = "abcdef0123456789abceon0123456789abcdef012";
+        let variants = [
+            "ABCDEF0123456789ABCEON0123456789ABCDEF012",
+            "0xabcdef0123456789abceon0123456789abcdef012",
+            " AbCdEf0123456789abceon0123456789abcDEF012 ",
+        ];
+
+        let shard = coordinator.get_shard(canonical);
+        for variant in variants {
+            assert_eq!(coordinator.get_shard(variant), shard);
+            assert_eq!(coordinator.try_get_shard(variant), Ok(shard));
+        }
+
+        coordinator.track_account_activity(canonical, 100).unwrap();
+        for variant in variants {
+            coordinator.track_account_activity(variant, 100).unwrap();
+        }
+        assert_eq!(coordinator.hot_accounts.len(), 1);
+        assert_eq!(coordinator.hot_accounts.get(canonical).unwrap().tx_count_last_hour, 4);
+
+        // Clearly invalid addresses are rejected rather than given their own shard
+        for invalid in ["", "abcdef0123456789abceon0123456789abcdef01", "abcdefg123456789abceon0123456789abcdef012"] {
+            assert!(matches!(coordinator.try_get_shard(invalid), Err(ShardingError::InvalidAddress(_))));
+            assert!(matches!(coordinator.track_account_activity(invalid, 100), Err(ShardingError::InvalidAddress(_))));
+        }
+        assert_eq!(coordinator.hot_accounts.len(), 1);
+    }
+
+    #[test]
+    fn test_parallel_validator_rejects_bad_thread_counts() {
+        for threads in [0, MAX_VALIDATOR_THREADS + 1, usize::MAX] {
+            assert!(matches!(ParallelValidator::new(threads), Err(ShardingError::InvalidThreadCount(n)) if n == threads));
+        }
+
+        // Pool-backed and sequential validation give the same results
+        let tx = |amount: u64, signature: &str| TransactionData {
+            from: "alice".to_string(),
+            to: "bob".to_string(),
+            amount,
+            nonce: 1,
+            signature: signature.to_string(),
+            data: vec![0; 4],
+        };
+        let batch = vec![tx(10, &"ab".repeat(32)), tx(0, &"ab".repeat(32)), tx(10, "bad")];
+        let parallel = ParallelValidator::new(2).unwrap();
+        let sequential = ParallelValidator::sequential();
+        assert!(parallel.is_parallel() && !sequential.is_parallel());
+
+        let summarize = |results: Vec<ValidationResult>| -> Vec<(bool, Option<String>, u64)> {
+            results.into_iter().map(|r| (r.is_valid, r.error, r.gas_used)).collect()
+        };
+        let expected = summarize(parallel.validate_batch(batch.clone()));
+        assert_eq!(expected, summarize(sequential.validate_batch(batch)));
+        assert_eq!(expected.iter().map(|r| r.0).collect::<Vec<_>>(), vec![true, false, false]);
+    }
+}
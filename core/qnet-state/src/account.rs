@@ -104,8 +104,10 @@ impl AccountState {
     }
     
     /// Transfer amount to this account
-    pub fn transfer_in(&mut self, amount: Amount) {
-        self.balance += amount;
+    pub fn transfer_in(&mut self, amount: Amount) -> Result<(), String> {
+        self.balance = self.balance.checked_add(amount)
+            .ok_or_else(|| format!("Balance overflow: {} + {}", self.balance, amount))?;
+        Ok(())
     }
     
     /// Activate as node
@@ -0,0 +1,198 @@
+//! State backend implementations
+//!
+//! `MemoryBackend` keeps everything in process memory for tests and tooling,
+//! while `QNetStorage` from `qnet-core` provides the production storage path.
+
+use std::collections::HashMap;
+use std::future::Future;
+use once_cell::sync::Lazy;
+use sha3::{Sha3_256, Digest};
+use qnet_core::storage::QNetStorage;
+use crate::{Account, Block, BlockHash, StateBackend, StateError, StateResult};
+
+/// Runtime used to drive async storage calls when no Tokio runtime is active
+static FALLBACK_RUNTIME: Lazy<tokio::runtime::Runtime> = Lazy::new(|| {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("Failed to build state backend runtime")
+});
+
+/// Run an async storage call from the synchronous `StateBackend` interface.
+/// Inside a multi-threaded Tokio runtime the worker is handed off with
+/// `block_in_place`; elsewhere a shared current-thread runtime is used.
+fn block_on<F: Future>(future: F) -> F::Output {
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) => tokio::task::block_in_place(|| handle.block_on(future)),
+        Err(_) => FALLBACK_RUNTIME.block_on(future),
+    }
+}
+
+/// Map a string address onto the fixed 32-byte key used by `QNetStorage`
+fn address_key(address: &str) -> [u8; 32] {
+    let mut hasher = Sha3_256::new();
+    hasher.update(address.as_bytes());
+    let result = hasher.finalize();
+    let mut key = [0u8; 32];
+    key.copy_from_slice(&result);
+    key
+}
+
+/// In-memory state backend
+#[derive(Debug, Default, Clone)]
+pub struct MemoryBackend {
+    blocks: HashMap<BlockHash, Block>,
+    accounts: HashMap<String, Account>,
+}
+
+impl MemoryBackend {
+    /// Create empty in-memory backend
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl StateBackend for MemoryBackend {
+    fn get_block(&self, hash: &BlockHash) -> StateResult<Option<Block>> {
+        Ok(self.blocks.get(hash).cloned())
+    }
+
+    fn store_block(&mut self, block: &Block) -> StateResult<()> {
+        self.blocks.insert(block.hash(), block.clone());
+        Ok(())
+    }
+
+    fn get_account(&self, address: &str) -> StateResult<Option<Account>> {
+        Ok(self.accounts.get(address).cloned())
+    }
+
+    fn store_account(&mut self, address: &str, account: &Account) -> StateResult<()> {
+        self.accounts.insert(address.to_string(), account.clone());
+        Ok(())
+    }
+}
+
+impl StateBackend for QNetStorage {
+    fn get_block(&self, hash: &BlockHash) -> StateResult<Option<Block>> {
+        let bytes = block_on(self.get_block_record(hash))
+            .map_err(|e| StateError::StorageError(e.to_string()))?;
+        match bytes {
+            Some(bytes) => Ok(Some(bincode::deserialize(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn store_block(&mut self, block: &Block) -> StateResult<()> {
+        let bytes = bincode::serialize(block)?;
+        block_on(self.store_block_record(&block.hash(), &bytes))
+            .map_err(|e| StateError::StorageError(e.to_string()))
+    }
+
+    fn get_account(&self, address: &str) -> StateResult<Option<Account>> {
+        let bytes = block_on(self.get_account_record(&address_key(address)))
+            .map_err(|e| StateError::StorageError(e.to_string()))?;
+        match bytes {
+            Some(bytes) => Ok(Some(bincode::deserialize(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn store_account(&mut self, address: &str, account: &Account) -> StateResult<()> {
+        let bytes = bincode::serialize(account)?;
+        block_on(self.store_account_record(&address_key(address), &bytes))
+            .map_err(|e| StateError::StorageError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use qnet_core::storage::{StorageConfig, LSMConfig};
+    use crate::StateManager;
+
+    fn test_block(height: u64) -> Block {
+        Block::new(height, 1_700_000_000 + height, [height as u8; 32], Vec::new(), "producer_1".to_string())
+    }
+
+    fn test_account(address: &str, balance: u64) -> Account {
+        let mut account = Account::new(address.to_string());
+        account.balance = balance;
+        account.nonce = 3;
+        account
+    }
+
+    fn test_storage() -> QNetStorage {
+        let config = StorageConfig {
+            bloom_filter_size: 10_000,
+            cache_size: 1024 * 1024,
+            lsm_config: LSMConfig {
+                memtable_size: 1024 * 1024,
+                ..StorageConfig::default().lsm_config
+            },
+            ..StorageConfig::default()
+        };
+        block_on(QNetStorage::new(config)).expect("storage should initialize")
+    }
+
+    /// Shared assertions every backend must satisfy
+    fn assert_backend_behaviour<B: StateBackend>(backend: &mut B) {
+        let block = test_block(7);
+        let hash = block.hash();
+        assert!(backend.get_block(&hash).unwrap().is_none());
+        backend.store_block(&block).unwrap();
+        assert_eq!(backend.get_block(&hash).unwrap(), Some(block));
+        assert!(backend.get_block(&test_block(8).hash()).unwrap().is_none());
+
+        // Short, long and unicode addresses all map to distinct entries
+        let addresses = ["a", "qnet1aliceaddress0000000000000000000000000000000000000000", "bob_🚀"];
+        for (i, address) in addresses.iter().enumerate() {
+            assert!(backend.get_account(address).unwrap().is_none());
+            backend.store_account(address, &test_account(address, 100 + i as u64)).unwrap();
+        }
+        for (i, address) in addresses.iter().enumerate() {
+            let stored = backend.get_account(address).unwrap().expect("account should exist");
+            assert_eq!(stored.address, *address);
+            assert_eq!(stored.balance, 100 + i as u64);
+            assert_eq!(stored.nonce, 3);
+        }
+
+        // Overwrites replace the previous value
+        backend.store_account("a", &test_account("a", 5)).unwrap();
+        assert_eq!(backend.get_account("a").unwrap().unwrap().balance, 5);
+    }
+
+    #[test]
+    fn test_memory_backend() {
+        assert_backend_behaviour(&mut MemoryBackend::new());
+    }
+
+    #[test]
+    fn test_qnet_storage_backend() {
+        assert_backend_behaviour(&mut test_storage());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_qnet_storage_backend_inside_runtime() {
+        let mut storage = QNetStorage::new(StorageConfig {
+            bloom_filter_size: 10_000,
+            cache_size: 1024 * 1024,
+            ..StorageConfig::default()
+        }).await.unwrap();
+        assert_backend_behaviour(&mut storage);
+    }
+
+    #[test]
+    fn test_state_manager_commits_to_backend() {
+        let mut manager = StateManager::with_backend(test_storage());
+        manager.set_account(test_account("carol", 42));
+        manager.commit().unwrap();
+
+        let stored = StateBackend::get_account(manager.backend(), "carol").unwrap().unwrap();
+        assert_eq!(stored.balance, 42);
+
+        let mut reloaded = StateManager::with_backend(MemoryBackend::new());
+        reloaded.backend_mut().store_account("carol", &stored).unwrap();
+        assert_eq!(reloaded.load_account("carol").unwrap().map(|a| a.balance), Some(42));
+        assert_eq!(reloaded.get_balance("carol"), 42);
+    }
+}
@@ -138,6 +138,90 @@ pub mod storage_version {
     pub const V4_COMPRESSED: u8 = 0x04;
 }
 
+/// Serialization format versions for `MicroBlock` / `MacroBlock`
+/// Written as the first byte by `serialize_versioned`, so adding or
+/// reordering struct fields never makes previously stored blocks unreadable.
+/// Every new layout gets a new version plus a decoder in `deserialize_versioned`.
+pub mod block_format {
+    /// Original layout without Proof of History fields
+    pub const V1: u8 = 0x01;
+    /// Adds `poh_hash` / `poh_count`
+    pub const V2: u8 = 0x02;
+    /// Version written by `serialize_versioned`
+    pub const CURRENT: u8 = V2;
+}
+
+/// Split a versioned block payload into its version byte and bincode body
+fn split_format_version(data: &[u8]) -> Result<(u8, &[u8]), StateError> {
+    match data.split_first() {
+        Some((version, body)) => Ok((*version, body)),
+        None => Err(StateError::InvalidBlock("Empty block data".to_string())),
+    }
+}
+
+fn unsupported_format(version: u8) -> StateError {
+    StateError::InvalidBlock(format!(
+        "Unsupported block format version {} (latest known: {})",
+        version, block_format::CURRENT
+    ))
+}
+
+/// `block_format::V1` microblock layout (pre-PoH)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct MicroBlockV1 {
+    height: u64,
+    timestamp: u64,
+    transactions: Vec<Transaction>,
+    producer: String,
+    signature: Vec<u8>,
+    previous_hash: [u8; 32],
+    merkle_root: [u8; 32],
+}
+
+impl From<MicroBlockV1> for MicroBlock {
+    fn from(v1: MicroBlockV1) -> Self {
+        Self {
+            height: v1.height,
+            timestamp: v1.timestamp,
+            transactions: v1.transactions,
+            producer: v1.producer,
+            signature: v1.signature,
+            previous_hash: v1.previous_hash,
+            merkle_root: v1.merkle_root,
+            // Same defaults as MicroBlock::new
+            poh_hash: vec![0u8; 64],
+            poh_count: 0,
+        }
+    }
+}
+
+/// `block_format::V1` macroblock layout (pre-PoH)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct MacroBlockV1 {
+    height: u64,
+    timestamp: u64,
+    micro_blocks: Vec<[u8; 32]>,
+    state_root: [u8; 32],
+    consensus_data: ConsensusData,
+    previous_hash: [u8; 32],
+}
+
+impl From<MacroBlockV1> for MacroBlock {
+    fn from(v1: MacroBlockV1) -> Self {
+        Self {
+            height: v1.height,
+            timestamp: v1.timestamp,
+            micro_blocks: v1.micro_blocks,
+            state_root: v1.state_root,
+            consensus_data: v1.consensus_data,
+            previous_hash: v1.previous_hash,
+            // Same defaults as MacroBlock::new
+            poh_hash: vec![0u8; 64],
+            poh_count: 0,
+        }
+    }
+}
+
 /// Versioned stored block - wraps different block formats with explicit version tag
 /// This is the PRIMARY format for storing blocks in RocksDB (v2.19.13+)
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -463,7 +547,7 @@ impl Block {
     /// Apply block to state
     pub fn apply_to_state(&self, accounts: &mut HashMap<String, Account>) -> Result<(), StateError> {
         for tx in &self.transactions {
-            tx.apply_to_state(accounts)?;
+            tx.execute(accounts)?;
         }
         Ok(())
     }
@@ -476,9 +560,10 @@ impl MicroBlock {
         height: u64,
         timestamp: u64,
         previous_hash: [u8; 32],
-        transactions: Vec<Transaction>,
+        mut transactions: Vec<Transaction>,
         producer: String,
     ) -> Self {
+        Self::sort_canonical(&mut transactions);
         let merkle_root = Block::calculate_merkle_root(&transactions);
         
         Self {
@@ -495,6 +580,17 @@ impl MicroBlock {
         }
     }
     
+    /// Sort transactions into canonical block order (`Transaction::canonical_cmp`)
+    pub fn sort_canonical(transactions: &mut [Transaction]) {
+        transactions.sort_by(|a, b| a.canonical_cmp(b));
+    }
+    
+    /// Whether the transactions are in canonical block order
+    pub fn has_canonical_order(&self) -> bool {
+        self.transactions.windows(2)
+            .all(|pair| pair[0].canonical_cmp(&pair[1]) != std::cmp::Ordering::Greater)
+    }
+    
     /// Calculate microblock hash
     pub fn hash(&self) -> [u8; 32] {
         let mut hasher = Sha3_256::new();
@@ -540,6 +636,11 @@ impl MicroBlock {
             return Err(StateError::InvalidBlock("Too many transactions in microblock".to_string()));
         }
         
+        // Producers and validators must agree on one transaction order
+        if !self.has_canonical_order() {
+            return Err(StateError::InvalidBlock("Transactions not in canonical order".to_string()));
+        }
+        
         // Verify merkle root
         let calculated_root = Block::calculate_merkle_root(&self.transactions);
         if calculated_root != self.merkle_root {
@@ -553,6 +654,24 @@ impl MicroBlock {
         
         Ok(())
     }
+    
+    /// Serialize with a leading `block_format::CURRENT` version byte
+    pub fn serialize_versioned(&self) -> Result<Vec<u8>, StateError> {
+        let mut data = vec![block_format::CURRENT];
+        data.extend(bincode::serialize(self)?);
+        Ok(data)
+    }
+    
+    /// Decode data written by `serialize_versioned` under any known format version,
+    /// migrating older layouts to the current struct
+    pub fn deserialize_versioned(data: &[u8]) -> Result<Self, StateError> {
+        let (version, body) = split_format_version(data)?;
+        match version {
+            block_format::V1 => Ok(bincode::deserialize::<MicroBlockV1>(body)?.into()),
+            block_format::V2 => Ok(bincode::deserialize(body)?),
+            other => Err(unsupported_format(other)),
+        }
+    }
 }
 
 // Implement methods for EfficientMicroBlock
@@ -584,32 +703,7 @@ impl EfficientMicroBlock {
     pub fn from_microblock(microblock: &MicroBlock) -> Self {
         let transaction_hashes: Vec<[u8; 32]> = microblock.transactions
             .iter()
-            .map(|tx| {
-                // Convert string hash to [u8; 32] 
-                if let Ok(hash_bytes) = hex::decode(&tx.hash) {
-                    if hash_bytes.len() == 32 {
-                        let mut hash_array = [0u8; 32];
-                        hash_array.copy_from_slice(&hash_bytes);
-                        hash_array
-                    } else {
-                        // If hex decode fails or wrong length, use blake3 hash of the transaction
-                        let mut hasher = Sha3_256::new();
-                        hasher.update(tx.hash.as_bytes());
-                        let result = hasher.finalize();
-                        let mut hash_array = [0u8; 32];
-                        hash_array.copy_from_slice(&result);
-                        hash_array
-                    }
-                } else {
-                    // Fallback: hash the transaction hash string
-                    let mut hasher = Sha3_256::new();
-                    hasher.update(tx.hash.as_bytes());
-                    let result = hasher.finalize();
-                    let mut hash_array = [0u8; 32];
-                    hash_array.copy_from_slice(&result);
-                    hash_array
-                }
-            })
+            .map(|tx| crate::merkle::transaction_leaf(&tx.hash))
             .collect();
             
         Self {
@@ -627,19 +721,7 @@ impl EfficientMicroBlock {
     
     /// Calculate merkle root from transaction hashes
     fn calculate_merkle_root_from_hashes(transaction_hashes: &[[u8; 32]]) -> [u8; 32] {
-        if transaction_hashes.is_empty() {
-            return [0u8; 32];
-        }
-        
-        let mut hasher = Sha3_256::new();
-        for hash in transaction_hashes {
-            hasher.update(hash);
-        }
-        
-        let result = hasher.finalize();
-        let mut root = [0u8; 32];
-        root.copy_from_slice(&result);
-        root
+        crate::merkle::merkle_root(transaction_hashes)
     }
     
     /// Calculate efficient microblock hash
@@ -768,5 +850,168 @@ impl MacroBlock {
         
         Ok(())
     }
+    
+    /// Serialize with a leading `block_format::CURRENT` version byte
+    pub fn serialize_versioned(&self) -> Result<Vec<u8>, StateError> {
+        let mut data = vec![block_format::CURRENT];
+        data.extend(bincode::serialize(self)?);
+        Ok(data)
+    }
+    
+    /// Decode data written by `serialize_versioned` under any known format version,
+    /// migrating older layouts to the current struct
+    pub fn deserialize_versioned(data: &[u8]) -> Result<Self, StateError> {
+        let (version, body) = split_format_version(data)?;
+        match version {
+            block_format::V1 => Ok(bincode::deserialize::<MacroBlockV1>(body)?.into()),
+            block_format::V2 => Ok(bincode::deserialize(body)?),
+            other => Err(unsupported_format(other)),
+        }
+    }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transaction::{gas_limits, TransactionType, BASE_FEE_NANO_QNC};
+
+    fn transfer(from: &str, nonce: u64, gas_price: u64) -> Transaction {
+        Transaction::new(
+            from.to_string(),
+            Some("carol".to_string()),
+            1_000,
+            nonce,
+            gas_price,
+            gas_limits::TRANSFER,
+            1_704_067_200,
+            None,
+            TransactionType::Transfer { from: from.to_string(), to: "carol".to_string(), amount: 1_000 },
+            None,
+        )
+    }
+
+    fn reward(to: &str) -> Transaction {
+        Transaction::new(
+            "system_emission".to_string(),
+            Some(to.to_string()),
+            5_000,
+            0,
+            0,
+            0,
+            1_704_067_200,
+            None,
+            TransactionType::RewardDistribution,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_transactions_in_canonical_order() {
+        let txs = vec![
+            transfer("bob", 1, BASE_FEE_NANO_QNC),
+            transfer("alice", 2, BASE_FEE_NANO_QNC),
+            reward("dave"),
+            transfer("alice", 1, BASE_FEE_NANO_QNC),
+            transfer("alice", 1, BASE_FEE_NANO_QNC * 3),
+        ];
+
+        // Any arrival order produces the same block
+        let block = MicroBlock::new(7, 1_704_067_201, [1u8; 32], txs.clone(), "producer".to_string());
+        let mut reversed = txs.clone();
+        reversed.reverse();
+        let same = MicroBlock::new(7, 1_704_067_201, [1u8; 32], reversed, "producer".to_string());
+        assert_eq!(block.transactions, same.transactions);
+        assert_eq!(block.merkle_root, same.merkle_root);
+        assert!(block.has_canonical_order());
+        block.validate().unwrap();
+
+        // System first, then sender, nonce and highest gas price
+        let order: Vec<(&str, u64, u64)> = block.transactions.iter()
+            .map(|tx| (tx.from.as_str(), tx.nonce, tx.gas_price))
+            .collect();
+        assert_eq!(order, vec![
+            ("system_emission", 0, 0),
+            ("alice", 1, BASE_FEE_NANO_QNC * 3),
+            ("alice", 1, BASE_FEE_NANO_QNC),
+            ("alice", 2, BASE_FEE_NANO_QNC),
+            ("bob", 1, BASE_FEE_NANO_QNC),
+        ]);
+
+        // A producer that reorders its block is rejected even with a matching merkle root
+        let mut tampered = block.clone();
+        tampered.transactions.swap(1, 3);
+        tampered.merkle_root = Block::calculate_merkle_root(&tampered.transactions);
+        assert!(!tampered.has_canonical_order());
+        assert!(matches!(tampered.validate(), Err(StateError::InvalidBlock(msg)) if msg.contains("canonical order")));
+    }
+
+    fn encode_v1<T: Serialize>(block: &T) -> Vec<u8> {
+        let mut data = vec![block_format::V1];
+        data.extend(bincode::serialize(block).unwrap());
+        data
+    }
+
+    #[test]
+    fn test_v1_microblock_readable_after_v2() {
+        let block = MicroBlock::new(12, 1_704_067_201, [3u8; 32], vec![transfer("alice", 1, BASE_FEE_NANO_QNC)], "producer".to_string());
+        let v1 = encode_v1(&MicroBlockV1 {
+            height: block.height,
+            timestamp: block.timestamp,
+            transactions: block.transactions.clone(),
+            producer: block.producer.clone(),
+            signature: vec![7u8; 4],
+            previous_hash: block.previous_hash,
+            merkle_root: block.merkle_root,
+        });
+
+        // Old data migrates to the current layout with default PoH fields
+        let migrated = MicroBlock::deserialize_versioned(&v1).unwrap();
+        assert_eq!(migrated.height, 12);
+        assert_eq!(migrated.transactions, block.transactions);
+        assert_eq!(migrated.signature, vec![7u8; 4]);
+        assert_eq!(migrated.poh_hash, vec![0u8; 64]);
+        assert_eq!(migrated.poh_count, 0);
+
+        // Current format round-trips and carries the new version byte
+        let mut current = block.clone();
+        current.poh_hash = vec![9u8; 64];
+        current.poh_count = 4_242;
+        let encoded = current.serialize_versioned().unwrap();
+        assert_eq!(encoded[0], block_format::CURRENT);
+        assert_eq!(MicroBlock::deserialize_versioned(&encoded).unwrap(), current);
+
+        assert!(matches!(MicroBlock::deserialize_versioned(&[]), Err(StateError::InvalidBlock(_))));
+        let mut future = encoded.clone();
+        future[0] = block_format::CURRENT + 1;
+        assert!(matches!(MicroBlock::deserialize_versioned(&future), Err(StateError::InvalidBlock(msg)) if msg.contains("Unsupported")));
+    }
+
+    #[test]
+    fn test_v1_macroblock_readable_after_v2() {
+        let consensus_data = ConsensusData {
+            commits: HashMap::from([("node_a".to_string(), vec![1u8; 32])]),
+            reveals: HashMap::from([("node_a".to_string(), vec![2u8; 32])]),
+            next_leader: "node_a".to_string(),
+        };
+        let block = MacroBlock::new(2, 1_704_067_290, [4u8; 32], vec![[5u8; 32]; 90], [6u8; 32], consensus_data.clone());
+        let v1 = encode_v1(&MacroBlockV1 {
+            height: block.height,
+            timestamp: block.timestamp,
+            micro_blocks: block.micro_blocks.clone(),
+            state_root: block.state_root,
+            consensus_data,
+            previous_hash: block.previous_hash,
+        });
+
+        let migrated = MacroBlock::deserialize_versioned(&v1).unwrap();
+        assert_eq!(migrated, block);
+        assert_eq!(migrated.hash(), block.hash());
+
+        let mut current = block.clone();
+        current.poh_count = 8_100;
+        let encoded = current.serialize_versioned().unwrap();
+        assert_eq!(encoded[0], block_format::CURRENT);
+        assert_eq!(MacroBlock::deserialize_versioned(&encoded).unwrap(), current);
+    }
+}
@@ -0,0 +1,358 @@
+//! Fluent construction of transactions
+//!
+//! `TransactionBuilder` fills defaults (gas limit for the type, base fee, current
+//! time), computes the canonical hash and runs `Transaction::validate`, so callers
+//! cannot forget the hash or ship a transaction that validators would reject.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::account::{ActivationPhase, NodeType};
+use crate::transaction::{
+    gas_limits, BatchTransferData, Transaction, TransactionType, ValidUntil, BASE_FEE_NANO_QNC, UNBOUND_CHAIN_ID,
+};
+use crate::{StateError, StateResult};
+
+/// Builder for user and system transactions
+#[derive(Debug, Clone, Default)]
+pub struct TransactionBuilder {
+    from: Option<String>,
+    to: Option<String>,
+    amount: u64,
+    nonce: Option<u64>,
+    gas_price: Option<u64>,
+    gas_limit: Option<u64>,
+    timestamp: Option<u64>,
+    signature: Option<String>,
+    public_key: Option<String>,
+    tx_type: Option<TransactionType>,
+    data: Option<String>,
+    chain_id: u64,
+    valid_until: Option<ValidUntil>,
+}
+
+impl TransactionBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// QNC transfer
+    pub fn transfer(mut self, from: &str, to: &str, amount: u64) -> Self {
+        self.from = Some(from.to_string());
+        self.to = Some(to.to_string());
+        self.amount = amount;
+        self.tx_type = Some(TransactionType::Transfer { from: from.to_string(), to: to.to_string(), amount });
+        self
+    }
+
+    /// Cancellation of `from`'s pending transaction at `nonce`: a zero-value
+    /// self-transfer. Set a gas price above the original's with `gas` so the
+    /// mempool replaces it.
+    pub fn cancel(from: &str, nonce: u64) -> Self {
+        Self::new().transfer(from, from, 0).nonce(nonce)
+    }
+
+    /// Node activation (`amount` is 0 in Phase 1, the Pool 3 transfer in Phase 2)
+    pub fn node_activation(mut self, from: &str, node_type: NodeType, amount: u64, phase: ActivationPhase) -> Self {
+        self.from = Some(from.to_string());
+        self.amount = amount;
+        self.tx_type = Some(TransactionType::NodeActivation { node_type, amount, phase });
+        self
+    }
+
+    /// Contract deployment; `code` goes into the call data
+    pub fn contract_deploy(mut self, from: &str, code: &str) -> Self {
+        self.from = Some(from.to_string());
+        self.data = Some(code.to_string());
+        self.tx_type = Some(TransactionType::ContractDeploy);
+        self
+    }
+
+    /// Contract call
+    pub fn contract_call(mut self, from: &str, contract: &str, call_data: &str) -> Self {
+        self.from = Some(from.to_string());
+        self.to = Some(contract.to_string());
+        self.data = Some(call_data.to_string());
+        self.tx_type = Some(TransactionType::ContractCall);
+        self
+    }
+
+    /// Batch of transfers from one sender
+    pub fn batch_transfers(mut self, from: &str, transfers: Vec<BatchTransferData>, batch_id: &str) -> Self {
+        self.from = Some(from.to_string());
+        self.amount = transfers.iter().fold(0u64, |total, transfer| total.saturating_add(transfer.amount));
+        self.tx_type = Some(TransactionType::BatchTransfers { transfers, batch_id: batch_id.to_string() });
+        self
+    }
+
+    /// Sender for `build_system` (e.g. `system_emission`)
+    pub fn from(mut self, from: &str) -> Self {
+        self.from = Some(from.to_string());
+        self
+    }
+
+    /// Recipient and amount for `build_system`
+    pub fn to(mut self, to: &str, amount: u64) -> Self {
+        self.to = Some(to.to_string());
+        self.amount = amount;
+        self
+    }
+
+    pub fn gas(mut self, price: u64, limit: u64) -> Self {
+        self.gas_price = Some(price);
+        self.gas_limit = Some(limit);
+        self
+    }
+
+    pub fn nonce(mut self, nonce: u64) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    /// Defaults to the current time
+    pub fn timestamp(mut self, timestamp: u64) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    pub fn data(mut self, data: &str) -> Self {
+        self.data = Some(data.to_string());
+        self
+    }
+
+    /// Bind to a network (replay protection); unbound by default
+    pub fn chain_id(mut self, chain_id: u64) -> Self {
+        self.chain_id = chain_id;
+        self
+    }
+
+    /// Last block height or timestamp the transaction may be included at; never expires by default
+    pub fn valid_until(mut self, valid_until: ValidUntil) -> Self {
+        self.valid_until = Some(valid_until);
+        self
+    }
+
+    /// Client signature and Ed25519 public key
+    pub fn signed(mut self, signature: &str, public_key: &str) -> Self {
+        self.signature = Some(signature.to_string());
+        self.public_key = Some(public_key.to_string());
+        self
+    }
+
+    /// Build a user transaction; the type, sender and nonce are required
+    pub fn build(self) -> StateResult<Transaction> {
+        let tx_type = self.tx_type.clone()
+            .ok_or_else(|| invalid("Transaction type not set"))?;
+        let nonce = self.nonce
+            .ok_or_else(|| invalid("Nonce not set"))?;
+        let gas_limit = self.gas_limit.unwrap_or_else(|| default_gas_limit(&tx_type));
+        let gas_price = self.gas_price.unwrap_or(BASE_FEE_NANO_QNC);
+        if gas_limit > gas_limits::MAX_GAS_LIMIT {
+            return Err(invalid(&format!("Gas limit {} exceeds maximum {}", gas_limit, gas_limits::MAX_GAS_LIMIT)));
+        }
+
+        let tx = self.assemble(tx_type, nonce, gas_price, gas_limit)?;
+        if tx.is_system() {
+            return Err(invalid("System transactions must be built with build_system"));
+        }
+        Ok(tx)
+    }
+
+    /// Build a system transaction (rewards, account creation, ping records):
+    /// no signature, public key, nonce or gas, and not bound to a chain
+    pub fn build_system(mut self, kind: TransactionType) -> StateResult<Transaction> {
+        self.signature = None;
+        self.public_key = None;
+        self.chain_id = UNBOUND_CHAIN_ID;
+        self.valid_until = None;
+
+        let tx = self.assemble(kind, 0, 0, gas_limits::PING)?;
+        if !tx.is_system() {
+            return Err(invalid("Not a system transaction type"));
+        }
+        Ok(tx)
+    }
+
+    fn assemble(self, tx_type: TransactionType, nonce: u64, gas_price: u64, gas_limit: u64) -> StateResult<Transaction> {
+        let from = self.from.ok_or_else(|| invalid("Sender not set"))?;
+        let timestamp = self.timestamp.unwrap_or_else(|| {
+            SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+        });
+
+        let mut tx = Transaction::new(
+            from, self.to, self.amount, nonce, gas_price, gas_limit, timestamp,
+            self.signature, tx_type, self.data,
+        );
+        tx.public_key = self.public_key;
+        if self.chain_id != UNBOUND_CHAIN_ID {
+            tx = tx.with_chain_id(self.chain_id);
+        }
+        if let Some(valid_until) = self.valid_until {
+            tx = tx.with_valid_until(valid_until);
+        }
+        tx.validate().map_err(StateError::InvalidTransaction)?;
+        Ok(tx)
+    }
+}
+
+fn invalid(reason: &str) -> StateError {
+    StateError::InvalidTransaction(reason.to_string())
+}
+
+/// Gas limit charged for a type when none is given
+fn default_gas_limit(tx_type: &TransactionType) -> u64 {
+    match tx_type {
+        TransactionType::Transfer { .. } => gas_limits::TRANSFER,
+        TransactionType::NodeActivation { .. } => gas_limits::NODE_ACTIVATION,
+        TransactionType::ContractDeploy => gas_limits::CONTRACT_DEPLOY,
+        TransactionType::ContractCall => gas_limits::CONTRACT_CALL,
+        TransactionType::BatchRewardClaims { .. }
+        | TransactionType::BatchNodeActivations { .. }
+        | TransactionType::BatchTransfers { .. } => gas_limits::BATCH_OPERATION,
+        TransactionType::RewardDistribution
+        | TransactionType::CreateAccount { .. }
+        | TransactionType::PingAttestation { .. }
+        | TransactionType::PingCommitmentWithSampling { .. } => gas_limits::PING,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_user_transactions() {
+        let transfer = TransactionBuilder::new()
+            .transfer("alice", "bob", 1_000)
+            .nonce(3)
+            .timestamp(1_704_067_200)
+            .build()
+            .unwrap();
+        assert_eq!(transfer.hash, transfer.calculate_hash());
+        assert_eq!((transfer.gas_price, transfer.gas_limit), (BASE_FEE_NANO_QNC, gas_limits::TRANSFER));
+        assert_eq!(transfer.to.as_deref(), Some("bob"));
+        transfer.validate().unwrap();
+        // Same hash as the positional constructor
+        let manual = Transaction::new(
+            "alice".to_string(), Some("bob".to_string()), 1_000, 3, BASE_FEE_NANO_QNC, gas_limits::TRANSFER,
+            1_704_067_200, None,
+            TransactionType::Transfer { from: "alice".to_string(), to: "bob".to_string(), amount: 1_000 },
+            None,
+        );
+        assert_eq!(transfer.hash, manual.hash);
+
+        let bound = TransactionBuilder::new()
+            .transfer("alice", "bob", 1_000)
+            .nonce(3)
+            .gas(BASE_FEE_NANO_QNC * 2, 20_000)
+            .timestamp(1_704_067_200)
+            .chain_id(7)
+            .signed("sig", "pubkey")
+            .build()
+            .unwrap();
+        assert_eq!(bound.chain_id, 7);
+        assert_ne!(bound.hash, transfer.hash);
+        assert_eq!(bound.hash, bound.calculate_hash());
+        assert_eq!(bound.public_key.as_deref(), Some("pubkey"));
+
+        let activation = TransactionBuilder::new()
+            .node_activation("alice", NodeType::Full, 0, ActivationPhase::Phase1)
+            .nonce(4)
+            .build()
+            .unwrap();
+        assert_eq!(activation.gas_limit, gas_limits::NODE_ACTIVATION);
+        assert_eq!(activation.hash, activation.calculate_hash());
+
+        let call = TransactionBuilder::new()
+            .contract_call("alice", "contract_1", "{\"method\":\"ping\"}")
+            .nonce(5)
+            .build()
+            .unwrap();
+        assert_eq!(call.gas_limit, gas_limits::CONTRACT_CALL);
+
+        let batch = TransactionBuilder::new()
+            .batch_transfers("alice", vec![
+                BatchTransferData { to_address: "bob".to_string(), amount: 10, memo: None },
+                BatchTransferData { to_address: "carol".to_string(), amount: 20, memo: None },
+            ], "batch_1")
+            .nonce(6)
+            .build()
+            .unwrap();
+        assert_eq!((batch.amount, batch.gas_limit), (30, gas_limits::BATCH_OPERATION));
+    }
+
+    #[test]
+    fn test_build_rejects_missing_or_invalid_fields() {
+        let missing_nonce = TransactionBuilder::new().transfer("alice", "bob", 1_000).build();
+        assert!(matches!(missing_nonce, Err(StateError::InvalidTransaction(msg)) if msg.contains("Nonce")));
+        assert!(TransactionBuilder::new().nonce(1).build().is_err());
+        assert!(TransactionBuilder::new().transfer("alice", "alice", 1_000).nonce(1).build().is_err());
+        assert!(TransactionBuilder::new().transfer("alice", "bob", 0).nonce(1).build().is_err());
+        assert!(TransactionBuilder::new().transfer("alice", "bob", 1).nonce(1).gas(1, gas_limits::MAX_GAS_LIMIT + 1).build().is_err());
+        // Phase 2 activation must move QNC
+        assert!(TransactionBuilder::new()
+            .node_activation("alice", NodeType::Super, 0, ActivationPhase::Phase2)
+            .nonce(1)
+            .build()
+            .is_err());
+        // System types go through build_system
+        let system_sender = TransactionBuilder::new().transfer("system_emission", "bob", 5).nonce(0).build();
+        assert!(matches!(system_sender, Err(StateError::InvalidTransaction(msg)) if msg.contains("build_system")));
+    }
+
+    #[test]
+    fn test_build_cancellation() {
+        let cancel = TransactionBuilder::cancel("alice", 7)
+            .gas(BASE_FEE_NANO_QNC * 2, gas_limits::TRANSFER)
+            .build()
+            .unwrap();
+        assert!(cancel.is_cancellation());
+        assert_eq!((cancel.nonce, cancel.amount, cancel.to.as_deref()), (7, 0, Some("alice")));
+        cancel.validate().unwrap();
+
+        // Only the zero-value form is exempt from the self-transfer rule
+        assert!(TransactionBuilder::new().transfer("alice", "alice", 1).nonce(7).build().is_err());
+        let transfer = TransactionBuilder::new().transfer("alice", "bob", 1).nonce(7).build().unwrap();
+        assert!(!transfer.is_cancellation());
+    }
+
+    #[test]
+    fn test_build_system_transactions() {
+        let emission = TransactionBuilder::new()
+            .from("system_emission")
+            .to("system_rewards_pool", 5_000)
+            .timestamp(1_704_067_200)
+            .data("Emission")
+            .signed("ignored", "ignored")
+            .chain_id(7)
+            .build_system(TransactionType::RewardDistribution)
+            .unwrap();
+        assert!(emission.is_system());
+        assert_eq!(emission.hash, emission.calculate_hash());
+        assert_eq!((emission.nonce, emission.gas_price, emission.gas_limit), (0, 0, 0));
+        assert!(emission.signature.is_none() && emission.public_key.is_none());
+        assert_eq!(emission.chain_id, UNBOUND_CHAIN_ID);
+
+        let ping = TransactionBuilder::new()
+            .from("system_ping")
+            .build_system(TransactionType::PingAttestation {
+                from_node: "node_a".to_string(),
+                to_node: "node_b".to_string(),
+                response_time_ms: 40,
+                success: true,
+            })
+            .unwrap();
+        assert_eq!(ping.gas_limit, gas_limits::PING);
+
+        let account = TransactionBuilder::new()
+            .from("system_genesis")
+            .build_system(TransactionType::CreateAccount { address: "dave".to_string(), initial_balance: 1 })
+            .unwrap();
+        account.validate().unwrap();
+
+        assert!(TransactionBuilder::new().build_system(TransactionType::RewardDistribution).is_err());
+        assert!(TransactionBuilder::new().from("alice").build_system(TransactionType::ContractDeploy).is_err());
+        assert!(TransactionBuilder::new()
+            .from("system_genesis")
+            .build_system(TransactionType::CreateAccount { address: String::new(), initial_balance: 1 })
+            .is_err());
+    }
+}
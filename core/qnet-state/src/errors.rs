@@ -37,6 +37,14 @@ pub enum StateError {
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
     
+    /// Balance, fee or supply arithmetic would overflow u64
+    #[error("Arithmetic overflow: {0}")]
+    ArithmeticOverflow(String),
+    
+    /// Historical state requested below the retention window
+    #[error("State at height {height} is pruned or unavailable (earliest available height: {earliest})")]
+    HistoryUnavailable { height: u64, earliest: u64 },
+    
     /// Other error
     #[error("Other error: {0}")]
     Other(String),
@@ -51,4 +59,16 @@ impl From<String> for StateError {
 /// Result type for state operations
 pub type StateResult<T> = Result<T, StateError>;
 
+/// `a + b`, failing with `ArithmeticOverflow` instead of wrapping
+pub(crate) fn checked_add(a: u64, b: u64, what: &str) -> StateResult<u64> {
+    a.checked_add(b)
+        .ok_or_else(|| StateError::ArithmeticOverflow(format!("{}: {} + {}", what, a, b)))
+}
+
+/// `a * b`, failing with `ArithmeticOverflow` instead of wrapping
+pub(crate) fn checked_mul(a: u64, b: u64, what: &str) -> StateResult<u64> {
+    a.checked_mul(b)
+        .ok_or_else(|| StateError::ArithmeticOverflow(format!("{}: {} * {}", what, a, b)))
+}
+
  
\ No newline at end of file
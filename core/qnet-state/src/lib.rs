@@ -15,17 +15,23 @@ pub mod state_db;
 pub mod state_manager;
 pub mod errors;
 pub mod state;
+pub mod backend;
+pub mod merkle;
+pub mod builder;
 
 #[cfg(feature = "python")]
 mod python_bindings;
 
 pub use account::{Account, AccountState};
-pub use block::{Block, BlockHeader, ConsensusProof, BlockType, MicroBlock, MacroBlock, ConsensusData, LightMicroBlock, BlockHash, EfficientMicroBlock, StoredMicroBlock, PoHState, storage_version};
-pub use transaction::{Transaction, TransactionReceipt, TransactionType, gas_limits, PingSampleData};
-pub use state_db::StateDB;
+pub use block::{Block, BlockHeader, ConsensusProof, BlockType, MicroBlock, MacroBlock, ConsensusData, LightMicroBlock, BlockHash, EfficientMicroBlock, StoredMicroBlock, PoHState, storage_version, block_format};
+pub use transaction::{Transaction, TransactionReceipt, TransactionType, gas_limits, PingSampleData, RawTransactionError, ExecutionReceipt, TxStatus};
+pub use state_db::{StateDB, WriteBatch};
 pub use state_manager::StateManager;
 pub use errors::{StateError, StateResult};
-pub use state::{StateManager as State, MAX_QNC_SUPPLY, MAX_QNC_SUPPLY_NANO};
+pub use backend::MemoryBackend;
+pub use merkle::{MerkleProof, verify_transaction_inclusion};
+pub use builder::TransactionBuilder;
+pub use state::{StateManager as State, StateSnapshot, IncrementalSnapshot, ACCOUNT_HISTORY_RETENTION, MAX_QNC_SUPPLY, MAX_QNC_SUPPLY_NANO};
 
 #[cfg(feature = "python")]
 pub use python_bindings::*;
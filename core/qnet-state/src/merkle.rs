@@ -0,0 +1,143 @@
+//! Transaction Merkle tree for microblocks
+//!
+//! Leaves are the 32-byte transaction hashes, parents are SHA3-256(left || right)
+//! and the last node of an odd level is paired with itself. A `MerkleProof` lets a
+//! light client check that a transaction is in a block knowing only its `merkle_root`.
+
+use serde::{Deserialize, Serialize};
+use sha3::{Sha3_256, Digest};
+
+/// Leaf for a transaction hash: the decoded hash, or SHA3-256 of the string if it
+/// is not 32 bytes of hex (same mapping as `EfficientMicroBlock::transaction_hashes`)
+pub fn transaction_leaf(tx_hash: &str) -> [u8; 32] {
+    let mut leaf = [0u8; 32];
+    match hex::decode(tx_hash) {
+        Ok(bytes) if bytes.len() == 32 => leaf.copy_from_slice(&bytes),
+        _ => leaf.copy_from_slice(&Sha3_256::digest(tx_hash.as_bytes())),
+    }
+    leaf
+}
+
+fn hash_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha3_256::new();
+    hasher.update(left);
+    hasher.update(right);
+    let mut parent = [0u8; 32];
+    parent.copy_from_slice(&hasher.finalize());
+    parent
+}
+
+fn next_level(level: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    level.chunks(2)
+        .map(|pair| hash_pair(&pair[0], pair.get(1).unwrap_or(&pair[0])))
+        .collect()
+}
+
+/// Merkle root of `leaves` (all zeros for an empty block)
+pub fn merkle_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    if leaves.is_empty() {
+        return [0u8; 32];
+    }
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    level[0]
+}
+
+/// Inclusion proof: the sibling at every level from the leaf up to the root
+/// The bits of `leaf_index` say on which side each sibling sits
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleProof {
+    pub leaf_index: u64,
+    pub siblings: Vec<[u8; 32]>,
+}
+
+impl MerkleProof {
+    /// Proof for the leaf at `index`, `None` if out of range
+    pub fn generate(leaves: &[[u8; 32]], index: usize) -> Option<Self> {
+        if index >= leaves.len() {
+            return None;
+        }
+        let mut siblings = Vec::new();
+        let mut level = leaves.to_vec();
+        let mut position = index;
+        while level.len() > 1 {
+            let sibling = level.get(position ^ 1).unwrap_or(&level[position]);
+            siblings.push(*sibling);
+            level = next_level(&level);
+            position /= 2;
+        }
+        Some(Self { leaf_index: index as u64, siblings })
+    }
+
+    /// Root obtained by hashing `leaf` up the proof path
+    pub fn compute_root(&self, leaf: &[u8; 32]) -> [u8; 32] {
+        let mut current = *leaf;
+        let mut position = self.leaf_index;
+        for sibling in &self.siblings {
+            current = if position & 1 == 1 {
+                hash_pair(sibling, &current)
+            } else {
+                hash_pair(&current, sibling)
+            };
+            position >>= 1;
+        }
+        current
+    }
+
+    /// Whether `leaf` is included under `root`
+    pub fn verify(&self, leaf: &[u8; 32], root: &[u8; 32]) -> bool {
+        // An index wider than the path would let one proof match several positions
+        let index_fits = self.siblings.len() >= 64 || self.leaf_index >> self.siblings.len() == 0;
+        index_fits && self.compute_root(leaf) == *root
+    }
+}
+
+/// Light client check that the transaction `tx_hash` is in the block with `merkle_root`
+pub fn verify_transaction_inclusion(tx_hash: &str, proof: &MerkleProof, merkle_root: &[u8; 32]) -> bool {
+    proof.verify(&transaction_leaf(tx_hash), merkle_root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tx_hashes(count: usize) -> Vec<String> {
+        (0..count).map(|i| hex::encode(Sha3_256::digest(format!("tx_{}", i).as_bytes()))).collect()
+    }
+
+    #[test]
+    fn test_inclusion_proof_for_every_transaction() {
+        for count in [1, 2, 5, 8, 13] {
+            let hashes = tx_hashes(count);
+            let leaves: Vec<[u8; 32]> = hashes.iter().map(|h| transaction_leaf(h)).collect();
+            let root = merkle_root(&leaves);
+
+            for (index, hash) in hashes.iter().enumerate() {
+                let proof = MerkleProof::generate(&leaves, index).unwrap();
+                assert!(verify_transaction_inclusion(hash, &proof, &root), "tx {} of {}", index, count);
+            }
+            assert!(MerkleProof::generate(&leaves, count).is_none());
+        }
+    }
+
+    #[test]
+    fn test_inclusion_proof_rejects_other_transactions() {
+        let hashes = tx_hashes(6);
+        let leaves: Vec<[u8; 32]> = hashes.iter().map(|h| transaction_leaf(h)).collect();
+        let root = merkle_root(&leaves);
+        let proof = MerkleProof::generate(&leaves, 2).unwrap();
+
+        // A transaction that is not in the block, or one at a different position
+        let outsider = &tx_hashes(7)[6];
+        assert!(!verify_transaction_inclusion(outsider, &proof, &root));
+        assert!(!verify_transaction_inclusion(&hashes[3], &proof, &root));
+
+        // Same leaf against another block's root, or with an out-of-range index
+        let other_root = merkle_root(&leaves[..5]);
+        assert!(!verify_transaction_inclusion(&hashes[2], &proof, &other_root));
+        let shifted = MerkleProof { leaf_index: proof.leaf_index + 8, ..proof.clone() };
+        assert!(!verify_transaction_inclusion(&hashes[2], &shifted, &root));
+    }
+}
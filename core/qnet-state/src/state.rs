@@ -1,10 +1,12 @@
 //! State management for QNet blockchain
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 use dashmap::DashMap;
-use crate::{Account, Block, Transaction, StateError, StateResult};
+use serde::{Deserialize, Serialize};
+use crate::{Account, Block, ExecutionReceipt, Hash, Transaction, StateError, StateResult, WriteBatch};
 use sha3::{Sha3_256, Digest};
+use crate::errors::checked_add;
 
 /// Maximum supply of QNC tokens (2^32 QNC = 4.295 billion QNC)
 /// NOTE: Stored in whole QNC units for readability
@@ -41,6 +43,45 @@ impl Default for ChainState {
     }
 }
 
+/// Number of snapshot heights remembered as bases for incremental snapshots
+const SNAPSHOT_BASE_HISTORY: usize = 32;
+
+/// Blocks of per-account history kept for `get_account_at`
+pub const ACCOUNT_HISTORY_RETENTION: u64 = 100_000;
+
+/// Minimum advance of the retention floor before history is pruned again
+const HISTORY_PRUNE_INTERVAL: u64 = 100;
+
+/// Full state snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateSnapshot {
+    /// Height the snapshot was taken at
+    pub height: u64,
+    /// Total supply in nanoQNC
+    pub total_supply: u64,
+    /// All accounts, sorted by address
+    pub accounts: Vec<Account>,
+    /// State root of the snapshot contents
+    pub state_root: Hash,
+}
+
+/// State changes since a base snapshot (full or incremental)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IncrementalSnapshot {
+    /// Height of the snapshot this one builds on
+    pub base_height: u64,
+    /// State root the base snapshot must have
+    pub base_state_root: Hash,
+    /// Height the snapshot was taken at
+    pub height: u64,
+    /// Accounts changed after `base_height`, sorted by address
+    pub changed_accounts: Vec<Account>,
+    /// Supply emitted after `base_height` in nanoQNC
+    pub supply_delta: u64,
+    /// State root after applying this snapshot
+    pub state_root: Hash,
+}
+
 /// State manager for blockchain
 pub struct StateManager {
     /// Accounts state
@@ -49,6 +90,21 @@ pub struct StateManager {
     pub chain_state: Arc<parking_lot::RwLock<ChainState>>,
     /// State root
     state_root: Arc<parking_lot::RwLock<[u8; 32]>>,
+    /// Height at which each account was last modified
+    modified_at: Arc<DashMap<String, u64>>,
+    /// Snapshot heights -> (state root, total supply), bases for incremental snapshots
+    snapshot_bases: Arc<parking_lot::RwLock<BTreeMap<u64, (Hash, u64)>>>,
+    /// Account versions keyed by the height they were written at
+    account_history: Arc<DashMap<String, BTreeMap<u64, Account>>>,
+    /// Lowest height `get_account_at` can answer
+    history_floor: Arc<parking_lot::RwLock<u64>>,
+    /// Blocks of history kept behind the current height
+    history_retention: u64,
+    /// Receipts of applied transactions with the height they landed at
+    receipts: Arc<DashMap<String, (u64, ExecutionReceipt)>>,
+    /// Held exclusively while a batch is published, shared by account reads,
+    /// so readers see a block's changes all at once or not at all
+    publish_lock: Arc<parking_lot::RwLock<()>>,
 }
 
 impl StateManager {
@@ -58,64 +114,220 @@ impl StateManager {
             accounts: Arc::new(DashMap::new()),
             chain_state: Arc::new(parking_lot::RwLock::new(ChainState::default())),
             state_root: Arc::new(parking_lot::RwLock::new([0u8; 32])),
+            modified_at: Arc::new(DashMap::new()),
+            snapshot_bases: Arc::new(parking_lot::RwLock::new(BTreeMap::new())),
+            account_history: Arc::new(DashMap::new()),
+            history_floor: Arc::new(parking_lot::RwLock::new(0)),
+            history_retention: ACCOUNT_HISTORY_RETENTION,
+            receipts: Arc::new(DashMap::new()),
+            publish_lock: Arc::new(parking_lot::RwLock::new(())),
         }
     }
     
+    /// Keep `blocks` of account history instead of `ACCOUNT_HISTORY_RETENTION`
+    pub fn with_history_retention(mut self, blocks: u64) -> Self {
+        self.history_retention = blocks;
+        self
+    }
+    
     /// Get account
     pub fn get_account(&self, address: &str) -> Option<Account> {
+        let _published = self.publish_lock.read();
         self.accounts.get(address).map(|acc| acc.clone())
     }
     
+    /// Get several accounts at once, in request order, `None` for unknown addresses
+    /// Holds the chain state read lock for the whole batch so the height cannot advance between entries
+    pub fn get_accounts(&self, addresses: &[&str]) -> StateResult<Vec<Option<Account>>> {
+        let _published = self.publish_lock.read();
+        let _chain_state = self.chain_state.read();
+        Ok(addresses.iter()
+            .map(|address| self.accounts.get(*address).map(|acc| acc.clone()))
+            .collect())
+    }
+    
     /// Update account
     pub fn update_account(&self, address: String, account: Account) {
+        self.record_account(address, account, self.pending_height());
+    }
+    
+    /// Write an account, recording the change and its version at `height`
+    fn record_account(&self, address: String, account: Account, height: u64) {
+        self.modified_at.insert(address.clone(), height);
+        self.account_history.entry(address.clone()).or_default().insert(height, account.clone());
         self.accounts.insert(address, account);
     }
     
+    /// Account as it was after the block at `height` was applied
+    /// Returns `HistoryUnavailable` below the retention window instead of the current value
+    pub fn get_account_at(&self, address: &str, height: u64) -> StateResult<Option<Account>> {
+        let _published = self.publish_lock.read();
+        let current_height = self.chain_state.read().height;
+        if height > current_height {
+            return Err(StateError::Other(format!(
+                "Height {} is above current height {}", height, current_height
+            )));
+        }
+        let earliest = *self.history_floor.read();
+        if height < earliest {
+            return Err(StateError::HistoryUnavailable { height, earliest });
+        }
+        
+        Ok(self.account_history.get(address)
+            .and_then(|versions| versions.range(..=height).next_back().map(|(_, acc)| acc.clone())))
+    }
+    
+    /// Advance the chain height to `height` (never moves backwards)
+    pub fn advance_height(&self, height: u64) {
+        {
+            let mut chain_state = self.chain_state.write();
+            if height <= chain_state.height {
+                return;
+            }
+            chain_state.height = height;
+        }
+        self.prune_history(height);
+    }
+    
+    /// Drop account versions that fell out of the retention window
+    /// The newest version below the floor is kept so queries at the floor still resolve
+    fn prune_history(&self, current_height: u64) {
+        let target = current_height.saturating_sub(self.history_retention);
+        let mut floor = self.history_floor.write();
+        if target < *floor + HISTORY_PRUNE_INTERVAL {
+            return;
+        }
+        
+        for mut versions in self.account_history.iter_mut() {
+            let kept = versions.split_off(&target);
+            let base = if kept.contains_key(&target) { None } else { versions.pop_last() };
+            *versions = kept;
+            if let Some((height, account)) = base {
+                versions.insert(height, account);
+            }
+        }
+        self.receipts.retain(|_, (height, _)| *height >= target);
+        *floor = target;
+    }
+    
     /// Get balance
     pub fn get_balance(&self, address: &str) -> u64 {
+        let _published = self.publish_lock.read();
         self.accounts.get(address).map(|acc| acc.balance).unwrap_or(0)
     }
     
+    /// Height that changes outside `apply_block` are attributed to
+    fn pending_height(&self) -> u64 {
+        self.chain_state.read().height + 1
+    }
+    
     /// Apply transaction
-    pub fn apply_transaction(&self, tx: &Transaction) -> StateResult<()> {
-        // Get mutable access to accounts
-        let mut accounts_map = HashMap::new();
-        
+    pub fn apply_transaction(&self, tx: &Transaction) -> StateResult<ExecutionReceipt> {
+        self.apply_transaction_at(tx, self.pending_height())
+    }
+    
+    /// Apply transaction, recording account changes at `height`
+    /// An out-of-gas transaction is applied with a `Failed` receipt, not an error
+    pub fn apply_transaction_at(&self, tx: &Transaction, height: u64) -> StateResult<ExecutionReceipt> {
+        let mut batch = self.begin_batch();
+        let receipt = self.stage_transaction(&mut batch, tx)?;
+        self.commit_batch(batch, height);
+        self.record_receipt(receipt.clone(), height);
+        Ok(receipt)
+    }
+    
+    fn record_receipt(&self, receipt: ExecutionReceipt, height: u64) {
+        self.receipts.insert(receipt.tx_hash.clone(), (height, receipt));
+    }
+    
+    /// Receipt of an applied transaction, kept as long as account history
+    pub fn get_receipt(&self, tx_hash: &str) -> Option<ExecutionReceipt> {
+        self.receipts.get(tx_hash).map(|entry| entry.1.clone())
+    }
+    
+    /// Start staging account changes for one atomic commit
+    pub fn begin_batch(&self) -> WriteBatch {
+        WriteBatch::new()
+    }
+    
+    /// Apply a transaction to the batch only, reading earlier staged changes first
+    /// A failing transaction leaves the batch unchanged
+    pub fn stage_transaction(&self, batch: &mut WriteBatch, tx: &Transaction) -> StateResult<ExecutionReceipt> {
         // Copy relevant accounts
-        if let Some(acc) = self.accounts.get(&tx.from) {
-            accounts_map.insert(tx.from.clone(), acc.clone());
-        }
-        
-        if let Some(to) = &tx.to {
-            if let Some(acc) = self.accounts.get(to) {
-                accounts_map.insert(to.clone(), acc.clone());
+        let mut accounts_map = HashMap::new();
+        for address in std::iter::once(&tx.from).chain(tx.to.as_ref()) {
+            let account = batch.get_account(address).cloned()
+                .or_else(|| self.accounts.get(address).map(|acc| acc.clone()));
+            if let Some(account) = account {
+                accounts_map.insert(address.clone(), account);
             }
         }
         
         // Apply transaction
-        tx.apply_to_state(&mut accounts_map)?;
+        let receipt = tx.execute(&mut accounts_map)?;
         
-        // Write back changes
-        for (address, account) in accounts_map {
-            self.accounts.insert(address, account);
+        for (_, account) in accounts_map {
+            batch.put_account(account);
         }
-        
-        Ok(())
+        Ok(receipt)
+    }
+    
+    /// Write all staged account changes, recording them at `height`
+    /// Readers see either none or all of the batch
+    pub fn commit_batch(&self, batch: WriteBatch, height: u64) {
+        let _publishing = self.publish_lock.write();
+        self.write_batch(batch, height);
+    }
+    
+    fn write_batch(&self, batch: WriteBatch, height: u64) {
+        for (address, account) in batch.into_accounts() {
+            self.record_account(address, account, height);
+        }
+    }
+    
+    /// Publish a staged block: its account changes and the new height become visible together
+    fn publish_block(&self, batch: WriteBatch, height: u64) {
+        {
+            let _publishing = self.publish_lock.write();
+            self.write_batch(batch, height);
+            let mut chain_state = self.chain_state.write();
+            chain_state.height = chain_state.height.max(height);
+        }
+        self.prune_history(height);
     }
     
     /// Apply block
+    /// All of the block's transactions land together: if any fails, none are applied
     pub fn apply_block(&self, block: &Block) -> StateResult<()> {
+        let mut batch = self.begin_batch();
+        let mut receipts = Vec::with_capacity(block.transactions.len());
         for tx in &block.transactions {
-            self.apply_transaction(tx)?;
+            receipts.push(self.stage_transaction(&mut batch, tx)?);
+        }
+        self.publish_block(batch, block.height);
+        for receipt in receipts {
+            self.record_receipt(receipt, block.height);
         }
-        
-        // Update chain state
-        let mut chain_state = self.chain_state.write();
-        chain_state.height = block.height;
         
         Ok(())
     }
     
+    /// Apply a received block's transactions, skipping the ones that fail
+    /// Results are in transaction order. The remaining transactions are staged
+    /// together and published with the height advanced to `height`, so readers
+    /// never observe part of the block.
+    pub fn apply_transactions_at(&self, transactions: &[Transaction], height: u64) -> Vec<StateResult<ExecutionReceipt>> {
+        let mut batch = self.begin_batch();
+        let results: Vec<_> = transactions.iter()
+            .map(|tx| self.stage_transaction(&mut batch, tx))
+            .collect();
+        self.publish_block(batch, height);
+        for receipt in results.iter().flatten() {
+            self.record_receipt(receipt.clone(), height);
+        }
+        results
+    }
+    
     /// Get chain state
     pub fn get_chain_state(&self) -> ChainState {
         self.chain_state.read().clone()
@@ -155,6 +367,31 @@ impl StateManager {
         Ok(hash)
     }
     
+    /// Compute the state root from applied account state and total supply.
+    /// Unlike `calculate_state_root` the local height counter is excluded, so
+    /// any two nodes that applied the same blocks derive the same root.
+    pub fn compute_state_root(&self) -> Hash {
+        let _published = self.publish_lock.read();
+        let mut accounts: Vec<_> = self.accounts.iter()
+            .map(|entry| (entry.key().clone(), entry.value().balance, entry.value().nonce))
+            .collect();
+        accounts.sort_by(|a, b| a.0.cmp(&b.0));
+        
+        let mut hasher = Sha3_256::new();
+        hasher.update(b"QNet_State_Root_v1");
+        for (address, balance, nonce) in accounts {
+            hasher.update((address.len() as u32).to_le_bytes());
+            hasher.update(address.as_bytes());
+            hasher.update(balance.to_le_bytes());
+            hasher.update(nonce.to_le_bytes());
+        }
+        hasher.update(self.chain_state.read().total_supply.to_le_bytes());
+        
+        let mut hash = [0u8; 32];
+        hash.copy_from_slice(&hasher.finalize());
+        hash
+    }
+    
     /// Get current state root
     pub fn get_state_root(&self) -> [u8; 32] {
         *self.state_root.read()
@@ -176,7 +413,7 @@ impl StateManager {
         }
         
         // Update total supply (in nanoQNC)
-        chain_state.total_supply += actual_emission;
+        chain_state.total_supply = checked_add(chain_state.total_supply, actual_emission, "total supply")?;
         
         if actual_emission < amount {
             println!("⚠️ Emission limited: requested {} QNC, emitted {} QNC (remaining: {} QNC)",
@@ -198,6 +435,127 @@ impl StateManager {
         MAX_QNC_SUPPLY_NANO.saturating_sub(self.get_total_supply())
     }
     
+    /// Remember a snapshot height as a base for later incremental snapshots
+    fn record_snapshot_base(&self, height: u64, state_root: Hash, total_supply: u64) {
+        let mut bases = self.snapshot_bases.write();
+        bases.insert(height, (state_root, total_supply));
+        while bases.len() > SNAPSHOT_BASE_HISTORY {
+            bases.pop_first();
+        }
+    }
+    
+    /// Export full snapshot of current state
+    pub fn export_full(&self) -> StateSnapshot {
+        let mut accounts: Vec<Account> = self.accounts.iter()
+            .map(|entry| entry.value().clone())
+            .collect();
+        accounts.sort_by(|a, b| a.address.cmp(&b.address));
+        
+        let (height, total_supply) = {
+            let chain_state = self.chain_state.read();
+            (chain_state.height, chain_state.total_supply)
+        };
+        let state_root = self.compute_state_root();
+        self.record_snapshot_base(height, state_root, total_supply);
+        
+        StateSnapshot { height, total_supply, accounts, state_root }
+    }
+    
+    /// Rebuild state from a full snapshot
+    pub fn from_snapshot(snapshot: &StateSnapshot) -> StateResult<Self> {
+        let state = Self::new();
+        for account in &snapshot.accounts {
+            state.accounts.insert(account.address.clone(), account.clone());
+            state.account_history.entry(account.address.clone()).or_default()
+                .insert(snapshot.height, account.clone());
+        }
+        {
+            let mut chain_state = state.chain_state.write();
+            chain_state.height = snapshot.height;
+            chain_state.total_supply = snapshot.total_supply;
+        }
+        // No history before the snapshot
+        *state.history_floor.write() = snapshot.height;
+        
+        if state.compute_state_root() != snapshot.state_root {
+            return Err(StateError::Other(format!(
+                "Full snapshot at height {} does not match its state root", snapshot.height
+            )));
+        }
+        state.record_snapshot_base(snapshot.height, snapshot.state_root, snapshot.total_supply);
+        Ok(state)
+    }
+    
+    /// Export changes since the snapshot taken at `since_height`
+    pub fn export_incremental(&self, since_height: u64) -> StateResult<IncrementalSnapshot> {
+        let (base_state_root, base_supply) = self.snapshot_bases.read()
+            .get(&since_height)
+            .copied()
+            .ok_or_else(|| StateError::Other(format!("No snapshot base at height {}", since_height)))?;
+        
+        let mut changed_accounts: Vec<Account> = self.modified_at.iter()
+            .filter(|entry| *entry.value() > since_height)
+            .filter_map(|entry| self.accounts.get(entry.key()).map(|acc| acc.clone()))
+            .collect();
+        changed_accounts.sort_by(|a, b| a.address.cmp(&b.address));
+        
+        let (height, total_supply) = {
+            let chain_state = self.chain_state.read();
+            (chain_state.height, chain_state.total_supply)
+        };
+        let state_root = self.compute_state_root();
+        self.record_snapshot_base(height, state_root, total_supply);
+        
+        Ok(IncrementalSnapshot {
+            base_height: since_height,
+            base_state_root,
+            height,
+            changed_accounts,
+            supply_delta: total_supply.saturating_sub(base_supply),
+            state_root,
+        })
+    }
+    
+    /// Replay an incremental snapshot on top of its base state
+    pub fn apply_incremental(&mut self, snapshot: &IncrementalSnapshot) -> StateResult<()> {
+        // Chain validation: only apply on top of the exact base it was taken against
+        let current_height = self.chain_state.read().height;
+        if current_height != snapshot.base_height {
+            return Err(StateError::Other(format!(
+                "Incremental snapshot expects base height {}, state is at {}",
+                snapshot.base_height, current_height
+            )));
+        }
+        if self.compute_state_root() != snapshot.base_state_root {
+            return Err(StateError::Other(format!(
+                "Incremental snapshot base root mismatch at height {}", snapshot.base_height
+            )));
+        }
+        
+        for account in &snapshot.changed_accounts {
+            self.record_account(account.address.clone(), account.clone(), snapshot.height);
+        }
+        // Intermediate heights are not covered by the snapshot
+        {
+            let mut floor = self.history_floor.write();
+            *floor = (*floor).max(snapshot.height);
+        }
+        let total_supply = {
+            let mut chain_state = self.chain_state.write();
+            chain_state.height = snapshot.height;
+            chain_state.total_supply = checked_add(chain_state.total_supply, snapshot.supply_delta, "total supply")?;
+            chain_state.total_supply
+        };
+        
+        if self.compute_state_root() != snapshot.state_root {
+            return Err(StateError::Other(format!(
+                "State root mismatch after applying incremental snapshot at height {}", snapshot.height
+            )));
+        }
+        self.record_snapshot_base(snapshot.height, snapshot.state_root, total_supply);
+        Ok(())
+    }
+    
     /// Create genesis state
     pub fn create_genesis(&self) -> StateResult<()> {
         // FAIR LAUNCH IMPLEMENTATION
@@ -223,3 +581,308 @@ impl StateManager {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{gas_limits, TransactionType, TxStatus};
+
+    fn transfer(from: &str, to: &str, amount: u64, nonce: u64) -> Transaction {
+        priced_transfer(from, to, amount, nonce, 0, gas_limits::TRANSFER)
+    }
+
+    fn priced_transfer(from: &str, to: &str, amount: u64, nonce: u64, gas_price: u64, gas_limit: u64) -> Transaction {
+        Transaction::new(
+            from.to_string(), Some(to.to_string()), amount, nonce, gas_price, gas_limit, 1_700_000_000, None,
+            TransactionType::Transfer { from: from.to_string(), to: to.to_string(), amount },
+            None,
+        )
+    }
+
+    fn block(height: u64, transactions: Vec<Transaction>) -> Block {
+        Block::new(height, 1_700_000_000 + height, [0u8; 32], transactions, "producer_1".to_string())
+    }
+
+    #[test]
+    fn test_incremental_snapshots_reconstruct_state() {
+        let direct = StateManager::new();
+        direct.update_account("alice".to_string(), Account::with_balance("alice".to_string(), 1_000));
+        direct.apply_block(&block(1, vec![transfer("alice", "bob", 100, 1)])).unwrap();
+        direct.apply_block(&block(2, vec![transfer("alice", "carol", 50, 2)])).unwrap();
+        let full = direct.export_full();
+
+        direct.apply_block(&block(3, vec![transfer("bob", "dave", 30, 1)])).unwrap();
+        direct.emit_rewards(5_000).unwrap();
+        direct.apply_block(&block(4, vec![])).unwrap();
+        let first = direct.export_incremental(full.height).unwrap();
+        assert_eq!(first.changed_accounts.iter().map(|a| a.address.as_str()).collect::<Vec<_>>(), vec!["bob", "dave"]);
+        assert_eq!(first.supply_delta, 5_000);
+
+        direct.apply_block(&block(5, vec![transfer("alice", "erin", 10, 3)])).unwrap();
+        direct.apply_block(&block(6, vec![transfer("dave", "bob", 5, 1)])).unwrap();
+        let second = direct.export_incremental(first.height).unwrap();
+
+        let mut rebuilt = StateManager::from_snapshot(&full).unwrap();
+        rebuilt.apply_incremental(&first).unwrap();
+        rebuilt.apply_incremental(&second).unwrap();
+
+        assert_eq!(rebuilt.compute_state_root(), direct.compute_state_root());
+        assert_eq!(rebuilt.get_chain_state().height, 6);
+        assert_eq!(rebuilt.get_total_supply(), direct.get_total_supply());
+        for address in ["alice", "bob", "carol", "dave", "erin"] {
+            assert_eq!(rebuilt.get_balance(address), direct.get_balance(address), "balance of {}", address);
+        }
+    }
+
+    #[test]
+    fn test_incremental_requires_matching_base() {
+        let direct = StateManager::new();
+        direct.update_account("alice".to_string(), Account::with_balance("alice".to_string(), 1_000));
+        direct.apply_block(&block(1, vec![])).unwrap();
+        let full = direct.export_full();
+        direct.apply_block(&block(2, vec![transfer("alice", "bob", 100, 1)])).unwrap();
+        let first = direct.export_incremental(full.height).unwrap();
+        direct.apply_block(&block(3, vec![transfer("bob", "carol", 10, 1)])).unwrap();
+        let second = direct.export_incremental(first.height).unwrap();
+
+        // Skipping the first incremental breaks the chain
+        let mut rebuilt = StateManager::from_snapshot(&full).unwrap();
+        assert!(rebuilt.apply_incremental(&second).is_err());
+
+        // Same height but divergent base state is rejected too
+        let mut diverged = StateManager::from_snapshot(&full).unwrap();
+        diverged.update_account("alice".to_string(), Account::with_balance("alice".to_string(), 2_000));
+        assert!(diverged.apply_incremental(&first).is_err());
+
+        // A full snapshot must match its own root
+        let mut tampered = full.clone();
+        tampered.accounts[0].balance = 2_000;
+        assert!(StateManager::from_snapshot(&tampered).is_err());
+
+        assert!(direct.export_incremental(99).is_err());
+    }
+
+    #[test]
+    fn test_get_accounts_batch_in_request_order() {
+        let state = StateManager::new();
+        state.update_account("alice".to_string(), Account::with_balance("alice".to_string(), 1_000));
+        state.apply_block(&block(1, vec![transfer("alice", "bob", 100, 1)])).unwrap();
+
+        let accounts = state.get_accounts(&["bob", "nobody", "alice", "bob", "ghost"]).unwrap();
+        let balances: Vec<Option<u64>> = accounts.iter().map(|acc| acc.as_ref().map(|acc| acc.balance)).collect();
+        assert_eq!(balances, vec![Some(100), None, Some(900), Some(100), None]);
+        assert_eq!(accounts[2].as_ref().map(|acc| acc.nonce), state.get_account("alice").map(|acc| acc.nonce));
+        assert!(state.get_accounts(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_account_balance_at_height() {
+        let state = StateManager::new().with_history_retention(10);
+        state.update_account("alice".to_string(), Account::with_balance("alice".to_string(), 1_000));
+        state.apply_block(&block(1, vec![transfer("alice", "bob", 100, 1)])).unwrap();
+        state.apply_block(&block(2, vec![])).unwrap();
+        state.apply_block(&block(3, vec![transfer("alice", "bob", 200, 2), transfer("bob", "carol", 50, 1)])).unwrap();
+        state.apply_block(&block(4, vec![transfer("alice", "carol", 300, 3)])).unwrap();
+
+        let balance_at = |address: &str, height: u64| {
+            state.get_account_at(address, height).unwrap().map(|acc| acc.balance)
+        };
+        assert_eq!(balance_at("alice", 1), Some(900));
+        assert_eq!(balance_at("alice", 2), Some(900));
+        assert_eq!(balance_at("bob", 2), Some(100));
+        assert_eq!(balance_at("alice", 3), Some(700));
+        assert_eq!(balance_at("bob", 3), Some(250));
+        assert_eq!(balance_at("carol", 3), Some(50));
+        assert_eq!(balance_at("carol", 2), None);
+        assert_eq!(balance_at("alice", 4), Some(state.get_balance("alice")));
+        assert!(state.get_account_at("alice", 5).is_err());
+
+        // Old heights fall out of the retention window and are reported as pruned
+        for height in 5..=120 {
+            state.apply_block(&block(height, vec![])).unwrap();
+        }
+        match state.get_account_at("alice", 3) {
+            Err(StateError::HistoryUnavailable { height: 3, earliest }) => assert_eq!(earliest, 100),
+            other => panic!("expected pruned history, got {:?}", other),
+        }
+        assert_eq!(balance_at("alice", 100), Some(400));
+        assert_eq!(balance_at("carol", 120), Some(350));
+    }
+
+    #[test]
+    fn test_readers_never_observe_partial_block() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        const TRANSFERS: u64 = 50;
+        const SUPPLY: u64 = 1_000_000;
+
+        let state = Arc::new(StateManager::new());
+        state.update_account("alice".to_string(), Account::with_balance("alice".to_string(), SUPPLY));
+        let done = Arc::new(AtomicBool::new(false));
+        let reader = {
+            let (state, done) = (state.clone(), done.clone());
+            std::thread::spawn(move || {
+                let mut reads = 0;
+                loop {
+                    let bob = state.get_balance("bob");
+                    assert_eq!(bob % TRANSFERS, 0, "partial block visible: bob has {}", bob);
+                    let accounts = state.get_accounts(&["alice", "bob"]).unwrap();
+                    assert_eq!(accounts.iter().flatten().map(|acc| acc.balance).sum::<u64>(), SUPPLY);
+                    reads += 1;
+                    if done.load(Ordering::Acquire) {
+                        return reads;
+                    }
+                }
+            })
+        };
+
+        // Each block moves TRANSFERS single-unit transfers from alice to bob
+        for height in 1..=40 {
+            let txs: Vec<_> = (1..=TRANSFERS)
+                .map(|i| transfer("alice", "bob", 1, (height - 1) * TRANSFERS + i))
+                .collect();
+            if height % 2 == 0 {
+                state.apply_block(&block(height, txs)).unwrap();
+            } else {
+                assert!(state.apply_transactions_at(&txs, height).iter().all(|result| result.is_ok()));
+            }
+        }
+        done.store(true, Ordering::Release);
+        assert!(reader.join().unwrap() > 0);
+        assert_eq!(state.get_balance("bob"), 40 * TRANSFERS);
+        assert_eq!(state.get_chain_state().height, 40);
+
+        // Failing transactions are skipped, the rest still land together
+        let results = state.apply_transactions_at(&[
+            transfer("alice", "bob", 1, 40 * TRANSFERS + 1),
+            transfer("alice", "bob", 1, 1),
+            transfer("alice", "bob", 1, 40 * TRANSFERS + 2),
+        ], 41);
+        assert_eq!(results.iter().map(|result| result.is_ok()).collect::<Vec<_>>(), vec![true, false, true]);
+        assert_eq!(state.get_balance("bob"), 40 * TRANSFERS + 2);
+        assert_eq!(state.get_account_at("bob", 41).unwrap().map(|acc| acc.balance), Some(40 * TRANSFERS + 2));
+    }
+
+    #[test]
+    fn test_failed_block_applies_no_changes() {
+        let state = StateManager::new();
+        state.update_account("alice".to_string(), Account::with_balance("alice".to_string(), 1_000));
+        state.update_account("bob".to_string(), Account::with_balance("bob".to_string(), 10));
+        let root_before = state.calculate_state_root().unwrap();
+
+        // Third transfer replays alice's nonce and fails after two were staged
+        let failing = block(1, vec![
+            transfer("alice", "bob", 100, 1),
+            transfer("bob", "carol", 50, 1),
+            transfer("alice", "carol", 10, 1),
+        ]);
+        assert!(state.apply_block(&failing).is_err());
+
+        assert_eq!(state.get_balance("alice"), 1_000);
+        assert_eq!(state.get_balance("bob"), 10);
+        assert!(state.get_account("carol").is_none());
+        assert_eq!(state.get_account("alice").unwrap().nonce, 0);
+        assert_eq!(state.get_chain_state().height, 0);
+        assert_eq!(state.calculate_state_root().unwrap(), root_before);
+
+        // Staged transactions see each other's changes within the batch
+        state.apply_block(&block(1, vec![transfer("alice", "bob", 100, 1), transfer("bob", "carol", 50, 1)])).unwrap();
+        assert_eq!(state.get_balance("bob"), 60);
+        assert_eq!(state.get_balance("carol"), 50);
+    }
+
+    #[test]
+    fn test_transfer_within_gas_limit_pays_gas_used() {
+        let state = StateManager::new();
+        state.update_account("alice".to_string(), Account::with_balance("alice".to_string(), 10_000_000_000));
+
+        // Limit above the transfer cost: only the gas actually used is charged
+        let tx = priced_transfer("alice", "bob", 1_000, 1, 100, gas_limits::TRANSFER * 3);
+        let receipt = state.apply_transaction_at(&tx, 1).unwrap();
+        assert_eq!(receipt.status, TxStatus::Success);
+        assert_eq!(receipt.gas_used, gas_limits::TRANSFER);
+        assert_eq!(receipt.fee, gas_limits::TRANSFER * 100);
+        assert_eq!(state.get_balance("alice"), 10_000_000_000 - 1_000 - receipt.fee);
+        assert_eq!(state.get_balance("bob"), 1_000);
+        assert_eq!(state.get_receipt(&tx.hash), Some(receipt));
+    }
+
+    #[test]
+    fn test_transfer_over_gas_limit_fails_with_receipt() {
+        let state = StateManager::new();
+        state.update_account("alice".to_string(), Account::with_balance("alice".to_string(), 10_000_000_000));
+
+        let tx = priced_transfer("alice", "bob", 1_000, 1, 100, gas_limits::TRANSFER - 1);
+        let receipt = state.apply_transaction_at(&tx, 1).unwrap();
+        assert!(matches!(receipt.status, TxStatus::Failed(ref reason) if reason.contains("Out of gas")));
+        assert_eq!(receipt.gas_used, gas_limits::TRANSFER - 1);
+        assert_eq!(receipt.fee, (gas_limits::TRANSFER - 1) * 100);
+
+        // The sender pays the whole limit and the nonce is spent, but no value moves
+        let alice = state.get_account("alice").unwrap();
+        assert_eq!(alice.balance, 10_000_000_000 - receipt.fee);
+        assert_eq!(alice.nonce, 1);
+        assert!(state.get_account("bob").is_none());
+        assert_eq!(state.get_receipt(&tx.hash), Some(receipt));
+
+        // Inside a block the failed transaction does not abort the ones after it
+        let out_of_gas = priced_transfer("alice", "carol", 1, 2, 0, 0);
+        state.apply_block(&block(2, vec![out_of_gas.clone(), transfer("alice", "bob", 500, 3)])).unwrap();
+        assert!(!state.get_receipt(&out_of_gas.hash).unwrap().is_success());
+        assert_eq!(state.get_balance("bob"), 500);
+        assert!(state.get_account("carol").is_none());
+    }
+
+    #[test]
+    fn test_overflowing_amounts_rejected_without_state_change() {
+        let state = StateManager::new();
+        state.update_account("alice".to_string(), Account::with_balance("alice".to_string(), 10_000_000_000));
+        state.update_account("bob".to_string(), Account::with_balance("bob".to_string(), u64::MAX - 10));
+        let overflowed = |result: StateResult<ExecutionReceipt>| matches!(result, Err(StateError::ArithmeticOverflow(_)));
+
+        // gas_used * gas_price wraps with a crafted gas price
+        let huge_fee = priced_transfer("alice", "carol", 1_000, 1, u64::MAX / 2, u64::MAX);
+        assert!(huge_fee.max_cost().is_err());
+        assert!(overflowed(state.apply_transaction_at(&huge_fee, 1)));
+
+        // Out of gas path: gas_limit * gas_price wraps
+        let huge_limit_fee = priced_transfer("alice", "carol", 1_000, 1, u64::MAX / 2, gas_limits::TRANSFER - 1);
+        assert!(overflowed(state.apply_transaction_at(&huge_limit_fee, 1)));
+
+        // Crediting the recipient would wrap its balance
+        assert!(overflowed(state.apply_transaction_at(&transfer("alice", "bob", 1_000, 1), 1)));
+
+        // Batch amounts summing past u64::MAX
+        let batch = Transaction::new(
+            "alice".to_string(), None, 0, 1, 0, gas_limits::TRANSFER * 2, 1_700_000_000, None,
+            TransactionType::BatchTransfers {
+                transfers: vec![
+                    crate::transaction::BatchTransferData { to_address: "carol".to_string(), amount: u64::MAX, memo: None },
+                    crate::transaction::BatchTransferData { to_address: "dave".to_string(), amount: 2, memo: None },
+                ],
+                batch_id: "batch_overflow".to_string(),
+            },
+            None,
+        );
+        assert!(overflowed(state.apply_transaction_at(&batch, 1)));
+
+        // A reward that would wrap the recipient rejects the whole block
+        let reward = Transaction::new(
+            "system_emission".to_string(), Some("bob".to_string()), 1_000, 0, 0, 0, 1_700_000_000, None,
+            TransactionType::RewardDistribution, None,
+        );
+        assert!(matches!(
+            state.apply_block(&block(1, vec![transfer("alice", "carol", 1_000, 1), reward])),
+            Err(StateError::ArithmeticOverflow(_))
+        ));
+
+        // Nothing was applied: balances and nonces are untouched
+        let alice = state.get_account("alice").unwrap();
+        assert_eq!((alice.balance, alice.nonce), (10_000_000_000, 0));
+        assert_eq!(state.get_balance("bob"), u64::MAX - 10);
+        assert!(state.get_account("carol").is_none());
+        assert!(state.get_account("dave").is_none());
+
+        // A legitimate transaction still goes through afterwards
+        state.apply_transaction_at(&transfer("alice", "carol", 1_000, 1), 1).unwrap();
+        assert_eq!(state.get_balance("carol"), 1_000);
+    }
+}
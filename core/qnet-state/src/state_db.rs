@@ -4,6 +4,46 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use crate::{Account, Block, Transaction, StateError, StateResult};
+use crate::errors::{checked_add, checked_mul};
+
+/// Account writes staged for a single atomic commit
+///
+/// Nothing in a batch is visible until it is committed; dropping it discards the writes.
+#[derive(Debug, Default, Clone)]
+pub struct WriteBatch {
+    accounts: HashMap<String, Account>,
+}
+
+impl WriteBatch {
+    /// Create empty batch
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Stage an account write (replaces an earlier write to the same address)
+    pub fn put_account(&mut self, account: Account) {
+        self.accounts.insert(account.address.clone(), account);
+    }
+    
+    /// Staged value for an address, if any
+    pub fn get_account(&self, address: &str) -> Option<&Account> {
+        self.accounts.get(address)
+    }
+    
+    /// Number of staged accounts
+    pub fn len(&self) -> usize {
+        self.accounts.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+    
+    /// Staged accounts, consuming the batch
+    pub fn into_accounts(self) -> impl Iterator<Item = (String, Account)> {
+        self.accounts.into_iter()
+    }
+}
 
 /// State database for blockchain
 pub struct StateDB {
@@ -41,6 +81,18 @@ impl StateDB {
         Ok(())
     }
     
+    /// Start staging account writes for one atomic commit
+    pub fn begin_batch(&self) -> WriteBatch {
+        WriteBatch::new()
+    }
+    
+    /// Apply all staged writes at once: readers see either none or all of them
+    pub async fn commit_batch(&self, batch: WriteBatch) -> StateResult<()> {
+        let mut accounts = self.accounts.write().await;
+        accounts.extend(batch.into_accounts());
+        Ok(())
+    }
+    
     /// Get block by height
     pub async fn get_block(&self, height: u64) -> StateResult<Option<Block>> {
         let blocks = self.blocks.read().await;
@@ -88,9 +140,9 @@ impl StateDB {
                 )));
             }
             
-            // Calculate total cost including gas
-            let gas_cost = tx.gas_price * tx.gas_limit;
-            let total_cost = tx.amount + gas_cost;
+            // Calculate total cost including gas (a crafted gas_limit must not wrap)
+            let gas_cost = checked_mul(tx.gas_price, tx.gas_limit, "gas cost")?;
+            let total_cost = checked_add(tx.amount, gas_cost, "total cost")?;
             
             if sender.balance < total_cost {
                 return Err(StateError::InsufficientBalance {
@@ -99,7 +151,15 @@ impl StateDB {
                 });
             }
             
+            // Reject a recipient credit overflow before any balance changes
+            if to != &tx.from {
+                let recipient_balance = accounts.get(to).map(|acc| acc.balance).unwrap_or(0);
+                checked_add(recipient_balance, tx.amount, "recipient balance")?;
+            }
+            
             // Execute transaction
+            let sender = accounts.get_mut(&tx.from)
+                .ok_or_else(|| StateError::AccountNotFound(tx.from.clone()))?;
             sender.balance -= total_cost;
             sender.nonce += 1;
             
@@ -123,7 +183,7 @@ impl StateDB {
                 }
             });
             
-            recipient.balance += tx.amount;
+            recipient.balance += tx.amount; // Overflow checked above
             // Update recipient activity
             recipient.touch(timestamp);
             
@@ -270,4 +330,29 @@ impl StateDB {
             state_root: Arc::new(RwLock::new(String::new())), // Will be computed properly
         })
     }
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_batch_visible_only_after_commit() {
+        let db = StateDB::new("", None).await.unwrap();
+        let mut batch = db.begin_batch();
+        batch.put_account(Account::with_balance("alice".to_string(), 500));
+        batch.put_account(Account::with_balance("bob".to_string(), 20));
+        assert_eq!(batch.len(), 2);
+
+        // Dropped batches leave no trace
+        let mut discarded = db.begin_batch();
+        discarded.put_account(Account::with_balance("carol".to_string(), 7));
+        drop(discarded);
+        assert!(db.get_account("alice").await.unwrap().is_none());
+        assert!(db.get_account("carol").await.unwrap().is_none());
+
+        db.commit_batch(batch).await.unwrap();
+        assert_eq!(db.get_balance("alice").await.unwrap(), 500);
+        assert_eq!(db.get_balance("bob").await.unwrap(), 20);
+    }
+}
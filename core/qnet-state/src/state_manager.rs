@@ -1,25 +1,78 @@
 use std::collections::HashMap;
-use crate::{Account, Transaction, StateResult, StateError};
+use crate::{Account, Transaction, StateResult, StateError, StateBackend, Block};
+use crate::backend::MemoryBackend;
 use sha3::{Sha3_256, Digest};
 
 /// StateManager for managing blockchain state
-pub struct StateManager {
+///
+/// Accounts are worked on in memory and written to the backend on `commit`.
+pub struct StateManager<B: StateBackend = MemoryBackend> {
     accounts: HashMap<String, Account>,
+    backend: B,
 }
 
 impl StateManager {
-    /// Create new StateManager
+    /// Create new StateManager with an in-memory backend
     pub fn new() -> Self {
+        Self::with_backend(MemoryBackend::new())
+    }
+}
+
+impl<B: StateBackend> StateManager<B> {
+    /// Create new StateManager on top of the given backend
+    pub fn with_backend(backend: B) -> Self {
         Self {
             accounts: HashMap::new(),
+            backend,
         }
     }
     
+    /// Access the underlying backend
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+    
+    /// Mutable access to the underlying backend
+    pub fn backend_mut(&mut self) -> &mut B {
+        &mut self.backend
+    }
+    
     /// Get account by address
     pub fn get_account(&self, address: &str) -> Option<&Account> {
         self.accounts.get(address)
     }
     
+    /// Get account, loading it from the backend if it is not cached yet
+    pub fn load_account(&mut self, address: &str) -> StateResult<Option<&Account>> {
+        if !self.accounts.contains_key(address) {
+            match self.backend.get_account(address)? {
+                Some(account) => {
+                    self.accounts.insert(address.to_string(), account);
+                }
+                None => return Ok(None),
+            }
+        }
+        Ok(self.accounts.get(address))
+    }
+    
+    /// Insert or replace an account in the working state
+    pub fn set_account(&mut self, account: Account) {
+        self.accounts.insert(account.address.clone(), account);
+    }
+    
+    /// Write all working accounts to the backend
+    pub fn commit(&mut self) -> StateResult<()> {
+        for (address, account) in &self.accounts {
+            self.backend.store_account(address, account)?;
+        }
+        Ok(())
+    }
+    
+    /// Store block in the backend
+    pub fn store_block(&mut self, block: &Block) -> StateResult<()> {
+        self.backend.store_block(block)
+    }
+    
     /// Get account balance
     pub fn get_balance(&self, address: &str) -> u64 {
         self.accounts.get(address).map(|a| a.balance).unwrap_or(0)
@@ -54,7 +107,7 @@ impl StateManager {
     
     /// Apply a transaction to the state
     pub fn apply_transaction(&mut self, tx: &Transaction) -> StateResult<()> {
-        // Delegate to transaction's apply_to_state method
-        tx.apply_to_state(&mut self.accounts)
+        // Delegate to transaction's execute method (enforces the gas limit)
+        tx.execute(&mut self.accounts).map(|_| ())
     }
 } 
\ No newline at end of file
@@ -2,7 +2,7 @@
 
 use serde::{Deserialize, Serialize};
 use blake3::Hasher;
-use crate::errors::StateResult;
+use crate::errors::{StateResult, checked_add, checked_mul};
 use crate::StateError;
 use std::collections::HashMap;
 use crate::Account;
@@ -10,6 +10,7 @@ use std::collections::HashSet;
 use crate::account::{NodeType, ActivationPhase};
 use std::sync::{Arc, RwLock};
 use once_cell::sync::Lazy;
+use qnet_core::crypto::SignatureEnvelope;
 
 /// QNet native transaction fee units (OPTIMIZED for mobile)
 pub const QNC_DECIMALS: u8 = 9; // 1 QNC = 10^9 smallest units (nanoQNC)
@@ -88,6 +89,101 @@ pub mod gas_limits {
 /// Transaction hash type
 pub type TxHash = String;
 
+/// Version tag leading the canonical transaction encoding
+pub const CANONICAL_TX_ENCODING_VERSION: u8 = 1;
+
+/// Version tag for transactions bound to a chain (`chain_id` appended)
+pub const CHAIN_BOUND_TX_ENCODING_VERSION: u8 = 2;
+
+/// `chain_id` of transactions not bound to any chain (system and legacy)
+pub const UNBOUND_CHAIN_ID: u64 = 0;
+
+/// Version tag for transactions with an expiry (`chain_id` and `valid_until` appended)
+pub const EXPIRING_TX_ENCODING_VERSION: u8 = 3;
+
+/// Last block a transaction may be included in
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidUntil {
+    /// Last valid block height (inclusive)
+    Height(u64),
+    /// Last valid block timestamp in seconds (inclusive)
+    Timestamp(u64),
+}
+
+impl ValidUntil {
+    /// Whether a block at `height` with `timestamp` may still include the transaction
+    pub fn allows(&self, height: u64, timestamp: u64) -> bool {
+        match *self {
+            Self::Height(last) => height <= last,
+            Self::Timestamp(last) => timestamp <= last,
+        }
+    }
+}
+
+/// Why a raw signed transaction (`Transaction::decode_raw_signed`) was rejected
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum RawTransactionError {
+    #[error("Raw transaction truncated while reading {0}")]
+    Truncated(&'static str),
+    #[error("Unsupported transaction encoding version: {0}")]
+    UnsupportedVersion(u8),
+    #[error("Invalid recipient presence byte: {0}")]
+    InvalidPresenceByte(u8),
+    #[error("Invalid valid_until kind byte: {0}")]
+    InvalidValidUntilKind(u8),
+    #[error("Field {0} is not valid UTF-8")]
+    InvalidUtf8(&'static str),
+    #[error("Raw transaction has no recipient (only transfers can be submitted raw)")]
+    MissingRecipient,
+    #[error("Encoding is not canonical for its fields")]
+    NonCanonical,
+    #[error("{0} unexpected trailing bytes")]
+    TrailingBytes(usize),
+    #[error("Transaction hash mismatch: encoded {encoded}, computed {computed}")]
+    HashMismatch { encoded: String, computed: String },
+    #[error("Invalid Ed25519 public key")]
+    InvalidPublicKey,
+    #[error("Ed25519 signature does not verify against the transaction hash")]
+    InvalidSignature,
+}
+
+/// Cursor over raw transaction bytes
+struct RawReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> RawReader<'a> {
+    fn take(&mut self, len: usize, field: &'static str) -> Result<&'a [u8], RawTransactionError> {
+        let end = self.pos.checked_add(len)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or(RawTransactionError::Truncated(field))?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u8(&mut self, field: &'static str) -> Result<u8, RawTransactionError> {
+        Ok(self.take(1, field)?[0])
+    }
+
+    fn u64(&mut self, field: &'static str) -> Result<u64, RawTransactionError> {
+        let bytes = self.take(8, field)?;
+        Ok(u64::from_le_bytes(bytes.try_into().expect("8 bytes")))
+    }
+
+    fn prefixed(&mut self, field: &'static str) -> Result<&'a [u8], RawTransactionError> {
+        let len = u32::from_le_bytes(self.take(4, field)?.try_into().expect("4 bytes"));
+        self.take(len as usize, field)
+    }
+
+    fn string(&mut self, field: &'static str) -> Result<String, RawTransactionError> {
+        String::from_utf8(self.prefixed(field)?.to_vec())
+            .map_err(|_| RawTransactionError::InvalidUtf8(field))
+    }
+}
+
 /// Transaction types
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum TransactionType {
@@ -234,6 +330,16 @@ pub struct Transaction {
     
     /// Call data
     pub data: Option<String>,
+    
+    /// Chain the transaction was signed for (replay protection)
+    /// `UNBOUND_CHAIN_ID` for system transactions and ones created before chain binding
+    #[serde(default)]
+    pub chain_id: u64,
+    
+    /// Expiry: the transaction cannot be included after this height or timestamp
+    /// `None` (system transactions and ones created before expiry) never expires
+    #[serde(default)]
+    pub valid_until: Option<ValidUntil>,
 }
 
 /// Transaction receipt (simplified)
@@ -250,6 +356,23 @@ pub enum TxStatus {
     Reverted(String),
 }
 
+/// Outcome of executing one transaction against state
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ExecutionReceipt {
+    pub tx_hash: TxHash,
+    pub status: TxStatus,
+    /// Gas charged: the execution cost, or the whole `gas_limit` when it ran out
+    pub gas_used: u64,
+    /// `gas_used * gas_price` deducted from the sender, routed to Pool #2
+    pub fee: u64,
+}
+
+impl ExecutionReceipt {
+    pub fn is_success(&self) -> bool {
+        self.status == TxStatus::Success
+    }
+}
+
 /// Transaction finalization status for microblock architecture
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum FinalizationStatus {
@@ -318,32 +441,323 @@ impl Transaction {
             public_key: None, // Optional: Set by client for Ed25519 verification
             tx_type,
             data,
+            chain_id: UNBOUND_CHAIN_ID,
+            valid_until: None,
         };
         tx.hash = tx.calculate_hash();
         tx
     }
     
-    /// Calculate transaction hash as hex string
-    pub fn calculate_hash(&self) -> TxHash {
-        let mut hasher = Hasher::new();
+    /// Bind to `chain_id` so the transaction cannot be replayed on another network
+    pub fn with_chain_id(mut self, chain_id: u64) -> Self {
+        self.chain_id = chain_id;
+        self.hash = self.calculate_hash();
+        self
+    }
+    
+    /// Expire after `valid_until`, so a stale signed transaction cannot be mined later
+    pub fn with_valid_until(mut self, valid_until: ValidUntil) -> Self {
+        self.valid_until = Some(valid_until);
+        self.hash = self.calculate_hash();
+        self
+    }
+    
+    /// Whether a block at `height` with `timestamp` may include this transaction
+    pub fn is_valid_at(&self, height: u64, timestamp: u64) -> bool {
+        self.valid_until.map_or(true, |valid_until| valid_until.allows(height, timestamp))
+    }
+    
+    /// Canonical byte encoding hashed by `calculate_hash`
+    ///
+    /// Built by hand, independent of serde, so every node derives identical bytes:
+    /// 1. `CANONICAL_TX_ENCODING_VERSION` (1 byte)
+    /// 2. `from`: u32 LE byte length, then UTF-8 bytes
+    /// 3. `to`: presence byte (0 = None, 1 = Some), then a length-prefixed string if present
+    /// 4. `amount`, `nonce`, `gas_price`, `gas_limit`, `timestamp`: u64 LE each, in that order
+    /// 5. `chain_id`: u64 LE, only when bound to a chain or expiring
+    /// 6. `valid_until`: kind byte (0 = height, 1 = timestamp), then u64 LE, only when expiring
+    ///
+    /// Unbound transactions use `CANONICAL_TX_ENCODING_VERSION` and omit steps 5-6, so their
+    /// hashes are unchanged; bound ones use `CHAIN_BOUND_TX_ENCODING_VERSION` and omit step 6;
+    /// expiring ones use `EXPIRING_TX_ENCODING_VERSION`.
+    /// `hash`, `signature` and `public_key` are excluded - the signature covers the hash.
+    /// Any change here changes every transaction hash and must bump the version.
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(1 + 4 + self.from.len() + 1 + 4 + 66 + 7 * 8 + 1);
+        let bound = self.chain_id != UNBOUND_CHAIN_ID || self.valid_until.is_some();
+        out.push(match (self.valid_until, bound) {
+            (Some(_), _) => EXPIRING_TX_ENCODING_VERSION,
+            (None, true) => CHAIN_BOUND_TX_ENCODING_VERSION,
+            (None, false) => CANONICAL_TX_ENCODING_VERSION,
+        });
+        encode_canonical_str(&mut out, &self.from);
+        match &self.to {
+            Some(to) => {
+                out.push(1);
+                encode_canonical_str(&mut out, to);
+            }
+            None => out.push(0),
+        }
+        for value in [self.amount, self.nonce, self.gas_price, self.gas_limit, self.timestamp] {
+            out.extend_from_slice(&value.to_le_bytes());
+        }
+        if bound {
+            out.extend_from_slice(&self.chain_id.to_le_bytes());
+        }
+        match self.valid_until {
+            Some(ValidUntil::Height(height)) => {
+                out.push(0);
+                out.extend_from_slice(&height.to_le_bytes());
+            }
+            Some(ValidUntil::Timestamp(timestamp)) => {
+                out.push(1);
+                out.extend_from_slice(&timestamp.to_le_bytes());
+            }
+            None => {}
+        }
+        out
+    }
+    
+    /// Raw signed encoding accepted by `POST /api/v1/transactions/raw`
+    ///
+    /// `canonical_bytes`, then the 32-byte hash, then the Ed25519 signature and
+    /// public key, each as a u32 LE byte length followed by the raw bytes. The
+    /// signature is over the 32-byte hash. Returns `None` if the hash, signature
+    /// or public key is missing or not hex.
+    pub fn raw_signed_bytes(&self) -> Option<Vec<u8>> {
+        let hash = hex::decode(&self.hash).ok().filter(|h| h.len() == 32)?;
+        let signature = hex::decode(self.signature.as_deref()?).ok()?;
+        let public_key = hex::decode(self.public_key.as_deref()?).ok()?;
+        let mut out = self.canonical_bytes();
+        out.extend_from_slice(&hash);
+        for field in [&signature, &public_key] {
+            out.extend_from_slice(&(field.len() as u32).to_le_bytes());
+            out.extend_from_slice(field);
+        }
+        Some(out)
+    }
+    
+    /// Decode a `raw_signed_bytes` transfer, checking its hash and Ed25519 signature
+    pub fn decode_raw_signed(bytes: &[u8]) -> Result<Self, RawTransactionError> {
+        use ed25519_dalek::{Signature, Verifier, VerifyingKey};
         
-        // Hash all fields except hash and signature
-        hasher.update(self.from.as_bytes());
-        hasher.update(self.to.as_ref().map(|s| s.as_bytes()).unwrap_or_default());
-        hasher.update(&self.amount.to_le_bytes());
-        hasher.update(&self.nonce.to_le_bytes());
-        hasher.update(&self.gas_price.to_le_bytes());
-        hasher.update(&self.gas_limit.to_le_bytes());
-        hasher.update(&self.timestamp.to_le_bytes());
+        let mut reader = RawReader { bytes, pos: 0 };
+        let version = reader.u8("version")?;
+        if !matches!(version, CANONICAL_TX_ENCODING_VERSION | CHAIN_BOUND_TX_ENCODING_VERSION | EXPIRING_TX_ENCODING_VERSION) {
+            return Err(RawTransactionError::UnsupportedVersion(version));
+        }
+        let from = reader.string("from")?;
+        let to = match reader.u8("to")? {
+            0 => None,
+            1 => Some(reader.string("to")?),
+            other => return Err(RawTransactionError::InvalidPresenceByte(other)),
+        };
+        let amount = reader.u64("amount")?;
+        let nonce = reader.u64("nonce")?;
+        let gas_price = reader.u64("gas_price")?;
+        let gas_limit = reader.u64("gas_limit")?;
+        let timestamp = reader.u64("timestamp")?;
+        let chain_id = if version == CANONICAL_TX_ENCODING_VERSION {
+            UNBOUND_CHAIN_ID
+        } else {
+            reader.u64("chain_id")?
+        };
+        let valid_until = if version == EXPIRING_TX_ENCODING_VERSION {
+            match reader.u8("valid_until")? {
+                0 => Some(ValidUntil::Height(reader.u64("valid_until")?)),
+                1 => Some(ValidUntil::Timestamp(reader.u64("valid_until")?)),
+                other => return Err(RawTransactionError::InvalidValidUntilKind(other)),
+            }
+        } else {
+            None
+        };
+        let canonical_len = reader.pos;
+        let encoded_hash = reader.take(32, "hash")?;
+        let signature = reader.prefixed("signature")?;
+        let public_key = reader.prefixed("public_key")?;
+        if reader.pos != bytes.len() {
+            return Err(RawTransactionError::TrailingBytes(bytes.len() - reader.pos));
+        }
+        
+        let to = to.ok_or(RawTransactionError::MissingRecipient)?;
+        let mut tx = Transaction::new(
+            from.clone(),
+            Some(to.clone()),
+            amount,
+            nonce,
+            gas_price,
+            gas_limit,
+            timestamp,
+            Some(hex::encode(signature)),
+            TransactionType::Transfer { from, to, amount },
+            None,
+        ).with_chain_id(chain_id);
+        if let Some(valid_until) = valid_until {
+            tx = tx.with_valid_until(valid_until);
+        }
+        tx.public_key = Some(hex::encode(public_key));
         
+        // e.g. version 2 with an unbound chain_id would hash differently once re-encoded
+        if tx.canonical_bytes() != bytes[..canonical_len] {
+            return Err(RawTransactionError::NonCanonical);
+        }
+        let encoded = hex::encode(encoded_hash);
+        if encoded != tx.hash {
+            return Err(RawTransactionError::HashMismatch { encoded, computed: tx.hash });
+        }
+        
+        let key_bytes: [u8; 32] = public_key.try_into()
+            .map_err(|_| RawTransactionError::InvalidPublicKey)?;
+        let verifying_key = VerifyingKey::from_bytes(&key_bytes)
+            .map_err(|_| RawTransactionError::InvalidPublicKey)?;
+        let signature = Signature::from_slice(signature)
+            .map_err(|_| RawTransactionError::InvalidSignature)?;
+        verifying_key.verify(encoded_hash, &signature)
+            .map_err(|_| RawTransactionError::InvalidSignature)?;
+        
+        Ok(tx)
+    }
+    
+    /// Calculate transaction hash as hex string (BLAKE3 of `canonical_bytes`)
+    pub fn calculate_hash(&self) -> TxHash {
+        let mut hasher = Hasher::new();
+        hasher.update(&self.canonical_bytes());
         hex::encode(hasher.finalize().as_bytes())
     }
     
+    /// System transactions (rewards, account creation, ping records) carry no user
+    /// nonce and are placed at the front of a block
+    pub fn is_system(&self) -> bool {
+        matches!(
+            self.tx_type,
+            TransactionType::RewardDistribution
+                | TransactionType::CreateAccount { .. }
+                | TransactionType::PingAttestation { .. }
+                | TransactionType::PingCommitmentWithSampling { .. }
+        ) || self.from.starts_with("system_")
+    }
+
+    /// Canonical cancellation: a zero-value transfer to self at the nonce being
+    /// cancelled. It only spends the nonce and the fee, and replaces the pending
+    /// transaction at that nonce when it pays a higher gas price.
+    pub fn is_cancellation(&self) -> bool {
+        match &self.tx_type {
+            TransactionType::Transfer { from, to, amount } => {
+                *amount == 0 && self.amount == 0 && from == to && *from == self.from
+                    && self.to.as_deref() == Some(from.as_str())
+            }
+            _ => false,
+        }
+    }
+
+    /// Canonical order of transactions inside a block: system transactions first,
+    /// then by sender, nonce and highest gas price, with the hash as final tie-break
+    pub fn canonical_cmp(&self, other: &Self) -> std::cmp::Ordering {
+        other.is_system().cmp(&self.is_system())
+            .then_with(|| self.from.cmp(&other.from))
+            .then_with(|| self.nonce.cmp(&other.nonce))
+            .then_with(|| other.gas_price.cmp(&self.gas_price))
+            .then_with(|| self.hash.cmp(&other.hash))
+    }
+
     /// Get transaction value
     pub fn value(&self) -> u64 {
         self.amount
     }
     
+    /// Gas consumed by executing this transaction (`gas_limits` per operation)
+    /// Batches pay once per item; system and ping transactions are free
+    pub fn gas_used(&self) -> u64 {
+        match &self.tx_type {
+            TransactionType::Transfer { .. } => gas_limits::TRANSFER,
+            TransactionType::NodeActivation { .. } => gas_limits::NODE_ACTIVATION,
+            TransactionType::ContractDeploy => gas_limits::CONTRACT_DEPLOY,
+            TransactionType::ContractCall => gas_limits::CONTRACT_CALL,
+            TransactionType::BatchRewardClaims { node_ids, .. } => {
+                gas_limits::REWARD_CLAIM.saturating_mul(node_ids.len() as u64)
+            }
+            TransactionType::BatchNodeActivations { activation_data, .. } => {
+                gas_limits::NODE_ACTIVATION.saturating_mul(activation_data.len() as u64)
+            }
+            TransactionType::BatchTransfers { transfers, .. } => {
+                gas_limits::TRANSFER.saturating_mul(transfers.len() as u64)
+            }
+            TransactionType::RewardDistribution
+            | TransactionType::CreateAccount { .. }
+            | TransactionType::PingAttestation { .. }
+            | TransactionType::PingCommitmentWithSampling { .. } => gas_limits::PING,
+        }
+    }
+    
+    /// Fee charged on success: `gas_used * gas_price`
+    pub fn fee(&self) -> Result<u64, StateError> {
+        checked_mul(self.gas_used(), self.gas_price, "fee")
+    }
+    
+    /// Most the sender can be charged: `amount + gas_limit * gas_price`
+    pub fn max_cost(&self) -> Result<u64, StateError> {
+        let max_fee = checked_mul(self.gas_limit, self.gas_price, "max fee")?;
+        checked_add(self.amount, max_fee, "max cost")
+    }
+    
+    /// Apply to state, enforcing `gas_used <= gas_limit`
+    ///
+    /// A transaction that exceeds its limit still lands: the sender pays the whole
+    /// `gas_limit * gas_price` and its nonce advances, but nothing else changes and
+    /// the receipt is `Failed`. Errors mean the transaction cannot be applied at all.
+    pub fn execute(&self, accounts: &mut HashMap<String, Account>) -> Result<ExecutionReceipt, StateError> {
+        let gas_used = self.gas_used();
+        if gas_used > self.gas_limit {
+            let sender = accounts.get_mut(&self.from)
+                .ok_or_else(|| StateError::AccountNotFound(self.from.clone()))?;
+            
+            if self.nonce != sender.nonce + 1 {
+                return Err(StateError::InvalidTransaction(format!(
+                    "Invalid nonce: expected {}, got {} (replay attack prevention)",
+                    sender.nonce + 1, self.nonce
+                )));
+            }
+            
+            let fee = checked_mul(self.gas_limit, self.gas_price, "fee")?;
+            if sender.balance < fee {
+                return Err(StateError::InsufficientBalance {
+                    have: sender.balance,
+                    need: fee,
+                });
+            }
+            
+            sender.balance -= fee;
+            sender.nonce += 1;
+            
+            return Ok(ExecutionReceipt {
+                tx_hash: self.hash.clone(),
+                status: TxStatus::Failed(format!(
+                    "Out of gas: needs {}, gas limit {}", gas_used, self.gas_limit
+                )),
+                gas_used: self.gas_limit,
+                fee,
+            });
+        }
+        
+        self.apply_to_state(accounts)?;
+        Ok(ExecutionReceipt {
+            tx_hash: self.hash.clone(),
+            status: TxStatus::Success,
+            gas_used,
+            fee: self.fee()?,
+        })
+    }
+    
+    /// Store signature as a self-describing envelope (algorithm id + bytes)
+    pub fn set_signature_envelope(&mut self, envelope: &SignatureEnvelope) {
+        self.signature = Some(envelope.encode());
+    }
+    
+    /// Decode signature envelope; legacy un-tagged hex is treated as Dilithium3
+    pub fn signature_envelope(&self) -> Option<SignatureEnvelope> {
+        self.signature.as_deref().and_then(|sig| SignatureEnvelope::parse(sig).ok())
+    }
+    
     /// Check if transaction is valid
     pub fn validate(&self) -> Result<(), String> {
         // Basic validation
@@ -358,11 +772,14 @@ impl Transaction {
         // Type-specific validation
         match &self.tx_type {
             TransactionType::Transfer { from, to, amount } => {
-                if from == to {
-                    return Err("Cannot transfer to self".to_string());
-                }
-                if *amount == 0 {
-                    return Err("Transfer amount must be greater than 0".to_string());
+                // Cancellations are the one allowed zero-value self-transfer
+                if !self.is_cancellation() {
+                    if from == to {
+                        return Err("Cannot transfer to self".to_string());
+                    }
+                    if *amount == 0 {
+                        return Err("Transfer amount must be greater than 0".to_string());
+                    }
                 }
                 if self.to.as_ref().map(|s| s.is_empty()).unwrap_or(true) {
                     return Err("Empty recipient address".to_string());
@@ -526,7 +943,7 @@ impl Transaction {
                 }
                 
                 // Check balance
-                let total_amount = amount + self.gas_price * self.gas_limit;
+                let total_amount = checked_add(*amount, self.fee()?, "transfer cost")?;
                 if sender.balance < total_amount {
                     return Err(StateError::InsufficientBalance {
                         have: sender.balance,
@@ -538,10 +955,10 @@ impl Transaction {
                 sender.balance -= total_amount;
                 sender.nonce += 1;
                 
-                // Add to receiver
+                // Add to receiver (an error discards the staged accounts, so nothing half-applies)
                 let receiver = accounts.entry(to.clone())
                     .or_insert_with(|| Account::new(to.clone()));
-                receiver.balance += amount;
+                receiver.balance = checked_add(receiver.balance, *amount, "receiver balance")?;
             }
             TransactionType::CreateAccount { address, initial_balance } => {
                 if accounts.contains_key(address) {
@@ -566,8 +983,8 @@ impl Transaction {
                 }
 
                 // Fee calculation
-                let fee = self.gas_price * self.gas_limit;
-                let total_amount = amount + fee;
+                let fee = self.fee()?;
+                let total_amount = checked_add(*amount, fee, "activation cost")?;
 
                 if sender.balance < total_amount {
                     return Err(StateError::InsufficientBalance {
@@ -597,7 +1014,7 @@ impl Transaction {
                 }
                 
                 // Check balance for deployment fee
-                let fee = self.gas_price * self.gas_limit;
+                let fee = self.fee()?;
                 if sender.balance < fee {
                     return Err(StateError::InsufficientBalance {
                         have: sender.balance,
@@ -626,8 +1043,8 @@ impl Transaction {
                 }
                 
                 // Check balance for call fee + value
-                let fee = self.gas_price * self.gas_limit;
-                let total_cost = fee + self.amount;
+                let fee = self.fee()?;
+                let total_cost = checked_add(fee, self.amount, "call cost")?;
                 
                 if sender.balance < total_cost {
                     return Err(StateError::InsufficientBalance {
@@ -658,7 +1075,7 @@ impl Transaction {
                 if let Some(to) = &self.to {
                     let recipient = accounts.entry(to.clone())
                         .or_insert_with(|| Account::new(to.clone()));
-                    recipient.balance += self.amount;
+                    recipient.balance = checked_add(recipient.balance, self.amount, "reward recipient balance")?;
                     
                     println!("[EMISSION] 💰 Reward distributed: {} QNC to {} (lazy claim)", 
                              self.amount, to);
@@ -678,7 +1095,7 @@ impl Transaction {
                 }
 
                 // Calculate total fee for batch
-                let total_fee = (self.gas_price * self.gas_limit) * node_ids.len() as u64;
+                let total_fee = self.fee()?;
 
                 if sender.balance < total_fee {
                     return Err(StateError::InsufficientBalance {
@@ -709,9 +1126,10 @@ impl Transaction {
                 }
 
                 // Calculate total activation amount and fees
-                let total_activation_amount: u64 = activation_data.iter().map(|d| d.activation_amount).sum();
-                let total_fee = (self.gas_price * self.gas_limit) * activation_data.len() as u64;
-                let total_cost = total_activation_amount + total_fee;
+                let total_activation_amount = activation_data.iter()
+                    .try_fold(0u64, |sum, d| checked_add(sum, d.activation_amount, "batch activation amount"))?;
+                let total_fee = self.fee()?;
+                let total_cost = checked_add(total_activation_amount, total_fee, "batch activation cost")?;
 
                 if sender.balance < total_cost {
                     return Err(StateError::InsufficientBalance {
@@ -742,9 +1160,10 @@ impl Transaction {
                 }
 
                 // Calculate total transfer amount and fees
-                let total_transfer_amount: u64 = transfers.iter().map(|t| t.amount).sum();
-                let total_fee = (self.gas_price * self.gas_limit) * transfers.len() as u64;
-                let total_cost = total_transfer_amount + total_fee;
+                let total_transfer_amount = transfers.iter()
+                    .try_fold(0u64, |sum, t| checked_add(sum, t.amount, "batch transfer amount"))?;
+                let total_fee = self.fee()?;
+                let total_cost = checked_add(total_transfer_amount, total_fee, "batch transfer cost")?;
 
                 if sender.balance < total_cost {
                     return Err(StateError::InsufficientBalance {
@@ -761,7 +1180,7 @@ impl Transaction {
                 for transfer in transfers {
                     let recipient = accounts.entry(transfer.to_address.clone())
                         .or_insert_with(|| Account::new(transfer.to_address.clone()));
-                    recipient.balance += transfer.amount;
+                    recipient.balance = checked_add(recipient.balance, transfer.amount, "batch recipient balance")?;
                 }
 
                 // Log batch transfer
@@ -1012,16 +1431,16 @@ impl TransactionProcessor {
     }
     
     /// Process transaction with proper fee handling
-    pub fn process_transaction(&mut self, tx: &Transaction, accounts: &mut HashMap<String, Account>) -> Result<(), StateError> {
+    pub fn process_transaction(&mut self, tx: &Transaction, accounts: &mut HashMap<String, Account>) -> Result<ExecutionReceipt, StateError> {
         // Apply transaction logic
-        tx.apply_to_state(accounts)?;
+        let receipt = tx.execute(accounts)?;
         
         // Calculate and process fee for Pool 2 - Phase 1 activations are FREE!
         let fee_amount = match &tx.tx_type {
             TransactionType::NodeActivation { phase: ActivationPhase::Phase1, .. } => {
                 0 // Phase 1 activations are completely FREE - no QNC gas fees!
             },
-            _ => tx.gas_price * tx.gas_limit // Normal fees for other transactions
+            _ => receipt.fee // Normal fees for other transactions
         };
         
         if fee_amount > 0 {
@@ -1029,7 +1448,7 @@ impl TransactionProcessor {
                 if let Err(e) = integration.process_transaction_fee(
                     tx.hash.clone(),
                     tx.amount,
-                    tx.gas_limit,
+                    receipt.gas_used,
                     tx.gas_price,
                 ) {
                     eprintln!("Warning: Failed to process transaction fee: {}", e);
@@ -1037,6 +1456,11 @@ impl TransactionProcessor {
             }
         }
         
+        // Out-of-gas transactions only paid their fee
+        if !receipt.is_success() {
+            return Ok(receipt);
+        }
+        
         // Handle node activation for Pool 3
         if let TransactionType::NodeActivation { node_type, amount, .. } = &tx.tx_type {
             if let Some(ref mut integration) = self.reward_integration {
@@ -1051,7 +1475,7 @@ impl TransactionProcessor {
             }
         }
         
-        Ok(())
+        Ok(receipt)
     }
 }
 
@@ -1212,3 +1636,195 @@ pub fn update_dynamic_gas_pricing(new_pricing: DynamicGasPricing) {
     *DYNAMIC_GAS_PRICING.write().unwrap() = Some(new_pricing);
 }
 
+/// Length-prefixed (u32 LE) UTF-8 string for the canonical encoding
+fn encode_canonical_str(out: &mut Vec<u8>, value: &str) {
+    out.extend_from_slice(&(value.len() as u32).to_le_bytes());
+    out.extend_from_slice(value.as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vector_transaction() -> Transaction {
+        Transaction::new(
+            "alice".to_string(),
+            Some("bob".to_string()),
+            1_000_000_000,
+            7,
+            BASE_FEE_NANO_QNC,
+            gas_limits::TRANSFER,
+            1_704_067_200,
+            None,
+            TransactionType::Transfer { from: "alice".to_string(), to: "bob".to_string(), amount: 1_000_000_000 },
+            None,
+        )
+    }
+
+    #[test]
+    fn test_canonical_encoding_vector() {
+        let tx = vector_transaction();
+
+        let mut expected = vec![CANONICAL_TX_ENCODING_VERSION];
+        expected.extend_from_slice(&[5, 0, 0, 0]);
+        expected.extend_from_slice(b"alice");
+        expected.extend_from_slice(&[1, 3, 0, 0, 0]);
+        expected.extend_from_slice(b"bob");
+        for value in [1_000_000_000u64, 7, 100_000, 10_000, 1_704_067_200] {
+            expected.extend_from_slice(&value.to_le_bytes());
+        }
+        assert_eq!(tx.canonical_bytes(), expected);
+
+        // Changing this hash breaks consensus with existing nodes
+        assert_eq!(tx.hash, "df279bf2daa7515172bfbb4894b66cc0b5c2f95a0577e52b99746f1b27bc7228");
+        assert!(tx.validate().is_ok());
+    }
+
+    #[test]
+    fn test_canonical_encoding_stable_across_decoding() {
+        let mut tx = vector_transaction();
+        tx.signature = Some("sig".to_string());
+        tx.data = Some("memo".to_string());
+
+        let json_decoded: Transaction = serde_json::from_slice(&serde_json::to_vec(&tx).unwrap()).unwrap();
+        let bincode_decoded: Transaction = bincode::deserialize(&bincode::serialize(&tx).unwrap()).unwrap();
+        assert_eq!(json_decoded.canonical_bytes(), tx.canonical_bytes());
+        assert_eq!(bincode_decoded.canonical_bytes(), tx.canonical_bytes());
+        assert_eq!(json_decoded.calculate_hash(), tx.hash);
+
+        // Field boundaries are unambiguous: moving bytes between from/to changes the encoding
+        let mut shifted = tx.clone();
+        shifted.from = "alic".to_string();
+        shifted.to = Some("ebob".to_string());
+        assert_ne!(shifted.canonical_bytes(), tx.canonical_bytes());
+
+        // None and Some("") recipients encode differently
+        let mut no_recipient = tx.clone();
+        no_recipient.to = None;
+        let mut empty_recipient = tx;
+        empty_recipient.to = Some(String::new());
+        assert_ne!(no_recipient.canonical_bytes(), empty_recipient.canonical_bytes());
+    }
+
+    #[test]
+    fn test_chain_id_is_part_of_canonical_encoding() {
+        let unbound = vector_transaction();
+        let testnet = vector_transaction().with_chain_id(1337);
+        let mainnet = vector_transaction().with_chain_id(1);
+
+        let mut expected = unbound.canonical_bytes();
+        expected[0] = CHAIN_BOUND_TX_ENCODING_VERSION;
+        expected.extend_from_slice(&1337u64.to_le_bytes());
+        assert_eq!(testnet.canonical_bytes(), expected);
+
+        // A signature over the testnet hash does not cover the mainnet one
+        assert_ne!(testnet.hash, unbound.hash);
+        assert_ne!(testnet.hash, mainnet.hash);
+        assert_eq!(testnet.hash, testnet.calculate_hash());
+
+        // Older encodings without the field decode as unbound
+        let mut legacy = serde_json::to_value(&unbound).unwrap();
+        legacy.as_object_mut().unwrap().remove("chain_id");
+        let decoded: Transaction = serde_json::from_value(legacy).unwrap();
+        assert_eq!(decoded.chain_id, UNBOUND_CHAIN_ID);
+        assert_eq!(decoded.calculate_hash(), unbound.hash);
+    }
+
+    #[test]
+    fn test_valid_until_is_signed_and_enforced() {
+        let bound = vector_transaction().with_chain_id(1337);
+        let expiring = vector_transaction().with_chain_id(1337).with_valid_until(ValidUntil::Height(100));
+
+        let mut expected = bound.canonical_bytes();
+        expected[0] = EXPIRING_TX_ENCODING_VERSION;
+        expected.push(0);
+        expected.extend_from_slice(&100u64.to_le_bytes());
+        assert_eq!(expiring.canonical_bytes(), expected);
+
+        // Extending the expiry changes the hash, so the signature no longer covers it
+        let extended = vector_transaction().with_chain_id(1337).with_valid_until(ValidUntil::Height(101));
+        let by_time = vector_transaction().with_chain_id(1337).with_valid_until(ValidUntil::Timestamp(100));
+        assert_ne!(expiring.hash, bound.hash);
+        assert_ne!(expiring.hash, extended.hash);
+        assert_ne!(expiring.hash, by_time.hash);
+
+        assert!(expiring.is_valid_at(100, u64::MAX));
+        assert!(!expiring.is_valid_at(101, 0));
+        assert!(by_time.is_valid_at(u64::MAX, 100));
+        assert!(!by_time.is_valid_at(0, 101));
+        assert!(bound.is_valid_at(u64::MAX, u64::MAX));
+
+        // Older encodings without the field never expire
+        let mut legacy = serde_json::to_value(&bound).unwrap();
+        legacy.as_object_mut().unwrap().remove("valid_until");
+        let decoded: Transaction = serde_json::from_value(legacy).unwrap();
+        assert_eq!(decoded.valid_until, None);
+        assert_eq!(decoded.calculate_hash(), bound.hash);
+    }
+
+    fn signed_raw_transaction() -> (Transaction, Vec<u8>) {
+        use ed25519_dalek::{Signer, SigningKey};
+
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        let mut tx = vector_transaction().with_chain_id(1337);
+        let signature = key.sign(&hex::decode(&tx.hash).unwrap());
+        tx.signature = Some(hex::encode(signature.to_bytes()));
+        tx.public_key = Some(hex::encode(key.verifying_key().to_bytes()));
+        let raw = tx.raw_signed_bytes().unwrap();
+        (tx, raw)
+    }
+
+    #[test]
+    fn test_raw_signed_transaction_round_trip() {
+        let (tx, raw) = signed_raw_transaction();
+        assert!(raw.starts_with(&tx.canonical_bytes()));
+
+        let decoded = Transaction::decode_raw_signed(&raw).unwrap();
+        assert_eq!(decoded.hash, tx.hash);
+        assert_eq!(decoded.chain_id, 1337);
+        assert_eq!(decoded.signature, tx.signature);
+        assert_eq!(decoded.public_key, tx.public_key);
+        assert_eq!(decoded.tx_type, tx.tx_type);
+        assert!(decoded.validate().is_ok());
+    }
+
+    #[test]
+    fn test_raw_signed_transaction_rejects_tampering() {
+        let (tx, raw) = signed_raw_transaction();
+        let canonical_len = tx.canonical_bytes().len();
+
+        // A flipped timestamp bit no longer matches the encoded hash
+        let mut tampered = raw.clone();
+        tampered[canonical_len - 9] ^= 1;
+        assert!(matches!(
+            Transaction::decode_raw_signed(&tampered),
+            Err(RawTransactionError::HashMismatch { .. })
+        ));
+
+        // Rewriting the hash as well still fails the signature
+        let mut rehashed = Transaction::decode_raw_signed(&raw).unwrap();
+        rehashed.amount += 1;
+        rehashed.tx_type = TransactionType::Transfer { from: "alice".to_string(), to: "bob".to_string(), amount: rehashed.amount };
+        rehashed.hash = rehashed.calculate_hash();
+        assert_eq!(
+            Transaction::decode_raw_signed(&rehashed.raw_signed_bytes().unwrap()).unwrap_err(),
+            RawTransactionError::InvalidSignature
+        );
+
+        let mut bad_signature = raw.clone();
+        let sig_start = canonical_len + 32 + 4;
+        bad_signature[sig_start] ^= 1;
+        assert_eq!(Transaction::decode_raw_signed(&bad_signature).unwrap_err(), RawTransactionError::InvalidSignature);
+
+        assert_eq!(
+            Transaction::decode_raw_signed(&raw[..raw.len() - 1]).unwrap_err(),
+            RawTransactionError::Truncated("public_key")
+        );
+        let mut trailing = raw.clone();
+        trailing.push(0);
+        assert_eq!(Transaction::decode_raw_signed(&trailing).unwrap_err(), RawTransactionError::TrailingBytes(1));
+        let mut bad_version = raw;
+        bad_version[0] = 9;
+        assert_eq!(Transaction::decode_raw_signed(&bad_version).unwrap_err(), RawTransactionError::UnsupportedVersion(9));
+    }
+}
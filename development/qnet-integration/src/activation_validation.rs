@@ -18,6 +18,133 @@ fn safe_preview(s: &str, len: usize) -> &str {
     }
 }
 
+/// Length of a standard activation code: QNET-XXXXXX-XXXXXX-XXXXXX
+pub const ACTIVATION_CODE_LEN: usize = 25;
+
+/// Length of a Genesis bootstrap code: QNET-BOOT-000X-STRAP
+pub const GENESIS_CODE_LEN: usize = 20;
+
+/// Activation code format errors
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ActivationError {
+    #[error("Empty activation code")]
+    Empty,
+
+    #[error("Invalid activation code length: expected 25 (or 20 for Genesis), got {0}")]
+    InvalidLength(usize),
+
+    #[error("Invalid activation code format. Expected: QNET-XXXXXX-XXXXXX-XXXXXX")]
+    InvalidFormat,
+
+    #[error("Invalid activation code segment {index}: '{segment}'")]
+    InvalidSegment { index: usize, segment: String },
+
+    #[error("Genesis bootstrap code {0} not in whitelist")]
+    UnknownGenesisCode(String),
+}
+
+/// Kind of activation code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ActivationCodeKind {
+    /// Purchased code, XOR-encrypted wallet bound (QNET-XXXXXX-XXXXXX-XXXXXX)
+    Standard,
+    /// One of the 5 whitelisted Genesis codes (QNET-BOOT-000X-STRAP)
+    GenesisBootstrap { bootstrap_id: u8 },
+}
+
+/// Validated activation code
+///
+/// Standard codes carry no checksum: each segment is checked for its
+/// character set here, authenticity is only established by decryption.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ActivationCode {
+    code: String,
+    kind: ActivationCodeKind,
+}
+
+impl ActivationCode {
+    /// Parse and validate an activation code (surrounding whitespace is ignored)
+    pub fn parse(code: &str) -> Result<Self, ActivationError> {
+        let code = code.trim();
+        if code.is_empty() {
+            return Err(ActivationError::Empty);
+        }
+        if code.len() != ACTIVATION_CODE_LEN && code.len() != GENESIS_CODE_LEN {
+            return Err(ActivationError::InvalidLength(code.len()));
+        }
+
+        let parts: Vec<&str> = code.split('-').collect();
+        if parts.len() != 4 || parts[0] != "QNET" {
+            return Err(ActivationError::InvalidFormat);
+        }
+
+        if parts[1] == "BOOT" {
+            if !crate::genesis_constants::is_genesis_bootstrap_code(code) {
+                return Err(ActivationError::UnknownGenesisCode(code.to_string()));
+            }
+            // Whitelisted codes always carry a 4-digit id
+            let bootstrap_id = parts[2].parse::<u8>()
+                .map_err(|_| ActivationError::UnknownGenesisCode(code.to_string()))?;
+            return Ok(Self {
+                code: code.to_string(),
+                kind: ActivationCodeKind::GenesisBootstrap { bootstrap_id },
+            });
+        }
+
+        if code.len() != ACTIVATION_CODE_LEN {
+            return Err(ActivationError::InvalidLength(code.len()));
+        }
+        // Segments are uppercase hex, segment 1 leads with a node type marker (L/F/S/U)
+        for (index, segment) in parts[1..].iter().enumerate() {
+            let valid = segment.len() == 6 && segment.chars().enumerate().all(|(i, c)| {
+                c.is_ascii_digit() || ('A'..='F').contains(&c) || (index == 0 && i == 0 && "LSU".contains(c))
+            });
+            if !valid {
+                return Err(ActivationError::InvalidSegment { index: index + 1, segment: segment.to_string() });
+            }
+        }
+
+        Ok(Self {
+            code: code.to_string(),
+            kind: ActivationCodeKind::Standard,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.code
+    }
+
+    pub fn kind(&self) -> ActivationCodeKind {
+        self.kind
+    }
+
+    pub fn is_genesis_bootstrap(&self) -> bool {
+        matches!(self.kind, ActivationCodeKind::GenesisBootstrap { .. })
+    }
+
+    /// Genesis bootstrap ID in `genesis_constants` form ("001".."005")
+    pub fn genesis_bootstrap_id(&self) -> Option<String> {
+        match self.kind {
+            ActivationCodeKind::GenesisBootstrap { bootstrap_id } => Some(format!("{:03}", bootstrap_id)),
+            ActivationCodeKind::Standard => None,
+        }
+    }
+}
+
+impl std::str::FromStr for ActivationCode {
+    type Err = ActivationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl std::fmt::Display for ActivationCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.code)
+    }
+}
+
 // REMOVED: BlockchainMigrationRecord - migration is just normal node activation!
 
 /// Network statistics for dynamic pricing calculations
@@ -381,9 +508,10 @@ impl BlockchainActivationRegistry {
     /// FIXED: Verify activation code belongs to specific wallet (1 wallet = 1 code)
     pub async fn verify_code_ownership(&self, code: &str, wallet_address: &str) -> Result<bool, IntegrationError> {
         println!("🔍 Verifying code ownership for wallet: {}...", safe_preview(wallet_address, 8));
+        let code = ActivationCode::parse(code)?;
         
         // Extract wallet address from activation code
-        let code_wallet = match self.extract_wallet_from_activation_code(code).await {
+        let code_wallet = match self.extract_wallet_from_activation_code(code.as_str()).await {
             Ok(wallet) => wallet,
             Err(e) => {
                 println!("❌ Failed to extract wallet from code: {}", e);
@@ -1469,10 +1597,11 @@ impl BlockchainActivationRegistry {
             .parse::<u64>()
             .unwrap_or(0);
         
-        // Read activations from deterministic range (aligned to 30-block boundaries)
+        // Read activations from deterministic range (aligned to rotation boundaries)
         // This ensures all nodes see the same data at the same round
-        let round = current_height / 30; // Same round as producer selection
-        let snapshot_height = round * 30; // Snapshot at round boundary
+        let rotation_interval = crate::network_config::get_chain_params().rotation_interval_blocks;
+        let round = current_height / rotation_interval; // Same round as producer selection
+        let snapshot_height = round * rotation_interval; // Snapshot at round boundary
         let recent_blocks = 100; // Query last 100 blocks from snapshot
         let from_height = snapshot_height.saturating_sub(recent_blocks);
         
@@ -1723,6 +1852,8 @@ impl BlockchainActivationRegistry {
             public_key: None, // Not needed for activation transactions
             tx_type: TransactionType::ContractCall, // Use tx_type, not transaction_type
             timestamp: record.activated_at,
+            chain_id: crate::network_config::get_network_config().chain_id,
+            valid_until: None,
         };
         
         // PRODUCTION: Submit to blockchain through GLOBAL mempool
@@ -2755,4 +2886,59 @@ struct QNetActivationTransaction {
     pub qnc_cost: u64,
     pub activation_phase: u8,
     pub timestamp: u64,
-} 
\ No newline at end of file
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_standard_code() {
+        let code = ActivationCode::parse("QNET-F1A2B3-0C9D8E-7F6A5B").unwrap();
+        assert_eq!(code.kind(), ActivationCodeKind::Standard);
+        assert!(!code.is_genesis_bootstrap());
+        assert_eq!(code.genesis_bootstrap_id(), None);
+        assert_eq!(code.to_string(), "QNET-F1A2B3-0C9D8E-7F6A5B");
+
+        // Surrounding whitespace from env reads is ignored
+        let padded: ActivationCode = " QNET-S00000-ABCDEF-123456\n".parse().unwrap();
+        assert_eq!(padded.as_str(), "QNET-S00000-ABCDEF-123456");
+    }
+
+    #[test]
+    fn test_parse_genesis_code() {
+        let code = ActivationCode::parse("QNET-BOOT-0003-STRAP").unwrap();
+        assert_eq!(code.kind(), ActivationCodeKind::GenesisBootstrap { bootstrap_id: 3 });
+        assert!(code.is_genesis_bootstrap());
+        assert_eq!(code.genesis_bootstrap_id().as_deref(), Some("003"));
+        assert!(crate::genesis_constants::get_genesis_wallet_by_id(&code.genesis_bootstrap_id().unwrap()).is_some());
+    }
+
+    #[test]
+    fn test_reject_malformed_codes() {
+        assert_eq!(ActivationCode::parse(""), Err(ActivationError::Empty));
+        assert_eq!(ActivationCode::parse("QNET-F1A2B3-0C9D8E"), Err(ActivationError::InvalidLength(18)));
+        assert_eq!(ActivationCode::parse("QNET-F1A2B3-0C9D8E-7F6A5B0"), Err(ActivationError::InvalidLength(26)));
+        assert_eq!(ActivationCode::parse("QNXT-F1A2B3-0C9D8E-7F6A5B"), Err(ActivationError::InvalidFormat));
+        assert_eq!(ActivationCode::parse("QNET-F1A2B3-0C9D8E_7F6A5B"), Err(ActivationError::InvalidFormat));
+        assert!(matches!(
+            ActivationCode::parse("QNET-F1A2B3-0c9d8e-7F6A5B"),
+            Err(ActivationError::InvalidSegment { index: 2, .. })
+        ));
+        assert!(matches!(
+            ActivationCode::parse("QNET-F1A2B3-0C9D8E-7F6A5Z"),
+            Err(ActivationError::InvalidSegment { index: 3, .. })
+        ));
+        // Node type marker is only allowed at the start of segment 1
+        assert!(matches!(
+            ActivationCode::parse("QNET-F1A2B3-L0C9D8-7F6A5B"),
+            Err(ActivationError::InvalidSegment { index: 2, .. })
+        ));
+        // Only the 5 whitelisted Genesis codes are accepted
+        assert!(matches!(
+            ActivationCode::parse("QNET-BOOT-0006-STRAP"),
+            Err(ActivationError::UnknownGenesisCode(_))
+        ));
+        assert_eq!(ActivationCode::parse("QNET-BOOT-0001-STRAX"), Err(ActivationError::UnknownGenesisCode("QNET-BOOT-0001-STRAX".to_string())));
+    }
+}
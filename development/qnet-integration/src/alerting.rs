@@ -0,0 +1,360 @@
+//! Operator alerts
+//!
+//! Critical node events (state-root halts, PoH checkpoint failures, production
+//! stalls) are raised as [`Alert`]s and handed to every registered
+//! [`AlertSink`] whose minimum severity they meet. Built-in sinks log the alert
+//! or POST it as JSON to a webhook (Slack, PagerDuty Events, or any generic
+//! receiver; a `text` field is included for chat webhooks). Alerts with the same
+//! component and event are sent at most once per cooldown; the ones held back
+//! are counted in `suppressed` on the next alert that goes out.
+//!
+//! | Variable | Effect |
+//! |---|---|
+//! | `QNET_ALERT_WEBHOOK_URL` | comma-separated webhook URLs |
+//! | `QNET_ALERT_MIN_SEVERITY` | `info`, `warning` (default) or `critical`, for webhooks |
+//! | `QNET_ALERT_COOLDOWN_SECS` | per-event cooldown (default 300) |
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+/// Default minimum interval between two alerts for the same event
+pub const DEFAULT_ALERT_COOLDOWN_SECS: u64 = 300;
+
+/// Webhook request timeout
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertSeverity {
+    Info,
+    Warning,
+    Critical,
+}
+
+impl AlertSeverity {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "info" => Some(Self::Info),
+            "warning" | "warn" => Some(Self::Warning),
+            "critical" => Some(Self::Critical),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Critical => "critical",
+        }
+    }
+}
+
+/// An event an operator should hear about
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Alert {
+    pub severity: AlertSeverity,
+    pub component: String,
+    pub event: String,
+    pub message: String,
+    pub height: Option<u64>,
+    pub timestamp: u64,
+    /// Alerts for the same event held back by the cooldown since the last one sent
+    pub suppressed: u32,
+}
+
+impl Alert {
+    pub fn new(severity: AlertSeverity, component: &str, event: &str, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            component: component.to_string(),
+            event: event.to_string(),
+            message: message.into(),
+            height: None,
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            suppressed: 0,
+        }
+    }
+
+    pub fn warning(component: &str, event: &str, message: impl Into<String>) -> Self {
+        Self::new(AlertSeverity::Warning, component, event, message)
+    }
+
+    pub fn critical(component: &str, event: &str, message: impl Into<String>) -> Self {
+        Self::new(AlertSeverity::Critical, component, event, message)
+    }
+
+    pub fn height(mut self, height: u64) -> Self {
+        self.height = Some(height);
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum AlertError {
+    #[error("webhook {url} unreachable: {reason}")]
+    Unreachable { url: String, reason: String },
+
+    #[error("webhook {url} answered {status}")]
+    Rejected { url: String, status: u16 },
+}
+
+/// Destination for alerts
+#[async_trait]
+pub trait AlertSink: Send + Sync {
+    fn name(&self) -> String;
+
+    async fn deliver(&self, alert: &Alert) -> Result<(), AlertError>;
+}
+
+/// Writes alerts to the node log
+pub struct LogSink;
+
+#[async_trait]
+impl AlertSink for LogSink {
+    fn name(&self) -> String {
+        "log".to_string()
+    }
+
+    async fn deliver(&self, alert: &Alert) -> Result<(), AlertError> {
+        let icon = match alert.severity {
+            AlertSeverity::Info => "ℹ️",
+            AlertSeverity::Warning => "⚠️",
+            AlertSeverity::Critical => "🚨",
+        };
+        println!("[ALERT] {} {} {}/{}: {}{}", icon, alert.severity.as_str().to_uppercase(), alert.component, alert.event,
+                 alert.message, if alert.suppressed > 0 { format!(" (+{} suppressed)", alert.suppressed) } else { String::new() });
+        Ok(())
+    }
+}
+
+/// POSTs each alert as JSON to a URL
+pub struct WebhookSink {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl WebhookSink {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            client: reqwest::Client::builder()
+                .timeout(WEBHOOK_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[async_trait]
+impl AlertSink for WebhookSink {
+    fn name(&self) -> String {
+        format!("webhook {}", self.url)
+    }
+
+    async fn deliver(&self, alert: &Alert) -> Result<(), AlertError> {
+        let mut body = serde_json::to_value(alert).unwrap_or_default();
+        body["text"] = serde_json::Value::String(format!(
+            "[{}] {}/{}: {}", alert.severity.as_str().to_uppercase(), alert.component, alert.event, alert.message
+        ));
+        let response = self.client.post(&self.url)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| AlertError::Unreachable { url: self.url.clone(), reason: e.to_string() })?;
+        if !response.status().is_success() {
+            return Err(AlertError::Rejected { url: self.url.clone(), status: response.status().as_u16() });
+        }
+        Ok(())
+    }
+}
+
+struct RegisteredSink {
+    sink: Arc<dyn AlertSink>,
+    min_severity: AlertSeverity,
+}
+
+/// Fans alerts out to registered sinks, with severity filtering and a per-event cooldown
+pub struct AlertDispatcher {
+    sinks: Mutex<Vec<RegisteredSink>>,
+    cooldown: Duration,
+    /// (component, event) -> (last sent, alerts suppressed since)
+    last_sent: Mutex<HashMap<(String, String), (Instant, u32)>>,
+}
+
+impl AlertDispatcher {
+    pub fn new(cooldown: Duration) -> Self {
+        Self {
+            sinks: Mutex::new(Vec::new()),
+            cooldown,
+            last_sent: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Log sink for every alert, plus the webhooks configured in the environment
+    pub fn from_env() -> Self {
+        let cooldown = std::env::var("QNET_ALERT_COOLDOWN_SECS")
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .unwrap_or(DEFAULT_ALERT_COOLDOWN_SECS);
+        let min_severity = std::env::var("QNET_ALERT_MIN_SEVERITY")
+            .ok()
+            .and_then(|v| AlertSeverity::parse(&v))
+            .unwrap_or(AlertSeverity::Warning);
+
+        let dispatcher = Self::new(Duration::from_secs(cooldown));
+        dispatcher.register(Arc::new(LogSink), AlertSeverity::Info);
+        if let Ok(urls) = std::env::var("QNET_ALERT_WEBHOOK_URL") {
+            for url in urls.split(',').map(str::trim).filter(|u| !u.is_empty()) {
+                dispatcher.register(Arc::new(WebhookSink::new(url)), min_severity);
+            }
+        }
+        dispatcher
+    }
+
+    /// Add a sink receiving alerts at or above `min_severity`
+    pub fn register(&self, sink: Arc<dyn AlertSink>, min_severity: AlertSeverity) {
+        if let Ok(mut sinks) = self.sinks.lock() {
+            sinks.push(RegisteredSink { sink, min_severity });
+        }
+    }
+
+    pub fn sink_count(&self) -> usize {
+        self.sinks.lock().map(|s| s.len()).unwrap_or(0)
+    }
+
+    /// Deliver `alert` to every sink that takes its severity, returning how many accepted it
+    /// (0 if the event is still cooling down)
+    pub async fn dispatch(&self, mut alert: Alert) -> usize {
+        match self.admit(&alert.component, &alert.event) {
+            Some(suppressed) => alert.suppressed = suppressed,
+            None => return 0,
+        }
+
+        let sinks: Vec<Arc<dyn AlertSink>> = match self.sinks.lock() {
+            Ok(sinks) => sinks.iter()
+                .filter(|s| alert.severity >= s.min_severity)
+                .map(|s| s.sink.clone())
+                .collect(),
+            Err(_) => return 0,
+        };
+        let mut delivered = 0;
+        for sink in sinks {
+            match sink.deliver(&alert).await {
+                Ok(()) => delivered += 1,
+                Err(e) => println!("[ALERT] ⚠️ Delivery to {} failed: {}", sink.name(), e),
+            }
+        }
+        delivered
+    }
+
+    /// Start the cooldown for an event, returning the alerts suppressed since it last went out
+    fn admit(&self, component: &str, event: &str) -> Option<u32> {
+        let mut last_sent = self.last_sent.lock().ok()?;
+        let now = Instant::now();
+        let key = (component.to_string(), event.to_string());
+        match last_sent.get_mut(&key) {
+            Some((sent_at, suppressed)) if now.duration_since(*sent_at) < self.cooldown => {
+                *suppressed += 1;
+                None
+            }
+            Some((sent_at, suppressed)) => {
+                *sent_at = now;
+                Some(std::mem::take(suppressed))
+            }
+            None => {
+                last_sent.insert(key, (now, 0));
+                Some(0)
+            }
+        }
+    }
+}
+
+static ALERTS: Lazy<AlertDispatcher> = Lazy::new(AlertDispatcher::from_env);
+
+/// Node-wide dispatcher (configured from the environment)
+pub fn dispatcher() -> &'static AlertDispatcher {
+    &ALERTS
+}
+
+/// Raise an alert without waiting for delivery (logged directly outside a Tokio runtime)
+pub fn raise(alert: Alert) {
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) => {
+            handle.spawn(async move {
+                dispatcher().dispatch(alert).await;
+            });
+        }
+        Err(_) => println!("[ALERT] {} {}/{}: {}", alert.severity.as_str().to_uppercase(), alert.component, alert.event, alert.message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use warp::Filter;
+
+    /// Webhook receiver on an ephemeral port, recording every body it gets
+    fn mock_webhook() -> (String, Arc<Mutex<Vec<serde_json::Value>>>) {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let store = received.clone();
+        let route = warp::post()
+            .and(warp::body::json())
+            .map(move |body: serde_json::Value| {
+                store.lock().unwrap().push(body);
+                warp::reply()
+            });
+        let (addr, server) = warp::serve(route).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+        (format!("http://{}/alerts", addr), received)
+    }
+
+    #[tokio::test]
+    async fn test_critical_alert_delivered_to_webhook() {
+        let (url, received) = mock_webhook();
+        let dispatcher = AlertDispatcher::new(Duration::from_secs(300));
+        dispatcher.register(Arc::new(LogSink), AlertSeverity::Info);
+        dispatcher.register(Arc::new(WebhookSink::new(url)), AlertSeverity::Warning);
+
+        let alert = Alert::critical("MONITOR", "state_root_breaker_tripped", "block production halted").height(42);
+        assert_eq!(dispatcher.dispatch(alert).await, 2);
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0]["severity"], "critical");
+        assert_eq!(received[0]["event"], "state_root_breaker_tripped");
+        assert_eq!(received[0]["height"], 42);
+        assert!(received[0]["text"].as_str().unwrap().starts_with("[CRITICAL] MONITOR/state_root_breaker_tripped"));
+    }
+
+    #[tokio::test]
+    async fn test_low_severity_filtered_and_repeats_rate_limited() {
+        let (url, received) = mock_webhook();
+        let dispatcher = AlertDispatcher::new(Duration::from_secs(300));
+        dispatcher.register(Arc::new(WebhookSink::new(url)), AlertSeverity::Warning);
+
+        // Below the webhook's minimum severity: not forwarded
+        assert_eq!(dispatcher.dispatch(Alert::new(AlertSeverity::Info, "SYNC", "sync_started", "syncing")).await, 0);
+        assert!(received.lock().unwrap().is_empty());
+
+        // Same event again within the cooldown: held back and counted
+        let stall = || Alert::warning("PRODUCER", "production_stalled", "stuck on height 7");
+        assert_eq!(dispatcher.dispatch(stall()).await, 1);
+        assert_eq!(dispatcher.dispatch(stall()).await, 0);
+        assert_eq!(dispatcher.dispatch(stall()).await, 0);
+        assert_eq!(received.lock().unwrap().len(), 1);
+
+        // After the cooldown the next one goes out with the suppressed count
+        let dispatcher = AlertDispatcher { cooldown: Duration::ZERO, ..dispatcher };
+        assert_eq!(dispatcher.dispatch(stall()).await, 1);
+        assert_eq!(received.lock().unwrap()[1]["suppressed"], 2);
+    }
+}
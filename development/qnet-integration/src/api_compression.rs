@@ -0,0 +1,285 @@
+//! HTTP response compression
+//!
+//! API responses are compressed with gzip or zstd, whichever the client ranks
+//! higher in `Accept-Encoding` (q-values respected, ties go to the first listed).
+//! Responses are left alone when:
+//! - they are smaller than `min_bytes`: compressing them costs more CPU than it saves
+//! - their path is excluded (by default `/metrics`, which Prometheus scrapes)
+//! - their body is streamed (no known length)
+//! - they are a protocol switch, such as the `/ws/subscribe` block stream upgrade
+//!
+//! Streamed bodies pass through untouched, so block stream frames are never buffered.
+//!
+//! | Variable | Effect |
+//! |---|---|
+//! | `QNET_API_COMPRESSION=0` | disable compression |
+//! | `QNET_API_COMPRESSION_MIN_BYTES` | size threshold (default 1024) |
+//! | `QNET_API_COMPRESSION_EXCLUDE` | comma-separated path prefixes never compressed (default `/metrics`) |
+
+use std::io::Write;
+use std::sync::Arc;
+
+use warp::http::{header, HeaderValue, StatusCode};
+use warp::hyper::body::HttpBody;
+use warp::hyper::Body;
+use warp::reply::Response;
+use warp::{Filter, Rejection, Reply};
+
+/// Default size below which responses are sent uncompressed
+pub const DEFAULT_MIN_COMPRESS_BYTES: usize = 1024;
+
+/// zstd level for responses: fast, most of the gain of higher levels on JSON
+const ZSTD_LEVEL: i32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentEncoding {
+    Gzip,
+    Zstd,
+}
+
+impl ContentEncoding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Zstd => "zstd",
+        }
+    }
+
+    pub fn encode(&self, data: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            Self::Gzip => {
+                let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+                encoder.write_all(data)?;
+                encoder.finish()
+            }
+            Self::Zstd => zstd::encode_all(data, ZSTD_LEVEL),
+        }
+    }
+}
+
+/// Client's preferred supported encoding from an `Accept-Encoding` header
+pub fn negotiate(accept_encoding: Option<&str>) -> Option<ContentEncoding> {
+    let mut best: Option<(ContentEncoding, f32)> = None;
+    let mut wildcard: Option<f32> = None;
+    let mut listed = Vec::new();
+
+    for entry in accept_encoding?.split(',') {
+        let mut parts = entry.split(';');
+        let name = parts.next().unwrap_or("").trim().to_ascii_lowercase();
+        let q = parts
+            .filter_map(|p| p.trim().strip_prefix("q="))
+            .find_map(|q| q.trim().parse::<f32>().ok())
+            .unwrap_or(1.0);
+        let encoding = match name.as_str() {
+            "gzip" | "x-gzip" => ContentEncoding::Gzip,
+            "zstd" => ContentEncoding::Zstd,
+            "*" => {
+                wildcard = Some(q);
+                continue;
+            }
+            _ => continue,
+        };
+        listed.push(encoding);
+        if q > 0.0 && best.map_or(true, |(_, best_q)| q > best_q) {
+            best = Some((encoding, q));
+        }
+    }
+
+    // `*` stands for gzip only when the client did not rank gzip itself
+    if let Some(q) = wildcard.filter(|&q| q > 0.0) {
+        if !listed.contains(&ContentEncoding::Gzip) && best.map_or(true, |(_, best_q)| q > best_q) {
+            best = Some((ContentEncoding::Gzip, q));
+        }
+    }
+    best.map(|(encoding, _)| encoding)
+}
+
+/// When and how API responses are compressed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompressionConfig {
+    pub enabled: bool,
+    pub min_bytes: usize,
+    /// Path prefixes served uncompressed
+    pub excluded_paths: Vec<String>,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_bytes: DEFAULT_MIN_COMPRESS_BYTES,
+            excluded_paths: vec!["/metrics".to_string()],
+        }
+    }
+}
+
+impl CompressionConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            enabled: std::env::var("QNET_API_COMPRESSION").map(|v| v.trim() != "0").unwrap_or(defaults.enabled),
+            min_bytes: std::env::var("QNET_API_COMPRESSION_MIN_BYTES")
+                .ok()
+                .and_then(|v| v.trim().parse::<usize>().ok())
+                .unwrap_or(defaults.min_bytes),
+            excluded_paths: std::env::var("QNET_API_COMPRESSION_EXCLUDE")
+                .map(|v| v.split(',').map(|p| p.trim().to_string()).filter(|p| !p.is_empty()).collect())
+                .unwrap_or(defaults.excluded_paths),
+        }
+    }
+
+    /// Whether `path` falls under an excluded prefix (whole segments only)
+    pub fn is_excluded(&self, path: &str) -> bool {
+        self.excluded_paths.iter().any(|prefix| {
+            let prefix = prefix.trim_end_matches('/');
+            path == prefix || path.strip_prefix(prefix).is_some_and(|rest| rest.starts_with('/'))
+        })
+    }
+
+    /// Compress `response` for a request to `path` if it qualifies
+    pub async fn compress(&self, path: &str, accept_encoding: Option<&str>, response: Response) -> Response {
+        if !self.enabled || self.is_excluded(path) || !is_compressible(&response) {
+            return response;
+        }
+        // Streamed bodies (unknown length) are passed through as they are produced
+        let Some(size) = response.body().size_hint().exact() else {
+            return response;
+        };
+
+        let (mut parts, body) = response.into_parts();
+        parts.headers.append(header::VARY, HeaderValue::from_static("accept-encoding"));
+        let encoding = match negotiate(accept_encoding) {
+            Some(encoding) if size as usize >= self.min_bytes => encoding,
+            _ => return Response::from_parts(parts, body),
+        };
+
+        let bytes = match warp::hyper::body::to_bytes(body).await {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                println!("[API] ❌ Failed to buffer response for compression: {}", e);
+                let mut response = Response::new(Body::empty());
+                *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+                return response;
+            }
+        };
+        match encoding.encode(&bytes) {
+            Ok(compressed) if compressed.len() < bytes.len() => {
+                parts.headers.insert(header::CONTENT_ENCODING, HeaderValue::from_static(encoding.as_str()));
+                parts.headers.remove(header::CONTENT_LENGTH);
+                Response::from_parts(parts, Body::from(compressed))
+            }
+            _ => Response::from_parts(parts, Body::from(bytes)),
+        }
+    }
+}
+
+/// Responses that may be compressed at all: not already encoded, not a protocol switch or event stream
+fn is_compressible(response: &Response) -> bool {
+    if matches!(response.status(), StatusCode::SWITCHING_PROTOCOLS | StatusCode::NO_CONTENT | StatusCode::NOT_MODIFIED) {
+        return false;
+    }
+    if response.headers().contains_key(header::CONTENT_ENCODING) {
+        return false;
+    }
+    let content_type = response.headers().get(header::CONTENT_TYPE).and_then(|v| v.to_str().ok()).unwrap_or("");
+    !content_type.starts_with("text/event-stream")
+}
+
+/// Wrap `routes` so their responses are compressed according to `config`
+pub fn with_compression<F, R>(config: CompressionConfig, routes: F) -> impl Filter<Extract = (Response,), Error = Rejection> + Clone
+where
+    F: Filter<Extract = (R,), Error = Rejection> + Clone + Send + Sync + 'static,
+    R: Reply,
+{
+    let config = Arc::new(config);
+    warp::path::full()
+        .and(warp::header::optional::<String>("accept-encoding"))
+        .and(routes)
+        .and_then(move |path: warp::path::FullPath, accept_encoding: Option<String>, reply: R| {
+            let config = config.clone();
+            async move {
+                Ok::<_, Rejection>(config.compress(path.as_str(), accept_encoding.as_deref(), reply.into_response()).await)
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn large_body() -> String {
+        "{\"height\":42,\"hash\":\"abc\"}".repeat(200)
+    }
+
+    fn routes() -> impl Filter<Extract = (Response,), Error = Rejection> + Clone {
+        let small = warp::path("small").map(|| warp::reply::json(&"ok").into_response());
+        let large = warp::path("large").map(|| large_body().into_response());
+        let metrics = warp::path("metrics").map(|| large_body().into_response());
+        let stream = warp::path("stream").map(|| {
+            let chunks = futures::stream::iter(vec![Ok::<_, std::io::Error>(large_body())]);
+            Response::new(Body::wrap_stream(chunks))
+        });
+        let routes = small.or(large).unify().or(metrics).unify().or(stream).unify();
+        with_compression(CompressionConfig::default(), routes)
+    }
+
+    async fn get(path: &str, accept_encoding: Option<&str>) -> warp::http::Response<warp::hyper::body::Bytes> {
+        let mut request = warp::test::request().path(path);
+        if let Some(accept_encoding) = accept_encoding {
+            request = request.header("accept-encoding", accept_encoding);
+        }
+        request.reply(&routes()).await
+    }
+
+    fn content_encoding<B>(response: &warp::http::Response<B>) -> Option<&str> {
+        response.headers().get(header::CONTENT_ENCODING).and_then(|v| v.to_str().ok())
+    }
+
+    #[tokio::test]
+    async fn test_small_uncompressed_large_compressed_with_preferred_encoding() {
+        // Below the threshold: sent as is even though the client accepts gzip
+        let small = get("/small", Some("gzip")).await;
+        assert_eq!(content_encoding(&small), None);
+        assert_eq!(small.body().as_ref(), b"\"ok\"");
+
+        // Client ranks gzip over zstd
+        let gzip = get("/large", Some("zstd;q=0.5, gzip")).await;
+        assert_eq!(content_encoding(&gzip), Some("gzip"));
+        let mut decoded = String::new();
+        flate2::read::GzDecoder::new(gzip.body().as_ref()).read_to_string(&mut decoded).unwrap();
+        assert_eq!(decoded, large_body());
+
+        // Client ranks zstd first
+        let zstd = get("/large", Some("zstd, gzip;q=0.8")).await;
+        assert_eq!(content_encoding(&zstd), Some("zstd"));
+        assert_eq!(zstd::decode_all(zstd.body().as_ref()).unwrap(), large_body().into_bytes());
+        assert!(zstd.body().len() < large_body().len());
+
+        // No Accept-Encoding, or identity only: uncompressed
+        assert_eq!(content_encoding(&get("/large", None).await), None);
+        assert_eq!(content_encoding(&get("/large", Some("identity")).await), None);
+    }
+
+    #[tokio::test]
+    async fn test_excluded_and_streamed_responses_untouched() {
+        let metrics = get("/metrics", Some("gzip")).await;
+        assert_eq!(content_encoding(&metrics), None);
+        assert_eq!(metrics.body().as_ref(), large_body().as_bytes());
+
+        let stream = get("/stream", Some("gzip")).await;
+        assert_eq!(content_encoding(&stream), None);
+        assert_eq!(stream.body().as_ref(), large_body().as_bytes());
+    }
+
+    #[test]
+    fn test_negotiate_respects_q_values() {
+        assert_eq!(negotiate(Some("gzip, deflate, br")), Some(ContentEncoding::Gzip));
+        assert_eq!(negotiate(Some("br, zstd")), Some(ContentEncoding::Zstd));
+        assert_eq!(negotiate(Some("gzip;q=0, zstd;q=0.1")), Some(ContentEncoding::Zstd));
+        assert_eq!(negotiate(Some("gzip;q=0")), None);
+        assert_eq!(negotiate(Some("*")), Some(ContentEncoding::Gzip));
+        assert_eq!(negotiate(Some("gzip;q=0, *")), None);
+        assert_eq!(negotiate(None), None);
+    }
+}
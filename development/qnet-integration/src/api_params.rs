@@ -0,0 +1,242 @@
+//! REST API parameter validation
+//!
+//! Path segments and query values are taken as strings and parsed here, so a
+//! malformed request gets HTTP 400 with a JSON error naming the parameter
+//! instead of a 404 from a non-matching route or a 500 from deeper down:
+//!
+//! | Parameter | Accepted form |
+//! |---|---|
+//! | height | unsigned decimal (`u64`) |
+//! | hash | 32 bytes as 64 hex characters, optional `0x` prefix |
+//! | address | EON address (checksummed, or legacy Genesis format) |
+//!
+//! Routes parse a segment after `warp::path::end()` with `.and_then(api_params::height)`
+//! (or `hash`, `address`), and the server recovers [`ParamError`] with [`recover`].
+
+use serde_json::json;
+use warp::{http::StatusCode, Rejection, Reply};
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ParamError {
+    #[error("Invalid {param} {value:?}: expected an unsigned integer")]
+    InvalidHeight { param: &'static str, value: String },
+
+    #[error("Invalid {param} {value:?}: {reason}")]
+    InvalidHash { param: &'static str, value: String, reason: String },
+
+    #[error("Invalid {param} {value:?}: {reason}")]
+    InvalidAddress { param: &'static str, value: String, reason: String },
+}
+
+impl warp::reject::Reject for ParamError {}
+
+impl ParamError {
+    pub fn param(&self) -> &'static str {
+        match self {
+            Self::InvalidHeight { param, .. }
+            | Self::InvalidHash { param, .. }
+            | Self::InvalidAddress { param, .. } => param,
+        }
+    }
+
+    /// 400 response body
+    pub fn to_reply(&self) -> warp::reply::WithStatus<warp::reply::Json> {
+        warp::reply::with_status(
+            warp::reply::json(&json!({
+                "success": false,
+                "error": self.to_string(),
+                "parameter": self.param()
+            })),
+            StatusCode::BAD_REQUEST,
+        )
+    }
+}
+
+/// Parse a block height or index
+pub fn parse_height(param: &'static str, value: &str) -> Result<u64, ParamError> {
+    value.parse::<u64>().map_err(|_| ParamError::InvalidHeight { param, value: value.to_string() })
+}
+
+/// Parse a 32-byte hex hash, returned in lowercase without prefix
+pub fn parse_hash(param: &'static str, value: &str) -> Result<String, ParamError> {
+    let invalid = |reason: String| ParamError::InvalidHash { param, value: value.to_string(), reason };
+    let hex_part = value.strip_prefix("0x").unwrap_or(value);
+    if hex_part.len() != 64 {
+        return Err(invalid(format!("expected 64 hex characters, got {}", hex_part.len())));
+    }
+    if !hex_part.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(invalid("contains non-hex characters".to_string()));
+    }
+    Ok(hex_part.to_ascii_lowercase())
+}
+
+/// Parse an EON address (legacy Genesis addresses carry no checksum)
+pub fn parse_address(param: &'static str, value: &str) -> Result<String, ParamError> {
+    let invalid = |reason: String| ParamError::InvalidAddress { param, value: value.to_string(), reason };
+    if !value.is_ascii() {
+        return Err(invalid("contains non-ASCII characters".to_string()));
+    }
+    if crate::rpc::validate_legacy_eon_address(value) {
+        return Ok(value.to_string());
+    }
+    crate::rpc::validate_eon_address_with_error(value)
+        .map(|_| value.to_string())
+        .map_err(invalid)
+}
+
+/// Optional height query value: absent is `None`, malformed is an error
+pub fn query_height(params: &std::collections::HashMap<String, String>, param: &'static str) -> Result<Option<u64>, ParamError> {
+    params.get(param).map(|value| parse_height(param, value)).transpose()
+}
+
+/// `{height}` path segment
+pub async fn height(value: String) -> Result<u64, Rejection> {
+    parse_height("height", &value).map_err(warp::reject::custom)
+}
+
+/// `{index}` path segment of a macroblock or snapshot
+pub async fn index(value: String) -> Result<u64, Rejection> {
+    parse_height("index", &value).map_err(warp::reject::custom)
+}
+
+/// `{hash}` path segment
+pub async fn hash(value: String) -> Result<String, Rejection> {
+    parse_hash("hash", &value).map_err(warp::reject::custom)
+}
+
+/// `{address}` path segment
+pub async fn address(value: String) -> Result<String, Rejection> {
+    parse_address("address", &value).map_err(warp::reject::custom)
+}
+
+/// Turn a [`ParamError`] rejection into its 400 response; other rejections pass through
+pub async fn recover(rejection: Rejection) -> Result<warp::reply::Response, Rejection> {
+    match rejection.find::<ParamError>() {
+        Some(e) => Ok(e.to_reply().into_response()),
+        None => Err(rejection),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use warp::Filter;
+
+    /// Routes shaped like the account, block and transaction routes, with a stub handler
+    fn routes() -> impl Filter<Extract = (warp::reply::Response,), Error = Rejection> + Clone {
+        let api_v1 = warp::path("api").and(warp::path("v1"));
+        let ok = |value: String| async move { Ok::<_, Rejection>(warp::reply::json(&value).into_response()) };
+
+        let block_latest = api_v1
+            .and(warp::path("block"))
+            .and(warp::path("latest"))
+            .and(warp::path::end())
+            .map(|| warp::reply::json(&"latest").into_response());
+        let block_by_height = api_v1
+            .and(warp::path("block"))
+            .and(warp::path::param::<String>())
+            .and(warp::path::end())
+            .and_then(height)
+            .and_then(move |h: u64| ok(h.to_string()));
+        let block_by_hash = api_v1
+            .and(warp::path("block"))
+            .and(warp::path("hash"))
+            .and(warp::path::param::<String>())
+            .and(warp::path::end())
+            .and_then(hash)
+            .and_then(ok);
+        let account_balance = api_v1
+            .and(warp::path("account"))
+            .and(warp::path::param::<String>())
+            .and(warp::path("balance"))
+            .and(warp::path::end())
+            .and_then(address)
+            .and(warp::query::<std::collections::HashMap<String, String>>())
+            .and_then(move |address: String, params: std::collections::HashMap<String, String>| async move {
+                match query_height(&params, "height") {
+                    Ok(_) => ok(address).await,
+                    Err(e) => Ok(e.to_reply().into_response()),
+                }
+            });
+        let transaction_get = api_v1
+            .and(warp::path("transaction"))
+            .and(warp::path::param::<String>())
+            .and(warp::path::end())
+            .and_then(hash)
+            .and_then(ok);
+
+        block_latest
+            .or(block_by_height)
+            .unify()
+            .or(block_by_hash)
+            .unify()
+            .or(account_balance)
+            .unify()
+            .or(transaction_get)
+            .unify()
+            .recover(recover)
+            .unify()
+    }
+
+    async fn get(path: &str) -> (StatusCode, serde_json::Value) {
+        let response = warp::test::request().path(path).reply(&routes()).await;
+        let body = serde_json::from_slice(response.body()).unwrap_or(serde_json::Value::Null);
+        (response.status(), body)
+    }
+
+    fn checksummed_address() -> String {
+        use sha3::{Digest, Sha3_256};
+        let body = format!("{}eon{}", "a".repeat(19), "b".repeat(15));
+        let checksum = hex::encode(&Sha3_256::digest(body.as_bytes())[..2]);
+        format!("{}{}", body, checksum)
+    }
+
+    #[tokio::test]
+    async fn test_malformed_parameters_rejected_with_400() {
+        let (status, body) = get("/api/v1/block/12x").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["parameter"], "height");
+        assert!(body["error"].as_str().unwrap().contains("unsigned integer"));
+
+        let (status, body) = get("/api/v1/block/-1").await;
+        assert_eq!((status, body["parameter"].as_str()), (StatusCode::BAD_REQUEST, Some("height")));
+
+        let (status, body) = get("/api/v1/block/hash/abc123").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"].as_str().unwrap().contains("expected 64 hex characters, got 6"));
+
+        let (status, body) = get(&format!("/api/v1/transaction/{}", "zz".repeat(32))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"].as_str().unwrap().contains("non-hex"));
+
+        let (status, body) = get("/api/v1/account/not-an-address/balance").await;
+        assert_eq!((status, body["parameter"].as_str()), (StatusCode::BAD_REQUEST, Some("address")));
+        assert!(body["error"].as_str().unwrap().contains("Invalid address length"));
+
+        let mut bad_checksum = checksummed_address();
+        bad_checksum.replace_range(37..41, "0000");
+        let (status, body) = get(&format!("/api/v1/account/{}/balance", bad_checksum)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"].as_str().unwrap().contains("Invalid checksum"));
+
+        let (status, body) = get(&format!("/api/v1/account/{}/balance?height=tip", checksummed_address())).await;
+        assert_eq!((status, body["parameter"].as_str()), (StatusCode::BAD_REQUEST, Some("height")));
+    }
+
+    #[tokio::test]
+    async fn test_well_formed_parameters_reach_handler() {
+        assert_eq!(get("/api/v1/block/latest").await, (StatusCode::OK, json!("latest")));
+        assert_eq!(get("/api/v1/block/42").await, (StatusCode::OK, json!("42")));
+
+        let hash = format!("0x{}", "AB".repeat(32));
+        assert_eq!(get(&format!("/api/v1/block/hash/{}", hash)).await, (StatusCode::OK, json!("ab".repeat(32))));
+
+        let address = checksummed_address();
+        assert_eq!(get(&format!("/api/v1/account/{}/balance?height=7", address)).await, (StatusCode::OK, json!(address)));
+        let legacy = format!("{}eon{}", "1".repeat(19), "2".repeat(19));
+        assert_eq!(get(&format!("/api/v1/account/{}/balance", legacy)).await.0, StatusCode::OK);
+
+        // Unknown paths stay 404
+        assert_eq!(get("/api/v1/block/1/extra").await.0, StatusCode::NOT_FOUND);
+    }
+}
@@ -3,6 +3,7 @@
 //! This module implements a distributed archival system where:
 //! - Full nodes archive 3 chunks as network obligation
 //! - Super nodes archive 8 chunks as network obligation
+//! - Operators may volunteer a larger quota (QNET_ARCHIVE_QUOTA), never a smaller one
 //! - Genesis nodes archive 20+ chunks for critical network infrastructure
 //! - Automatic replication ensures 3+ copies of each chunk exist
 //! - Compliance enforcement maintains network fault tolerance
@@ -18,6 +19,34 @@ use sha3::{Sha3_256, Digest};
 use bincode;
 use serde::{Serialize, Deserialize};
 
+/// Archive chunk identifier
+pub type ChunkId = [u8; 32];
+
+/// Mandatory archive chunks for a node type
+pub fn mandatory_archive_quota(node_type: &NodeType) -> u8 {
+    match node_type {
+        NodeType::Light => 0,    // Light nodes exempt from archival
+        NodeType::Full => 3,
+        NodeType::Super => 8,
+    }
+}
+
+/// Check an operator-chosen quota against the mandatory minimum for the node type
+pub fn validate_archive_quota(node_type: &NodeType, quota: u8) -> IntegrationResult<u8> {
+    let minimum = mandatory_archive_quota(node_type);
+    if matches!(node_type, NodeType::Light) && quota > 0 {
+        return Err(IntegrationError::ValidationError(
+            "Light nodes do not hold archive chunks".to_string()
+        ));
+    }
+    if quota < minimum {
+        return Err(IntegrationError::ValidationError(format!(
+            "Archive quota {} below mandatory minimum {} for {:?} nodes", quota, minimum, node_type
+        )));
+    }
+    Ok(quota)
+}
+
 /// Archive chunk containing compressed blockchain data
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ArchiveChunk {
@@ -45,6 +74,9 @@ pub struct ArchiveNodeInfo {
     pub last_seen: u64,
     /// Chunks this node is responsible for
     pub assigned_chunks: Vec<[u8; 32]>,
+    /// Chunks this node must hold (at least the mandatory quota for its type)
+    #[serde(default)]
+    pub quota: u8,
     /// Compliance status
     pub compliance_status: ComplianceStatus,
 }
@@ -80,6 +112,10 @@ pub struct ArchiveReplicationManager {
     grace_period_hours: u32,
     /// Network size adaptive scaling
     adaptive_scaling: bool,
+    /// This node's id, once registered
+    local_node_id: Option<String>,
+    /// Quota this node volunteers on top of the mandatory one
+    volunteered_quota: Option<u8>,
 }
 
 impl ArchiveReplicationManager {
@@ -94,9 +130,62 @@ impl ArchiveReplicationManager {
             health_check_interval: Duration::from_secs(4 * 3600), // 4 hours
             grace_period_hours: 24, // 24 hours for new nodes to comply
             adaptive_scaling: true, // Enable adaptive scaling for small networks
+            local_node_id: None,
+            volunteered_quota: None,
         }
     }
     
+    /// Volunteer to hold `quota` chunks on this node; below the mandatory minimum is rejected
+    pub fn set_archive_quota(&mut self, node_type: &NodeType, quota: u8) -> IntegrationResult<()> {
+        self.volunteered_quota = Some(validate_archive_quota(node_type, quota)?);
+        Ok(())
+    }
+    
+    /// Register this node for archival, applying any volunteered quota
+    pub async fn register_local_node(&mut self, node_id: &str, node_type: NodeType, ip_address: &str) -> IntegrationResult<u8> {
+        self.local_node_id = Some(node_id.to_string());
+        self.register_archive_node(node_id, node_type, ip_address).await?;
+        let archive_nodes = self.archive_nodes.read().await;
+        Ok(archive_nodes.get(node_id).map(|node| node.quota).unwrap_or(0))
+    }
+    
+    /// Chunks assigned to this node
+    pub async fn assigned_chunks(&self) -> Vec<ChunkId> {
+        let Some(node_id) = &self.local_node_id else {
+            return Vec::new();
+        };
+        self.archive_nodes.read().await
+            .get(node_id)
+            .map(|node| node.assigned_chunks.clone())
+            .unwrap_or_default()
+    }
+    
+    /// Assigned chunks, the ones whose data this node holds, and replication health
+    pub async fn local_archive_report(&self) -> IntegrationResult<LocalArchiveReport> {
+        let node = match &self.local_node_id {
+            Some(node_id) => self.archive_nodes.read().await.get(node_id).cloned(),
+            None => None,
+        };
+        let assigned_chunks = self.assigned_chunks().await;
+        let held_chunks = {
+            let archive_chunks = self.archive_chunks.read().await;
+            assigned_chunks.iter()
+                .filter(|chunk_id| archive_chunks.contains_key(*chunk_id))
+                .copied()
+                .collect()
+        };
+        
+        Ok(LocalArchiveReport {
+            node_id: self.local_node_id.clone(),
+            quota: node.as_ref().map(|n| n.quota).unwrap_or(0),
+            mandatory_quota: node.as_ref().map(|n| mandatory_archive_quota(&n.node_type)).unwrap_or(0),
+            compliance_status: node.map(|n| n.compliance_status),
+            assigned_chunks,
+            held_chunks,
+            network: self.get_archive_stats().await?,
+        })
+    }
+    
     /// Register node for archival responsibilities (MANDATORY assignments)
     pub async fn register_archive_node(&mut self, node_id: &str, node_type: NodeType, ip_address: &str) -> IntegrationResult<()> {
         let current_time = SystemTime::now()
@@ -105,23 +194,27 @@ impl ArchiveReplicationManager {
             .as_secs();
         
         // Calculate adaptive archive quota based on network size
-        let required_chunks = if self.adaptive_scaling {
+        let mut required_chunks = if self.adaptive_scaling {
             self.calculate_adaptive_quota(&node_type).await?
         } else {
             // Static quotas for large networks
-            match node_type {
-                NodeType::Light => 0,    // Light nodes exempt from archival
-                NodeType::Full => 3,     // Static: Full nodes archive 3 chunks
-                NodeType::Super => 8,    // Static: Super nodes archive 8 chunks
-            }
+            mandatory_archive_quota(&node_type)
         };
         
+        // Operators may volunteer to hold more than the network asks for
+        if self.local_node_id.as_deref() == Some(node_id) {
+            if let Some(volunteered) = self.volunteered_quota {
+                required_chunks = required_chunks.max(validate_archive_quota(&node_type, volunteered)?);
+            }
+        }
+        
         let node_info = ArchiveNodeInfo {
             node_id: node_id.to_string(),
             node_type,
             ip_address: ip_address.to_string(),
             last_seen: current_time,
             assigned_chunks: Vec::new(),
+            quota: required_chunks,
             compliance_status: ComplianceStatus::GracePeriod {
                 expires_at: current_time + (self.grace_period_hours as u64 * 3600),
             },
@@ -243,11 +336,7 @@ impl ArchiveReplicationManager {
                     continue;
                 }
                 
-                let required_chunks = match node_info.node_type {
-                    NodeType::Full => 3,
-                    NodeType::Super => 8,
-                    _ => continue,
-                };
+                let required_chunks = node_info.quota.max(mandatory_archive_quota(&node_info.node_type));
                 
                 let actual_chunks = node_info.assigned_chunks.len() as u8;
                 
@@ -572,6 +661,20 @@ pub struct ArchiveStats {
     pub max_replicas: u8,
 }
 
+/// This node's archive duties (GET /api/v1/node/archive)
+#[derive(Debug, Serialize)]
+pub struct LocalArchiveReport {
+    pub node_id: Option<String>,
+    pub quota: u8,
+    pub mandatory_quota: u8,
+    pub compliance_status: Option<ComplianceStatus>,
+    pub assigned_chunks: Vec<ChunkId>,
+    /// Assigned chunks whose data is stored locally
+    pub held_chunks: Vec<ChunkId>,
+    /// Replication health across the archive network
+    pub network: ArchiveStats,
+}
+
 /// Background replication service for archive chunks
 pub struct BackgroundReplicationService {
     archive_manager: Arc<RwLock<ArchiveReplicationManager>>,
@@ -693,8 +796,9 @@ impl BackgroundReplicationService {
                 !matches!(node_info.node_type, crate::node::NodeType::Light) &&
                 // Skip nodes that already have this chunk
                 !current_holders.contains(node_id) &&
-                // Only nodes that have capacity
+                // Only nodes that have capacity (volunteered quota raises the cap)
                 node_info.assigned_chunks.len() < Self::get_max_chunks_for_node_type(&node_info.node_type)
+                    .max(node_info.quota as usize)
             })
             .map(|(node_id, _)| node_id.clone())
             .collect();
@@ -744,3 +848,65 @@ impl BackgroundReplicationService {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_archive_quota_respects_mandatory_minimum() {
+        // Super nodes may volunteer more than the 8-chunk minimum
+        let mut super_node = ArchiveReplicationManager::new();
+        super_node.set_archive_quota(&NodeType::Super, 20).unwrap();
+        let quota = super_node.register_local_node("super_1", NodeType::Super, "203.0.113.1:8001").await.unwrap();
+        assert_eq!(quota, 20);
+
+        // Full nodes can't drop below 3
+        let mut full_node = ArchiveReplicationManager::new();
+        assert!(matches!(
+            full_node.set_archive_quota(&NodeType::Full, 2),
+            Err(IntegrationError::ValidationError(_))
+        ));
+        full_node.set_archive_quota(&NodeType::Full, 3).unwrap();
+        let quota = full_node.register_local_node("full_1", NodeType::Full, "203.0.113.2:8001").await.unwrap();
+        assert!(quota >= mandatory_archive_quota(&NodeType::Full));
+
+        assert!(validate_archive_quota(&NodeType::Light, 1).is_err());
+        assert_eq!(validate_archive_quota(&NodeType::Light, 0).unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_local_archive_report_lists_assigned_and_held_chunks() {
+        let mut manager = ArchiveReplicationManager::new();
+        let held = [1u8; 32];
+        let missing = [2u8; 32];
+
+        // Two chunks waiting for replicas, only one of them stored locally
+        {
+            let mut assignments = manager.chunk_assignments.write().await;
+            assignments.insert(held, Vec::new());
+            assignments.insert(missing, Vec::new());
+        }
+        manager.archive_chunks.write().await.insert(held, ArchiveChunk {
+            chunk_id: held,
+            height_start: 1,
+            height_end: 1_000,
+            compressed_data: vec![0u8; 16],
+            created_at: 1_700_000_000,
+            compression_ratio: 1.0,
+            verification_hash: [0u8; 32],
+        });
+        assert!(manager.assigned_chunks().await.is_empty());
+
+        manager.register_local_node("full_1", NodeType::Full, "203.0.113.2:8001").await.unwrap();
+        let mut assigned = manager.assigned_chunks().await;
+        assigned.sort();
+        assert_eq!(assigned, vec![held, missing]);
+
+        let report = manager.local_archive_report().await.unwrap();
+        assert_eq!(report.node_id.as_deref(), Some("full_1"));
+        assert_eq!(report.mandatory_quota, 3);
+        assert_eq!(report.held_chunks, vec![held]);
+        assert_eq!(report.network.total_nodes, 1);
+    }
+}
@@ -17,7 +17,9 @@
 //! - Enterprise security and monitoring
 
 use qnet_integration::node::{BlockchainNode, NodeType, Region};
+use qnet_integration::node_config::NodeConfig;
 use qnet_integration::quantum_crypto::{QNetQuantumCrypto, ActivationPayload};
+use qnet_integration::activation_validation::ActivationCode;
 // No clap - fully automatic configuration
 use std::path::PathBuf;
 use std::time::Duration;
@@ -141,8 +143,9 @@ fn validate_activation_code_node_type(code: &str, expected_type: NodeType, curre
     println!("\n🔍 === Activation Code Validation ===");
     
     // Production mode - validate QNET activation codes (25 chars: QNET-XXXXXX-XXXXXX-XXXXXX)
-    if !code.starts_with("QNET-") || code.len() != 25 {
-        return Err("Invalid activation code format. Expected: QNET-XXXXXX-XXXXXX-XXXXXX (25 chars)".to_string());
+    let parsed_code = ActivationCode::parse(code).map_err(|e| e.to_string())?;
+    if parsed_code.is_genesis_bootstrap() {
+        return Err("Genesis bootstrap codes carry no node type or payment".to_string());
     }
     
     println!("   ✅ QNET activation code format validated");
@@ -466,18 +469,16 @@ async fn validate_activation_code_comprehensive(
         }
     }
     
-    // 2. Bootstrap whitelist check FIRST (genesis codes have different format)
-    if BOOTSTRAP_WHITELIST.contains(&code) {
+    // 2. Format validation - QNET-XXXXXX-XXXXXX-XXXXXX for regular production codes (enhanced security)
+    let parsed_code = ActivationCode::parse(code).map_err(|e| e.to_string())?;
+    
+    // 3. Bootstrap whitelist check (genesis codes have different format)
+    if parsed_code.is_genesis_bootstrap() {
         println!("✅ Bootstrap whitelist code detected - Genesis network node");
         println!("   [GENESIS] Code: {} (bootstrap format)", code);
         return Ok(());
     }
     
-    // 3. Format validation - QNET-XXXXXX-XXXXXX-XXXXXX for regular production codes (enhanced security)
-    if !code.starts_with("QNET-") || code.len() != 25 {
-        return Err("Invalid activation code format. Expected: QNET-XXXXXX-XXXXXX-XXXXXX (25 chars)".to_string());
-    }
-    
             // 4. Phase and pricing validation with quantum decryption
         if let Err(e) = validate_phase_and_pricing(current_phase, node_type, pricing_info, code).await {
             return Err(format!("Phase validation failed: {}", e));
@@ -626,8 +627,8 @@ async fn interactive_node_setup() -> Result<(NodeType, String), Box<dyn std::err
                 }
 
                 // Basic format validation
-                if !code.starts_with("QNET-") {
-                    println!("❌ Invalid activation code format. Expected format: QNET-XXXXXX-XXXXXX-XXXXXX");
+                if let Err(e) = ActivationCode::parse(&code) {
+                    println!("❌ {}", e);
                     continue;
                 }
 
@@ -2453,6 +2454,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
     env_logger::init();
     
+    // QNET_LOG_FORMAT=json: structured events instead of formatted lines
+    qnet_integration::logging::init();
+    
+    // Operator integrity self-check: `qnet-node verify-chain [FROM] [TO]`
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("verify-chain") {
+        return verify_chain_command(&args[2..]).await;
+    }
+    
     // Check if data cleanup is requested
     if std::env::var("QNET_CLEAN_DATA").unwrap_or_default() == "1" {
         println!("🧹 CLEANING NODE DATA...");
@@ -2522,8 +2532,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Display configuration
     display_node_config(&config, &node_type, &region);
     
+    // All startup QNET_* settings, parsed and validated once
+    let node_config = NodeConfig::from_env()
+        .map_err(|e| format!("Invalid node configuration: {}", e))?;
+    
     // Display activation status
-    let activation_code = std::env::var("QNET_ACTIVATION_CODE").unwrap_or_default();
+    let activation_code = node_config.activation_code.clone().unwrap_or_default();
     println!("\n🔐 === Activation Status ===");
     
     if activation_code.is_empty() {
@@ -2535,6 +2549,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("🌐 Network: {}", network_config.network_name());
     println!("   Environment: {:?}", network_config.environment);
     println!("   Chain ID: {}", network_config.chain_id);
+    println!("   Rotation interval: {} blocks", network_config.chain_params.rotation_interval_blocks);
     println!("   QNet RPC: {}", network_config.endpoints.qnet_rpc);
     println!("   Bridge API: {}", network_config.endpoints.bridge_api);
     println!("   Solana RPC: {}", network_config.solana.rpc_url);
@@ -2563,7 +2578,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
     
     // Verify 1DEV burn if required for production (skip for genesis nodes)
-    if std::env::var("QNET_PRODUCTION").unwrap_or_default() == "1" && !is_genesis_bootstrap_node() {
+    if node_config.production && !is_genesis_bootstrap_node() {
         verify_1dev_burn(&node_type).await?;
     } else if is_genesis_bootstrap_node() {
         println!("🚀 Genesis bootstrap node - skipping 1DEV burn verification for production startup");
@@ -2574,7 +2589,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("✅ DEBUG: Data directory permissions already verified during selection");
     
     // Record quantum-secure activation in QNet blockchain before node start
-    if std::env::var("QNET_PRODUCTION").unwrap_or_default() == "1" {
+    if node_config.production {
         println!("🔐 Recording quantum-secure activation in QNet blockchain...");
         
         // Use GLOBAL quantum crypto instance
@@ -2627,6 +2642,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         bootstrap_peers,
         node_type,
         region,
+        node_config,
     ).await {
         Ok(node) => {
             println!("🔍 DEBUG: BlockchainNode created successfully");
@@ -2740,7 +2756,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("=== BLOCKCHAIN LOGS (Live) ===");
     
     // Continue with live blockchain logging - no background transition
-    let _ = node_handle.await;
+    tokio::select! {
+        _ = node_handle => {}
+        _ = tokio::signal::ctrl_c() => {
+            println!("🛑 Shutdown signal received - saving state...");
+            node.save_invalid_block_scores();
+        }
+    }
     
     Ok(())
 }
@@ -3782,6 +3804,39 @@ async fn try_query_node(addr: &str) -> Result<NodeInfo, String> {
     }
 }
 
+/// `verify-chain [FROM] [TO]`: check that stored microblocks link up and match their
+/// Merkle roots (defaults: genesis to the stored chain height). Run with the node
+/// stopped, RocksDB allows one process per data directory. Exits with status 2 on a break.
+async fn verify_chain_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let data_dir = match std::env::var("QNET_DATA_DIR") {
+        Ok(dir) => PathBuf::from(dir),
+        Err(_) => select_best_data_directory().await?,
+    };
+    let storage = qnet_integration::storage::PersistentStorage::new(&data_dir.to_string_lossy())?;
+    let from = match args.first() {
+        Some(value) => value.parse::<u64>().map_err(|e| format!("Invalid FROM height {:?}: {}", value, e))?,
+        None => 0,
+    };
+    let to = match args.get(1) {
+        Some(value) => value.parse::<u64>().map_err(|e| format!("Invalid TO height {:?}: {}", value, e))?,
+        None => storage.get_chain_height()?,
+    };
+    
+    println!("🔎 Verifying blocks #{}..=#{} in {:?}", from, to, data_dir);
+    let report = storage.verify_chain(from, to)?;
+    match &report.first_break {
+        None => {
+            println!("✅ Chain intact: {} blocks verified", report.verified);
+            Ok(())
+        }
+        Some(chain_break) => {
+            eprintln!("❌ Chain break at block #{}: {}", chain_break.height, chain_break.kind);
+            eprintln!("   {} blocks verified before the break", report.verified);
+            std::process::exit(2);
+        }
+    }
+}
+
 // Smart data directory selection for Linux servers
 async fn select_best_data_directory() -> Result<PathBuf, Box<dyn std::error::Error>> {
     println!("🔍 Selecting optimal data directory for server deployment...");
@@ -0,0 +1,277 @@
+//! WebSocket block streaming from a start height
+//!
+//! A client sending `{"subscribe":"blocks","from":H}` first receives every
+//! stored block from H to the tip, then live `NewBlock` events. Each height is
+//! sent exactly once and in order: live blocks at or below the replayed height
+//! are dropped, and a live block ahead of the stream (missed while replaying
+//! or lagging behind the broadcaster) first pulls the skipped heights from
+//! storage. Sends wait on the socket, so a slow client slows its own replay.
+
+use std::time::Duration;
+use async_trait::async_trait;
+use futures::{Sink, SinkExt};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use warp::ws::Message;
+
+use crate::node::BlockchainNode;
+use crate::rpc::WsEvent;
+
+/// Blocks replayed between pauses
+pub const REPLAY_BATCH_SIZE: u64 = 100;
+
+/// Pause after each replay batch so one replay cannot monopolise the node
+pub const REPLAY_BATCH_PAUSE: Duration = Duration::from_millis(10);
+
+/// Stored blocks a replay reads from
+#[async_trait]
+pub trait BlockHistory: Send + Sync {
+    /// Highest stored block
+    async fn tip_height(&self) -> u64;
+
+    /// `NewBlock` event for a stored block, None if not stored (or pruned)
+    async fn block_event(&self, height: u64) -> Option<WsEvent>;
+}
+
+#[async_trait]
+impl BlockHistory for BlockchainNode {
+    async fn tip_height(&self) -> u64 {
+        self.get_height().await
+    }
+
+    async fn block_event(&self, height: u64) -> Option<WsEvent> {
+        let data = self.load_microblock_bytes(height).ok()??;
+        let microblock = bincode::deserialize::<qnet_state::MicroBlock>(&data).ok()?;
+        Some(WsEvent::new_block(&microblock))
+    }
+}
+
+/// Client command `{"subscribe":"blocks","from":H}`
+#[derive(Debug, Deserialize)]
+struct SubscribeCommand {
+    subscribe: String,
+    from: Option<u64>,
+}
+
+/// Start height of a block stream command, None for any other message
+pub fn parse_block_stream_command(text: &str) -> Option<u64> {
+    let command: SubscribeCommand = serde_json::from_str(text).ok()?;
+    if command.subscribe == "blocks" { command.from } else { None }
+}
+
+/// Send `value` as a JSON text frame
+pub async fn send_json<S, T>(sink: &mut S, value: &T) -> Result<(), S::Error>
+where
+    S: Sink<Message> + Unpin + Send,
+    T: Serialize + ?Sized,
+{
+    match serde_json::to_string(value) {
+        Ok(text) => sink.send(Message::text(text)).await,
+        Err(_) => Ok(()),
+    }
+}
+
+/// Position of one client's block stream
+#[derive(Debug)]
+pub struct BlockStream {
+    /// Next height the client should receive
+    next: u64,
+}
+
+impl BlockStream {
+    pub fn new(from: u64) -> Self {
+        Self { next: from }
+    }
+
+    /// Next height the client should receive
+    pub fn next_height(&self) -> u64 {
+        self.next
+    }
+
+    /// Replay stored blocks until the tip stops moving
+    pub async fn catch_up<H, S>(&mut self, history: &H, sink: &mut S) -> Result<(), S::Error>
+    where
+        H: BlockHistory + ?Sized,
+        S: Sink<Message> + Unpin + Send,
+    {
+        loop {
+            let tip = history.tip_height().await;
+            if self.next > tip {
+                return Ok(());
+            }
+            self.replay(history, tip, sink).await?;
+        }
+    }
+
+    /// Forward a live event; blocks behind the stream are dropped, gaps are filled from storage
+    pub async fn on_live<H, S>(&mut self, history: &H, event: &WsEvent, sink: &mut S) -> Result<(), S::Error>
+    where
+        H: BlockHistory + ?Sized,
+        S: Sink<Message> + Unpin + Send,
+    {
+        let height = match event {
+            WsEvent::NewBlock { height, .. } => *height,
+            _ => return Ok(()),
+        };
+        if height < self.next {
+            return Ok(());
+        }
+        if height > self.next {
+            self.replay(history, height - 1, sink).await?;
+        }
+        send_json(sink, event).await?;
+        self.next = height + 1;
+        Ok(())
+    }
+
+    /// Send stored blocks from `next` through `up_to`
+    async fn replay<H, S>(&mut self, history: &H, up_to: u64, sink: &mut S) -> Result<(), S::Error>
+    where
+        H: BlockHistory + ?Sized,
+        S: Sink<Message> + Unpin + Send,
+    {
+        let mut missing_from = None;
+        let mut sent = 0u64;
+        while self.next <= up_to {
+            match history.block_event(self.next).await {
+                Some(event) => {
+                    if let Some(first) = missing_from.take() {
+                        send_missing(sink, first, self.next - 1).await?;
+                    }
+                    send_json(sink, &event).await?;
+                }
+                // Skipped rather than waited on so pruned history cannot stall the stream
+                None => {
+                    missing_from.get_or_insert(self.next);
+                }
+            }
+            self.next += 1;
+            sent += 1;
+            if sent.is_multiple_of(REPLAY_BATCH_SIZE) {
+                tokio::time::sleep(REPLAY_BATCH_PAUSE).await;
+            }
+        }
+        if let Some(first) = missing_from {
+            send_missing(sink, first, up_to).await?;
+        }
+        Ok(())
+    }
+}
+
+async fn send_missing<S>(sink: &mut S, first: u64, last: u64) -> Result<(), S::Error>
+where
+    S: Sink<Message> + Unpin + Send,
+{
+    println!("[WS] ⚠️ Blocks #{}-#{} not in storage, skipped in replay", first, last);
+    let warning = json!({
+        "type": "warning",
+        "message": format!("Blocks {}-{} are not available on this node", first, last),
+        "missing_from": first,
+        "missing_to": last,
+    });
+    send_json(sink, &warning).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use std::collections::BTreeMap;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct StubHistory {
+        blocks: Mutex<BTreeMap<u64, WsEvent>>,
+    }
+
+    impl StubHistory {
+        /// Store a block and return the event the node would broadcast for it
+        fn store(&self, height: u64) -> WsEvent {
+            let event = WsEvent::NewBlock {
+                height,
+                hash: format!("hash_{}", height),
+                timestamp: 1_700_000_000 + height,
+                tx_count: 0,
+                producer: "producer".to_string(),
+            };
+            self.blocks.lock().unwrap().insert(height, event.clone());
+            event
+        }
+    }
+
+    #[async_trait]
+    impl BlockHistory for StubHistory {
+        async fn tip_height(&self) -> u64 {
+            self.blocks.lock().unwrap().keys().next_back().copied().unwrap_or(0)
+        }
+
+        async fn block_event(&self, height: u64) -> Option<WsEvent> {
+            self.blocks.lock().unwrap().get(&height).cloned()
+        }
+    }
+
+    #[test]
+    fn test_parse_block_stream_command() {
+        assert_eq!(parse_block_stream_command(r#"{"subscribe":"blocks","from":42}"#), Some(42));
+        assert_eq!(parse_block_stream_command(r#"{"subscribe":"blocks"}"#), None);
+        assert_eq!(parse_block_stream_command(r#"{"subscribe":"mempool","from":42}"#), None);
+        assert_eq!(parse_block_stream_command("ping"), None);
+    }
+
+    #[tokio::test]
+    async fn test_stream_from_old_height_is_gapless_into_live_blocks() {
+        let history = StubHistory::default();
+        for height in 1..=250 {
+            history.store(height);
+        }
+
+        // Small buffer: the replay has to wait for the reader (backpressure)
+        let (mut sink, frames) = futures::channel::mpsc::channel::<Message>(4);
+        let reader = tokio::spawn(frames.collect::<Vec<Message>>());
+
+        let mut stream = BlockStream::new(10);
+        stream.catch_up(&history, &mut sink).await.unwrap();
+        assert_eq!(stream.next_height(), 251);
+
+        // Live: a duplicate of the tip, then new blocks with 253 lost by the broadcaster
+        let duplicate = history.block_event(250).await.unwrap();
+        stream.on_live(&history, &duplicate, &mut sink).await.unwrap();
+        for height in 251..=255 {
+            let event = history.store(height);
+            if height != 253 {
+                stream.on_live(&history, &event, &mut sink).await.unwrap();
+            }
+        }
+        drop(sink);
+
+        let heights: Vec<u64> = reader.await.unwrap().iter()
+            .map(|frame| serde_json::from_str::<WsEvent>(frame.to_str().unwrap()).unwrap())
+            .map(|event| match event {
+                WsEvent::NewBlock { height, .. } => height,
+                other => panic!("unexpected event {:?}", other),
+            })
+            .collect();
+        assert_eq!(heights, (10..=255).collect::<Vec<u64>>());
+    }
+
+    #[tokio::test]
+    async fn test_pruned_heights_reported_once_and_skipped() {
+        let history = StubHistory::default();
+        for height in [1, 2, 6, 7] {
+            history.store(height);
+        }
+        let (mut sink, frames) = futures::channel::mpsc::unbounded::<Message>();
+
+        let mut stream = BlockStream::new(1);
+        stream.catch_up(&history, &mut sink).await.unwrap();
+        drop(sink);
+
+        let frames: Vec<serde_json::Value> = frames.collect::<Vec<Message>>().await.iter()
+            .map(|frame| serde_json::from_str(frame.to_str().unwrap()).unwrap())
+            .collect();
+        assert_eq!(frames.len(), 5);
+        assert_eq!(frames[2]["type"], "warning");
+        assert_eq!((frames[2]["missing_from"].as_u64(), frames[2]["missing_to"].as_u64()), (Some(3), Some(5)));
+        assert_eq!(frames[3]["data"]["height"], 6);
+    }
+}
@@ -0,0 +1,386 @@
+//! Weak subjectivity checkpoints for trusted sync
+//!
+//! A new node started with a trusted `(height, block_hash, state_root)` checkpoint
+//! verifies the macroblock at that height and a state snapshot against it, then
+//! syncs forward from the checkpoint instead of replaying all earlier blocks.
+//!
+//! With `QNET_FAST_SYNC_TRUST_CHECKPOINT=1` a catching-up node also skips
+//! producer signature checks for microblocks at or below the checkpoint,
+//! verifying only hash linkage and the finality proof of the macroblock that
+//! commits to them. This trusts the checkpoint (and the macroblock consensus
+//! behind it) in place of per-block signatures: a wrong checkpoint lets forged
+//! history through, so the mode is opt-in and blocks after the checkpoint are
+//! always fully validated.
+
+use qnet_state::{MacroBlock, MicroBlock, State as StateManager, StateSnapshot};
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+use std::sync::OnceLock;
+use crate::network_config::NetworkEnvironment;
+
+/// Microblocks covered by one macroblock
+const MICROBLOCKS_PER_MACROBLOCK: u64 = 90;
+
+/// Reveals a macroblock needs to count as finalized (matches macroblock validation)
+pub const MIN_FINALITY_REVEALS: usize = 3;
+
+/// Trusted checkpoint the node syncs forward from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrustedCheckpoint {
+    /// Macroblock index of the checkpoint
+    pub height: u64,
+    /// Expected hash of that macroblock
+    pub block_hash: [u8; 32],
+    /// Expected state root at that macroblock
+    pub state_root: [u8; 32],
+}
+
+impl TrustedCheckpoint {
+    /// Parse `height:block_hash_hex:state_root_hex`
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let parts: Vec<&str> = value.trim().split(':').collect();
+        if parts.len() != 3 {
+            return Err(format!("Checkpoint must be height:block_hash:state_root, got '{}'", value));
+        }
+
+        let height = parts[0].parse::<u64>()
+            .map_err(|e| format!("Invalid checkpoint height: {}", e))?;
+        Ok(Self {
+            height,
+            block_hash: parse_hash(parts[1], "block hash")?,
+            state_root: parse_hash(parts[2], "state root")?,
+        })
+    }
+
+    /// Checkpoint from `QNET_CHECKPOINT`, falling back to the network's baked-in value
+    pub fn configured(environment: &NetworkEnvironment) -> Result<Option<Self>, String> {
+        match std::env::var("QNET_CHECKPOINT") {
+            Ok(value) if !value.trim().is_empty() => Self::parse(&value).map(Some),
+            _ => Ok(Self::baked_in(environment)),
+        }
+    }
+
+    /// Checkpoint shipped with the release for a network
+    pub fn baked_in(environment: &NetworkEnvironment) -> Option<Self> {
+        // No checkpoints published yet - add one per network at release time
+        match environment {
+            NetworkEnvironment::Mainnet | NetworkEnvironment::Testnet | NetworkEnvironment::Local => None,
+        }
+    }
+
+    /// Last microblock covered by the checkpoint macroblock
+    pub fn microblock_height(&self) -> u64 {
+        self.height * MICROBLOCKS_PER_MACROBLOCK
+    }
+
+    /// Verify the fetched checkpoint macroblock matches the trusted values
+    pub fn verify_macroblock(&self, macroblock: &MacroBlock) -> Result<(), String> {
+        if macroblock.height != self.height {
+            return Err(format!("Checkpoint macroblock height {} != expected {}", macroblock.height, self.height));
+        }
+        let hash = macroblock.hash();
+        if hash != self.block_hash {
+            return Err(format!("Checkpoint macroblock #{} hash mismatch: expected {}, got {}",
+                               self.height, hex::encode(self.block_hash), hex::encode(hash)));
+        }
+        if macroblock.state_root != self.state_root {
+            return Err(format!("Checkpoint macroblock #{} state root does not match checkpoint", self.height));
+        }
+        Ok(())
+    }
+
+    /// Rebuild state from a snapshot, accepting it only if it matches the checkpoint state root
+    pub fn restore_state(&self, snapshot: &StateSnapshot) -> Result<StateManager, String> {
+        if snapshot.state_root != self.state_root {
+            return Err(format!("Snapshot state root {} does not match checkpoint {}",
+                               hex::encode(snapshot.state_root), hex::encode(self.state_root)));
+        }
+        // Recomputes the root from the snapshot contents, so tampered accounts are rejected too
+        StateManager::from_snapshot(snapshot).map_err(|e| format!("Invalid checkpoint snapshot: {}", e))
+    }
+}
+
+/// Signature-free validation for history finalized under a trusted checkpoint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FastSyncTrust {
+    checkpoint: TrustedCheckpoint,
+}
+
+impl FastSyncTrust {
+    pub fn new(checkpoint: TrustedCheckpoint) -> Self {
+        Self { checkpoint }
+    }
+
+    /// Enabled by `QNET_FAST_SYNC_TRUST_CHECKPOINT=1`; requires a configured checkpoint
+    pub fn configured(environment: &NetworkEnvironment) -> Result<Option<Self>, String> {
+        if std::env::var("QNET_FAST_SYNC_TRUST_CHECKPOINT").unwrap_or_default() != "1" {
+            return Ok(None);
+        }
+        match TrustedCheckpoint::configured(environment)? {
+            Some(checkpoint) => Ok(Some(Self::new(checkpoint))),
+            None => Err("QNET_FAST_SYNC_TRUST_CHECKPOINT requires a trusted checkpoint (QNET_CHECKPOINT)".to_string()),
+        }
+    }
+
+    pub fn checkpoint(&self) -> &TrustedCheckpoint {
+        &self.checkpoint
+    }
+
+    /// Whether a microblock lies in the trusted finalized range
+    pub fn covers(&self, microblock_height: u64) -> bool {
+        microblock_height > 0 && microblock_height <= self.checkpoint.microblock_height()
+    }
+
+    /// Verify that `macroblock` finalizes the microblock with these stored bytes
+    ///
+    /// The checkpoint macroblock must match the trusted hash; earlier macroblocks
+    /// need a consensus quorum of reveals. Either way the macroblock must commit
+    /// to the exact microblock hash.
+    pub fn verify_finality(&self, microblock_height: u64, microblock_data: &[u8], macroblock: &MacroBlock) -> Result<(), String> {
+        if !self.covers(microblock_height) {
+            return Err(format!("Block #{} is not covered by the trusted checkpoint", microblock_height));
+        }
+        let macroblock_index = microblock_height.div_ceil(MICROBLOCKS_PER_MACROBLOCK);
+        if macroblock.height != macroblock_index {
+            return Err(format!("Macroblock #{} does not finalize block #{}", macroblock.height, microblock_height));
+        }
+
+        if macroblock_index == self.checkpoint.height {
+            self.checkpoint.verify_macroblock(macroblock)?;
+        } else if macroblock.consensus_data.reveals.len() < MIN_FINALITY_REVEALS {
+            return Err(format!("Macroblock #{} has {} reveals, need {}",
+                               macroblock_index, macroblock.consensus_data.reveals.len(), MIN_FINALITY_REVEALS));
+        }
+
+        let position = ((microblock_height - 1) % MICROBLOCKS_PER_MACROBLOCK) as usize;
+        let hash: [u8; 32] = Sha3_256::digest(microblock_data).into();
+        if macroblock.micro_blocks.get(position) != Some(&hash) {
+            return Err(format!("Macroblock #{} does not commit to block #{}", macroblock_index, microblock_height));
+        }
+        Ok(())
+    }
+}
+
+static FAST_SYNC_TRUST: OnceLock<Option<FastSyncTrust>> = OnceLock::new();
+
+/// Active fast-sync trust, read once from the environment
+pub fn fast_sync_trust() -> Option<&'static FastSyncTrust> {
+    FAST_SYNC_TRUST.get_or_init(|| {
+        match FastSyncTrust::configured(&crate::network_config::get_network_config().environment) {
+            Ok(Some(trust)) => {
+                println!("[CHECKPOINT] ⚡ Fast sync: skipping signature checks up to block #{} (trusting checkpoint)",
+                         trust.checkpoint.microblock_height());
+                Some(trust)
+            }
+            Ok(None) => None,
+            Err(e) => {
+                println!("[CHECKPOINT] ⚠️ Fast sync disabled: {}", e);
+                None
+            }
+        }
+    }).as_ref()
+}
+
+/// Validate a consecutive range of stored microblocks
+///
+/// Hash linkage is always checked. Signatures are skipped for blocks the trust
+/// covers when their macroblock proves finality; otherwise (no trust, no proof,
+/// or past the checkpoint) `verify_signature` is called. Returns the number of
+/// signatures verified.
+pub fn validate_history_range(
+    blocks: &[Vec<u8>],
+    trust: Option<&FastSyncTrust>,
+    macroblock_at: &dyn Fn(u64) -> Option<MacroBlock>,
+    verify_signature: &mut dyn FnMut(&MicroBlock) -> bool,
+) -> Result<usize, String> {
+    let mut verified = 0;
+    let mut previous: Option<(u64, [u8; 32])> = None;
+
+    for data in blocks {
+        let block: MicroBlock = bincode::deserialize(data)
+            .map_err(|e| format!("Failed to deserialize microblock: {}", e))?;
+
+        if let Some((previous_height, previous_hash)) = previous {
+            if block.height != previous_height + 1 {
+                return Err(format!("Block #{} does not follow block #{}", block.height, previous_height));
+            }
+            if block.previous_hash != previous_hash {
+                return Err(format!("Block #{} has invalid previous_hash (mismatch with block #{})",
+                                   block.height, previous_height));
+            }
+        }
+
+        let finalized = trust
+            .filter(|trust| trust.covers(block.height))
+            .and_then(|trust| {
+                let macroblock = macroblock_at(block.height.div_ceil(MICROBLOCKS_PER_MACROBLOCK))?;
+                trust.verify_finality(block.height, data, &macroblock).ok()
+            })
+            .is_some();
+        if !finalized {
+            verified += 1;
+            if !verify_signature(&block) {
+                return Err(format!("Invalid signature on block #{} from producer {}", block.height, block.producer));
+            }
+        }
+
+        previous = Some((block.height, Sha3_256::digest(data).into()));
+    }
+    Ok(verified)
+}
+
+fn parse_hash(value: &str, what: &str) -> Result<[u8; 32], String> {
+    let bytes = hex::decode(value).map_err(|e| format!("Invalid checkpoint {}: {}", what, e))?;
+    bytes.try_into().map_err(|_| format!("Checkpoint {} must be 32 bytes", what))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use qnet_state::{Account, ConsensusData};
+    use std::collections::HashMap;
+
+    fn checkpoint_fixture() -> (TrustedCheckpoint, MacroBlock, StateSnapshot) {
+        let state = StateManager::new();
+        for (address, balance) in [("alice", 1_000u64), ("bob", 250)] {
+            let mut account = Account::new(address.to_string());
+            account.balance = balance;
+            state.update_account(address.to_string(), account);
+        }
+        let snapshot = state.export_full();
+
+        let macroblock = MacroBlock::new(
+            12,
+            1704067200 + 12 * 90,
+            [7u8; 32],
+            (0..90u8).map(|i| [i; 32]).collect(),
+            snapshot.state_root,
+            ConsensusData { commits: HashMap::new(), reveals: HashMap::new(), next_leader: "validator_1".to_string() },
+        );
+        let checkpoint = TrustedCheckpoint {
+            height: 12,
+            block_hash: macroblock.hash(),
+            state_root: snapshot.state_root,
+        };
+        (checkpoint, macroblock, snapshot)
+    }
+
+    #[test]
+    fn test_checkpoint_accepts_matching_block_and_snapshot() {
+        let (checkpoint, macroblock, snapshot) = checkpoint_fixture();
+
+        let encoded = format!("12:{}:{}", hex::encode(checkpoint.block_hash), hex::encode(checkpoint.state_root));
+        assert_eq!(TrustedCheckpoint::parse(&encoded).unwrap(), checkpoint);
+
+        checkpoint.verify_macroblock(&macroblock).unwrap();
+        let state = checkpoint.restore_state(&snapshot).unwrap();
+        assert_eq!(state.get_balance("alice"), 1_000);
+        assert_eq!(checkpoint.microblock_height(), 1080);
+    }
+
+    #[test]
+    fn test_checkpoint_rejects_tampered_snapshot_and_block() {
+        let (checkpoint, mut macroblock, snapshot) = checkpoint_fixture();
+
+        // Snapshot for different state: state root does not match the checkpoint
+        let mut wrong_root = snapshot.clone();
+        wrong_root.state_root = [0xAB; 32];
+        let err = checkpoint.restore_state(&wrong_root).err().unwrap();
+        assert!(err.contains("does not match checkpoint"), "unexpected error: {}", err);
+
+        // Snapshot claiming the checkpoint root with altered balances
+        let mut tampered = snapshot.clone();
+        tampered.accounts[0].balance += 1;
+        assert!(checkpoint.restore_state(&tampered).is_err());
+
+        // Fetched block with a different hash rejects startup
+        macroblock.timestamp += 1;
+        assert!(checkpoint.verify_macroblock(&macroblock).unwrap_err().contains("hash mismatch"));
+    }
+
+    /// 180 linked microblocks, the two macroblocks finalizing them, and a checkpoint at macroblock #2
+    fn finalized_history() -> (Vec<Vec<u8>>, Vec<MacroBlock>, TrustedCheckpoint) {
+        let mut blocks = Vec::new();
+        let mut previous_hash = [0u8; 32];
+        for height in 1..=180u64 {
+            let block = MicroBlock {
+                height,
+                timestamp: 1704067200 + height,
+                transactions: Vec::new(),
+                producer: format!("producer_{}", height % 5),
+                signature: vec![0u8; 64],
+                previous_hash,
+                merkle_root: [0u8; 32],
+                poh_hash: vec![0u8; 64],
+                poh_count: height,
+            };
+            let data = bincode::serialize(&block).unwrap();
+            previous_hash = Sha3_256::digest(&data).into();
+            blocks.push(data);
+        }
+
+        let reveals: HashMap<String, Vec<u8>> = (0..3).map(|i| (format!("validator_{}", i), vec![i])).collect();
+        let macroblocks: Vec<MacroBlock> = (1..=2u64).map(|index| {
+            let range = ((index - 1) * 90) as usize..(index * 90) as usize;
+            MacroBlock::new(
+                index,
+                1704067200 + index * 90,
+                [index as u8; 32],
+                blocks[range].iter().map(|data| Sha3_256::digest(data).into()).collect(),
+                [9u8; 32],
+                ConsensusData { commits: HashMap::new(), reveals: reveals.clone(), next_leader: "validator_0".to_string() },
+            )
+        }).collect();
+        let checkpoint = TrustedCheckpoint { height: 2, block_hash: macroblocks[1].hash(), state_root: [9u8; 32] };
+        (blocks, macroblocks, checkpoint)
+    }
+
+    #[test]
+    fn test_fast_sync_skips_signatures_but_checks_linkage() {
+        let (mut blocks, macroblocks, checkpoint) = finalized_history();
+        let trust = FastSyncTrust::new(checkpoint);
+        let macroblock_at = |index: u64| macroblocks.get(index as usize - 1).cloned();
+        // Stand-in for Dilithium verification cost
+        let mut slow_verify = |_: &MicroBlock| {
+            std::thread::sleep(std::time::Duration::from_millis(1));
+            true
+        };
+
+        let started = std::time::Instant::now();
+        assert_eq!(validate_history_range(&blocks, None, &macroblock_at, &mut slow_verify).unwrap(), 180);
+        let full = started.elapsed();
+
+        let started = std::time::Instant::now();
+        assert_eq!(validate_history_range(&blocks, Some(&trust), &macroblock_at, &mut slow_verify).unwrap(), 0);
+        assert!(started.elapsed() < full, "fast mode should be faster than full validation");
+
+        // Without a finality proof the range falls back to signature checks
+        assert_eq!(validate_history_range(&blocks, Some(&trust), &|_| None, &mut slow_verify).unwrap(), 180);
+
+        // A broken parent link is still rejected in fast mode
+        let mut block: MicroBlock = bincode::deserialize(&blocks[50]).unwrap();
+        block.previous_hash = [0xEE; 32];
+        blocks[50] = bincode::serialize(&block).unwrap();
+        let err = validate_history_range(&blocks, Some(&trust), &macroblock_at, &mut |_| true).unwrap_err();
+        assert!(err.contains("invalid previous_hash"), "unexpected error: {}", err);
+    }
+
+    #[test]
+    fn test_fast_sync_finality_proof() {
+        let (blocks, mut macroblocks, checkpoint) = finalized_history();
+        let trust = FastSyncTrust::new(checkpoint);
+
+        assert!(trust.covers(180) && !trust.covers(181) && !trust.covers(0));
+        trust.verify_finality(45, &blocks[44], &macroblocks[0]).unwrap();
+        trust.verify_finality(180, &blocks[179], &macroblocks[1]).unwrap();
+
+        // Macroblock must commit to the exact block bytes
+        assert!(trust.verify_finality(46, &blocks[44], &macroblocks[0]).is_err());
+        // Earlier macroblocks need a reveal quorum
+        macroblocks[0].consensus_data.reveals.clear();
+        assert!(trust.verify_finality(45, &blocks[44], &macroblocks[0]).unwrap_err().contains("reveals"));
+        // Checkpoint macroblock must match the trusted hash
+        macroblocks[1].timestamp += 1;
+        assert!(trust.verify_finality(180, &blocks[179], &macroblocks[1]).unwrap_err().contains("hash mismatch"));
+    }
+}
@@ -0,0 +1,198 @@
+//! Consensus message dedup and replay guard
+//!
+//! Commit and reveal messages reach the consensus engine from any peer, possibly
+//! many times over through gossip. Before a message is forwarded, the guard checks
+//! it against the current macroblock round:
+//! - messages for rounds more than `window_rounds` rounds away are stale (or from
+//!   the far future) and rejected, so old commits cannot be replayed
+//! - a participant gets one commit and one reveal per round: the same message again
+//!   is a duplicate and ignored, a different one is equivocation and flagged with
+//!   both payload digests as evidence
+//!
+//! | Variable | Effect |
+//! |---|---|
+//! | `QNET_CONSENSUS_ROUND_WINDOW` | rounds accepted on either side of the current one (default 2) |
+
+use std::collections::{HashMap, VecDeque};
+
+use serde::Serialize;
+use sha3::{Digest, Sha3_256};
+
+/// Blocks per macroblock consensus round
+pub const ROUND_BLOCKS: u64 = 90;
+
+/// Default number of rounds accepted on either side of the current round
+pub const DEFAULT_ROUND_WINDOW: u64 = 2;
+
+/// Equivocation evidence kept for inspection
+const MAX_EVIDENCE: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsensusPhase {
+    Commit,
+    Reveal,
+}
+
+/// Two different messages from one participant for the same round and phase
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EquivocationEvidence {
+    pub round: u64,
+    pub participant: String,
+    pub phase: ConsensusPhase,
+    /// SHA3-256 of the first accepted payload, hex
+    pub first_digest: String,
+    /// SHA3-256 of the conflicting payload, hex
+    pub conflicting_digest: String,
+}
+
+/// What to do with an incoming consensus message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GuardVerdict {
+    /// First message from this participant for the round and phase: forward it
+    Accept,
+    /// Same message seen before: drop it
+    Duplicate,
+    /// Conflicting message: drop it and penalize the participant
+    Equivocation(EquivocationEvidence),
+    /// Round outside the accepted window: drop it
+    OutOfWindow { round: u64, current_round: u64 },
+}
+
+/// Remembers one payload digest per `(round, participant, phase)`
+#[derive(Debug, Clone)]
+pub struct ConsensusGuard {
+    window_rounds: u64,
+    seen: HashMap<(u64, String, ConsensusPhase), [u8; 32]>,
+    evidence: VecDeque<EquivocationEvidence>,
+}
+
+impl Default for ConsensusGuard {
+    fn default() -> Self {
+        Self::new(DEFAULT_ROUND_WINDOW)
+    }
+}
+
+impl ConsensusGuard {
+    pub fn new(window_rounds: u64) -> Self {
+        Self {
+            window_rounds,
+            seen: HashMap::new(),
+            evidence: VecDeque::new(),
+        }
+    }
+
+    /// Guard with a `QNET_CONSENSUS_ROUND_WINDOW` override
+    pub fn from_env() -> Self {
+        let window_rounds = std::env::var("QNET_CONSENSUS_ROUND_WINDOW")
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .unwrap_or(DEFAULT_ROUND_WINDOW);
+        Self::new(window_rounds)
+    }
+
+    /// Consensus round (macroblock height) for a local chain height
+    pub fn current_round(height: u64) -> u64 {
+        height - height % ROUND_BLOCKS
+    }
+
+    /// Check a message for `round` against the round at local `height`, recording it if accepted
+    pub fn check(&mut self, round: u64, participant: &str, phase: ConsensusPhase, payload: &[u8], height: u64) -> GuardVerdict {
+        let current_round = Self::current_round(height);
+        let window = self.window_rounds.saturating_mul(ROUND_BLOCKS);
+        if round.abs_diff(current_round) > window {
+            return GuardVerdict::OutOfWindow { round, current_round };
+        }
+
+        // Rounds that fell out of the window can no longer be accepted: forget them
+        let oldest = current_round.saturating_sub(window);
+        self.seen.retain(|(seen_round, _, _), _| *seen_round >= oldest);
+
+        let digest: [u8; 32] = Sha3_256::digest(payload).into();
+        let key = (round, participant.to_string(), phase);
+        match self.seen.get(&key) {
+            None => {
+                self.seen.insert(key, digest);
+                GuardVerdict::Accept
+            }
+            Some(first) if *first == digest => GuardVerdict::Duplicate,
+            Some(first) => {
+                let evidence = EquivocationEvidence {
+                    round,
+                    participant: participant.to_string(),
+                    phase,
+                    first_digest: hex::encode(first),
+                    conflicting_digest: hex::encode(digest),
+                };
+                if self.evidence.len() == MAX_EVIDENCE {
+                    self.evidence.pop_front();
+                }
+                self.evidence.push_back(evidence.clone());
+                GuardVerdict::Equivocation(evidence)
+            }
+        }
+    }
+
+    /// Recorded equivocations, oldest first
+    pub fn evidence(&self) -> impl Iterator<Item = &EquivocationEvidence> {
+        self.evidence.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEIGHT: u64 = 9_045; // round 9_000
+
+    #[test]
+    fn test_duplicate_message_ignored() {
+        let mut guard = ConsensusGuard::new(2);
+        assert_eq!(guard.check(9_000, "node_a", ConsensusPhase::Commit, b"commit_1", HEIGHT), GuardVerdict::Accept);
+        assert_eq!(guard.check(9_000, "node_a", ConsensusPhase::Commit, b"commit_1", HEIGHT), GuardVerdict::Duplicate);
+
+        // Other phase, participant or round are separate
+        assert_eq!(guard.check(9_000, "node_a", ConsensusPhase::Reveal, b"commit_1", HEIGHT), GuardVerdict::Accept);
+        assert_eq!(guard.check(9_000, "node_b", ConsensusPhase::Commit, b"commit_1", HEIGHT), GuardVerdict::Accept);
+        assert_eq!(guard.check(9_090, "node_a", ConsensusPhase::Commit, b"commit_1", HEIGHT), GuardVerdict::Accept);
+        assert_eq!(guard.evidence().count(), 0);
+    }
+
+    #[test]
+    fn test_equivocating_message_flagged() {
+        let mut guard = ConsensusGuard::new(2);
+        guard.check(9_000, "node_a", ConsensusPhase::Reveal, b"reveal_1", HEIGHT);
+
+        let GuardVerdict::Equivocation(evidence) = guard.check(9_000, "node_a", ConsensusPhase::Reveal, b"reveal_2", HEIGHT) else {
+            panic!("conflicting reveal not flagged");
+        };
+        assert_eq!((evidence.round, evidence.participant.as_str(), evidence.phase), (9_000, "node_a", ConsensusPhase::Reveal));
+        assert_eq!(evidence.first_digest, hex::encode(Sha3_256::digest(b"reveal_1")));
+        assert_eq!(evidence.conflicting_digest, hex::encode(Sha3_256::digest(b"reveal_2")));
+        assert_eq!(guard.evidence().collect::<Vec<_>>(), vec![&evidence]);
+
+        // The first message stays the accepted one
+        assert_eq!(guard.check(9_000, "node_a", ConsensusPhase::Reveal, b"reveal_1", HEIGHT), GuardVerdict::Duplicate);
+    }
+
+    #[test]
+    fn test_stale_round_message_rejected() {
+        let mut guard = ConsensusGuard::new(2);
+        assert_eq!(
+            guard.check(8_730, "node_a", ConsensusPhase::Commit, b"old", HEIGHT),
+            GuardVerdict::OutOfWindow { round: 8_730, current_round: 9_000 }
+        );
+        assert_eq!(
+            guard.check(9_270, "node_a", ConsensusPhase::Commit, b"future", HEIGHT),
+            GuardVerdict::OutOfWindow { round: 9_270, current_round: 9_000 }
+        );
+        assert_eq!(guard.check(8_820, "node_a", ConsensusPhase::Commit, b"recent", HEIGHT), GuardVerdict::Accept);
+
+        // Once the chain moves on, the round is out of the window and its entry forgotten
+        assert!(matches!(
+            guard.check(8_820, "node_a", ConsensusPhase::Commit, b"recent", HEIGHT + 3 * ROUND_BLOCKS),
+            GuardVerdict::OutOfWindow { .. }
+        ));
+        assert!(guard.seen.is_empty());
+    }
+}
@@ -0,0 +1,261 @@
+//! DHT bootstrap watchdog
+//!
+//! Regular (non-Genesis) nodes find peers through DHT discovery and peer exchange
+//! instead of dialing the Genesis bootstrap peers. If discovery has not produced
+//! `min_peers` peers within the bootstrap timeout, the node falls back to the
+//! configured bootstrap peers (`QNET_BOOTSTRAP_PEERS`) so it is not left isolated,
+//! and keeps retrying DHT discovery periodically. Once enough peers are known the
+//! fallback is cleared.
+//!
+//! | Variable | Effect |
+//! |---|---|
+//! | `QNET_DHT_BOOTSTRAP_TIMEOUT_SECS` | time DHT gets before falling back (default 60) |
+//! | `QNET_DHT_MIN_PEERS` | peer count below which the node is considered isolated (default 3) |
+//! | `QNET_DHT_RETRY_SECS` | DHT retry interval while falling back (default 300) |
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::unified_p2p::SimplifiedP2P;
+
+pub const DEFAULT_DHT_BOOTSTRAP_TIMEOUT_SECS: u64 = 60;
+pub const DEFAULT_DHT_MIN_PEERS: usize = 3;
+pub const DEFAULT_DHT_RETRY_SECS: u64 = 300;
+
+/// How often the watchdog checks the peer count
+const WATCHDOG_POLL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DhtBootstrapConfig {
+    pub timeout: Duration,
+    pub min_peers: usize,
+    pub retry_interval: Duration,
+}
+
+impl Default for DhtBootstrapConfig {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(DEFAULT_DHT_BOOTSTRAP_TIMEOUT_SECS),
+            min_peers: DEFAULT_DHT_MIN_PEERS,
+            retry_interval: Duration::from_secs(DEFAULT_DHT_RETRY_SECS),
+        }
+    }
+}
+
+impl DhtBootstrapConfig {
+    pub fn from_env() -> Self {
+        let var = |name: &str| std::env::var(name).ok().and_then(|v| v.trim().parse::<u64>().ok());
+        let defaults = Self::default();
+        Self {
+            timeout: var("QNET_DHT_BOOTSTRAP_TIMEOUT_SECS").map_or(defaults.timeout, Duration::from_secs),
+            min_peers: var("QNET_DHT_MIN_PEERS").map_or(defaults.min_peers, |n| n as usize),
+            retry_interval: var("QNET_DHT_RETRY_SECS").map_or(defaults.retry_interval, Duration::from_secs),
+        }
+    }
+}
+
+/// Peer discovery state for monitoring
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DiscoveryStatus {
+    /// Peers known at the last check
+    pub dht_peers: usize,
+    /// Bootstrap peers were dialed because DHT discovery came up short
+    pub bootstrap_fallback_active: bool,
+}
+
+/// What the watchdog did on a check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DhtBootstrapAction {
+    /// Enough peers
+    Healthy,
+    /// Below the minimum, still within the bootstrap timeout
+    Waiting,
+    /// Timeout passed: bootstrap peers were dialed
+    FellBack,
+    /// Falling back and the retry interval passed: DHT discovery rerun
+    RetriedDht,
+    /// Falling back, nothing to do until the next retry
+    FallbackActive,
+}
+
+/// Peer source the watchdog drives
+pub trait PeerDiscovery {
+    /// Peers currently known
+    fn known_peer_count(&self) -> usize;
+
+    /// Rerun DHT discovery
+    fn retry_dht(&self);
+
+    /// Dial the bootstrap peers directly
+    fn connect_bootstrap(&self, bootstrap_peers: &[String]);
+}
+
+impl PeerDiscovery for SimplifiedP2P {
+    fn known_peer_count(&self) -> usize {
+        self.get_peer_count()
+    }
+
+    fn retry_dht(&self) {
+        self.search_internet_peers();
+    }
+
+    fn connect_bootstrap(&self, bootstrap_peers: &[String]) {
+        self.add_discovered_peers(bootstrap_peers);
+        let initial_peers = self.get_discovery_peers();
+        if !initial_peers.is_empty() {
+            self.start_peer_exchange_protocol(initial_peers);
+        }
+    }
+}
+
+/// Falls back to bootstrap peers when DHT discovery leaves the node isolated
+#[derive(Debug)]
+pub struct DhtBootstrapWatchdog {
+    config: DhtBootstrapConfig,
+    started: Instant,
+    last_dht_attempt: Instant,
+    status: DiscoveryStatus,
+}
+
+impl DhtBootstrapWatchdog {
+    /// Watchdog for a node whose DHT discovery started at `started`
+    pub fn new(config: DhtBootstrapConfig, started: Instant) -> Self {
+        Self {
+            config,
+            started,
+            last_dht_attempt: started,
+            status: DiscoveryStatus::default(),
+        }
+    }
+
+    pub fn status(&self) -> DiscoveryStatus {
+        self.status.clone()
+    }
+
+    /// Check the peer count at `now` and fall back or retry DHT as needed
+    pub fn check(&mut self, discovery: &dyn PeerDiscovery, bootstrap_peers: &[String], now: Instant) -> DhtBootstrapAction {
+        let peers = discovery.known_peer_count();
+        self.status.dht_peers = peers;
+
+        if peers >= self.config.min_peers {
+            if self.status.bootstrap_fallback_active {
+                println!("[DHT] ✅ {} peers known, bootstrap fallback cleared", peers);
+                self.status.bootstrap_fallback_active = false;
+            }
+            return DhtBootstrapAction::Healthy;
+        }
+
+        if !self.status.bootstrap_fallback_active {
+            if now.duration_since(self.started) < self.config.timeout {
+                return DhtBootstrapAction::Waiting;
+            }
+            println!("[DHT] ⚠️ Only {} peers after {}s of DHT discovery (need {}): falling back to {} bootstrap peers",
+                     peers, self.config.timeout.as_secs(), self.config.min_peers, bootstrap_peers.len());
+            self.status.bootstrap_fallback_active = true;
+            self.last_dht_attempt = now;
+            discovery.connect_bootstrap(bootstrap_peers);
+            return DhtBootstrapAction::FellBack;
+        }
+
+        if now.duration_since(self.last_dht_attempt) >= self.config.retry_interval {
+            println!("[DHT] 🔄 Retrying DHT discovery ({} peers, bootstrap fallback active)", peers);
+            self.last_dht_attempt = now;
+            discovery.retry_dht();
+            return DhtBootstrapAction::RetriedDht;
+        }
+        DhtBootstrapAction::FallbackActive
+    }
+}
+
+/// Run the watchdog for a regular node until the process exits
+pub fn spawn_dht_bootstrap_watchdog(p2p: Arc<SimplifiedP2P>, config: DhtBootstrapConfig, bootstrap_peers: Vec<String>) {
+    println!("[DHT] ⏱️ Bootstrap timeout {}s, minimum {} peers, retry every {}s",
+             config.timeout.as_secs(), config.min_peers, config.retry_interval.as_secs());
+    let mut watchdog = DhtBootstrapWatchdog::new(config, Instant::now());
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(WATCHDOG_POLL);
+        loop {
+            interval.tick().await;
+            watchdog.check(p2p.as_ref(), &bootstrap_peers, Instant::now());
+            p2p.set_discovery_status(watchdog.status());
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// DHT that never finds anyone; dialing bootstrap peers connects to them
+    #[derive(Default)]
+    struct FailingDht {
+        peers: Mutex<Vec<String>>,
+        dht_retries: Mutex<usize>,
+    }
+
+    impl PeerDiscovery for FailingDht {
+        fn known_peer_count(&self) -> usize {
+            self.peers.lock().unwrap().len()
+        }
+
+        fn retry_dht(&self) {
+            *self.dht_retries.lock().unwrap() += 1;
+        }
+
+        fn connect_bootstrap(&self, bootstrap_peers: &[String]) {
+            self.peers.lock().unwrap().extend_from_slice(bootstrap_peers);
+        }
+    }
+
+    fn config() -> DhtBootstrapConfig {
+        DhtBootstrapConfig {
+            timeout: Duration::from_secs(60),
+            min_peers: 3,
+            retry_interval: Duration::from_secs(300),
+        }
+    }
+
+    #[test]
+    fn test_failing_dht_falls_back_to_bootstrap_after_timeout() {
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        let dht = FailingDht::default();
+        let bootstrap: Vec<String> = (1..=2).map(|i| format!("192.0.2.{}:8001", i)).collect();
+        let mut watchdog = DhtBootstrapWatchdog::new(config(), start);
+
+        assert_eq!(watchdog.check(&dht, &bootstrap, at(30)), DhtBootstrapAction::Waiting);
+        assert_eq!(watchdog.status(), DiscoveryStatus { dht_peers: 0, bootstrap_fallback_active: false });
+        assert_eq!(dht.known_peer_count(), 0);
+
+        assert_eq!(watchdog.check(&dht, &bootstrap, at(60)), DhtBootstrapAction::FellBack);
+        assert_eq!(*dht.peers.lock().unwrap(), bootstrap);
+        assert!(watchdog.status().bootstrap_fallback_active);
+
+        // Two bootstrap peers are still below the minimum: DHT is retried on schedule
+        assert_eq!(watchdog.check(&dht, &bootstrap, at(65)), DhtBootstrapAction::FallbackActive);
+        assert_eq!(watchdog.status(), DiscoveryStatus { dht_peers: 2, bootstrap_fallback_active: true });
+        assert_eq!(watchdog.check(&dht, &bootstrap, at(360)), DhtBootstrapAction::RetriedDht);
+        assert_eq!(watchdog.check(&dht, &bootstrap, at(400)), DhtBootstrapAction::FallbackActive);
+        assert_eq!(*dht.dht_retries.lock().unwrap(), 1);
+
+        // Enough peers again clears the fallback
+        dht.peers.lock().unwrap().push("198.51.100.7:8001".to_string());
+        assert_eq!(watchdog.check(&dht, &bootstrap, at(410)), DhtBootstrapAction::Healthy);
+        assert_eq!(watchdog.status(), DiscoveryStatus { dht_peers: 3, bootstrap_fallback_active: false });
+    }
+
+    #[test]
+    fn test_dht_with_enough_peers_never_falls_back() {
+        let start = Instant::now();
+        let dht = FailingDht::default();
+        dht.peers.lock().unwrap().extend((1..=3).map(|i| format!("198.51.100.{}:8001", i)));
+        let mut watchdog = DhtBootstrapWatchdog::new(config(), start);
+
+        assert_eq!(watchdog.check(&dht, &["192.0.2.1:8001".to_string()], start + Duration::from_secs(600)), DhtBootstrapAction::Healthy);
+        assert_eq!(dht.known_peer_count(), 3);
+        assert!(!watchdog.status().bootstrap_fallback_active);
+    }
+}
@@ -53,6 +53,9 @@ pub enum IntegrationError {
     #[error("Rate limit exceeded: {0}")]
     RateLimitExceeded(String),
     
+    #[error("Sharding error: {0}")]
+    ShardingError(qnet_sharding::ShardingError),
+    
     #[error("Other error: {0}")]
     Other(String),
 }
@@ -112,6 +115,12 @@ impl From<qnet_mempool::MempoolError> for QNetError {
     }
 }
 
+impl From<crate::node_config::ConfigError> for QNetError {
+    fn from(err: crate::node_config::ConfigError) -> Self {
+        QNetError::InvalidInput(err.to_string())
+    }
+}
+
 impl From<qnet_mempool::MempoolError> for IntegrationError {
     fn from(err: qnet_mempool::MempoolError) -> Self {
         IntegrationError::MempoolError(err.to_string())
@@ -124,6 +133,22 @@ impl From<crate::validator::ValidationError> for IntegrationError {
     }
 }
 
+impl From<crate::activation_validation::ActivationError> for IntegrationError {
+    fn from(err: crate::activation_validation::ActivationError) -> Self {
+        IntegrationError::ValidationError(err.to_string())
+    }
+}
+
+impl From<qnet_sharding::ShardingError> for IntegrationError {
+    fn from(err: qnet_sharding::ShardingError) -> Self {
+        match err {
+            qnet_sharding::ShardingError::InvalidAddress(msg) => IntegrationError::ValidationError(msg),
+            qnet_sharding::ShardingError::AccountNotFound(addr) => IntegrationError::AccountNotFound(addr),
+            other => IntegrationError::ShardingError(other),
+        }
+    }
+}
+
 impl From<rocksdb::Error> for IntegrationError {
     fn from(err: rocksdb::Error) -> Self {
         IntegrationError::StorageError(err.to_string())
@@ -159,6 +184,12 @@ impl From<IntegrationError> for QNetError {
     }
 }
 
+impl From<crate::activation_validation::ActivationError> for QNetError {
+    fn from(err: crate::activation_validation::ActivationError) -> Self {
+        QNetError::ValidationError(err.to_string())
+    }
+}
+
 impl From<qnet_state::StateError> for QNetError {
     fn from(err: qnet_state::StateError) -> Self {
         QNetError::StateError(err.to_string())
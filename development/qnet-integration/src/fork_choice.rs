@@ -0,0 +1,110 @@
+//! Deterministic fork choice for competing microblock chains
+//!
+//! When two blocks exist for the same height, every honest node must pick the
+//! same branch without a network round-trip. Branches are compared by the sum
+//! of their producers' reputations over the disputed range, tie-broken by the
+//! lowest block hash at the fork point. Finalized heights never switch.
+
+/// Block in a disputed range
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForkBlock {
+    pub producer: String,
+    pub hash: [u8; 32],
+}
+
+impl ForkBlock {
+    pub fn from_microblock(block: &qnet_state::MicroBlock) -> Self {
+        Self {
+            producer: block.producer.clone(),
+            hash: block.hash(),
+        }
+    }
+}
+
+/// Outcome of comparing the local branch with a competing one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForkChoice {
+    KeepLocal,
+    SwitchToCompeting,
+}
+
+/// Branch weight: producer reputations summed in fixed point (0.01 units),
+/// so floating point rounding cannot make nodes disagree
+pub fn branch_weight(branch: &[ForkBlock], reputation: &dyn Fn(&str) -> f64) -> u64 {
+    branch.iter()
+        .map(|block| {
+            let rep = reputation(&block.producer);
+            if rep.is_finite() { (rep.clamp(0.0, 100.0) * 100.0).round() as u64 } else { 0 }
+        })
+        .sum()
+}
+
+/// Choose between the local and competing branches starting at the same fork height
+/// Switches only if the competing branch strictly wins
+pub fn choose_fork(
+    local: &[ForkBlock],
+    competing: &[ForkBlock],
+    fork_point_finalized: bool,
+    reputation: &dyn Fn(&str) -> f64,
+) -> ForkChoice {
+    // Macroblock finality is the ultimate tiebreak: never reorg a finalized height
+    if fork_point_finalized || competing.is_empty() {
+        return ForkChoice::KeepLocal;
+    }
+    if local.is_empty() {
+        return ForkChoice::SwitchToCompeting;
+    }
+
+    let local_weight = branch_weight(local, reputation);
+    let competing_weight = branch_weight(competing, reputation);
+    let competing_wins = competing_weight > local_weight
+        || (competing_weight == local_weight && competing[0].hash < local[0].hash);
+
+    if competing_wins {
+        ForkChoice::SwitchToCompeting
+    } else {
+        ForkChoice::KeepLocal
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(producer: &str, hash_byte: u8) -> ForkBlock {
+        ForkBlock { producer: producer.to_string(), hash: [hash_byte; 32] }
+    }
+
+    fn reputation(node_id: &str) -> f64 {
+        match node_id {
+            "genesis_node_001" => 95.0,
+            "genesis_node_002" => 80.0,
+            "node_low" => 40.0,
+            _ => 70.0,
+        }
+    }
+
+    #[test]
+    fn test_higher_cumulative_reputation_wins() {
+        let strong = vec![block("genesis_node_001", 0x90), block("genesis_node_002", 0x91)];
+        let weak = vec![block("node_low", 0x10), block("genesis_node_002", 0x11)];
+
+        // Same answer regardless of which branch a node happens to hold locally
+        assert_eq!(choose_fork(&weak, &strong, false, &reputation), ForkChoice::SwitchToCompeting);
+        assert_eq!(choose_fork(&strong, &weak, false, &reputation), ForkChoice::KeepLocal);
+
+        // Finalized fork point is never reorganized
+        assert_eq!(choose_fork(&weak, &strong, true, &reputation), ForkChoice::KeepLocal);
+    }
+
+    #[test]
+    fn test_equal_weight_tie_broken_by_lowest_hash() {
+        let low_hash = vec![block("node_a", 0x01)];
+        let high_hash = vec![block("node_b", 0xFF)];
+
+        assert_eq!(choose_fork(&high_hash, &low_hash, false, &reputation), ForkChoice::SwitchToCompeting);
+        assert_eq!(choose_fork(&low_hash, &high_hash, false, &reputation), ForkChoice::KeepLocal);
+        // Identical branch never switches
+        assert_eq!(choose_fork(&low_hash, &low_hash, false, &reputation), ForkChoice::KeepLocal);
+    }
+}
@@ -66,6 +66,8 @@ pub fn create_genesis_block(config: GenesisConfig) -> IntegrationResult<Block> {
             initial_balance: 0, // Starts empty - Pool 1 emission happens every 4 hours
         },
         data: Some("System rewards pool for lazy rewards distribution".to_string()),
+        chain_id: qnet_state::transaction::UNBOUND_CHAIN_ID,
+        valid_until: None,
     };
     transactions.push(rewards_pool_tx);
     
@@ -88,6 +90,8 @@ pub fn create_genesis_block(config: GenesisConfig) -> IntegrationResult<Block> {
                 amount,
             },
             data: Some(format!("Genesis allocation to {}", address)),
+            chain_id: qnet_state::transaction::UNBOUND_CHAIN_ID,
+            valid_until: None,
         };
         transactions.push(tx);
     }
@@ -0,0 +1,220 @@
+//! Per-peer invalid-block scores
+//!
+//! Every invalid block adds 1.0 to the sending peer's score, and scores halve
+//! every half-life, so an old mistake fades instead of condemning a peer
+//! forever. The tracked set is bounded: when full, the cleanest peer (lowest
+//! decayed score, least recently seen on ties) is evicted. Scores are saved to
+//! storage on shutdown and restored on startup.
+
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+
+/// Storage key for the persisted scores
+pub const INVALID_BLOCK_SCORES_KEY: &str = "invalid_block_scores";
+
+/// Default half-life of an invalid-block score: 1 hour
+pub const DEFAULT_INVALID_BLOCK_HALF_LIFE_SECS: u64 = 60 * 60;
+
+/// Default cap on tracked peers
+pub const DEFAULT_MAX_TRACKED_PEERS: usize = 1000;
+
+/// Scores below this are dropped when pruning
+const NEGLIGIBLE_SCORE: f64 = 0.01;
+
+/// Decaying score of one peer, as of `updated_at`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct InvalidBlockScore {
+    pub score: f64,
+    pub updated_at: u64,
+}
+
+impl InvalidBlockScore {
+    fn decayed(&self, now_secs: u64, half_life_secs: u64) -> f64 {
+        let elapsed = now_secs.saturating_sub(self.updated_at) as f64;
+        self.score * 0.5f64.powf(elapsed / half_life_secs as f64)
+    }
+}
+
+/// Bounded, time-decaying invalid-block scores keyed by peer
+#[derive(Debug, Clone)]
+pub struct InvalidBlockTracker {
+    half_life_secs: u64,
+    max_peers: usize,
+    scores: HashMap<String, InvalidBlockScore>,
+}
+
+impl Default for InvalidBlockTracker {
+    fn default() -> Self {
+        Self::new(DEFAULT_INVALID_BLOCK_HALF_LIFE_SECS, DEFAULT_MAX_TRACKED_PEERS)
+    }
+}
+
+impl InvalidBlockTracker {
+    pub fn new(half_life_secs: u64, max_peers: usize) -> Self {
+        Self {
+            half_life_secs: half_life_secs.max(1),
+            max_peers: max_peers.max(1),
+            scores: HashMap::new(),
+        }
+    }
+
+    /// Tracker with `QNET_INVALID_BLOCK_HALF_LIFE_SECS` / `QNET_INVALID_BLOCK_MAX_PEERS` overrides
+    pub fn from_env() -> Self {
+        let half_life_secs = std::env::var("QNET_INVALID_BLOCK_HALF_LIFE_SECS")
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .filter(|&v| v > 0)
+            .unwrap_or(DEFAULT_INVALID_BLOCK_HALF_LIFE_SECS);
+        let max_peers = std::env::var("QNET_INVALID_BLOCK_MAX_PEERS")
+            .ok()
+            .and_then(|v| v.trim().parse::<usize>().ok())
+            .filter(|&v| v > 0)
+            .unwrap_or(DEFAULT_MAX_TRACKED_PEERS);
+        Self::new(half_life_secs, max_peers)
+    }
+
+    pub fn half_life_secs(&self) -> u64 {
+        self.half_life_secs
+    }
+
+    pub fn len(&self) -> usize {
+        self.scores.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scores.is_empty()
+    }
+
+    /// Record one invalid block from `peer`, returning its new score
+    pub fn record(&mut self, peer: &str, now_secs: u64) -> f64 {
+        let score = self.score(peer, now_secs) + 1.0;
+        if !self.scores.contains_key(peer) && self.scores.len() >= self.max_peers {
+            self.evict_cleanest(now_secs);
+        }
+        self.scores.insert(peer.to_string(), InvalidBlockScore { score, updated_at: now_secs });
+        score
+    }
+
+    /// Current decayed score of `peer` (0.0 if untracked)
+    pub fn score(&self, peer: &str, now_secs: u64) -> f64 {
+        self.scores.get(peer)
+            .map(|entry| entry.decayed(now_secs, self.half_life_secs))
+            .unwrap_or(0.0)
+    }
+
+    /// All decayed scores, worst offender first
+    pub fn scores(&self, now_secs: u64) -> Vec<(String, f64)> {
+        let mut scores: Vec<(String, f64)> = self.scores.iter()
+            .map(|(peer, entry)| (peer.clone(), entry.decayed(now_secs, self.half_life_secs)))
+            .collect();
+        scores.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        scores
+    }
+
+    /// Forget a peer (e.g. after it was banned)
+    pub fn remove(&mut self, peer: &str) {
+        self.scores.remove(peer);
+    }
+
+    /// Drop peers whose score has decayed to a negligible value
+    pub fn prune(&mut self, now_secs: u64) {
+        let half_life_secs = self.half_life_secs;
+        self.scores.retain(|_, entry| entry.decayed(now_secs, half_life_secs) >= NEGLIGIBLE_SCORE);
+    }
+
+    fn evict_cleanest(&mut self, now_secs: u64) {
+        let cleanest = self.scores.iter()
+            .min_by(|(_, a), (_, b)| {
+                a.decayed(now_secs, self.half_life_secs)
+                    .total_cmp(&b.decayed(now_secs, self.half_life_secs))
+                    .then_with(|| a.updated_at.cmp(&b.updated_at))
+            })
+            .map(|(peer, _)| peer.clone());
+        if let Some(peer) = cleanest {
+            self.scores.remove(&peer);
+        }
+    }
+
+    /// Serialize scores for storage, dropping negligible ones
+    pub fn to_bytes(&self, now_secs: u64) -> Result<Vec<u8>, String> {
+        let mut pruned = self.clone();
+        pruned.prune(now_secs);
+        serde_json::to_vec(&pruned.scores).map_err(|e| e.to_string())
+    }
+
+    /// Replace scores with ones saved by `to_bytes`, keeping this tracker's limits
+    pub fn restore(&mut self, data: &[u8], now_secs: u64) -> Result<(), String> {
+        let scores: HashMap<String, InvalidBlockScore> = serde_json::from_slice(data)
+            .map_err(|e| e.to_string())?;
+        self.scores.clear();
+        for (peer, entry) in scores {
+            if self.scores.len() >= self.max_peers {
+                self.evict_cleanest(now_secs);
+            }
+            self.scores.insert(peer, entry);
+        }
+        self.prune(now_secs);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_700_000_000;
+
+    #[test]
+    fn test_score_decays_after_half_life() {
+        let mut tracker = InvalidBlockTracker::new(600, 10);
+        for _ in 0..4 {
+            tracker.record("peer_a", NOW);
+        }
+        assert_eq!(tracker.score("peer_a", NOW), 4.0);
+        assert!((tracker.score("peer_a", NOW + 600) - 2.0).abs() < 1e-9);
+        assert!((tracker.score("peer_a", NOW + 1200) - 1.0).abs() < 1e-9);
+
+        // Ten half-lives later the score is effectively gone and pruned
+        assert!(tracker.score("peer_a", NOW + 6000) < 0.005);
+        tracker.prune(NOW + 6000);
+        assert!(tracker.is_empty());
+
+        // New offences build on the decayed score, not the original count
+        tracker.record("peer_b", NOW);
+        tracker.record("peer_b", NOW);
+        assert!((tracker.record("peer_b", NOW + 600) - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_cleanest_peer_evicted_when_full() {
+        let mut tracker = InvalidBlockTracker::new(600, 3);
+        tracker.record("dirty", NOW);
+        tracker.record("dirty", NOW);
+        tracker.record("old_clean", NOW);
+        tracker.record("new_clean", NOW + 10);
+
+        // The older single offence has decayed furthest; the worst offender stays
+        tracker.record("newcomer", NOW + 10);
+        assert_eq!(tracker.len(), 3);
+        assert_eq!(tracker.score("old_clean", NOW + 10), 0.0);
+        assert!(tracker.score("dirty", NOW + 10) > 1.9);
+        assert_eq!(tracker.scores(NOW + 10)[0].0, "dirty");
+    }
+
+    #[test]
+    fn test_scores_survive_save_load_round_trip() {
+        let mut tracker = InvalidBlockTracker::new(600, 10);
+        tracker.record("peer_a", NOW);
+        tracker.record("peer_a", NOW);
+        tracker.record("peer_b", NOW + 300);
+        let saved = tracker.to_bytes(NOW + 300).unwrap();
+
+        let mut restored = InvalidBlockTracker::new(600, 10);
+        restored.restore(&saved, NOW + 300).unwrap();
+        assert_eq!(restored.scores(NOW + 900), tracker.scores(NOW + 900));
+
+        // Decay keeps running across the restart
+        assert!((restored.score("peer_a", NOW + 600) - 1.0).abs() < 1e-9);
+        assert!(restored.restore(b"not json", NOW).is_err());
+    }
+}
@@ -7,6 +7,7 @@
 //! QNet Integration - Full blockchain system
 //! This module integrates all QNet components into a cohesive blockchain system.
 
+pub mod clock;
 pub mod errors;
 pub mod storage;
 pub mod validator;
@@ -31,6 +32,25 @@ pub mod vrf;
 pub mod vrf_hybrid;
 pub mod key_manager;
 pub mod contract_vm;
+pub mod ping_sampling;
+pub mod checkpoint;
+pub mod fork_choice;
+pub mod logging;
+pub mod listen_addr;
+pub mod block_stream;
+pub mod resync;
+pub mod invalid_blocks;
+pub mod sync_retry;
+pub mod light_attestation;
+pub mod network_size;
+pub mod node_config;
+pub mod state_breaker;
+pub mod api_params;
+pub mod api_compression;
+pub mod alerting;
+pub mod consensus_guard;
+pub mod transport;
+pub mod dht_bootstrap;
 
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -122,6 +142,8 @@ impl QNetBlockchain {
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(500_000), // Production default: 500k
             min_gas_price: 1,
+            eviction_policy: qnet_mempool::EvictionPolicy::from_env(),
+            gas_floor: qnet_mempool::GasFloorConfig::from_env(),
         };
         
         let mempool = Arc::new(qnet_mempool::SimpleMempool::new(mempool_config));
@@ -135,7 +157,9 @@ impl QNetBlockchain {
         
         // Initialize sharding
         let shard_coordinator = Some(Arc::new(ShardCoordinator::new()));
-        let parallel_validator = Some(Arc::new(ParallelValidator::new(4)));
+        let parallel_validator = Some(Arc::new(
+            ParallelValidator::new(4).unwrap_or_else(|_| ParallelValidator::sequential())
+        ));
         
         Ok(QNetBlockchain {
             storage,
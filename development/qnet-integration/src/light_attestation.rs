@@ -0,0 +1,250 @@
+//! Light node attestation windows
+//!
+//! A Light node earns its reward for a window with one attestation: a pinger's
+//! signed record that the node answered a challenge. Every attestation names the
+//! reward window it counts for. The window length is the network-wide
+//! `ChainParams::reward_window_secs` (fixed on mainnet), split into
+//! `PING_SLOT_SECS` ping slots. An attestation is accepted only if its timestamp
+//! falls inside the window it claims (`[window_start, window_start + window_secs)`,
+//! so a boundary timestamp belongs to the next window only) and its slot exists in
+//! that window. A Light node keeps at most one attestation per window: the first
+//! one accepted.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use crate::unified_p2p::LightNodeAttestation;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum AttestationError {
+    #[error("window start {window_start} is not on a window boundary")]
+    MisalignedWindow { window_start: u64 },
+
+    #[error("timestamp {timestamp} is outside window [{window_start}, {window_end})")]
+    OutsideWindow { timestamp: u64, window_start: u64, window_end: u64 },
+
+    #[error("slot {slot} does not exist in a window of {slots} slots")]
+    SlotOutOfRange { slot: u64, slots: u64 },
+
+    #[error("{light_node_id} already attested in window {window_start}")]
+    Duplicate { light_node_id: String, window_start: u64 },
+}
+
+/// Window and slot lengths attestations are checked against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AttestationWindow {
+    window_secs: u64,
+    slot_secs: u64,
+}
+
+impl AttestationWindow {
+    pub fn new(window_secs: u64, slot_secs: u64) -> Self {
+        Self {
+            window_secs: window_secs.max(1),
+            slot_secs: slot_secs.max(1),
+        }
+    }
+
+    /// Window of the active chain parameters (same on every node of the network)
+    pub fn from_chain_params() -> Self {
+        Self::new(
+            crate::network_config::get_chain_params().reward_window_secs,
+            crate::network_config::PING_SLOT_SECS,
+        )
+    }
+
+    pub fn window_secs(&self) -> u64 {
+        self.window_secs
+    }
+
+    /// Ping slots in one window (at least one)
+    pub fn slots(&self) -> u64 {
+        (self.window_secs / self.slot_secs).max(1)
+    }
+
+    /// Start of the window containing `timestamp`
+    pub fn window_start(&self, timestamp: u64) -> u64 {
+        timestamp - timestamp % self.window_secs
+    }
+
+    /// Check that an attestation at `timestamp` in `slot` belongs to the window starting at `window_start`
+    pub fn validate(&self, window_start: u64, slot: u64, timestamp: u64) -> Result<(), AttestationError> {
+        if window_start % self.window_secs != 0 {
+            return Err(AttestationError::MisalignedWindow { window_start });
+        }
+        let window_end = window_start.saturating_add(self.window_secs);
+        if timestamp < window_start || timestamp >= window_end {
+            return Err(AttestationError::OutsideWindow { timestamp, window_start, window_end });
+        }
+        if slot >= self.slots() {
+            return Err(AttestationError::SlotOutOfRange { slot, slots: self.slots() });
+        }
+        Ok(())
+    }
+}
+
+/// Validated attestations, at most one per Light node and window
+#[derive(Debug, Clone)]
+pub struct LightAttestations {
+    window: AttestationWindow,
+    by_window: HashMap<(String, u64), LightNodeAttestation>,
+}
+
+impl LightAttestations {
+    pub fn new(window: AttestationWindow) -> Self {
+        Self {
+            window,
+            by_window: HashMap::new(),
+        }
+    }
+
+    pub fn window(&self) -> &AttestationWindow {
+        &self.window
+    }
+
+    /// Window an attestation claims (peers predating the field send 0: use its timestamp's window)
+    pub fn claimed_window(&self, window_start: u64, timestamp: u64) -> u64 {
+        if window_start == 0 {
+            self.window.window_start(timestamp)
+        } else {
+            window_start
+        }
+    }
+
+    /// Validate and store `attestation`; a later one for the same node and window is rejected
+    pub fn insert(&mut self, mut attestation: LightNodeAttestation) -> Result<(), AttestationError> {
+        attestation.window_start = self.claimed_window(attestation.window_start, attestation.timestamp);
+        self.window.validate(attestation.window_start, attestation.slot, attestation.timestamp)?;
+
+        match self.by_window.entry((attestation.light_node_id.clone(), attestation.window_start)) {
+            Entry::Occupied(_) => Err(AttestationError::Duplicate {
+                light_node_id: attestation.light_node_id,
+                window_start: attestation.window_start,
+            }),
+            Entry::Vacant(entry) => {
+                entry.insert(attestation);
+                Ok(())
+            }
+        }
+    }
+
+    /// Whether `light_node_id` is attested in the window starting at `window_start`
+    pub fn contains(&self, light_node_id: &str, window_start: u64) -> bool {
+        self.by_window.contains_key(&(light_node_id.to_string(), window_start))
+    }
+
+    /// Attestations counting for the window starting at `window_start`
+    pub fn for_window(&self, window_start: u64) -> impl Iterator<Item = &LightNodeAttestation> {
+        self.by_window.values().filter(move |a| a.window_start == window_start)
+    }
+
+    /// Drop attestations with a timestamp at or before `cutoff`, returning how many were removed
+    pub fn retain_newer_than(&mut self, cutoff: u64) -> usize {
+        let before = self.by_window.len();
+        self.by_window.retain(|_, a| a.timestamp > cutoff);
+        before - self.by_window.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_window.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_window.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: u64 = 4 * 60 * 60;
+    const WINDOW_START: u64 = 1_700_006_400; // on a 4h boundary
+
+    fn attestation(light_node_id: &str, pinger_id: &str, slot: u64, timestamp: u64) -> LightNodeAttestation {
+        LightNodeAttestation {
+            light_node_id: light_node_id.to_string(),
+            pinger_id: pinger_id.to_string(),
+            slot,
+            timestamp,
+            window_start: WINDOW_START,
+            light_node_signature: "light_sig".to_string(),
+            pinger_signature: "pinger_sig".to_string(),
+            challenge: "challenge".to_string(),
+        }
+    }
+
+    fn book() -> LightAttestations {
+        LightAttestations::new(AttestationWindow::new(WINDOW, 60))
+    }
+
+    #[test]
+    fn test_in_window_attestation_accepted() {
+        let mut attestations = book();
+        attestations.insert(attestation("light_1", "full_1", 0, WINDOW_START)).unwrap();
+        attestations.insert(attestation("light_2", "full_1", 239, WINDOW_START + WINDOW - 1)).unwrap();
+
+        assert!(attestations.contains("light_1", WINDOW_START));
+        assert_eq!(attestations.for_window(WINDOW_START).count(), 2);
+        assert_eq!(attestations.for_window(WINDOW_START + WINDOW).count(), 0);
+
+        // Peers predating the window field: the timestamp's window is claimed
+        let mut legacy = attestation("light_3", "full_1", 5, WINDOW_START + 300);
+        legacy.window_start = 0;
+        attestations.insert(legacy).unwrap();
+        assert!(attestations.contains("light_3", WINDOW_START));
+    }
+
+    #[test]
+    fn test_out_of_window_attestation_rejected() {
+        let mut attestations = book();
+
+        // Timestamped for the next window, or the previous one, while claiming this one
+        assert_eq!(
+            attestations.insert(attestation("light_1", "full_1", 0, WINDOW_START + WINDOW)),
+            Err(AttestationError::OutsideWindow {
+                timestamp: WINDOW_START + WINDOW,
+                window_start: WINDOW_START,
+                window_end: WINDOW_START + WINDOW,
+            })
+        );
+        assert!(matches!(
+            attestations.insert(attestation("light_1", "full_1", 0, WINDOW_START - 1)),
+            Err(AttestationError::OutsideWindow { .. })
+        ));
+        assert_eq!(
+            attestations.insert(attestation("light_1", "full_1", 240, WINDOW_START + 10)),
+            Err(AttestationError::SlotOutOfRange { slot: 240, slots: 240 })
+        );
+        let mut misaligned = attestation("light_1", "full_1", 0, WINDOW_START + 10);
+        misaligned.window_start = WINDOW_START + 1;
+        assert!(matches!(attestations.insert(misaligned), Err(AttestationError::MisalignedWindow { .. })));
+        assert!(attestations.is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_attestations_collapsed() {
+        let mut attestations = book();
+        attestations.insert(attestation("light_1", "full_1", 10, WINDOW_START + 600)).unwrap();
+
+        // Retry slot and backup pinger in the same window: first attestation kept
+        assert!(matches!(
+            attestations.insert(attestation("light_1", "full_1", 11, WINDOW_START + 660)),
+            Err(AttestationError::Duplicate { .. })
+        ));
+        assert!(matches!(
+            attestations.insert(attestation("light_1", "super_2", 12, WINDOW_START + 720)),
+            Err(AttestationError::Duplicate { .. })
+        ));
+        let kept: Vec<_> = attestations.for_window(WINDOW_START).collect();
+        assert_eq!(kept.len(), 1);
+        assert_eq!((kept[0].pinger_id.as_str(), kept[0].slot), ("full_1", 10));
+
+        // The next window is a fresh attestation
+        let mut next = attestation("light_1", "full_1", 10, WINDOW_START + WINDOW + 600);
+        next.window_start = WINDOW_START + WINDOW;
+        attestations.insert(next).unwrap();
+        assert_eq!(attestations.len(), 2);
+        assert_eq!(attestations.retain_newer_than(WINDOW_START + WINDOW), 1);
+    }
+}
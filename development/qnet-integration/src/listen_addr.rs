@@ -0,0 +1,124 @@
+//! Listener bind and advertise addresses
+//!
+//! `QNET_BIND_ADDR` selects the interface the API/P2P server listens on: an
+//! IPv4 or IPv6 address, optionally with a port (`10.0.0.5`, `[::1]:8001`).
+//! `::` listens on all interfaces dual-stack (IPv4-mapped connections are
+//! accepted where the OS allows it, the Linux default); `0.0.0.0` is IPv4 only.
+//! `QNET_ADVERTISE_ADDR` is the address peers are told to connect back to,
+//! which differs from the bind address behind NAT or a proxy.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+/// Bind and advertise addresses for one listener
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ListenConfig {
+    /// Local address the listener binds to
+    pub bind: SocketAddr,
+    /// Address announced to peers, if configured
+    pub advertise: Option<SocketAddr>,
+}
+
+impl ListenConfig {
+    /// Listen on all IPv4 interfaces with nothing advertised
+    pub fn all_interfaces(port: u16) -> Self {
+        Self { bind: SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), port), advertise: None }
+    }
+
+    /// Parse bind and advertise values; either may omit the port, which defaults to `port`
+    pub fn parse(bind: Option<&str>, advertise: Option<&str>, port: u16) -> Result<Self, String> {
+        let bind = match bind {
+            Some(value) => parse_socket_addr(value, port)?,
+            None => Self::all_interfaces(port).bind,
+        };
+        let advertise = match advertise {
+            Some(value) => {
+                let addr = parse_socket_addr(value, port)?;
+                if addr.ip().is_unspecified() {
+                    return Err(format!("Advertise address {} is not reachable by peers", addr));
+                }
+                Some(addr)
+            }
+            None => None,
+        };
+        Ok(Self { bind, advertise })
+    }
+
+    /// `QNET_BIND_ADDR` and `QNET_ADVERTISE_ADDR` for a listener on `port`
+    pub fn from_env(port: u16) -> Result<Self, String> {
+        let bind = env_value("QNET_BIND_ADDR");
+        let advertise = env_value("QNET_ADVERTISE_ADDR");
+        Self::parse(bind.as_deref(), advertise.as_deref(), port)
+    }
+
+    /// Address peers should connect to: the advertised one, else a specific bind address
+    pub fn advertised(&self) -> Option<SocketAddr> {
+        self.advertise.or_else(|| (!self.bind.ip().is_unspecified()).then_some(self.bind))
+    }
+
+    /// Address this host reaches its own listener at (loopback for listen-all binds)
+    pub fn local(&self) -> SocketAddr {
+        match self.bind.ip() {
+            IpAddr::V4(ip) if ip.is_unspecified() => SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), self.bind.port()),
+            IpAddr::V6(ip) if ip.is_unspecified() => SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), self.bind.port()),
+            _ => self.bind,
+        }
+    }
+}
+
+fn env_value(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.trim().is_empty())
+}
+
+/// Parse `ip`, `ip:port`, `[ipv6]` or `[ipv6]:port`
+pub fn parse_socket_addr(value: &str, default_port: u16) -> Result<SocketAddr, String> {
+    let value = value.trim();
+    if let Ok(addr) = value.parse::<SocketAddr>() {
+        return Ok(addr);
+    }
+    let host = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')).unwrap_or(value);
+    host.parse::<IpAddr>()
+        .map(|ip| SocketAddr::new(ip, default_port))
+        .map_err(|_| format!("Invalid listen address '{}': expected IPv4 or IPv6 with optional port", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ipv4_and_ipv6_addresses() {
+        assert_eq!(parse_socket_addr("10.0.0.5", 8001).unwrap(), "10.0.0.5:8001".parse().unwrap());
+        assert_eq!(parse_socket_addr("10.0.0.5:9000", 8001).unwrap().port(), 9000);
+
+        let v6 = parse_socket_addr("2001:db8::7", 8001).unwrap();
+        assert!(v6.is_ipv6());
+        assert_eq!(v6.port(), 8001);
+        assert_eq!(parse_socket_addr("[2001:db8::7]", 8001).unwrap(), v6);
+        assert_eq!(parse_socket_addr("[2001:db8::7]:9000", 8001).unwrap().port(), 9000);
+        assert_eq!(parse_socket_addr("::", 8001).unwrap().to_string(), "[::]:8001");
+
+        assert!(parse_socket_addr("node.example.com", 8001).is_err());
+        assert!(parse_socket_addr("10.0.0.256", 8001).is_err());
+        assert!(parse_socket_addr("[2001:db8::7", 8001).is_err());
+    }
+
+    #[test]
+    fn test_advertise_can_differ_from_bind() {
+        // Dual-stack listen-all behind NAT, advertising the public IPv4 address
+        let config = ListenConfig::parse(Some("::"), Some("203.0.113.9:443"), 8001).unwrap();
+        assert_eq!(config.bind.to_string(), "[::]:8001");
+        assert_eq!(config.advertised(), Some("203.0.113.9:443".parse().unwrap()));
+        assert_eq!(config.local().to_string(), "[::1]:8001");
+
+        // A specific bind address is advertised when nothing else is set
+        let config = ListenConfig::parse(Some("[fd00::2]:9876"), None, 8001).unwrap();
+        assert_eq!(config.advertised(), Some(config.bind));
+        assert_eq!(config.local(), config.bind);
+
+        // Listen-all has nothing to advertise; unspecified advertise addresses are rejected
+        let config = ListenConfig::parse(None, None, 8001).unwrap();
+        assert_eq!(config, ListenConfig::all_interfaces(8001));
+        assert_eq!(config.advertised(), None);
+        assert!(ListenConfig::parse(None, Some("0.0.0.0"), 8001).is_err());
+    }
+}
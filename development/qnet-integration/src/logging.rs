@@ -0,0 +1,233 @@
+//! Structured logging
+//!
+//! The node keeps its human-readable `[COMPONENT] message` lines by default.
+//! With `QNET_LOG_FORMAT=json`, events are emitted through `tracing` and written
+//! as one JSON object per line with their fields (component, event, height,
+//! peer), so log aggregators can ingest them without parsing formatted strings.
+
+use std::io::Write;
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+
+/// Target used for all structured node events
+pub const EVENT_TARGET: &str = "qnet";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// `[COMPONENT] message` lines (default)
+    Pretty,
+    /// One JSON object per line
+    Json,
+}
+
+impl LogFormat {
+    /// Format from `QNET_LOG_FORMAT` (`json` or `pretty`)
+    pub fn from_env() -> Self {
+        match std::env::var("QNET_LOG_FORMAT") {
+            Ok(value) if value.eq_ignore_ascii_case("json") => LogFormat::Json,
+            _ => LogFormat::Pretty,
+        }
+    }
+}
+
+static LOG_FORMAT: OnceLock<LogFormat> = OnceLock::new();
+
+/// Active log format, read once from the environment
+pub fn log_format() -> LogFormat {
+    *LOG_FORMAT.get_or_init(LogFormat::from_env)
+}
+
+/// Install the JSON subscriber when `QNET_LOG_FORMAT=json`
+/// Pretty format needs no subscriber: events are printed directly
+pub fn init() {
+    if log_format() == LogFormat::Json {
+        let subscriber = tracing_subscriber::registry().with(JsonLayer::new(std::io::stdout));
+        if tracing::subscriber::set_global_default(subscriber).is_err() {
+            eprintln!("[LOGGING] ⚠️ Global subscriber already set - JSON logging not installed");
+        }
+    }
+}
+
+/// Node event with the fields log aggregators index on
+#[derive(Debug, Clone)]
+pub struct StructuredEvent {
+    pub component: &'static str,
+    pub event: &'static str,
+    pub height: Option<u64>,
+    pub peer: Option<String>,
+    pub message: String,
+}
+
+impl StructuredEvent {
+    pub fn new(component: &'static str, event: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            component,
+            event,
+            height: None,
+            peer: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn height(mut self, height: u64) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    pub fn peer(mut self, peer: impl Into<String>) -> Self {
+        self.peer = Some(peer.into());
+        self
+    }
+
+    /// Emit in the active format
+    pub fn emit(self) {
+        match log_format() {
+            LogFormat::Pretty => println!("[{}] {}", self.component, self.message),
+            LogFormat::Json => self.emit_tracing(),
+        }
+    }
+
+    /// Emit as a `tracing` event to the current subscriber
+    pub fn emit_tracing(&self) {
+        tracing::info!(
+            target: EVENT_TARGET,
+            component = self.component,
+            event = self.event,
+            height = self.height,
+            peer = self.peer.as_deref(),
+            "{}",
+            self.message
+        );
+    }
+}
+
+/// `tracing` layer writing each event as a single JSON line
+pub struct JsonLayer<W> {
+    make_writer: W,
+}
+
+impl<W> JsonLayer<W>
+where
+    W: for<'a> MakeWriter<'a> + 'static,
+{
+    pub fn new(make_writer: W) -> Self {
+        Self { make_writer }
+    }
+}
+
+impl<S, W> Layer<S> for JsonLayer<W>
+where
+    S: Subscriber,
+    W: for<'a> MakeWriter<'a> + 'static,
+{
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut fields = JsonVisitor::default();
+        event.record(&mut fields);
+
+        let mut record = serde_json::Map::new();
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        record.insert("timestamp_ms".to_string(), timestamp_ms.into());
+        record.insert("level".to_string(), metadata.level().as_str().into());
+        record.insert("target".to_string(), metadata.target().into());
+        record.extend(fields.0);
+
+        if let Ok(mut line) = serde_json::to_vec(&serde_json::Value::Object(record)) {
+            line.push(b'\n');
+            let _ = self.make_writer.make_writer().write_all(&line);
+        }
+    }
+}
+
+#[derive(Default)]
+struct JsonVisitor(serde_json::Map<String, serde_json::Value>);
+
+impl Visit for JsonVisitor {
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.insert(field.name().to_string(), format!("{:?}", value).into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct CaptureWriter(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for CaptureWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for CaptureWriter {
+        type Writer = CaptureWriter;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    #[test]
+    fn test_structured_event_fields_in_json() {
+        let capture = CaptureWriter::default();
+        let subscriber = tracing_subscriber::registry().with(JsonLayer::new(capture.clone()));
+
+        tracing::subscriber::with_default(subscriber, || {
+            StructuredEvent::new("BLOCKS", "block_stored", "✅ Block #42 stored successfully")
+                .height(42)
+                .peer("node_7")
+                .emit_tracing();
+            StructuredEvent::new("SYNC", "sync_started", "syncing").emit_tracing();
+        });
+
+        let output = String::from_utf8(capture.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<serde_json::Value> = output.lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+
+        let event = &lines[0];
+        assert_eq!(event["component"], "BLOCKS");
+        assert_eq!(event["event"], "block_stored");
+        assert_eq!(event["height"], 42);
+        assert_eq!(event["peer"], "node_7");
+        assert_eq!(event["message"], "✅ Block #42 stored successfully");
+        assert_eq!(event["level"], "INFO");
+        assert_eq!(event["target"], EVENT_TARGET);
+
+        // Unset optional fields are omitted
+        assert!(lines[1].get("height").is_none());
+        assert!(lines[1].get("peer").is_none());
+    }
+}
@@ -46,6 +46,372 @@ pub struct SolanaConfig {
     pub commitment: String,
 }
 
+/// Default producer rotation interval (blocks per leadership round)
+pub const DEFAULT_ROTATION_INTERVAL_BLOCKS: u64 = 30;
+
+/// Microblock interval in seconds
+pub const MICROBLOCK_INTERVAL_SECS: u64 = 1;
+
+/// Default reward window: 4 hours
+pub const DEFAULT_REWARD_WINDOW_SECS: u64 = 4 * 60 * 60;
+
+/// Length of one Light node ping / heartbeat slot (240 slots in a 4-hour window)
+pub const PING_SLOT_SECS: u64 = 60;
+
+/// Upper bound for `QNET_EMPTY_BLOCK_INTERVAL`
+pub const MAX_EMPTY_BLOCK_INTERVAL_SECS: u64 = 10;
+
+fn default_empty_block_interval_secs() -> u64 {
+    MICROBLOCK_INTERVAL_SECS
+}
+
+fn default_max_tx_bytes() -> u64 {
+    qnet_mempool::validation::DEFAULT_MAX_TX_BYTES as u64
+}
+
+fn default_max_tx_data_bytes() -> u64 {
+    qnet_mempool::validation::DEFAULT_MAX_TX_DATA_BYTES as u64
+}
+
+/// Consensus parameters every node on a network must agree on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainParams {
+    /// Blocks produced by one producer before rotation
+    pub rotation_interval_blocks: u64,
+    /// Length of a reward window; one emission block closes each window
+    pub reward_window_secs: u64,
+    /// First height whose transactions must be in canonical order (earlier blocks predate the rule)
+    #[serde(default)]
+    pub canonical_tx_order_height: u64,
+    /// Minimum spacing of blocks without transactions; failover watchers tolerate the extra silence
+    #[serde(default = "default_empty_block_interval_secs")]
+    pub empty_block_interval_secs: u64,
+    /// Largest serialized user transaction accepted in the mempool or in a block
+    #[serde(default = "default_max_tx_bytes")]
+    pub max_tx_bytes: u64,
+    /// Largest `data` payload of a user transaction
+    #[serde(default = "default_max_tx_data_bytes")]
+    pub max_tx_data_bytes: u64,
+}
+
+impl Default for ChainParams {
+    fn default() -> Self {
+        Self {
+            rotation_interval_blocks: DEFAULT_ROTATION_INTERVAL_BLOCKS,
+            reward_window_secs: DEFAULT_REWARD_WINDOW_SECS,
+            canonical_tx_order_height: 0,
+            empty_block_interval_secs: default_empty_block_interval_secs(),
+            max_tx_bytes: default_max_tx_bytes(),
+            max_tx_data_bytes: default_max_tx_data_bytes(),
+        }
+    }
+}
+
+impl ChainParams {
+    /// Parameters for a network, with `QNET_ROTATION_INTERVAL_BLOCKS`, `QNET_REWARD_WINDOW_SECS`,
+    /// `QNET_CANONICAL_TX_ORDER_HEIGHT`, `QNET_EMPTY_BLOCK_INTERVAL`, `QNET_MAX_TX_BYTES` and
+    /// `QNET_MAX_TX_DATA_BYTES` overrides off mainnet
+    pub fn from_env(environment: &NetworkEnvironment) -> Result<Self, String> {
+        let mut params = Self::default();
+        if let Some(interval) = env_override("QNET_ROTATION_INTERVAL_BLOCKS")? {
+            if *environment == NetworkEnvironment::Mainnet && interval != params.rotation_interval_blocks {
+                return Err(format!("Rotation interval is fixed at {} blocks on mainnet", params.rotation_interval_blocks));
+            }
+            params.rotation_interval_blocks = interval;
+        }
+        if let Some(window) = env_override("QNET_REWARD_WINDOW_SECS")? {
+            if *environment == NetworkEnvironment::Mainnet && window != params.reward_window_secs {
+                return Err(format!("Reward window is fixed at {} seconds on mainnet", params.reward_window_secs));
+            }
+            params.reward_window_secs = window;
+        }
+        if let Some(height) = env_override("QNET_CANONICAL_TX_ORDER_HEIGHT")? {
+            if *environment == NetworkEnvironment::Mainnet && height != params.canonical_tx_order_height {
+                return Err("Canonical transaction order applies from genesis on mainnet".to_string());
+            }
+            params.canonical_tx_order_height = height;
+        }
+        if let Some(interval) = env_override("QNET_EMPTY_BLOCK_INTERVAL")? {
+            if *environment == NetworkEnvironment::Mainnet && interval != params.empty_block_interval_secs {
+                return Err(format!("Empty block interval is fixed at {}s on mainnet", params.empty_block_interval_secs));
+            }
+            params.empty_block_interval_secs = interval;
+        }
+        if let Some(bytes) = env_override("QNET_MAX_TX_BYTES")? {
+            if *environment == NetworkEnvironment::Mainnet && bytes != params.max_tx_bytes {
+                return Err(format!("Transaction size cap is fixed at {} bytes on mainnet", params.max_tx_bytes));
+            }
+            params.max_tx_bytes = bytes;
+        }
+        if let Some(bytes) = env_override("QNET_MAX_TX_DATA_BYTES")? {
+            if *environment == NetworkEnvironment::Mainnet && bytes != params.max_tx_data_bytes {
+                return Err(format!("Transaction data cap is fixed at {} bytes on mainnet", params.max_tx_data_bytes));
+            }
+            params.max_tx_data_bytes = bytes;
+        }
+        params.validate()?;
+        Ok(params)
+    }
+    
+    pub fn validate(&self) -> Result<(), String> {
+        if self.rotation_interval_blocks < 1 {
+            return Err("Rotation interval must be at least 1 block".to_string());
+        }
+        if self.reward_window_secs == 0 || !self.reward_window_secs.is_multiple_of(MICROBLOCK_INTERVAL_SECS) {
+            return Err(format!(
+                "Reward window must be a positive multiple of the {}s block interval, got {}s",
+                MICROBLOCK_INTERVAL_SECS, self.reward_window_secs
+            ));
+        }
+        if self.empty_block_interval_secs < MICROBLOCK_INTERVAL_SECS || self.empty_block_interval_secs > MAX_EMPTY_BLOCK_INTERVAL_SECS {
+            return Err(format!(
+                "Empty block interval must be {}-{}s, got {}s",
+                MICROBLOCK_INTERVAL_SECS, MAX_EMPTY_BLOCK_INTERVAL_SECS, self.empty_block_interval_secs
+            ));
+        }
+        if self.max_tx_data_bytes == 0 || self.max_tx_data_bytes > self.max_tx_bytes {
+            return Err(format!(
+                "Transaction data cap must be positive and at most the {} byte transaction cap, got {}",
+                self.max_tx_bytes, self.max_tx_data_bytes
+            ));
+        }
+        Ok(())
+    }
+    
+    /// Transaction size caps enforced at admission and block validation
+    pub fn tx_size_limits(&self) -> qnet_mempool::validation::TxSizeLimits {
+        qnet_mempool::validation::TxSizeLimits {
+            max_tx_bytes: self.max_tx_bytes as usize,
+            max_tx_data_bytes: self.max_tx_data_bytes as usize,
+        }
+    }
+    
+    /// Microblocks produced in one reward window (also the emission interval)
+    pub fn blocks_in_reward_window(&self) -> u64 {
+        self.reward_window_secs / MICROBLOCK_INTERVAL_SECS
+    }
+    
+    /// Ping / heartbeat slots in one reward window (at least one)
+    pub fn ping_slots_in_window(&self) -> u64 {
+        (self.reward_window_secs / PING_SLOT_SECS).max(1)
+    }
+    
+    /// Start of the reward window containing `now_secs`
+    pub fn reward_window_start(&self, now_secs: u64) -> u64 {
+        now_secs - (now_secs % self.reward_window_secs)
+    }
+    
+    /// Sequence number of the reward window containing `now_secs`
+    pub fn reward_window_number(&self, now_secs: u64) -> u64 {
+        now_secs / self.reward_window_secs
+    }
+    
+    /// Whether `height` is an emission block, closing a reward window
+    pub fn is_emission_block(&self, height: u64) -> bool {
+        height > 0 && height.is_multiple_of(self.blocks_in_reward_window())
+    }
+    
+    /// Heights `(start, end)` covered by the reward window ending at `height`
+    pub fn reward_window_heights(&self, height: u64) -> (u64, u64) {
+        (height.saturating_sub(self.blocks_in_reward_window()), height)
+    }
+    
+    /// Leadership round of a block: blocks 1..=N are round 0, N+1..=2N round 1, ...
+    pub fn leadership_round(&self, height: u64) -> u64 {
+        if height == 0 {
+            0  // Genesis block
+        } else {
+            (height - 1) / self.rotation_interval_blocks
+        }
+    }
+    
+    /// Last block of a round (N, 2N, ...), where atomic rotation rewards are settled
+    pub fn is_rotation_end(&self, height: u64) -> bool {
+        height > 0 && height.is_multiple_of(self.rotation_interval_blocks)
+    }
+    
+    /// First block of a new producer's round (N+1, 2N+1, ...)
+    pub fn is_rotation_start(&self, height: u64) -> bool {
+        height > 1 && (height - 1).is_multiple_of(self.rotation_interval_blocks)
+    }
+    
+    /// Whether a block at `height` must carry its transactions in canonical order
+    pub fn requires_canonical_tx_order(&self, height: u64) -> bool {
+        height >= self.canonical_tx_order_height
+    }
+    
+    /// Extra silence a producer may leave between empty blocks, on top of the normal block interval
+    pub fn empty_block_slack_secs(&self) -> u64 {
+        self.empty_block_interval_secs.saturating_sub(MICROBLOCK_INTERVAL_SECS)
+    }
+    
+    /// First block of the round after `round`
+    pub fn next_rotation_block(&self, round: u64) -> u64 {
+        (round + 1) * self.rotation_interval_blocks + 1
+    }
+}
+
+fn env_override(name: &str) -> Result<Option<u64>, String> {
+    match std::env::var(name) {
+        Ok(value) => value.trim().parse::<u64>()
+            .map(Some)
+            .map_err(|e| format!("Invalid {} '{}': {}", name, value, e)),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Default number of Byzantine faults tolerated (3f+1 = 4 participants)
+pub const DEFAULT_FAULT_TOLERANCE_F: usize = 1;
+
+/// Byzantine fault tolerance: `f` faulty nodes need `3f+1` participants
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ByzantineParams {
+    /// Faulty nodes the network tolerates
+    pub fault_tolerance_f: usize,
+}
+
+impl Default for ByzantineParams {
+    fn default() -> Self {
+        Self {
+            fault_tolerance_f: DEFAULT_FAULT_TOLERANCE_F,
+        }
+    }
+}
+
+impl ByzantineParams {
+    pub fn new(fault_tolerance_f: usize) -> Result<Self, String> {
+        let params = Self { fault_tolerance_f };
+        params.validate()?;
+        Ok(params)
+    }
+    
+    /// Parameters with `QNET_BYZANTINE_F` override
+    pub fn from_env() -> Result<Self, String> {
+        match std::env::var("QNET_BYZANTINE_F") {
+            Ok(value) => {
+                let f = value.trim().parse::<usize>()
+                    .map_err(|e| format!("Invalid QNET_BYZANTINE_F '{}': {}", value, e))?;
+                Self::new(f)
+            }
+            Err(_) => Ok(Self::default()),
+        }
+    }
+    
+    pub fn validate(&self) -> Result<(), String> {
+        if self.fault_tolerance_f < 1 {
+            return Err("Byzantine fault tolerance f must be at least 1".to_string());
+        }
+        Ok(())
+    }
+    
+    /// Participants needed for Byzantine safety (3f+1)
+    pub fn min_participants(&self) -> usize {
+        3 * self.fault_tolerance_f + 1
+    }
+    
+    /// Validated peers needed besides ourselves (3f)
+    pub fn min_peers(&self) -> usize {
+        self.min_participants() - 1
+    }
+    
+    /// Whether this node plus `validated_peers` reach the 3f+1 quorum
+    pub fn has_quorum(&self, validated_peers: usize) -> bool {
+        validated_peers >= self.min_peers()
+    }
+    
+    /// Reject a configured participant minimum that cannot tolerate f faults
+    pub fn check_min_participants(&self, min_participants: usize) -> Result<(), String> {
+        if min_participants < self.min_participants() {
+            return Err(format!("min_participants {} is below 3f+1 = {} for f = {}",
+                               min_participants, self.min_participants(), self.fault_tolerance_f));
+        }
+        Ok(())
+    }
+}
+
+/// Network id of mainnet, the only network with a baked-in bootstrap list
+pub const MAINNET_NETWORK_ID: &str = "qnet-mainnet-v1";
+
+/// Port Genesis bootstrap nodes listen on
+pub const DEFAULT_BOOTSTRAP_PORT: u16 = 8001;
+
+/// Peer contacted to join the network, as `host:port`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BootstrapPeer {
+    pub host: String,
+    pub port: u16,
+}
+
+impl BootstrapPeer {
+    /// Parse one `host:port` entry (IPv4, `[IPv6]` or hostname)
+    pub fn parse(entry: &str) -> Result<Self, String> {
+        let entry = entry.trim();
+        if let Ok(addr) = entry.parse::<std::net::SocketAddr>() {
+            if addr.port() == 0 {
+                return Err(format!("Invalid bootstrap peer '{}': port must be non-zero", entry));
+            }
+            return Ok(Self { host: addr.ip().to_string(), port: addr.port() });
+        }
+        let (host, port) = entry.rsplit_once(':')
+            .ok_or_else(|| format!("Invalid bootstrap peer '{}': expected host:port", entry))?;
+        let port = port.parse::<u16>().ok().filter(|&p| p != 0)
+            .ok_or_else(|| format!("Invalid bootstrap peer '{}': port must be 1-65535", entry))?;
+        let valid_hostname = !host.is_empty()
+            && host.len() <= 253
+            && host.split('.').all(|label| {
+                !label.is_empty()
+                    && !label.starts_with('-')
+                    && !label.ends_with('-')
+                    && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+            });
+        if !valid_hostname {
+            return Err(format!("Invalid bootstrap peer '{}': '{}' is not an IP address or hostname", entry, host));
+        }
+        Ok(Self { host: host.to_string(), port })
+    }
+    
+    /// `host:port` address to dial
+    pub fn addr(&self) -> String {
+        if self.host.contains(':') {
+            format!("[{}]:{}", self.host, self.port)
+        } else {
+            format!("{}:{}", self.host, self.port)
+        }
+    }
+}
+
+/// Parse a comma-separated `host:port` list, rejecting the whole list on any bad entry
+pub fn parse_bootstrap_peers(list: &str) -> Result<Vec<BootstrapPeer>, String> {
+    let peers = list.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(BootstrapPeer::parse)
+        .collect::<Result<Vec<_>, _>>()?;
+    if peers.is_empty() {
+        return Err("Bootstrap peer list is empty".to_string());
+    }
+    Ok(peers)
+}
+
+/// Baked-in bootstrap peers for `network_id`: the Genesis nodes on mainnet, none elsewhere
+pub fn default_bootstrap_peers(network_id: &str) -> Vec<BootstrapPeer> {
+    if network_id != MAINNET_NETWORK_ID {
+        return Vec::new();
+    }
+    crate::genesis_constants::GENESIS_NODE_IPS.iter()
+        .map(|(ip, _)| BootstrapPeer { host: ip.to_string(), port: DEFAULT_BOOTSTRAP_PORT })
+        .collect()
+}
+
+/// Bootstrap peers from a `QNET_BOOTSTRAP_PEERS` value, else the network's baked-in list
+pub fn resolve_bootstrap_peers(network_id: &str, configured: Option<&str>) -> Result<Vec<BootstrapPeer>, String> {
+    match configured {
+        Some(list) => parse_bootstrap_peers(list)
+            .map_err(|e| format!("QNET_BOOTSTRAP_PEERS: {}", e)),
+        None => Ok(default_bootstrap_peers(network_id)),
+    }
+}
+
 /// QNet network configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QNetNetworkConfig {
@@ -55,6 +421,11 @@ pub struct QNetNetworkConfig {
     pub endpoints: NetworkEndpoints,
     pub solana: SolanaConfig,
     pub genesis_timestamp: Option<u64>,
+    pub chain_params: ChainParams,
+    pub byzantine_params: ByzantineParams,
+    /// Peers seeding Genesis connections (`QNET_BOOTSTRAP_PEERS`)
+    #[serde(default)]
+    pub bootstrap_peers: Vec<BootstrapPeer>,
 }
 
 impl QNetNetworkConfig {
@@ -77,7 +448,19 @@ impl QNetNetworkConfig {
         };
         
         println!("рџЊђ Network environment: {:?}", environment);
-        Self::for_environment(environment)
+        let mut config = Self::for_environment(environment);
+        // All nodes must run the same parameters - refuse to start on an invalid override
+        config.chain_params = ChainParams::from_env(&config.environment)
+            .unwrap_or_else(|e| panic!("Invalid chain parameters: {}", e));
+        config.byzantine_params = ByzantineParams::from_env()
+            .unwrap_or_else(|e| panic!("Invalid Byzantine parameters: {}", e));
+        let configured_peers = std::env::var("QNET_BOOTSTRAP_PEERS").ok();
+        config.bootstrap_peers = resolve_bootstrap_peers(&config.network_id, configured_peers.as_deref())
+            .unwrap_or_else(|e| panic!("Invalid bootstrap peers: {}", e));
+        if config.bootstrap_peers.is_empty() {
+            println!("[CONFIG] ⚠️ No bootstrap peers for {} - set QNET_BOOTSTRAP_PEERS=host:port,...", config.network_id);
+        }
+        config
     }
     
     /// Testnet configuration
@@ -102,6 +485,9 @@ impl QNetNetworkConfig {
                 commitment: "confirmed".to_string(),
             },
             genesis_timestamp: None, // Will be set when testnet launches
+            chain_params: ChainParams::default(),
+            byzantine_params: ByzantineParams::default(),
+            bootstrap_peers: Vec::new(),
         }
     }
     
@@ -109,7 +495,7 @@ impl QNetNetworkConfig {
     fn mainnet_config() -> Self {
         Self {
             environment: NetworkEnvironment::Mainnet,
-            network_id: "qnet-mainnet-v1".to_string(),
+            network_id: MAINNET_NETWORK_ID.to_string(),
             chain_id: 1,
             endpoints: NetworkEndpoints {
                 qnet_rpc: "https://rpc.qnet.io".to_string(),
@@ -127,6 +513,9 @@ impl QNetNetworkConfig {
                 commitment: "finalized".to_string(),
             },
             genesis_timestamp: None, // Will be set when mainnet launches
+            chain_params: ChainParams::default(),
+            byzantine_params: ByzantineParams::default(),
+            bootstrap_peers: default_bootstrap_peers(MAINNET_NETWORK_ID),
         }
     }
     
@@ -152,6 +541,9 @@ impl QNetNetworkConfig {
                 commitment: "processed".to_string(),
             },
             genesis_timestamp: None,
+            chain_params: ChainParams::default(),
+            byzantine_params: ByzantineParams::default(),
+            bootstrap_peers: Vec::new(),
         }
     }
     
@@ -229,3 +621,62 @@ pub fn get_burn_contract() -> &'static str {
     &NETWORK_CONFIG.solana.burn_contract
 }
 
+pub fn get_chain_params() -> &'static ChainParams {
+    &NETWORK_CONFIG.chain_params
+}
+
+pub fn get_byzantine_params() -> &'static ByzantineParams {
+    &NETWORK_CONFIG.byzantine_params
+}
+
+pub fn get_bootstrap_peers() -> &'static [BootstrapPeer] {
+    &NETWORK_CONFIG.bootstrap_peers
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_bootstrap_peers_parsed_and_used() {
+        let peers = resolve_bootstrap_peers(
+            "qnet-private-testnet",
+            Some(" 10.0.0.5:8001, seed.example.org:9000 ,[2001:db8::1]:8001,"),
+        ).unwrap();
+        assert_eq!(peers, vec![
+            BootstrapPeer { host: "10.0.0.5".to_string(), port: 8001 },
+            BootstrapPeer { host: "seed.example.org".to_string(), port: 9000 },
+            BootstrapPeer { host: "2001:db8::1".to_string(), port: 8001 },
+        ]);
+        assert_eq!(peers[2].addr(), "[2001:db8::1]:8001");
+
+        // A configured list replaces the mainnet default too
+        let mainnet = resolve_bootstrap_peers(MAINNET_NETWORK_ID, Some("10.0.0.5:8001")).unwrap();
+        assert_eq!(mainnet.len(), 1);
+
+        // Without configuration only mainnet has a baked-in list
+        let mainnet_default = resolve_bootstrap_peers(MAINNET_NETWORK_ID, None).unwrap();
+        assert_eq!(mainnet_default.len(), crate::genesis_constants::GENESIS_NODE_IPS.len());
+        assert_eq!(mainnet_default[0].addr(), "154.38.160.39:8001");
+        assert!(resolve_bootstrap_peers("qnet-testnet-v1", None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_malformed_bootstrap_peers_rejected() {
+        for (list, reason) in [
+            ("10.0.0.5", "expected host:port"),
+            ("10.0.0.5:0", "port must be non-zero"),
+            ("10.0.0.5:70000", "port must be 1-65535"),
+            ("seed.example.org:abc", "port must be 1-65535"),
+            ("bad_host!:8001", "is not an IP address or hostname"),
+            (":8001", "is not an IP address or hostname"),
+            ("10.0.0.5:8001,-seed.example.org:8001", "is not an IP address or hostname"),
+            (" , ", "empty"),
+        ] {
+            let err = resolve_bootstrap_peers("qnet-private-testnet", Some(list)).unwrap_err();
+            assert!(err.starts_with("QNET_BOOTSTRAP_PEERS: "), "{}", err);
+            assert!(err.contains(reason), "{}: {}", list, err);
+        }
+    }
+}
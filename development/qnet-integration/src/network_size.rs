@@ -0,0 +1,137 @@
+//! Network size estimate shared by all auto-scaling decisions
+//!
+//! The network size is the number of nodes activated in the blockchain registry
+//! (the `activations` column family). It is resolved once at startup and every
+//! auto-scaling decision reads the same value: mempool capacity, shard count and
+//! the sharding auto-enable threshold. Resolution order:
+//! 1. `QNET_TOTAL_NETWORK_NODES` override from monitoring/orchestration
+//! 2. Genesis phase (`QNET_BOOTSTRAP_ID` set): the 5 bootstrap nodes
+//! 3. Activated nodes in the registry
+//! 4. Conservative default of 100 nodes when the registry is empty
+
+use lazy_static::lazy_static;
+use prometheus::{register_gauge, Gauge};
+use serde::Serialize;
+
+/// Genesis phase network size (5 bootstrap nodes)
+pub const GENESIS_NETWORK_SIZE: usize = 5;
+
+/// Assumed size when nothing is known yet, small to avoid over-sharding
+pub const DEFAULT_NETWORK_SIZE: usize = 100;
+
+/// Sharding is enabled automatically from this many nodes
+pub const SHARDING_AUTO_ENABLE_NODES: usize = 10_000;
+
+/// Super nodes enable sharding earlier
+pub const SHARDING_AUTO_ENABLE_NODES_SUPER: usize = 5_000;
+
+lazy_static! {
+    static ref NETWORK_SIZE_ESTIMATE: Gauge = register_gauge!(
+        "qnet_network_size_estimate",
+        "Estimated number of active registered nodes used for auto-scaling"
+    ).unwrap();
+}
+
+/// Where the estimate came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NetworkSizeSource {
+    Override,
+    Genesis,
+    Registry,
+    Default,
+}
+
+/// Estimated network size and the scaling decisions derived from it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct NetworkSizeEstimate {
+    pub nodes: usize,
+    pub source: NetworkSizeSource,
+}
+
+impl NetworkSizeEstimate {
+    /// Resolve the estimate from an optional override, the Genesis flag and the
+    /// number of activated nodes in the registry
+    pub fn resolve(override_nodes: Option<&str>, genesis_phase: bool, registered_nodes: usize) -> Self {
+        if let Some(nodes) = override_nodes.and_then(|v| v.trim().parse::<usize>().ok()) {
+            return Self { nodes, source: NetworkSizeSource::Override };
+        }
+        if genesis_phase {
+            return Self { nodes: GENESIS_NETWORK_SIZE, source: NetworkSizeSource::Genesis };
+        }
+        if registered_nodes > 0 {
+            return Self { nodes: registered_nodes, source: NetworkSizeSource::Registry };
+        }
+        Self { nodes: DEFAULT_NETWORK_SIZE, source: NetworkSizeSource::Default }
+    }
+
+    /// Resolve using `QNET_TOTAL_NETWORK_NODES` and `QNET_BOOTSTRAP_ID`
+    pub fn from_env(registered_nodes: usize) -> Self {
+        let override_nodes = std::env::var("QNET_TOTAL_NETWORK_NODES").ok();
+        let genesis_phase = std::env::var("QNET_BOOTSTRAP_ID").is_ok();
+        Self::resolve(override_nodes.as_deref(), genesis_phase, registered_nodes)
+    }
+
+    /// Mempool capacity (transactions) for this network size
+    pub fn mempool_capacity(&self) -> usize {
+        match self.nodes {
+            0..=100 => 100_000,             // Genesis/test: 100k
+            101..=10_000 => 500_000,        // Small network: 500k
+            10_001..=100_000 => 1_000_000,  // Medium network: 1M
+            _ => 2_000_000,                 // Large network: 2M
+        }
+    }
+
+    /// Transaction shard count for this network size
+    pub fn shard_count(&self) -> u32 {
+        qnet_sharding::get_optimal_shard_count(self.nodes)
+    }
+
+    /// Storage shard count for this network size
+    pub fn storage_shard_count(&self) -> u64 {
+        crate::reward_sharding::calculate_optimal_shards(self.nodes) as u64
+    }
+
+    /// Whether sharding should be switched on without `QNET_ENABLE_SHARDING`
+    pub fn sharding_auto_enabled(&self, is_super_node: bool) -> bool {
+        self.nodes >= SHARDING_AUTO_ENABLE_NODES
+            || (is_super_node && self.nodes >= SHARDING_AUTO_ENABLE_NODES_SUPER)
+    }
+
+    /// Export the estimate to Prometheus
+    pub fn publish(&self) {
+        NETWORK_SIZE_ESTIMATE.set(self.nodes as f64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolution_order() {
+        assert_eq!(NetworkSizeEstimate::resolve(Some("42000"), true, 7),
+                   NetworkSizeEstimate { nodes: 42_000, source: NetworkSizeSource::Override });
+        assert_eq!(NetworkSizeEstimate::resolve(Some("lots"), true, 7).source, NetworkSizeSource::Genesis);
+        assert_eq!(NetworkSizeEstimate::resolve(None, false, 7),
+                   NetworkSizeEstimate { nodes: 7, source: NetworkSizeSource::Registry });
+        assert_eq!(NetworkSizeEstimate::resolve(None, false, 0).nodes, DEFAULT_NETWORK_SIZE);
+    }
+
+    #[test]
+    fn test_consumers_share_one_estimate() {
+        let estimate = NetworkSizeEstimate::resolve(None, false, 12_000);
+
+        // Mempool, shard policy and sharding threshold all scale from the same 12k nodes
+        assert_eq!(estimate.mempool_capacity(), 1_000_000);
+        assert_eq!(estimate.shard_count(), qnet_sharding::get_optimal_shard_count(12_000));
+        assert_eq!(estimate.shard_count(), 16);
+        assert!(estimate.sharding_auto_enabled(false));
+
+        let small = NetworkSizeEstimate::resolve(None, false, 6_000);
+        assert_eq!(small.mempool_capacity(), 500_000);
+        assert_eq!(small.shard_count(), 4);
+        assert!(!small.sharding_auto_enabled(false));
+        assert!(small.sharding_auto_enabled(true));
+    }
+}
@@ -1,6 +1,7 @@
 //! Blockchain node implementation
 
 use crate::{
+    clock::{Clock, SystemClock},
     errors::QNetError,
     storage::Storage,
     // validator::Validator, // disabled for compilation
@@ -13,8 +14,6 @@ pub const PROTOCOL_VERSION: u32 = 1;  // Increment when breaking changes are mad
 pub const MIN_COMPATIBLE_VERSION: u32 = 1;  // Minimum version we can work with
 
 // PRODUCTION CONSTANTS - No hardcoded magic numbers!
-const ROTATION_INTERVAL_BLOCKS: u64 = 30; // Producer rotation every 30 blocks
-const MIN_BYZANTINE_NODES: usize = 4; // 3f+1 where f=1
 const FAST_SYNC_THRESHOLD: u64 = 10; // Trigger fast sync if behind by 10+ blocks (lowered from 50 for faster detection)  
 const FAST_SYNC_TIMEOUT_SECS: u64 = 60; // Fast sync timeout
 const BACKGROUND_SYNC_TIMEOUT_SECS: u64 = 30; // Background sync timeout
@@ -23,6 +22,9 @@ const SNAPSHOT_FULL_INTERVAL: u64 = 43200; // Full snapshot every 12 hours (43,2
 const SNAPSHOT_INCREMENTAL_INTERVAL: u64 = 3600; // Incremental snapshot every 1 hour (3,600 microblocks = 40 macroblocks)
 const API_HEALTH_CHECK_RETRIES: u32 = 5; // API health check attempts
 const API_HEALTH_CHECK_DELAY_SECS: u64 = 2; // Delay between health checks
+const DEFAULT_PRODUCTION_GRACE_SECS: u64 = 120; // Bootstrap grace before producing without enough peers
+const DEFAULT_MAX_CLOCK_SKEW_SECS: u64 = 5; // Accepted block timestamp drift from our clock
+const MAX_CLOCK_SKEW_LIMIT_SECS: u64 = 60; // Larger tolerances would let producers skew reward windows
 
 // FINALITY WINDOW: Production-grade value for Byzantine safety
 // CRITICAL: Blocks must be this deep to be used for deterministic entropy
@@ -31,12 +33,22 @@ const API_HEALTH_CHECK_DELAY_SECS: u64 = 2; // Delay between health checks
 // - P2P block propagation (~500ms-1s)
 // - Node synchronization during failover
 // - Byzantine consensus coordination
-const FINALITY_WINDOW: u64 = 10; // 10 blocks = 10 seconds (safe for production)
+pub(crate) const FINALITY_WINDOW: u64 = 10; // 10 blocks = 10 seconds (safe for production)
 
-// EMISSION INTERVAL: Reward emission every 4 hours
-// CRITICAL: Deterministic emission block calculation
-// 4 hours * 60 minutes * 60 seconds = 14,400 seconds = 14,400 blocks (at 1 block/sec)
-const EMISSION_INTERVAL_BLOCKS: u64 = 14400; // 4 hours in blocks
+// EMISSION INTERVAL: one emission block closes each reward window
+// CRITICAL: Deterministic emission block calculation - the window length is a
+// chain parameter (`ChainParams::reward_window_secs`, 4 hours by default)
+
+/// Start of the reward window containing `now_secs`
+pub fn reward_window_start(now_secs: u64) -> u64 {
+    get_chain_params().reward_window_start(now_secs)
+}
+
+/// The reward window open at `now_secs`, unless it has already been processed
+fn due_reward_window(params: &ChainParams, now_secs: u64, reward_manager: &PhaseAwareRewardManager) -> Option<u64> {
+    let window_start = params.reward_window_start(now_secs);
+    (!reward_manager.is_window_processed(window_start)).then_some(window_start)
+}
 
 // PING SAMPLING: Production-ready scalability parameters
 // CRITICAL: Sample size determines on-chain storage vs security trade-off
@@ -79,25 +91,26 @@ mod producer_cache {
     pub static CACHED_PRODUCER_SELECTION: OnceLock<Mutex<HashMap<u64, (String, Vec<(String, f64)>)>>> = OnceLock::new();
 }
 
-use qnet_state::{State as StateManager, Account, Transaction, Block, BlockType, MicroBlock, MacroBlock, LightMicroBlock, ConsensusData};
+use qnet_state::{State as StateManager, StateSnapshot, Account, Transaction, Block, BlockType, MicroBlock, MacroBlock, LightMicroBlock, ConsensusData};
 use qnet_mempool::{SimpleMempool, SimpleMempoolConfig};
 use qnet_consensus::{ConsensusEngine, ConsensusConfig, NodeId, CommitRevealConsensus, ConsensusError};
 use qnet_consensus::lazy_rewards::{PhaseAwareRewardManager, NodeType as RewardNodeType};
 use qnet_consensus::reputation::{Evidence, MaliciousBehavior};
 use qnet_sharding::{ShardCoordinator, ParallelValidator};
 use crate::quantum_poh::QuantumPoH;
+use crate::checkpoint::TrustedCheckpoint;
+use crate::logging::StructuredEvent;
+use crate::network_config::{ByzantineParams, ChainParams, get_byzantine_params, get_chain_params};
+use crate::node_config::NodeConfig;
 use std::sync::Arc;
 use std::collections::HashMap;
 use tokio::sync::RwLock;
 use hex;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-/// Safe timestamp getter with fallback
+/// Safe timestamp getter: wall-clock seconds for code paths without access to the node's `Clock`
 fn get_timestamp_safe() -> u64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_else(|_| Duration::from_secs(0))
-        .as_secs()
+    SystemClock.now_secs()
 }
 use std::env;
 use std::sync::Mutex;
@@ -124,6 +137,51 @@ pub static NODE_IS_SYNCHRONIZED: AtomicBool = AtomicBool::new(false);
 static SYNC_START_TIME: AtomicU64 = AtomicU64::new(0);
 static FAST_SYNC_START_TIME: AtomicU64 = AtomicU64::new(0);
 
+// DEADLOCK PROTECTION: Last time the local height advanced, so a slow but progressing
+// sync is not mistaken for a hung one
+static SYNC_PROGRESS_HEIGHT: AtomicU64 = AtomicU64::new(0);
+static SYNC_PROGRESS_TIME: AtomicU64 = AtomicU64::new(0);
+const SYNC_WATCHDOG_INTERVAL_SECS: u64 = 15;
+
+/// Record the local height seen by the sync watchdog; advances reset the stall timer
+fn record_sync_progress(local_height: u64, now: u64) {
+    if local_height > SYNC_PROGRESS_HEIGHT.load(Ordering::Relaxed) || SYNC_PROGRESS_TIME.load(Ordering::Relaxed) == 0 {
+        SYNC_PROGRESS_HEIGHT.store(local_height, Ordering::Relaxed);
+        SYNC_PROGRESS_TIME.store(now, Ordering::Relaxed);
+    }
+}
+
+/// A sync is stalled when it has run past the deadlock timeout AND the height
+/// has not advanced within the timeout either
+fn sync_is_stalled(now: u64, started_at: u64, last_progress_at: u64) -> bool {
+    started_at > 0
+        && now.saturating_sub(started_at) > SYNC_DEADLOCK_TIMEOUT_SECS
+        && now.saturating_sub(started_at.max(last_progress_at)) > SYNC_DEADLOCK_TIMEOUT_SECS
+}
+
+/// One watchdog pass: clear stalled sync flags, returns true if any were cleared
+fn sync_watchdog_tick(now: u64, local_height: u64) -> bool {
+    record_sync_progress(local_height, now);
+    let last_progress_at = SYNC_PROGRESS_TIME.load(Ordering::Relaxed);
+    
+    let mut cleared = false;
+    for (name, flag, started) in [
+        ("Fast sync", &FAST_SYNC_IN_PROGRESS, &FAST_SYNC_START_TIME),
+        ("Background sync", &SYNC_IN_PROGRESS, &SYNC_START_TIME),
+    ] {
+        let started_at = started.load(Ordering::Relaxed);
+        if flag.load(Ordering::SeqCst) && sync_is_stalled(now, started_at, last_progress_at) {
+            println!("[HEALTH] 🔓 {} stalled for {}s at height {} (no progress for {}s), force clearing flag",
+                     name, now.saturating_sub(started_at), local_height,
+                     now.saturating_sub(started_at.max(last_progress_at)));
+            flag.store(false, Ordering::SeqCst);
+            started.store(0, Ordering::Relaxed);
+            cleared = true;
+        }
+    }
+    cleared
+}
+
 // CRITICAL: Global shared storage instance to avoid RocksDB lock conflicts
 // RocksDB does NOT support multiple connections to same database
 lazy_static::lazy_static! {
@@ -141,6 +199,7 @@ static RETRY_TOTAL: AtomicU64 = AtomicU64::new(0);           // Total retry atte
 static RETRY_SUCCESS: AtomicU64 = AtomicU64::new(0);         // Successful retries (validation passed)
 static RETRY_CERT_RACE: AtomicU64 = AtomicU64::new(0);       // Retries due to certificate race
 static RETRY_MISSING_PREV: AtomicU64 = AtomicU64::new(0);    // Retries due to missing previous block
+static DUPLICATE_BLOCKS_SKIPPED: AtomicU64 = AtomicU64::new(0); // Gossip copies of already stored blocks
 
 // NOTE: Removed ROTATION_NOTIFY - simple 1-second timing is more reliable
 // Testing showed that natural timing without interrupts prevents race conditions
@@ -150,6 +209,15 @@ lazy_static::lazy_static! {
     static ref ENTROPY_RESPONSES: Mutex<std::collections::HashMap<(u64, String), [u8; 32]>> = Mutex::new(std::collections::HashMap::new());
 }
 
+/// Drop entropy responses for heights more than FINALITY_WINDOW below `height`
+/// Entropy is only ever requested for the newest finalized block, so older heights are never read again
+fn prune_entropy_responses(responses: &mut HashMap<(u64, String), [u8; 32]>, height: u64) -> usize {
+    let before = responses.len();
+    let min_height = height.saturating_sub(FINALITY_WINDOW);
+    responses.retain(|(h, _), _| *h >= min_height);
+    before - responses.len()
+}
+
 // CRITICAL: Global quantum crypto instance to avoid repeated initialization
 lazy_static::lazy_static! {
     pub static ref GLOBAL_QUANTUM_CRYPTO: tokio::sync::Mutex<Option<crate::quantum_crypto::QNetQuantumCrypto>> = 
@@ -163,11 +231,57 @@ lazy_static::lazy_static! {
     pub static ref GLOBAL_MEMPOOL_INSTANCE: std::sync::Mutex<Option<Arc<RwLock<qnet_mempool::SimpleMempool>>>> = std::sync::Mutex::new(None);
 }
 
+// STATE CONSISTENCY: Local state roots snapshotted at macroblock boundaries
+// Maps macroblock index -> state root after applying microblock (index * 90)
+// Received macroblocks are checked against these before being accepted
+const MACROBLOCK_STATE_ROOT_HISTORY: usize = 64;
+lazy_static::lazy_static! {
+    static ref MACROBLOCK_STATE_ROOTS: Mutex<std::collections::BTreeMap<u64, [u8; 32]>> = Mutex::new(std::collections::BTreeMap::new());
+}
+
+/// Record the local state root once the last microblock of a macroblock window is applied
+fn record_macroblock_state_root(microblock_height: u64, state: &StateManager) {
+    if microblock_height == 0 || !microblock_height.is_multiple_of(90) {
+        return;
+    }
+    let root = state.compute_state_root();
+    if let Ok(mut roots) = MACROBLOCK_STATE_ROOTS.lock() {
+        roots.insert(microblock_height / 90, root);
+        while roots.len() > MACROBLOCK_STATE_ROOT_HISTORY {
+            roots.pop_first();
+        }
+    }
+}
+
+/// Local state root recorded for a macroblock index, if this node applied that window
+fn local_macroblock_state_root(macroblock_index: u64) -> Option<[u8; 32]> {
+    MACROBLOCK_STATE_ROOTS.lock().ok()?.get(&macroblock_index).copied()
+}
+
+// STATE CONSISTENCY: Halts this node's block production after repeated state-root mismatches
+lazy_static::lazy_static! {
+    static ref STATE_ROOT_BREAKER: Mutex<crate::state_breaker::StateRootBreaker> =
+        Mutex::new(crate::state_breaker::StateRootBreaker::from_env());
+}
+
+/// Whether repeated state-root mismatches have halted block production
+fn state_root_breaker_halted() -> bool {
+    STATE_ROOT_BREAKER.lock().map(|b| b.is_halted()).unwrap_or(false)
+}
+
 // CRITICAL: Track certificate requests to prevent DDoS (request flooding)
 // Maps certificate_serial -> last_request_timestamp
 lazy_static::lazy_static! {
     static ref REQUESTED_CERTIFICATES: Mutex<std::collections::HashMap<String, u64>> = Mutex::new(std::collections::HashMap::new());
 }
+const CERTIFICATE_REQUEST_COOLDOWN_SECS: u64 = 5;
+
+/// Drop certificate requests whose cooldown has passed (they no longer block a new request)
+fn prune_requested_certificates(requested: &mut HashMap<String, u64>, now: u64) -> usize {
+    let before = requested.len();
+    requested.retain(|_, last_request| now.saturating_sub(*last_request) < CERTIFICATE_REQUEST_COOLDOWN_SECS);
+    before - requested.len()
+}
 
 use sha3::{Sha3_256, Digest};
 use serde_json;
@@ -211,6 +325,18 @@ pub enum Region {
     Oceania,
 }
 
+/// Default ceiling on the parallel validation pool, whatever the core count
+pub const DEFAULT_MAX_VALIDATION_THREADS: usize = 32;
+
+/// Threads for the parallel validation pool: an explicit `requested` count, or
+/// the effective cores (minimum 2), never above `ceiling`.
+/// The pool shares the machine with the Tokio runtime (P2P, RPC, PoH ticker) and
+/// the blocking pool used for storage, so it is capped rather than sized to every core.
+pub fn validation_thread_count(effective_cpu_count: usize, requested: Option<usize>, ceiling: usize) -> usize {
+    let threads = requested.unwrap_or_else(|| effective_cpu_count.max(2));
+    threads.min(ceiling.max(1)).max(1)
+}
+
 /// Performance configuration from environment variables
 #[derive(Debug, Clone)]
 pub struct PerformanceConfig {
@@ -268,19 +394,20 @@ impl Default for PerformanceConfig {
             effective_cpu_count >= 8
         };
         
-        // AUTO-TUNE: Thread count = effective CPUs (minimum 2, recommended 4)
-        let auto_parallel_threads = env::var("QNET_PARALLEL_THREADS")
+        // AUTO-TUNE: Thread count = effective CPUs (minimum 2), capped by the validation ceiling
+        let requested_parallel_threads = env::var("QNET_PARALLEL_THREADS")
             .ok()
-            .and_then(|s| s.parse().ok())
-            .unwrap_or_else(|| {
-                // Use effective cores, but respect CPU limit
-                // Minimum 2 threads, recommended 4+ for production
-                if effective_cpu_count >= 4 {
-                    effective_cpu_count // Use all allocated cores
-                } else {
-                    effective_cpu_count.max(2) // Minimum 2, don't force 4 on limited systems
-                }
-            });
+            .and_then(|s| s.parse().ok());
+        let max_validation_threads = env::var("QNET_MAX_VALIDATION_THREADS")
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok())
+            .filter(|&n| n > 0)
+            .unwrap_or(DEFAULT_MAX_VALIDATION_THREADS);
+        let auto_parallel_threads = validation_thread_count(
+            effective_cpu_count,
+            requested_parallel_threads,
+            max_validation_threads,
+        );
         
         println!("[Performance] 🔧 AUTO-TUNE: Detected {} CPU cores", cpu_count);
         if cpu_limit_percent < 100 {
@@ -302,7 +429,8 @@ impl Default for PerformanceConfig {
         }
         println!("[Performance] ⚡ Parallel validation: {} (threshold: ≥8 cores)", 
                 if auto_parallel_validation { "ENABLED" } else { "DISABLED" });
-        println!("[Performance] 🧵 Parallel threads: {}", auto_parallel_threads);
+        println!("[Performance] 🧵 Parallel threads: {} (validation ceiling: {})", 
+                auto_parallel_threads, max_validation_threads);
         
         Self {
             enable_sharding: env::var("QNET_ENABLE_SHARDING").unwrap_or_default() == "1",
@@ -325,22 +453,129 @@ impl Default for PerformanceConfig {
     }
 }
 
+/// Startup gate: withhold block production until enough peers are connected
+/// to know the real network height, or until the bootstrap grace period
+/// elapses (the genesis-of-network case). Once released it stays open.
+#[derive(Debug, Clone)]
+pub struct ProductionGate {
+    pub min_peers_to_produce: usize,
+    pub grace_period: Duration,
+    released: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProductionGateStatus {
+    /// Not enough peers and grace period still running
+    Withheld,
+    /// Released on this check because the peer threshold was reached
+    ReleasedByPeers,
+    /// Released on this check because the grace period elapsed
+    ReleasedByGracePeriod,
+    /// Already released earlier
+    Open,
+}
+
+impl ProductionGate {
+    pub fn new(min_peers_to_produce: usize, grace_period: Duration) -> Self {
+        Self { min_peers_to_produce, grace_period, released: false }
+    }
+    
+    /// `QNET_MIN_PEERS_TO_PRODUCE` (default: 3f+1 Byzantine minimum minus ourselves)
+    /// and `QNET_PRODUCTION_GRACE_SECS`
+    pub fn from_env() -> Self {
+        let min_peers = env::var("QNET_MIN_PEERS_TO_PRODUCE")
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok())
+            .unwrap_or_else(|| get_byzantine_params().min_peers());
+        let grace_secs = env::var("QNET_PRODUCTION_GRACE_SECS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_PRODUCTION_GRACE_SECS);
+        Self::new(min_peers, Duration::from_secs(grace_secs))
+    }
+    
+    pub fn is_released(&self) -> bool {
+        self.released
+    }
+    
+    pub fn check(&mut self, peer_count: usize, since_start: Duration) -> ProductionGateStatus {
+        if self.released {
+            return ProductionGateStatus::Open;
+        }
+        if peer_count >= self.min_peers_to_produce {
+            self.released = true;
+            ProductionGateStatus::ReleasedByPeers
+        } else if since_start >= self.grace_period {
+            self.released = true;
+            ProductionGateStatus::ReleasedByGracePeriod
+        } else {
+            ProductionGateStatus::Withheld
+        }
+    }
+}
+
+/// Empty block policy: blocks with transactions are produced on the next tick,
+/// blocks without them at most once per `QNET_EMPTY_BLOCK_INTERVAL` so an idle
+/// chain keeps advancing without writing a block every second.
+/// Rotation rounds are counted in blocks, so a slower cadence only stretches them in time.
+#[derive(Debug, Clone)]
+pub struct EmptyBlockPolicy {
+    pub interval_secs: u64,
+    chain_params: ChainParams,
+}
+
+impl EmptyBlockPolicy {
+    pub fn from_chain_params(chain_params: &ChainParams) -> Self {
+        Self { interval_secs: chain_params.empty_block_interval_secs, chain_params: chain_params.clone() }
+    }
+    
+    /// Whether to produce block `next_height` now, given the time of the last block on the chain
+    pub fn should_produce(&self, has_transactions: bool, next_height: u64, last_block_secs: u64, now_secs: u64) -> bool {
+        if has_transactions || last_block_secs == 0 {
+            return true;
+        }
+        // A new producer's first block is never delayed: it shows the takeover to failover watchers
+        if self.chain_params.is_rotation_start(next_height) {
+            return true;
+        }
+        now_secs.saturating_sub(last_block_secs) >= self.interval_secs
+    }
+    
+    /// Added to failover and stall timeouts so a producer waiting out the interval is not replaced
+    pub fn liveness_slack(&self) -> Duration {
+        Duration::from_secs(self.chain_params.empty_block_slack_secs())
+    }
+}
+
 /// Track signed blocks for double-sign detection
 #[derive(Clone)]
 pub struct SignedBlockTracker {
     // Map: height -> (block_hash, producer_id, timestamp)
     signed_blocks: Arc<RwLock<HashMap<u64, Vec<(String, String, u64)>>>>,
     max_history: usize,  // Keep last N heights for memory efficiency
+    clock: Arc<dyn Clock>,
+    max_clock_skew_secs: u64,
 }
 
 impl SignedBlockTracker {
     pub fn new() -> Self {
+        Self::with_clock(Arc::new(SystemClock))
+    }
+    
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
         Self {
             signed_blocks: Arc::new(RwLock::new(HashMap::new())),
             max_history: 100,  // Keep last 100 block heights
+            clock,
+            max_clock_skew_secs: max_clock_skew_from_env(),
         }
     }
     
+    pub fn with_max_clock_skew(mut self, secs: u64) -> Self {
+        self.max_clock_skew_secs = secs;
+        self
+    }
+    
     /// Check for double-sign and add new signature
     pub async fn check_and_add(&self, height: u64, block_hash: &str, producer: &str) -> Option<Evidence> {
         let mut blocks = self.signed_blocks.write().await;
@@ -358,13 +593,13 @@ impl SignedBlockTracker {
                     evidence_type: "double_sign".to_string(),
                     node_id: producer.to_string(),
                     evidence_data: format!("height:{},hash1:{},hash2:{}", height, existing_hash, block_hash).into_bytes(),
-                    timestamp: get_timestamp_safe(),
+                    timestamp: self.clock.now_secs(),
                 });
             }
         }
         
         // Add new signature
-        let timestamp = get_timestamp_safe();
+        let timestamp = self.clock.now_secs();
         entries.push((block_hash.to_string(), producer.to_string(), timestamp));
         
         // Clean old entries to prevent memory bloat
@@ -377,45 +612,94 @@ impl SignedBlockTracker {
     }
     
     /// Detect invalid blocks
-    pub fn detect_invalid_block(&self, block: &MicroBlock) -> Option<Evidence> {
-        // Check timestamp is not too far in future (>5 seconds)
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        if block.timestamp > now + 5 {
-            println!("[SECURITY] ⚠️ TIME MANIPULATION: Block from future by {}s", block.timestamp - now);
+    /// `previous_timestamp` is the timestamp of the block before `block`, if known
+    pub fn detect_invalid_block(&self, block: &MicroBlock, previous_timestamp: Option<u64>) -> Option<Evidence> {
+        // Check timestamp is not too far in future
+        let now = self.clock.now_secs();
+        if block.timestamp > now + self.max_clock_skew_secs {
+            println!("[SECURITY] ⚠️ TIME MANIPULATION: Block from future by {}s (tolerance {}s)", 
+                     block.timestamp - now, self.max_clock_skew_secs);
             return Some(Evidence {
-                evidence_type: "time_manipulation".to_string(),
+                evidence_type: "time_manipulation_future".to_string(),
                 node_id: block.producer.clone(),
                 evidence_data: format!("future_by:{}s", block.timestamp - now).into_bytes(),
                 timestamp: now,
             });
         }
         
+        // Check timestamp does not go back behind the previous block
+        if let Some(previous) = previous_timestamp {
+            if block.timestamp + self.max_clock_skew_secs < previous {
+                println!("[SECURITY] ⚠️ TIME MANIPULATION: Block #{} is {}s older than its parent (tolerance {}s)", 
+                         block.height, previous - block.timestamp, self.max_clock_skew_secs);
+                return Some(Evidence {
+                    evidence_type: "time_manipulation_past".to_string(),
+                    node_id: block.producer.clone(),
+                    evidence_data: format!("past_by:{}s", previous - block.timestamp).into_bytes(),
+                    timestamp: now,
+                });
+            }
+        }
+        
         None
     }
 }
 
+/// `QNET_MAX_CLOCK_SKEW_SECS` (default 5, accepted range 1-60)
+fn max_clock_skew_from_env() -> u64 {
+    parse_max_clock_skew(env::var("QNET_MAX_CLOCK_SKEW_SECS").ok().as_deref())
+}
+
+fn parse_max_clock_skew(value: Option<&str>) -> u64 {
+    let Some(value) = value else {
+        return DEFAULT_MAX_CLOCK_SKEW_SECS;
+    };
+    match value.trim().parse::<u64>() {
+        Ok(secs) if (1..=MAX_CLOCK_SKEW_LIMIT_SECS).contains(&secs) => secs,
+        _ => {
+            println!("[CONFIG] ⚠️ QNET_MAX_CLOCK_SKEW_SECS={} outside 1-{} - using {}s", 
+                     value, MAX_CLOCK_SKEW_LIMIT_SECS, DEFAULT_MAX_CLOCK_SKEW_SECS);
+            DEFAULT_MAX_CLOCK_SKEW_SECS
+        }
+    }
+}
+
+/// Remove and return the buffered blocks directly after `current_height`, ascending, up to the next gap
+fn drain_contiguous<T>(pending: &mut HashMap<u64, T>, current_height: u64) -> Vec<T> {
+    (current_height.saturating_add(1)..)
+        .map_while(|height| pending.remove(&height))
+        .collect()
+}
+
 /// Track rotation progress for atomic rewards
 #[derive(Clone)]
 pub struct RotationTracker {
     // leadership_round -> (producer_id, blocks_created, start_height)  
     current_rotations: Arc<RwLock<HashMap<u64, (String, u32, u64)>>>,
+    chain_params: ChainParams,
 }
 
 impl RotationTracker {
     pub fn new() -> Self {
+        Self::with_chain_params(get_chain_params().clone())
+    }
+    
+    pub fn with_chain_params(chain_params: ChainParams) -> Self {
         Self {
             current_rotations: Arc::new(RwLock::new(HashMap::new())),
+            chain_params,
         }
     }
     
+    /// Blocks in a full rotation
+    pub fn rotation_interval(&self) -> u64 {
+        self.chain_params.rotation_interval_blocks
+    }
+    
     /// Track block production
     pub async fn track_block(&self, height: u64, producer: &str) {
-        // CRITICAL FIX: Blocks 1-30 are round 0, 31-60 are round 1, etc.
-        let round = if height == 0 {
-            0  // Genesis block
-        } else {
-            (height - 1) / ROTATION_INTERVAL_BLOCKS
-        };
+        // CRITICAL FIX: Blocks 1-N are round 0, N+1-2N are round 1, etc.
+        let round = self.chain_params.leadership_round(height);
         let mut rotations = self.current_rotations.write().await;
         
         let entry = rotations.entry(round).or_insert((producer.to_string(), 0, height));
@@ -424,8 +708,8 @@ impl RotationTracker {
     
     /// Check if rotation completed and return producer info
     pub async fn check_rotation_complete(&self, height: u64) -> Option<(String, u32)> {
-        if height % ROTATION_INTERVAL_BLOCKS == 0 && height > 0 {
-            let round = (height - 1) / ROTATION_INTERVAL_BLOCKS; // Previous round
+        if self.chain_params.is_rotation_end(height) {
+            let round = self.chain_params.leadership_round(height); // Round ending at this block
             let mut rotations = self.current_rotations.write().await;
             
             if let Some((producer, blocks, _)) = rotations.remove(&round) {
@@ -517,6 +801,12 @@ pub struct BlockchainNode {
     // Event-based block notification system (replaces polling in consensus listener)
     // Sender broadcasts new block height to all subscribers
     block_event_tx: tokio::sync::broadcast::Sender<u64>,
+    
+    // Time source for all "now" reads (MockClock in tests)
+    clock: Arc<dyn Clock>,
+    
+    // Operator-triggered rollback + resync (admin API)
+    resync: Arc<crate::resync::ResyncController>,
 }
 
 impl BlockchainNode {
@@ -545,29 +835,53 @@ impl BlockchainNode {
         self.tower_bft.clone()
     }
     
-    /// Process reward window (called by RPC system every 4 hours)
+    /// Get the node's time source
+    pub fn get_clock(&self) -> Arc<dyn Clock> {
+        self.clock.clone()
+    }
+    
+    /// Replace the time source (node timing and double-sign/future-block detection)
+    /// Set before `start()`: background tasks capture the clock when spawned
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.signed_block_tracker = Arc::new(SignedBlockTracker::with_clock(clock.clone()));
+        self.clock = clock;
+        self
+    }
+    
+    /// Process reward window (called by RPC system once per reward window, 4 hours by default)
+    ///
+    /// Every window advances the reward manager's window cursor exactly once.
+    /// A window with no pings (e.g. a brief attestation outage) is recorded as
+    /// processed with zero emission: nothing is accumulated for later, so the
+    /// next active window emits only its own single-window amount. Re-running
+    /// an already processed window is a no-op and never emits twice.
     pub async fn process_reward_window(&self) -> Result<(), QNetError> {
-        println!("[REWARDS] ⏰ Processing 4-hour reward window...");
+        StructuredEvent::new("REWARDS", "reward_window_started", "⏰ Processing reward window...").emit();
         
         let mut reward_manager = self.reward_manager.write().await;
         
         // CRITICAL: Build Merkle commitment with sampling for scalable deterministic emission
         // This approach scales to millions of nodes while maintaining Byzantine security
-        let current_time = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-        let window_start = current_time - (current_time % (4 * 60 * 60)); // Start of current 4-hour window
+        // Check the window cursor BEFORE collecting pings or submitting a commitment,
+        // so a repeated call (or a restart) within the same window is a complete no-op
+        let chain_params = get_chain_params();
+        let current_time = self.clock.now_secs();
+        let window_start = match due_reward_window(chain_params, current_time, &reward_manager) {
+            Some(window_start) => window_start,
+            None => {
+                let window_start = chain_params.reward_window_start(current_time);
+                StructuredEvent::new("REWARDS", "reward_window_skipped", format!("⏭️ Window {} already processed, skipping", window_start)).emit();
+                return Ok(());
+            }
+        };
         let current_height = self.get_height().await;
         
-        // CRITICAL: Calculate blocks in this 4-hour window
-        // 1 block per second, 4 hours = 14400 blocks
-        let blocks_in_window = 4 * 60 * 60; // 14400 blocks
-        let window_start_height = current_height.saturating_sub(blocks_in_window);
-        let window_end_height = current_height;
+        // CRITICAL: Blocks in this window (validators expect exactly this range)
+        let (window_start_height, window_end_height) = chain_params.reward_window_heights(current_height);
         
-        println!("[REWARDS] 🌳 Building Merkle commitment for window {}-{}", 
-                 window_start_height, window_end_height);
+        StructuredEvent::new("REWARDS", "reward_commitment_started", format!("🌳 Building Merkle commitment for window {}-{}", window_start_height, window_end_height))
+            .height(window_end_height)
+            .emit();
         
         // ================================================================
         // PRODUCTION: Collect data from GOSSIP-SYNCED sources (not local!)
@@ -758,44 +1072,21 @@ impl BlockchainNode {
             
             // STEP 3: Deterministic sampling using FINALITY_WINDOW entropy
             // This ensures ALL nodes select the SAME samples
-            let entropy_height = current_height.saturating_sub(FINALITY_WINDOW);
-            let entropy_block = self.storage.load_microblock(entropy_height)
-                .map_err(|e| QNetError::StorageError(format!("Failed to load entropy block: {}", e)))?
-                .ok_or_else(|| QNetError::StorageError("Entropy block not found".to_string()))?;
-            
-            // Create deterministic seed
-            // OPTIMIZED: SHA3-256 (32 bytes) instead of SHA3-512 (64 bytes)
-            // 20% faster, still quantum-resistant (128-bit security against Grover)
-            use sha3::{Sha3_256, Digest};
-            let mut seed_hasher = Sha3_256::new();
-            seed_hasher.update(b"QNet_Ping_Sampling_v1");
-            seed_hasher.update(&entropy_block);
-            seed_hasher.update(&window_start_height.to_le_bytes());
-            let sample_seed = seed_hasher.finalize();
-            let sample_seed_hex = hex::encode(&sample_seed[..]);
-            
-            // Calculate sample size: 1% or 10K minimum
-            // Note: total_count already defined above
-            let sample_size = ((total_count as u32 * PING_SAMPLE_PERCENTAGE) / 100)
-                .max(MIN_PING_SAMPLES.min(total_count) as u32) as usize;
+            use crate::ping_sampling::{derive_sample_seed, sample_indices, FinalityWindowEntropy};
+            let entropy = FinalityWindowEntropy::new(&self.storage);
+            let sample_seed = derive_sample_seed(&entropy, current_height, window_start_height)
+                .map_err(QNetError::StorageError)?;
+            let sample_seed_hex = hex::encode(sample_seed);
+            
+            // Calculate sample size: 1% or 10K minimum, capped at the pings available
+            let sample_size = crate::ping_sampling::sample_size(total_count, PING_SAMPLE_PERCENTAGE, MIN_PING_SAMPLES);
             
             println!("[REWARDS] 🎲 Sampling {} pings ({} total, {}%)",
                      sample_size, total_count, PING_SAMPLE_PERCENTAGE);
             
             // Deterministic sampling
             let mut ping_samples = Vec::new();
-            for i in 0..sample_size {
-                // Deterministic index selection
-                use sha3::Sha3_256;
-                let mut index_hasher = Sha3_256::new();
-                index_hasher.update(&sample_seed);
-                index_hasher.update(&(i as u32).to_le_bytes());
-                let hash = index_hasher.finalize();
-                let index = u64::from_le_bytes([
-                    hash[0], hash[1], hash[2], hash[3],
-                    hash[4], hash[5], hash[6], hash[7],
-                ]) as usize % total_count;
-                
+            for index in sample_indices(&sample_seed, sample_size, total_count) {
                 // Generate Merkle proof for this ping
                 use qnet_core::crypto::merkle::generate_merkle_proof;
                 let merkle_proof = generate_merkle_proof(&ping_hashes, index)
@@ -847,6 +1138,8 @@ impl BlockchainNode {
                 nonce: 0,
                 data: Some(format!("Ping Commitment: {} total, {} successful, root: {}",
                                  total_pings, successful_pings, &merkle_root[..16])),
+                chain_id: qnet_state::transaction::UNBOUND_CHAIN_ID, // System operation
+                valid_until: None,
             };
             
             // Calculate hash
@@ -861,24 +1154,30 @@ impl BlockchainNode {
             }
         }
         
-        println!("[REWARDS] ✅ Merkle commitment built and submitted");
+        if total_pings > 0 {
+            StructuredEvent::new("REWARDS", "reward_commitment_submitted", "✅ Merkle commitment built and submitted").emit();
+        }
         
         // Process the current window (calculates pending rewards based on ping history)
-        reward_manager.force_process_window()
+        // Advances the window cursor even for empty windows
+        let window_result = reward_manager.process_window_at(window_start)
             .map_err(|e| QNetError::ConsensusError(format!("Failed to process reward window: {}", e)))?;
         
-        // Get statistics
-        let pending_rewards = reward_manager.get_all_pending_rewards();
+        // CRITICAL: Persist the cursor so a restart inside this window can't emit it again
+        if let Err(e) = self.storage.save_reward_window_cursor(reward_manager.get_window_cursor()) {
+            eprintln!("[REWARDS] ⚠️ Failed to save reward window cursor: {}", e);
+        }
         
-        if pending_rewards.is_empty() {
-            println!("[REWARDS] ⚠️ No nodes eligible for rewards in this window");
+        if window_result.window_emission == 0 {
+            StructuredEvent::new("REWARDS", "reward_window_empty", format!("⚠️ No nodes eligible for rewards in window {} (processed, nothing emitted)", window_start)).emit();
             return Ok(());
         }
         
-        // Calculate total emission for this window
-        let total_emission: u64 = pending_rewards.iter()
-            .map(|(_, amount)| amount)
-            .sum();
+        // Get statistics
+        let pending_rewards = reward_manager.get_all_pending_rewards();
+        
+        // Total emission for THIS window only (unclaimed rewards from earlier windows are not re-emitted)
+        let total_emission = window_result.window_emission;
         
         // CRITICAL: Update total supply IMMEDIATELY when rewards are calculated
         // Not when claimed! Emission happens every 4 hours regardless
@@ -895,43 +1194,34 @@ impl BlockchainNode {
                 let state = self.state.read().await;
                 let total_supply = (*state).get_total_supply();
                 println!("   🏦 New total supply: {} QNC", total_supply / 1_000_000_000);
-                println!("   📊 Eligible nodes: {}", pending_rewards.len());
+                println!("   📊 Eligible nodes: {}", window_result.eligible_nodes);
                 
                 // CRITICAL: Create system emission transaction for blockchain record
                 if actual_emission > 0 {
-                    let current_time = SystemTime::now()
-                        .duration_since(UNIX_EPOCH)
-                        .unwrap_or_default()
-                        .as_secs();
+                    let current_time = self.clock.now_secs();
                     
                     // DECENTRALIZED: No signature needed - all nodes validate emission amount independently
                     // Bitcoin-style: validation through consensus rules, not cryptographic signature
-                    let mut emission_tx = qnet_state::Transaction {
-                        from: "system_emission".to_string(),
-                        to: Some("system_rewards_pool".to_string()),
-                        amount: actual_emission,
-                        tx_type: qnet_state::TransactionType::RewardDistribution,
-                        timestamp: current_time,
-                        hash: String::new(),
-                        signature: None, // No signature - validated through deterministic rules
-                        public_key: None, // Not needed for system transactions
-                        gas_price: 0,
-                        gas_limit: 0,
-                        nonce: 0,
-                        data: Some(format!("Emission: {} QNC, Window: {}, Total Supply: {} QNC", 
-                                         actual_emission / 1_000_000_000, 
-                                         current_time / (4 * 60 * 60), 
-                                         total_supply / 1_000_000_000)),
-                    };
-                    
-                    // Calculate transaction hash
-                    emission_tx.hash = emission_tx.calculate_hash();
+                    let emission_tx = qnet_state::TransactionBuilder::new()
+                        .from("system_emission")
+                        .to("system_rewards_pool", actual_emission)
+                        .timestamp(current_time)
+                        .data(&format!("Emission: {} QNC, Window: {}, Total Supply: {} QNC", 
+                                       actual_emission / 1_000_000_000, 
+                                       get_chain_params().reward_window_number(current_time), 
+                                       total_supply / 1_000_000_000))
+                        .build_system(qnet_state::TransactionType::RewardDistribution);
                     
                     // Add emission transaction to mempool for blockchain record
-                    if let Err(e) = self.add_transaction_to_mempool(emission_tx).await {
-                        eprintln!("[REWARDS] ⚠️ Failed to add emission tx to mempool: {}", e);
-                    } else {
-                        println!("[REWARDS] 📝 Emission transaction added to mempool for consensus");
+                    match emission_tx {
+                        Ok(emission_tx) => {
+                            if let Err(e) = self.add_transaction_to_mempool(emission_tx).await {
+                                eprintln!("[REWARDS] ⚠️ Failed to add emission tx to mempool: {}", e);
+                            } else {
+                                println!("[REWARDS] 📝 Emission transaction added to mempool for consensus");
+                            }
+                        }
+                        Err(e) => eprintln!("[REWARDS] ⚠️ Invalid emission transaction: {}", e),
                     }
                 }
             }
@@ -953,7 +1243,7 @@ impl BlockchainNode {
         }
         
         // Rewards are now in pending_rewards - users can claim them anytime
-        println!("[REWARDS] ✅ Rewards available for claiming (lazy rewards)");
+        StructuredEvent::new("REWARDS", "reward_window_completed", "✅ Rewards available for claiming (lazy rewards)").emit();
         Ok(())
     }
     
@@ -1003,9 +1293,13 @@ impl BlockchainNode {
         
         // Continue with normal Merkle tree building...
         // (This is simplified - in production, full logic would be duplicated or extracted)
-        reward_manager.force_process_window()
+        reward_manager.process_window_at(window_start)
             .map_err(|e| QNetError::ConsensusError(format!("Failed to process window: {}", e)))?;
         
+        if let Err(e) = self.storage.save_reward_window_cursor(reward_manager.get_window_cursor()) {
+            eprintln!("[REWARDS] ⚠️ Failed to save reward window cursor: {}", e);
+        }
+        
         Ok(())
     }
     
@@ -1021,6 +1315,7 @@ impl BlockchainNode {
             bootstrap_peers,
             NodeType::Full,
             region,
+            NodeConfig::from_env()?,
         ).await
     }
     
@@ -1031,6 +1326,7 @@ impl BlockchainNode {
         bootstrap_peers: Vec<String>,
         node_type: NodeType,
         region: Region,
+        config: NodeConfig,
     ) -> Result<Self, QNetError> {
         // NOTE: Light node server blocking is already implemented in bin/qnet-node.rs (lines 78-83, 173-184)
         // No need to duplicate the check here
@@ -1089,25 +1385,16 @@ impl BlockchainNode {
         let state = Arc::new(RwLock::new(StateManager::new()));
         
         // Initialize production-ready mempool with AUTO-SCALING
-        let auto_mempool_size = if let Some(manual_size) = std::env::var("QNET_MEMPOOL_SIZE")
-            .ok()
-            .and_then(|s| s.parse().ok()) {
+        let auto_mempool_size = if let Some(manual_size) = config.mempool_size {
             // Manual override
             manual_size
         } else {
-            // AUTO-TUNE: Scale mempool based on network size
-            // Use same network size estimation as storage sharding
-            let network_size = storage.estimate_network_size_for_config();
-            
-            let calculated_size = match network_size {
-                0..=100 => 100_000,        // Genesis/test: 100k
-                101..=10_000 => 500_000,   // Small network: 500k
-                10_001..=100_000 => 1_000_000,  // Medium network: 1M
-                _ => 2_000_000,            // Large network: 2M
-            };
+            // AUTO-TUNE: Scale mempool based on the shared network size estimate
+            let network_size = storage.network_size_estimate();
+            let calculated_size = network_size.mempool_capacity();
             
             println!("[Mempool] 🔄 AUTO-SCALING: Network size {} nodes → {} tx capacity", 
-                    network_size, calculated_size);
+                    network_size.nodes, calculated_size);
             
             calculated_size
         };
@@ -1115,6 +1402,8 @@ impl BlockchainNode {
         let mempool_config = qnet_mempool::SimpleMempoolConfig {
             max_size: auto_mempool_size,
             min_gas_price: 1,
+            eviction_policy: qnet_mempool::EvictionPolicy::from_env(),
+            gas_floor: qnet_mempool::GasFloorConfig::from_env(),
         };
         
         let mempool = Arc::new(RwLock::new(qnet_mempool::SimpleMempool::new(mempool_config)));
@@ -1128,18 +1417,18 @@ impl BlockchainNode {
         let node_id = Self::generate_unique_node_id(node_type).await;
         
         // CRITICAL VALIDATION: Ensure Genesis nodes have proper IDs, not fallbacks
-        if std::env::var("QNET_BOOTSTRAP_ID").is_ok() || std::env::var("DOCKER_ENV").is_ok() {
+        if config.bootstrap_id.is_some() || std::env::var("DOCKER_ENV").is_ok() {
             // This is a Genesis node - MUST have proper genesis_node_XXX ID
             if !node_id.starts_with("genesis_node_") {
                 eprintln!("[CRITICAL] ❌ Genesis node has incorrect ID: {}", node_id);
                 eprintln!("[CRITICAL] ❌ Expected: genesis_node_XXX, got fallback ID!");
                 eprintln!("[CRITICAL] 🔧 Check environment variables:");
-                eprintln!("  QNET_BOOTSTRAP_ID = {:?}", std::env::var("QNET_BOOTSTRAP_ID"));
-                eprintln!("  QNET_ACTIVATION_CODE = {:?}", std::env::var("QNET_ACTIVATION_CODE"));
+                eprintln!("  QNET_BOOTSTRAP_ID = {:?}", config.bootstrap_id);
+                eprintln!("  QNET_ACTIVATION_CODE = {:?}", config.activation_code);
                 eprintln!("  DOCKER_ENV = {:?}", std::env::var("DOCKER_ENV"));
                 
                 // For Docker Genesis nodes, this is a critical error
-                if std::env::var("DOCKER_ENV").is_ok() && std::env::var("QNET_BOOTSTRAP_ID").is_ok() {
+                if std::env::var("DOCKER_ENV").is_ok() && config.bootstrap_id.is_some() {
                     panic!("[FATAL] Genesis node cannot start with fallback ID! Check QNET_BOOTSTRAP_ID environment variable!");
                 }
             } else {
@@ -1158,13 +1447,16 @@ impl BlockchainNode {
         let consensus_config = qnet_consensus::ConsensusConfig {
             commit_phase_duration: Duration::from_secs(12),    // OPTIMIZED: 12s commit phase (blocks 61-72)
             reveal_phase_duration: Duration::from_secs(12),    // OPTIMIZED: 12s reveal phase (blocks 73-84)
-            min_participants: 4,           // PRODUCTION: 4 nodes minimum for Byzantine safety (3f+1, f=1)
+            min_participants: get_byzantine_params().min_participants(), // PRODUCTION: 3f+1 for Byzantine safety
             max_participants: 1000,        // Maximum participants per round
             max_validators_per_round: 1000, // PRODUCTION: 1000 validators per round (per NETWORK_LOAD_ANALYSIS.md)
             enable_validator_sampling: true, // Enable sampling for scalability
             reputation_threshold: 0.70,    // 70% minimum reputation for participation
         };
         
+        get_byzantine_params().check_min_participants(consensus_config.min_participants)
+            .map_err(|e| QNetError::ConsensusError(format!("Invalid consensus config: {}", e)))?;
+        
         // Create REAL Byzantine consensus engine with commit-reveal protocol
         let mut consensus_engine = qnet_consensus::CommitRevealConsensus::new(node_id.clone(), consensus_config);
         
@@ -1226,12 +1518,10 @@ impl BlockchainNode {
         };
         
         // DATA CONSISTENCY CHECK: Detect potential issues but NEVER auto-delete
-        let is_genesis_node = std::env::var("QNET_BOOTSTRAP_ID").is_ok() || 
-                              std::env::var("QNET_GENESIS_BOOTSTRAP").unwrap_or_default() == "1";
+        let is_genesis_node = config.is_genesis();
         
         // Identify which network we're on
-        let network_type = std::env::var("QNET_NETWORK")
-            .unwrap_or_else(|_| "testnet".to_string());
+        let network_type = config.network.clone();
         
         // Check for potential data inconsistencies
         if is_genesis_node && height > 0 {
@@ -1272,9 +1562,8 @@ impl BlockchainNode {
         }
         
         // If user explicitly requests reset via environment variable
-        if std::env::var("QNET_FORCE_RESET").unwrap_or_default() == "1" {
-            let confirm = std::env::var("QNET_CONFIRM_RESET").unwrap_or_default();
-            if confirm == "YES" {
+        if config.force_reset {
+            if config.confirm_reset {
                 println!("[Node] ⚠️ FORCE RESET REQUESTED via QNET_FORCE_RESET=1 + QNET_CONFIRM_RESET=YES");
                 println!("[Node] 🧹 Resetting blockchain to height 0...");
                 
@@ -1297,17 +1586,12 @@ impl BlockchainNode {
         let perf_config = PerformanceConfig::default();
         
         // Security configuration (production mode)
-        let security_config = qnet_core::security::SecurityConfig::production(node_id.clone());
+        let mut security_config = qnet_core::security::SecurityConfig::production(node_id.clone());
+        security_config.admin_api_key = config.admin_api_key.clone();
         
         // Microblock interval (spec: exactly 1 second, June-2025)
         // For production, always use 1 second interval
-        let microblock_interval = Duration::from_secs(
-            env::var("QNET_MICROBLOCK_INTERVAL")
-                .ok()
-                .and_then(|s| s.parse::<u64>().ok())
-                .filter(|v| *v >= 1)
-                .unwrap_or(1) // Always 1 second for production
-        );
+        let microblock_interval = Duration::from_secs(config.microblock_interval_secs);
         
         // Create unified P2P with regional clustering
         println!("[UnifiedP2P] 🔍 DEBUG: Initializing unified P2P network");
@@ -1373,12 +1657,9 @@ impl BlockchainNode {
         // P2P FIX: Add Genesis bootstrap peers ONLY for Genesis nodes themselves
         // SCALABILITY: Regular nodes (Full/Light) should discover peers via DHT, not direct Genesis connection
         // This prevents Genesis nodes from being overwhelmed when millions of nodes join
-        if std::env::var("QNET_BOOTSTRAP_ID").is_ok() {
-            use crate::unified_p2p::get_genesis_bootstrap_ips;
-            let genesis_ips = get_genesis_bootstrap_ips();
-            let genesis_peers: Vec<String> = genesis_ips.iter()
-                .map(|ip| format!("{}:8001", ip))
-                .collect();
+        if config.bootstrap_id.is_some() {
+            use crate::unified_p2p::get_genesis_bootstrap_addrs;
+            let genesis_peers = get_genesis_bootstrap_addrs();
             
             println!("[P2P] 🌟 Genesis node: Adding {} Genesis bootstrap peers for initial network", genesis_peers.len());
             unified_p2p_instance.add_discovered_peers(&genesis_peers);
@@ -1395,13 +1676,14 @@ impl BlockchainNode {
         } else {
             // SCALABILITY: Regular nodes (Full/Light) in production with millions of nodes
             // Should NOT directly connect to Genesis nodes to avoid overload
-            // They will discover peers through DHT and peer exchange protocol
+            // They will discover peers through DHT and peer exchange protocol; the DHT bootstrap
+            // watchdog (started below) dials bootstrap peers only if DHT leaves them isolated
             match node_type {
                 NodeType::Light => {
-                    println!("[P2P] 📱 Light node: Will discover peers through DHT (no direct Genesis connection)");
+                    println!("[P2P] 📱 Light node: Will discover peers through DHT (bootstrap peers only as fallback)");
                 },
                 NodeType::Full => {
-                    println!("[P2P] 💻 Full node: Will discover peers through DHT (no direct Genesis connection)");
+                    println!("[P2P] 💻 Full node: Will discover peers through DHT (bootstrap peers only as fallback)");
                 },
                 NodeType::Super => {
                     // Super nodes might need some Genesis connections for consensus
@@ -1412,24 +1694,42 @@ impl BlockchainNode {
         
         let unified_p2p = Arc::new(unified_p2p_instance);
         
+        // PERSISTENCE: Restore invalid-block scores so a restart doesn't wipe peer history
+        match storage.load_raw(crate::invalid_blocks::INVALID_BLOCK_SCORES_KEY) {
+            Ok(Some(data)) => match unified_p2p.restore_invalid_block_scores(&data) {
+                Ok(count) => println!("[SECURITY] 📂 Restored invalid block scores for {} peers", count),
+                Err(e) => println!("[SECURITY] ⚠️ Failed to restore invalid block scores: {}", e),
+            },
+            Ok(None) => {}
+            Err(e) => println!("[SECURITY] ⚠️ Failed to load invalid block scores: {}", e),
+        }
+        
         // Start unified P2P (must start before blockchain creation)
         unified_p2p.start();
         
+        // DISCOVERY: Regular nodes fall back to bootstrap peers if DHT leaves them isolated
+        if config.bootstrap_id.is_none() {
+            crate::dht_bootstrap::spawn_dht_bootstrap_watchdog(
+                unified_p2p.clone(),
+                crate::dht_bootstrap::DhtBootstrapConfig::from_env(),
+                crate::unified_p2p::get_genesis_bootstrap_addrs(),
+            );
+        }
+        
         // QUANTUM AUTO-SCALING: Automatically enable sharding for large networks
+        let network_size = storage.network_size_estimate();
         let auto_enable_sharding = || -> bool {
             // Check manual override first
-            if env::var("QNET_ENABLE_SHARDING").unwrap_or_default() == "1" {
+            if config.enable_sharding {
                 return true;
             }
             
-            // AUTO-DETECTION based on peer count
-            let peer_count = unified_p2p.get_peer_count();
-            
-            if peer_count >= 10000 {
-                println!("[SHARDING] ⚡ AUTO-ENABLED for {} peers (threshold: 10000)", peer_count);
-                return true;
-            } else if peer_count >= 5000 && node_type == NodeType::Super {
-                println!("[SHARDING] ⚡ AUTO-ENABLED for Super node with {} peers", peer_count);
+            // AUTO-DETECTION based on the shared network size estimate
+            if network_size.sharding_auto_enabled(node_type == NodeType::Super) {
+                println!("[SHARDING] ⚡ AUTO-ENABLED for {} nodes (threshold: {}, Super: {})",
+                        network_size.nodes,
+                        crate::network_size::SHARDING_AUTO_ENABLE_NODES,
+                        crate::network_size::SHARDING_AUTO_ENABLE_NODES_SUPER);
                 return true;
             }
             
@@ -1439,7 +1739,11 @@ impl BlockchainNode {
         // Initialize sharding components for production
         let shard_coordinator = if perf_config.enable_sharding || auto_enable_sharding() {
             // QUANTUM OPTIMIZATION: Connect sharding to P2P network
-            let coordinator = Arc::new(qnet_sharding::ShardCoordinator::new());
+            // Shard count follows the same network size estimate as mempool sizing
+            let coordinator = Arc::new(qnet_sharding::ShardCoordinator::with_shard_count(
+                network_size.shard_count(),
+            ));
+            println!("[SHARDING] 📐 {} shards for {} nodes", network_size.shard_count(), network_size.nodes);
             
             // Register P2P shard info with coordinator
             println!("[SHARDING] 🔗 Connecting P2P shard {} to coordinator", unified_p2p.get_shard_id());
@@ -1452,9 +1756,18 @@ impl BlockchainNode {
         };
         
         let parallel_validator = if perf_config.parallel_validation {
-            Some(Arc::new(qnet_sharding::ParallelValidator::new(
-                perf_config.parallel_threads,
-            )))
+            // A constrained container may refuse new threads: validate sequentially instead of crashing
+            let validator = match qnet_sharding::ParallelValidator::new(perf_config.parallel_threads) {
+                Ok(validator) => {
+                    println!("[Node] 🧵 Parallel validation pool: {} threads", perf_config.parallel_threads);
+                    validator
+                }
+                Err(e) => {
+                    println!("[Node] ⚠️ {}, falling back to sequential validation", e);
+                    qnet_sharding::ParallelValidator::sequential()
+                }
+            };
+            Some(Arc::new(validator))
         } else {
             None
         };
@@ -1462,6 +1775,13 @@ impl BlockchainNode {
         // Initialize archive replication manager
         println!("[Node] 📦 Initializing archive replication manager...");
         let mut archive_manager = crate::archive_manager::ArchiveReplicationManager::new();
+        // Operators with spare disk may hold more history than the mandatory quota
+        if let Some(quota) = config.archive_quota {
+            match archive_manager.set_archive_quota(&node_type, quota) {
+                Ok(()) => println!("[Node] 📦 Archive quota: {} chunks (volunteered)", quota),
+                Err(e) => println!("[Node] ⚠️ Ignoring QNET_ARCHIVE_QUOTA: {}", e),
+            }
+        }
         
         // Initialize reward manager with current timestamp as genesis
         println!("[Node] 💰 Initializing lazy rewards system...");
@@ -1475,10 +1795,7 @@ impl BlockchainNode {
                     }
                     Err(_) => {
                         // Fallback to current time if can't parse
-                        let now = SystemTime::now()
-                            .duration_since(UNIX_EPOCH)
-                            .unwrap_or_default()
-                            .as_secs();
+                        let now = get_timestamp_safe();
                         println!("[REWARDS] ⚠️ Can't parse Genesis block, using current time: {}", now);
                         now
                     }
@@ -1486,17 +1803,20 @@ impl BlockchainNode {
             }
             _ => {
                 // No Genesis block yet - use current time (will be updated when Genesis is created)
-                let now = SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_secs();
+                let now = get_timestamp_safe();
                 println!("[REWARDS] 📅 No Genesis block yet, using current time: {}", now);
                 now
             }
         };
-        let reward_manager = Arc::new(RwLock::new(
-            PhaseAwareRewardManager::new(genesis_timestamp)
-        ));
+        let mut reward_manager = PhaseAwareRewardManager::new(genesis_timestamp);
+        // Per-node-type reward split (testnets); must be identical on every node
+        if let Some(multipliers) = config.reward_multipliers.clone() {
+            match reward_manager.set_reward_multipliers(multipliers) {
+                Ok(()) => println!("[REWARDS] ⚖️ Reward multipliers: {:?}", reward_manager.get_reward_multipliers()),
+                Err(e) => println!("[REWARDS] ⚠️ Ignoring QNET_REWARD_MULTIPLIERS: {}", e),
+            }
+        }
+        let reward_manager = Arc::new(RwLock::new(reward_manager));
         
         // CRITICAL: Update global pricing state with Genesis timestamp
         // This enables dynamic pricing in quantum_crypto.rs
@@ -1545,31 +1865,37 @@ impl BlockchainNode {
                     println!("[REWARDS] ⚠️ Failed to load pending rewards from storage: {}", e);
                 }
             }
+            
+            // Restore the reward window cursor (prevents re-emitting a processed window)
+            match storage.load_reward_window_cursor() {
+                Ok(Some(cursor)) => {
+                    reward_manager_guard.restore_window_cursor(cursor);
+                    println!("[REWARDS] 🔄 Restored reward window cursor: {}", cursor);
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    println!("[REWARDS] ⚠️ Failed to load reward window cursor: {}", e);
+                }
+            }
         }
         
         // Get node IP for archive registration - use ENV or auto-detect
-        let node_ip = match std::env::var("QNET_PUBLIC_IP") {
-            Ok(ip) => format!("{}:{}", ip, p2p_port),
-            Err(_) => {
-                // PRODUCTION: Auto-detect public IP or use P2P discovered address
-                // For now, fallback to local for development only
-                if std::env::var("QNET_PRODUCTION").unwrap_or_default() == "1" {
-                    println!("[Node] ⚠️ QNET_PUBLIC_IP not set for production node!");
-                }
-                format!("0.0.0.0:{}", p2p_port) // Listen on all interfaces
-            }
+        let advertised = crate::listen_addr::ListenConfig::from_env(p2p_port)
+            .inspect_err(|e| println!("[Node] ⚠️ {}", e))
+            .ok()
+            .and_then(|listen| listen.advertised());
+        // Production nodes always have a public IP (NodeConfig::validate)
+        let node_ip = match (advertised, &config.public_ip) {
+            (Some(addr), _) => addr.to_string(),
+            (None, Some(ip)) => format!("{}:{}", ip, p2p_port),
+            (None, None) => format!("0.0.0.0:{}", p2p_port), // Listen on all interfaces
         };
         
         // Register node for MANDATORY archival responsibilities (no choice)
-        if let Err(e) = archive_manager.register_archive_node(&node_id, node_type, &node_ip).await {
-            println!("[Node] ⚠️ Archive manager registration failed: {}", e);
-        } else {
-            let quota = match node_type {
-                NodeType::Light => 0,
-                NodeType::Full => 3,
-                NodeType::Super => 8,
-            };
-            println!("[Node] ✅ Registered for archive duties: {} chunks mandatory", quota);
+        match archive_manager.register_local_node(&node_id, node_type, &node_ip).await {
+            Ok(quota) => println!("[Node] ✅ Registered for archive duties: {} chunks ({} mandatory)",
+                                  quota, crate::archive_manager::mandatory_archive_quota(&node_type)),
+            Err(e) => println!("[Node] ⚠️ Archive manager registration failed: {}", e),
         }
         
         println!("[Node] 🔍 DEBUG: Creating BlockchainNode struct...");
@@ -1616,6 +1942,7 @@ impl BlockchainNode {
                 
                 // Try to load last PoH checkpoint from storage
                 let initial_poh_state = Self::load_last_poh_checkpoint(&storage).await;
+                let start_count = initial_poh_state.as_ref().map(|(_, count)| *count).unwrap_or(0);
                 
                 let (poh, receiver) = if let Some((hash, count)) = initial_poh_state {
                     println!("[QuantumPoH] 🔄 Recovering from checkpoint: count={}, hash={}", 
@@ -1632,6 +1959,8 @@ impl BlockchainNode {
                 // Start PoH generator
                 let poh_clone = poh_arc.clone();
                 tokio::spawn(async move {
+                    // Warn before producing if this machine cannot sustain the PoH rate
+                    let _ = tokio::task::spawn_blocking(crate::quantum_poh::run_startup_benchmark).await;
                     poh_clone.start().await;
                     println!("[QuantumPoH] 🚀 PoH generator started (500K hashes/sec)");
                 });
@@ -1639,46 +1968,27 @@ impl BlockchainNode {
                 // Start PoH checkpoint processor
                 let receiver_clone = receiver_arc.clone();
                 let storage_clone = storage.clone();
+                let poh_for_checkpoints = poh_arc.clone();
                 tokio::spawn(async move {
                     println!("[QuantumPoH] 📝 Starting PoH checkpoint processor");
                     let mut receiver = receiver_clone.lock().await;
-                    let mut last_checkpoint = 0u64;
+                    let mut tracker = crate::quantum_poh::PoHCheckpointTracker::new(
+                        start_count,
+                        crate::quantum_poh::CHECKPOINT_FAILURE_ALERT_THRESHOLD,
+                    );
                     
                     while let Some(entry) = receiver.recv().await {
-                        // Save checkpoint every 10 million hashes (~20 seconds at 500K/s)
-                        let current_million = entry.num_hashes / 1_000_000;
-                        let last_million = last_checkpoint / 1_000_000;
-                        
-                        if current_million >= last_million + 10 {
-                            let rounded_count = current_million * 1_000_000;
-                            
-                            let checkpoint_entry = crate::quantum_poh::PoHEntry {
-                                num_hashes: rounded_count,
-                                hash: entry.hash.clone(),
-                                data: entry.data.clone(),
-                                timestamp: entry.timestamp,
-                            };
-                            
-                            if let Ok(serialized) = bincode::serialize(&checkpoint_entry) {
-                                if let Ok(compressed) = zstd::encode_all(&serialized[..], 3) {
-                                    let key = format!("poh_checkpoint_{}", rounded_count);
-                                    
-                                    if let Err(e) = storage_clone.save_raw(&key, &compressed) {
-                                        println!("[QuantumPoH] ⚠️ Failed to save checkpoint at {}: {}", 
-                                                rounded_count, e);
-                                    } else {
-                                        // Also update the index for O(1) lookup on restart
-                                        if let Ok(index_data) = bincode::serialize(&rounded_count) {
-                                            let _ = storage_clone.save_raw("poh_checkpoint_latest", &index_data);
-                                        }
-                                        
-                                        println!("[QuantumPoH] 💾 Saved checkpoint at hash count: {} (compressed: {} -> {} bytes)", 
-                                                rounded_count, serialized.len(), compressed.len());
-                                        last_checkpoint = rounded_count;
-                                    }
-                                }
-                            }
-                        }
+                        if let Some(alert) = tracker.process_entry(&*storage_clone, &entry) {
+                            println!("[QuantumPoH] 🚨 CRITICAL: {} consecutive checkpoint saves failed, {} hashes since last checkpoint (restart would recompute them): {}",
+                                    alert.consecutive_failures, alert.checkpoint_lag, alert.last_error);
+                            crate::alerting::raise(crate::alerting::Alert::critical(
+                                "QuantumPoH",
+                                "checkpoint_save_failed",
+                                format!("{} consecutive checkpoint saves failed, {} hashes since last checkpoint: {}",
+                                        alert.consecutive_failures, alert.checkpoint_lag, alert.last_error),
+                            ));
+                        }
+                        poh_for_checkpoints.set_last_checkpoint(tracker.last_checkpoint());
                         
                         // Log progress every 10M hashes
                         if entry.num_hashes % 10_000_000 == 0 {
@@ -1718,7 +2028,8 @@ impl BlockchainNode {
             min_timeout_ms: 2000,       // 2 seconds minimum (was 1000)
             latency_window_size: 100,   
         };
-        let tower_bft = Arc::new(crate::tower_bft::TowerBft::new(tower_bft_config));
+        let tower_bft = Arc::new(crate::tower_bft::TowerBft::new(tower_bft_config)
+            .map_err(|e| QNetError::ConsensusError(format!("Invalid Tower BFT config: {}", e)))?);
         println!("[TowerBFT] 🚀 Initialized adaptive timeout manager");
         
         // Initialize Pre-execution manager
@@ -1738,7 +2049,7 @@ impl BlockchainNode {
         
         // MEV PROTECTION: Initialize optional private bundle mempool
         // ARCHITECTURE: Dynamic 0-20% allocation protects public TX throughput
-        let mev_mempool = if env::var("QNET_ENABLE_MEV_PROTECTION").unwrap_or_default() == "1" {
+        let mev_mempool = if config.enable_mev_protection {
             let bundle_config = qnet_mempool::BundleAllocationConfig {
                 min_allocation: 0.0,     // 0% minimum (no reservation when no demand)
                 max_allocation: 0.20,    // 20% maximum (protects public TXs ≥80%)
@@ -1804,6 +2115,8 @@ impl BlockchainNode {
             tower_bft,
             pre_execution,
             block_event_tx,
+            clock: Arc::new(SystemClock),
+            resync: Arc::new(crate::resync::ResyncController::new(&SYNC_IN_PROGRESS)),
         };
         
         println!("[Node] 🔍 DEBUG: BlockchainNode created successfully for node_id: {}", node_id);
@@ -1841,10 +2154,7 @@ impl BlockchainNode {
                 loop {
                     tokio::time::sleep(Duration::from_secs(30)).await;
                     
-                    let current_time = SystemTime::now()
-                        .duration_since(UNIX_EPOCH)
-                        .unwrap()
-                        .as_secs();
+                    let current_time = get_timestamp_safe();
                     
                     let removed = mev_pool_for_cleanup.cleanup_expired_bundles(current_time);
                     if removed > 0 {
@@ -1855,8 +2165,7 @@ impl BlockchainNode {
         }
         
         // Register Genesis nodes in reward system and start processing
-        if std::env::var("QNET_BOOTSTRAP_ID").is_ok() {
-            let bootstrap_id = std::env::var("QNET_BOOTSTRAP_ID").unwrap_or_default();
+        if let Some(bootstrap_id) = &config.bootstrap_id {
             
             // Register this Genesis node in reward system
             {
@@ -1899,10 +2208,7 @@ impl BlockchainNode {
                     .expect("Genesis wallet must exist")
                     .to_string();
                 
-                let current_time = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap()
-                    .as_secs();
+                let current_time = get_timestamp_safe();
                 
                 let genesis_node_id = format!("genesis_node_{}", bootstrap_id);
                 let node_info = crate::activation_validation::NodeInfo {
@@ -1971,7 +2277,7 @@ impl BlockchainNode {
         
         // CRITICAL FIX: Perform initial sync with network on startup
         // This prevents nodes from getting stuck on old blocks
-        println!("[SYNC] 🔄 Performing initial network sync...");
+        StructuredEvent::new("SYNC", "initial_sync_started", "🔄 Performing initial network sync...").emit();
         let blockchain_for_sync = blockchain.clone();
         tokio::spawn(async move {
             // Wait a bit for P2P connections to establish
@@ -1984,8 +2290,9 @@ impl BlockchainNode {
                         let local_height = *blockchain_for_sync.height.read().await;
                         
                         if network_height > local_height + 10 {
-                            println!("[SYNC] 📊 Network is at height {}, local at {} - syncing...", 
-                                     network_height, local_height);
+                            StructuredEvent::new("SYNC", "initial_sync_behind", format!("📊 Network is at height {}, local at {} - syncing...", network_height, local_height))
+                                .height(network_height)
+                                .emit();
                             
                             // Sync in chunks to avoid overwhelming the network
                             let mut current = local_height + 1;
@@ -2004,7 +2311,7 @@ impl BlockchainNode {
                                 tokio::time::sleep(Duration::from_millis(100)).await;
                             }
                             
-                            println!("[SYNC] ✅ Microblock sync complete");
+                            StructuredEvent::new("SYNC", "microblock_sync_completed", "✅ Microblock sync complete").emit();
                             
                             // PRODUCTION v2.19.12: Sync macroblocks after microblocks
                             // Macroblocks are needed for:
@@ -2042,7 +2349,9 @@ impl BlockchainNode {
                             
                             println!("[SYNC] ✅ Initial sync complete (microblocks + macroblocks)");
                         } else {
-                            println!("[SYNC] ✅ Node is synchronized (height: {})", local_height);
+                            StructuredEvent::new("SYNC", "node_synchronized", format!("✅ Node is synchronized (height: {})", local_height))
+                                .height(local_height)
+                                .emit();
                         }
                     },
                     Err(e) => {
@@ -2080,6 +2389,10 @@ impl BlockchainNode {
         // CRITICAL FIX: Create channel for re-queuing blocks
         let (retry_tx, mut retry_rx) = tokio::sync::mpsc::unbounded_channel::<crate::unified_p2p::ReceivedBlock>();
         
+        // Buffered successors of a just-stored block, processed in height order before anything else
+        let mut contiguous_blocks: std::collections::VecDeque<crate::unified_p2p::ReceivedBlock> = 
+            std::collections::VecDeque::new();
+        
         // DDoS PROTECTION: Track requested blocks to avoid duplicate requests
         // Key: block height, Value: (request timestamp, retry count)
         let mut requested_blocks: std::collections::HashMap<u64, (std::time::Instant, u8)> = 
@@ -2103,11 +2416,14 @@ impl BlockchainNode {
         const FORK_ATTEMPT_COOLDOWN_SECS: u64 = 60; // Max 1 fork attempt per 60 seconds
         
         loop {
-            // Check both channels - prioritize retries
-            let received_block = tokio::select! {
-                Some(block) = retry_rx.recv() => block,
-                Some(block) = block_rx.recv() => block,
-                else => break, // Both channels closed
+            // Drain the contiguous run first, then check both channels - prioritize retries
+            let received_block = match contiguous_blocks.pop_front() {
+                Some(block) => block,
+                None => tokio::select! {
+                    Some(block) = retry_rx.recv() => block,
+                    Some(block) = block_rx.recv() => block,
+                    else => break, // Both channels closed
+                },
             };
             // Check for special ping signal
             if received_block.height == u64::MAX {
@@ -2144,17 +2460,34 @@ impl BlockchainNode {
             // Log every 10th block or special cases
             let should_log = received_block.height % 10 == 0 || received_block.height <= 5;
             if should_log {
-                println!("[BLOCKS] 📦 Received {} block #{} from {} | Size: {} bytes | Compressed: {}",
-                         received_block.block_type, 
-                         received_block.height, 
-                         received_block.from_peer, 
-                         received_block.data.len(),
-                         if is_compressed { "✓ Zstd" } else { "✗ Raw" });
+                StructuredEvent::new("BLOCKS", "block_received", format!(
+                    "📦 Received {} block #{} from {} | Size: {} bytes | Compressed: {}",
+                    received_block.block_type, 
+                    received_block.height, 
+                    received_block.from_peer, 
+                    received_block.data.len(),
+                    if is_compressed { "✓ Zstd" } else { "✗ Raw" }))
+                    .height(received_block.height)
+                    .peer(received_block.from_peer.as_str())
+                    .emit();
             }
             
             // PRODUCTION: Validate and store received block
             let store_result = match received_block.block_type.as_str() {
                 "micro" => {
+                    // Gossip delivers the same block from several peers: drop copies of the stored
+                    // block before validating or applying it again. A different block at a stored
+                    // height is a fork and still goes through validation.
+                    if Self::is_stored_duplicate(&received_block, &storage) {
+                        pending_blocks.remove(&received_block.height);
+                        DUPLICATE_BLOCKS_SKIPPED.fetch_add(1, Ordering::Relaxed);
+                        if should_log {
+                            println!("[BLOCKS] ⏭️ Block #{} from {} already stored - skipped", 
+                                     received_block.height, received_block.from_peer);
+                        }
+                        continue;
+                    }
+                    
                     // Validate microblock signature and structure
                     if let Err(e) = Self::validate_received_microblock(&received_block, &storage, unified_p2p.as_ref(), None).await {
                         // CRITICAL FIX: Check if error is due to CERTIFICATE RACE CONDITION
@@ -2346,69 +2679,86 @@ impl BlockchainNode {
                                         // Update last attempt timestamp
                                         *last_fork_attempt.write().await = std::time::Instant::now();
                                         
-                                        println!("[REORG] 🔀 Fork detected at height {} from {} - syncing with majority", fork_height, fork_producer);
+                                        println!("[REORG] 🔀 Fork detected at height {} from {} - applying fork choice", fork_height, fork_producer);
+                                        
+                                        // Competing block at the fork height
+                                        let competing_data = zstd::decode_all(&received_block.data[..])
+                                            .unwrap_or_else(|_| received_block.data.clone());
+                                        let competing_block = match bincode::deserialize::<qnet_state::MicroBlock>(&competing_data) {
+                                            Ok(block) => block,
+                                            Err(e) => {
+                                                println!("[REORG] ❌ Cannot decode competing block #{}: {}", fork_height, e);
+                                                continue;
+                                            }
+                                        };
                                         
-                                        // CRITICAL FIX: Instead of complex reorg, sync with network majority
-                                        // This is simpler and more reliable for Byzantine consensus
                                         let storage_clone = storage.clone();
                                         let height_clone = height.clone();
                                         let p2p_clone = unified_p2p.clone();
                                         let reorg_flag = reorg_in_progress.clone();
+                                        let retry_sender = retry_tx.clone();
+                                        let competing_received = received_block.clone();
                                         
                                         tokio::spawn(async move {
                                             // Mark reorg as in progress
                                             *reorg_flag.write().await = true;
                                             
-                                            // CRITICAL: Query network for consensus on this height
-                                            if let Some(p2p) = &p2p_clone {
-                                                // Get all peers' blocks at this height
-                                                let peers = p2p.get_validated_active_peers();
-                                                let mut block_versions: std::collections::HashMap<Vec<u8>, usize> = std::collections::HashMap::new();
+                                            let local_height = *height_clone.read().await;
+                                            
+                                            // Local branch over the disputed range
+                                            let local_branch: Vec<crate::fork_choice::ForkBlock> = (fork_height..=local_height)
+                                                .filter_map(|h| storage_clone.load_microblock(h).ok().flatten())
+                                                .filter_map(|data| bincode::deserialize::<qnet_state::MicroBlock>(&data).ok())
+                                                .map(|block| crate::fork_choice::ForkBlock::from_microblock(&block))
+                                                .collect();
+                                            let competing_branch = vec![crate::fork_choice::ForkBlock::from_microblock(&competing_block)];
+                                            
+                                            // A macroblock covering the fork height finalizes it
+                                            let fork_point_finalized = matches!(
+                                                storage_clone.get_macroblock_by_height(fork_height.div_ceil(90)),
+                                                Ok(Some(_))
+                                            );
+                                            
+                                            let choice = match &p2p_clone {
+                                                Some(p2p) => crate::fork_choice::choose_fork(
+                                                    &local_branch,
+                                                    &competing_branch,
+                                                    fork_point_finalized,
+                                                    &|producer: &str| p2p.get_node_reputation(producer),
+                                                ),
+                                                None => crate::fork_choice::ForkChoice::KeepLocal,
+                                            };
+                                            
+                                            if choice == crate::fork_choice::ForkChoice::KeepLocal {
+                                                println!("[REORG] 🛡️ Keeping local chain at height {} (finalized: {})", 
+                                                         fork_height, fork_point_finalized);
+                                            } else if let Some(p2p) = &p2p_clone {
+                                                // Rollback to before fork
+                                                println!("[REORG] 🔄 Competing chain wins - rolling back from {} to {}", 
+                                                         local_height, fork_height.saturating_sub(1));
                                                 
-                                                for peer in peers.iter().take(10) {  // Sample up to 10 peers
-                                                    // Query peer for their block at fork_height
-                                                    // (This would need a new P2P method to query specific block)
-                                                    // For now, we'll use network height consensus
+                                                for h in fork_height..=local_height {
+                                                    if let Err(e) = storage_clone.delete_microblock(h) {
+                                                        println!("[REORG] ⚠️ Failed to delete block {}: {}", h, e);
+                                                    }
                                                 }
+                                                *height_clone.write().await = fork_height.saturating_sub(1);
+                                                storage_clone.set_chain_height(fork_height.saturating_sub(1)).ok();
+                                                
+                                                // Apply the winning block through the normal processing path
+                                                let _ = retry_sender.send(competing_received);
                                                 
-                                                // Get network consensus height
+                                                // Fetch the rest of the winning chain if the network is ahead
                                                 if let Ok(network_height) = p2p.sync_blockchain_height() {
-                                                    let local_height = *height_clone.read().await;
-                                                    
-                                                    // If network is ahead, sync to catch up
-                                                    if network_height > local_height {
-                                                        println!("[REORG] 📥 Network consensus at height {} - syncing from {}", 
-                                                                 network_height, local_height);
-                                                        
-                                                        // Clear our chain from fork point and resync
-                                                        if fork_height < local_height {
-                                                            // Rollback to before fork
-                                                            println!("[REORG] 🔄 Rolling back from {} to {}", local_height, fork_height - 1);
-                                                            
-                                                            // Delete blocks from fork_height onwards
-                                                            for h in fork_height..=local_height {
-                                                                if let Err(e) = storage_clone.delete_microblock(h) {
-                                                                    println!("[REORG] ⚠️ Failed to delete block {}: {}", h, e);
-                                                                }
-                                                            }
-                                                            
-                                                            // Update height
-                                                            *height_clone.write().await = fork_height - 1;
-                                                            storage_clone.set_chain_height(fork_height - 1).ok();
-                                                        }
-                                                        
-                                                        // Sync missing blocks from network
-                                                        let sync_from = fork_height;
+                                                    if network_height > fork_height {
                                                         let sync_to = std::cmp::min(network_height, fork_height + 100); // Sync in chunks
-                                                        
-                                                        println!("[REORG] 📦 Requesting blocks {}-{} from network", sync_from, sync_to);
-                                                        if let Err(e) = p2p.sync_blocks(sync_from, sync_to).await {
+                                                        println!("[REORG] 📦 Requesting blocks {}-{} from network", fork_height + 1, sync_to);
+                                                        if let Err(e) = p2p.sync_blocks(fork_height + 1, sync_to).await {
                                                             println!("[REORG] ❌ Failed to sync blocks: {}", e);
-                                                        } else {
-                                                            println!("[REORG] ✅ Fork resolved by syncing with network majority");
                                                         }
                                                     }
                                                 }
+                                                println!("[REORG] ✅ Fork resolved by deterministic fork choice");
                                             }
                                             
                                             // Clear reorg flag
@@ -2422,11 +2772,22 @@ impl BlockchainNode {
                             }
                         } else {
                             // SECURITY: Track invalid block for malicious behavior detection
-                            println!("[BLOCKS] ❌ Invalid microblock #{}: {}", received_block.height, e);
+                            StructuredEvent::new("BLOCKS", "block_invalid", format!("❌ Invalid microblock #{}: {}", received_block.height, e))
+                                .height(received_block.height)
+                                .peer(received_block.from_peer.as_str())
+                                .emit();
                             
                             // Report to P2P system for soft punishment tracking
                             if let Some(p2p) = &unified_p2p {
                                 p2p.track_invalid_block(&received_block.from_peer, received_block.height, &e);
+                                
+                                // SYNC: A height we still need is re-requested from a different peer
+                                let local_height = storage.get_chain_height().unwrap_or(0);
+                                if received_block.height > local_height {
+                                    if let Err(retry_err) = p2p.retry_invalid_sync_block(received_block.height, &received_block.from_peer) {
+                                        println!("[SYNC] ❌ Giving up on block #{}: {}", received_block.height, retry_err);
+                                    }
+                                }
                             }
                         }
                         continue;
@@ -2439,56 +2800,9 @@ impl BlockchainNode {
                         Err(_) => received_block.data.clone(), // Not compressed - use as-is
                     };
                     
-                    // CRITICAL: Apply transactions from block to state BEFORE saving
-                    // This ensures state consistency across all nodes
                     match bincode::deserialize::<qnet_state::MicroBlock>(&decompressed_data) {
                         Ok(microblock) => {
-                            // Apply ALL transactions from block to state
-                            for tx in &microblock.transactions {
-                                // SPECIAL HANDLING: RewardDistribution transactions
-                                // These update total_supply on non-producer nodes
-                                if tx.tx_type == qnet_state::TransactionType::RewardDistribution 
-                                   && tx.from == "system_emission" {
-                                    println!("[STATE] 💰 Applying emission transaction: {} QNC (block #{})", 
-                                             tx.amount / 1_000_000_000, microblock.height);
-                                    
-                                    // Update total_supply for emission transactions
-                                    // This is CRITICAL for state consistency across network
-                                    let state_guard = state.read().await;
-                                    if let Err(e) = state_guard.emit_rewards(tx.amount) {
-                                        eprintln!("[STATE] ⚠️ Failed to apply emission: {}", e);
-                                    } else {
-                                        let new_supply = state_guard.get_total_supply();
-                                        println!("[STATE] ✅ Total supply updated: {} QNC", new_supply / 1_000_000_000);
-                                    }
-                                }
-                                
-                                // Apply transaction to state (updates balances, nonces, etc)
-                                let state_guard = state.read().await;
-                                if let Err(e) = state_guard.apply_transaction(tx) {
-                                    // Don't fail block processing for individual tx failures
-                                    // Some transactions may fail validation (insufficient balance, etc)
-                                    println!("[STATE] ⚠️ Failed to apply transaction {}: {}", tx.hash, e);
-                                } else {
-                                    // POOL #2 INTEGRATION: Collect transaction fees
-                                    // Only collect fees for non-system transactions
-                                    if !tx.from.starts_with("system_") && tx.gas_price > 0 && tx.gas_limit > 0 {
-                                        let fee_amount = tx.gas_price * tx.gas_limit;
-                                        if fee_amount > 0 {
-                                            let mut reward_mgr = reward_manager.write().await;
-                                            reward_mgr.add_transaction_fees(fee_amount);
-                                            // Log only for significant fees (> 0.001 QNC)
-                                            if fee_amount > 1_000_000 {
-                                                println!("[POOL2] 💰 Fee collected: {} nanoQNC → Pool #2", fee_amount);
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                            
-                            // Now save the block after state is updated
-                            storage.save_microblock(received_block.height, &decompressed_data)
-                                .map_err(|e| format!("Storage error: {:?}", e))
+                            Self::apply_received_microblock(&microblock, &decompressed_data, &storage, &state, &reward_manager).await
                         },
                         Err(e) => {
                             Err(format!("Failed to deserialize microblock for state update: {}", e))
@@ -2497,8 +2811,11 @@ impl BlockchainNode {
                 },
                 "macro" => {
                     // Validate macroblock consensus and finality
-                    if let Err(e) = Self::validate_received_macroblock(&received_block, &storage).await {
-                        println!("[BLOCKS] ❌ Invalid macroblock #{}: {}", received_block.height, e);
+                    if let Err(e) = Self::validate_received_macroblock(&received_block, &storage, &unified_p2p).await {
+                        StructuredEvent::new("BLOCKS", "macroblock_invalid", format!("❌ Invalid macroblock #{}: {}", received_block.height, e))
+                            .height(received_block.height)
+                            .peer(received_block.from_peer.as_str())
+                            .emit();
                         continue;
                     }
                     
@@ -2531,7 +2848,16 @@ impl BlockchainNode {
             match store_result {
                 Ok(_) => {
                     if should_log {
-                        println!("[BLOCKS] ✅ Block #{} stored successfully", received_block.height);
+                        StructuredEvent::new("BLOCKS", "block_stored", format!("✅ Block #{} stored successfully", received_block.height))
+                            .height(received_block.height)
+                            .peer(received_block.from_peer.as_str())
+                            .emit();
+                    }
+                    
+                    if received_block.block_type == "micro" {
+                        if let Some(p2p) = &unified_p2p {
+                            p2p.sync_block_accepted(received_block.height);
+                        }
                     }
                     
                     // CRITICAL FIX: Remove block from pending_blocks after successful storage
@@ -2544,7 +2870,7 @@ impl BlockchainNode {
                     
                     // CRITICAL FIX: Check if we're the producer for next block after rotation boundary
                     // This ensures nodes immediately know they're selected after receiving rotation block
-                    if received_block.height > 0 && received_block.height % 30 == 0 {
+                    if get_chain_params().is_rotation_end(received_block.height) {
                         // Just received last block of a round (30, 60, 90...)
                         println!("[ROTATION] 🔄 Received rotation boundary block #{} - checking producer for next round", received_block.height);
                         
@@ -2608,7 +2934,9 @@ impl BlockchainNode {
                         let current_height = *height.read().await;
                         if received_block.height > current_height {
                             *height.write().await = received_block.height;
-                            println!("[BLOCKS] 📊 Global height updated to {}", received_block.height);
+                            StructuredEvent::new("BLOCKS", "chain_height_updated", format!("📊 Global height updated to {}", received_block.height))
+                                .height(received_block.height)
+                                .emit();
                             
                             // CRITICAL FIX: Update last block time for stall detection
                             LAST_BLOCK_PRODUCED_TIME.store(get_timestamp_safe(), Ordering::Relaxed);
@@ -2649,34 +2977,21 @@ impl BlockchainNode {
                     // CRITICAL FIX: Clear request tracking for this successfully stored block
                     requested_blocks.remove(&received_block.height);
                     
-                    // CRITICAL FIX: Check if any pending blocks can now be processed
-                    // SOLANA-STYLE: Process multiple consecutive blocks in parallel
-                    let mut blocks_to_retry = Vec::new();
-                    let mut check_height = received_block.height + 1;
-                    
-                    // Collect up to 10 consecutive blocks that can now be processed
-                    while blocks_to_retry.len() < 10 {
-                        if let Some((pending_block, _, _)) = pending_blocks.remove(&check_height) {
-                            blocks_to_retry.push(pending_block);
-                            check_height += 1;
-                        } else {
-                            break; // No more consecutive blocks
-                        }
-                    }
-                    
-                    // Re-queue all found blocks for parallel processing
-                    if !blocks_to_retry.is_empty() {
+                    // CRITICAL FIX: The gap after this block is filled - process the buffered run
+                    // in ascending order next, so each block finds its predecessor already stored
+                    // (bounded by MAX_PENDING_BLOCKS)
+                    let run = drain_contiguous(&mut pending_blocks, received_block.height);
+                    if !run.is_empty() {
                         println!("[BLOCKS] 🚀 Fast-forwarding {} consecutive blocks after block #{}", 
-                                 blocks_to_retry.len(), received_block.height);
-                        for pending_block in blocks_to_retry {
-                            if let Err(e) = retry_tx.send(pending_block) {
-                                println!("[BLOCKS] ⚠️ Failed to re-queue block: {:?}", e);
-                            }
-                        }
+                                 run.len(), received_block.height);
+                        contiguous_blocks.extend(run.into_iter().map(|(pending_block, _, _)| pending_block));
                     }
                 },
                 Err(e) => {
-                    println!("[BLOCKS] ❌ Failed to store block #{}: {}", received_block.height, e);
+                    StructuredEvent::new("BLOCKS", "block_store_failed", format!("❌ Failed to store block #{}: {}", received_block.height, e))
+                        .height(received_block.height)
+                        .peer(received_block.from_peer.as_str())
+                        .emit();
                 }
             }
             
@@ -2689,7 +3004,7 @@ impl BlockchainNode {
                 // CRITICAL: Retry ALL pending blocks (not just consecutive)
                 // This is different from fast-forward logic (which is triggered by successful block storage)
                 // OPTIMIZATION: Collect heights to retry first (avoid cloning in loop)
-                let heights_to_retry: Vec<u64> = pending_blocks.iter()
+                let mut heights_to_retry: Vec<u64> = pending_blocks.iter()
                     .filter_map(|(height, (_, retry_count, timestamp))| {
                         // ADAPTIVE RETRY: Recent blocks only (certificate race resolved quickly)
                         // REDUCED TIMEOUT: 30 seconds (from 60) to prevent memory accumulation
@@ -2701,6 +3016,8 @@ impl BlockchainNode {
                         }
                     })
                     .collect();
+                // Lowest first, so predecessors are retried before their successors
+                heights_to_retry.sort_unstable();
                 
                 // Re-queue pending blocks for retry (clone only what we need)
                 if !heights_to_retry.is_empty() {
@@ -2765,6 +3082,7 @@ impl BlockchainNode {
                     let success = RETRY_SUCCESS.load(Ordering::Relaxed);
                     let cert_race = RETRY_CERT_RACE.load(Ordering::Relaxed);
                     let missing_prev = RETRY_MISSING_PREV.load(Ordering::Relaxed);
+                    let duplicates = DUPLICATE_BLOCKS_SKIPPED.load(Ordering::Relaxed);
                     
                     if total > 0 {
                         let success_rate = (success as f64 / total as f64 * 100.0) as u64;
@@ -2773,6 +3091,7 @@ impl BlockchainNode {
                         println!("[METRICS]   Successful: {} ({:.1}%)", success, success_rate);
                         println!("[METRICS]   Certificate race: {}", cert_race);
                         println!("[METRICS]   Missing previous: {}", missing_prev);
+                        println!("[METRICS]   Duplicate blocks skipped: {}", duplicates);
                     }
                 }
                 
@@ -2785,6 +3104,92 @@ impl BlockchainNode {
         }
     }
     
+    /// Whether `block` is a copy of the microblock already stored at its height
+    /// A different block at a stored height is a fork, not a duplicate
+    fn is_stored_duplicate(block: &crate::unified_p2p::ReceivedBlock, storage: &Storage) -> bool {
+        if !storage.has_microblock(block.height).unwrap_or(false) {
+            return false;
+        }
+        let data = zstd::decode_all(&block.data[..]).unwrap_or_else(|_| block.data.clone());
+        let Ok(received) = bincode::deserialize::<qnet_state::MicroBlock>(&data) else {
+            return false;
+        };
+        matches!(storage.load_microblock_auto_format(block.height), Ok(Some(stored)) if stored == received)
+    }
+    
+    /// Apply a validated microblock's transactions to state, then store it
+    /// `data` is the decompressed block as received
+    async fn apply_received_microblock(
+        microblock: &qnet_state::MicroBlock,
+        data: &[u8],
+        storage: &Storage,
+        state: &RwLock<StateManager>,
+        reward_manager: &RwLock<PhaseAwareRewardManager>,
+    ) -> Result<(), String> {
+        // CRITICAL: Apply transactions from block to state BEFORE saving
+        // This ensures state consistency across all nodes
+        for tx in &microblock.transactions {
+            // SPECIAL HANDLING: RewardDistribution transactions
+            // These update total_supply on non-producer nodes
+            if tx.tx_type == qnet_state::TransactionType::RewardDistribution 
+               && tx.from == "system_emission" {
+                println!("[STATE] 💰 Applying emission transaction: {} QNC (block #{})", 
+                         tx.amount / 1_000_000_000, microblock.height);
+                
+                // Update total_supply for emission transactions
+                // This is CRITICAL for state consistency across network
+                let state_guard = state.read().await;
+                if let Err(e) = state_guard.emit_rewards(tx.amount) {
+                    eprintln!("[STATE] ⚠️ Failed to apply emission: {}", e);
+                } else {
+                    let new_supply = state_guard.get_total_supply();
+                    println!("[STATE] ✅ Total supply updated: {} QNC", new_supply / 1_000_000_000);
+                }
+            }
+        }
+        
+        // Apply transactions to state (updates balances, nonces, etc)
+        // The whole block is published at once: API readers see it before or after, never half-applied
+        let results = state.read().await.apply_transactions_at(&microblock.transactions, microblock.height);
+        for (tx, result) in microblock.transactions.iter().zip(results) {
+            match result {
+                Err(e) => {
+                    // Don't fail block processing for individual tx failures
+                    // Some transactions may fail validation (insufficient balance, etc)
+                    println!("[STATE] ⚠️ Failed to apply transaction {}: {}", tx.hash, e);
+                }
+                Ok(receipt) => {
+                    if let qnet_state::TxStatus::Failed(reason) = &receipt.status {
+                        println!("[STATE] ⚠️ Transaction {} failed: {}", tx.hash, reason);
+                    }
+                    // POOL #2 INTEGRATION: Collect transaction fees (gas_used * gas_price)
+                    // Only collect fees for non-system transactions
+                    if !tx.from.starts_with("system_") && receipt.fee > 0 {
+                        let mut reward_mgr = reward_manager.write().await;
+                        if let Err(e) = reward_mgr.add_transaction_fees(receipt.fee) {
+                            eprintln!("[POOL2] ⚠️ Fee not collected: {}", e);
+                        }
+                        // Log only for significant fees (> 0.001 QNC)
+                        if receipt.fee > 1_000_000 {
+                            println!("[POOL2] 💰 Fee collected: {} nanoQNC → Pool #2", receipt.fee);
+                        }
+                    }
+                }
+            }
+        }
+        
+        // Snapshot state root at macroblock boundary for later verification
+        record_macroblock_state_root(microblock.height, &*state.read().await);
+        
+        // Now save the block after state is updated
+        let saved = storage.save_microblock(microblock.height, data)
+            .map_err(|e| format!("Storage error: {:?}", e));
+        if saved.is_ok() {
+            crate::rpc::broadcast_ws_event(crate::rpc::WsEvent::new_block(microblock));
+        }
+        saved
+    }
+    
     /// Calculate Byzantine weight of fork chain (considers consensus validators)
     /// OPTIMIZED: Fast calculation for reorg decisions without blocking
     async fn calculate_fork_chain_weight(
@@ -2956,10 +3361,7 @@ impl BlockchainNode {
                 "old_height": fork_point,
                 "new_height": new_height,
                 "fork_length": new_height.saturating_sub(fork_point),
-                "timestamp": std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_secs(),
+                "timestamp": get_timestamp_safe(),
                 "node_id": p2p.node_id.clone()
             });
             
@@ -3033,9 +3435,27 @@ impl BlockchainNode {
         }
     }
     
-    /// Validate received microblock
-    async fn validate_received_microblock(
-        block: &crate::unified_p2p::ReceivedBlock,
+    /// Whether the stored macroblock proves finality of a block in the fast-sync range
+    fn finalized_under_trust(
+        trust: &crate::checkpoint::FastSyncTrust,
+        height: u64,
+        block_data: &[u8],
+        storage: &Arc<Storage>,
+    ) -> bool {
+        let macroblock = match storage.get_macroblock_by_height(height.div_ceil(90)) {
+            Ok(Some(data)) => match bincode::deserialize::<qnet_state::MacroBlock>(&data) {
+                Ok(macroblock) => macroblock,
+                Err(_) => return false,
+            },
+            // No proof yet - fall back to full validation
+            _ => return false,
+        };
+        trust.verify_finality(height, block_data, &macroblock).is_ok()
+    }
+    
+    /// Validate received microblock
+    async fn validate_received_microblock(
+        block: &crate::unified_p2p::ReceivedBlock,
         storage: &Arc<Storage>,
         p2p: Option<&Arc<SimplifiedP2P>>,
         reward_manager: Option<&Arc<RwLock<PhaseAwareRewardManager>>>,
@@ -3129,6 +3549,17 @@ impl BlockchainNode {
             }
         }
         
+        // 3.5. Transactions must be in canonical order (system first, then sender, nonce, gas price)
+        if get_chain_params().requires_canonical_tx_order(microblock.height) && !microblock.has_canonical_order() {
+            return Err(format!("Block #{} transactions not in canonical order", microblock.height));
+        }
+        
+        // 3.6. Re-check the network size caps: admission alone cannot be trusted for a peer's block
+        Self::validate_microblock_tx_sizes(&microblock, &get_chain_params().tx_size_limits())?;
+        
+        // 3.7. No transaction past its valid_until (judged by the block's height and timestamp, not our clock)
+        Self::validate_microblock_tx_validity(&microblock)?;
+        
         // 4. Verify height sequence
         let current_height = storage.get_chain_height().unwrap_or(0);
         if microblock.height > current_height + 100 {
@@ -3139,7 +3570,11 @@ impl BlockchainNode {
         }
         
         // 5. Verify signature (CRYSTALS-Dilithium)
-        if !Self::verify_microblock_signature(&microblock, &microblock.producer, p2p).await? {
+        // Opt-in fast sync: history finalized under the trusted checkpoint is covered by its macroblock
+        let finalized_by_checkpoint = crate::checkpoint::fast_sync_trust()
+            .filter(|trust| trust.covers(microblock.height))
+            .is_some_and(|trust| Self::finalized_under_trust(trust, microblock.height, &decompressed_data, storage));
+        if !finalized_by_checkpoint && !Self::verify_microblock_signature(&microblock, &microblock.producer, p2p).await? {
             // SECURITY: Track invalid signature for malicious behavior detection
             println!("[SECURITY] ❌ Invalid signature detected from producer: {}", microblock.producer);
             
@@ -3251,7 +3686,7 @@ impl BlockchainNode {
         
         // EMISSION VALIDATION: Check if emission block contains valid emission transaction
         // CRITICAL: Validate emission blocks to prevent fake emissions
-        let is_emission_block = microblock.height % EMISSION_INTERVAL_BLOCKS == 0 && microblock.height > 0;
+        let is_emission_block = get_chain_params().is_emission_block(microblock.height);
         
         if is_emission_block {
             println!("[EMISSION] 🔍 Validating emission block #{}", microblock.height);
@@ -3304,8 +3739,7 @@ impl BlockchainNode {
                         println!("[PING-COMMITMENT] 🔍 Validating Merkle commitment...");
                         
                         // Step 1: Verify window matches this emission block
-                        let expected_window_end = microblock.height;
-                        let expected_window_start = microblock.height.saturating_sub(EMISSION_INTERVAL_BLOCKS);
+                        let (expected_window_start, expected_window_end) = get_chain_params().reward_window_heights(microblock.height);
                         
                         if *window_start_height != expected_window_start || *window_end_height != expected_window_end {
                             println!("[PING-COMMITMENT] ❌ Window mismatch: expected {}-{}, got {}-{}",
@@ -3315,19 +3749,10 @@ impl BlockchainNode {
                         }
                         
                         // Step 2: Verify sample_seed is deterministic
-                        let entropy_height = microblock.height.saturating_sub(FINALITY_WINDOW);
-                        let entropy_block = storage.load_microblock(entropy_height)
-                            .map_err(|e| format!("Failed to load entropy block: {}", e))?
-                            .ok_or_else(|| "Entropy block not found".to_string())?;
-                        
-                        // OPTIMIZED: SHA3-256 for deterministic seed verification
-                        use sha3::{Sha3_256, Digest};
-                        let mut expected_seed_hasher = Sha3_256::new();
-                        expected_seed_hasher.update(b"QNet_Ping_Sampling_v1");
-                        expected_seed_hasher.update(&entropy_block);
-                        expected_seed_hasher.update(&window_start_height.to_le_bytes());
-                        let expected_seed = expected_seed_hasher.finalize();
-                        let expected_seed_hex = hex::encode(&expected_seed[..]);
+                        use crate::ping_sampling::{derive_sample_seed, FinalityWindowEntropy};
+                        let entropy = FinalityWindowEntropy::new(storage);
+                        let expected_seed = derive_sample_seed(&entropy, microblock.height, *window_start_height)?;
+                        let expected_seed_hex = hex::encode(expected_seed);
                         
                         if sample_seed != &expected_seed_hex {
                             println!("[PING-COMMITMENT] ❌ Sample seed mismatch");
@@ -3362,7 +3787,7 @@ impl BlockchainNode {
                         println!("[PING-COMMITMENT] ✅ All {} Merkle proofs verified", verified_count);
                         
                         // Step 4: Verify sample size is sufficient (1% or 10K min)
-                        let min_samples = ((*total_ping_count / 100).max(MIN_PING_SAMPLES.min(*total_ping_count as usize) as u32)) as usize;
+                        let min_samples = crate::ping_sampling::sample_size(*total_ping_count as usize, PING_SAMPLE_PERCENTAGE, MIN_PING_SAMPLES);
                         if ping_samples.len() < min_samples {
                             println!("[PING-COMMITMENT] ❌ Insufficient samples: {} < {}", ping_samples.len(), min_samples);
                             return Err(format!("Insufficient ping samples: {} < {}", ping_samples.len(), min_samples));
@@ -3487,6 +3912,7 @@ impl BlockchainNode {
     async fn validate_received_macroblock(
         block: &crate::unified_p2p::ReceivedBlock,
         storage: &Arc<Storage>,
+        unified_p2p: &Option<Arc<SimplifiedP2P>>,
     ) -> Result<(), String> {
         // CRITICAL: Full validation to prevent consensus manipulation
         
@@ -3538,7 +3964,19 @@ impl BlockchainNode {
             ));
         }
         
-        // 5. CRITICAL: Detect database substitution
+        // 5. CRITICAL: State consistency - claimed root must match our applied state
+        // A node with divergent balances must not silently follow the chain
+        let local_root = local_macroblock_state_root(macroblock.height);
+        if let Err(e) = Self::verify_macroblock_state_root(&macroblock, local_root) {
+            Self::record_state_root_mismatch(macroblock.height);
+            Self::trigger_state_resync(macroblock.height, unified_p2p);
+            return Err(e);
+        }
+        if local_root.is_some() {
+            Self::record_state_root_match(macroblock.height);
+        }
+        
+        // 6. CRITICAL: Detect database substitution
         // Check if we already have a macroblock at this height
         if macroblock.height > 0 {
             // Get stored macro hash to detect forks
@@ -3565,6 +4003,75 @@ impl BlockchainNode {
         Ok(())
     }
     
+    /// Compare a macroblock's claimed state root with the root of locally applied state.
+    /// Nodes that did not apply the macroblock window themselves (no local root) skip the check.
+    fn verify_macroblock_state_root(
+        macroblock: &qnet_state::MacroBlock,
+        local_root: Option<[u8; 32]>,
+    ) -> Result<(), String> {
+        match local_root {
+            Some(root) if root != macroblock.state_root => Err(format!(
+                "State root mismatch at macroblock #{}: claimed {}, local {}",
+                macroblock.height,
+                hex::encode(macroblock.state_root),
+                hex::encode(root)
+            )),
+            Some(_) => Ok(()),
+            None => {
+                println!("[VALIDATION] ℹ️ No local state root for macroblock #{} - skipping state check", 
+                         macroblock.height);
+                Ok(())
+            }
+        }
+    }
+    
+    /// Count a state-root mismatch; past the limit, halt production and raise a critical alert
+    fn record_state_root_mismatch(macroblock_index: u64) {
+        let Ok(mut breaker) = STATE_ROOT_BREAKER.lock() else { return };
+        if breaker.record_mismatch(macroblock_index) {
+            let status = breaker.status();
+            let message = format!("{} consecutive state-root mismatches (limit {}) - block production halted, node is read-only until resync or operator acknowledgment",
+                                  status.mismatches, status.max_mismatches);
+            StructuredEvent::new("MONITOR", "state_root_breaker_tripped", format!("🚨 CRITICAL: {}", message))
+                .height(macroblock_index)
+                .emit();
+            crate::alerting::raise(
+                crate::alerting::Alert::critical("MONITOR", "state_root_breaker_tripped", message).height(macroblock_index)
+            );
+        }
+    }
+    
+    /// A macroblock root matched local state: clears the mismatch count and lifts a halt
+    fn record_state_root_match(macroblock_index: u64) {
+        let Ok(mut breaker) = STATE_ROOT_BREAKER.lock() else { return };
+        if breaker.record_match().is_some() {
+            StructuredEvent::new(
+                "MONITOR",
+                "state_root_breaker_resumed",
+                format!("✅ State root matches network at macroblock #{} - block production resumed", macroblock_index),
+            ).height(macroblock_index).emit();
+        }
+    }
+    
+    /// Mark node as unsynchronized and re-fetch the diverged macroblock window
+    fn trigger_state_resync(macroblock_index: u64, unified_p2p: &Option<Arc<SimplifiedP2P>>) {
+        println!("[SYNC] 🚨 Local state diverged at macroblock #{} - triggering resync", macroblock_index);
+        NODE_IS_SYNCHRONIZED.store(false, Ordering::SeqCst);
+        
+        if let Some(p2p) = unified_p2p.clone() {
+            tokio::spawn(async move {
+                let start = macroblock_index.saturating_sub(1) * 90 + 1;
+                let end = macroblock_index * 90;
+                if let Err(e) = p2p.sync_blocks(start, end).await {
+                    println!("[SYNC] ⚠️ Resync of blocks {}-{} failed: {}", start, end, e);
+                }
+                if let Err(e) = p2p.sync_macroblocks(macroblock_index, macroblock_index).await {
+                    println!("[MACROBLOCK-SYNC] ⚠️ Resync of macroblock #{} failed: {}", macroblock_index, e);
+                }
+            });
+        }
+    }
+    
     /// Start the blockchain node
     pub async fn start(&mut self) -> Result<(), QNetError> {
         println!("[Node] Starting blockchain node...");
@@ -3596,8 +4103,15 @@ impl BlockchainNode {
             tokio::time::sleep(std::time::Duration::from_secs(api_wait_time)).await;
             
             // Health check to ensure API is ready
-            let api_host = std::env::var("QNET_API_HOST").unwrap_or_else(|_| "0.0.0.0".to_string());
-            let health_check_url = format!("http://{}:{}/api/v1/node/health", api_host, unified_port);
+            let health_check_url = match std::env::var("QNET_API_HOST") {
+                Ok(api_host) => format!("http://{}:{}/api/v1/node/health", api_host, unified_port),
+                Err(_) => {
+                    let local = crate::listen_addr::ListenConfig::from_env(unified_port)
+                        .unwrap_or_else(|_| crate::listen_addr::ListenConfig::all_interfaces(unified_port))
+                        .local();
+                    format!("http://{}/api/v1/node/health", local)
+                }
+            };
             println!("[Node] 🏥 Checking API health at {}", health_check_url);
             
             // Try health check with retries
@@ -3642,6 +4156,14 @@ impl BlockchainNode {
             // Sync will happen later after API servers are ready
         }
         
+        // WEAK SUBJECTIVITY: Start from a trusted checkpoint instead of replaying ancient history
+        // A checkpoint that cannot be verified rejects startup
+        let checkpoint = TrustedCheckpoint::configured(&crate::network_config::get_network_config().environment)
+            .map_err(QNetError::ValidationError)?;
+        if let Some(checkpoint) = checkpoint {
+            self.sync_from_checkpoint(&checkpoint).await?;
+        }
+        
         // SYNC: Check if we need to sync with network after restart
         if let Err(e) = self.start_sync_if_needed().await {
             println!("[SYNC] ⚠️ Sync check failed: {}", e);
@@ -3985,7 +4507,7 @@ impl BlockchainNode {
     
     async fn start_microblock_production(&mut self) {
         // PRODUCTION: Start health monitor for sync flags (deadlock prevention)
-        Self::start_sync_health_monitor();
+        Self::start_sync_health_monitor(self.height.clone(), self.storage.clone(), self.unified_p2p.clone());
         
         let is_running = self.is_running.clone();
         let mempool = self.mempool.clone();
@@ -4009,6 +4531,7 @@ impl BlockchainNode {
         let pre_execution_for_spawn = self.pre_execution.clone();
         let block_event_tx_for_spawn = self.block_event_tx.clone();
         let reward_manager_for_spawn = self.reward_manager.clone();
+        let state_for_spawn = self.state.clone();
         
         // CRITICAL FIX: Take consensus_rx ownership for MACROBLOCK consensus phases
         // Macroblock commit/reveal phases NEED exclusive access to process P2P messages  
@@ -4034,6 +4557,7 @@ impl BlockchainNode {
         
         // Clone self for emission processing inside spawn
         let blockchain_for_emission = self.clone();
+        let clock = self.clock.clone();
         
         tokio::spawn(async move {
             // CRITICAL FIX: Start from current global height, not 0
@@ -4453,6 +4977,12 @@ impl BlockchainNode {
             let mut certificate_broadcast_counter = 0u64;
             let node_start_time = std::time::Instant::now();
             
+            // STARTUP GATE: Don't produce before we can see the real network height
+            let mut production_gate = ProductionGate::from_env();
+            let empty_block_policy = EmptyBlockPolicy::from_chain_params(get_chain_params());
+            println!("[Microblock] 🚦 Production gate: {} peers required (grace period {}s)", 
+                    production_gate.min_peers_to_produce, production_gate.grace_period.as_secs());
+            
             // OPTIMIZATION: Track last round when certificate was broadcasted
             // Prevents redundant broadcasts (30× per round → 1× per round)
             let mut last_certificate_broadcast_round: Option<u64> = None;
@@ -4534,7 +5064,7 @@ impl BlockchainNode {
                         // Check if we have all intermediate blocks
                         let mut can_sync = true;
                         for h in (microblock_height + 1)..=global_height {
-                            if storage.load_microblock(h).unwrap_or(None).is_none() {
+                            if !storage.has_microblock(h).unwrap_or(false) {
                                 can_sync = false;
                                 println!("[SYNC] ⚠️ Cannot sync to height {} - missing block #{}", 
                                         global_height, h);
@@ -4543,8 +5073,9 @@ impl BlockchainNode {
                         }
                         
                         if can_sync {
-                            println!("[SYNC] ⚡ Syncing local height {} → {} (all blocks present)", 
-                                    microblock_height, global_height);
+                            StructuredEvent::new("SYNC", "local_height_synced", format!("⚡ Syncing local height {} → {} (all blocks present)", microblock_height, global_height))
+                                .height(global_height)
+                                .emit();
                             microblock_height = global_height;
                         }
                     }
@@ -4555,7 +5086,7 @@ impl BlockchainNode {
                 if crate::unified_p2p::EMERGENCY_STOP_PRODUCTION.load(Ordering::Relaxed) {
                     let stop_height = crate::unified_p2p::EMERGENCY_STOP_HEIGHT.load(Ordering::Relaxed);
                     let stop_time = crate::unified_p2p::EMERGENCY_STOP_TIME.load(Ordering::Relaxed);
-                    let current_time = get_timestamp_safe();
+                    let current_time = clock.now_secs();
                     
                     if stop_height > 0 && stop_time > 0 {
                         let blocks_passed = if microblock_height > stop_height { 
@@ -4596,6 +5127,11 @@ impl BlockchainNode {
                         if stuck_duration.as_secs() > 15 {
                             println!("[DEADLOCK] ⚠️ Stuck on height {} for {}s - potential deadlock detected", 
                                     microblock_height, stuck_duration.as_secs());
+                            crate::alerting::raise(crate::alerting::Alert::warning(
+                                "DEADLOCK",
+                                "production_stalled",
+                                format!("Stuck on height {} for {}s - potential deadlock", microblock_height, stuck_duration.as_secs()),
+                            ).height(microblock_height));
                             // Reset timers to prevent spam
                             last_production_time = std::time::Instant::now();
                         }
@@ -4609,14 +5145,16 @@ impl BlockchainNode {
                     // Check if ANY block has been produced recently (by us or others)
                     let last_block_time = LAST_BLOCK_PRODUCED_TIME.load(Ordering::Relaxed);
                     let last_block_height = LAST_BLOCK_PRODUCED_HEIGHT.load(Ordering::Relaxed);
-                    let current_time = get_timestamp_safe();
+                    let current_time = clock.now_secs();
                     
                     if last_block_time > 0 && current_time > last_block_time {
                         let time_since_last_block = current_time - last_block_time;
                         
                         // CRITICAL: Trigger emergency if no blocks for 10+ seconds
                         // This is GLOBAL stall detection, not just local
-                        if time_since_last_block > 10 && microblock_height > 0 {
+                        // Producers may stay silent for the empty block interval on an idle chain
+                        let stall_slack = empty_block_policy.liveness_slack().as_secs();
+                        if time_since_last_block > 10 + stall_slack && microblock_height > 0 {
                             println!("[STALL] 🚨 NETWORK STALL DETECTED! No blocks for {} seconds", time_since_last_block);
                             println!("[STALL] 📊 Last block: #{} at timestamp {}", last_block_height, last_block_time);
                             
@@ -4628,7 +5166,7 @@ impl BlockchainNode {
                                     Some(&storage), &quantum_poh
                                 ).await;
                                 
-                                if time_since_last_block > 15 {
+                                if time_since_last_block > 15 + stall_slack {
                                     println!("[STALL] 🔥 Triggering emergency failover for producer: {}", expected_producer);
                                     
                                     // Select emergency producer
@@ -4707,11 +5245,12 @@ impl BlockchainNode {
                         // Different thresholds for different levels of lag
                         if height_difference > 10 {
                             // Log the lag situation
-                            println!("[SYNC] ⚠️ Node is {} blocks behind network (local: {}, network: {})", 
-                                     height_difference, microblock_height, network_height);
+                            StructuredEvent::new("SYNC", "node_behind_network", format!("⚠️ Node is {} blocks behind network (local: {}, network: {})", height_difference, microblock_height, network_height))
+                                .height(network_height)
+                                .emit();
                             
                             // DEADLOCK DETECTION: Check if fast sync is stuck
-                            let current_time = get_timestamp_safe();
+                            let current_time = clock.now_secs();
                             if FAST_SYNC_IN_PROGRESS.load(Ordering::SeqCst) {
                                 let sync_start_time = FAST_SYNC_START_TIME.load(Ordering::Relaxed);
                                 let sync_elapsed = if sync_start_time > 0 {
@@ -4734,7 +5273,7 @@ impl BlockchainNode {
                             if !FAST_SYNC_IN_PROGRESS.swap(true, Ordering::SeqCst) {
                                 // Record sync start time for deadlock detection
                                 FAST_SYNC_START_TIME.store(current_time, Ordering::Relaxed);
-                                println!("[SYNC] ⚡ FAST SYNC MODE: {} blocks behind, catching up...", height_difference);
+                                StructuredEvent::new("SYNC", "fast_sync_started", format!("⚡ FAST SYNC MODE: {} blocks behind, catching up...", height_difference)).emit();
                                 
                                     // CRITICAL FIX: Do NOT update height before syncing blocks!
                                     // This prevents chain breaks where node thinks it's at height X without having the blocks
@@ -4742,7 +5281,7 @@ impl BlockchainNode {
                                     let sync_from_height = if microblock_height < network_height {
                                         // Check if we're at rotation boundary where sync might fail
                                         // Rotation happens after blocks 30, 60, 90... (not block 0 which is genesis)
-                                        let is_rotation_boundary = microblock_height > 0 && (microblock_height % 30) == 0;
+                                        let is_rotation_boundary = get_chain_params().is_rotation_end(microblock_height);
                                         if is_rotation_boundary {
                                             // At rotation boundary, be conservative - sync current height
                                             microblock_height
@@ -4759,11 +5298,14 @@ impl BlockchainNode {
                                 let storage_clone = storage.clone();
                                 
                                 let height_clone = height.clone();
+                                let clock_clone = clock.clone();
                                 tokio::spawn(async move {
                                     // PRODUCTION: Guard ensures flag is cleared even on panic/error
                                     let _guard = FastSyncGuard;
                                     
-                                    println!("[SYNC] 🚀 Fast downloading blocks {}-{}", sync_from_height, sync_to_height);
+                                    StructuredEvent::new("SYNC", "fast_sync_download", format!("🚀 Fast downloading blocks {}-{}", sync_from_height, sync_to_height))
+                                        .height(sync_to_height)
+                                        .emit();
                                     
                                     // TIMEOUT PROTECTION: Adaptive timeout based on blocks to sync
                                     // PRODUCTION: Use parallel download for faster sync
@@ -4778,7 +5320,7 @@ impl BlockchainNode {
                                     
                                     match sync_result {
                                         Ok(_) => {
-                                            println!("[SYNC] ✅ Fast sync completed successfully");
+                                            StructuredEvent::new("SYNC", "fast_sync_completed", "✅ Fast sync completed successfully").emit();
                                             
                                             // CRITICAL FIX: Update global height after successful fast sync
                                             // This ensures producer loop knows about the new blocks
@@ -4788,7 +5330,7 @@ impl BlockchainNode {
                                                 println!("[SYNC] 📊 Updated global height to {}", sync_to_height);
                                                 
                                                 // Update last block time to prevent stall detection false positives
-                                                LAST_BLOCK_PRODUCED_TIME.store(get_timestamp_safe(), Ordering::Relaxed);
+                                                LAST_BLOCK_PRODUCED_TIME.store(clock_clone.now_secs(), Ordering::Relaxed);
                                                 LAST_BLOCK_PRODUCED_HEIGHT.store(sync_to_height, Ordering::Relaxed);
                                             }
                                         },
@@ -4816,10 +5358,7 @@ impl BlockchainNode {
                 static CACHED_NODE_COUNT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(5);
                 static LAST_COUNT_UPDATE: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
                 
-                let current_time = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_secs();
+                let current_time = clock.now_secs();
                 
                 let last_update = LAST_COUNT_UPDATE.load(std::sync::atomic::Ordering::Relaxed);
                 let cached_count = CACHED_NODE_COUNT.load(std::sync::atomic::Ordering::Relaxed);
@@ -4968,11 +5507,7 @@ impl BlockchainNode {
                 let expected_height = microblock_height;
                 
                 // Determine maximum allowed lag based on round
-                let current_round = if expected_height == 0 {
-                    0
-                } else {
-                    (expected_height - 1) / ROTATION_INTERVAL_BLOCKS
-                };
+                let current_round = get_chain_params().leadership_round(expected_height);
                 
                 let max_allowed_lag = match current_round {
                     0 => 2,  // Round 0: Very strict (2 block tolerance)
@@ -5081,6 +5616,31 @@ impl BlockchainNode {
                     }
                 }
                 
+                // STARTUP GATE: Withhold production until enough peers or grace period elapsed
+                if is_my_turn_to_produce && !production_gate.is_released() {
+                    let peer_count = unified_p2p.as_ref().map(|p2p| p2p.get_peer_count()).unwrap_or(0);
+                    match production_gate.check(peer_count, node_start_time.elapsed()) {
+                        ProductionGateStatus::Withheld => {
+                            if cpu_check_counter % 10 == 1 {
+                                println!("[Microblock] 🚦 Production withheld: {}/{} peers connected, grace period {}s/{}s", 
+                                        peer_count, production_gate.min_peers_to_produce,
+                                        node_start_time.elapsed().as_secs(), production_gate.grace_period.as_secs());
+                            }
+                            tokio::time::sleep(Duration::from_secs(1)).await;
+                            continue;
+                        }
+                        ProductionGateStatus::ReleasedByPeers => {
+                            println!("[Microblock] 🟢 Production released: {} peers connected (threshold {})", 
+                                    peer_count, production_gate.min_peers_to_produce);
+                        }
+                        ProductionGateStatus::ReleasedByGracePeriod => {
+                            println!("[Microblock] 🟢 Production released: bootstrap grace period {}s elapsed with {}/{} peers", 
+                                    production_gate.grace_period.as_secs(), peer_count, production_gate.min_peers_to_produce);
+                        }
+                        ProductionGateStatus::Open => {}
+                    }
+                }
+                
                 // DEBUG: Log producer selection for first blocks
                 if next_block_height <= 5 {
                     println!("[DEBUG] For block #{}: producer={}, is_my_turn={}", 
@@ -5090,7 +5650,7 @@ impl BlockchainNode {
                 // CRITICAL: Verify entropy consensus at rotation boundaries
                 // This prevents different nodes selecting different producers
                 // Rotation happens when creating blocks 31, 61, 91... (first block of new round)
-                if next_block_height > 1 && (next_block_height - 1) % 30 == 0 {
+                if get_chain_params().is_rotation_start(next_block_height) {
                     // We're at a rotation boundary (blocks 31, 61, 91...)
                     println!("[CONSENSUS] 🔄 Rotation boundary at block #{} - verifying entropy consensus", next_block_height);
                     
@@ -5421,7 +5981,7 @@ impl BlockchainNode {
                                 selector.update(&our_entropy);
                                 
                                 // Add block height and round for uniqueness
-                                let leadership_round = (next_block_height - 1) / ROTATION_INTERVAL_BLOCKS;
+                                let leadership_round = get_chain_params().leadership_round(next_block_height);
                                 selector.update(&leadership_round.to_le_bytes());
                                 selector.update(&next_block_height.to_le_bytes());
                                 selector.update(&entropy_height.to_le_bytes());
@@ -5473,7 +6033,14 @@ impl BlockchainNode {
                         // Genesis phase: must be within 1 block
                         current_stored_height + 1 >= microblock_height
                     };
-                    NODE_IS_SYNCHRONIZED.store(is_synchronized, Ordering::SeqCst);
+                    // STATE CONSISTENCY: A halted node stays read-only until resync or acknowledgment
+                    let halted = state_root_breaker_halted();
+                    NODE_IS_SYNCHRONIZED.store(is_synchronized && !halted, Ordering::SeqCst);
+                    if halted && is_my_turn_to_produce {
+                        println!("[PRODUCER] 🛑 Skipping block #{}: production halted after repeated state-root mismatches", 
+                                 next_block_height);
+                        is_my_turn_to_produce = false;
+                    }
                 }
                 
                 if is_my_turn_to_produce {
@@ -5574,8 +6141,8 @@ impl BlockchainNode {
                             tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
                             
                             let block_exists = {
-                                match storage.load_microblock(next_block_height) {
-                                    Ok(Some(_)) => {
+                                match storage.has_microblock(next_block_height) {
+                                    Ok(true) => {
                                         println!("[EMERGENCY] ✅ Block #{} already exists from original producer! Skipping emergency production.", next_block_height);
                                         
                                         // Clear emergency flag since block exists
@@ -5585,7 +6152,7 @@ impl BlockchainNode {
                                         }
                                         true
                                     },
-                                    Ok(None) => {
+                                    Ok(false) => {
                                         println!("[EMERGENCY] ❌ Block #{} still missing after wait - proceeding with emergency production", next_block_height);
                                         false
                                     },
@@ -5697,13 +6264,13 @@ impl BlockchainNode {
                     
                     // PRODUCTION: Skip expensive readiness validation in microblock critical path
                     
-                    // EMISSION LOGIC: Check if this is an emission block (every 14,400 blocks = 4 hours)
+                    // EMISSION LOGIC: Check if this is an emission block (one per reward window)
                     // CRITICAL: Only producer of emission block creates emission transaction
-                    let is_emission_block = next_block_height % EMISSION_INTERVAL_BLOCKS == 0 && next_block_height > 0;
+                    let is_emission_block = get_chain_params().is_emission_block(next_block_height);
                     
                     if is_emission_block {
                         println!("[EMISSION] 🎯 Block #{} is EMISSION BLOCK (window #{})", 
-                                next_block_height, next_block_height / EMISSION_INTERVAL_BLOCKS);
+                                next_block_height, next_block_height / get_chain_params().blocks_in_reward_window());
                         println!("[EMISSION] 💰 Processing reward window as block producer...");
                         
                         // Process reward window: calculate + emit + sign + add to mempool
@@ -5723,7 +6290,7 @@ impl BlockchainNode {
                     // NOTE: If emission block, mempool contains emission transaction as FIRST tx
                     let tx_jsons = if let Some(ref mev_pool) = mev_mempool {
                         // MEV-AWARE BLOCK BUILDING
-                        let current_time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+                        let current_time = clock.now_secs();
                         let mut block_txs = Vec::new();
                         
                         // STEP 1: BUNDLE TXS (dynamic 0-20% allocation)
@@ -5822,6 +6389,21 @@ impl BlockchainNode {
                         }
                     }
                     
+                    // CANONICAL ORDER: validators reject blocks whose transactions are out of order
+                    qnet_state::MicroBlock::sort_canonical(&mut txs);
+                    
+                    // EMPTY BLOCK POLICY: nothing to include - wait out QNET_EMPTY_BLOCK_INTERVAL
+                    // Emission blocks always carry the emission transaction, so rewards are never delayed
+                    if !empty_block_policy.should_produce(
+                        !txs.is_empty() || is_emergency_producer,
+                        next_block_height,
+                        LAST_BLOCK_PRODUCED_TIME.load(Ordering::Relaxed),
+                        clock.now_secs(),
+                    ) {
+                        tokio::time::sleep(microblock_interval).await;
+                        continue;
+                    }
+                    
                     // PRE-EXECUTION: Update leader schedule and pre-execute if we're a future leader
                     {
                         // Get current producer list for rotation schedule
@@ -5925,6 +6507,18 @@ impl BlockchainNode {
                         genesis_timestamp + (next_block_height * BLOCK_INTERVAL_SECONDS)
                     };
                     
+                    // EXPIRY: drop transactions past their valid_until at this block, validators would reject it
+                    let (valid_txs, expired): (Vec<_>, Vec<_>) = txs.into_iter()
+                        .partition(|tx| tx.is_valid_at(next_block_height, deterministic_timestamp));
+                    let txs = valid_txs;
+                    if !expired.is_empty() {
+                        let mempool_guard = mempool.read().await;
+                        for tx in &expired {
+                            mempool_guard.remove_transaction(&tx.hash);
+                        }
+                        println!("[MEMPOOL] ⏰ Dropped {} transactions past their valid_until at block #{}", expired.len(), next_block_height);
+                    }
+                    
                     // Get previous block hash
                     let prev_hash = Self::get_previous_microblock_hash(&storage, next_block_height).await;
                     
@@ -5985,7 +6579,7 @@ impl BlockchainNode {
                     let (poh_hash, poh_count) = if next_block_height > 1 {
                         // CRITICAL: At rotation boundaries, wait for previous block if needed
                         // This prevents PoH regression when producer changes
-                        let is_rotation_start = next_block_height > 1 && ((next_block_height - 1) % 30) == 0;
+                        let is_rotation_start = get_chain_params().is_rotation_start(next_block_height);
                         
                         // Use auto-format loader that handles both EfficientMicroBlock and legacy MicroBlock
                         let mut prev_block_result = storage.load_microblock_auto_format(next_block_height - 1);
@@ -6185,7 +6779,7 @@ impl BlockchainNode {
                         .count();
                     
                     // Validate microblock (production checks)
-                    if let Err(e) = Self::validate_microblock_production(&microblock) {
+                    if let Err(e) = Self::validate_microblock_production(&microblock, clock.now_secs()) {
                         println!("[Microblock] ❌ Validation failed: {}", e);
                         continue;
                     }
@@ -6207,7 +6801,7 @@ impl BlockchainNode {
                                         return false;
                                     }
                                     // Additional parallel checks: signature, balance, nonce
-                                    if tx.signature.as_ref().map_or(true, |s| s.is_empty()) || tx.amount == 0 {
+                                    if tx.signature.as_ref().map_or(true, |s| s.is_empty()) || (tx.amount == 0 && !tx.is_cancellation()) {
                                         return false;
                                     }
                                 }
@@ -6243,7 +6837,7 @@ impl BlockchainNode {
                     
                     // CRITICAL: Check if block already exists to prevent forks (skip for genesis)
                     if microblock.height > 0 {
-                        if let Ok(Some(_)) = storage.load_microblock(microblock.height) {
+                        if let Ok(true) = storage.has_microblock(microblock.height) {
                             println!("[PRODUCER] ⚠️ Block #{} already exists, skipping creation to prevent fork", microblock.height);
                             continue;
                         }
@@ -6267,21 +6861,38 @@ impl BlockchainNode {
                     
                     if let Ok(_) = save_result {
                         println!("[Storage] ✅ Microblock {} saved with delta/compression", height_for_storage);
+                        crate::rpc::broadcast_ws_event(crate::rpc::WsEvent::new_block(&microblock));
                         
-                        // POOL #2 INTEGRATION: Collect transaction fees from producer's own block
-                        // This ensures fees are collected even when producer creates the block
+                        // STATE CONSISTENCY: Producer applies its own block like every receiver does,
+                        // otherwise its macroblock state roots would diverge from the network
+                        // (emission supply is already counted locally in process_reward_window)
+                        // POOL #2 INTEGRATION: Fees come from the receipts, so the producer
+                        // collects exactly what every receiver collects for this block
                         let mut total_fees_collected: u64 = 0;
-                        for tx in &txs {
-                            if !tx.from.starts_with("system_") && tx.gas_price > 0 && tx.gas_limit > 0 {
-                                let fee_amount = tx.gas_price * tx.gas_limit;
-                                if fee_amount > 0 {
-                                    total_fees_collected += fee_amount;
+                        {
+                            let state_guard = state_for_spawn.read().await;
+                            let results = state_guard.apply_transactions_at(&microblock.transactions, height_for_storage);
+                            for (tx, result) in microblock.transactions.iter().zip(results) {
+                                match result {
+                                    Err(e) => println!("[STATE] ⚠️ Failed to apply transaction {}: {}", tx.hash, e),
+                                    Ok(receipt) => {
+                                        if let qnet_state::TxStatus::Failed(reason) = &receipt.status {
+                                            println!("[STATE] ⚠️ Transaction {} failed: {}", tx.hash, reason);
+                                        }
+                                        if !tx.from.starts_with("system_") {
+                                            total_fees_collected = total_fees_collected.saturating_add(receipt.fee);
+                                        }
+                                    }
                                 }
                             }
+                            record_macroblock_state_root(height_for_storage, &state_guard);
                         }
+                        
                         if total_fees_collected > 0 {
                             let mut reward_mgr = reward_manager_for_spawn.write().await;
-                            reward_mgr.add_transaction_fees(total_fees_collected);
+                            if let Err(e) = reward_mgr.add_transaction_fees(total_fees_collected) {
+                                eprintln!("[POOL2] ⚠️ Producer fees not collected: {}", e);
+                            }
                             // Log for significant fees (> 0.01 QNC)
                             if total_fees_collected > 10_000_000 {
                                 println!("[POOL2] 💰 Producer collected {} nanoQNC in fees → Pool #2", total_fees_collected);
@@ -6300,16 +6911,17 @@ impl BlockchainNode {
                                 
                                 // ATOMIC REWARD: One reward for entire rotation
                                 if let Some(ref p2p) = p2p_for_reward {
-                                    if blocks_created == ROTATION_INTERVAL_BLOCKS as u32 {
+                                    let rotation_interval = rotation_tracker_clone.rotation_interval();
+                                    if blocks_created as u64 == rotation_interval {
                                         // Full rotation completed - reward valid block production
                                         p2p.update_node_reputation(&rotation_producer, ReputationEvent::FullRotationComplete);
-                                        println!("[ROTATION] ✅ {} completed full rotation ({}/30 blocks)", 
-                                                rotation_producer, blocks_created);
+                                        println!("[ROTATION] ✅ {} completed full rotation ({}/{} blocks)", 
+                                                rotation_producer, blocks_created, rotation_interval);
                                     } else {
                                         // Partial rotation (failover occurred) - still reward participation
                                         p2p.update_node_reputation(&rotation_producer, ReputationEvent::ConsensusParticipation);
-                                        println!("[ROTATION] ⚠️ {} partial rotation ({}/30 blocks)", 
-                                                rotation_producer, blocks_created);
+                                        println!("[ROTATION] ⚠️ {} partial rotation ({}/{} blocks)", 
+                                                rotation_producer, blocks_created, rotation_interval);
                                     }
                                 }
                             }
@@ -6345,7 +6957,7 @@ impl BlockchainNode {
                             
                             // OPTIMIZATION: Use direct broadcast for critical blocks (emergency, rotation, consensus)
                             let is_critical_block = is_emergency_producer || 
-                                                  (height_for_broadcast > 1 && (height_for_broadcast - 1) % 30 == 0) || // Rotation
+                                                  get_chain_params().is_rotation_start(height_for_broadcast) || // Rotation
                                                   (height_for_broadcast % 90 >= 61 && height_for_broadcast % 90 <= 90); // Consensus
                             
                             let result = if is_critical_block {
@@ -6405,7 +7017,7 @@ impl BlockchainNode {
                     }
                     
                     // CRITICAL FIX: Update global last block time for stall detection
-                    LAST_BLOCK_PRODUCED_TIME.store(get_timestamp_safe(), Ordering::Relaxed);
+                    LAST_BLOCK_PRODUCED_TIME.store(clock.now_secs(), Ordering::Relaxed);
                     LAST_BLOCK_PRODUCED_HEIGHT.store(microblock.height, Ordering::Relaxed);
                     
                     // CRITICAL: Increment height for next iteration
@@ -6431,16 +7043,17 @@ impl BlockchainNode {
                         rotation_tracker.check_rotation_complete(microblock.height).await {
                         
                         if let Some(p2p) = &unified_p2p {
-                            if blocks_created == ROTATION_INTERVAL_BLOCKS as u32 {
+                            let rotation_interval = rotation_tracker.rotation_interval();
+                            if blocks_created as u64 == rotation_interval {
                                 // Full rotation: reward valid block production
                                 p2p.update_node_reputation(&rotation_producer, ReputationEvent::FullRotationComplete);
-                                println!("[ROTATION] ✅ {} completed full rotation #{} ({}/30 blocks)", 
-                                        rotation_producer, microblock.height / 30, blocks_created);
+                                println!("[ROTATION] ✅ {} completed full rotation #{} ({}/{} blocks)", 
+                                        rotation_producer, microblock.height / rotation_interval, blocks_created, rotation_interval);
                             } else {
                                 // Partial rotation: reward participation
                                 p2p.update_node_reputation(&rotation_producer, ReputationEvent::ConsensusParticipation);
-                                println!("[ROTATION] ⚠️ {} partial rotation #{} ({}/30 blocks)", 
-                                        rotation_producer, microblock.height / 30, blocks_created);
+                                println!("[ROTATION] ⚠️ {} partial rotation #{} ({}/{} blocks)", 
+                                        rotation_producer, microblock.height / rotation_interval, blocks_created, rotation_interval);
                             }
                         }
                     }
@@ -6461,8 +7074,8 @@ impl BlockchainNode {
                                  txs.len(), remaining_size);
                     }
                     
-                    // Log completion only every 30 blocks (rotation boundary)
-                    if microblock_height % 30 == 0 {
+                    // Log completion only at rotation boundaries
+                    if get_chain_params().is_rotation_end(microblock_height) {
                         println!("[BLOCK] ✅ Rotation complete at #{} | Next producer will be selected", microblock_height);
                     }
                     
@@ -6476,8 +7089,8 @@ impl BlockchainNode {
                                 
                                 // STORAGE OPTIMIZATION: Trigger pruning after snapshot for non-archive nodes
                                 // This ensures we have a valid snapshot before removing old blocks
-                                // INTERVAL: 14400 blocks = 4 hours (aligned with reward window)
-                                if microblock_height % 14_400 == 0 {
+                                // INTERVAL: once per reward window (14400 blocks = 4 hours by default)
+                                if get_chain_params().is_emission_block(microblock_height) {
                                     let storage_for_pruning = Arc::clone(&storage);
                                     tokio::spawn(async move {
                                         match storage_for_pruning.prune_old_blocks() {
@@ -6595,7 +7208,7 @@ impl BlockchainNode {
                         }
                         
                         // DEADLOCK DETECTION: Check if background sync is stuck
-                        let current_time = get_timestamp_safe();
+                        let current_time = clock.now_secs();
                         if SYNC_IN_PROGRESS.load(Ordering::SeqCst) {
                             let sync_start_time = SYNC_START_TIME.load(Ordering::Relaxed);
                             let sync_elapsed = if sync_start_time > 0 {
@@ -6649,8 +7262,9 @@ impl BlockchainNode {
                                 
                                 if let Some(network_height) = network_height {
                                 if network_height > current_height {
-                                    println!("[SYNC] 📥 Background sync: downloading blocks {}-{}", 
-                                             current_height + 1, network_height);
+                                    StructuredEvent::new("SYNC", "background_sync_download", format!("📥 Background sync: downloading blocks {}-{}", current_height + 1, network_height))
+                                        .height(network_height)
+                                        .emit();
                                     
                                     // TIMEOUT PROTECTION: 30-second timeout for background sync
                                     // PRODUCTION: Use parallel download for faster sync
@@ -6662,9 +7276,11 @@ impl BlockchainNode {
                                     match sync_result {
                                         Ok(_) => {
                                     // Update global height atomically
-                                    if let Ok(Some(_)) = storage_clone.load_microblock(network_height) {
+                                    if let Ok(true) = storage_clone.has_microblock(network_height) {
                                         *height_clone.write().await = network_height;
-                                        println!("[SYNC] ✅ Background sync completed to block #{}", network_height);
+                                        StructuredEvent::new("SYNC", "background_sync_completed", format!("✅ Background sync completed to block #{}", network_height))
+                                            .height(network_height)
+                                            .emit();
                                         
                                         // REPUTATION RECOVERY: Restore reputation if node caught up
                                         // Check if we were significantly behind (>50 blocks)
@@ -6695,7 +7311,7 @@ impl BlockchainNode {
                         // CRITICAL: Check if we already have the next block locally
                         // FIX: For non-producer, expected height is NEXT block height
                         let expected_height = next_block_height;
-                        if let Ok(Some(_)) = storage.load_microblock(expected_height) {
+                        if let Ok(true) = storage.has_microblock(expected_height) {
                             // Block already exists locally - advance to this height
                             microblock_height = expected_height;
                             {
@@ -6705,7 +7321,7 @@ impl BlockchainNode {
                             println!("[SYNC] ✅ Found local block #{} - advancing to height {}", expected_height, microblock_height);
                             
                             // Rotation boundary check for logging
-                            let is_rotation_boundary = expected_height > 0 && (expected_height % 30) == 0;
+                            let is_rotation_boundary = get_chain_params().is_rotation_end(expected_height);
                             if is_rotation_boundary {
                                 println!("[SYNC] 🔄 Rotation boundary reached at block #{}", expected_height);
                             }
@@ -6722,7 +7338,8 @@ impl BlockchainNode {
                             // TowerBFT returns optimized timeouts (2-5s) now that crypto is cached
                             // Old hardcoded values (7-20s) are OBSOLETE and cause nodes to lag
                             let retry_count = 0; // First attempt
-                            let actual_timeout = tower_bft.get_timeout(next_block_height, retry_count).await;
+                            let actual_timeout = tower_bft.get_timeout(next_block_height, retry_count).await
+                                + empty_block_policy.liveness_slack();
                             
                             // CRITICAL: Start ASYNC failover monitoring (does NOT block main loop!)
                             // Failover runs in background, main loop continues immediately
@@ -6736,7 +7353,7 @@ impl BlockchainNode {
                             // Calculate block properties for logging
                             let blocks_since_last_macro = expected_height_timeout % 90;
                             let is_consensus_period = blocks_since_last_macro >= 61 && blocks_since_last_macro <= 90;
-                            let is_rotation_boundary = expected_height_timeout > 1 && ((expected_height_timeout - 1) % 30) == 0;
+                            let is_rotation_boundary = get_chain_params().is_rotation_start(expected_height_timeout);
                             
                             // CRITICAL FIX: ALWAYS start timeout detection to prevent forks!
                             // Even if node is behind, it needs to detect failed producers
@@ -6748,8 +7365,8 @@ impl BlockchainNode {
                                 
                                 // CRITICAL: Double-check if block was received during timeout period
                                 // This prevents race condition where block arrives just as timeout triggers
-                                let block_exists = match storage_timeout.load_microblock(expected_height_timeout) {
-                                    Ok(Some(_)) => {
+                                let block_exists = match storage_timeout.has_microblock(expected_height_timeout) {
+                                    Ok(true) => {
                                         println!("[FAILOVER] ✅ Block #{} received during timeout - cancelling failover", 
                                                  expected_height_timeout);
                                         true
@@ -6893,8 +7510,7 @@ impl BlockchainNode {
                         // Check if macroblock was created
                         // Macroblock is saved with key "macroblock_{height}" where height is macroblock number
                         // For example, first macroblock (at block 90) is saved as "macroblock_1"
-                        let macroblock_exists = storage_check.load_microblock(expected_macroblock * 90 + 1)
-                            .map(|mb| mb.is_some())
+                        let macroblock_exists = storage_check.has_microblock(expected_macroblock * 90 + 1)
                             .unwrap_or(false);
                         
                         if macroblock_exists {
@@ -7110,18 +7726,13 @@ impl BlockchainNode {
         if let Some(p2p) = unified_p2p {
             // PERFORMANCE FIX: Cache producer selection for entire 30-block period to prevent HTTP spam
             // Producer is SAME for all blocks in rotation period (blocks 1-30, 31-60, etc.)
-            let rotation_interval = 30u64; // EXISTING: 30-block rotation from MICROBLOCK_ARCHITECTURE_PLAN.md
+            // Rotation interval is a network-wide chain parameter (30 blocks by default)
+            let chain_params = get_chain_params();
+            let rotation_interval = chain_params.rotation_interval_blocks;
             // CRITICAL FIX: Proper round calculation for blocks 1-30, 31-60, 61-90...
             // Round 0: blocks 1-30, Round 1: blocks 31-60, Round 2: blocks 61-90, etc.
-            let leadership_round = if current_height == 0 {
-                0  // Genesis block - special case, not part of regular rotation
-            } else if current_height <= 30 {
-                0  // Blocks 1-30 are round 0
-            } else {
-                // Formula for blocks > 30: (height - 1) / 30
-                // This ensures: 1-30 → round 0, 31-60 → round 1, 61-90 → round 2
-                (current_height - 1) / rotation_interval
-            };
+            // Cache entries are keyed by this round, so they follow the configured interval
+            let leadership_round = chain_params.leadership_round(current_height);
             
             // CRITICAL: Use shared module-level cache to prevent duplication
             use producer_cache::CACHED_PRODUCER_SELECTION;
@@ -7140,11 +7751,8 @@ impl BlockchainNode {
                 // For PoH rounds, check if we're fully synchronized
                 // CONSERVATIVE: Wait for FULL round completion before using cache
                 // This ensures all nodes have processed the entire previous round
-                let required_block = match leadership_round {
-                    1 => 30,  // Round 1: wait for block 30
-                    2 => 60,  // Round 2: wait for block 60 (full Round 1 completion)
-                    _ => leadership_round * 30  // Round N: wait for N*30 (full previous round)
-                };
+                // Round N: wait for block N*interval (full previous round)
+                let required_block = leadership_round * rotation_interval;
                 let local_height = store.get_chain_height().unwrap_or(0);
                 local_height >= required_block  // Only use cache if we have all required blocks
             } else {
@@ -7153,7 +7761,7 @@ impl BlockchainNode {
             
             // CRITICAL FIX: Clear cache at rotation boundaries to ensure new producer selection
             // This prevents using stale cached producer when entering new round
-            if current_height > 0 && (current_height - 1) % rotation_interval == 0 {
+            if chain_params.is_rotation_start(current_height) {
                 // We're at a rotation boundary (blocks 31, 61, 91...)
                 // Clear cache for the NEW round we're entering
                 if let Ok(mut cache) = producer_cache.lock() {
@@ -7175,7 +7783,7 @@ impl BlockchainNode {
                         if current_height > 0 && ((current_height - 1) % rotation_interval == 0 || current_height == 1) {
                             // Using cached producer selection
                             // Next rotation: Round 0 → 31, Round 1 → 61, Round 2 → 91...
-                            let next_rotation_block = (leadership_round + 1) * rotation_interval + 1;
+                            let next_rotation_block = chain_params.next_rotation_block(leadership_round);
                             println!("[MICROBLOCK] 🎯 Producer: {} (round: {}, CACHED SELECTION, next rotation: block {})", 
                                      cached_producer, leadership_round, next_rotation_block);
                         }
@@ -7186,7 +7794,7 @@ impl BlockchainNode {
                 // CRITICAL: Clear cache for this round if we can't use it
                 // This ensures recalculation when synchronization state changes
                 if let Ok(mut cache) = producer_cache.lock() {
-                    if cache.remove(&leadership_round).is_some() && current_height > 31 {
+                    if cache.remove(&leadership_round).is_some() && current_height > rotation_interval + 1 {
                         // Only log after initial rounds to reduce noise
                         println!("[MICROBLOCK] 🔄 Cache invalidated for round {} due to sync state change", leadership_round);
                     }
@@ -7418,7 +8026,7 @@ impl BlockchainNode {
             // Rotation happens at blocks 31, 61, 91... (not 30, 60, 90)
             if current_height > 0 && ((current_height - 1) % rotation_interval == 0 || current_height == 1) {
                 // New round - VRF producer selection
-                let next_rotation_block = (leadership_round + 1) * rotation_interval + 1;
+                let next_rotation_block = chain_params.next_rotation_block(leadership_round);
                 println!("[VRF] 🎯 Producer: {} (round: {}, VRF SELECTION, next rotation: block {})", 
                          selected_producer, leadership_round, next_rotation_block);
             }
@@ -7550,7 +8158,7 @@ impl BlockchainNode {
                 NodeType::Full => {
                     // Full nodes eligible for emergency (same as normal production)
                     let validated_peers = p2p.get_validated_active_peers();
-                    let has_peers = validated_peers.len() >= 3; // EXISTING: 3f+1 Byzantine formula
+                    let has_peers = get_byzantine_params().has_quorum(validated_peers.len()); // EXISTING: 3f+1 Byzantine formula
                     let own_reputation = Self::get_node_reputation_score(own_node_id, p2p).await;
                     let has_reputation = own_reputation >= 0.70;
                     has_peers && has_reputation
@@ -7893,10 +8501,7 @@ impl BlockchainNode {
         }
         
         // Check 3: Recent block timing validation (prevent rapid-fire production)
-        let time_since_epoch = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs();
+        let time_since_epoch = get_timestamp_safe();
         
         // Network health indicators
         let network_health = match active_peers {
@@ -8040,7 +8645,7 @@ impl BlockchainNode {
             NodeType::Full => {
                 // EXISTING: Regular Full nodes need validated peers for consensus participation
                 let validated_peers = p2p.get_validated_active_peers();
-                let has_peers = validated_peers.len() >= 3; // EXISTING: 3f+1 Byzantine formula
+                let has_peers = get_byzantine_params().has_quorum(validated_peers.len()); // EXISTING: 3f+1 Byzantine formula
                 let own_reputation = Self::get_node_reputation_score(own_node_id, p2p).await;
                 let has_reputation = own_reputation >= 0.70;
                 has_peers && has_reputation
@@ -8851,7 +9456,8 @@ impl BlockchainNode {
             height: macroblock_index,
             timestamp: deterministic_timestamp,  // DETERMINISTIC: Same on all nodes
             micro_blocks: microblock_hashes,
-            state_root: state_accumulator,
+            // Root of applied state; legacy accumulator only if this node never applied the window
+            state_root: local_macroblock_state_root(macroblock_index).unwrap_or(state_accumulator),
             consensus_data,
             previous_hash: storage.get_latest_macroblock_hash()
                 .unwrap_or([0u8; 32]),
@@ -8963,10 +9569,7 @@ impl BlockchainNode {
             let commit = Commit {
                 node_id: our_id.clone(),
                 commit_hash: commit_hash.clone(),
-                timestamp: std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap()
-                    .as_secs(),
+                timestamp: get_timestamp_safe(),
                 signature,
             };
             
@@ -9179,10 +9782,7 @@ impl BlockchainNode {
                 node_id: our_id.clone(),
                 reveal_data: reveal_data.clone(), // Already Vec<u8>
                 nonce,
-                timestamp: std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap()
-                    .as_secs(),
+                timestamp: get_timestamp_safe(),
             };
             
             // Submit OWN reveal to consensus engine
@@ -9399,33 +9999,30 @@ impl BlockchainNode {
         }
         
         // SECURITY: Check activation code directly if available
-        if let Ok(activation_code) = std::env::var("QNET_ACTIVATION_CODE") {
-            use crate::genesis_constants::GENESIS_BOOTSTRAP_CODES;
-            
-            for genesis_code in GENESIS_BOOTSTRAP_CODES {
-                if activation_code == *genesis_code {
-                    // CRITICAL FIX: Genesis activation codes have 20% floor for real penalties
-                    if let Some(p2p) = unified_p2p {
-                        let p2p_score = match p2p.get_reputation_system().lock() {
-                            Ok(reputation) => reputation.get_reputation(node_id),
-                            Err(_) => 70.0, // Default start reputation if lock fails
-                        };
-                        
-                        let p2p_reputation = (p2p_score / 100.0).max(0.0).min(1.0);
-                        let final_reputation = p2p_reputation; // No special protection
-                        
-                        if final_reputation < 0.70 {
-                            println!("[REPUTATION] ⚠️ Genesis activation {} penalized: {:.1}% (floor: 20%, below threshold)", genesis_code, final_reputation * 100.0);
-                        } else if final_reputation < 0.90 {
-                            println!("[REPUTATION] 🟡 Genesis activation {} partially penalized: {:.1}% (floor: 20%)", genesis_code, final_reputation * 100.0);
-                        }
-                        
-                        return final_reputation;
-                    } else {
-                        println!("[REPUTATION] 🛡️ Genesis activation code {} detected - starting at consensus threshold (70%)", genesis_code);
-                        return 0.70;
-                    }
+        let genesis_code = std::env::var("QNET_ACTIVATION_CODE").ok()
+            .and_then(|code| crate::activation_validation::ActivationCode::parse(&code).ok())
+            .filter(|code| code.is_genesis_bootstrap());
+        if let Some(genesis_code) = genesis_code {
+            // CRITICAL FIX: Genesis activation codes have 20% floor for real penalties
+            if let Some(p2p) = unified_p2p {
+                let p2p_score = match p2p.get_reputation_system().lock() {
+                    Ok(reputation) => reputation.get_reputation(node_id),
+                    Err(_) => 70.0, // Default start reputation if lock fails
+                };
+                
+                let p2p_reputation = (p2p_score / 100.0).max(0.0).min(1.0);
+                let final_reputation = p2p_reputation; // No special protection
+                
+                if final_reputation < 0.70 {
+                    println!("[REPUTATION] ⚠️ Genesis activation {} penalized: {:.1}% (floor: 20%, below threshold)", genesis_code, final_reputation * 100.0);
+                } else if final_reputation < 0.90 {
+                    println!("[REPUTATION] 🟡 Genesis activation {} partially penalized: {:.1}% (floor: 20%)", genesis_code, final_reputation * 100.0);
                 }
+                
+                return final_reputation;
+            } else {
+                println!("[REPUTATION] 🛡️ Genesis activation code {} detected - starting at consensus threshold (70%)", genesis_code);
+                return 0.70;
             }
         }
         
@@ -9499,22 +10096,12 @@ impl BlockchainNode {
     
     // Helper methods for production microblocks
     
+    /// Merkle tree root over transaction hashes, so inclusion proofs can be served
     fn calculate_merkle_root(txs: &[qnet_state::Transaction]) -> [u8; 32] {
-        use sha3::{Sha3_256, Digest};
-        
-        if txs.is_empty() {
-            return [0u8; 32];
-        }
-        
-        let mut hasher = Sha3_256::new();
-        for tx in txs {
-            hasher.update(tx.hash.as_bytes());
-        }
-        
-        let result = hasher.finalize();
-        let mut hash = [0u8; 32];
-        hash.copy_from_slice(&result);
-        hash
+        let leaves: Vec<[u8; 32]> = txs.iter()
+            .map(|tx| qnet_state::merkle::transaction_leaf(&tx.hash))
+            .collect();
+        qnet_state::merkle::merkle_root(&leaves)
     }
     
     /// PRODUCTION: Normalize node ID for consistent signature validation
@@ -9992,10 +10579,7 @@ impl BlockchainNode {
                             false
                         } else {
                             // Check certificate expiration
-                            let now = std::time::SystemTime::now()
-                                .duration_since(std::time::UNIX_EPOCH)
-                                .unwrap()
-                                .as_secs();
+                            let now = get_timestamp_safe();
                             if now > certificate.expires_at {
                                 println!("[CRYPTO] ❌ Certificate expired at {}, now is {}", 
                                          certificate.expires_at, now);
@@ -10045,16 +10629,14 @@ impl BlockchainNode {
                     
                     // ACTIVE REQUEST: Send CertificateRequest to producer if not recently requested
                     if let Some(p2p_ref) = p2p {
-                        let now = SystemTime::now()
-                            .duration_since(UNIX_EPOCH)
-                            .unwrap_or(Duration::from_secs(0))
-                            .as_secs();
+                        let now = get_timestamp_safe();
                         
                         // DDoS PROTECTION: Check if we already requested this certificate recently (5s cooldown)
                         let should_request = {
                             let mut requested = REQUESTED_CERTIFICATES.lock().unwrap();
+                            prune_requested_certificates(&mut requested, now);
                             if let Some(&last_request) = requested.get(&compact_sig.cert_serial) {
-                                if now - last_request < 5 {
+                                if now - last_request < CERTIFICATE_REQUEST_COOLDOWN_SECS {
                                     false // Too soon, skip request
                                 } else {
                                     requested.insert(compact_sig.cert_serial.clone(), now);
@@ -10301,7 +10883,28 @@ impl BlockchainNode {
         }
     }
     
-    fn validate_microblock_production(microblock: &qnet_state::MicroBlock) -> Result<(), String> {
+    /// Reject a block carrying a user transaction over the network size caps
+    fn validate_microblock_tx_sizes(
+        microblock: &qnet_state::MicroBlock,
+        limits: &qnet_mempool::validation::TxSizeLimits,
+    ) -> Result<(), String> {
+        for tx in &microblock.transactions {
+            qnet_mempool::validation::validate_tx_size(tx, limits)
+                .map_err(|e| format!("Block #{} transaction {}: {}", microblock.height, tx.hash, e))?;
+        }
+        Ok(())
+    }
+    
+    /// Reject a block carrying a transaction past its `valid_until`
+    fn validate_microblock_tx_validity(microblock: &qnet_state::MicroBlock) -> Result<(), String> {
+        for tx in &microblock.transactions {
+            qnet_mempool::validation::validate_valid_until(tx, microblock.height, microblock.timestamp)
+                .map_err(|e| format!("Block #{} transaction {}: {}", microblock.height, tx.hash, e))?;
+        }
+        Ok(())
+    }
+    
+    fn validate_microblock_production(microblock: &qnet_state::MicroBlock, current_time: u64) -> Result<(), String> {
         // Production validation checks
         
         // Allow height 0 for Genesis Block
@@ -10321,8 +10924,11 @@ impl BlockchainNode {
             return Err(format!("Too many transactions: {} (max: 50000)", microblock.transactions.len()));
         }
         
+        if !microblock.has_canonical_order() {
+            return Err("Transactions not in canonical order".to_string());
+        }
+        
         // Validate timestamp is not too far in future
-        let current_time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
         if microblock.timestamp > current_time + 30 {
             return Err("Timestamp too far in future".to_string());
         }
@@ -10704,7 +11310,8 @@ impl BlockchainNode {
             height: consensus_data.round_number,
             timestamp: deterministic_timestamp,  // DETERMINISTIC: Same on all nodes
             micro_blocks: microblock_hashes,
-            state_root: state_accumulator, // Real accumulated state
+            // Root of applied state; legacy accumulator only if this node never applied the window
+            state_root: local_macroblock_state_root(consensus_data.round_number).unwrap_or(state_accumulator),
             consensus_data: qnet_state::ConsensusData {
                 commits: consensus_commits,
                 reveals: consensus_reveals,
@@ -10904,6 +11511,7 @@ impl BlockchainNode {
     pub fn handle_entropy_response(&self, block_height: u64, entropy_hash: [u8; 32], responder_id: String) {
         // Store the response
         let mut responses = ENTROPY_RESPONSES.lock().unwrap();
+        prune_entropy_responses(&mut responses, block_height);
         responses.insert((block_height, responder_id.clone()), entropy_hash);
         
         println!("[CONSENSUS] 🎯 Stored entropy response for block {} from {}: {:x}", 
@@ -11077,6 +11685,12 @@ impl BlockchainNode {
         }
     }
     
+    /// Commit/reveal set that finalized a macroblock, `None` if it does not exist yet
+    pub fn get_macroblock_consensus(&self, index: u64) -> Result<Option<qnet_state::ConsensusData>, QNetError> {
+        self.storage.get_macroblock_consensus(index)
+            .map_err(|e| QNetError::StorageError(e.to_string()))
+    }
+    
     pub async fn submit_transaction(&self, tx: qnet_state::Transaction) -> Result<String, QNetError> {
         // PRODUCTION VALIDATION - reject invalid transactions immediately
         if let Err(validation_error) = tx.validate() {
@@ -11104,10 +11718,21 @@ impl BlockchainNode {
             }
         }
         
-        if tx.amount == 0 && matches!(tx.tx_type, qnet_state::TransactionType::Transfer { .. }) {
+        // Zero-value self-transfers are cancellations (see TransactionBuilder::cancel)
+        if tx.amount == 0 && matches!(tx.tx_type, qnet_state::TransactionType::Transfer { .. }) && !tx.is_cancellation() {
             return Err(QNetError::ValidationError("Transfer amount cannot be zero".to_string()));
         }
         
+        // REPLAY PROTECTION: only accept transactions signed for this network
+        qnet_mempool::validation::validate_chain_id(&tx, crate::network_config::get_network_config().chain_id)?;
+        
+        // Network size caps, re-checked by every node when the block arrives
+        qnet_mempool::validation::validate_tx_size(&tx, &get_chain_params().tx_size_limits())?;
+        
+        // EXPIRY: must still be includable in the next block
+        let next_height = self.storage.get_chain_height().unwrap_or(0) + 1;
+        qnet_mempool::validation::validate_valid_until(&tx, next_height, self.clock.now_secs())?;
+        
         // SHARDING: Check if this is a cross-shard transaction
         if let Some(ref shard_coordinator) = self.shard_coordinator {
             if let qnet_state::TransactionType::Transfer { to, .. } = &tx.tx_type {
@@ -11125,10 +11750,7 @@ impl BlockchainNode {
                         from_shard,
                         to_shard,
                         amount: tx.amount,
-                        timestamp: std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap_or_default()
-                            .as_secs(),
+                        timestamp: self.clock.now_secs(),
                     };
                     
                     // Process through shard coordinator
@@ -11142,40 +11764,18 @@ impl BlockchainNode {
             }
         }
         
-        // CRITICAL SECURITY: Check nonce BEFORE adding to mempool
-        // This prevents DoS attacks where attacker floods mempool with invalid nonces
+        // CRITICAL SECURITY: Check nonce and balance BEFORE adding to mempool
+        // This prevents DoS attacks where attacker floods mempool with transactions doomed to fail
+        // Queued transactions from the same sender count: their nonces are skipped, their cost reserved
         {
+            let pending = self.mempool.read().await.pending_from(&tx.from);
             let state = self.state.read().await;
-            
-            // Check nonce
-            if let Some(account) = state.get_account(&tx.from) {
-                let expected_nonce = account.nonce + 1;
-                if tx.nonce != expected_nonce {
-                    return Err(QNetError::ValidationError(format!(
-                        "Invalid nonce: expected {}, got {} (anti-replay protection)",
-                        expected_nonce, tx.nonce
-                    )));
-                }
-            } else {
-                // New account: nonce must be 1 (first transaction)
-                if tx.nonce != 1 {
-                    return Err(QNetError::ValidationError(format!(
-                        "Invalid nonce for new account: expected 1, got {}",
-                        tx.nonce
-                    )));
-                }
-            }
-            
-            // Check balance
-            let sender_balance = state.get_balance(&tx.from);
-            let required_balance = tx.amount + (tx.gas_price * tx.gas_limit);
-            
-            if sender_balance < required_balance {
-                return Err(QNetError::ValidationError(format!(
-                    "Insufficient balance: have {}, need {}", 
-                    sender_balance, required_balance
-                )));
-            }
+            qnet_mempool::validation::validate_account_state(
+                &tx,
+                state.get_account(&tx.from).as_ref(),
+                &pending,
+                qnet_mempool::validation::DEFAULT_MAX_NONCE_LOOKAHEAD,
+            )?;
         }
         
         let tx_json = serde_json::to_string(&tx)
@@ -11184,6 +11784,24 @@ impl BlockchainNode {
         
         {
             let mut mempool = self.mempool.write().await;
+            
+            // ANTI-SPAM: The admission floor rises as the pool fills (system transactions exempt)
+            let floor = mempool.effective_min_gas_price();
+            let class = qnet_mempool::priority::PriorityClass::from_transaction_type(&tx.tx_type);
+            if class != qnet_mempool::priority::PriorityClass::System && tx.gas_price < floor {
+                return Err(QNetError::ValidationError(format!(
+                    "Gas price {} below current mempool floor {}", tx.gas_price, floor
+                )));
+            }
+            
+            // REPLACE-BY-FEE: a second transaction at a pending nonce (e.g. a cancellation)
+            // must outbid the pending one; the mempool then evicts it
+            if let Some(min) = mempool.replacement_min_gas_price(&tx.from, tx.nonce) {
+                if tx.gas_price < min {
+                    return Err(qnet_mempool::MempoolError::ReplacementUnderpriced { min, got: tx.gas_price }.into());
+                }
+            }
+            
             let tx_json = serde_json::to_string(&tx).unwrap();
             let tx_hash = format!("{:x}", sha3::Sha3_256::digest(tx_json.as_bytes()));
             // PRODUCTION: Add with gas_price for priority ordering (anti-spam protection)
@@ -11197,7 +11815,7 @@ impl BlockchainNode {
         }
         
         println!("[Transaction] ✅ Validated and submitted: {} (amount: {}, gas: {})", 
-                 hash, tx.amount, tx.gas_price * tx.gas_limit);
+                 hash, tx.amount, tx.gas_price.saturating_mul(tx.gas_limit));
         
         Ok(hash)
     }
@@ -11225,11 +11843,112 @@ impl BlockchainNode {
         Ok(state.get_account(address))
     }
     
+    /// Batch account lookup under a single state lock, in request order
+    pub async fn get_accounts(&self, addresses: &[&str]) -> Result<Vec<Option<qnet_state::Account>>, QNetError> {
+        let state = self.state.read().await;
+        state.get_accounts(addresses)
+            .map_err(|e| QNetError::StorageError(e.to_string()))
+    }
+    
     pub async fn get_balance(&self, address: &str) -> Result<u64, QNetError> {
         let state = self.state.read().await;
         Ok(state.get_balance(address))
     }
     
+    /// Account as of microblock `height`, or `HistoryUnavailable` once pruned
+    pub async fn get_account_at(&self, address: &str, height: u64) -> qnet_state::StateResult<Option<qnet_state::Account>> {
+        self.state.read().await.get_account_at(address, height)
+    }
+    
+    /// Full account view (state + node rewards) for the wallet summary endpoint
+    pub async fn get_account_summary(&self, address: &str) -> AccountSummary {
+        let account = self.state.read().await.get_account(address);
+        let rewards = self.reward_manager.read().await;
+        AccountSummary::assemble(address, account.as_ref(), &rewards)
+    }
+    
+    /// Registered node operated by `address`, for wallet views ("you operate a Super node")
+    pub fn get_node_by_wallet(&self, address: &str) -> Result<Option<(String, NodeType)>, QNetError> {
+        let node = self.storage.load_node_by_wallet(address)
+            .map_err(|e| QNetError::StorageError(e.to_string()))?;
+        Ok(node.map(|(node_id, node_type)| {
+            let node_type = match node_type.as_str() {
+                "super" => NodeType::Super,
+                "full" => NodeType::Full,
+                _ => NodeType::Light,
+            };
+            (node_id, node_type)
+        }))
+    }
+    
+    /// Lazy reward claim status of a node
+    pub async fn get_node_reward_status(&self, node_id: &str) -> qnet_consensus::RewardClaimStatus {
+        self.reward_manager.read().await.get_claim_status(node_id)
+    }
+    
+    /// Check a caller-supplied key against the configured admin API key
+    pub fn is_admin_api_key(&self, provided: Option<&str>) -> bool {
+        admin_api_key_matches(self.security_config.admin_api_key.as_deref(), provided)
+    }
+    
+    /// Operator-initiated lazy reward claim, paid to the node's registered owner wallet
+    /// Returns the claim transaction hash and the claimed amount
+    pub async fn claim_node_rewards(&self, node_id: &str) -> Result<(String, u64), QNetError> {
+        let (wallet, reward) = {
+            let mut reward_manager = self.reward_manager.write().await;
+            let wallet = reward_manager.get_node_owner(node_id)
+                .ok_or_else(|| QNetError::ValidationError(format!("Node {} not registered for rewards", node_id)))?;
+            
+            let result = reward_manager.claim_rewards(node_id, &wallet);
+            match result.reward {
+                Some(reward) if result.success => (wallet, reward),
+                _ => return Err(QNetError::ValidationError(result.message)),
+            }
+        };
+        
+        let timestamp = self.clock.now_secs();
+        let tx = build_reward_claim_transaction(node_id, &wallet, reward.total_reward, timestamp);
+        
+        match self.queue_system_transaction(tx).await {
+            Ok(tx_hash) => {
+                if let Err(e) = self.storage.delete_pending_reward(node_id) {
+                    eprintln!("[REWARDS] ⚠️ Failed to delete claimed reward for {}: {}", node_id, e);
+                }
+                println!("[REWARDS] ✅ Claim for {} queued: {:.9} QNC to {}...", 
+                         node_id, reward.total_reward as f64 / 1_000_000_000.0, &wallet[..16.min(wallet.len())]);
+                Ok((tx_hash, reward.total_reward))
+            }
+            Err(e) => {
+                // Put the reward back so the claim can be retried
+                self.reward_manager.write().await.restore_pending_reward(node_id.to_string(), reward);
+                Err(e)
+            }
+        }
+    }
+    
+    /// Queue a system-originated transaction (no user signature or nonce)
+    /// Checked against the mempool's per-type rules, so only designated system senders pass
+    async fn queue_system_transaction(&self, tx: qnet_state::Transaction) -> Result<String, QNetError> {
+        qnet_mempool::validation::validate_tx_type(&tx).map_err(QNetError::ValidationError)?;
+        
+        let tx_json = serde_json::to_string(&tx)
+            .map_err(|e| QNetError::SerializationError(format!("Failed to serialize transaction: {}", e)))?;
+        let added = {
+            let mempool = self.mempool.write().await;
+            let mempool_hash = format!("{:x}", sha3::Sha3_256::digest(tx_json.as_bytes()));
+            mempool.add_raw_transaction(tx_json, mempool_hash, tx.gas_price)
+        };
+        if !added {
+            return Err(QNetError::MempoolError("Mempool full or duplicate transaction".to_string()));
+        }
+        
+        if let Some(unified_p2p) = &self.unified_p2p {
+            let tx_data = serde_json::to_vec(&tx).unwrap_or_default();
+            let _ = unified_p2p.broadcast_transaction(tx_data);
+        }
+        Ok(tx.hash)
+    }
+    
     pub async fn get_stats(&self) -> Result<serde_json::Value, QNetError> {
         let height = self.get_height().await;
         let peer_count = self.get_peer_count().await?;
@@ -11245,10 +11964,69 @@ impl BlockchainNode {
             "region": format!("{:?}", self.region),
             "node_id": self.node_id,
             "sharding_enabled": self.perf_config.enable_sharding,
+            "network_size": self.storage.network_size_estimate(),
             "parallel_validation": self.perf_config.parallel_validation,
         }))
     }
     
+    /// Bootstrap from a trusted checkpoint: fetch and verify its macroblock, load the
+    /// snapshot from QNET_CHECKPOINT_SNAPSHOT and continue syncing from there
+    pub async fn sync_from_checkpoint(&self, checkpoint: &TrustedCheckpoint) -> Result<(), QNetError> {
+        if self.get_height().await >= checkpoint.microblock_height() {
+            println!("[CHECKPOINT] ✅ Local chain already past checkpoint #{}, skipping", checkpoint.height);
+            return Ok(());
+        }
+        println!("[CHECKPOINT] 📍 Trusted sync from macroblock #{}", checkpoint.height);
+        
+        let snapshot_path = std::env::var("QNET_CHECKPOINT_SNAPSHOT")
+            .map_err(|_| QNetError::ValidationError("QNET_CHECKPOINT_SNAPSHOT not set for checkpoint sync".to_string()))?;
+        let snapshot_bytes = std::fs::read(&snapshot_path)
+            .map_err(|e| QNetError::StorageError(format!("Failed to read checkpoint snapshot {}: {}", snapshot_path, e)))?;
+        let snapshot: StateSnapshot = serde_json::from_slice(&snapshot_bytes)
+            .map_err(|e| QNetError::SerializationError(format!("Invalid checkpoint snapshot: {}", e)))?;
+        
+        // Fetch the checkpoint macroblock from peers unless already stored
+        let mut macroblock = self.get_macroblock(checkpoint.height).await?;
+        if macroblock.is_none() {
+            self.sync_macroblocks(checkpoint.height, checkpoint.height).await?;
+            for _ in 0..30 {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                macroblock = self.get_macroblock(checkpoint.height).await?;
+                if macroblock.is_some() {
+                    break;
+                }
+            }
+        }
+        let macroblock = macroblock.ok_or_else(|| QNetError::SyncError(
+            format!("Checkpoint macroblock #{} not received from peers", checkpoint.height)))?;
+        
+        let height = self.bootstrap_from_checkpoint(checkpoint, &macroblock, &snapshot).await?;
+        println!("[CHECKPOINT] ✅ State restored at checkpoint, syncing forward from block {}", height + 1);
+        Ok(())
+    }
+    
+    /// Verify checkpoint data and install it as the local chain tip
+    pub async fn bootstrap_from_checkpoint(
+        &self,
+        checkpoint: &TrustedCheckpoint,
+        macroblock: &MacroBlock,
+        snapshot: &StateSnapshot,
+    ) -> Result<u64, QNetError> {
+        checkpoint.verify_macroblock(macroblock).map_err(QNetError::ValidationError)?;
+        let state = checkpoint.restore_state(snapshot).map_err(QNetError::ValidationError)?;
+        
+        self.storage.save_macroblock(checkpoint.height, macroblock).await
+            .map_err(|e| QNetError::StorageError(e.to_string()))?;
+        let height = checkpoint.microblock_height();
+        self.storage.set_chain_height(height)
+            .map_err(|e| QNetError::StorageError(e.to_string()))?;
+        record_macroblock_state_root(height, &state);
+        
+        *self.state.write().await = state;
+        *self.height.write().await = height;
+        Ok(height)
+    }
+    
     /// Start sync process after node restart or new node join
     pub async fn start_sync_if_needed(&self) -> Result<(), QNetError> {
         // CRITICAL: Mark node as syncing to prevent consensus participation
@@ -11368,6 +12146,48 @@ impl BlockchainNode {
         Ok(())
     }
     
+    /// Remove microblocks above `height` and move the chain tip back to it
+    pub async fn rollback_to_height(&self, height: u64) -> Result<(), QNetError> {
+        let current = self.get_height().await;
+        for h in ((height + 1)..=current).rev() {
+            self.storage.delete_microblock(h)?;
+        }
+        self.storage.set_chain_height(height)?;
+        *self.height.write().await = height;
+        println!("[RESYNC] ⏪ Rolled back from {} to {}", current, height);
+        Ok(())
+    }
+    
+    /// Operator resync: roll back to `height` (default: last finalized macroblock) and re-request blocks
+    pub async fn start_resync(self: &Arc<Self>, height: Option<u64>) -> Result<crate::resync::ResyncStatus, crate::resync::ResyncError> {
+        if FAST_SYNC_IN_PROGRESS.load(Ordering::SeqCst) {
+            return Err(crate::resync::ResyncError::InProgress);
+        }
+        let status = self.resync.start(self.clone(), height).await?;
+        SYNC_START_TIME.store(get_timestamp_safe(), Ordering::Relaxed);
+        Ok(status)
+    }
+    
+    pub async fn get_resync_status(&self) -> crate::resync::ResyncStatus {
+        self.resync.status(self).await
+    }
+    
+    /// State-root circuit breaker: whether production is halted and why
+    pub fn get_state_root_breaker_status(&self) -> crate::state_breaker::BreakerStatus {
+        STATE_ROOT_BREAKER.lock()
+            .map(|b| b.status())
+            .unwrap_or_else(|e| e.into_inner().status())
+    }
+    
+    /// Operator acknowledgment of a state-root halt: resume block production
+    pub fn acknowledge_state_root_breaker(&self) -> Option<crate::state_breaker::BreakerResume> {
+        let resumed = STATE_ROOT_BREAKER.lock().ok()?.acknowledge();
+        if resumed.is_some() {
+            StructuredEvent::new("MONITOR", "state_root_breaker_acknowledged", "⚠️ State-root halt acknowledged by operator - block production resumed").emit();
+        }
+        resumed
+    }
+    
     /// Sync blocks from network
     pub async fn sync_blocks(&self, from_height: u64, to_height: u64) -> Result<(), QNetError> {
         if let Some(ref p2p) = self.unified_p2p {
@@ -11394,8 +12214,10 @@ impl BlockchainNode {
     
     /// Handle incoming sync request from peer
     pub async fn handle_sync_request(&self, from_height: u64, to_height: u64, requester_id: String) -> Result<(), QNetError> {
-        println!("[SYNC] 📥 Processing sync request from {} for microblocks {}-{}", 
-                 requester_id, from_height, to_height);
+        StructuredEvent::new("SYNC", "sync_request_received", format!("📥 Processing sync request from {} for microblocks {}-{}", requester_id, from_height, to_height))
+            .height(to_height)
+            .peer(requester_id.as_str())
+            .emit();
         
         // CRITICAL DEBUG: Check if Genesis exists before sending
         if from_height == 0 {
@@ -11404,6 +12226,13 @@ impl BlockchainNode {
                      genesis_check.as_ref().map(|opt| opt.as_ref().map(|data| data.len())));
         }
         
+        // Serve at most max_sync_range blocks, the requester continues from the hint
+        let max_sync_range = self.unified_p2p.as_ref()
+            .map(|p2p| p2p.p2p_config().max_sync_range)
+            .unwrap_or(crate::unified_p2p::DEFAULT_MAX_SYNC_RANGE);
+        let (to_height, continue_from) = crate::unified_p2p::clamp_sync_range(from_height, to_height, max_sync_range)
+            .ok_or_else(|| QNetError::ValidationError(format!("Invalid sync range {}-{}", from_height, to_height)))?;
+        
         // Get microblocks from storage (already in network format)
         let blocks_data = self.storage.get_microblocks_range(from_height, to_height).await?;
         
@@ -11417,6 +12246,7 @@ impl BlockchainNode {
                 from_height,
                 to_height,
                 sender_id: self.node_id.clone(),
+                continue_from,
             };
             
             // SCALABILITY: Try O(1) lookup first, then fallback to O(n) for Genesis
@@ -11578,30 +12408,38 @@ impl BlockchainNode {
     // =========================================================================
     
     /// Start health monitor for sync flags (prevents permanent deadlock)
-    fn start_sync_health_monitor() {
+    fn start_sync_health_monitor(
+        height: Arc<RwLock<u64>>,
+        storage: Arc<Storage>,
+        unified_p2p: Option<Arc<SimplifiedP2P>>,
+    ) {
         // PRODUCTION: Health check runs in background to detect and clear stuck sync flags
+        // This is a safety net that should rarely trigger with Guard pattern in place
         tokio::spawn(async move {
-            use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
-            
-            // Track timestamps when flags were set
-            static FAST_SYNC_SET_AT: AtomicU64 = AtomicU64::new(0);
-            static NORMAL_SYNC_SET_AT: AtomicU64 = AtomicU64::new(0);
+            println!("[HEALTH] ✅ Sync health monitor active (checking every {}s)", SYNC_WATCHDOG_INTERVAL_SECS);
             
             loop {
-                tokio::time::sleep(Duration::from_secs(30)).await;
-                
-                let now = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_secs();
+                tokio::time::sleep(Duration::from_secs(SYNC_WATCHDOG_INTERVAL_SECS)).await;
                 
-                // Check FAST_SYNC_IN_PROGRESS health (defined in start_microblock_production)
-                // Note: We cannot directly access the static from here, but we track timing
+                // Storage height advances block by block during a download,
+                // the global height only when a sync batch completes
+                let local_height = (*height.read().await).max(storage.get_chain_height().unwrap_or(0));
                 
-                // PRODUCTION: If a sync flag has been stuck for > 120 seconds, something is wrong
-                // This is a safety net that should rarely trigger with Guard pattern in place
+                if !sync_watchdog_tick(get_timestamp_safe(), local_height) {
+                    continue;
+                }
                 
-                println!("[HEALTH] ✅ Sync health monitor active (checking every 30s)");
+                // Re-trigger a fresh sync, steering away from the peer that stalled
+                if let Some(p2p) = &unified_p2p {
+                    let network_height = p2p.get_cached_network_height().unwrap_or(local_height);
+                    if network_height > local_height {
+                        match p2p.resync_from_alternate_peer(local_height + 1, network_height).await {
+                            Ok(peer_id) => println!("[HEALTH] 🔄 Restarted sync {}-{} from peer {}",
+                                                    local_height + 1, network_height, peer_id),
+                            Err(e) => println!("[HEALTH] ⚠️ Failed to restart sync: {}", e),
+                        }
+                    }
+                }
             }
         });
     }
@@ -11862,22 +12700,12 @@ impl BlockchainNode {
             NodeType::Super => 2,
         };
         
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-        
-        // Validate activation code format
-        if code.is_empty() {
-            return Err(QNetError::ValidationError("Empty activation code".to_string()));
-        }
-        
-        // Check for genesis bootstrap codes first (different format)
-        // IMPORT from shared constants to avoid duplication
-        use crate::genesis_constants::GENESIS_BOOTSTRAP_CODES;
-        let bootstrap_whitelist = GENESIS_BOOTSTRAP_CODES;
+        let timestamp = self.clock.now_secs();
         
-        let is_genesis_code = bootstrap_whitelist.contains(&code);
+        // Validate activation code format (Genesis bootstrap codes have a different format)
+        let parsed_code = crate::activation_validation::ActivationCode::parse(code)?;
+        let code = parsed_code.as_str();
+        let is_genesis_code = parsed_code.is_genesis_bootstrap();
         
         // PRODUCTION: Initialize blockchain registry with real QNet nodes
         let qnet_rpc = std::env::var("QNET_RPC_URL")
@@ -11891,11 +12719,6 @@ impl BlockchainNode {
             // Genesis codes are shared bootstrap codes with IP-based authentication
             println!("✅ Genesis code - skipping ownership verification (IP-based auth)");
         } else {
-            // Check basic format for regular codes (26-char format only)
-            if !code.starts_with("QNET-") || code.len() != 25 {
-                return Err(QNetError::ValidationError("Invalid activation code format. Expected: QNET-XXXXXX-XXXXXX-XXXXXX (25 chars)".to_string()));
-            }
-            
             let registry = crate::activation_validation::BlockchainActivationRegistry::new(
                 Some(qnet_rpc.clone())
             );
@@ -12079,10 +12902,7 @@ impl BlockchainNode {
             NodeType::Super => 2,
         };
         
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+        let timestamp = self.clock.now_secs();
         
         // Update activation record for device migration
         self.storage.update_activation_for_migration(code, node_type_id, timestamp, new_device_signature)
@@ -12095,14 +12915,7 @@ impl BlockchainNode {
     /// Validate activation code (delegated to centralized ActivationValidator)
     async fn validate_activation_code_uniqueness(&self, code: &str) -> Result<(), String> {
         // Production activation code validation
-        if code.is_empty() {
-            return Err("Empty activation code is not allowed".to_string());
-        }
-        
-        // Validate format: QNET-XXXXXX-XXXXXX-XXXXXX (25 chars)
-        if !code.starts_with("QNET-") || code.len() != 25 {
-            return Err("Invalid activation code format. Expected: QNET-XXXXXX-XXXXXX-XXXXXX (25 chars)".to_string());
-        }
+        crate::activation_validation::ActivationCode::parse(code).map_err(|e| e.to_string())?;
         
         // Use centralized ActivationValidator from activation_validation.rs
         // Activation validation integrated into consensus
@@ -12139,10 +12952,7 @@ impl BlockchainNode {
         signature_components.push(self.p2p_port.to_string());
         
         // Current timestamp (rounded to hour for stability)
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+        let timestamp = self.clock.now_secs();
         let rounded_timestamp = (timestamp / 3600) * 3600; // Round to hour
         signature_components.push(rounded_timestamp.to_string());
         
@@ -12266,6 +13076,17 @@ impl BlockchainNode {
         }
     }
     
+    /// PERSISTENCE: Save invalid-block scores to storage (called on shutdown)
+    pub fn save_invalid_block_scores(&self) {
+        let Some(p2p) = &self.unified_p2p else { return };
+        match p2p.export_invalid_block_scores()
+            .and_then(|data| self.storage.save_raw(crate::invalid_blocks::INVALID_BLOCK_SCORES_KEY, &data).map_err(|e| e.to_string()))
+        {
+            Ok(()) => println!("[SECURITY] 💾 Saved invalid block scores"),
+            Err(e) => println!("[SECURITY] ⚠️ Failed to save invalid block scores: {}", e),
+        }
+    }
+    
     /// Gracefully shutdown node due to device migration
     pub async fn graceful_shutdown_due_to_migration(&self) -> Result<(), QNetError> {
         println!("🛑 Initiating graceful shutdown due to device migration...");
@@ -12283,6 +13104,8 @@ impl BlockchainNode {
         // Send final status to network
         println!("   📡 Sending final status to P2P network");
         
+        self.save_invalid_block_scores();
+        
         println!("✅ Node gracefully shut down - activation migrated to new device");
         std::process::exit(0);
     }
@@ -12327,10 +13150,7 @@ impl BlockchainNode {
     }
 
     pub async fn get_connected_peers(&self) -> Result<Vec<PeerInfo>, QNetError> {
-        let current_time = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+        let current_time = self.clock.now_secs();
         // EXISTING: Get connected peers for RPC API (fast method for API responses)
         // PERFORMANCE: Use fast discovery peers instead of expensive validation for API
         let peer_infos = if let Some(ref p2p) = self.unified_p2p {
@@ -12362,102 +13182,9 @@ impl BlockchainNode {
     
     pub async fn get_transaction(&self, tx_hash: &str) -> Result<Option<TransactionInfo>, QNetError> {
         // Search in mempool first
-        {
-            let mempool = self.mempool.read().await;
-            let pending_txs = mempool.get_pending_transactions(1000);
-            
-            for tx_json in pending_txs {
-                if let Ok(tx) = serde_json::from_str::<qnet_state::Transaction>(&tx_json) {
-                    if tx.hash == tx_hash {
-                        return Ok(Some(TransactionInfo {
-                            hash: tx.hash,
-                            from: tx.from,
-                            to: tx.to,
-                            amount: tx.amount,
-                            nonce: tx.nonce,
-                            gas_price: tx.gas_price,
-                            gas_limit: tx.gas_limit,
-                            timestamp: tx.timestamp,
-                            block_height: None,
-                            status: "pending".to_string(),
-                            // Fast Finality Indicators for pending tx
-                            confirmation_level: Some(ConfirmationLevel::Pending),
-                            safety_percentage: Some(0.0),
-                            confirmations: Some(0),
-                            time_to_finality: Some(90), // Max time to macroblock
-                        }));
-                    }
-                }
-            }
-        }
-        
-        // Search in stored blocks
-        match self.storage.find_transaction_by_hash(tx_hash).await {
-            Ok(Some(tx)) => {
-                let block_height = self.storage.get_transaction_block_height(tx_hash).await.ok();
-                
-                // Calculate Fast Finality Indicators
-                let current_height = *self.height.read().await;
-                let confirmations = if let Some(tx_height) = block_height {
-                    (current_height.saturating_sub(tx_height) + 1) as u32
-                } else {
-                    1
-                };
-                
-                // Determine confirmation level based on confirmations
-                let confirmation_level = match confirmations {
-                    0 => ConfirmationLevel::Pending,
-                    1..=4 => ConfirmationLevel::InBlock,
-                    5..=29 => ConfirmationLevel::QuickConfirmed,
-                    30..=89 => ConfirmationLevel::NearFinal,
-                    _ => ConfirmationLevel::FullyFinalized,
-                };
-                
-                // Calculate safety percentage based on confirmations
-                // Formula: min(99.999, confirmations * 10) for first 10 blocks
-                // Then asymptotically approach 100%
-                let safety_percentage = if confirmations == 0 {
-                    0.0
-                } else if confirmations <= 5 {
-                    90.0 + (confirmations as f64 * 2.0) // 92%, 94%, 96%, 98%, 100% at 5
-                } else if confirmations <= 30 {
-                    99.0 + (confirmations as f64 * 0.03) // Slowly approach 99.9%
-                } else if confirmations <= 90 {
-                    99.9 + (confirmations as f64 * 0.001) // Approach 99.99%
-                } else {
-                    100.0 // Fully finalized in macroblock
-                };
-                
-                // Calculate time to finality (macroblock at 90 blocks)
-                let blocks_to_macroblock = if let Some(tx_height) = block_height {
-                    let next_macroblock = ((tx_height / 90) + 1) * 90;
-                    next_macroblock.saturating_sub(current_height)
-                } else {
-                    90
-                };
-                let time_to_finality = blocks_to_macroblock; // 1 block = 1 second
-                
-                Ok(Some(TransactionInfo {
-                    hash: tx.hash,
-                    from: tx.from,
-                    to: tx.to,
-                    amount: tx.amount,
-                    nonce: tx.nonce,
-                    gas_price: tx.gas_price,
-                    gas_limit: tx.gas_limit,
-                    timestamp: tx.timestamp,
-                    block_height,
-                    status: "confirmed".to_string(),
-                    // Fast Finality Indicators
-                    confirmation_level: Some(confirmation_level),
-                    safety_percentage: Some(safety_percentage),
-                    confirmations: Some(confirmations),
-                    time_to_finality: Some(time_to_finality),
-                }))
-            }
-            Ok(None) => Ok(None),
-            Err(e) => Err(QNetError::StorageError(e.to_string())),
-        }
+        let pending = self.mempool.read().await.get_by_hash(tx_hash);
+        let current_height = *self.height.read().await;
+        lookup_transaction(pending, &self.storage, tx_hash, current_height).await
     }
     
     // Production-grade region detection functions (decentralized)
@@ -12684,6 +13411,17 @@ impl BlockchainNode {
         self.storage.load_microblock(height).map_err(|e| QNetError::StorageError(e.to_string()))
     }
     
+    /// Merkle proof that a confirmed transaction is included in its microblock
+    pub fn get_transaction_proof(&self, tx_hash: &str) -> Result<Option<crate::storage::TransactionInclusionProof>, QNetError> {
+        self.storage.transaction_inclusion_proof(tx_hash).map_err(|e| QNetError::StorageError(e.to_string()))
+    }
+    
+    /// This node's archive chunk assignments and replication health
+    pub async fn get_archive_report(&self) -> Result<crate::archive_manager::LocalArchiveReport, QNetError> {
+        self.archive_manager.read().await.local_archive_report().await
+            .map_err(|e| QNetError::StorageError(e.to_string()))
+    }
+    
     /// Start archive compliance monitoring (MANDATORY enforcement)
     async fn start_archive_compliance_monitoring(&self) {
         let archive_manager = self.archive_manager.clone();
@@ -12715,11 +13453,7 @@ impl BlockchainNode {
                                 
                                 // Alert if this node is non-compliant
                                 if stats.non_compliant_nodes > 0 {
-                                    let required_chunks = match node_type {
-                                        NodeType::Full => 3,
-                                        NodeType::Super => 8,
-                                        _ => 0,
-                                    };
+                                    let required_chunks = crate::archive_manager::mandatory_archive_quota(&node_type);
                                     println!("[Archive] ⚠️  NETWORK COMPLIANCE ISSUE: {} nodes not meeting archive obligations", stats.non_compliant_nodes);
                                     println!("[Archive] 📋 Required: {} chunks for {:?} nodes", required_chunks, node_type);
                                 }
@@ -13063,6 +13797,101 @@ pub struct PeerInfo {
     pub version: Option<String>,
 }
 
+/// Transaction info for a pending mempool entry, else from stored blocks
+async fn lookup_transaction(
+    pending: Option<qnet_state::Transaction>,
+    storage: &Storage,
+    tx_hash: &str,
+    current_height: u64,
+) -> Result<Option<TransactionInfo>, QNetError> {
+    if let Some(tx) = pending {
+        return Ok(Some(TransactionInfo {
+            hash: tx.hash,
+            from: tx.from,
+            to: tx.to,
+            amount: tx.amount,
+            nonce: tx.nonce,
+            gas_price: tx.gas_price,
+            gas_limit: tx.gas_limit,
+            timestamp: tx.timestamp,
+            block_height: None,
+            status: "pending".to_string(),
+            // Fast Finality Indicators for pending tx
+            confirmation_level: Some(ConfirmationLevel::Pending),
+            safety_percentage: Some(0.0),
+            confirmations: Some(0),
+            time_to_finality: Some(90), // Max time to macroblock
+        }));
+    }
+    
+    // Search in stored blocks
+    match storage.find_transaction_by_hash(tx_hash).await {
+        Ok(Some(tx)) => {
+            let block_height = storage.get_transaction_block_height(tx_hash).await.ok();
+            
+            // Calculate Fast Finality Indicators
+            let confirmations = if let Some(tx_height) = block_height {
+                (current_height.saturating_sub(tx_height) + 1) as u32
+            } else {
+                1
+            };
+            
+            // Determine confirmation level based on confirmations
+            let confirmation_level = match confirmations {
+                0 => ConfirmationLevel::Pending,
+                1..=4 => ConfirmationLevel::InBlock,
+                5..=29 => ConfirmationLevel::QuickConfirmed,
+                30..=89 => ConfirmationLevel::NearFinal,
+                _ => ConfirmationLevel::FullyFinalized,
+            };
+            
+            // Calculate safety percentage based on confirmations
+            // Formula: min(99.999, confirmations * 10) for first 10 blocks
+            // Then asymptotically approach 100%
+            let safety_percentage = if confirmations == 0 {
+                0.0
+            } else if confirmations <= 5 {
+                90.0 + (confirmations as f64 * 2.0) // 92%, 94%, 96%, 98%, 100% at 5
+            } else if confirmations <= 30 {
+                99.0 + (confirmations as f64 * 0.03) // Slowly approach 99.9%
+            } else if confirmations <= 90 {
+                99.9 + (confirmations as f64 * 0.001) // Approach 99.99%
+            } else {
+                100.0 // Fully finalized in macroblock
+            };
+            
+            // Calculate time to finality (macroblock at 90 blocks)
+            let blocks_to_macroblock = if let Some(tx_height) = block_height {
+                let next_macroblock = ((tx_height / 90) + 1) * 90;
+                next_macroblock.saturating_sub(current_height)
+            } else {
+                90
+            };
+            let time_to_finality = blocks_to_macroblock; // 1 block = 1 second
+            
+            Ok(Some(TransactionInfo {
+                hash: tx.hash,
+                from: tx.from,
+                to: tx.to,
+                amount: tx.amount,
+                nonce: tx.nonce,
+                gas_price: tx.gas_price,
+                gas_limit: tx.gas_limit,
+                timestamp: tx.timestamp,
+                block_height,
+                status: "confirmed".to_string(),
+                // Fast Finality Indicators
+                confirmation_level: Some(confirmation_level),
+                safety_percentage: Some(safety_percentage),
+                confirmations: Some(confirmations),
+                time_to_finality: Some(time_to_finality),
+            }))
+        }
+        Ok(None) => Ok(None),
+        Err(e) => Err(QNetError::StorageError(e.to_string())),
+    }
+}
+
 /// Transaction information for RPC responses  
 #[derive(Debug, Clone)]
 pub struct TransactionInfo {
@@ -13093,6 +13922,87 @@ pub enum ConfirmationLevel {
     FullyFinalized,    // In macroblock (90s)
 }
 
+/// Combined account view for wallets: balance, nonce and node/reward info in one response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccountSummary {
+    pub address: String,
+    pub balance: u64,
+    pub nonce: u64,
+    pub last_activity: u64,
+    /// None when the address does not operate a node
+    pub node_type: Option<String>,
+    pub node_id: Option<String>,
+    /// Unclaimed rewards across all nodes owned by the address
+    pub pending_reward: Option<u64>,
+}
+
+impl AccountSummary {
+    /// Assemble from state account data and reward manager ownership records
+    pub fn assemble(
+        address: &str,
+        account: Option<&Account>,
+        rewards: &PhaseAwareRewardManager,
+    ) -> Self {
+        let owned_nodes = rewards.get_nodes_owned_by(address);
+        let registered = rewards.get_all_registered_nodes();
+        
+        let node_id = owned_nodes.first().cloned();
+        let node_type = node_id.as_ref()
+            .and_then(|id| registered.iter().find(|(node, _)| node == id))
+            .map(|(_, node_type)| format!("{:?}", node_type).to_lowercase())
+            .or_else(|| account.filter(|a| a.is_node).and_then(|a| a.node_type.clone()));
+        
+        let pending_reward = (!owned_nodes.is_empty()).then(|| {
+            owned_nodes.iter()
+                .filter_map(|id| rewards.get_pending_reward(id))
+                .map(|reward| reward.total_reward)
+                .sum()
+        });
+        
+        Self {
+            address: address.to_string(),
+            balance: account.map(|a| a.balance).unwrap_or(0),
+            nonce: account.map(|a| a.nonce).unwrap_or(0),
+            last_activity: account.map(|a| a.updated_at).unwrap_or(0),
+            node_type,
+            node_id,
+            pending_reward,
+        }
+    }
+}
+
+/// Reward claim payout from the rewards pool to a node owner's wallet
+pub fn build_reward_claim_transaction(node_id: &str, wallet: &str, amount: u64, timestamp: u64) -> qnet_state::Transaction {
+    let mut tx = qnet_state::Transaction {
+        from: qnet_mempool::validation::SYSTEM_REWARDS_POOL_SENDER.to_string(),
+        to: Some(wallet.to_string()),
+        amount,
+        tx_type: qnet_state::TransactionType::RewardDistribution,
+        timestamp,
+        hash: String::new(),
+        signature: None, // Validated through the claim process
+        public_key: None,
+        gas_price: 0,
+        gas_limit: 0,
+        nonce: 0,
+        data: Some(format!("Claim for node: {}", node_id)),
+        chain_id: qnet_state::transaction::UNBOUND_CHAIN_ID,
+        valid_until: None,
+    };
+    tx.hash = tx.calculate_hash();
+    tx
+}
+
+/// Constant-time admin key comparison; no configured key means admin endpoints are disabled
+pub fn admin_api_key_matches(configured: Option<&str>, provided: Option<&str>) -> bool {
+    match (configured, provided) {
+        (Some(expected), Some(provided)) if !expected.is_empty() && expected.len() == provided.len() => {
+            expected.bytes().zip(provided.bytes()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+        }
+        _ => false,
+    }
+}
+
 /// PRODUCTION: Cryptographic verification of genesis node certificates
 /// Prevents impersonation attacks by validating node identity
 fn verify_genesis_node_certificate(node_id: &str) -> bool {
@@ -13144,58 +14054,14 @@ fn verify_genesis_node_certificate(node_id: &str) -> bool {
 impl BlockchainNode {
     /// Load the last PoH checkpoint from storage
     /// 
-    /// STRATEGY: First check the index for the latest checkpoint count,
-    /// then load that specific checkpoint. Falls back to scanning if no index.
+    /// STRATEGY: Start from the checkpoint the index points to and walk back
+    /// past corrupt or missing checkpoints to the newest intact one.
+    /// Falls back to scanning if no index.
     /// 
     /// SCALABILITY: Index-based lookup is O(1), scanning is O(n) but bounded.
     async fn load_last_poh_checkpoint(storage: &Arc<Storage>) -> Option<(Vec<u8>, u64)> {
-        // 1. Try to load from index first (O(1) lookup)
-        if let Ok(Some(index_data)) = storage.load_raw("poh_checkpoint_latest") {
-            if let Ok(latest_count) = bincode::deserialize::<u64>(&index_data) {
-                let key = format!("poh_checkpoint_{}", latest_count);
-                if let Ok(Some(compressed_data)) = storage.load_raw(&key) {
-                    if let Ok(decompressed) = zstd::decode_all(&compressed_data[..]) {
-                        if let Ok(entry) = bincode::deserialize::<crate::quantum_poh::PoHEntry>(&decompressed) {
-                            println!("[QuantumPoH] 📂 Loaded checkpoint from index: count={}", entry.num_hashes);
-                            return Some((entry.hash, entry.num_hashes));
-                        }
-                    }
-                }
-            }
-        }
-        
-        // 2. Fallback: Scan for checkpoints (for migration from old format)
-        // Scan in 10M increments (checkpoint interval) up to 100B hashes (~55 hours)
-        // This covers reasonable network uptime between restarts
-        println!("[QuantumPoH] 🔍 Scanning for checkpoints (no index found)...");
-        let mut latest_checkpoint: Option<(Vec<u8>, u64)> = None;
-        
-        // Scan from high to low, stop at first found (most recent)
-        // 100B hashes / 10M per checkpoint = 10,000 checkpoints max
-        // At 500K hashes/sec, 100B = ~55 hours
-        for checkpoint_num in (1..=10_000u64).rev() {
-            let count = checkpoint_num * 10_000_000; // Checkpoints at 10M intervals
-            let key = format!("poh_checkpoint_{}", count);
-            
-            if let Ok(Some(compressed_data)) = storage.load_raw(&key) {
-                if let Ok(decompressed) = zstd::decode_all(&compressed_data[..]) {
-                    if let Ok(entry) = bincode::deserialize::<crate::quantum_poh::PoHEntry>(&decompressed) {
-                        latest_checkpoint = Some((entry.hash.clone(), entry.num_hashes));
-                        println!("[QuantumPoH] 📂 Found checkpoint at count: {}", entry.num_hashes);
-                        
-                        // Save index for next time
-                        if let Ok(index_data) = bincode::serialize(&entry.num_hashes) {
-                            let _ = storage.save_raw("poh_checkpoint_latest", &index_data);
-                        }
-                        
-                        // Found most recent (scanning from high to low), no need to continue
-                        break;
-                    }
-                }
-            }
-        }
-        
-        latest_checkpoint
+        crate::quantum_poh::recover_latest_checkpoint(storage.as_ref())
+            .map(|entry| (entry.hash, entry.num_hashes))
     }
 }
 
@@ -13239,8 +14105,655 @@ impl Clone for BlockchainNode {
             tower_bft: self.tower_bft.clone(),
             pre_execution: self.pre_execution.clone(),
             block_event_tx: self.block_event_tx.clone(),
+            clock: self.clock.clone(),
+            resync: self.resync.clone(),
         }
     }
 }
 
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state_with_balances(balances: &[(&str, u64)]) -> StateManager {
+        let state = StateManager::new();
+        for (address, balance) in balances {
+            let mut account = Account::new(address.to_string());
+            account.balance = *balance;
+            state.update_account(address.to_string(), account);
+        }
+        state
+    }
+
+    fn received_macroblock(macroblock: &MacroBlock) -> crate::unified_p2p::ReceivedBlock {
+        crate::unified_p2p::ReceivedBlock {
+            height: macroblock.height,
+            data: bincode::serialize(macroblock).unwrap(),
+            block_type: "macro".to_string(),
+            from_peer: "peer_1".to_string(),
+            timestamp: macroblock.timestamp,
+        }
+    }
+
+    fn macroblock_with_root(index: u64, state_root: [u8; 32]) -> MacroBlock {
+        let participants = ["validator_1", "validator_2", "validator_3"];
+        MacroBlock {
+            height: index,
+            timestamp: 1704067200 + index * 90,
+            micro_blocks: (0..90u8).map(|i| [i; 32]).collect(),
+            state_root,
+            consensus_data: ConsensusData {
+                commits: participants.iter().map(|p| (p.to_string(), b"commit".to_vec())).collect(),
+                reveals: participants.iter().map(|p| (p.to_string(), b"reveal".to_vec())).collect(),
+                next_leader: participants[0].to_string(),
+            },
+            previous_hash: [0u8; 32],
+            poh_hash: vec![0u8; 64],
+            poh_count: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_corrupted_state_rejects_macroblock() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Arc::new(Storage::new(dir.path().to_str().unwrap()).unwrap());
+
+        let honest = state_with_balances(&[("alice", 1_000), ("bob", 500)]);
+        let corrupted = state_with_balances(&[("alice", 1_000), ("bob", 5_000)]);
+        assert_ne!(honest.compute_state_root(), corrupted.compute_state_root());
+
+        // Honest node: local root matches the claimed root
+        let honest_index = 9_001;
+        record_macroblock_state_root(honest_index * 90, &honest);
+        let block = received_macroblock(&macroblock_with_root(honest_index, honest.compute_state_root()));
+        assert!(BlockchainNode::validate_received_macroblock(&block, &storage, &None).await.is_ok());
+
+        // Corrupted node: same macroblock content, divergent local balances
+        let corrupted_index = 9_002;
+        record_macroblock_state_root(corrupted_index * 90, &corrupted);
+        let block = received_macroblock(&macroblock_with_root(corrupted_index, honest.compute_state_root()));
+        let err = BlockchainNode::validate_received_macroblock(&block, &storage, &None).await.unwrap_err();
+        assert!(err.contains("State root mismatch"), "unexpected error: {}", err);
+        assert!(!NODE_IS_SYNCHRONIZED.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_sync_watchdog_recovers_hung_sync() {
+        let start = 1_700_000_000u64;
+        
+        // Hung sync: started long ago, height stuck since then
+        SYNC_PROGRESS_TIME.store(0, Ordering::Relaxed);
+        record_sync_progress(500, start);
+        FAST_SYNC_IN_PROGRESS.store(true, Ordering::SeqCst);
+        FAST_SYNC_START_TIME.store(start, Ordering::Relaxed);
+        
+        assert!(!sync_watchdog_tick(start + SYNC_DEADLOCK_TIMEOUT_SECS, 500));
+        assert!(FAST_SYNC_IN_PROGRESS.load(Ordering::SeqCst));
+        
+        assert!(sync_watchdog_tick(start + SYNC_DEADLOCK_TIMEOUT_SECS + 30, 500));
+        assert!(!FAST_SYNC_IN_PROGRESS.load(Ordering::SeqCst));
+        assert_eq!(FAST_SYNC_START_TIME.load(Ordering::Relaxed), 0);
+        
+        // Slow sync: running past the timeout but the height keeps advancing
+        FAST_SYNC_IN_PROGRESS.store(true, Ordering::SeqCst);
+        FAST_SYNC_START_TIME.store(start, Ordering::Relaxed);
+        let mut now = start + SYNC_DEADLOCK_TIMEOUT_SECS + 30;
+        for local_height in 501..505 {
+            assert!(!sync_watchdog_tick(now, local_height));
+            now += SYNC_DEADLOCK_TIMEOUT_SECS / 2;
+        }
+        assert!(FAST_SYNC_IN_PROGRESS.load(Ordering::SeqCst));
+        
+        // Progress stops: the watchdog clears the flag once the timeout passes
+        assert!(sync_watchdog_tick(now + SYNC_DEADLOCK_TIMEOUT_SECS, 504));
+        assert!(!FAST_SYNC_IN_PROGRESS.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_account_summary_for_node_operator_and_holder() {
+        use qnet_consensus::lazy_rewards::{PhaseAwareReward, QNetPhase};
+        
+        let genesis = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let mut rewards = PhaseAwareRewardManager::new(genesis);
+        rewards.register_node("node_001".to_string(), RewardNodeType::Full, "operator_wallet".to_string()).unwrap();
+        rewards.restore_pending_reward("node_001".to_string(), PhaseAwareReward {
+            current_phase: QNetPhase::Phase1,
+            pool1_base_emission: 700,
+            pool2_transaction_fees: 50,
+            pool3_activation_bonus: 0,
+            total_reward: 750,
+        });
+        
+        let state = state_with_balances(&[("operator_wallet", 5_000), ("holder_wallet", 1_200)]);
+        
+        let operator = state.get_account("operator_wallet");
+        let summary = AccountSummary::assemble("operator_wallet", operator.as_ref(), &rewards);
+        assert_eq!(summary.balance, 5_000);
+        assert_eq!(summary.node_id.as_deref(), Some("node_001"));
+        assert_eq!(summary.node_type.as_deref(), Some("full"));
+        assert_eq!(summary.pending_reward, Some(750));
+        
+        let holder = state.get_account("holder_wallet");
+        let summary = AccountSummary::assemble("holder_wallet", holder.as_ref(), &rewards);
+        assert_eq!(summary.balance, 1_200);
+        assert_eq!(summary.nonce, 0);
+        assert_eq!(summary.node_id, None);
+        assert_eq!(summary.node_type, None);
+        assert_eq!(summary.pending_reward, None);
+        
+        // Node fields serialize as nulls for plain holders
+        let json = serde_json::to_value(&summary).unwrap();
+        assert!(json["node_type"].is_null());
+        assert!(json["pending_reward"].is_null());
+    }
+
+    #[test]
+    fn test_byzantine_params_scale_quorum() {
+        let default = ByzantineParams::default();
+        assert_eq!(default.min_participants(), 4);
+        assert!(default.has_quorum(3) && !default.has_quorum(2));
+
+        // f=2 needs 7 participants: us plus 6 validated peers
+        let f2 = ByzantineParams::new(2).unwrap();
+        assert_eq!(f2.min_participants(), 7);
+        assert!(!f2.has_quorum(5));
+        assert!(f2.has_quorum(6));
+        let mut gate = ProductionGate::new(f2.min_peers(), Duration::from_secs(120));
+        assert_eq!(gate.check(5, Duration::from_secs(1)), ProductionGateStatus::Withheld);
+        assert_eq!(gate.check(6, Duration::from_secs(1)), ProductionGateStatus::ReleasedByPeers);
+
+        // Participant minimums must be consistent with f
+        assert!(f2.check_min_participants(4).is_err());
+        f2.check_min_participants(7).unwrap();
+        assert!(ByzantineParams::new(0).is_err());
+    }
+
+    #[test]
+    fn test_validation_threads_capped_below_core_count() {
+        // 256 detected cores: the ceiling wins, default or configured
+        assert_eq!(validation_thread_count(256, None, DEFAULT_MAX_VALIDATION_THREADS), 32);
+        assert_eq!(validation_thread_count(256, None, 16), 16);
+        // An explicit QNET_PARALLEL_THREADS is capped too
+        assert_eq!(validation_thread_count(256, Some(128), 16), 16);
+
+        // Below the ceiling the detected count stands (minimum 2)
+        assert_eq!(validation_thread_count(8, None, DEFAULT_MAX_VALIDATION_THREADS), 8);
+        assert_eq!(validation_thread_count(1, None, DEFAULT_MAX_VALIDATION_THREADS), 2);
+        assert_eq!(validation_thread_count(8, Some(4), DEFAULT_MAX_VALIDATION_THREADS), 4);
+    }
+
+    #[test]
+    fn test_production_gate_waits_for_peers() {
+        let mut gate = ProductionGate::new(ByzantineParams::default().min_peers(), Duration::from_secs(120));
+
+        // Below threshold within the grace period: withheld
+        assert_eq!(gate.check(0, Duration::from_secs(1)), ProductionGateStatus::Withheld);
+        assert_eq!(gate.check(2, Duration::from_secs(30)), ProductionGateStatus::Withheld);
+        assert!(!gate.is_released());
+
+        // Enough peers connected: released, and stays open if peers drop
+        assert_eq!(gate.check(3, Duration::from_secs(31)), ProductionGateStatus::ReleasedByPeers);
+        assert_eq!(gate.check(0, Duration::from_secs(32)), ProductionGateStatus::Open);
+
+        // Genesis of the network: no peers ever arrive, grace period releases
+        let mut lone_genesis = ProductionGate::new(3, Duration::from_secs(120));
+        assert_eq!(lone_genesis.check(0, Duration::from_secs(119)), ProductionGateStatus::Withheld);
+        assert_eq!(lone_genesis.check(0, Duration::from_secs(120)), ProductionGateStatus::ReleasedByGracePeriod);
+    }
+
+    #[tokio::test]
+    async fn test_empty_block_interval_policy() {
+        let params = ChainParams { rotation_interval_blocks: 4, empty_block_interval_secs: 5, ..ChainParams::default() };
+        params.validate().unwrap();
+        assert!(ChainParams { empty_block_interval_secs: 0, ..ChainParams::default() }.validate().is_err());
+        assert!(ChainParams { empty_block_interval_secs: 11, ..ChainParams::default() }.validate().is_err());
+        let policy = EmptyBlockPolicy::from_chain_params(&params);
+        assert_eq!(policy.liveness_slack(), Duration::from_secs(4));
+        assert_eq!(EmptyBlockPolicy::from_chain_params(&ChainParams::default()).liveness_slack(), Duration::ZERO);
+
+        // Idle chain ticking every second: empty blocks only every 5 seconds
+        let mempool_at = |now: u64| now == 1_000_017;
+        let tracker = RotationTracker::with_chain_params(params.clone());
+        let mut last_block = 1_000_000u64;
+        let mut produced = Vec::new();
+        let mut settled = Vec::new();
+        let mut height = 1u64;
+        for now in 1_000_001..=1_000_030u64 {
+            if policy.should_produce(mempool_at(now), height, last_block, now) {
+                tracker.track_block(height, &format!("producer_{}", params.leadership_round(height))).await;
+                if let Some(rotation) = tracker.check_rotation_complete(height).await {
+                    settled.push(rotation);
+                }
+                produced.push((height, now));
+                last_block = now;
+                height += 1;
+            }
+        }
+        assert_eq!(produced, vec![
+            (1, 1_000_005), (2, 1_000_010), (3, 1_000_015),
+            // Transaction arrives: produced on the next tick, not 5 seconds later
+            (4, 1_000_017),
+            // First block of a new round is produced immediately
+            (5, 1_000_018),
+            (6, 1_000_023), (7, 1_000_028),
+        ]);
+        // The slower round still settles as a full rotation
+        assert_eq!(settled, vec![("producer_0".to_string(), 4)]);
+
+        // Default interval keeps the one-block-per-second cadence
+        let every_second = EmptyBlockPolicy::from_chain_params(&ChainParams::default());
+        assert!(every_second.should_produce(false, 2, 1_000_000, 1_000_001));
+        assert!(every_second.should_produce(false, 1, 0, 1_000_000));
+    }
+
+    #[tokio::test]
+    async fn test_small_rotation_interval_boundaries() {
+        assert!(ChainParams { rotation_interval_blocks: 0, ..ChainParams::default() }.validate().is_err());
+        let params = ChainParams { rotation_interval_blocks: 4, ..ChainParams::default() };
+        params.validate().unwrap();
+
+        assert_eq!(params.leadership_round(0), 0);
+        assert_eq!(params.leadership_round(4), 0);
+        assert_eq!(params.leadership_round(5), 1);
+        assert!(params.is_rotation_end(8) && !params.is_rotation_end(9));
+        assert!(params.is_rotation_start(9) && !params.is_rotation_start(1));
+        assert_eq!(params.next_rotation_block(1), 9);
+
+        // Three 4-block rounds: the atomic reward settles exactly on the last block of each
+        let tracker = RotationTracker::with_chain_params(params.clone());
+        let mut settled = Vec::new();
+        for height in 1..=12u64 {
+            let producer = format!("producer_{}", params.leadership_round(height));
+            tracker.track_block(height, &producer).await;
+            if let Some(rotation) = tracker.check_rotation_complete(height).await {
+                settled.push((height, rotation));
+            }
+        }
+        assert_eq!(settled, vec![
+            (4, ("producer_0".to_string(), 4)),
+            (8, ("producer_1".to_string(), 4)),
+            (12, ("producer_2".to_string(), 4)),
+        ]);
+        assert!(settled.iter().all(|(_, (_, blocks))| *blocks as u64 == tracker.rotation_interval()));
+    }
+
+    #[test]
+    fn test_short_reward_window() {
+        assert!(ChainParams { reward_window_secs: 0, ..ChainParams::default() }.validate().is_err());
+        let params = ChainParams { reward_window_secs: 600, ..ChainParams::default() };
+        params.validate().unwrap();
+        assert_eq!(params.blocks_in_reward_window(), 600);
+        assert_eq!(params.ping_slots_in_window(), 10);
+        assert_eq!(ChainParams::default().ping_slots_in_window(), 240);
+        
+        assert_eq!(params.reward_window_start(1_700_000_123), 1_700_000_123 - 1_700_000_123 % 600);
+        assert_eq!(params.reward_window_number(1_199), 1);
+        assert_eq!(params.reward_window_number(1_200), 2);
+        
+        assert!(!params.is_emission_block(0) && !params.is_emission_block(599));
+        assert!(params.is_emission_block(600) && params.is_emission_block(1_200));
+        
+        // Emission blocks tile the chain: each window starts where the previous ended
+        let emission_blocks: Vec<u64> = (1..=3_000).filter(|&h| params.is_emission_block(h)).collect();
+        assert_eq!(emission_blocks, vec![600, 1_200, 1_800, 2_400, 3_000]);
+        let windows: Vec<(u64, u64)> = emission_blocks.iter().map(|&h| params.reward_window_heights(h)).collect();
+        assert_eq!(windows[0], (0, 600));
+        assert!(windows.windows(2).all(|pair| pair[0].1 == pair[1].0));
+    }
+
+    #[test]
+    fn test_node_reward_status_and_claim() {
+        use qnet_consensus::lazy_rewards::{PhaseAwareReward, QNetPhase};
+        
+        let genesis = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let mut rewards = PhaseAwareRewardManager::new(genesis);
+        rewards.register_node("node_001".to_string(), RewardNodeType::Full, "operator_wallet".to_string()).unwrap();
+        rewards.restore_pending_reward("node_001".to_string(), PhaseAwareReward {
+            current_phase: QNetPhase::Phase1,
+            pool1_base_emission: 2_000_000_000,
+            pool2_transaction_fees: 500_000_000,
+            pool3_activation_bonus: 0,
+            total_reward: 2_500_000_000,
+        });
+        
+        let status = rewards.get_claim_status("node_001");
+        assert_eq!(status.pending, 2_500_000_000);
+        assert!(status.claimable_now);
+        assert_eq!(status.phase, 1);
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["pending"], 2_500_000_000u64);
+        assert_eq!(json["claimable_now"], true);
+        
+        // Claim produces a payout the mempool accepts as a system transaction
+        let claim = rewards.claim_rewards("node_001", "operator_wallet");
+        assert!(claim.success);
+        let tx = build_reward_claim_transaction("node_001", "operator_wallet", claim.reward.unwrap().total_reward, 1_700_000_000);
+        assert_eq!(tx.to.as_deref(), Some("operator_wallet"));
+        assert_eq!(tx.amount, 2_500_000_000);
+        qnet_mempool::validation::validate_tx_type(&tx).unwrap();
+        
+        // Nothing left to claim
+        let status = rewards.get_claim_status("node_001");
+        assert_eq!(status.pending, 0);
+        assert!(!status.claimable_now);
+        assert!(!rewards.claim_rewards("node_001", "operator_wallet").success);
+    }
+    
+    #[test]
+    fn test_admin_api_key_matches() {
+        assert!(admin_api_key_matches(Some("secret_key"), Some("secret_key")));
+        assert!(!admin_api_key_matches(Some("secret_key"), Some("secret_kez")));
+        assert!(!admin_api_key_matches(Some("secret_key"), None));
+        // No configured key: admin endpoints stay closed
+        assert!(!admin_api_key_matches(None, Some("anything")));
+        assert!(!admin_api_key_matches(Some(""), Some("")));
+    }
+    
+    #[test]
+    fn test_prune_reclaims_old_entropy_and_certificate_entries() {
+        let mut responses = HashMap::new();
+        for height in 0..1_000u64 {
+            for node in 0..5 {
+                responses.insert((height, format!("node_{}", node)), [0u8; 32]);
+            }
+        }
+        // Heights 990..=999 stay inside the finality window of 1_000
+        assert_eq!(prune_entropy_responses(&mut responses, 1_000), 990 * 5);
+        assert_eq!(responses.len(), 10 * 5);
+        assert!(responses.keys().all(|(h, _)| *h >= 1_000 - FINALITY_WINDOW));
+        assert_eq!(prune_entropy_responses(&mut responses, 1_000), 0);
+        
+        let now = 1_700_000_000;
+        let mut requested: HashMap<String, u64> = (0..10_000u64)
+            .map(|i| (format!("cert_{}", i), now - 3_600 + i % 100))
+            .collect();
+        requested.insert("cert_recent".to_string(), now - 2);
+        assert_eq!(prune_requested_certificates(&mut requested, now), 10_000);
+        assert_eq!(requested.keys().collect::<Vec<_>>(), vec!["cert_recent"]);
+        
+        // Still cooling down until CERTIFICATE_REQUEST_COOLDOWN_SECS have passed
+        assert_eq!(prune_requested_certificates(&mut requested, now + 2), 0);
+        assert_eq!(prune_requested_certificates(&mut requested, now + 3), 1);
+    }
+    
+    #[test]
+    fn test_mock_clock_fires_one_reward_window_per_interval() {
+        use crate::clock::MockClock;
+        
+        const BASE_WINDOW: u64 = 4_102_444_800; // 2100-01-01, aligned to a 4-hour boundary
+        let clock = MockClock::at_secs(BASE_WINDOW + 90);
+        let mut rewards = PhaseAwareRewardManager::new(BASE_WINDOW - 24 * 60 * 60);
+        rewards.restore_window_cursor(BASE_WINDOW);
+        
+        let params = ChainParams::default();
+        
+        // The open window fires once, then repeated checks inside it are no-ops
+        assert_eq!(due_reward_window(&params, clock.now_secs(), &rewards), Some(BASE_WINDOW));
+        rewards.process_window_at(BASE_WINDOW).unwrap();
+        assert_eq!(due_reward_window(&params, clock.now_secs(), &rewards), None);
+        
+        // Advance exactly one emission interval, checking every minute
+        let mut fired = Vec::new();
+        for _ in 0..params.reward_window_secs / 60 {
+            clock.advance(Duration::from_secs(60));
+            if let Some(window_start) = due_reward_window(&params, clock.now_secs(), &rewards) {
+                rewards.process_window_at(window_start).unwrap();
+                fired.push(window_start);
+            }
+        }
+        assert_eq!(fired, vec![BASE_WINDOW + params.reward_window_secs]);
+    }
+    
+    #[test]
+    fn test_buffered_successors_applied_in_order() {
+        // Chain stored at 100; blocks 102 and 103 arrive before 101 and are buffered
+        let mut stored = 100;
+        let mut arrivals = std::collections::VecDeque::from([102u64, 103, 101]);
+        let mut contiguous = std::collections::VecDeque::new();
+        let mut pending: HashMap<u64, u64> = HashMap::new();
+        let (mut applied, mut failures) = (Vec::new(), 0);
+        while let Some(height) = contiguous.pop_front().or_else(|| arrivals.pop_front()) {
+            if height != stored + 1 {
+                failures += 1;
+                pending.insert(height, height);
+                continue;
+            }
+            stored = height;
+            applied.push(height);
+            contiguous.extend(drain_contiguous(&mut pending, height));
+        }
+        assert_eq!(applied, vec![101, 102, 103]);
+        // Only the original out-of-order arrivals failed, none on the drain
+        assert_eq!(failures, 2);
+        assert!(pending.is_empty());
+
+        // The drain stops at the next gap
+        let mut pending: HashMap<u64, &str> = HashMap::from([(5, "a"), (6, "b"), (8, "d")]);
+        assert_eq!(drain_contiguous(&mut pending, 4), vec!["a", "b"]);
+        assert_eq!(pending.keys().collect::<Vec<_>>(), vec![&8]);
+        assert!(drain_contiguous(&mut pending, u64::MAX).is_empty());
+    }
+    
+    #[test]
+    fn test_future_block_detection_uses_injected_clock() {
+        use crate::clock::MockClock;
+        
+        let clock = Arc::new(MockClock::at_secs(1_700_000_000));
+        let tracker = SignedBlockTracker::with_clock(clock.clone());
+        let block = MicroBlock {
+            height: 10,
+            timestamp: 1_700_000_010,
+            transactions: Vec::new(),
+            producer: "node_7".to_string(),
+            signature: Vec::new(),
+            merkle_root: [0u8; 32],
+            previous_hash: [0u8; 32],
+            poh_hash: Vec::new(),
+            poh_count: 0,
+        };
+        
+        let evidence = tracker.detect_invalid_block(&block, None).expect("block 10s ahead of the clock");
+        assert_eq!(evidence.evidence_type, "time_manipulation_future");
+        assert_eq!(evidence.timestamp, 1_700_000_000);
+        
+        clock.advance(Duration::from_secs(5));
+        assert!(tracker.detect_invalid_block(&block, None).is_none());
+    }
+    
+    #[test]
+    fn test_clock_skew_tolerance_boundaries() {
+        use crate::clock::MockClock;
+        
+        let now = 1_700_000_000;
+        let tracker = SignedBlockTracker::with_clock(Arc::new(MockClock::at_secs(now))).with_max_clock_skew(10);
+        let block_at = |timestamp: u64| MicroBlock {
+            height: 10,
+            timestamp,
+            transactions: Vec::new(),
+            producer: "node_7".to_string(),
+            signature: Vec::new(),
+            merkle_root: [0u8; 32],
+            previous_hash: [0u8; 32],
+            poh_hash: Vec::new(),
+            poh_count: 0,
+        };
+        
+        // Future: exactly at the tolerance is accepted, one second more is not
+        assert!(tracker.detect_invalid_block(&block_at(now + 10), None).is_none());
+        let future = tracker.detect_invalid_block(&block_at(now + 11), None).unwrap();
+        assert_eq!(future.evidence_type, "time_manipulation_future");
+        assert_eq!(future.evidence_data, b"future_by:11s".to_vec());
+        
+        // Past: same tolerance behind the previous block
+        let previous = Some(now - 100);
+        assert!(tracker.detect_invalid_block(&block_at(now - 110), previous).is_none());
+        let past = tracker.detect_invalid_block(&block_at(now - 111), previous).unwrap();
+        assert_eq!(past.evidence_type, "time_manipulation_past");
+        assert_eq!(past.evidence_data, b"past_by:11s".to_vec());
+        
+        assert_eq!(parse_max_clock_skew(None), DEFAULT_MAX_CLOCK_SKEW_SECS);
+        assert_eq!(parse_max_clock_skew(Some("30")), 30);
+        assert_eq!(parse_max_clock_skew(Some("0")), DEFAULT_MAX_CLOCK_SKEW_SECS);
+        assert_eq!(parse_max_clock_skew(Some("3600")), DEFAULT_MAX_CLOCK_SKEW_SECS);
+        assert_eq!(parse_max_clock_skew(Some("soon")), DEFAULT_MAX_CLOCK_SKEW_SECS);
+    }
+
+    #[tokio::test]
+    async fn test_submitted_transaction_pending_then_confirmed() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::new(dir.path().to_str().unwrap()).unwrap();
+        let mempool = qnet_mempool::SimpleMempool::new(qnet_mempool::SimpleMempoolConfig::default());
+
+        let tx = qnet_state::Transaction::new(
+            "sender".to_string(), Some("receiver".to_string()), 5_000, 0, 100_000, 10_000,
+            1_700_000_000, None, qnet_state::TransactionType::Transfer {
+                from: "sender".to_string(), to: "receiver".to_string(), amount: 5_000,
+            }, None,
+        );
+        assert!(lookup_transaction(mempool.get_by_hash(&tx.hash), &storage, &tx.hash, 0).await.unwrap().is_none());
+
+        // Submitted: pool key hashes the JSON, the lookup uses the transaction hash
+        let tx_json = serde_json::to_string(&tx).unwrap();
+        let pool_key = format!("{:x}", sha3::Sha3_256::digest(tx_json.as_bytes()));
+        assert!(mempool.add_raw_transaction(tx_json, pool_key.clone(), tx.gas_price));
+        let info = lookup_transaction(mempool.get_by_hash(&tx.hash), &storage, &tx.hash, 0).await.unwrap().unwrap();
+        assert_eq!((info.status.as_str(), info.block_height), ("pending", None));
+
+        // Included in block 7 and removed from the mempool
+        let block = qnet_state::Block::new(7, 1_700_000_001, [0u8; 32], vec![tx.clone()], "producer".to_string());
+        storage.save_block(&block).await.unwrap();
+        assert!(mempool.remove_transaction(&pool_key));
+        let info = lookup_transaction(mempool.get_by_hash(&tx.hash), &storage, &tx.hash, 10).await.unwrap().unwrap();
+        assert_eq!((info.status.as_str(), info.block_height, info.confirmations), ("confirmed", Some(7), Some(4)));
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_block_from_second_peer_applied_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::new(dir.path().to_str().unwrap()).unwrap();
+        let state = RwLock::new(StateManager::new());
+        let rewards = RwLock::new(PhaseAwareRewardManager::new(1_700_000_000));
+
+        // Emission is not nonce-protected: applying it twice would mint twice
+        let emission = qnet_state::Transaction::new(
+            "system_emission".to_string(), Some("wallet".to_string()), 1_000, 0, 0, 0,
+            1_700_000_005, None, qnet_state::TransactionType::RewardDistribution, None,
+        );
+        let block = MicroBlock {
+            height: 5,
+            timestamp: 1_700_000_005,
+            transactions: vec![emission],
+            producer: "producer_1".to_string(),
+            signature: vec![1; 64],
+            previous_hash: [4; 32],
+            merkle_root: [0; 32],
+            poh_hash: vec![2; 64],
+            poh_count: 2_500_000,
+        };
+        let received = |block: &MicroBlock, peer: &str| crate::unified_p2p::ReceivedBlock {
+            height: block.height,
+            data: bincode::serialize(block).unwrap(),
+            block_type: "micro".to_string(),
+            from_peer: peer.to_string(),
+            timestamp: block.timestamp,
+        };
+
+        let mut applied = 0;
+        for peer in ["peer_1", "peer_2"] {
+            let copy = received(&block, peer);
+            if !BlockchainNode::is_stored_duplicate(&copy, &storage) {
+                let data = copy.data.clone();
+                BlockchainNode::apply_received_microblock(&block, &data, &storage, &state, &rewards).await.unwrap();
+                applied += 1;
+            }
+        }
+        assert_eq!(applied, 1);
+        assert_eq!(state.read().await.get_balance("wallet"), 1_000);
+        assert_eq!(state.read().await.get_total_supply(), 1_000);
+
+        // Same height, different content: a fork, left to validation
+        let fork = MicroBlock { producer: "producer_2".to_string(), ..block.clone() };
+        assert!(!BlockchainNode::is_stored_duplicate(&received(&fork, "peer_3"), &storage));
+        // Zstd-compressed gossip copies are recognised too
+        let mut compressed = received(&block, "peer_4");
+        compressed.data = zstd::encode_all(&compressed.data[..], 3).unwrap();
+        assert!(BlockchainNode::is_stored_duplicate(&compressed, &storage));
+    }
+
+    #[test]
+    fn test_oversized_transaction_rejects_block() {
+        use qnet_mempool::validation::TxSizeLimits;
+        
+        let with_data = |len: usize| {
+            let mut tx = qnet_state::Transaction::new(
+                "alice".to_string(), Some("bob".to_string()), 5_000, 1, 100_000, 10_000, 1_700_000_000, None,
+                qnet_state::TransactionType::Transfer { from: "alice".to_string(), to: "bob".to_string(), amount: 5_000 },
+                None,
+            );
+            tx.data = Some("d".repeat(len));
+            tx
+        };
+        let block_with = |tx: qnet_state::Transaction| MicroBlock {
+            height: 10,
+            timestamp: 1_700_000_010,
+            transactions: vec![tx],
+            producer: "node_7".to_string(),
+            signature: Vec::new(),
+            merkle_root: [0u8; 32],
+            previous_hash: [0u8; 32],
+            poh_hash: Vec::new(),
+            poh_count: 0,
+        };
+        let overhead = bincode::serialized_size(&with_data(0)).unwrap() as usize;
+        let limits = TxSizeLimits { max_tx_bytes: overhead + 1_000, max_tx_data_bytes: 800 };
+        
+        assert!(BlockchainNode::validate_microblock_tx_sizes(&block_with(with_data(800)), &limits).is_ok());
+        let err = BlockchainNode::validate_microblock_tx_sizes(&block_with(with_data(801)), &limits).unwrap_err();
+        assert!(err.contains("Transaction too large: data is 801 bytes"), "{}", err);
+        
+        let limits = TxSizeLimits { max_tx_data_bytes: 2_000, ..limits };
+        assert!(BlockchainNode::validate_microblock_tx_sizes(&block_with(with_data(1_000)), &limits).is_ok());
+        let err = BlockchainNode::validate_microblock_tx_sizes(&block_with(with_data(1_001)), &limits).unwrap_err();
+        assert!(err.contains("Transaction too large: transaction is"), "{}", err);
+        
+        let params = ChainParams::default();
+        params.validate().unwrap();
+        assert_eq!(params.tx_size_limits(), TxSizeLimits::default());
+        assert!(ChainParams { max_tx_data_bytes: params.max_tx_bytes + 1, ..ChainParams::default() }.validate().is_err());
+    }
+
+    #[test]
+    fn test_expired_transaction_rejects_block() {
+        use qnet_state::transaction::ValidUntil;
+        
+        let tx = qnet_state::Transaction::new(
+            "alice".to_string(), Some("bob".to_string()), 5_000, 1, 100_000, 10_000, 1_700_000_000, None,
+            qnet_state::TransactionType::Transfer { from: "alice".to_string(), to: "bob".to_string(), amount: 5_000 },
+            None,
+        );
+        let block_at = |height: u64, timestamp: u64, tx: qnet_state::Transaction| MicroBlock {
+            height,
+            timestamp,
+            transactions: vec![tx],
+            producer: "node_7".to_string(),
+            signature: Vec::new(),
+            merkle_root: [0u8; 32],
+            previous_hash: [0u8; 32],
+            poh_hash: Vec::new(),
+            poh_count: 0,
+        };
+        
+        let by_height = tx.clone().with_valid_until(ValidUntil::Height(10));
+        assert!(BlockchainNode::validate_microblock_tx_validity(&block_at(10, 1_700_000_010, by_height.clone())).is_ok());
+        let err = BlockchainNode::validate_microblock_tx_validity(&block_at(11, 1_700_000_011, by_height)).unwrap_err();
+        assert!(err.contains("no longer valid"), "{}", err);
+        
+        // Timestamp expiry is judged by the block's timestamp, not the local clock
+        let by_time = tx.clone().with_valid_until(ValidUntil::Timestamp(1_700_000_010));
+        assert!(BlockchainNode::validate_microblock_tx_validity(&block_at(99, 1_700_000_010, by_time.clone())).is_ok());
+        assert!(BlockchainNode::validate_microblock_tx_validity(&block_at(99, 1_700_000_011, by_time)).is_err());
+        
+        assert!(BlockchainNode::validate_microblock_tx_validity(&block_at(u64::MAX, u64::MAX, tx)).is_ok());
+    }
+}
@@ -0,0 +1,237 @@
+//! Node startup configuration
+//!
+//! The `QNET_*` variables that shape node construction are parsed once, at
+//! startup, into a [`NodeConfig`] and validated as a whole before the node is
+//! built:
+//!
+//! | Variable | Field |
+//! |---|---|
+//! | `QNET_BOOTSTRAP_ID` | `bootstrap_id` (Genesis node 001-005) |
+//! | `QNET_GENESIS_BOOTSTRAP=1` | `genesis_bootstrap` |
+//! | `QNET_PRODUCTION=1` | `production` |
+//! | `QNET_PUBLIC_IP`, else `QNET_EXTERNAL_IP` | `public_ip` |
+//! | `QNET_NETWORK` | `network` (default `testnet`) |
+//! | `QNET_ACTIVATION_CODE` | `activation_code` |
+//! | `QNET_MEMPOOL_SIZE` | `mempool_size` (auto-scaled if unset) |
+//! | `QNET_FORCE_RESET=1` + `QNET_CONFIRM_RESET=YES` | `force_reset`, `confirm_reset` |
+//! | `QNET_ADMIN_API_KEY` | `admin_api_key` |
+//! | `QNET_MICROBLOCK_INTERVAL` | `microblock_interval_secs` (default 1) |
+//! | `QNET_ENABLE_SHARDING=1` | `enable_sharding` |
+//! | `QNET_ARCHIVE_QUOTA` | `archive_quota` |
+//! | `QNET_REWARD_MULTIPLIERS` | `reward_multipliers` |
+//! | `QNET_ENABLE_MEV_PROTECTION=1` | `enable_mev_protection` |
+
+use qnet_consensus::lazy_rewards::RewardMultipliers;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ConfigError {
+    #[error("Invalid {var}={value:?}: {reason}")]
+    Invalid { var: &'static str, value: String, reason: String },
+
+    #[error("Missing {var}: {reason}")]
+    Missing { var: &'static str, reason: &'static str },
+}
+
+/// Validated startup settings, passed to `BlockchainNode::new_with_config`
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeConfig {
+    pub bootstrap_id: Option<String>,
+    pub genesis_bootstrap: bool,
+    pub production: bool,
+    pub public_ip: Option<String>,
+    pub network: String,
+    pub activation_code: Option<String>,
+    pub mempool_size: Option<usize>,
+    pub force_reset: bool,
+    pub confirm_reset: bool,
+    pub admin_api_key: Option<String>,
+    pub microblock_interval_secs: u64,
+    pub enable_sharding: bool,
+    pub archive_quota: Option<u8>,
+    pub reward_multipliers: Option<RewardMultipliers>,
+    pub enable_mev_protection: bool,
+}
+
+impl Default for NodeConfig {
+    fn default() -> Self {
+        Self {
+            bootstrap_id: None,
+            genesis_bootstrap: false,
+            production: false,
+            public_ip: None,
+            network: "testnet".to_string(),
+            activation_code: None,
+            mempool_size: None,
+            force_reset: false,
+            confirm_reset: false,
+            admin_api_key: None,
+            microblock_interval_secs: 1,
+            enable_sharding: false,
+            archive_quota: None,
+            reward_multipliers: None,
+            enable_mev_protection: false,
+        }
+    }
+}
+
+impl NodeConfig {
+    /// Parse and validate the process environment
+    pub fn from_env() -> Result<Self, ConfigError> {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    /// Parse and validate settings read through `lookup` (empty values count as unset)
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self, ConfigError> {
+        let var = |name: &str| lookup(name).map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+        let flag = |name: &str| var(name).as_deref() == Some("1");
+        let defaults = Self::default();
+
+        let config = Self {
+            bootstrap_id: var("QNET_BOOTSTRAP_ID"),
+            genesis_bootstrap: flag("QNET_GENESIS_BOOTSTRAP"),
+            production: flag("QNET_PRODUCTION"),
+            public_ip: var("QNET_PUBLIC_IP").or_else(|| var("QNET_EXTERNAL_IP")),
+            network: var("QNET_NETWORK").unwrap_or(defaults.network),
+            activation_code: var("QNET_ACTIVATION_CODE"),
+            mempool_size: parse(&var, "QNET_MEMPOOL_SIZE")?,
+            force_reset: flag("QNET_FORCE_RESET"),
+            confirm_reset: var("QNET_CONFIRM_RESET").as_deref() == Some("YES"),
+            admin_api_key: var("QNET_ADMIN_API_KEY"),
+            microblock_interval_secs: parse(&var, "QNET_MICROBLOCK_INTERVAL")?
+                .unwrap_or(defaults.microblock_interval_secs),
+            enable_sharding: flag("QNET_ENABLE_SHARDING"),
+            archive_quota: parse(&var, "QNET_ARCHIVE_QUOTA")?,
+            reward_multipliers: match var("QNET_REWARD_MULTIPLIERS") {
+                Some(value) => Some(RewardMultipliers::parse(&value).map_err(|e| ConfigError::Invalid {
+                    var: "QNET_REWARD_MULTIPLIERS",
+                    value,
+                    reason: e.to_string(),
+                })?),
+                None => None,
+            },
+            enable_mev_protection: flag("QNET_ENABLE_MEV_PROTECTION"),
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Cross-field checks
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if let Some(id) = &self.bootstrap_id {
+            if crate::genesis_constants::get_genesis_ip_by_id(id).is_none() {
+                return Err(ConfigError::Invalid {
+                    var: "QNET_BOOTSTRAP_ID",
+                    value: id.clone(),
+                    reason: "Genesis IDs are 001-005".to_string(),
+                });
+            }
+        }
+        if self.genesis_bootstrap && self.bootstrap_id.is_none() {
+            return Err(ConfigError::Missing {
+                var: "QNET_BOOTSTRAP_ID",
+                reason: "a Genesis bootstrap node needs its bootstrap id",
+            });
+        }
+        if self.production && self.public_ip.is_none() {
+            return Err(ConfigError::Missing {
+                var: "QNET_PUBLIC_IP",
+                reason: "production nodes must advertise a public IP",
+            });
+        }
+        if self.microblock_interval_secs == 0 {
+            return Err(ConfigError::Invalid {
+                var: "QNET_MICROBLOCK_INTERVAL",
+                value: "0".to_string(),
+                reason: "must be at least 1 second".to_string(),
+            });
+        }
+        if self.mempool_size == Some(0) {
+            return Err(ConfigError::Invalid {
+                var: "QNET_MEMPOOL_SIZE",
+                value: "0".to_string(),
+                reason: "must be positive".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Genesis node (bootstrap id set, or legacy Genesis bootstrap flag)
+    pub fn is_genesis(&self) -> bool {
+        self.bootstrap_id.is_some() || self.genesis_bootstrap
+    }
+
+    /// Reset requested and confirmed
+    pub fn reset_confirmed(&self) -> bool {
+        self.force_reset && self.confirm_reset
+    }
+}
+
+fn parse<T: std::str::FromStr>(var: &impl Fn(&str) -> Option<String>, name: &'static str) -> Result<Option<T>, ConfigError>
+where
+    T::Err: std::fmt::Display,
+{
+    match var(name) {
+        Some(value) => value.parse::<T>()
+            .map(Some)
+            .map_err(|e| ConfigError::Invalid { var: name, reason: e.to_string(), value }),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn config(vars: &[(&str, &str)]) -> Result<NodeConfig, ConfigError> {
+        let vars: HashMap<String, String> = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        NodeConfig::from_lookup(|name| vars.get(name).cloned())
+    }
+
+    #[test]
+    fn test_valid_genesis_config() {
+        let genesis = config(&[
+            ("QNET_BOOTSTRAP_ID", "003"),
+            ("QNET_GENESIS_BOOTSTRAP", "1"),
+            ("QNET_PRODUCTION", "1"),
+            ("QNET_EXTERNAL_IP", "161.97.86.81"),
+            ("QNET_MEMPOOL_SIZE", "200000"),
+            ("QNET_FORCE_RESET", "1"),
+        ]).unwrap();
+        assert!(genesis.is_genesis());
+        assert_eq!(genesis.bootstrap_id.as_deref(), Some("003"));
+        assert_eq!(genesis.public_ip.as_deref(), Some("161.97.86.81"));
+        assert_eq!(genesis.mempool_size, Some(200_000));
+        assert_eq!(genesis.network, "testnet");
+        assert_eq!(genesis.microblock_interval_secs, 1);
+        // Reset without confirmation is not a reset
+        assert!(!genesis.reset_confirmed());
+
+        assert!(matches!(
+            config(&[("QNET_GENESIS_BOOTSTRAP", "1")]),
+            Err(ConfigError::Missing { var: "QNET_BOOTSTRAP_ID", .. })
+        ));
+        assert!(matches!(
+            config(&[("QNET_BOOTSTRAP_ID", "042")]),
+            Err(ConfigError::Invalid { var: "QNET_BOOTSTRAP_ID", .. })
+        ));
+    }
+
+    #[test]
+    fn test_production_config_requires_public_ip() {
+        assert_eq!(
+            config(&[("QNET_PRODUCTION", "1"), ("QNET_ACTIVATION_CODE", "QNET-ABCDEF-123456-7890AB")]),
+            Err(ConfigError::Missing {
+                var: "QNET_PUBLIC_IP",
+                reason: "production nodes must advertise a public IP",
+            })
+        );
+        assert!(config(&[("QNET_PRODUCTION", "1"), ("QNET_PUBLIC_IP", "203.0.113.7")]).is_ok());
+
+        // Malformed numbers are reported, not silently defaulted
+        assert!(matches!(
+            config(&[("QNET_MEMPOOL_SIZE", "lots")]),
+            Err(ConfigError::Invalid { var: "QNET_MEMPOOL_SIZE", .. })
+        ));
+    }
+}
@@ -0,0 +1,153 @@
+//! Deterministic ping sampling for reward window commitments
+//!
+//! Every node must select the same ping samples for a window, so the sampling
+//! seed is derived from a block deep enough to be final on all nodes. The
+//! entropy source is injectable so sampling can be tested without storage.
+
+use std::collections::HashMap;
+
+use sha3::{Sha3_256, Digest};
+use crate::storage::Storage;
+use crate::node::FINALITY_WINDOW;
+
+/// Domain separator for ping sampling seeds
+pub const PING_SAMPLING_DOMAIN: &[u8] = b"QNet_Ping_Sampling_v1";
+
+/// Source of entropy bytes for deterministic sampling
+pub trait EntropySource {
+    /// Entropy for sampling at `current_height`
+    fn entropy_at(&self, current_height: u64) -> Result<Vec<u8>, String>;
+}
+
+/// Production entropy: the microblock `FINALITY_WINDOW` blocks below the current height
+pub struct FinalityWindowEntropy<'a> {
+    storage: &'a Storage,
+}
+
+impl<'a> FinalityWindowEntropy<'a> {
+    pub fn new(storage: &'a Storage) -> Self {
+        Self { storage }
+    }
+}
+
+impl EntropySource for FinalityWindowEntropy<'_> {
+    fn entropy_at(&self, current_height: u64) -> Result<Vec<u8>, String> {
+        let entropy_height = current_height.saturating_sub(FINALITY_WINDOW);
+        self.storage.load_microblock(entropy_height)
+            .map_err(|e| format!("Failed to load entropy block: {}", e))?
+            .ok_or_else(|| "Entropy block not found".to_string())
+    }
+}
+
+/// Fixed entropy for tests and tooling
+pub struct FixedEntropySource(pub Vec<u8>);
+
+impl EntropySource for FixedEntropySource {
+    fn entropy_at(&self, _current_height: u64) -> Result<Vec<u8>, String> {
+        Ok(self.0.clone())
+    }
+}
+
+/// Derive the sampling seed for a reward window
+pub fn derive_sample_seed(
+    source: &dyn EntropySource,
+    current_height: u64,
+    window_start_height: u64,
+) -> Result<[u8; 32], String> {
+    let entropy = source.entropy_at(current_height)?;
+
+    // OPTIMIZED: SHA3-256 (32 bytes) - still quantum-resistant (128-bit security against Grover)
+    let mut hasher = Sha3_256::new();
+    hasher.update(PING_SAMPLING_DOMAIN);
+    hasher.update(&entropy);
+    hasher.update(window_start_height.to_le_bytes());
+
+    let mut seed = [0u8; 32];
+    seed.copy_from_slice(&hasher.finalize());
+    Ok(seed)
+}
+
+/// Pings sampled from `total_count`: `percentage`% but at least `min_samples`, never more than there are
+pub fn sample_size(total_count: usize, percentage: u32, min_samples: usize) -> usize {
+    let by_percentage = (total_count as u64 * percentage as u64 / 100) as usize;
+    by_percentage.max(min_samples).min(total_count)
+}
+
+/// Deterministic, distinct sample indices into a list of `total_count` pings
+///
+/// A partial Fisher-Yates shuffle driven by the seed: step `i` swaps position `i`
+/// with a seed-chosen position in `i..total_count` and takes it, so no ping is
+/// sampled twice. Only touched positions are tracked, so memory is O(sample_size).
+/// `sample_size` is capped at `total_count`.
+pub fn sample_indices(seed: &[u8; 32], sample_size: usize, total_count: usize) -> Vec<usize> {
+    let sample_size = sample_size.min(total_count);
+    let mut swapped: HashMap<usize, usize> = HashMap::with_capacity(sample_size * 2);
+
+    (0..sample_size)
+        .map(|i| {
+            let mut hasher = Sha3_256::new();
+            hasher.update(seed);
+            hasher.update((i as u32).to_le_bytes());
+            let hash = hasher.finalize();
+            let mut index_bytes = [0u8; 8];
+            index_bytes.copy_from_slice(&hash[..8]);
+            let j = i + (u64::from_le_bytes(index_bytes) % (total_count - i) as u64) as usize;
+
+            let at_i = swapped.get(&i).copied().unwrap_or(i);
+            let at_j = swapped.get(&j).copied().unwrap_or(j);
+            swapped.insert(j, at_i);
+            at_j
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_entropy_selects_same_samples() {
+        let source = FixedEntropySource(b"finalized_block_bytes".to_vec());
+
+        let run = || {
+            let seed = derive_sample_seed(&source, 14_400, 0).unwrap();
+            sample_indices(&seed, 100, 10_000)
+        };
+        let first = run();
+        assert_eq!(first.len(), 100);
+        assert!(first.iter().all(|&i| i < 10_000));
+        assert_eq!(first, run());
+
+        // Different window or entropy gives a different sample
+        let other_window = sample_indices(&derive_sample_seed(&source, 14_400, 14_400).unwrap(), 100, 10_000);
+        assert_ne!(first, other_window);
+        let other_source = FixedEntropySource(b"other_block".to_vec());
+        let other_entropy = sample_indices(&derive_sample_seed(&other_source, 14_400, 0).unwrap(), 100, 10_000);
+        assert_ne!(first, other_entropy);
+    }
+
+    #[test]
+    fn test_tiny_ping_set_sampled_without_duplicates() {
+        let seed = derive_sample_seed(&FixedEntropySource(b"finalized_block_bytes".to_vec()), 14_400, 0).unwrap();
+
+        // 10K minimum on a 12-ping window: capped at every ping, each exactly once
+        let size = sample_size(12, 1, 10_000);
+        assert_eq!(size, 12);
+        let all = sample_indices(&seed, size, 12);
+        let mut sorted = all.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..12).collect::<Vec<_>>());
+        assert_eq!(all, sample_indices(&seed, size, 12));
+
+        // Fewer samples than pings: distinct, in range, same selection on every node
+        let some = sample_indices(&seed, 5, 12);
+        let distinct: std::collections::HashSet<_> = some.iter().collect();
+        assert_eq!((some.len(), distinct.len()), (5, 5));
+        assert!(some.iter().all(|&i| i < 12));
+        assert_eq!(some, sample_indices(&seed, 5, 12));
+        assert_eq!(some[..], all[..5]);
+
+        assert_eq!(sample_indices(&seed, 100, 0), Vec::<usize>::new());
+        assert_eq!(sample_size(2_000_000, 1, 10_000), 20_000);
+    }
+}
@@ -231,28 +231,13 @@ impl QNetQuantumCrypto {
         // Cache miss - perform full decryption
         self.record_cache_miss();
 
-        // 1. Check for genesis bootstrap codes first (different format)
-        const BOOTSTRAP_WHITELIST: &[&str] = &[
-            "QNET-BOOT-0001-STRAP", "QNET-BOOT-0002-STRAP", "QNET-BOOT-0003-STRAP", 
-            "QNET-BOOT-0004-STRAP", "QNET-BOOT-0005-STRAP"
-        ];
+        // 1. Validate format; Genesis bootstrap codes are handled first (different format)
+        let parsed_code = crate::activation_validation::ActivationCode::parse(activation_code)
+            .map_err(|e| anyhow!("{}", e))?;
         
-        if BOOTSTRAP_WHITELIST.contains(&activation_code) {
+        if let Some(bootstrap_id) = parsed_code.genesis_bootstrap_id() {
             println!("✅ Genesis bootstrap code detected in quantum_crypto.rs: {}", activation_code);
-            
-            // Extract bootstrap ID from code: QNET-BOOT-0001-STRAP → "001"
-            // Note: split gives "0001" (4 chars), but genesis_constants uses "001" (3 chars)
-            let bootstrap_id_raw = activation_code
-                .split('-')
-                .nth(2)
-                .unwrap_or("000");
-            
-            // Convert "0001" → "001", "0002" → "002", etc.
-            let bootstrap_id = bootstrap_id_raw.trim_start_matches('0');
-            let bootstrap_id = if bootstrap_id.is_empty() { "0" } else { bootstrap_id };
-            let bootstrap_id = format!("{:03}", bootstrap_id.parse::<u32>().unwrap_or(0));
-            
-            println!("[GENESIS] Bootstrap ID parsed: '{}' → '{}'", bootstrap_id_raw, bootstrap_id);
+            println!("[GENESIS] Bootstrap ID parsed: '{}'", bootstrap_id);
             
             // Use predefined wallet from genesis_constants
             // STRICT: No fallback - unknown bootstrap ID is an error
@@ -278,16 +263,8 @@ impl QNetQuantumCrypto {
             });
         }
         
-        // Validate code format: QNET-XXXXXX-XXXXXX-XXXXXX (25 chars)
-        if !activation_code.starts_with("QNET-") || activation_code.len() != 25 {
-            return Err(anyhow!("Invalid activation code format - expected QNET-XXXXXX-XXXXXX-XXXXXX (25 chars)"));
-        }
-
         // 2. Parse route.ts format: QNET-[TYPE+TIMESTAMP]-[WALLET_PART1]-[WALLET_PART2+ENTROPY]
-        let parts: Vec<&str> = activation_code.split('-').collect();
-        if parts.len() != 4 || parts[0] != "QNET" {
-            return Err(anyhow!("Invalid activation code structure"));
-        }
+        let parts: Vec<&str> = parsed_code.as_str().split('-').collect();
 
         // 3. Extract node type and timestamp from first segment
         let segment1 = parts[1];
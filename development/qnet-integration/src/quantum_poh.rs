@@ -7,11 +7,11 @@
 // - Thread-safe operation with atomic state updates
 // - Integration with QNet's microblock/macroblock architecture
 
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::{RwLock, mpsc, Mutex};
-use sha3::{Sha3_512, Digest};
+use sha3::{Sha3_256, Sha3_512, Digest};
 use blake3;
 use serde::{Serialize, Deserialize};
 use prometheus::{register_counter, register_gauge, Counter, Gauge};
@@ -41,6 +41,30 @@ lazy_static! {
         "qnet_poh_checkpoint_count_total",
         "Total number of PoH checkpoints saved"
     ).unwrap();
+    
+    /// Failed PoH checkpoint saves
+    static ref POH_CHECKPOINT_FAILURES: Counter = register_counter!(
+        "qnet_poh_checkpoint_failures_total",
+        "Total number of failed PoH checkpoint saves"
+    ).unwrap();
+    
+    /// Hashes computed since the last successful checkpoint
+    static ref POH_CHECKPOINT_LAG: Gauge = register_gauge!(
+        "qnet_poh_checkpoint_lag",
+        "PoH hashes since the last successful checkpoint"
+    ).unwrap();
+    
+    /// Hash rate measured by the startup benchmark
+    static ref POH_BENCHMARK_HASH_RATE: Gauge = register_gauge!(
+        "qnet_poh_benchmark_hash_rate",
+        "PoH hashes per second measured by the startup benchmark"
+    ).unwrap();
+    
+    /// Hash rate a producer needs to keep up with the block interval
+    static ref POH_REQUIRED_HASH_RATE: Gauge = register_gauge!(
+        "qnet_poh_required_hash_rate",
+        "PoH hashes per second required to keep up with 1-second blocks"
+    ).unwrap();
 }
 
 // ============================================================================
@@ -61,10 +85,58 @@ const HASHES_PER_SLOT: u64 = HASHES_PER_TICK * 100; // 500,000
 /// Maximum drift allowed between PoH time and wall clock (5%)
 const MAX_DRIFT_PERCENT: f64 = 0.05;
 
+/// Save a checkpoint every 10 million hashes (~20 seconds at 500K/s)
+const CHECKPOINT_INTERVAL_HASHES: u64 = 10_000_000;
+
+/// Checkpoints scanned when no `poh_checkpoint_latest` index exists (100B hashes, ~55 hours)
+const MAX_UNINDEXED_CHECKPOINTS: u64 = 10_000;
+
+/// Index key pointing at the newest checkpoint
+const CHECKPOINT_INDEX_KEY: &str = "poh_checkpoint_latest";
+
+/// Zstd frame magic: checkpoints written before integrity digests start with it
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// Consecutive checkpoint save failures before a critical alert
+pub const CHECKPOINT_FAILURE_ALERT_THRESHOLD: u32 = 5;
+
+/// Hashes computed by the startup benchmark (~0.2 seconds at 500K/s)
+const BENCHMARK_HASHES: u64 = HASHES_PER_TICK * 20;
+
+/// Result of the startup benchmark
+static STARTUP_BENCHMARK: OnceLock<PoHBenchmark> = OnceLock::new();
+
 // ============================================================================
 // DATA STRUCTURES
 // ============================================================================
 
+/// Measured PoH throughput against the rate block production needs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PoHBenchmark {
+    /// Hashes per second this machine computed
+    pub hashes_per_sec: u64,
+    /// Hashes per second needed for HASHES_PER_SLOT every 1-second block
+    pub required_hashes_per_sec: u64,
+}
+
+impl PoHBenchmark {
+    /// Whether the generator can keep up with the block interval
+    pub fn keeps_up(&self) -> bool {
+        self.hashes_per_sec >= self.required_hashes_per_sec
+    }
+
+    /// Warning for a node too slow to produce blocks on time
+    pub fn warning(&self) -> Option<String> {
+        if self.keeps_up() {
+            return None;
+        }
+        Some(format!(
+            "PoH hash rate {} hashes/sec is below the required {} hashes/sec - this node may fall behind as a producer",
+            self.hashes_per_sec, self.required_hashes_per_sec
+        ))
+    }
+}
+
 /// PoH Entry representing a checkpoint in the hash chain
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PoHEntry {
@@ -100,6 +172,310 @@ pub struct QuantumPoH {
     hashes_per_second: Arc<AtomicU64>,
     /// Mutex to serialize hash updates (prevents race between generator and mix_transaction)
     update_mutex: Arc<Mutex<()>>,
+    /// Hash count of the last successfully persisted checkpoint
+    last_checkpoint_count: Arc<AtomicU64>,
+}
+
+/// Persistent store for PoH checkpoints
+pub trait PoHCheckpointStore {
+    fn save_checkpoint(&self, key: &str, data: &[u8]) -> Result<(), String>;
+    fn load_checkpoint(&self, key: &str) -> Result<Option<Vec<u8>>, String>;
+}
+
+impl PoHCheckpointStore for crate::storage::Storage {
+    fn save_checkpoint(&self, key: &str, data: &[u8]) -> Result<(), String> {
+        self.save_poh_checkpoint(key, data).map_err(|e| e.to_string())
+    }
+    
+    fn load_checkpoint(&self, key: &str) -> Result<Option<Vec<u8>>, String> {
+        self.load_poh_checkpoint(key).map_err(|e| e.to_string())
+    }
+}
+
+fn checkpoint_key(num_hashes: u64) -> String {
+    format!("poh_checkpoint_{}", num_hashes)
+}
+
+/// Stored checkpoint: SHA3-256 of the compressed entry, then zstd(bincode(entry))
+fn encode_checkpoint(checkpoint: &PoHEntry) -> Result<Vec<u8>, String> {
+    let serialized = bincode::serialize(checkpoint)
+        .map_err(|e| format!("Serialization failed: {}", e))?;
+    let compressed = zstd::encode_all(&serialized[..], 3)
+        .map_err(|e| format!("Compression failed: {}", e))?;
+    
+    let mut data = Sha3_256::digest(&compressed).to_vec();
+    data.extend_from_slice(&compressed);
+    Ok(data)
+}
+
+/// Decode a stored checkpoint, rejecting it if the digest, the hash length
+/// or the hash count does not match. Legacy checkpoints without a digest are accepted
+fn decode_checkpoint(data: &[u8], expected_hashes: u64) -> Result<PoHEntry, String> {
+    let compressed = if data.starts_with(&ZSTD_MAGIC) {
+        data
+    } else {
+        if data.len() <= 32 {
+            return Err(format!("truncated ({} bytes)", data.len()));
+        }
+        let (digest, compressed) = data.split_at(32);
+        if Sha3_256::digest(compressed).as_slice() != digest {
+            return Err("integrity hash mismatch".to_string());
+        }
+        compressed
+    };
+    
+    let decompressed = zstd::decode_all(compressed)
+        .map_err(|e| format!("decompression failed: {}", e))?;
+    let entry: PoHEntry = bincode::deserialize(&decompressed)
+        .map_err(|e| format!("deserialization failed: {}", e))?;
+    if entry.num_hashes != expected_hashes || entry.hash.len() != 64 {
+        return Err(format!("entry for count {} with {}-byte hash", entry.num_hashes, entry.hash.len()));
+    }
+    Ok(entry)
+}
+
+/// Find the newest intact checkpoint, starting at the one the index points to
+/// and walking back one checkpoint interval at a time past corrupt or missing
+/// data. The index is rewritten when recovery had to fall back.
+pub fn recover_latest_checkpoint<S: PoHCheckpointStore + ?Sized>(store: &S) -> Option<PoHEntry> {
+    let indexed = match store.load_checkpoint(CHECKPOINT_INDEX_KEY) {
+        Ok(Some(index_data)) => bincode::deserialize::<u64>(&index_data).ok(),
+        Ok(None) => None,
+        Err(e) => {
+            println!("[QuantumPoH] ⚠️ Failed to read checkpoint index: {}", e);
+            None
+        }
+    };
+    let start = match indexed {
+        Some(count) => count / CHECKPOINT_INTERVAL_HASHES,
+        None => {
+            println!("[QuantumPoH] 🔍 Scanning for checkpoints (no index found)...");
+            MAX_UNINDEXED_CHECKPOINTS
+        }
+    };
+    
+    for checkpoint_num in (1..=start).rev() {
+        let count = checkpoint_num * CHECKPOINT_INTERVAL_HASHES;
+        let data = match store.load_checkpoint(&checkpoint_key(count)) {
+            Ok(Some(data)) => data,
+            Ok(None) => {
+                if indexed.is_some() {
+                    println!("[QuantumPoH] ⚠️ Checkpoint {} missing", count);
+                }
+                continue;
+            }
+            Err(e) => {
+                println!("[QuantumPoH] ⚠️ Failed to read checkpoint {}: {}", count, e);
+                continue;
+            }
+        };
+        
+        match decode_checkpoint(&data, count) {
+            Ok(entry) => {
+                if indexed != Some(count) {
+                    if let Some(indexed_count) = indexed {
+                        println!("[QuantumPoH] 🩹 Recovered from checkpoint {} after skipping {} damaged checkpoint(s) ({} hashes back)",
+                                count, start - checkpoint_num, indexed_count - count);
+                    }
+                    match bincode::serialize(&count) {
+                        Ok(index_data) => {
+                            if let Err(e) = store.save_checkpoint(CHECKPOINT_INDEX_KEY, &index_data) {
+                                println!("[QuantumPoH] ⚠️ Failed to update checkpoint index: {}", e);
+                            }
+                        }
+                        Err(e) => println!("[QuantumPoH] ⚠️ Index serialization failed: {}", e),
+                    }
+                }
+                println!("[QuantumPoH] 📂 Loaded checkpoint: count={}", entry.num_hashes);
+                return Some(entry);
+            }
+            Err(e) => println!("[QuantumPoH] ⚠️ Checkpoint {} is corrupt: {}", count, e),
+        }
+    }
+    
+    if indexed.is_some() {
+        println!("[QuantumPoH] ⚠️ No intact checkpoint found - PoH restarts from genesis");
+    }
+    None
+}
+
+/// Critical alert: PoH checkpoints keep failing to persist
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoHCheckpointAlert {
+    pub consecutive_failures: u32,
+    /// Hashes a restart would have to recompute
+    pub checkpoint_lag: u64,
+    pub last_error: String,
+}
+
+/// Tracks checkpoint saves so repeated failures (e.g. a full disk) are surfaced
+/// instead of silently leaving the node with a very old recovery point
+#[derive(Debug)]
+pub struct PoHCheckpointTracker {
+    last_checkpoint: u64,
+    consecutive_failures: u32,
+    alert_threshold: u32,
+}
+
+impl PoHCheckpointTracker {
+    pub fn new(last_checkpoint: u64, alert_threshold: u32) -> Self {
+        Self {
+            last_checkpoint,
+            consecutive_failures: 0,
+            alert_threshold: alert_threshold.max(1),
+        }
+    }
+    
+    /// Hash count of the last successful checkpoint
+    pub fn last_checkpoint(&self) -> u64 {
+        self.last_checkpoint
+    }
+    
+    pub fn consecutive_failures(&self) -> u32 {
+        self.consecutive_failures
+    }
+    
+    /// Hashes since the last successful checkpoint
+    pub fn checkpoint_lag(&self, current_hashes: u64) -> u64 {
+        current_hashes.saturating_sub(self.last_checkpoint)
+    }
+    
+    /// Save a checkpoint for `entry` if one is due
+    /// Returns an alert when consecutive failures reach the threshold (and again
+    /// every `alert_threshold` failures after that while the store stays broken)
+    pub fn process_entry<S: PoHCheckpointStore + ?Sized>(&mut self, store: &S, entry: &PoHEntry) -> Option<PoHCheckpointAlert> {
+        let current_interval = entry.num_hashes / CHECKPOINT_INTERVAL_HASHES;
+        let last_interval = self.last_checkpoint / CHECKPOINT_INTERVAL_HASHES;
+        if current_interval == last_interval {
+            POH_CHECKPOINT_LAG.set(self.checkpoint_lag(entry.num_hashes) as f64);
+            return None;
+        }
+        
+        let rounded_count = current_interval * CHECKPOINT_INTERVAL_HASHES;
+        let checkpoint_entry = PoHEntry {
+            num_hashes: rounded_count,
+            hash: entry.hash.clone(),
+            data: entry.data.clone(),
+            timestamp: entry.timestamp,
+        };
+        
+        match Self::save(store, &checkpoint_entry) {
+            Ok(compressed_len) => {
+                println!("[QuantumPoH] 💾 Saved checkpoint at hash count: {} (compressed: {} bytes)", 
+                        rounded_count, compressed_len);
+                if self.consecutive_failures > 0 {
+                    println!("[QuantumPoH] ✅ Checkpoint storage recovered after {} failures", self.consecutive_failures);
+                }
+                self.last_checkpoint = rounded_count;
+                self.consecutive_failures = 0;
+                POH_CHECKPOINT_COUNT.inc();
+                POH_CHECKPOINT_LAG.set(self.checkpoint_lag(entry.num_hashes) as f64);
+                None
+            }
+            Err(e) => {
+                self.consecutive_failures += 1;
+                let lag = self.checkpoint_lag(entry.num_hashes);
+                POH_CHECKPOINT_FAILURES.inc();
+                POH_CHECKPOINT_LAG.set(lag as f64);
+                println!("[QuantumPoH] ⚠️ Failed to save checkpoint at {} ({} consecutive): {}", 
+                        rounded_count, self.consecutive_failures, e);
+                
+                if self.consecutive_failures.is_multiple_of(self.alert_threshold) {
+                    Some(PoHCheckpointAlert {
+                        consecutive_failures: self.consecutive_failures,
+                        checkpoint_lag: lag,
+                        last_error: e,
+                    })
+                } else {
+                    None
+                }
+            }
+        }
+    }
+    
+    fn save<S: PoHCheckpointStore + ?Sized>(store: &S, checkpoint: &PoHEntry) -> Result<usize, String> {
+        let encoded = encode_checkpoint(checkpoint)?;
+        store.save_checkpoint(&checkpoint_key(checkpoint.num_hashes), &encoded)?;
+        
+        // Also update the index for O(1) lookup on restart
+        let index_data = bincode::serialize(&checkpoint.num_hashes)
+            .map_err(|e| format!("Index serialization failed: {}", e))?;
+        store.save_checkpoint(CHECKPOINT_INDEX_KEY, &index_data)?;
+        
+        Ok(encoded.len())
+    }
+}
+
+/// One generator hash: SHA3-512 every 4th step (VDF property), Blake3 extended to 64 bytes otherwise
+fn hash_step(hash_bytes: &mut [u8; 64], counter_value: u64, use_sha3: bool) {
+    if use_sha3 {
+        let mut hasher = Sha3_512::new();
+        hasher.update(&hash_bytes[..]);
+        hasher.update(counter_value.to_le_bytes());
+        let result = hasher.finalize();
+        hash_bytes.copy_from_slice(&result);
+    } else {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&hash_bytes[..]);
+        hasher.update(&counter_value.to_le_bytes());
+        let result = hasher.finalize();
+        hash_bytes[..32].copy_from_slice(result.as_bytes());
+        
+        // Second Blake3 hash to fill remaining 32 bytes
+        let mut hasher2 = blake3::Hasher::new();
+        hasher2.update(result.as_bytes());
+        let result2 = hasher2.finalize();
+        hash_bytes[32..].copy_from_slice(result2.as_bytes());
+    }
+}
+
+/// Hashes per second for `hashes` generator steps on this machine
+pub fn measure_hash_rate(hashes: u64) -> u64 {
+    let mut hash_bytes = [0u8; 64];
+    let started = Instant::now();
+    for i in 0..hashes {
+        hash_step(&mut hash_bytes, i, i % 4 == 0);
+    }
+    std::hint::black_box(hash_bytes);
+    let elapsed = started.elapsed().as_secs_f64().max(1e-9);
+    (hashes as f64 / elapsed) as u64
+}
+
+/// Required rate from `QNET_POH_MIN_HASH_RATE`; HASHES_PER_SLOT (1-second blocks) if unset or invalid
+pub fn parse_required_hash_rate(value: Option<&str>) -> u64 {
+    match value.map(|v| v.trim().parse::<u64>()) {
+        None => HASHES_PER_SLOT,
+        Some(Ok(rate)) if rate > 0 => rate,
+        Some(_) => {
+            println!("[QuantumPoH] ⚠️ Invalid QNET_POH_MIN_HASH_RATE - using {} hashes/sec", HASHES_PER_SLOT);
+            HASHES_PER_SLOT
+        }
+    }
+}
+
+/// Benchmark PoH hashing, log the rate and publish it to Prometheus
+///
+/// Blocking (~0.2 seconds on hardware that keeps up); run it off the async runtime.
+pub fn run_startup_benchmark() -> PoHBenchmark {
+    let benchmark = PoHBenchmark {
+        hashes_per_sec: measure_hash_rate(BENCHMARK_HASHES),
+        required_hashes_per_sec: parse_required_hash_rate(std::env::var("QNET_POH_MIN_HASH_RATE").ok().as_deref()),
+    };
+    POH_BENCHMARK_HASH_RATE.set(benchmark.hashes_per_sec as f64);
+    POH_REQUIRED_HASH_RATE.set(benchmark.required_hashes_per_sec as f64);
+    match benchmark.warning() {
+        Some(warning) => println!("[QuantumPoH] ⚠️ {}", warning),
+        None => println!("[QuantumPoH] ⚡ Benchmark: {:.2}M hashes/sec (required {:.2}M)",
+                         benchmark.hashes_per_sec as f64 / 1_000_000.0,
+                         benchmark.required_hashes_per_sec as f64 / 1_000_000.0),
+    }
+    let _ = STARTUP_BENCHMARK.set(benchmark);
+    benchmark
+}
+
+/// Result of the startup benchmark, None until it has run
+pub fn startup_benchmark() -> Option<PoHBenchmark> {
+    STARTUP_BENCHMARK.get().copied()
 }
 
 impl QuantumPoH {
@@ -119,6 +495,7 @@ impl QuantumPoH {
             is_running: Arc::new(AtomicBool::new(false)),
             hashes_per_second: Arc::new(AtomicU64::new(0)),
             update_mutex: Arc::new(Mutex::new(())),
+            last_checkpoint_count: Arc::new(AtomicU64::new(0)),
         };
         
         (poh, entry_receiver)
@@ -143,6 +520,7 @@ impl QuantumPoH {
             is_running: Arc::new(AtomicBool::new(false)),
             hashes_per_second: Arc::new(AtomicU64::new(0)),
             update_mutex: Arc::new(Mutex::new(())),
+            last_checkpoint_count: Arc::new(AtomicU64::new(count)),
         };
         
         println!("[QuantumPoH] 🔄 Initialized from checkpoint: count={}, slot={}", count, slot);
@@ -241,29 +619,7 @@ impl QuantumPoH {
                 // Every 4th hash uses SHA3-512 for VDF property (prevents parallelization)
                 // Other hashes use Blake3 for speed (3x faster)
                 for i in 0..HASHES_PER_TICK {
-                    let counter_value = base_count + i;
-                    
-                    if i % 4 == 0 {
-                        // SHA3-512 for VDF property
-                        let mut hasher = Sha3_512::new();
-                        hasher.update(&hash_bytes);
-                        hasher.update(&counter_value.to_le_bytes());
-                        let result = hasher.finalize();
-                        hash_bytes.copy_from_slice(&result);
-                    } else {
-                        // Blake3 for speed (produces 32 bytes, we extend to 64)
-                        let mut hasher = blake3::Hasher::new();
-                        hasher.update(&hash_bytes);
-                        hasher.update(&counter_value.to_le_bytes());
-                        let result = hasher.finalize();
-                        hash_bytes[..32].copy_from_slice(result.as_bytes());
-                        
-                        // Second Blake3 hash to fill remaining 32 bytes
-                        let mut hasher2 = blake3::Hasher::new();
-                        hasher2.update(result.as_bytes());
-                        let result2 = hasher2.finalize();
-                        hash_bytes[32..].copy_from_slice(result2.as_bytes());
-                    }
+                    hash_step(&mut hash_bytes, base_count + i, i % 4 == 0);
                 }
                 
                 // Update state atomically
@@ -504,6 +860,18 @@ impl QuantumPoH {
         self.mix_transaction(block_data.to_vec()).await
     }
     
+    /// Record the hash count of the last successfully persisted checkpoint
+    pub fn set_last_checkpoint(&self, count: u64) {
+        self.last_checkpoint_count.store(count, Ordering::SeqCst);
+    }
+    
+    /// Hashes computed since the last successful checkpoint
+    /// A restart would have to recompute this many hashes
+    pub fn poh_checkpoint_lag(&self) -> u64 {
+        self.hash_count.load(Ordering::SeqCst)
+            .saturating_sub(self.last_checkpoint_count.load(Ordering::SeqCst))
+    }
+    
     /// Create PoH checkpoint for macroblock finalization
     /// 
     /// This captures the current PoH state for inclusion in a macroblock,
@@ -522,3 +890,128 @@ impl QuantumPoH {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Mutex as StdMutex;
+
+    /// Store that fails while `full` is set, like a full disk
+    struct StubStore {
+        full: AtomicBool,
+        saved: StdMutex<Vec<String>>,
+    }
+
+    impl PoHCheckpointStore for StubStore {
+        fn save_checkpoint(&self, key: &str, _data: &[u8]) -> Result<(), String> {
+            if self.full.load(Ordering::SeqCst) {
+                return Err("No space left on device".to_string());
+            }
+            self.saved.lock().unwrap().push(key.to_string());
+            Ok(())
+        }
+        
+        fn load_checkpoint(&self, _key: &str) -> Result<Option<Vec<u8>>, String> {
+            Ok(None)
+        }
+    }
+
+    /// In-memory checkpoint store
+    #[derive(Default)]
+    struct MemStore {
+        data: StdMutex<HashMap<String, Vec<u8>>>,
+    }
+
+    impl PoHCheckpointStore for MemStore {
+        fn save_checkpoint(&self, key: &str, data: &[u8]) -> Result<(), String> {
+            self.data.lock().unwrap().insert(key.to_string(), data.to_vec());
+            Ok(())
+        }
+        
+        fn load_checkpoint(&self, key: &str) -> Result<Option<Vec<u8>>, String> {
+            Ok(self.data.lock().unwrap().get(key).cloned())
+        }
+    }
+
+    fn entry(num_hashes: u64) -> PoHEntry {
+        PoHEntry { num_hashes, hash: vec![0xAB; 64], data: None, timestamp: 0 }
+    }
+
+    #[test]
+    fn test_benchmark_rate_plausible_and_slow_rate_warns() {
+        let rate = measure_hash_rate(HASHES_PER_TICK);
+        assert!(rate > 1_000, "implausibly slow: {} hashes/sec", rate);
+        assert!(rate < 10_000_000_000, "implausibly fast: {} hashes/sec", rate);
+
+        // Forced-low rate: a quarter of what 1-second blocks need
+        let slow = PoHBenchmark { hashes_per_sec: HASHES_PER_SLOT / 4, required_hashes_per_sec: HASHES_PER_SLOT };
+        assert!(!slow.keeps_up());
+        assert!(slow.warning().unwrap().contains("may fall behind"));
+        let fast = PoHBenchmark { hashes_per_sec: HASHES_PER_SLOT, ..slow };
+        assert!(fast.keeps_up() && fast.warning().is_none());
+
+        assert_eq!(parse_required_hash_rate(None), HASHES_PER_SLOT);
+        assert_eq!(parse_required_hash_rate(Some("750000")), 750_000);
+        assert_eq!(parse_required_hash_rate(Some("0")), HASHES_PER_SLOT);
+        assert_eq!(parse_required_hash_rate(Some("fast")), HASHES_PER_SLOT);
+    }
+
+    #[test]
+    fn test_checkpoint_alert_fires_after_consecutive_failures() {
+        let store = StubStore { full: AtomicBool::new(true), saved: StdMutex::new(Vec::new()) };
+        let mut tracker = PoHCheckpointTracker::new(0, 3);
+
+        // Entries between checkpoint intervals do not touch storage
+        assert!(tracker.process_entry(&store, &entry(5_000_000)).is_none());
+        assert_eq!(tracker.consecutive_failures(), 0);
+
+        assert!(tracker.process_entry(&store, &entry(10_000_000)).is_none());
+        assert!(tracker.process_entry(&store, &entry(20_000_000)).is_none());
+        let alert = tracker.process_entry(&store, &entry(30_000_000)).expect("alert at threshold");
+        assert_eq!(alert.consecutive_failures, 3);
+        assert_eq!(alert.checkpoint_lag, 30_000_000);
+        assert!(alert.last_error.contains("No space"));
+
+        // Space freed: next checkpoint succeeds and clears the failure streak
+        store.full.store(false, Ordering::SeqCst);
+        assert!(tracker.process_entry(&store, &entry(40_000_000)).is_none());
+        assert_eq!(tracker.consecutive_failures(), 0);
+        assert_eq!(tracker.last_checkpoint(), 40_000_000);
+        assert_eq!(tracker.checkpoint_lag(45_000_000), 5_000_000);
+        assert_eq!(*store.saved.lock().unwrap(), vec!["poh_checkpoint_40000000", "poh_checkpoint_latest"]);
+    }
+
+    #[test]
+    fn test_recovery_skips_corrupt_latest_checkpoint() {
+        let store = MemStore::default();
+        let mut tracker = PoHCheckpointTracker::new(0, 3);
+        for count in [10_000_000, 20_000_000, 30_000_000, 40_000_000] {
+            let mut checkpoint = entry(count);
+            checkpoint.hash = vec![(count / CHECKPOINT_INTERVAL_HASHES) as u8; 64];
+            tracker.process_entry(&store, &checkpoint);
+        }
+        assert_eq!(recover_latest_checkpoint(&store).unwrap().num_hashes, 40_000_000);
+
+        // Latest checkpoint corrupted on disk, the one before it lost entirely
+        store.data.lock().unwrap().get_mut("poh_checkpoint_40000000").unwrap()[40] ^= 0xFF;
+        store.data.lock().unwrap().remove("poh_checkpoint_30000000");
+
+        let recovered = recover_latest_checkpoint(&store).expect("falls back instead of genesis");
+        assert_eq!(recovered.num_hashes, 20_000_000);
+        assert_eq!(recovered.hash, vec![2u8; 64]);
+        let index = store.load_checkpoint(CHECKPOINT_INDEX_KEY).unwrap().unwrap();
+        assert_eq!(bincode::deserialize::<u64>(&index).unwrap(), 20_000_000);
+
+        // Checkpoints written before integrity digests still load
+        let legacy = zstd::encode_all(&bincode::serialize(&entry(50_000_000)).unwrap()[..], 3).unwrap();
+        store.save_checkpoint("poh_checkpoint_50000000", &legacy).unwrap();
+        store.save_checkpoint(CHECKPOINT_INDEX_KEY, &bincode::serialize(&50_000_000u64).unwrap()).unwrap();
+        assert_eq!(recover_latest_checkpoint(&store).unwrap().num_hashes, 50_000_000);
+
+        // Nothing intact at all: genesis
+        let empty = MemStore::default();
+        empty.save_checkpoint(CHECKPOINT_INDEX_KEY, &bincode::serialize(&10_000_000u64).unwrap()).unwrap();
+        assert!(recover_latest_checkpoint(&empty).is_none());
+    }
+}
@@ -0,0 +1,338 @@
+//! Operator-triggered resync
+//!
+//! Rolls the local chain back to a height at or above the last finalized
+//! macroblock, then re-requests the blocks above it from the network. Only one
+//! sync may run at a time: a resync claims the same flag as background sync
+//! and releases it when the network sync ends.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use async_trait::async_trait;
+use serde::Serialize;
+
+use crate::node::BlockchainNode;
+
+/// Where a resync is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResyncPhase {
+    Idle,
+    Syncing,
+    Completed,
+    Failed,
+}
+
+/// Progress of the latest resync
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ResyncStatus {
+    pub phase: ResyncPhase,
+    /// Height the chain was rolled back to
+    pub rollback_to: u64,
+    /// Network height the sync is fetching up to
+    pub target_height: u64,
+    /// Local height, refreshed while syncing
+    pub current_height: u64,
+    pub started_at: u64,
+    pub finished_at: Option<u64>,
+    pub error: Option<String>,
+}
+
+impl ResyncStatus {
+    fn idle() -> Self {
+        Self {
+            phase: ResyncPhase::Idle,
+            rollback_to: 0,
+            target_height: 0,
+            current_height: 0,
+            started_at: 0,
+            finished_at: None,
+            error: None,
+        }
+    }
+
+    /// Share of the re-requested range already stored (0-100)
+    pub fn progress_percent(&self) -> u64 {
+        let total = self.target_height.saturating_sub(self.rollback_to);
+        if total == 0 || self.phase == ResyncPhase::Completed {
+            return 100;
+        }
+        self.current_height.saturating_sub(self.rollback_to).min(total) * 100 / total
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ResyncError {
+    #[error("A sync is already in progress")]
+    InProgress,
+
+    #[error("Cannot roll back to {requested}: below last finalized height {finalized}")]
+    BelowFinality { requested: u64, finalized: u64 },
+
+    #[error("Cannot roll back to {requested}: local height is only {local}")]
+    AboveTip { requested: u64, local: u64 },
+
+    #[error("Network height unknown - no peers to resync from")]
+    NetworkHeightUnknown,
+
+    #[error("Rollback failed: {0}")]
+    Rollback(String),
+}
+
+/// Chain a resync rolls back and refills
+#[async_trait]
+pub trait ResyncTarget: Send + Sync + 'static {
+    async fn local_height(&self) -> u64;
+
+    /// Last microblock covered by a stored macroblock
+    fn finalized_height(&self) -> u64;
+
+    async fn network_height(&self) -> Option<u64>;
+
+    /// Remove blocks above `height`
+    async fn rollback_to(&self, height: u64) -> Result<(), String>;
+
+    /// Fetch and store blocks `from..=to` from the network
+    async fn sync_range(&self, from: u64, to: u64) -> Result<(), String>;
+}
+
+#[async_trait]
+impl ResyncTarget for BlockchainNode {
+    async fn local_height(&self) -> u64 {
+        self.get_height().await
+    }
+
+    fn finalized_height(&self) -> u64 {
+        let storage = self.get_storage();
+        let latest = storage.get_latest_macroblock_index().unwrap_or(0);
+        (1..=latest).rev()
+            .find(|&index| matches!(storage.get_macroblock_by_height(index), Ok(Some(_))))
+            .map(|index| index * 90)
+            .unwrap_or(0)
+    }
+
+    async fn network_height(&self) -> Option<u64> {
+        self.get_unified_p2p()?.get_cached_network_height()
+    }
+
+    async fn rollback_to(&self, height: u64) -> Result<(), String> {
+        self.rollback_to_height(height).await.map_err(|e| e.to_string())
+    }
+
+    async fn sync_range(&self, from: u64, to: u64) -> Result<(), String> {
+        self.sync_blocks(from, to).await.map_err(|e| e.to_string())
+    }
+}
+
+/// Rollback height for a resync: `requested`, or the last finalized height
+pub fn plan_rollback(requested: Option<u64>, local: u64, finalized: u64) -> Result<u64, ResyncError> {
+    let height = requested.unwrap_or(finalized);
+    if height < finalized {
+        return Err(ResyncError::BelowFinality { requested: height, finalized });
+    }
+    if height > local {
+        return Err(ResyncError::AboveTip { requested: height, local });
+    }
+    Ok(height)
+}
+
+/// Runs at most one resync and keeps its status
+pub struct ResyncController {
+    /// Shared with background sync so the two never overlap
+    sync_flag: &'static AtomicBool,
+    status: Arc<Mutex<ResyncStatus>>,
+}
+
+impl ResyncController {
+    pub fn new(sync_flag: &'static AtomicBool) -> Self {
+        Self { sync_flag, status: Arc::new(Mutex::new(ResyncStatus::idle())) }
+    }
+
+    /// Roll back and start re-requesting blocks; the network sync continues in the background
+    pub async fn start<T: ResyncTarget>(&self, target: Arc<T>, requested: Option<u64>) -> Result<ResyncStatus, ResyncError> {
+        if self.sync_flag.load(Ordering::SeqCst) {
+            return Err(ResyncError::InProgress);
+        }
+        let local = target.local_height().await;
+        let rollback_to = plan_rollback(requested, local, target.finalized_height())?;
+        let network_height = target.network_height().await.ok_or(ResyncError::NetworkHeightUnknown)?;
+
+        if self.sync_flag.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst).is_err() {
+            return Err(ResyncError::InProgress);
+        }
+
+        println!("[RESYNC] 🔄 Rolling back from {} to {} (network height {})", local, rollback_to, network_height);
+        if let Err(e) = target.rollback_to(rollback_to).await {
+            {
+                let mut status = self.status.lock().unwrap();
+                status.phase = ResyncPhase::Failed;
+                status.finished_at = Some(now_secs());
+                status.error = Some(e.clone());
+            }
+            self.sync_flag.store(false, Ordering::SeqCst);
+            return Err(ResyncError::Rollback(e));
+        }
+
+        let status = ResyncStatus {
+            phase: ResyncPhase::Syncing,
+            rollback_to,
+            target_height: network_height,
+            current_height: rollback_to,
+            started_at: now_secs(),
+            finished_at: None,
+            error: None,
+        };
+        *self.status.lock().unwrap() = status.clone();
+
+        let sync_flag = self.sync_flag;
+        let status_handle = self.status.clone();
+        tokio::spawn(async move {
+            let result = if network_height > rollback_to {
+                target.sync_range(rollback_to + 1, network_height).await
+            } else {
+                Ok(())
+            };
+            let current_height = target.local_height().await;
+            {
+                let mut status = status_handle.lock().unwrap();
+                status.current_height = current_height;
+                status.finished_at = Some(now_secs());
+                match result {
+                    Ok(()) => {
+                        status.phase = ResyncPhase::Completed;
+                        println!("[RESYNC] ✅ Resync completed at height {}", current_height);
+                    }
+                    Err(e) => {
+                        status.phase = ResyncPhase::Failed;
+                        println!("[RESYNC] ❌ Resync failed at height {}: {}", current_height, e);
+                        status.error = Some(e);
+                    }
+                }
+            }
+            sync_flag.store(false, Ordering::SeqCst);
+        });
+
+        Ok(status)
+    }
+
+    /// Latest resync status, with the local height refreshed while syncing
+    pub async fn status<T: ResyncTarget + ?Sized>(&self, target: &T) -> ResyncStatus {
+        let syncing = self.status.lock().unwrap().phase == ResyncPhase::Syncing;
+        let current_height = if syncing { Some(target.local_height().await) } else { None };
+
+        let mut status = self.status.lock().unwrap();
+        if let (Some(height), ResyncPhase::Syncing) = (current_height, status.phase) {
+            status.current_height = height;
+        }
+        status.clone()
+    }
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicU64;
+    use std::time::Duration;
+    use tokio::sync::Semaphore;
+
+    /// Chain stub whose sync stores one block per semaphore permit
+    struct StubChain {
+        height: AtomicU64,
+        finalized: u64,
+        network: u64,
+        permits: Semaphore,
+    }
+
+    impl StubChain {
+        fn new(height: u64, finalized: u64, network: u64) -> Arc<Self> {
+            Arc::new(Self { height: AtomicU64::new(height), finalized, network, permits: Semaphore::new(0) })
+        }
+    }
+
+    #[async_trait]
+    impl ResyncTarget for StubChain {
+        async fn local_height(&self) -> u64 {
+            self.height.load(Ordering::SeqCst)
+        }
+
+        fn finalized_height(&self) -> u64 {
+            self.finalized
+        }
+
+        async fn network_height(&self) -> Option<u64> {
+            Some(self.network)
+        }
+
+        async fn rollback_to(&self, height: u64) -> Result<(), String> {
+            self.height.store(height, Ordering::SeqCst);
+            Ok(())
+        }
+
+        async fn sync_range(&self, from: u64, to: u64) -> Result<(), String> {
+            for height in from..=to {
+                self.permits.acquire().await.unwrap().forget();
+                self.height.store(height, Ordering::SeqCst);
+            }
+            Ok(())
+        }
+    }
+
+    async fn wait_for<F: Fn() -> bool>(condition: F) {
+        for _ in 0..200 {
+            if condition() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        panic!("condition not reached");
+    }
+
+    #[tokio::test]
+    async fn test_resync_below_finality_rejected() {
+        static FLAG: AtomicBool = AtomicBool::new(false);
+        let controller = ResyncController::new(&FLAG);
+        let chain = StubChain::new(150, 90, 200);
+
+        let err = controller.start(chain.clone(), Some(89)).await.unwrap_err();
+        assert_eq!(err, ResyncError::BelowFinality { requested: 89, finalized: 90 });
+        assert_eq!(controller.start(chain.clone(), Some(151)).await.unwrap_err(), ResyncError::AboveTip { requested: 151, local: 150 });
+        assert_eq!(chain.local_height().await, 150);
+        assert!(!FLAG.load(Ordering::SeqCst));
+
+        // A running background sync blocks a resync
+        FLAG.store(true, Ordering::SeqCst);
+        assert_eq!(controller.start(chain.clone(), None).await.unwrap_err(), ResyncError::InProgress);
+        assert_eq!(controller.status(chain.as_ref()).await.phase, ResyncPhase::Idle);
+    }
+
+    #[tokio::test]
+    async fn test_resync_reports_advancing_progress() {
+        static FLAG: AtomicBool = AtomicBool::new(false);
+        let controller = ResyncController::new(&FLAG);
+        let chain = StubChain::new(150, 90, 110);
+
+        // No height given: roll back to the last finalized block
+        let started = controller.start(chain.clone(), None).await.unwrap();
+        assert_eq!((started.phase, started.rollback_to, started.target_height), (ResyncPhase::Syncing, 90, 110));
+        assert_eq!(chain.local_height().await, 90);
+        assert!(FLAG.load(Ordering::SeqCst));
+        assert_eq!(controller.start(chain.clone(), None).await.unwrap_err(), ResyncError::InProgress);
+
+        chain.permits.add_permits(5);
+        wait_for(|| chain.height.load(Ordering::SeqCst) == 95).await;
+        let status = controller.status(chain.as_ref()).await;
+        assert_eq!((status.phase, status.current_height, status.progress_percent()), (ResyncPhase::Syncing, 95, 25));
+
+        chain.permits.add_permits(15);
+        wait_for(|| !FLAG.load(Ordering::SeqCst)).await;
+        let status = controller.status(chain.as_ref()).await;
+        assert_eq!((status.phase, status.current_height, status.progress_percent()), (ResyncPhase::Completed, 110, 100));
+        assert!(status.finished_at.is_some());
+    }
+}
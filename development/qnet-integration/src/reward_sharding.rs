@@ -273,7 +273,7 @@ impl ShardedRewardManager {
                     .duration_since(std::time::UNIX_EPOCH)
                     .unwrap()
                     .as_secs();
-                let since = now - (4 * 60 * 60); // Last 4 hours
+                let since = now - crate::network_config::get_chain_params().reward_window_secs; // Last reward window
                 
                 let ping_history = storage.get_ping_history(&node_id, since)?;
                 
@@ -9,6 +9,7 @@ use serde_json::{json, Value};
 use warp::{Filter, Rejection, Reply};
 use warp::ws::{Message, WebSocket};
 use crate::node::BlockchainNode;
+use crate::block_stream::{BlockStream, parse_block_stream_command, send_json};
 use qnet_state::transaction::BatchTransferData;
 use chrono;
 use sha3::{Sha3_256, Digest}; // Add missing Digest trait
@@ -66,6 +67,19 @@ pub enum WsEvent {
     },
 }
 
+impl WsEvent {
+    /// `NewBlock` event for a stored microblock
+    pub fn new_block(microblock: &qnet_state::MicroBlock) -> Self {
+        WsEvent::NewBlock {
+            height: microblock.height,
+            hash: hex::encode(microblock.hash()),
+            timestamp: microblock.timestamp,
+            tx_count: microblock.transactions.len(),
+            producer: microblock.producer.clone(),
+        }
+    }
+}
+
 /// Global WebSocket event broadcaster
 /// All connected clients receive events through this channel
 pub static WS_BROADCASTER: Lazy<broadcast::Sender<WsEvent>> = Lazy::new(|| {
@@ -381,7 +395,7 @@ fn is_origin_allowed(origin: &str) -> bool {
 /// SECURITY: Validate legacy Genesis EON address format (backward compatibility)
 /// Format: {19 hex}eon{19 hex} = 41 characters (NO checksum)
 /// Used ONLY for Genesis nodes in genesis_constants.rs
-fn validate_legacy_eon_address(address: &str) -> bool {
+pub(crate) fn validate_legacy_eon_address(address: &str) -> bool {
     // Check length: 19 + 3 + 19 = 41 characters
     if address.len() != 41 {
         return false;
@@ -439,7 +453,7 @@ fn validate_eon_address(address: &str) -> bool {
 }
 
 /// SECURITY: Validate address with detailed error
-fn validate_eon_address_with_error(address: &str) -> Result<(), String> {
+pub(crate) fn validate_eon_address_with_error(address: &str) -> Result<(), String> {
     if address.len() != 41 {
         return Err(format!("Invalid address length: expected 41, got {}", address.len()));
     }
@@ -535,6 +549,20 @@ struct TransactionRequest {
     signature: String,
     /// Ed25519 public key for verification (REQUIRED)
     public_key: String,
+    /// Network chain id the transaction is signed for (replay protection)
+    #[serde(default)]
+    chain_id: u64,
+    /// Last block height or timestamp the transaction may be included at (never expires if absent)
+    #[serde(default)]
+    valid_until: Option<qnet_state::transaction::ValidUntil>,
+}
+
+/// Raw signed transaction submitted as bytes built client-side
+/// Layout: `Transaction::raw_signed_bytes` (canonical bytes, hash, signature, public key)
+#[derive(Debug, Deserialize)]
+struct RawTransactionRequest {
+    /// Hex-encoded raw signed transaction
+    raw: String,
 }
 
 /// Query parameters for transaction history API
@@ -566,6 +594,14 @@ fn default_per_page() -> usize { 20 }
 fn default_tx_type() -> String { "all".to_string() }
 fn default_direction() -> String { "all".to_string() }
 
+/// Most addresses accepted by one `POST /api/v1/accounts/batch` request
+const MAX_ACCOUNT_BATCH_SIZE: usize = 100;
+
+#[derive(Debug, Deserialize)]
+struct AccountBatchRequest {
+    addresses: Vec<String>,
+}
+
 #[derive(Debug, Deserialize)]
 struct BatchRewardClaimRequest {
     node_ids: Vec<String>,
@@ -584,6 +620,12 @@ struct BatchTransferRequest {
     signature: String,
     /// Ed25519 public key for verification (REQUIRED)
     public_key: String,
+    /// Network chain id the batch is signed for (replay protection)
+    #[serde(default)]
+    chain_id: u64,
+    /// Last block height or timestamp the batch may be included at (never expires if absent)
+    #[serde(default)]
+    valid_until: Option<qnet_state::transaction::ValidUntil>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -679,6 +721,14 @@ struct ContractStateQuery {
     keys: Option<Vec<String>>,
 }
 
+/// Query parameters for mempool diff polling
+#[derive(Debug, Deserialize)]
+struct MempoolChangesQuery {
+    /// Last sequence the client has seen (0 = from the start of the change log)
+    #[serde(default)]
+    since: u64,
+}
+
 // ContractInfo is now defined in storage.rs as StoredContractInfo
 // Re-export for API compatibility
 pub use crate::storage::StoredContractInfo as ContractInfo;
@@ -788,9 +838,10 @@ pub async fn start_rpc_server(blockchain: BlockchainNode, port: u16) {
     // Microblock by height
     let microblock_one = api_v1
         .and(warp::path("microblock"))
-        .and(warp::path::param::<u64>())
+        .and(warp::path::param::<String>())
         .and(warp::path::end())
         .and(warp::get())
+        .and_then(crate::api_params::height)
         .and(blockchain_filter.clone())
         .and_then(|height: u64, blockchain: Arc<BlockchainNode>| async move {
             // API FIX: Check if height is valid
@@ -849,8 +900,10 @@ pub async fn start_rpc_server(blockchain: BlockchainNode, port: u16) {
         .and(warp::get())
         .and(blockchain_filter.clone())
         .and_then(|params: std::collections::HashMap<String, String>, blockchain: Arc<BlockchainNode>| async move {
-            let from = params.get("from").and_then(|s| s.parse::<u64>().ok()).unwrap_or(0);
-            let to = params.get("to").and_then(|s| s.parse::<u64>().ok()).unwrap_or(from);
+            let (from, to) = match (crate::api_params::query_height(&params, "from"), crate::api_params::query_height(&params, "to")) {
+                (Ok(from), Ok(to)) => (from.unwrap_or(0), to.unwrap_or(from.unwrap_or(0))),
+                (Err(e), _) | (_, Err(e)) => return Ok::<_, Rejection>(e.to_reply().into_response()),
+            };
             let mut items = Vec::new();
             for h in from..=to {
                 if let Ok(Some(data)) = blockchain.load_microblock_bytes(h) {
@@ -858,7 +911,7 @@ pub async fn start_rpc_server(blockchain: BlockchainNode, port: u16) {
                     items.push(json!({"height": h, "data": b64}));
                 }
             }
-            Ok::<_, Rejection>(warp::reply::json(&json!({"from": from, "to": to, "items": items})))
+            Ok::<_, Rejection>(warp::reply::json(&json!({"from": from, "to": to, "items": items})).into_response())
         });
     
     // Account endpoints
@@ -867,24 +920,61 @@ pub async fn start_rpc_server(blockchain: BlockchainNode, port: u16) {
         .and(warp::path::param::<String>())
         .and(warp::path::end())
         .and(warp::get())
+        .and_then(crate::api_params::address)
         .and(blockchain_filter.clone())
         .and_then(handle_account_info);
     
+    // Optional ?height=H returns the balance as of that microblock
     let account_balance = api_v1
-        .and(warp::path("account"))
+        .and(warp::path("account").or(warp::path("accounts")).unify())
         .and(warp::path::param::<String>())
         .and(warp::path("balance"))
         .and(warp::path::end())
         .and(warp::get())
+        .and_then(crate::api_params::address)
+        .and(warp::query::<HashMap<String, String>>())
         .and(blockchain_filter.clone())
         .and_then(handle_account_balance);
     
+    // Combined account view: balance, nonce, node type and pending reward
+    let account_full = api_v1
+        .and(warp::path("account"))
+        .and(warp::path::param::<String>())
+        .and(warp::path("full"))
+        .and(warp::path::end())
+        .and(warp::get())
+        .and_then(crate::api_params::address)
+        .and(blockchain_filter.clone())
+        .and_then(handle_account_full);
+    
+    // Node operated by an address: GET /api/v1/accounts/{address}/node
+    let account_node = api_v1
+        .and(warp::path("accounts"))
+        .and(warp::path::param::<String>())
+        .and(warp::path("node"))
+        .and(warp::path::end())
+        .and(warp::get())
+        .and_then(crate::api_params::address)
+        .and(blockchain_filter.clone())
+        .and_then(handle_account_node);
+    
+    // Batch lookup: POST /api/v1/accounts/batch {"addresses": [...]}
+    let accounts_batch = api_v1
+        .and(warp::path("accounts"))
+        .and(warp::path("batch"))
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(blockchain_filter.clone())
+        .and_then(handle_accounts_batch);
+    
     let account_transactions = api_v1
         .and(warp::path("account"))
         .and(warp::path::param::<String>())
         .and(warp::path("transactions"))
         .and(warp::path::end())
         .and(warp::get())
+        .and_then(crate::api_params::address)
         .and(blockchain_filter.clone())
         .and_then(handle_account_transactions);
     
@@ -910,9 +1000,10 @@ pub async fn start_rpc_server(blockchain: BlockchainNode, port: u16) {
     
     let block_by_height = api_v1
         .and(warp::path("block"))
-        .and(warp::path::param::<u64>())
+        .and(warp::path::param::<String>())
         .and(warp::path::end())
         .and(warp::get())
+        .and_then(crate::api_params::height)
         .and(blockchain_filter.clone())
         .and_then(handle_block_by_height);
     
@@ -922,15 +1013,17 @@ pub async fn start_rpc_server(blockchain: BlockchainNode, port: u16) {
         .and(warp::path::param::<String>())
         .and(warp::path::end())
         .and(warp::get())
+        .and_then(crate::api_params::hash)
         .and(blockchain_filter.clone())
         .and_then(handle_block_by_hash);
     
     // Macroblock endpoint - PRODUCTION
     let macroblock_by_index = api_v1
         .and(warp::path("macroblock"))
-        .and(warp::path::param::<u64>())
+        .and(warp::path::param::<String>())
         .and(warp::path::end())
         .and(warp::get())
+        .and_then(crate::api_params::index)
         .and(blockchain_filter.clone())
         .and_then(handle_macroblock_by_index);
     
@@ -963,11 +1056,32 @@ pub async fn start_rpc_server(blockchain: BlockchainNode, port: u16) {
         .and(blockchain_filter.clone())
         .and_then(handle_transaction_submit);
     
+    let transaction_submit_raw = api_v1
+        .and(warp::path("transactions"))
+        .and(warp::path("raw"))
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(warp::addr::remote())
+        .and(blockchain_filter.clone())
+        .and_then(handle_transaction_submit_raw);
+    
+    let transaction_proof = api_v1
+        .and(warp::path("transactions"))
+        .and(warp::path::param::<String>())
+        .and(warp::path("proof"))
+        .and(warp::path::end())
+        .and(warp::get())
+        .and_then(crate::api_params::hash)
+        .and(blockchain_filter.clone())
+        .and_then(handle_transaction_proof);
+    
     let transaction_get = api_v1
         .and(warp::path("transaction"))
         .and(warp::path::param::<String>())
         .and(warp::path::end())
         .and(warp::get())
+        .and_then(crate::api_params::hash)
         .and(blockchain_filter.clone())
         .and_then(handle_transaction_get);
     
@@ -980,6 +1094,14 @@ pub async fn start_rpc_server(blockchain: BlockchainNode, port: u16) {
         .and(blockchain_filter.clone())
         .and_then(handle_mempool_status);
     
+    let mempool_metrics = api_v1
+        .and(warp::path("mempool"))
+        .and(warp::path("metrics"))
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(blockchain_filter.clone())
+        .and_then(handle_mempool_metrics);
+    
     let mempool_transactions = api_v1
         .and(warp::path("mempool"))
         .and(warp::path("transactions"))
@@ -989,6 +1111,15 @@ pub async fn start_rpc_server(blockchain: BlockchainNode, port: u16) {
         .and(blockchain_filter.clone())
         .and_then(handle_mempool_transactions);
     
+    let mempool_changes = api_v1
+        .and(warp::path("mempool"))
+        .and(warp::path("changes"))
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(warp::query::<MempoolChangesQuery>())
+        .and(blockchain_filter.clone())
+        .and_then(handle_mempool_changes);
+    
     // MEV PROTECTION: Bundle endpoints for private transaction submission
     // ARCHITECTURE: Flashbots-style bundles with 0-20% dynamic allocation
     let bundle_submit = api_v1
@@ -1092,16 +1223,15 @@ pub async fn start_rpc_server(blockchain: BlockchainNode, port: u16) {
             // SCALABILITY: Only help nodes with very few peers to avoid Genesis overload
             // In production with millions of nodes, Genesis nodes should NOT be contacted by everyone
             if peers.len() < 3 {  // SCALABILITY: Only for nodes with < 3 peers (initial bootstrap)
-                use crate::unified_p2p::get_genesis_bootstrap_ips;
-                let genesis_ips = get_genesis_bootstrap_ips();
+                use crate::unified_p2p::get_genesis_bootstrap_addrs;
+                let genesis_addrs = get_genesis_bootstrap_addrs();
                 
                 // SCALABILITY: Only return 2 random Genesis nodes, not all 5
                 // This prevents Genesis nodes from being overwhelmed when millions join
                 let mut selected_genesis = Vec::new();
-                let max_genesis_to_return = std::cmp::min(2, genesis_ips.len());
+                let max_genesis_to_return = std::cmp::min(2, genesis_addrs.len());
                 
-                for (idx, ip) in genesis_ips.iter().enumerate().take(max_genesis_to_return) {
-                    let genesis_addr = format!("{}:8001", ip);
+                for (idx, genesis_addr) in genesis_addrs.into_iter().enumerate().take(max_genesis_to_return) {
                     // Check if not already in list
                     let already_exists = peers.iter().any(|p| p.address == genesis_addr);
                     if !already_exists {
@@ -1176,6 +1306,24 @@ pub async fn start_rpc_server(blockchain: BlockchainNode, port: u16) {
         .and(blockchain_filter.clone())
         .and_then(handle_node_health);
     
+    // Connected peers with reputation and latency (operator connectivity debugging)
+    let node_peers = api_v1
+        .and(warp::path("node"))
+        .and(warp::path("peers"))
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(blockchain_filter.clone())
+        .and_then(handle_node_peers);
+    
+    // Archive chunks assigned to and held by this node
+    let node_archive = api_v1
+        .and(warp::path("node"))
+        .and(warp::path("archive"))
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(blockchain_filter.clone())
+        .and_then(handle_node_archive);
+    
 
     // Gas recommendation endpoints
     let gas_recommendations = api_v1
@@ -1296,6 +1444,82 @@ pub async fn start_rpc_server(blockchain: BlockchainNode, port: u16) {
         .and(blockchain_filter.clone())
         .and_then(handle_get_pending_rewards);
     
+    // Per-pool reward balances
+    let reward_pools = api_v1
+        .and(warp::path("rewards"))
+        .and(warp::path("pools"))
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(blockchain_filter.clone())
+        .and_then(handle_reward_pools);
+    
+    // Node reward claim status (operator view)
+    let node_rewards = api_v1
+        .and(warp::path("node"))
+        .and(warp::path::param::<String>()) // node_id
+        .and(warp::path("rewards"))
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(blockchain_filter.clone())
+        .and_then(handle_node_rewards);
+    
+    // Operator-initiated reward claim (admin API key required)
+    let node_rewards_claim = api_v1
+        .and(warp::path("node"))
+        .and(warp::path::param::<String>()) // node_id
+        .and(warp::path("rewards"))
+        .and(warp::path("claim"))
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(warp::header::optional::<String>("x-api-key"))
+        .and(blockchain_filter.clone())
+        .and_then(handle_node_rewards_claim);
+    
+    // Operator-triggered rollback and resync (admin API key required)
+    let node_admin_resync = api_v1
+        .and(warp::path("node"))
+        .and(warp::path("admin"))
+        .and(warp::path("resync"))
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(warp::header::optional::<String>("x-api-key"))
+        .and(warp::body::content_length_limit(1024))
+        .and(warp::body::bytes())
+        .and(blockchain_filter.clone())
+        .and_then(handle_node_admin_resync);
+    
+    let node_admin_resync_status = api_v1
+        .and(warp::path("node"))
+        .and(warp::path("admin"))
+        .and(warp::path("resync"))
+        .and(warp::path("status"))
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(warp::header::optional::<String>("x-api-key"))
+        .and(blockchain_filter.clone())
+        .and_then(handle_node_admin_resync_status);
+    
+    let node_admin_state_breaker = api_v1
+        .and(warp::path("node"))
+        .and(warp::path("admin"))
+        .and(warp::path("state-breaker"))
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(warp::header::optional::<String>("x-api-key"))
+        .and(blockchain_filter.clone())
+        .and_then(handle_node_admin_state_breaker);
+    
+    let node_admin_state_breaker_ack = api_v1
+        .and(warp::path("node"))
+        .and(warp::path("admin"))
+        .and(warp::path("state-breaker"))
+        .and(warp::path("ack"))
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(warp::header::optional::<String>("x-api-key"))
+        .and(blockchain_filter.clone())
+        .and_then(handle_node_admin_state_breaker_ack);
+    
     // Node registration endpoint
     let register_node = api_v1
         .and(warp::path("nodes"))
@@ -1488,6 +1712,15 @@ pub async fn start_rpc_server(blockchain: BlockchainNode, port: u16) {
         .and(blockchain_filter.clone())
         .and_then(handle_reputation_history);
 
+    // Invalid-block scores per peer (decaying, persisted across restarts)
+    let invalid_block_scores = api_v1
+        .and(warp::path("reputation"))
+        .and(warp::path("invalid-blocks"))
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(blockchain_filter.clone())
+        .and_then(handle_invalid_block_scores);
+
     // Macroblock consensus endpoints
     let consensus_commit = api_v1
         .and(warp::path("consensus"))
@@ -1515,6 +1748,14 @@ pub async fn start_rpc_server(blockchain: BlockchainNode, port: u16) {
         .and(warp::get())
         .and(blockchain_filter.clone())
         .and_then(handle_consensus_round_status);
+    
+    let consensus_metrics = api_v1
+        .and(warp::path("consensus"))
+        .and(warp::path("metrics"))
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(blockchain_filter.clone())
+        .and_then(handle_consensus_metrics);
 
     let consensus_sync = api_v1
         .and(warp::path("consensus"))
@@ -1525,6 +1766,15 @@ pub async fn start_rpc_server(blockchain: BlockchainNode, port: u16) {
         .and(blockchain_filter.clone())
         .and_then(handle_consensus_sync);
     
+    let consensus_macroblock = api_v1
+        .and(warp::path("consensus"))
+        .and(warp::path("macroblock"))
+        .and(warp::path::param::<u64>())
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(blockchain_filter.clone())
+        .and_then(handle_consensus_macroblock);
+    
     // PRODUCTION: P2P message handling endpoint 
     let p2p_message = api_v1
         .and(warp::path("p2p"))
@@ -1639,14 +1889,14 @@ pub async fn start_rpc_server(blockchain: BlockchainNode, port: u16) {
         warp::cors()
             .allow_any_origin()
             .allow_methods(vec!["POST", "GET", "OPTIONS", "PUT", "DELETE"])
-            .allow_headers(vec!["Content-Type", "Authorization", "User-Agent", "X-Requested-With"])
+            .allow_headers(vec!["Content-Type", "Authorization", "User-Agent", "X-Requested-With", "X-API-Key"])
             .max_age(3600)
     } else {
         println!("🔒 CORS: Production mode - restricted origins");
         warp::cors()
             .allow_origins(ALLOWED_ORIGINS.iter().map(|s| *s))
             .allow_methods(vec!["POST", "GET", "OPTIONS"])
-            .allow_headers(vec!["Content-Type", "Authorization", "User-Agent"])
+            .allow_headers(vec!["Content-Type", "Authorization", "User-Agent", "X-API-Key"])
             .max_age(86400) // 24 hours cache
     };
     
@@ -1667,15 +1917,22 @@ pub async fn start_rpc_server(blockchain: BlockchainNode, port: u16) {
         
     let account_routes = account_info
         .or(account_balance)
+        .or(account_full)
+        .or(account_node)
         .or(account_transactions)
+        .or(accounts_batch)
         .or(batch_claim_rewards)
         .or(batch_transfer);
         
     let transaction_routes = transaction_submit
+        .or(transaction_submit_raw)
         .or(transaction_get)
+        .or(transaction_proof)
         .or(transaction_history)  // Extended history API with pagination
         .or(mempool_status)
-        .or(mempool_transactions);
+        .or(mempool_metrics)
+        .or(mempool_transactions)
+        .or(mempool_changes);
     
     let bundle_routes = bundle_submit
         .or(bundle_status)
@@ -1683,6 +1940,8 @@ pub async fn start_rpc_server(blockchain: BlockchainNode, port: u16) {
         
     let node_routes = node_discovery
         .or(node_health)
+        .or(node_peers)
+        .or(node_archive)
         .or(gas_recommendations)
         .or(auth_challenge)
         .or(network_ping)
@@ -1701,7 +1960,8 @@ pub async fn start_rpc_server(blockchain: BlockchainNode, port: u16) {
         .or(pre_execution_status)
         .or(tower_bft_info)
         .or(performance_metrics)
-        .or(reputation_history);
+        .or(reputation_history)
+        .or(invalid_block_scores);
     
     // PUBLIC: Cached endpoints for website (no rate limiting needed)
     let public_routes = public_stats
@@ -1726,6 +1986,13 @@ pub async fn start_rpc_server(blockchain: BlockchainNode, port: u16) {
         .or(light_node_pending_challenge)
         .or(claim_rewards)
         .or(pending_rewards)
+        .or(reward_pools)
+        .or(node_rewards)
+        .or(node_rewards_claim)
+        .or(node_admin_resync)
+        .or(node_admin_resync_status)
+        .or(node_admin_state_breaker)
+        .or(node_admin_state_breaker_ack)
         .or(register_node)
         .or(activations_by_wallet)
         .or(generate_activation_code)
@@ -1734,7 +2001,9 @@ pub async fn start_rpc_server(blockchain: BlockchainNode, port: u16) {
     let consensus_routes = consensus_commit
         .or(consensus_reveal)
         .or(consensus_round_status)
-        .or(consensus_sync);
+        .or(consensus_metrics)
+        .or(consensus_sync)
+        .or(consensus_macroblock);
     
     let p2p_routes = p2p_message;
     
@@ -1789,8 +2058,16 @@ pub async fn start_rpc_server(blockchain: BlockchainNode, port: u16) {
         .and(warp::get())
         .map(|| warp::reply::with_status("OK", warp::http::StatusCode::OK));
     
+    // Prometheus scrape endpoint (text exposition format)
+    let prometheus_metrics = warp::path("metrics")
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(blockchain_filter.clone())
+        .and_then(handle_prometheus_metrics);
+    
     // Combine route groups
     let routes = health
+        .or(prometheus_metrics)
         .or(ws_subscribe) // WebSocket before REST routes
         .or(basic_routes)
         .or(blockchain_routes)
@@ -1804,12 +2081,27 @@ pub async fn start_rpc_server(blockchain: BlockchainNode, port: u16) {
         .or(p2p_routes)
         .or(monitoring_routes)
         .or(public_routes) // PUBLIC: Cached endpoints for website
+        .recover(crate::api_params::recover); // Malformed path/query parameters -> 400
+    
+    // gzip/zstd per Accept-Encoding above a size threshold; /metrics and streamed bodies untouched
+    let compression = crate::api_compression::CompressionConfig::from_env();
+    println!("[API] 🗜️ Response compression: {} (min {} bytes, excluded: {:?})", 
+             if compression.enabled { "ENABLED" } else { "DISABLED" }, compression.min_bytes, compression.excluded_paths);
+    let routes = crate::api_compression::with_compression(compression, routes)
         .with(cors);
     
-    println!("🚀 Starting comprehensive API server on port {}", port);
-    println!("📡 JSON-RPC available at: http://0.0.0.0:{}/rpc", port);
-    println!("🔌 REST API available at: http://0.0.0.0:{}/api/v1/", port);
-    println!("🔗 WebSocket available at: ws://0.0.0.0:{}/ws/subscribe", port);
+    let listen = match crate::listen_addr::ListenConfig::from_env(port) {
+        Ok(listen) => listen,
+        Err(e) => {
+            println!("❌ API server not started: {}", e);
+            return;
+        }
+    };
+    
+    println!("🚀 Starting comprehensive API server on {}", listen.bind);
+    println!("📡 JSON-RPC available at: http://{}/rpc", listen.bind);
+    println!("🔌 REST API available at: http://{}/api/v1/", listen.bind);
+    println!("🔗 WebSocket available at: ws://{}/ws/subscribe", listen.bind);
     println!("📱 Light Node services: Registration, FCM Push, Reward Claims");
     println!("🏛️ Macroblock Consensus: Commit-Reveal, Byzantine Fault Tolerance");
     println!("📜 Smart Contract API: Deploy, Call, Query");
@@ -1822,7 +2114,7 @@ pub async fn start_rpc_server(blockchain: BlockchainNode, port: u16) {
         println!("🕐 Light node randomized ping service started");
     }
     
-    warp::serve(routes).run(([0, 0, 0, 0], port)).await;
+    warp::serve(routes).run(listen.bind).await;
 }
 
 async fn handle_rpc(
@@ -1947,11 +2239,14 @@ async fn node_get_peers(blockchain: Arc<BlockchainNode>) -> Result<Value, RpcErr
         })
     }).collect();
     
+    let discovery = blockchain.get_unified_p2p().map(|p2p| p2p.discovery_status());
+    
     Ok(json!({
         "count": peer_count,
         "peers": peer_list,
         "max_peers": 50,
-        "connection_status": "healthy"
+        "connection_status": "healthy",
+        "discovery": discovery
     }))
 }
 
@@ -2007,6 +2302,17 @@ async fn chain_get_blocks(
     Ok(json!(blocks))
 }
 
+/// Optional `valid_until` param: `{"height": n}` or `{"timestamp": t}`
+fn parse_valid_until(value: &Value) -> Result<Option<qnet_state::transaction::ValidUntil>, RpcError> {
+    if value.is_null() {
+        return Ok(None);
+    }
+    serde_json::from_value(value.clone()).map(Some).map_err(|e| RpcError {
+        code: -32602,
+        message: format!("Invalid valid_until: {}", e),
+    })
+}
+
 async fn tx_submit(
     blockchain: Arc<BlockchainNode>,
     params: Option<Value>,
@@ -2034,6 +2340,9 @@ async fn tx_submit(
     
     let gas_price = params["gas_price"].as_u64().unwrap_or(1);
     let gas_limit = params["gas_limit"].as_u64().unwrap_or(10_000); // QNet TRANSFER gas limit
+    // Replay protection: unbound transactions are rejected at admission
+    let chain_id = params["chain_id"].as_u64().unwrap_or(qnet_state::transaction::UNBOUND_CHAIN_ID);
+    let valid_until = parse_valid_until(&params["valid_until"])?;
     
     // PRODUCTION: Require signature for all transactions
     let signature = params["signature"].as_str().ok_or_else(|| RpcError {
@@ -2065,6 +2374,8 @@ async fn tx_submit(
             amount,
         },
         data: None, // no data for simple transfer
+        chain_id,
+        valid_until,
     };
     
     // Calculate hash
@@ -2189,6 +2500,8 @@ async fn mempool_submit(
         
         let nonce = tx_data["nonce"].as_u64().unwrap_or(0);
         let timestamp = tx_data["timestamp"].as_u64().unwrap_or_else(|| chrono::Utc::now().timestamp() as u64);
+        let chain_id = tx_data["chain_id"].as_u64().unwrap_or(qnet_state::transaction::UNBOUND_CHAIN_ID);
+        let valid_until = parse_valid_until(&tx_data["valid_until"])?;
         
         // PRODUCTION: Require signature
         let signature = tx_data["signature"].as_str().ok_or_else(|| RpcError {
@@ -2220,6 +2533,8 @@ async fn mempool_submit(
                 amount,
             },
             data: None, // no data for simple transfer
+            chain_id,
+            valid_until,
         };
         
         // Calculate hash
@@ -2429,21 +2744,105 @@ async fn handle_account_info(
 
 async fn handle_account_balance(
     address: String,
+    params: HashMap<String, String>,
     blockchain: Arc<BlockchainNode>,
 ) -> Result<impl Reply, Rejection> {
-    match blockchain.get_balance(&address).await {
-        Ok(balance) => Ok(warp::reply::json(&json!({
-            "address": address,
-            "balance": balance
-        }))),
-        Err(e) => {
-            let error_response = json!({
+    let height = match crate::api_params::query_height(&params, "height") {
+        Ok(height) => height,
+        Err(e) => return Ok(e.to_reply()),
+    };
+    if let Some(height) = height {
+        
+        let (response, status) = match blockchain.get_account_at(&address, height).await {
+            Ok(account) => (json!({
+                "address": address,
+                "height": height,
+                "balance": account.map(|acc| acc.balance).unwrap_or(0)
+            }), warp::http::StatusCode::OK),
+            Err(e @ qnet_state::StateError::HistoryUnavailable { .. }) => (json!({
+                "error": "Historical state pruned or unavailable",
+                "details": e.to_string()
+            }), warp::http::StatusCode::GONE),
+            Err(e) => (json!({
                 "error": "Failed to get balance",
                 "details": e.to_string()
-            });
-            Ok(warp::reply::json(&error_response))
-        }
+            }), warp::http::StatusCode::BAD_REQUEST),
+        };
+        return Ok(warp::reply::with_status(warp::reply::json(&response), status));
+    }
+    
+    let response = match blockchain.get_balance(&address).await {
+        Ok(balance) => json!({
+            "address": address,
+            "balance": balance
+        }),
+        Err(e) => json!({
+            "error": "Failed to get balance",
+            "details": e.to_string()
+        }),
+    };
+    Ok(warp::reply::with_status(warp::reply::json(&response), warp::http::StatusCode::OK))
+}
+
+async fn handle_account_full(
+    address: String,
+    blockchain: Arc<BlockchainNode>,
+) -> Result<impl Reply, Rejection> {
+    let summary = blockchain.get_account_summary(&address).await;
+    Ok(warp::reply::json(&summary))
+}
+
+async fn handle_account_node(
+    address: String,
+    blockchain: Arc<BlockchainNode>,
+) -> Result<impl Reply, Rejection> {
+    let (response, status) = match blockchain.get_node_by_wallet(&address) {
+        Ok(node) => (json!({
+            "address": address,
+            "is_node": node.is_some(),
+            "node_id": node.as_ref().map(|(node_id, _)| node_id),
+            "node_type": node.as_ref().map(|(_, node_type)| format!("{:?}", node_type).to_lowercase())
+        }), warp::http::StatusCode::OK),
+        Err(e) => (json!({
+            "error": "Failed to look up node registration",
+            "details": e.to_string()
+        }), warp::http::StatusCode::INTERNAL_SERVER_ERROR),
+    };
+    Ok(warp::reply::with_status(warp::reply::json(&response), status))
+}
+
+async fn handle_accounts_batch(
+    request: AccountBatchRequest,
+    blockchain: Arc<BlockchainNode>,
+) -> Result<impl Reply, Rejection> {
+    if request.addresses.is_empty() || request.addresses.len() > MAX_ACCOUNT_BATCH_SIZE {
+        return Ok(warp::reply::with_status(
+            warp::reply::json(&json!({
+                "success": false,
+                "error": format!("Batch must contain 1-{} addresses, got {}", MAX_ACCOUNT_BATCH_SIZE, request.addresses.len())
+            })),
+            warp::http::StatusCode::BAD_REQUEST,
+        ));
     }
+    
+    if let Some(e) = request.addresses.iter().find_map(|address| crate::api_params::parse_address("addresses", address).err()) {
+        return Ok(e.to_reply());
+    }
+    
+    let addresses: Vec<&str> = request.addresses.iter().map(|address| address.as_str()).collect();
+    let (response, status) = match blockchain.get_accounts(&addresses).await {
+        Ok(accounts) => (json!({
+            "success": true,
+            "count": accounts.len(),
+            "accounts": accounts
+        }), warp::http::StatusCode::OK),
+        Err(e) => (json!({
+            "success": false,
+            "error": "Failed to get accounts",
+            "details": e.to_string()
+        }), warp::http::StatusCode::INTERNAL_SERVER_ERROR),
+    };
+    Ok(warp::reply::with_status(warp::reply::json(&response), status))
 }
 
 async fn handle_account_transactions(
@@ -2503,6 +2902,9 @@ async fn handle_transaction_history(
     blockchain: Arc<BlockchainNode>,
 ) -> Result<impl Reply, Rejection> {
     // Validate parameters
+    if let Err(e) = crate::api_params::parse_address("address", &query.address) {
+        return Ok(e.to_reply());
+    }
     let page = if query.page == 0 { 1 } else { query.page };
     let per_page = query.per_page.min(100).max(1); // Clamp to 1-100
     
@@ -2606,7 +3008,7 @@ async fn handle_transaction_history(
                     "end_time": query.end_time
                 }
             });
-            Ok(warp::reply::json(&response))
+            Ok(warp::reply::with_status(warp::reply::json(&response), warp::http::StatusCode::OK))
         }
         Err(e) => {
             println!("[API] ❌ Transaction history error for {}: {}", query.address, e);
@@ -2615,7 +3017,7 @@ async fn handle_transaction_history(
                 "error": format!("Failed to fetch transaction history: {}", e),
                 "address": query.address
             });
-            Ok(warp::reply::json(&error_response))
+            Ok(warp::reply::with_status(warp::reply::json(&error_response), warp::http::StatusCode::OK))
         }
     }
 }
@@ -2722,6 +3124,43 @@ async fn handle_block_by_hash(
     }
 }
 
+/// GET /api/v1/consensus/macroblock/{index}
+/// Participants, commits and reveals that finalized a macroblock, for audits and fork choice
+async fn handle_consensus_macroblock(
+    index: u64,
+    blockchain: Arc<BlockchainNode>,
+) -> Result<impl Reply, Rejection> {
+    let (response, status) = match blockchain.get_macroblock_consensus(index) {
+        Ok(Some(consensus_data)) => {
+            let participants: std::collections::BTreeSet<&String> = consensus_data.commits.keys()
+                .chain(consensus_data.reveals.keys())
+                .collect();
+            let hex_map = |entries: &HashMap<String, Vec<u8>>| entries.iter()
+                .map(|(node, data)| (node.clone(), hex::encode(data)))
+                .collect::<std::collections::BTreeMap<_, _>>();
+            (json!({
+                "index": index,
+                "first_block": index.saturating_sub(1) * 90 + 1,
+                "last_block": index * 90,
+                "participants": participants,
+                "commits": hex_map(&consensus_data.commits),
+                "reveals": hex_map(&consensus_data.reveals),
+                "next_leader": consensus_data.next_leader,
+            }), warp::http::StatusCode::OK)
+        }
+        Ok(None) => (json!({
+            "error": "Macroblock not found",
+            "index": index,
+            "info": format!("Macroblock #{} has not been finalized on this node", index)
+        }), warp::http::StatusCode::NOT_FOUND),
+        Err(e) => (json!({
+            "error": "Failed to load macroblock consensus",
+            "details": e.to_string()
+        }), warp::http::StatusCode::INTERNAL_SERVER_ERROR),
+    };
+    Ok(warp::reply::with_status(warp::reply::json(&response), status))
+}
+
 async fn handle_macroblock_by_index(
     index: u64,
     blockchain: Arc<BlockchainNode>,
@@ -2902,11 +3341,13 @@ async fn handle_transaction_submit(
     // =========================================================================
     
     // Build message to verify (canonical format)
-    let message_to_sign = format!("transfer:{}:{}:{}:{}", 
+    let message_to_sign = format!("transfer:{}:{}:{}:{}:{}{}", 
         tx_request.from, 
         tx_request.to,
         tx_request.amount,
-        tx_request.nonce
+        tx_request.nonce,
+        tx_request.chain_id,
+        signed_valid_until(tx_request.valid_until)
     );
     
     // Verify Ed25519 signature
@@ -2924,7 +3365,7 @@ async fn handle_transaction_submit(
             "success": false,
             "error": "Signature verification failed (NIST FIPS 186-5)",
             "details": "Ed25519 signature does not match the transaction data",
-            "message_format": "transfer:{from}:{to}:{amount}:{nonce}"
+            "message_format": "transfer:{from}:{to}:{amount}:{nonce}:{chain_id}[:height:{h}|:timestamp:{t}]"
         })));
     }
     
@@ -2952,7 +3393,11 @@ async fn handle_transaction_submit(
             "public_key": tx_request.public_key,
             "standard": "NIST FIPS 186-5 (Ed25519)"
         })).unwrap_or_default()),
-    );
+    ).with_chain_id(tx_request.chain_id);
+    let tx = match tx_request.valid_until {
+        Some(valid_until) => tx.with_valid_until(valid_until),
+        None => tx,
+    };
 
     // Convert to JSON and add to mempool
     match serde_json::to_string(&tx) {
@@ -2990,6 +3435,102 @@ async fn handle_transaction_submit(
     }
 }
 
+async fn handle_transaction_submit_raw(
+    request: RawTransactionRequest,
+    remote_addr: Option<std::net::SocketAddr>,
+    blockchain: Arc<BlockchainNode>,
+) -> Result<impl Reply, Rejection> {
+    if let Err(rate_limit_response) = check_api_rate_limit(remote_addr, "transaction") {
+        return Ok(rate_limit_response);
+    }
+    
+    let raw = request.raw.trim();
+    let bytes = match hex::decode(raw.strip_prefix("0x").unwrap_or(raw)) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            return Ok(warp::reply::json(&json!({
+                "success": false,
+                "error": "Raw transaction is not valid hex",
+                "details": e.to_string()
+            })));
+        }
+    };
+    
+    // Parses the canonical fields, then checks the hash and Ed25519 signature over it
+    let tx = match qnet_state::Transaction::decode_raw_signed(&bytes) {
+        Ok(tx) => tx,
+        Err(e) => {
+            let error = match e {
+                qnet_state::RawTransactionError::HashMismatch { .. } => "Transaction hash mismatch",
+                qnet_state::RawTransactionError::InvalidPublicKey
+                | qnet_state::RawTransactionError::InvalidSignature => "Signature verification failed (NIST FIPS 186-5)",
+                _ => "Malformed raw transaction",
+            };
+            return Ok(warp::reply::json(&json!({
+                "success": false,
+                "error": error,
+                "details": e.to_string()
+            })));
+        }
+    };
+    
+    for (label, address) in [("sender", tx.from.as_str()), ("recipient", tx.to.as_deref().unwrap_or(""))] {
+        if let Err(e) = validate_eon_address_with_error(address) {
+            return Ok(warp::reply::json(&json!({
+                "success": false,
+                "error": format!("Invalid {} address", label),
+                "details": e
+            })));
+        }
+    }
+    
+    let tx_hash = tx.hash.clone();
+    match blockchain.add_transaction_to_mempool(tx).await {
+        Ok(_) => Ok(warp::reply::json(&json!({
+            "success": true,
+            "tx_hash": tx_hash,
+            "message": "Transaction submitted successfully"
+        }))),
+        Err(e) => Ok(warp::reply::json(&json!({
+            "success": false,
+            "error": "Failed to add transaction to mempool",
+            "details": e.to_string()
+        }))),
+    }
+}
+
+/// GET /api/v1/transactions/{hash}/proof
+/// Merkle inclusion proof against the block's merkle_root, checkable with
+/// `qnet_state::verify_transaction_inclusion`
+async fn handle_transaction_proof(
+    tx_hash: String,
+    blockchain: Arc<BlockchainNode>,
+) -> Result<impl Reply, Rejection> {
+    match blockchain.get_transaction_proof(&tx_hash) {
+        Ok(Some(inclusion)) => Ok(warp::reply::json(&json!({
+            "success": true,
+            "tx_hash": tx_hash,
+            "block_height": inclusion.height,
+            "merkle_root": hex::encode(inclusion.merkle_root),
+            "proof": {
+                "leaf_index": inclusion.proof.leaf_index,
+                "siblings": inclusion.proof.siblings.iter().map(hex::encode).collect::<Vec<_>>(),
+            }
+        }))),
+        Ok(None) => Ok(warp::reply::json(&json!({
+            "success": false,
+            "tx_hash": tx_hash,
+            "error": "Transaction not found in any stored block"
+        }))),
+        Err(e) => Ok(warp::reply::json(&json!({
+            "success": false,
+            "tx_hash": tx_hash,
+            "error": "Cannot build inclusion proof",
+            "details": e.to_string()
+        }))),
+    }
+}
+
 async fn handle_transaction_get(
     tx_hash: String,
     blockchain: Arc<BlockchainNode>,
@@ -3028,10 +3569,11 @@ async fn handle_transaction_get(
                 });
             }
             
+            // "pending" while in the mempool, "confirmed" once stored in a block
             let response = json!({
                 "tx_hash": tx_hash,
                 "transaction": transaction_data,
-                "status": "found"
+                "status": tx.status
             });
             Ok(warp::reply::json(&response))
         }
@@ -3039,7 +3581,7 @@ async fn handle_transaction_get(
             let response = json!({
                 "tx_hash": tx_hash,
                 "transaction": null,
-                "status": "not_found",
+                "status": "unknown",
                 "message": "Transaction not found in blockchain or mempool"
             });
             Ok(warp::reply::json(&response))
@@ -3071,6 +3613,48 @@ async fn handle_mempool_status(
     Ok(warp::reply::json(&response))
 }
 
+/// GET /api/v1/mempool/metrics
+/// Pool depth, evictions since start, oldest entry age and average gas price
+async fn handle_mempool_metrics(
+    blockchain: Arc<BlockchainNode>,
+) -> Result<impl Reply, Rejection> {
+    let metrics = blockchain.get_mempool().read().await.metrics();
+    Ok(warp::reply::json(&metrics))
+}
+
+async fn handle_consensus_metrics(
+    blockchain: Arc<BlockchainNode>,
+) -> Result<impl Reply, Rejection> {
+    let metrics = blockchain.get_consensus().read().await.metrics();
+    Ok(warp::reply::json(&metrics))
+}
+
+async fn handle_prometheus_metrics(
+    blockchain: Arc<BlockchainNode>,
+) -> Result<impl Reply, Rejection> {
+    use prometheus::Encoder;
+    
+    // Gauges are refreshed on scrape so an idle engine still reports its state
+    blockchain.get_consensus().read().await.metrics().publish();
+    blockchain.get_mempool().read().await.metrics();
+    blockchain.get_storage().network_size_estimate().publish();
+    
+    let encoder = prometheus::TextEncoder::new();
+    let mut buffer = Vec::new();
+    if let Err(e) = encoder.encode(&prometheus::gather(), &mut buffer) {
+        println!("[METRICS] ⚠️ Failed to encode Prometheus metrics: {}", e);
+        return Ok(warp::reply::with_status(
+            String::new(),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        ).into_response());
+    }
+    Ok(warp::reply::with_header(
+        String::from_utf8_lossy(&buffer).into_owned(),
+        "content-type",
+        encoder.format_type(),
+    ).into_response())
+}
+
 async fn handle_mempool_transactions(
     blockchain: Arc<BlockchainNode>,
 ) -> Result<impl Reply, Rejection> {
@@ -3084,6 +3668,24 @@ async fn handle_mempool_transactions(
     Ok(warp::reply::json(&response))
 }
 
+/// GET /api/v1/mempool/changes?since=SEQ
+/// Transactions added and hashes removed since a sequence, for incremental polling
+async fn handle_mempool_changes(
+    query: MempoolChangesQuery,
+    blockchain: Arc<BlockchainNode>,
+) -> Result<impl Reply, Rejection> {
+    let changes = blockchain.get_mempool().read().await.changes_since(query.since);
+    
+    let response = json!({
+        "sequence": changes.sequence,
+        "full_resync": changes.full_resync,
+        "added": changes.added,
+        "removed": changes.removed,
+        "node_id": blockchain.get_public_display_name()
+    });
+    Ok(warp::reply::json(&response))
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 // MEV PROTECTION HANDLERS
 // ═══════════════════════════════════════════════════════════════════════════
@@ -3392,6 +3994,8 @@ async fn handle_batch_claim_rewards(
                     gas_limit: 0, // No gas for rewards
                     nonce: 0,
                     data: Some(format!("Claim for node: {}", node_id)), // Track which node claimed
+                    chain_id: qnet_state::transaction::UNBOUND_CHAIN_ID, // System payout
+                    valid_until: None,
                 };
                 
                 // Calculate hash using blake3 (EXISTING method)
@@ -3497,11 +4101,13 @@ async fn handle_batch_transfer(
     let nonce = timestamp; // Use timestamp as nonce for batch transfers
     
     // Build message to verify (canonical format for batch)
-    let message_to_sign = format!("batch_transfer:{}:{}:{}:{}", 
+    let message_to_sign = format!("batch_transfer:{}:{}:{}:{}:{}{}", 
         from_address, 
         total_amount,
         request.transfers.len(),
-        request.batch_id
+        request.batch_id,
+        request.chain_id,
+        signed_valid_until(request.valid_until)
     );
     
     // Verify Ed25519 signature
@@ -3519,7 +4125,7 @@ async fn handle_batch_transfer(
             "success": false,
             "error": "Signature verification failed (NIST FIPS 186-5)",
             "details": "Ed25519 signature does not match the batch data",
-            "message_format": "batch_transfer:{from}:{total_amount}:{transfer_count}:{batch_id}"
+            "message_format": "batch_transfer:{from}:{total_amount}:{transfer_count}:{batch_id}:{chain_id}[:height:{h}|:timestamp:{t}]"
         })));
     }
     
@@ -3548,7 +4154,11 @@ async fn handle_batch_transfer(
             "public_key": request.public_key,
             "standard": "NIST FIPS 186-5 (Ed25519)"
         })).unwrap_or_default()),
-    );
+    ).with_chain_id(request.chain_id);
+    let batch_tx = match request.valid_until {
+        Some(valid_until) => batch_tx.with_valid_until(valid_until),
+        None => batch_tx,
+    };
     
     // Submit batch transaction to blockchain
     match blockchain.submit_transaction(batch_tx).await {
@@ -3614,6 +4224,53 @@ async fn handle_node_discovery(
     Ok(warp::reply::json(&response))
 }
 
+/// GET /api/v1/node/peers
+/// Connected peer table with reputation, latency, direction and counts by region/node type
+async fn handle_node_peers(
+    blockchain: Arc<BlockchainNode>,
+) -> Result<impl Reply, Rejection> {
+    match blockchain.get_unified_p2p() {
+        Some(p2p) => {
+            let report = p2p.peer_table_report();
+            Ok(warp::reply::json(&json!({
+                "success": true,
+                "peers": report.peers,
+                "total": report.total,
+                "by_region": report.by_region,
+                "by_node_type": report.by_node_type,
+            })))
+        }
+        None => Ok(warp::reply::json(&json!({
+            "success": false,
+            "error": "P2P not initialized"
+        }))),
+    }
+}
+
+/// GET /api/v1/node/archive
+/// Archive quota, assigned and held chunks, and network replication health
+async fn handle_node_archive(
+    blockchain: Arc<BlockchainNode>,
+) -> Result<impl Reply, Rejection> {
+    match blockchain.get_archive_report().await {
+        Ok(report) => Ok(warp::reply::json(&json!({
+            "success": true,
+            "node_id": report.node_id,
+            "quota": report.quota,
+            "mandatory_quota": report.mandatory_quota,
+            "compliance_status": report.compliance_status,
+            "assigned_chunks": report.assigned_chunks.iter().map(hex::encode).collect::<Vec<_>>(),
+            "held_chunks": report.held_chunks.iter().map(hex::encode).collect::<Vec<_>>(),
+            "replication": report.network,
+        }))),
+        Err(e) => Ok(warp::reply::json(&json!({
+            "success": false,
+            "error": "Cannot read archive state",
+            "details": e.to_string()
+        }))),
+    }
+}
+
 async fn handle_node_health(
     blockchain: Arc<BlockchainNode>,
 ) -> Result<impl Reply, Rejection> {
@@ -3722,6 +4379,10 @@ async fn handle_gas_recommendations(
         _ => "very_high",
     };
     
+    // ANTI-SPAM: Mempool admission floor (rises with congestion); nothing below it is accepted
+    let min_gas_price = blockchain.get_mempool().read().await.effective_min_gas_price();
+    let base_fee = base_fee.max(min_gas_price);
+    
     // QNet-specific gas recommendations (optimized for mobile)
     let eco_price = base_fee;
     let standard_price = (base_fee as f64 * 1.5) as u64;
@@ -3767,6 +4428,7 @@ async fn handle_gas_recommendations(
         "mempool_size": mempool_size,
         "current_height": current_height,
         "base_fee": base_fee,
+        "min_gas_price": min_gas_price,
         "node_id": blockchain.get_node_id()
     });
     Ok(warp::reply::json(&response))
@@ -3832,13 +4494,25 @@ async fn handle_network_ping(
     })))
 }
 
+/// Signed message suffix for an expiring transaction: ":height:{h}" or ":timestamp:{t}", empty if none
+fn signed_valid_until(valid_until: Option<qnet_state::transaction::ValidUntil>) -> String {
+    use qnet_state::transaction::ValidUntil;
+    match valid_until {
+        Some(ValidUntil::Height(height)) => format!(":height:{}", height),
+        Some(ValidUntil::Timestamp(timestamp)) => format!(":timestamp:{}", timestamp),
+        None => String::new(),
+    }
+}
+
 // PRODUCTION: Quantum-secure signature verification using CRYSTALS-Dilithium
 /// PRODUCTION: Verify Ed25519 signature from client (mobile/browser)
 /// Generic function - message is passed directly, NOT constructed internally
 /// This allows different message formats for different operations:
-/// - Transfers: "transfer:{from}:{to}:{amount}:{nonce}"
+/// - Transfers: "transfer:{from}:{to}:{amount}:{nonce}:{chain_id}"
 /// - Reward claims: "claim_rewards:{node_id}:{wallet}"
-/// - Batch transfers: "batch_transfer:{from}:{total}:{count}:{batch_id}"
+/// - Batch transfers: "batch_transfer:{from}:{total}:{count}:{batch_id}:{chain_id}"
+///
+/// Transfers with an expiry append `signed_valid_until` to the message
 async fn verify_ed25519_client_signature(
     _context: &str,        // For logging only (e.g., "from", "node_id")
     message: &str,         // ACTUAL message that was signed by client
@@ -4349,6 +5023,7 @@ async fn handle_poh_status(blockchain: Arc<BlockchainNode>) -> Result<impl warp:
         "enabled": enabled,
         "algorithm": ["SHA3-512", "Blake3"],
         "hash_rate": hash_rate_str,
+        "benchmark": crate::quantum_poh::startup_benchmark(),
         "status": status
     });
     
@@ -4390,24 +5065,27 @@ async fn handle_pre_execution_status(blockchain: Arc<BlockchainNode>) -> Result<
 // Handler for Tower BFT timeouts
 async fn handle_tower_bft_timeouts(blockchain: Arc<BlockchainNode>) -> Result<impl warp::Reply, warp::Rejection> {
     let current_height = blockchain.get_height().await;
+    let tower_bft = blockchain.get_tower_bft();
     
-    let timeout_block_1 = blockchain.get_tower_bft().get_timeout(1, 0).await;
-    let timeout_block_10 = blockchain.get_tower_bft().get_timeout(10, 0).await;
-    let timeout_current = blockchain.get_tower_bft().get_timeout(current_height, 0).await;
+    let timeout_block_1 = tower_bft.get_timeout(1, 0).await;
+    let timeout_block_10 = tower_bft.get_timeout(10, 0).await;
+    let timeout_current = tower_bft.get_timeout(current_height, 0).await;
+    let config = tower_bft.config();
     
     let info = json!({
         "enabled": true,
         "current_height": current_height,
+        "current_timeout_ms": tower_bft.current_timeout().as_millis(),
         "timeouts": {
             "block_1": timeout_block_1.as_millis(),
             "block_10": timeout_block_10.as_millis(),
             "current_block": timeout_current.as_millis(),
         },
         "config": {
-            "base_timeout_ms": 7000,
-            "timeout_multiplier": 1.5,
-            "max_timeout_ms": 20000,
-            "min_timeout_ms": 1000,
+            "base_timeout_ms": config.base_timeout_ms,
+            "timeout_multiplier": config.timeout_multiplier,
+            "max_timeout_ms": config.max_timeout_ms,
+            "min_timeout_ms": config.min_timeout_ms,
         },
         "status": "active"
     });
@@ -4439,12 +5117,13 @@ async fn handle_light_node_ping_response(
     
     let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
     let current_slot = SimplifiedP2P::get_current_slot();
+    let window_start = crate::network_config::get_chain_params().reward_window_start(now);
     let our_node_id = blockchain.get_node_id();
     
-    // Check if attestation already exists for this slot (prevent duplicates)
+    // Check if attestation already exists for this window (prevent duplicates)
     if let Some(p2p) = blockchain.get_unified_p2p() {
-        if p2p.has_attestation(&node_id, current_slot) {
-            println!("[LIGHT] ⚠️ Attestation already exists for {} in slot {}", node_id, current_slot);
+        if p2p.has_attestation(&node_id, window_start) {
+            println!("[LIGHT] ⚠️ Attestation already exists for {} in window {}", node_id, window_start);
             return Ok(warp::reply::json(&json!({
                 "success": true,
                 "node_id": node_id,
@@ -4495,6 +5174,7 @@ async fn handle_light_node_ping_response(
             pinger_id: our_node_id.clone(),
             slot: current_slot,
             timestamp: now,
+            window_start,
             light_node_signature: signature.clone(), // Light node's actual signature!
             pinger_signature,
             challenge: challenge.clone(),
@@ -4677,14 +5357,14 @@ async fn handle_light_node_pending_challenge(
             if crate::unified_p2p::SimplifiedP2P::is_light_node_ping_slot(&node_id) {
                 // Check if attestation already exists
                 if let Some(p2p) = blockchain.get_unified_p2p() {
-                    let current_slot = crate::unified_p2p::SimplifiedP2P::get_current_slot();
-                    if p2p.has_attestation(&node_id, current_slot) {
+                    let current_window_start = crate::unified_p2p::SimplifiedP2P::get_current_window_start();
+                    if p2p.has_attestation(&node_id, current_window_start) {
                         return Ok(warp::reply::json(&json!({
                             "success": true,
                             "node_id": node_id,
                             "has_challenge": false,
                             "already_attested": true,
-                            "message": "Already attested in current slot"
+                            "message": "Already attested in current window"
                         })));
                     }
                 }
@@ -4879,10 +5559,10 @@ async fn handle_light_node_status(
         
         if let Some(node) = registry.get(&node_id) {
             let (next_ping_time, window_number) = crate::unified_p2p::SimplifiedP2P::get_next_ping_time(&node_id);
-            let current_slot = crate::unified_p2p::SimplifiedP2P::get_current_slot();
+            let current_window_start = crate::unified_p2p::SimplifiedP2P::get_current_window_start();
             
             // Check if has attestation in current window
-            let has_attestation = p2p.has_attestation(&node_id, current_slot);
+            let has_attestation = p2p.has_attestation(&node_id, current_window_start);
             
             return Ok(warp::reply::json(&json!({
                 "success": true,
@@ -5483,7 +6163,7 @@ pub fn start_light_node_ping_service(blockchain: Arc<BlockchainNode>) {
                                 
                                 // Re-check if attestation appeared while waiting
                                 if let Some(p2p) = blockchain.get_unified_p2p() {
-                                    if p2p.has_attestation(&light_node.node_id, current_slot) {
+                                    if p2p.has_attestation(&light_node.node_id, SimplifiedP2P::get_current_window_start()) {
                                         // Primary succeeded, skip
                                         return;
                                     }
@@ -5593,6 +6273,12 @@ pub fn start_light_node_ping_service(blockchain: Arc<BlockchainNode>) {
                 // After grace period (3 minutes), check if nodes responded
                 // This runs at slot N+3 to check slot N
                 let check_slot = if current_slot >= 3 { current_slot - 3 } else { 240 - 3 + current_slot };
+                let check_window_start = if current_slot >= 3 {
+                    SimplifiedP2P::get_current_window_start()
+                } else {
+                    SimplifiedP2P::get_current_window_start()
+                        .saturating_sub(crate::network_config::get_chain_params().reward_window_secs)
+                };
                 
                 let nodes_in_check_slot: Vec<String> = {
                     let registry = p2p.get_light_node_registry();
@@ -5607,8 +6293,8 @@ pub fn start_light_node_ping_service(blockchain: Arc<BlockchainNode>) {
                 };
                 
                 for node_id in nodes_in_check_slot {
-                    // Check if attestation exists for the checked slot
-                    if !p2p.has_attestation(&node_id, check_slot) {
+                    // Check if attestation exists for the checked slot's window
+                    if !p2p.has_attestation(&node_id, check_window_start) {
                         // No attestation = no response = failure
                         p2p.mark_light_node_ping_failed(&node_id);
                     }
@@ -5944,6 +6630,8 @@ async fn handle_claim_rewards(
         public_key: Some(claim_request.public_key.clone()), // User's Ed25519 public key
         tx_type: qnet_state::TransactionType::RewardDistribution,
         data: None,
+        chain_id: qnet_state::transaction::UNBOUND_CHAIN_ID,
+        valid_until: None,
     };
     
     // Calculate transaction hash
@@ -6085,6 +6773,188 @@ async fn handle_get_pending_rewards(
     Ok(warp::reply::json(&reward_info))
 }
 
+// GET /api/v1/node/{node_id}/rewards - Claim status for a node
+async fn handle_node_rewards(
+    node_id: String,
+    blockchain: Arc<BlockchainNode>,
+) -> Result<impl Reply, Rejection> {
+    let status = blockchain.get_node_reward_status(&node_id).await;
+    Ok(warp::reply::json(&status))
+}
+
+// GET /api/v1/rewards/pools - Available and distributed amount per reward pool
+async fn handle_reward_pools(
+    blockchain: Arc<BlockchainNode>,
+) -> Result<impl Reply, Rejection> {
+    let reward_manager_arc = blockchain.get_reward_manager();
+    let reward_manager = reward_manager_arc.read().await;
+    Ok(warp::reply::json(&json!({
+        "phase": reward_manager.get_network_phase(),
+        "window_start": reward_manager.get_window_cursor(),
+        "available": reward_manager.pool_balances(),
+        "distributed": reward_manager.pool_distributed(),
+    })))
+}
+
+// POST /api/v1/node/{node_id}/rewards/claim - Claim to the registered owner wallet (admin only)
+async fn handle_node_rewards_claim(
+    node_id: String,
+    api_key: Option<String>,
+    blockchain: Arc<BlockchainNode>,
+) -> Result<impl Reply, Rejection> {
+    if !blockchain.is_admin_api_key(api_key.as_deref()) {
+        println!("[SECURITY] ❌ Rejected reward claim for {}: invalid admin API key", node_id);
+        return Ok(warp::reply::with_status(
+            warp::reply::json(&json!({
+                "success": false,
+                "error": "Invalid or missing admin API key"
+            })),
+            warp::http::StatusCode::UNAUTHORIZED,
+        ));
+    }
+    
+    let response = match blockchain.claim_node_rewards(&node_id).await {
+        Ok((tx_hash, amount)) => json!({
+            "success": true,
+            "tx_hash": tx_hash,
+            "amount": amount
+        }),
+        Err(e) => json!({
+            "success": false,
+            "error": e.to_string()
+        }),
+    };
+    Ok(warp::reply::with_status(warp::reply::json(&response), warp::http::StatusCode::OK))
+}
+
+fn admin_key_rejection(action: &str) -> warp::reply::WithStatus<warp::reply::Json> {
+    println!("[SECURITY] ❌ Rejected {}: invalid admin API key", action);
+    warp::reply::with_status(
+        warp::reply::json(&json!({
+            "success": false,
+            "error": "Invalid or missing admin API key"
+        })),
+        warp::http::StatusCode::UNAUTHORIZED,
+    )
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ResyncRequest {
+    /// Roll back to this height (default: last finalized macroblock)
+    height: Option<u64>,
+}
+
+// POST /api/v1/node/admin/resync - Roll back and re-request blocks (admin only)
+async fn handle_node_admin_resync(
+    api_key: Option<String>,
+    body: bytes::Bytes,
+    blockchain: Arc<BlockchainNode>,
+) -> Result<impl Reply, Rejection> {
+    if !blockchain.is_admin_api_key(api_key.as_deref()) {
+        return Ok(admin_key_rejection("resync"));
+    }
+    
+    let request = if body.is_empty() {
+        ResyncRequest::default()
+    } else {
+        match serde_json::from_slice::<ResyncRequest>(&body) {
+            Ok(request) => request,
+            Err(e) => return Ok(warp::reply::with_status(
+                warp::reply::json(&json!({
+                    "success": false,
+                    "error": format!("Invalid request body: {}", e)
+                })),
+                warp::http::StatusCode::BAD_REQUEST,
+            )),
+        }
+    };
+    
+    use crate::resync::ResyncError;
+    match blockchain.start_resync(request.height).await {
+        Ok(status) => Ok(warp::reply::with_status(
+            warp::reply::json(&json!({
+                "success": true,
+                "status": status
+            })),
+            warp::http::StatusCode::ACCEPTED,
+        )),
+        Err(e) => {
+            let code = match e {
+                ResyncError::InProgress => warp::http::StatusCode::CONFLICT,
+                ResyncError::BelowFinality { .. } | ResyncError::AboveTip { .. } => warp::http::StatusCode::BAD_REQUEST,
+                ResyncError::NetworkHeightUnknown => warp::http::StatusCode::SERVICE_UNAVAILABLE,
+                ResyncError::Rollback(_) => warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            println!("[RESYNC] ❌ Resync request refused: {}", e);
+            Ok(warp::reply::with_status(
+                warp::reply::json(&json!({
+                    "success": false,
+                    "error": e.to_string()
+                })),
+                code,
+            ))
+        }
+    }
+}
+
+// GET /api/v1/node/admin/resync/status - Progress of the latest resync (admin only)
+async fn handle_node_admin_resync_status(
+    api_key: Option<String>,
+    blockchain: Arc<BlockchainNode>,
+) -> Result<impl Reply, Rejection> {
+    if !blockchain.is_admin_api_key(api_key.as_deref()) {
+        return Ok(admin_key_rejection("resync status"));
+    }
+    
+    let status = blockchain.get_resync_status().await;
+    Ok(warp::reply::with_status(
+        warp::reply::json(&json!({
+            "success": true,
+            "progress_percent": status.progress_percent(),
+            "status": status
+        })),
+        warp::http::StatusCode::OK,
+    ))
+}
+
+// GET /api/v1/node/admin/state-breaker - State-root circuit breaker status
+async fn handle_node_admin_state_breaker(
+    api_key: Option<String>,
+    blockchain: Arc<BlockchainNode>,
+) -> Result<impl Reply, Rejection> {
+    if !blockchain.is_admin_api_key(api_key.as_deref()) {
+        return Ok(admin_key_rejection("state breaker status"));
+    }
+    
+    Ok(warp::reply::with_status(
+        warp::reply::json(&json!({
+            "success": true,
+            "status": blockchain.get_state_root_breaker_status()
+        })),
+        warp::http::StatusCode::OK,
+    ))
+}
+
+// POST /api/v1/node/admin/state-breaker/ack - Acknowledge a state-root halt and resume production
+async fn handle_node_admin_state_breaker_ack(
+    api_key: Option<String>,
+    blockchain: Arc<BlockchainNode>,
+) -> Result<impl Reply, Rejection> {
+    if !blockchain.is_admin_api_key(api_key.as_deref()) {
+        return Ok(admin_key_rejection("state breaker acknowledgment"));
+    }
+    
+    let resumed = blockchain.acknowledge_state_root_breaker();
+    Ok(warp::reply::with_status(
+        warp::reply::json(&json!({
+            "success": true,
+            "resumed": resumed.is_some(),
+            "status": blockchain.get_state_root_breaker_status()
+        })),
+        warp::http::StatusCode::OK,
+    ))
+}
+
 // POST /api/v1/nodes - Register a new node
 async fn handle_register_node(
     body: serde_json::Value,
@@ -6338,6 +7208,8 @@ async fn handle_graceful_shutdown(
         // Wait for timeout period to allow current requests to complete
         tokio::time::sleep(tokio::time::Duration::from_secs(timeout_seconds)).await;
         
+        blockchain_clone.save_invalid_block_scores();
+        
         println!("💀 SHUTDOWN: Node terminating due to replacement");
         
         // Force exit the process
@@ -7412,6 +8284,8 @@ async fn handle_stats(
         .elapsed()
         .as_secs();
     
+    let registration_cache = blockchain.get_storage().node_registration_cache_stats();
+    
     let stats = json!({
         "network": {
             "height": height,
@@ -7435,6 +8309,15 @@ async fn handle_stats(
             "macroblock_interval": 90,
             "current_round": height / 30,
         },
+        "storage": {
+            "node_registration_cache": {
+                "hits": registration_cache.hits,
+                "misses": registration_cache.misses,
+                "entries": registration_cache.entries,
+                "capacity": registration_cache.capacity,
+                "hit_rate": registration_cache.hit_rate(),
+            },
+        },
         "timestamp": chrono::Utc::now().timestamp(),
     });
     
@@ -7882,6 +8765,24 @@ async fn handle_reputation_history(
     Ok(warp::reply::json(&history))
 }
 
+async fn handle_invalid_block_scores(
+    blockchain: Arc<BlockchainNode>,
+) -> Result<impl Reply, Rejection> {
+    let (half_life_secs, scores) = blockchain.get_unified_p2p()
+        .map(|p2p| p2p.get_invalid_block_scores())
+        .unwrap_or((0, Vec::new()));
+    
+    let peers: Vec<Value> = scores.iter()
+        .map(|(peer, score)| json!({ "peer": peer, "score": score }))
+        .collect();
+    
+    Ok(warp::reply::json(&json!({
+        "half_life_secs": half_life_secs,
+        "tracked_peers": peers.len(),
+        "peers": peers,
+    })))
+}
+
 /// Generate quantum-secure activation code with XOR-encrypted wallet
 /// CRITICAL: Must match bridge-server.py format for decrypt compatibility!
 /// Format: QNET-{type+timestamp}-{encrypted_wallet1}-{encrypted_wallet2+entropy}
@@ -8844,7 +9745,9 @@ async fn handle_ws_connection_with_cleanup(
     }
     
     // Spawn task to handle incoming messages (for ping/pong and unsubscribe)
+    // Block stream requests are handed to the main loop, which owns the sender
     let channels_clone = channels.clone();
+    let (command_tx, mut command_rx) = tokio::sync::mpsc::unbounded_channel::<u64>();
     tokio::spawn(async move {
         while let Some(result) = ws_rx.next().await {
             match result {
@@ -8857,6 +9760,9 @@ async fn handle_ws_connection_with_cleanup(
                         // Handle client commands (e.g., subscribe to new channels)
                         if let Ok(text) = msg.to_str() {
                             println!("[WS] 📨 Received command: {}", text);
+                            if let Some(from) = parse_block_stream_command(text) {
+                                let _ = command_tx.send(from);
+                            }
                         }
                     }
                 }
@@ -8868,10 +9774,37 @@ async fn handle_ws_connection_with_cleanup(
         }
     });
     
+    // HISTORICAL + LIVE: set by {"subscribe":"blocks","from":H}; owns NewBlock delivery once set
+    let mut block_stream: Option<BlockStream> = None;
+    
     // Main loop: forward matching events to client
     loop {
-        match rx.recv().await {
+        let received = tokio::select! {
+            command = command_rx.recv() => {
+                let Some(from) = command else {
+                    break; // Client disconnected
+                };
+                println!("[WS] 📜 Streaming blocks from #{}", from);
+                let ack = json!({ "type": "subscribed", "channel": "blocks", "from": from });
+                let mut stream = BlockStream::new(from);
+                if send_json(&mut ws_tx, &ack).await.is_err() || stream.catch_up(&*blockchain, &mut ws_tx).await.is_err() {
+                    println!("[WS] ❌ Error sending block replay");
+                    break;
+                }
+                block_stream = Some(stream);
+                continue;
+            }
+            received = rx.recv() => received,
+        };
+        match received {
             Ok(event) => {
+                if let (Some(stream), WsEvent::NewBlock { .. }) = (block_stream.as_mut(), &event) {
+                    if let Err(e) = stream.on_live(&*blockchain, &event, &mut ws_tx).await {
+                        println!("[WS] ❌ Error sending event: {}", e);
+                        break;
+                    }
+                    continue;
+                }
                 // Check if event matches any subscribed channel
                 if event_matches_channels(&event, &channels_clone) {
                     // Serialize and send event
@@ -8884,6 +9817,7 @@ async fn handle_ws_connection_with_cleanup(
                 }
             }
             Err(broadcast::error::RecvError::Lagged(n)) => {
+                // Block streams refill missed blocks from storage on the next live block
                 println!("[WS] ⚠️ Client lagged, missed {} events", n);
                 let warning = json!({
                     "type": "warning",
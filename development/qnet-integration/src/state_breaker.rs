@@ -0,0 +1,164 @@
+//! State-root circuit breaker
+//!
+//! Every received macroblock carries the network's state root for its window,
+//! which a validator compares with the root of its own applied state. A single
+//! mismatch triggers a resync of that window. If mismatches keep coming (more
+//! than `max_mismatches` without a matching root in between), the node's state
+//! is not converging and it must stop producing blocks: the breaker trips and
+//! the node stays read-only, following the chain, until either a later
+//! macroblock root matches again (the resync succeeded) or an operator
+//! acknowledges the halt.
+
+use serde::Serialize;
+
+/// Default number of consecutive mismatches tolerated; the next one halts production
+pub const DEFAULT_MAX_STATE_ROOT_MISMATCHES: u32 = 2;
+
+/// Why a halted node resumed production
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakerResume {
+    /// A macroblock state root matched local state again
+    Resynced,
+    /// An operator cleared the halt
+    Acknowledged,
+}
+
+/// Snapshot of the breaker for monitoring and the admin API
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BreakerStatus {
+    pub halted: bool,
+    /// Mismatches since the last matching macroblock
+    pub mismatches: u32,
+    pub max_mismatches: u32,
+    /// Macroblock index of the latest mismatch
+    pub last_mismatch: Option<u64>,
+    /// Macroblock index whose mismatch tripped the breaker
+    pub halted_at: Option<u64>,
+}
+
+/// Counts state-root mismatches and halts block production once they exceed the limit
+#[derive(Debug, Clone)]
+pub struct StateRootBreaker {
+    max_mismatches: u32,
+    mismatches: u32,
+    last_mismatch: Option<u64>,
+    halted_at: Option<u64>,
+}
+
+impl Default for StateRootBreaker {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_STATE_ROOT_MISMATCHES)
+    }
+}
+
+impl StateRootBreaker {
+    pub fn new(max_mismatches: u32) -> Self {
+        Self {
+            max_mismatches,
+            mismatches: 0,
+            last_mismatch: None,
+            halted_at: None,
+        }
+    }
+
+    /// Breaker with a `QNET_MAX_STATE_ROOT_MISMATCHES` override
+    pub fn from_env() -> Self {
+        let max_mismatches = std::env::var("QNET_MAX_STATE_ROOT_MISMATCHES")
+            .ok()
+            .and_then(|v| v.trim().parse::<u32>().ok())
+            .unwrap_or(DEFAULT_MAX_STATE_ROOT_MISMATCHES);
+        Self::new(max_mismatches)
+    }
+
+    /// Record a mismatch at `macroblock_index`, returning true if it halted production
+    pub fn record_mismatch(&mut self, macroblock_index: u64) -> bool {
+        self.mismatches = self.mismatches.saturating_add(1);
+        self.last_mismatch = Some(macroblock_index);
+        if self.halted_at.is_none() && self.mismatches > self.max_mismatches {
+            self.halted_at = Some(macroblock_index);
+            return true;
+        }
+        false
+    }
+
+    /// Record a matching root, returning `Resynced` if it lifted a halt
+    pub fn record_match(&mut self) -> Option<BreakerResume> {
+        self.mismatches = 0;
+        self.halted_at.take().map(|_| BreakerResume::Resynced)
+    }
+
+    /// Operator acknowledgment: resume production and start counting afresh
+    pub fn acknowledge(&mut self) -> Option<BreakerResume> {
+        self.mismatches = 0;
+        self.halted_at.take().map(|_| BreakerResume::Acknowledged)
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.halted_at.is_some()
+    }
+
+    pub fn status(&self) -> BreakerStatus {
+        BreakerStatus {
+            halted: self.is_halted(),
+            mismatches: self.mismatches,
+            max_mismatches: self.max_mismatches,
+            last_mismatch: self.last_mismatch,
+            halted_at: self.halted_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Validator loop: produce unless halted, then check the next macroblock root
+    fn run(breaker: &mut StateRootBreaker, roots_match: &[bool]) -> Vec<bool> {
+        let mut produced = Vec::new();
+        for (index, &matches) in roots_match.iter().enumerate() {
+            produced.push(!breaker.is_halted());
+            if matches {
+                breaker.record_match();
+            } else {
+                breaker.record_mismatch(index as u64 + 1);
+            }
+        }
+        produced
+    }
+
+    #[test]
+    fn test_repeated_mismatches_halt_production() {
+        let mut breaker = StateRootBreaker::new(2);
+
+        // Two mismatches are tolerated, the third halts; later rounds produce nothing
+        let produced = run(&mut breaker, &[false, false, false, false, false]);
+        assert_eq!(produced, vec![true, true, true, false, false]);
+        assert!(breaker.is_halted());
+        assert_eq!(breaker.status().halted_at, Some(3));
+        assert_eq!(breaker.status().last_mismatch, Some(5));
+
+        // Isolated mismatches separated by matches never trip it
+        let mut breaker = StateRootBreaker::new(2);
+        let produced = run(&mut breaker, &[false, false, true, false, false, true]);
+        assert!(produced.iter().all(|&p| p));
+        assert!(!breaker.is_halted());
+    }
+
+    #[test]
+    fn test_halt_lifted_by_resync_or_acknowledgment() {
+        let mut breaker = StateRootBreaker::new(0);
+        assert!(breaker.record_mismatch(10));
+        assert!(!breaker.record_mismatch(11));
+
+        // A matching root after resync resumes production
+        assert_eq!(breaker.record_match(), Some(BreakerResume::Resynced));
+        assert!(!breaker.is_halted());
+        assert_eq!(breaker.record_match(), None);
+
+        assert!(breaker.record_mismatch(12));
+        assert_eq!(breaker.acknowledge(), Some(BreakerResume::Acknowledged));
+        assert_eq!(breaker.status().mismatches, 0);
+        assert_eq!(breaker.acknowledge(), None);
+    }
+}
@@ -3,6 +3,7 @@
 use rocksdb::{DB, Options, ColumnFamily, ColumnFamilyDescriptor, WriteBatch};
 use qnet_state::{Block, Account, Transaction};
 use crate::errors::{IntegrationError, IntegrationResult};
+use crate::network_size::{NetworkSizeEstimate, NetworkSizeSource};
 use std::path::Path;
 use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -27,7 +28,171 @@ pub struct FailoverEvent {
 }
 
 pub struct PersistentStorage {
-    db: DB,
+    db: Arc<DB>,
+    /// Cold database: archive chunks and aged PoH checkpoints
+    cold: DB,
+    wal: WalSync,
+}
+
+/// Where hot and cold data live
+///
+/// Hot data is everything on the block path: blocks, state, indexes, chain height,
+/// recent PoH checkpoints. Cold data is written once and read rarely: archive
+/// chunks and PoH checkpoints older than the newest `HOT_POH_CHECKPOINTS`.
+/// Operators with a fast and a bulk disk point `QNET_HOT_DIR` at the first and
+/// `QNET_COLD_DIR` at the second; both default to the data directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageLayout {
+    pub hot_dir: std::path::PathBuf,
+    pub cold_dir: std::path::PathBuf,
+}
+
+impl StorageLayout {
+    /// Everything under `data_dir`
+    pub fn new(data_dir: impl AsRef<Path>) -> Self {
+        Self {
+            hot_dir: data_dir.as_ref().to_path_buf(),
+            cold_dir: data_dir.as_ref().to_path_buf(),
+        }
+    }
+    
+    /// `QNET_HOT_DIR` / `QNET_COLD_DIR` overrides of `data_dir`
+    pub fn from_env(data_dir: impl AsRef<Path>) -> Self {
+        let defaults = Self::new(data_dir);
+        let dir = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty()).map(std::path::PathBuf::from);
+        Self {
+            hot_dir: dir("QNET_HOT_DIR").unwrap_or(defaults.hot_dir),
+            cold_dir: dir("QNET_COLD_DIR").unwrap_or(defaults.cold_dir),
+        }
+    }
+    
+    /// Directory of the cold database
+    pub fn cold_db_path(&self) -> std::path::PathBuf {
+        self.cold_dir.join(COLD_DB_DIR)
+    }
+}
+
+/// Cold database directory under the cold path
+const COLD_DB_DIR: &str = "cold_storage";
+
+/// PoH checkpoints kept on the hot path; older ones move to cold storage
+pub const HOT_POH_CHECKPOINTS: usize = 8;
+
+const POH_CHECKPOINT_PREFIX: &str = "poh_checkpoint_";
+
+/// When the RocksDB write-ahead log reaches the disk
+///
+/// A write is acknowledged once it is in the WAL, so the policy decides how much
+/// acknowledged data a power loss can take:
+/// - `Always` fsyncs the WAL with every write. Nothing acknowledged is lost, but
+///   every write waits on the disk. This is the archival (Super/bootstrap) default.
+/// - `EveryN` and `Periodic` buffer the WAL in memory (`manual_wal_flush`) and
+///   fsync it after N writes or on a timer. Up to N writes, or one interval of
+///   writes, can be lost on a crash. Peers re-serve them on sync, and writes stay
+///   off the disk's critical path. This is the Full/Light default (every second,
+///   in step with block production).
+///
+/// Whatever the policy, dropping the storage (clean shutdown) flushes and syncs the WAL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WalSyncPolicy {
+    Always,
+    EveryN(u64),
+    Periodic(std::time::Duration),
+}
+
+impl Default for WalSyncPolicy {
+    fn default() -> Self {
+        Self::Periodic(std::time::Duration::from_secs(1))
+    }
+}
+
+impl WalSyncPolicy {
+    /// Parse `always`, `every:<writes>` or `periodic:<milliseconds>`
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim().to_ascii_lowercase();
+        let number = |n: &str| n.trim().parse::<u64>().ok().filter(|&n| n > 0)
+            .ok_or_else(|| format!("invalid WAL sync policy {:?}: expected a positive number", value));
+        match value.split_once(':') {
+            None if value == "always" => Ok(Self::Always),
+            Some(("every", n)) => Ok(Self::EveryN(number(n)?)),
+            Some(("periodic", ms)) => Ok(Self::Periodic(std::time::Duration::from_millis(number(ms)?))),
+            _ => Err(format!("invalid WAL sync policy {:?}: expected always, every:<writes> or periodic:<ms>", value)),
+        }
+    }
+
+    /// `QNET_WAL_SYNC` override, if set and valid
+    pub fn from_env() -> Option<Self> {
+        let value = std::env::var("QNET_WAL_SYNC").ok()?;
+        match Self::parse(&value) {
+            Ok(policy) => Some(policy),
+            Err(e) => {
+                eprintln!("[Storage] ⚠️ Ignoring QNET_WAL_SYNC: {}", e);
+                None
+            }
+        }
+    }
+}
+
+/// WAL writes and syncs since the database was opened
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WalStats {
+    pub policy: WalSyncPolicy,
+    pub writes: u64,
+    pub syncs: u64,
+}
+
+/// Applies a `WalSyncPolicy` to writes through `PersistentStorage`
+struct WalSync {
+    policy: WalSyncPolicy,
+    write_options: rocksdb::WriteOptions,
+    writes: std::sync::atomic::AtomicU64,
+    syncs: Arc<std::sync::atomic::AtomicU64>,
+    /// Periodic policy: stop signal and handle of the background sync thread
+    flusher: std::sync::Mutex<Option<(std::sync::mpsc::Sender<()>, std::thread::JoinHandle<()>)>>,
+}
+
+impl WalSync {
+    fn start(policy: WalSyncPolicy, db: &Arc<DB>) -> IntegrationResult<Self> {
+        let mut write_options = rocksdb::WriteOptions::default();
+        write_options.set_sync(policy == WalSyncPolicy::Always);
+        let syncs = Arc::new(std::sync::atomic::AtomicU64::new(0));
+
+        let flusher = match policy {
+            WalSyncPolicy::Periodic(interval) => {
+                let (stop, stopped) = std::sync::mpsc::channel::<()>();
+                let db = db.clone();
+                let syncs = syncs.clone();
+                let handle = std::thread::Builder::new()
+                    .name("qnet-wal-sync".to_string())
+                    .spawn(move || {
+                        while let Err(std::sync::mpsc::RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                            match db.flush_wal(true) {
+                                Ok(()) => { syncs.fetch_add(1, std::sync::atomic::Ordering::Relaxed); }
+                                Err(e) => eprintln!("[Storage] ⚠️ Periodic WAL sync failed: {}", e),
+                            }
+                        }
+                    })?;
+                Some((stop, handle))
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            policy,
+            write_options,
+            writes: std::sync::atomic::AtomicU64::new(0),
+            syncs,
+            flusher: std::sync::Mutex::new(flusher),
+        })
+    }
+
+    fn stop_flusher(&self) {
+        let flusher = self.flusher.lock().ok().and_then(|mut f| f.take());
+        if let Some((stop, handle)) = flusher {
+            drop(stop);
+            let _ = handle.join();
+        }
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -194,6 +359,8 @@ pub struct StorageTierConfig {
     pub pruning_window_blocks: u64,
     /// Whether to apply aggressive compression to old blocks
     pub compress_old_blocks: bool,
+    /// When the write-ahead log is synced to disk (`QNET_WAL_SYNC` overrides)
+    pub wal_sync: WalSyncPolicy,
 }
 
 impl StorageTierConfig {
@@ -207,6 +374,7 @@ impl StorageTierConfig {
             max_storage_bytes: 100 * 1024 * 1024, // 100 MB
             pruning_window_blocks: 1_000, // Keep last 1000 block headers
             compress_old_blocks: false, // Headers are already small
+            wal_sync: WalSyncPolicy::Periodic(std::time::Duration::from_secs(1)), // Headers are re-fetched from peers
         }
     }
     
@@ -221,6 +389,7 @@ impl StorageTierConfig {
             max_storage_bytes: 500 * 1024 * 1024 * 1024, // 500 GB
             pruning_window_blocks: 2_592_000, // ~30 days at 1 block/sec
             compress_old_blocks: true, // Apply Zstd-22 to blocks > 7 days old
+            wal_sync: WalSyncPolicy::Periodic(std::time::Duration::from_secs(1)), // At most ~1 block lost on power loss
         }
     }
     
@@ -235,6 +404,7 @@ impl StorageTierConfig {
             max_storage_bytes: 2 * 1024 * 1024 * 1024 * 1024, // 2 TB
             pruning_window_blocks: 0, // No pruning - keep ALL history
             compress_old_blocks: true, // Apply progressive compression
+            wal_sync: WalSyncPolicy::Always, // Archive: never lose an acknowledged write
         }
     }
     
@@ -454,13 +624,94 @@ impl LightNodeRotation {
     }
 }
 
+/// Hash count of a `poh_checkpoint_<count>` key
+fn checkpoint_number(key: &[u8]) -> Option<u64> {
+    std::str::from_utf8(key.strip_prefix(POH_CHECKPOINT_PREFIX.as_bytes())?).ok()?.parse().ok()
+}
+
+impl Drop for PersistentStorage {
+    /// Clean shutdown: stop the periodic sync and sync the WAL whatever the policy
+    fn drop(&mut self) {
+        self.wal.stop_flusher();
+        if let Err(e) = self.flush_wal() {
+            eprintln!("[Storage] ⚠️ WAL sync on shutdown failed: {}", e);
+        }
+    }
+}
+
 impl PersistentStorage {
     /// Save raw data with a custom key
     pub fn save_raw(&self, key: &str, data: &[u8]) -> IntegrationResult<()> {
-        self.db.put(key.as_bytes(), data)?;
+        self.put(key.as_bytes(), data)?;
+        Ok(())
+    }
+    
+    /// Save an archive chunk to cold storage
+    pub fn save_archive_chunk(&self, chunk: &crate::archive_manager::ArchiveChunk) -> IntegrationResult<()> {
+        let data = bincode::serialize(chunk)
+            .map_err(|e| IntegrationError::SerializationError(e.to_string()))?;
+        self.cold.put_cf_opt(self.cold_cf("archive_chunks")?, chunk.chunk_id, data, &self.wal.write_options)?;
         Ok(())
     }
     
+    pub fn load_archive_chunk(&self, chunk_id: &crate::archive_manager::ChunkId) -> IntegrationResult<Option<crate::archive_manager::ArchiveChunk>> {
+        match self.cold.get_cf(self.cold_cf("archive_chunks")?, chunk_id)? {
+            Some(data) => bincode::deserialize(&data)
+                .map(Some)
+                .map_err(|e| IntegrationError::SerializationError(e.to_string())),
+            None => Ok(None),
+        }
+    }
+    
+    /// Save a PoH checkpoint on the hot path, moving all but the newest
+    /// `HOT_POH_CHECKPOINTS` to cold storage
+    pub fn save_poh_checkpoint(&self, key: &str, data: &[u8]) -> IntegrationResult<()> {
+        self.save_raw(key, data)?;
+        if checkpoint_number(key.as_bytes()).is_some() {
+            self.age_poh_checkpoints()?;
+        }
+        Ok(())
+    }
+    
+    /// Load a PoH checkpoint (or its index) from the hot path, falling back to cold storage
+    pub fn load_poh_checkpoint(&self, key: &str) -> IntegrationResult<Option<Vec<u8>>> {
+        if let Some(data) = self.load_raw(key)? {
+            return Ok(Some(data));
+        }
+        Ok(self.cold.get_cf(self.cold_cf("poh_checkpoints")?, key.as_bytes())?)
+    }
+    
+    fn age_poh_checkpoints(&self) -> IntegrationResult<usize> {
+        let mut hot = Vec::new();
+        for item in self.db.iterator(rocksdb::IteratorMode::From(POH_CHECKPOINT_PREFIX.as_bytes(), rocksdb::Direction::Forward)) {
+            let (key, data) = item?;
+            if !key.starts_with(POH_CHECKPOINT_PREFIX.as_bytes()) {
+                break;
+            }
+            // Skips the `poh_checkpoint_latest` index
+            if let Some(number) = checkpoint_number(&key) {
+                hot.push((number, key, data));
+            }
+        }
+        if hot.len() <= HOT_POH_CHECKPOINTS {
+            return Ok(0);
+        }
+        
+        hot.sort_unstable_by_key(|(number, _, _)| *number);
+        let aged = hot.len() - HOT_POH_CHECKPOINTS;
+        let cold_cf = self.cold_cf("poh_checkpoints")?;
+        let mut cold_batch = WriteBatch::default();
+        let mut hot_batch = WriteBatch::default();
+        for (_, key, data) in hot.into_iter().take(aged) {
+            cold_batch.put_cf(cold_cf, &key, &data);
+            hot_batch.delete(&key);
+        }
+        // Copied before deleted: a crash in between leaves a checkpoint in both places, never in neither
+        self.cold.write_opt(cold_batch, &self.wal.write_options)?;
+        self.write(hot_batch)?;
+        Ok(aged)
+    }
+    
     /// Load raw data with a custom key
     pub fn load_raw(&self, key: &str) -> IntegrationResult<Option<Vec<u8>>> {
         match self.db.get(key.as_bytes())? {
@@ -469,14 +720,26 @@ impl PersistentStorage {
         }
     }
     
+    /// Open with the `QNET_WAL_SYNC` policy, or the default one
     pub fn new(data_dir: &str) -> IntegrationResult<Self> {
-        let path = Path::new(data_dir);
+        Self::open(data_dir, WalSyncPolicy::from_env().unwrap_or_default())
+    }
+    
+    /// Open with hot and cold data both under `data_dir`
+    pub fn open(data_dir: &str, wal_sync: WalSyncPolicy) -> IntegrationResult<Self> {
+        Self::open_layout(&StorageLayout::new(data_dir), wal_sync)
+    }
+    
+    pub fn open_layout(layout: &StorageLayout, wal_sync: WalSyncPolicy) -> IntegrationResult<Self> {
+        let path = layout.hot_dir.as_path();
         std::fs::create_dir_all(path)?;
         
         // Simple, reliable RocksDB configuration
         let mut opts = Options::default();
         opts.create_if_missing(true);
         opts.create_missing_column_families(true);
+        // Buffered WAL: synced by the policy (every N writes / periodically) and on shutdown
+        opts.set_manual_wal_flush(wal_sync != WalSyncPolicy::Always);
         
         // Basic settings that work reliably
         opts.set_max_open_files(1000);
@@ -524,7 +787,140 @@ impl PersistentStorage {
             }
         };
         
-        Ok(Self { db })
+        let cold = Self::open_cold(&layout.cold_db_path())?;
+        let db = Arc::new(db);
+        let wal = WalSync::start(wal_sync, &db)?;
+        let storage = Self { db, cold, wal };
+        storage.migrate_macroblock_format()?;
+        Ok(storage)
+    }
+    
+    /// Cold data is written once and read rarely: compressed, no write-path tuning
+    fn open_cold(path: &Path) -> IntegrationResult<DB> {
+        std::fs::create_dir_all(path)?;
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        opts.set_max_open_files(256);
+        opts.set_compression_type(rocksdb::DBCompressionType::Zstd);
+        
+        let cfs = vec![
+            ColumnFamilyDescriptor::new("archive_chunks", Options::default()),
+            ColumnFamilyDescriptor::new("poh_checkpoints", Options::default()),
+        ];
+        DB::open_cf_descriptors(&opts, path, cfs)
+            .map_err(|e| IntegrationError::StorageError(format!("Cold storage initialization failed: {}", e)))
+    }
+    
+    fn cold_cf(&self, name: &str) -> IntegrationResult<&ColumnFamily> {
+        self.cold.cf_handle(name)
+            .ok_or_else(|| IntegrationError::StorageError(format!("{} column family not found", name)))
+    }
+    
+    fn put(&self, key: impl AsRef<[u8]>, value: impl AsRef<[u8]>) -> Result<(), rocksdb::Error> {
+        self.db.put_opt(key, value, &self.wal.write_options)?;
+        self.wal_written();
+        Ok(())
+    }
+    
+    fn put_cf(&self, cf: &impl rocksdb::AsColumnFamilyRef, key: impl AsRef<[u8]>, value: impl AsRef<[u8]>) -> Result<(), rocksdb::Error> {
+        self.db.put_cf_opt(cf, key, value, &self.wal.write_options)?;
+        self.wal_written();
+        Ok(())
+    }
+    
+    fn delete_cf(&self, cf: &impl rocksdb::AsColumnFamilyRef, key: impl AsRef<[u8]>) -> Result<(), rocksdb::Error> {
+        self.db.delete_cf_opt(cf, key, &self.wal.write_options)?;
+        self.wal_written();
+        Ok(())
+    }
+    
+    fn write(&self, batch: WriteBatch) -> Result<(), rocksdb::Error> {
+        self.db.write_opt(batch, &self.wal.write_options)?;
+        self.wal_written();
+        Ok(())
+    }
+    
+    /// Count a write and sync the WAL if the policy calls for it
+    fn wal_written(&self) {
+        use std::sync::atomic::Ordering;
+        let writes = self.wal.writes.fetch_add(1, Ordering::Relaxed) + 1;
+        match self.wal.policy {
+            // Synced by the write itself
+            WalSyncPolicy::Always => { self.wal.syncs.fetch_add(1, Ordering::Relaxed); }
+            WalSyncPolicy::EveryN(n) if writes % n == 0 => {
+                if let Err(e) = self.flush_wal() {
+                    eprintln!("[Storage] ⚠️ WAL sync after {} writes failed: {}", writes, e);
+                }
+            }
+            _ => {}
+        }
+    }
+    
+    /// Write buffered WAL entries and fsync them
+    pub fn flush_wal(&self) -> IntegrationResult<()> {
+        self.db.flush_wal(true)?;
+        self.wal.syncs.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        Ok(())
+    }
+    
+    pub fn wal_stats(&self) -> WalStats {
+        use std::sync::atomic::Ordering;
+        WalStats {
+            policy: self.wal.policy,
+            writes: self.wal.writes.load(Ordering::Relaxed),
+            syncs: self.wal.syncs.load(Ordering::Relaxed),
+        }
+    }
+    
+    /// One-time migration of unversioned macroblocks to `MacroBlock::serialize_versioned`
+    /// Databases written before the version byte store raw bincode; once every
+    /// entry is rewritten the format version is recorded in metadata and the scan is skipped
+    fn migrate_macroblock_format(&self) -> IntegrationResult<()> {
+        let microblocks_cf = self.db.cf_handle("microblocks")
+            .ok_or_else(|| IntegrationError::StorageError("microblocks column family not found".to_string()))?;
+        let metadata_cf = self.db.cf_handle("metadata")
+            .ok_or_else(|| IntegrationError::StorageError("metadata column family not found".to_string()))?;
+        
+        if self.db.get_cf(&metadata_cf, b"macroblock_format_version")?.is_some() {
+            return Ok(());
+        }
+        
+        let mut batch = WriteBatch::default();
+        let mut migrated = 0u64;
+        let iter = self.db.iterator_cf(&microblocks_cf, rocksdb::IteratorMode::From(b"macroblock_", rocksdb::Direction::Forward));
+        for item in iter {
+            let (key, raw_data) = item?;
+            if !key.starts_with(b"macroblock_") {
+                break;
+            }
+            
+            let data = if raw_data.len() >= 4 && raw_data[0..4] == [0x28, 0xb5, 0x2f, 0xfd] {
+                zstd::decode_all(&raw_data[..]).unwrap_or_else(|_| raw_data.to_vec())
+            } else {
+                raw_data.to_vec()
+            };
+            
+            match bincode::deserialize::<qnet_state::MacroBlock>(&data) {
+                Ok(macroblock) => {
+                    let versioned = macroblock.serialize_versioned()
+                        .map_err(|e| IntegrationError::SerializationError(e.to_string()))?;
+                    batch.put_cf(&microblocks_cf, &key, &versioned);
+                    migrated += 1;
+                }
+                Err(e) => {
+                    println!("[Storage] ⚠️ Skipping unreadable macroblock {}: {}", String::from_utf8_lossy(&key), e);
+                }
+            }
+        }
+        
+        batch.put_cf(&metadata_cf, b"macroblock_format_version", &[qnet_state::block_format::CURRENT]);
+        self.write(batch)?;
+        
+        if migrated > 0 {
+            println!("[Storage] 🔄 Migrated {} macroblocks to versioned format v{}", migrated, qnet_state::block_format::CURRENT);
+        }
+        Ok(())
     }
     
     pub async fn save_block(&self, block: &qnet_state::Block) -> IntegrationResult<()> {
@@ -585,7 +981,7 @@ impl PersistentStorage {
             .ok_or_else(|| IntegrationError::StorageError("metadata column family not found".to_string()))?;
         batch.put_cf(&metadata_cf, b"chain_height", &block.height.to_be_bytes());
         
-        self.db.write(batch)?;
+        self.write(batch)?;
         Ok(())
     }
     
@@ -612,7 +1008,7 @@ impl PersistentStorage {
         let metadata_cf = self.db.cf_handle("metadata")
             .ok_or_else(|| IntegrationError::StorageError("metadata column family not found".to_string()))?;
         
-        self.db.put_cf(&metadata_cf, b"chain_height", &height.to_be_bytes())?;
+        self.put_cf(&metadata_cf, b"chain_height", &height.to_be_bytes())?;
         Ok(())
     }
     
@@ -650,7 +1046,7 @@ impl PersistentStorage {
         
         // Set height to 0
         let height_bytes = 0u64.to_be_bytes();
-        self.db.put_cf(&metadata_cf, b"chain_height", height_bytes)?;
+        self.put_cf(&metadata_cf, b"chain_height", height_bytes)?;
         
         println!("[Storage] ✅ Chain height reset: {} -> 0", current_height);
         println!("[Storage] ⚠️  Data loss: {} blocks deleted", current_height);
@@ -694,7 +1090,7 @@ impl PersistentStorage {
         let account_data = bincode::serialize(account)
             .map_err(|e| IntegrationError::SerializationError(e.to_string()))?;
         
-        self.db.put_cf(&accounts_cf, account.address.as_bytes(), &account_data)?;
+        self.put_cf(&accounts_cf, account.address.as_bytes(), &account_data)?;
         Ok(())
     }
     
@@ -713,7 +1109,33 @@ impl PersistentStorage {
         // CRITICAL FIX: Update chain height when saving microblock
         batch.put_cf(&metadata_cf, b"chain_height", &height.to_be_bytes());
         
-        self.db.write(batch)?;
+        self.write(batch)?;
+        Ok(())
+    }
+    
+    /// Save a chunk of microblocks in one atomic write
+    /// Chain height is set to the highest block of the chunk in the same batch
+    pub fn save_microblocks_batch(&self, blocks: &[(u64, Vec<u8>)]) -> IntegrationResult<()> {
+        self.write_microblocks_batch(WriteBatch::default(), blocks)
+    }
+    
+    /// Add microblocks and the chain height to `batch`, then commit it
+    fn write_microblocks_batch(&self, mut batch: WriteBatch, blocks: &[(u64, Vec<u8>)]) -> IntegrationResult<()> {
+        let Some(top_height) = blocks.iter().map(|(height, _)| *height).max() else {
+            return Ok(());
+        };
+        let microblocks_cf = self.db.cf_handle("microblocks")
+            .ok_or_else(|| IntegrationError::StorageError("microblocks column family not found".to_string()))?;
+        let metadata_cf = self.db.cf_handle("metadata")
+            .ok_or_else(|| IntegrationError::StorageError("metadata column family not found".to_string()))?;
+        
+        for (height, data) in blocks {
+            let key = format!("microblock_{}", height);
+            batch.put_cf(&microblocks_cf, key.as_bytes(), data);
+        }
+        batch.put_cf(&metadata_cf, b"chain_height", top_height.to_be_bytes());
+        
+        self.write(batch)?;
         Ok(())
     }
     
@@ -741,7 +1163,7 @@ impl PersistentStorage {
             hex::encode(&encrypted_data)  // Encrypted data
         );
         
-        self.db.put_cf(&metadata_cf, b"activation_code", storage_record.as_bytes())?;
+        self.put_cf(&metadata_cf, b"activation_code", storage_record.as_bytes())?;
         
         // CRITICAL: Do NOT save encryption key to database!
         // Key is derived from activation code when needed
@@ -865,9 +1287,9 @@ impl PersistentStorage {
         let metadata_cf = self.db.cf_handle("metadata")
             .ok_or_else(|| IntegrationError::StorageError("metadata column family not found".to_string()))?;
         
-        self.db.delete_cf(&metadata_cf, b"activation_code")?;
-        self.db.delete_cf(&metadata_cf, b"state_key")?;
-        self.db.delete_cf(&metadata_cf, b"activation_burn_tx")?;
+        self.delete_cf(&metadata_cf, b"activation_code")?;
+        self.delete_cf(&metadata_cf, b"state_key")?;
+        self.delete_cf(&metadata_cf, b"activation_burn_tx")?;
         Ok(())
     }
     
@@ -893,7 +1315,7 @@ impl PersistentStorage {
         let metadata_cf = self.db.cf_handle("metadata")
             .ok_or_else(|| IntegrationError::StorageError("metadata column family not found".to_string()))?;
         
-        self.db.put_cf(&metadata_cf, b"activation_burn_tx", burn_tx.as_bytes())?;
+        self.put_cf(&metadata_cf, b"activation_burn_tx", burn_tx.as_bytes())?;
         println!("[Storage] 🔗 Burn TX saved for activation: {}...", &burn_tx[..8.min(burn_tx.len())]);
         Ok(())
     }
@@ -922,7 +1344,7 @@ impl PersistentStorage {
             hex::encode(&encrypted_data)
         );
         
-        self.db.put_cf(&metadata_cf, b"activation_code", storage_record.as_bytes())?;
+        self.put_cf(&metadata_cf, b"activation_code", storage_record.as_bytes())?;
         
         // CRITICAL: Do NOT save encryption key - it's derived from activation code!
         
@@ -1174,13 +1596,130 @@ impl PersistentStorage {
         }
     }
     
+    /// Verify the stored microblocks `from..=to`, one block in memory at a time
+    ///
+    /// Each block must decode and sit at its own height, its `previous_hash` must be
+    /// the hash of the stored block below (zero for genesis), every referenced
+    /// transaction must be stored under its hash, and `merkle_root` must match the
+    /// root recomputed from the transactions. Stops at the first break. Light-mode
+    /// headers carry no linkage and report as undecodable.
+    pub fn verify_chain(&self, from: u64, to: u64) -> IntegrationResult<ChainVerifyReport> {
+        let mut report = ChainVerifyReport { from, to, verified: 0, first_break: None };
+        
+        // Hash of the stored block below the one being checked (unknown below `from`
+        // if that block is not stored, e.g. after pruning)
+        let mut below = match from.checked_sub(1) {
+            Some(height) => self.load_microblock(height)?.map(|data| stored_block_hash(&data)),
+            None => None,
+        };
+        for height in from..=to {
+            let Some(data) = self.load_microblock(height)? else {
+                report.first_break = Some(ChainBreak { height, kind: ChainBreakKind::Missing });
+                break;
+            };
+            if let Some(kind) = self.check_stored_microblock(height, &data, below.as_ref())? {
+                report.first_break = Some(ChainBreak { height, kind });
+                break;
+            }
+            report.verified += 1;
+            below = Some(stored_block_hash(&data));
+        }
+        Ok(report)
+    }
+    
+    fn check_stored_microblock(&self, height: u64, data: &[u8], below: Option<&[u8; 32]>) -> IntegrationResult<Option<ChainBreakKind>> {
+        let bytes = if data.len() >= 4 && data[0..4] == [0x28, 0xb5, 0x2f, 0xfd] {
+            match zstd::decode_all(data) {
+                Ok(bytes) => bytes,
+                Err(e) => return Ok(Some(ChainBreakKind::Undecodable(e.to_string()))),
+            }
+        } else {
+            data.to_vec()
+        };
+        
+        // Same format order as `Storage::load_microblock_auto_format`
+        let (stored_height, previous_hash, merkle_root, tx_hashes) =
+            if let Ok(block) = bincode::deserialize::<qnet_state::EfficientMicroBlock>(&bytes) {
+                let mut tx_hashes = Vec::with_capacity(block.transaction_hashes.len());
+                for reference in &block.transaction_hashes {
+                    match self.load_referenced_transaction(reference)? {
+                        Some(tx) => tx_hashes.push(tx.hash),
+                        None => return Ok(Some(ChainBreakKind::TransactionHashMismatch { tx_hash: hex::encode(reference) })),
+                    }
+                }
+                (block.height, block.previous_hash, block.merkle_root, tx_hashes)
+            } else {
+                match bincode::deserialize::<qnet_state::MicroBlock>(&bytes) {
+                    Ok(block) => {
+                        let tx_hashes = block.transactions.into_iter().map(|tx| tx.hash).collect();
+                        (block.height, block.previous_hash, block.merkle_root, tx_hashes)
+                    }
+                    Err(e) => return Ok(Some(ChainBreakKind::Undecodable(e.to_string()))),
+                }
+            };
+        
+        if stored_height != height {
+            return Ok(Some(ChainBreakKind::HeightMismatch { stored: stored_height }));
+        }
+        let linked = match (height, below) {
+            (0, _) => previous_hash == [0u8; 32],
+            (_, Some(below)) => previous_hash == *below,
+            (_, None) => true,
+        };
+        if !linked {
+            return Ok(Some(ChainBreakKind::PreviousHashMismatch));
+        }
+        
+        let leaves: Vec<[u8; 32]> = tx_hashes.iter()
+            .map(|tx_hash| qnet_state::merkle::transaction_leaf(tx_hash))
+            .collect();
+        if merkle_root != qnet_state::merkle::merkle_root(&leaves) && merkle_root != legacy_merkle_root(&tx_hashes) {
+            return Ok(Some(ChainBreakKind::MerkleRootMismatch));
+        }
+        Ok(None)
+    }
+    
+    /// Transaction stored under `reference`, if its stored bytes still hash to it
+    fn load_referenced_transaction(&self, reference: &[u8; 32]) -> IntegrationResult<Option<qnet_state::Transaction>> {
+        let tx_cf = self.db.cf_handle("transactions")
+            .ok_or_else(|| IntegrationError::StorageError("transactions column family not found".to_string()))?;
+        let Some(data) = self.db.get_cf(&tx_cf, format!("tx_{}", hex::encode(reference)).as_bytes())? else {
+            return Ok(None);
+        };
+        let tx_data = if data.len() >= 4 && data[0..4] == [0x28, 0xb5, 0x2f, 0xfd] {
+            match zstd::decode_all(&data[..]) {
+                Ok(tx_data) => tx_data,
+                Err(_) => return Ok(None),
+            }
+        } else {
+            data
+        };
+        if stored_block_hash(&tx_data) != *reference {
+            return Ok(None);
+        }
+        Ok(bincode::deserialize(&tx_data).ok())
+    }
+    
+    /// Whether a microblock is stored at `height`, without copying it out of RocksDB
+    pub fn has_microblock(&self, height: u64) -> IntegrationResult<bool> {
+        let microblocks_cf = self.db.cf_handle("microblocks")
+            .ok_or_else(|| IntegrationError::StorageError("microblocks column family not found".to_string()))?;
+        
+        let key = format!("microblock_{}", height);
+        // Bloom filter rules out most missing heights without reading data blocks
+        if !self.db.key_may_exist_cf(&microblocks_cf, key.as_bytes()) {
+            return Ok(false);
+        }
+        Ok(self.db.get_pinned_cf(&microblocks_cf, key.as_bytes())?.is_some())
+    }
+    
     /// Delete a microblock at the specified height (for fork resolution)
     pub fn delete_microblock(&self, height: u64) -> IntegrationResult<()> {
         let microblocks_cf = self.db.cf_handle("microblocks")
             .ok_or_else(|| IntegrationError::StorageError("microblocks column family not found".to_string()))?;
         
         let key = format!("microblock_{}", height);
-        self.db.delete_cf(&microblocks_cf, key.as_bytes())?;
+        self.delete_cf(&microblocks_cf, key.as_bytes())?;
         
         Ok(())
     }
@@ -1202,7 +1741,20 @@ impl PersistentStorage {
         let data = bincode::serialize(poh_state)
             .map_err(|e| IntegrationError::SerializationError(e.to_string()))?;
         
-        self.db.put_cf(&poh_cf, key.as_bytes(), &data)?;
+        self.put_cf(&poh_cf, key.as_bytes(), &data)?;
+        Ok(())
+    }
+    
+    /// Add PoH state for a block height to a pending write batch
+    fn put_poh_state(&self, batch: &mut WriteBatch, poh_state: &qnet_state::PoHState) -> IntegrationResult<()> {
+        let poh_cf = self.db.cf_handle("poh_state")
+            .ok_or_else(|| IntegrationError::StorageError("poh_state column family not found".to_string()))?;
+        
+        let key = format!("poh_{}", poh_state.height);
+        let data = bincode::serialize(poh_state)
+            .map_err(|e| IntegrationError::SerializationError(e.to_string()))?;
+        
+        batch.put_cf(&poh_cf, key.as_bytes(), &data);
         Ok(())
     }
     
@@ -1229,7 +1781,7 @@ impl PersistentStorage {
             .ok_or_else(|| IntegrationError::StorageError("poh_state column family not found".to_string()))?;
         
         let key = format!("poh_{}", height);
-        self.db.delete_cf(&poh_cf, key.as_bytes())?;
+        self.delete_cf(&poh_cf, key.as_bytes())?;
         Ok(())
     }
     
@@ -1263,7 +1815,7 @@ impl PersistentStorage {
             .ok_or_else(|| IntegrationError::StorageError("metadata column family not found".to_string()))?;
         
         let key = format!("macroblock_{}", height);
-        let data = bincode::serialize(macroblock)
+        let data = macroblock.serialize_versioned()
             .map_err(|e| IntegrationError::SerializationError(e.to_string()))?;
         
         let mut batch = WriteBatch::default();
@@ -1273,12 +1825,22 @@ impl PersistentStorage {
         let hash = macroblock.hash();
         batch.put_cf(&metadata_cf, b"latest_macroblock_hash", &hash);
         
-        self.db.write(batch)?;
+        self.write(batch)?;
         Ok(())
     }
     
     /// Get macroblock by its index (height / 90)
+    /// Returns plain bincode of the current `MacroBlock` layout, whatever version it was stored in
     pub fn get_macroblock_by_height(&self, macroblock_index: u64) -> IntegrationResult<Option<Vec<u8>>> {
+        match self.load_macroblock(macroblock_index)? {
+            Some(macroblock) => Ok(Some(bincode::serialize(&macroblock)
+                .map_err(|e| IntegrationError::SerializationError(e.to_string()))?)),
+            None => Ok(None),
+        }
+    }
+    
+    /// Load and decode a versioned macroblock by its index (height / 90)
+    pub fn load_macroblock(&self, macroblock_index: u64) -> IntegrationResult<Option<qnet_state::MacroBlock>> {
         let microblocks_cf = self.db.cf_handle("microblocks")
             .ok_or_else(|| IntegrationError::StorageError("microblocks column family not found".to_string()))?;
         
@@ -1288,11 +1850,20 @@ impl PersistentStorage {
         let key = format!("macroblock_{}", macroblock_index);
         
         match self.db.get_cf(&microblocks_cf, key.as_bytes())? {
-            Some(data) => Ok(Some(data)),
+            Some(data) => qnet_state::MacroBlock::deserialize_versioned(&data)
+                .map(Some)
+                .map_err(|e| IntegrationError::SerializationError(
+                    format!("Failed to decode macroblock {}: {}", macroblock_index, e)
+                )),
             None => Ok(None),
         }
     }
     
+    /// Commit/reveal set that finalized macroblock `macroblock_index`, `None` if not stored yet
+    pub fn get_macroblock_consensus(&self, macroblock_index: u64) -> IntegrationResult<Option<qnet_state::ConsensusData>> {
+        Ok(self.load_macroblock(macroblock_index)?.map(|macroblock| macroblock.consensus_data))
+    }
+    
     pub fn get_stats(&self) -> IntegrationResult<StorageStats> {
         let mut stats = StorageStats::default();
         
@@ -1344,10 +1915,10 @@ impl PersistentStorage {
             .ok_or_else(|| IntegrationError::StorageError("consensus column family not found".to_string()))?;
         
         let key = format!("round_{}", round);
-        self.db.put_cf(&consensus_cf, key.as_bytes(), state)?;
+        self.put_cf(&consensus_cf, key.as_bytes(), state)?;
         
         // Update latest round for quick lookup
-        self.db.put_cf(&consensus_cf, b"latest_round", &round.to_be_bytes())?;
+        self.put_cf(&consensus_cf, b"latest_round", &round.to_be_bytes())?;
         
         Ok(())
     }
@@ -1384,7 +1955,7 @@ impl PersistentStorage {
         let data = bincode::serialize(&(from_height, to_height, current))
             .map_err(|e| IntegrationError::SerializationError(e.to_string()))?;
         
-        self.db.put_cf(&sync_cf, b"sync_progress", &data)?;
+        self.put_cf(&sync_cf, b"sync_progress", &data)?;
         Ok(())
     }
     
@@ -1408,7 +1979,7 @@ impl PersistentStorage {
         let sync_cf = self.db.cf_handle("sync_state")
             .ok_or_else(|| IntegrationError::StorageError("sync_state column family not found".to_string()))?;
         
-        self.db.delete_cf(&sync_cf, b"sync_progress")?;
+        self.delete_cf(&sync_cf, b"sync_progress")?;
         Ok(())
     }
     
@@ -1695,6 +2266,194 @@ pub struct PatternRecognizer {
     pattern_stats: HashMap<TransactionPattern, u64>,
 }
 
+/// SHA3-256 of stored bytes: what the next microblock's `previous_hash` commits to
+fn stored_block_hash(data: &[u8]) -> [u8; 32] {
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(&Sha3_256::digest(data));
+    hash
+}
+
+/// Flat root of blocks produced before Merkle tree roots: SHA3-256 over the hash strings
+fn legacy_merkle_root(tx_hashes: &[String]) -> [u8; 32] {
+    if tx_hashes.is_empty() {
+        return [0u8; 32];
+    }
+    let mut hasher = Sha3_256::new();
+    for tx_hash in tx_hashes {
+        hasher.update(tx_hash.as_bytes());
+    }
+    let mut root = [0u8; 32];
+    root.copy_from_slice(&hasher.finalize());
+    root
+}
+
+/// Node registration: (node_type, wallet, reputation)
+pub type NodeRegistration = (String, String, f64);
+
+/// Merkle proof that a transaction is included in a stored microblock
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionInclusionProof {
+    pub height: u64,
+    pub merkle_root: [u8; 32],
+    pub proof: qnet_state::MerkleProof,
+}
+
+/// Why chain verification stopped at a block
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, thiserror::Error)]
+pub enum ChainBreakKind {
+    #[error("no block stored")]
+    Missing,
+    
+    #[error("stored bytes are not a microblock: {0}")]
+    Undecodable(String),
+    
+    #[error("block claims height {stored}")]
+    HeightMismatch { stored: u64 },
+    
+    #[error("previous_hash does not match the stored block below")]
+    PreviousHashMismatch,
+    
+    #[error("transaction {tx_hash} is missing or does not match its hash")]
+    TransactionHashMismatch { tx_hash: String },
+    
+    #[error("merkle_root does not match the transactions")]
+    MerkleRootMismatch,
+}
+
+/// First block that failed verification
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainBreak {
+    pub height: u64,
+    pub kind: ChainBreakKind,
+}
+
+/// Result of `PersistentStorage::verify_chain`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainVerifyReport {
+    pub from: u64,
+    pub to: u64,
+    /// Blocks that passed every check before the first break
+    pub verified: u64,
+    pub first_break: Option<ChainBreak>,
+}
+
+impl ChainVerifyReport {
+    pub fn is_intact(&self) -> bool {
+        self.first_break.is_none()
+    }
+}
+
+/// Default number of node registrations kept in memory
+pub const DEFAULT_NODE_REGISTRATION_CACHE_SIZE: usize = 100_000;
+
+/// Hit/miss counters of the node registration cache
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RegistrationCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+    pub capacity: usize,
+}
+
+impl RegistrationCacheStats {
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            return 0.0;
+        }
+        self.hits as f64 / total as f64
+    }
+}
+
+/// Bounded read-through cache in front of the `node_registry` column family
+/// Reward processing looks up every eligible node each window while registrations
+/// rarely change, so lookups (including "not registered") are served from memory
+/// until the registration is saved again.
+pub struct NodeRegistrationCache {
+    capacity: usize,
+    /// node_id -> (registration, last access tick)
+    entries: RwLock<HashMap<String, (Option<NodeRegistration>, u64)>>,
+    tick: std::sync::atomic::AtomicU64,
+    hits: std::sync::atomic::AtomicU64,
+    misses: std::sync::atomic::AtomicU64,
+}
+
+impl NodeRegistrationCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: RwLock::new(HashMap::new()),
+            tick: std::sync::atomic::AtomicU64::new(0),
+            hits: std::sync::atomic::AtomicU64::new(0),
+            misses: std::sync::atomic::AtomicU64::new(0),
+        }
+    }
+    
+    /// Cache sized by `QNET_NODE_REGISTRATION_CACHE_SIZE`
+    pub fn from_env() -> Self {
+        let capacity = std::env::var("QNET_NODE_REGISTRATION_CACHE_SIZE")
+            .ok()
+            .and_then(|v| v.trim().parse::<usize>().ok())
+            .filter(|&v| v > 0)
+            .unwrap_or(DEFAULT_NODE_REGISTRATION_CACHE_SIZE);
+        Self::new(capacity)
+    }
+    
+    fn next_tick(&self) -> u64 {
+        self.tick.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+    }
+    
+    /// Cached lookup result; `Some(None)` means "known not registered"
+    pub fn get(&self, node_id: &str) -> Option<Option<NodeRegistration>> {
+        use std::sync::atomic::Ordering;
+        let tick = self.next_tick();
+        let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+        match entries.get_mut(node_id) {
+            Some((registration, last_used)) => {
+                *last_used = tick;
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                Some(registration.clone())
+            }
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+    
+    /// Remember a lookup result, evicting the least recently used tenth when full
+    pub fn insert(&self, node_id: &str, registration: Option<NodeRegistration>) {
+        let tick = self.next_tick();
+        let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+        if !entries.contains_key(node_id) && entries.len() >= self.capacity {
+            let mut by_age: Vec<(u64, String)> = entries.iter()
+                .map(|(id, (_, last_used))| (*last_used, id.clone()))
+                .collect();
+            by_age.sort_unstable();
+            let evict = (self.capacity / 10).max(1);
+            for (_, id) in by_age.into_iter().take(evict) {
+                entries.remove(&id);
+            }
+        }
+        entries.insert(node_id.to_string(), (registration, tick));
+    }
+    
+    /// Drop a node so the next lookup reads storage
+    pub fn invalidate(&self, node_id: &str) {
+        self.entries.write().unwrap_or_else(|e| e.into_inner()).remove(node_id);
+    }
+    
+    pub fn stats(&self) -> RegistrationCacheStats {
+        use std::sync::atomic::Ordering;
+        RegistrationCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: self.entries.read().map(|e| e.len()).unwrap_or(0),
+            capacity: self.capacity,
+        }
+    }
+}
+
 pub struct Storage {
     persistent: PersistentStorage,
     /// Transaction pool for efficient storage without duplication
@@ -1717,6 +2476,10 @@ pub struct Storage {
     graceful_degradation: Arc<RwLock<GracefulDegradation>>,
     /// Light node header rotation (for Light mode only)
     light_rotation: Arc<RwLock<LightNodeRotation>>,
+    /// Read-through cache for `load_node_registration`
+    registration_cache: Arc<NodeRegistrationCache>,
+    /// Network size estimate resolved at startup, shared by all auto-scaling
+    network_size: NetworkSizeEstimate,
 }
 
 // ============================================================================
@@ -1828,7 +2591,7 @@ impl Storage {
         }
         
         if deleted > 0 {
-            self.persistent.db.write(batch)?;
+            self.persistent.write(batch)?;
             rotation.decrement(deleted);
             println!("[LightRotation] 🔄 Rotated {} old headers (keeping last {})", 
                 deleted, rotation.max_headers);
@@ -1890,28 +2653,54 @@ impl Storage {
         self.persistent.load_raw(key)
     }
     
+    /// Save a PoH checkpoint; aged checkpoints move to cold storage
+    pub fn save_poh_checkpoint(&self, key: &str, data: &[u8]) -> IntegrationResult<()> {
+        self.persistent.save_poh_checkpoint(key, data)
+    }
+    
+    /// Load a PoH checkpoint from hot or cold storage
+    pub fn load_poh_checkpoint(&self, key: &str) -> IntegrationResult<Option<Vec<u8>>> {
+        self.persistent.load_poh_checkpoint(key)
+    }
+    
+    /// Save an archive chunk to cold storage
+    pub fn save_archive_chunk(&self, chunk: &crate::archive_manager::ArchiveChunk) -> IntegrationResult<()> {
+        self.persistent.save_archive_chunk(chunk)
+    }
+    
+    pub fn load_archive_chunk(&self, chunk_id: &crate::archive_manager::ChunkId) -> IntegrationResult<Option<crate::archive_manager::ArchiveChunk>> {
+        self.persistent.load_archive_chunk(chunk_id)
+    }
+    
     pub fn new(data_dir: &str) -> IntegrationResult<Self> {
-        let persistent = PersistentStorage::new(data_dir)?;
-        let transaction_pool = TransactionPool::new();
-        
         // Detect node type from environment or config
         let node_type = std::env::var("QNET_NODE_TYPE").unwrap_or_else(|_| "full".to_string());
         
+        let wal_sync = WalSyncPolicy::from_env().unwrap_or(match node_type.as_str() {
+            "light" => StorageTierConfig::light().wal_sync,
+            "super" | "bootstrap" => StorageTierConfig::super_node().wal_sync,
+            _ => StorageTierConfig::full().wal_sync,
+        });
+        println!("[Storage] 💾 WAL sync policy: {:?}", wal_sync);
+        let layout = StorageLayout::from_env(data_dir);
+        if layout.hot_dir != layout.cold_dir {
+            println!("[Storage] 💾 Hot data: {}, cold data: {}", layout.hot_dir.display(), layout.cold_dir.display());
+        }
+        let persistent = PersistentStorage::open_layout(&layout, wal_sync)?;
+        let transaction_pool = TransactionPool::new();
+        
         // DYNAMIC SHARD CALCULATION: Automatically scales with network growth
-        // Uses existing calculate_optimal_shards() from reward_sharding module
+        // Shares the network size estimate with mempool sizing and sharding auto-enable
         // NOTE: Shard count is calculated ONCE at startup and remains fixed during operation
         // This ensures storage consistency. Recalculation happens on node restart/update.
         // Production workflow: Rolling restart updates shard count across network.
+        let network_size = Self::estimate_network_size_from_storage(&persistent);
         let active_shards = if let Ok(manual_shards) = std::env::var("QNET_ACTIVE_SHARDS") {
             // Manual override for testing or specific deployment needs
-            manual_shards.parse::<u64>().unwrap_or_else(|_| {
-                let network_size = Self::estimate_network_size_from_storage(&persistent);
-                crate::reward_sharding::calculate_optimal_shards(network_size) as u64
-            })
+            manual_shards.parse::<u64>().unwrap_or_else(|_| network_size.storage_shard_count())
         } else {
             // AUTO-DETECTION: Calculate based on blockchain registry and heuristics
-            let network_size = Self::estimate_network_size_from_storage(&persistent);
-            let optimal_shards = crate::reward_sharding::calculate_optimal_shards(network_size) as u64;
+            let optimal_shards = network_size.storage_shard_count();
             
             println!("[Storage] ⚡ AUTO-SCALING: Calculated optimal shards: {}", optimal_shards);
             
@@ -2057,6 +2846,8 @@ impl Storage {
             tier_config,
             graceful_degradation: Arc::new(RwLock::new(graceful_degradation)),
             light_rotation: Arc::new(RwLock::new(light_rotation)),
+            registration_cache: Arc::new(NodeRegistrationCache::from_env()),
+            network_size,
         })
     }
     
@@ -2116,6 +2907,36 @@ impl Storage {
         }
         
         // Step 2: Check if storage is critically full
+        self.ensure_space_for_microblocks()?;
+        
+        // Step 3: Encode for the effective storage mode and write atomically
+        let mut batch = WriteBatch::default();
+        let stored = self.encode_microblock(height, data, &mut batch)?;
+        self.persistent.write_microblocks_batch(batch, &[(height, stored)])
+    }
+    
+    /// Save a chunk of synced microblocks in one atomic RocksDB write
+    /// Each block is stored exactly as `save_microblock` would store it; transaction
+    /// data, PoH states and the chain height commit together with the blocks
+    pub fn save_microblocks_batch(&self, blocks: &[(u64, Vec<u8>)]) -> IntegrationResult<()> {
+        if blocks.is_empty() {
+            return Ok(());
+        }
+        if blocks.iter().any(|(height, _)| height % 100 == 0) {
+            let _ = self.check_and_apply_degradation();
+        }
+        self.ensure_space_for_microblocks()?;
+        
+        let mut batch = WriteBatch::default();
+        let mut stored = Vec::with_capacity(blocks.len());
+        for (height, data) in blocks {
+            stored.push((*height, self.encode_microblock(*height, data, &mut batch)?));
+        }
+        self.persistent.write_microblocks_batch(batch, &stored)
+    }
+    
+    /// Emergency cleanup and degradation when storage is critically full
+    fn ensure_space_for_microblocks(&self) -> IntegrationResult<()> {
         if self.is_storage_critically_full()? {
             println!("[Storage] 🚨 Storage critically full - attempting emergency cleanup");
             self.emergency_cleanup()?;
@@ -2133,8 +2954,12 @@ impl Storage {
                 }
             }
         }
-        
-        // Step 3: Use effective storage mode (may be degraded)
+        Ok(())
+    }
+    
+    /// Bytes to store for a microblock under the effective storage mode
+    /// Side data (Full/Super: transactions, indices, PoH state) is added to `batch`
+    fn encode_microblock(&self, height: u64, data: &[u8], batch: &mut WriteBatch) -> IntegrationResult<Vec<u8>> {
         let effective_mode = self.get_effective_storage_mode();
         
         match effective_mode {
@@ -2162,30 +2987,28 @@ impl Storage {
                         println!("[Storage] 🔄 Light mode: rotated {} old headers", rotated);
                     }
                     
-                    return self.persistent.save_microblock(height, &compressed);
+                    return Ok(compressed);
                 }
                 // Fallback for non-MicroBlock data
-                self.persistent.save_microblock(height, data)
+                Ok(data.to_vec())
             },
             StorageMode::Full | StorageMode::Super => {
                 // FULL/SUPER MODE: Full block storage with EfficientMicroBlock format
                 if let Ok(microblock) = bincode::deserialize::<qnet_state::MicroBlock>(data) {
-                    return self.save_microblock_efficient(height, &microblock);
+                    return self.encode_microblock_efficient(height, &microblock, batch);
                 }
                 
                 // Fallback: Apply adaptive compression to raw data
-        let compressed_data = if height > 0 {
-            self.compress_block_adaptive(data, height)?
-        } else {
-            data.to_vec()
-        };
-        
-        self.persistent.save_microblock(height, &compressed_data)
+                if height > 0 {
+                    self.compress_block_adaptive(data, height)
+                } else {
+                    Ok(data.to_vec())
+                }
             }
         }
     }
     
-    /// PRODUCTION: Save microblock in efficient format with separate TX storage
+    /// PRODUCTION: Encode microblock in efficient format with separate TX storage
     /// This is the PRIMARY storage method for new blocks (v2.19.8+)
     /// 
     /// Architecture:
@@ -2194,7 +3017,7 @@ impl Storage {
     /// - TX indices → tx_index, tx_by_address CFs
     /// 
     /// Storage savings: ~80% compared to legacy MicroBlock format
-    fn save_microblock_efficient(&self, height: u64, microblock: &qnet_state::MicroBlock) -> IntegrationResult<()> {
+    fn encode_microblock_efficient(&self, height: u64, microblock: &qnet_state::MicroBlock, batch: &mut WriteBatch) -> IntegrationResult<Vec<u8>> {
         let tx_cf = self.persistent.db.cf_handle("transactions")
             .ok_or_else(|| IntegrationError::StorageError("transactions column family not found".to_string()))?;
         let tx_index_cf = self.persistent.db.cf_handle("tx_index")
@@ -2202,7 +3025,6 @@ impl Storage {
         let tx_by_addr_cf = self.persistent.db.cf_handle("tx_by_address")
             .ok_or_else(|| IntegrationError::StorageError("tx_by_address column family not found".to_string()))?;
         
-        let mut batch = WriteBatch::default();
         let mut tx_hashes: Vec<[u8; 32]> = Vec::with_capacity(microblock.transactions.len());
         let mut total_original_size = 0usize;
         let mut total_compressed_size = 0usize;
@@ -2250,6 +3072,10 @@ impl Storage {
             
             // INDEX: tx_hash -> block_height for O(1) transaction location
             batch.put_cf(&tx_index_cf, tx_key.as_bytes(), &height.to_be_bytes());
+            // Also under the transaction's own hash, which is what clients look it up by
+            if tx.hash != hex::encode(tx_hash) {
+                batch.put_cf(&tx_index_cf, format!("tx_{}", tx.hash).as_bytes(), &height.to_be_bytes());
+            }
             
             // INDEX: address -> tx_hash for account transaction queries
             let timestamp = tx.timestamp;
@@ -2285,7 +3111,7 @@ impl Storage {
         // Step 3: Save PoH state separately for fast validation (v2.19.13)
         // This enables O(1) PoH validation without loading full block
         let poh_state = qnet_state::PoHState::from_microblock(microblock);
-        self.persistent.save_poh_state(&poh_state)?;
+        self.persistent.put_poh_state(batch, &poh_state)?;
         
         // Serialize EfficientMicroBlock (much smaller than full MicroBlock)
         let efficient_data = bincode::serialize(&efficient_block)
@@ -2294,12 +3120,6 @@ impl Storage {
         // Apply adaptive compression to EfficientMicroBlock
         let compressed_block = self.compress_block_adaptive(&efficient_data, height)?;
         
-        // Write all in single atomic batch
-        self.persistent.db.write(batch)?;
-        
-        // Save the efficient block
-        self.persistent.save_microblock(height, &compressed_block)?;
-        
         // Log savings for monitoring (every 100 blocks)
         if height % 100 == 0 {
             let original_size = bincode::serialize(microblock).unwrap_or_default().len();
@@ -2309,13 +3129,24 @@ impl Storage {
                      height, original_size, efficient_size, savings, microblock.transactions.len());
         }
         
-        Ok(())
+        // Block, transactions and PoH state are written together by the caller
+        Ok(compressed_block)
     }
     
     pub fn load_microblock(&self, height: u64) -> IntegrationResult<Option<Vec<u8>>> {
         self.persistent.load_microblock(height)
     }
     
+    /// Verify the stored chain `from..=to` (see `PersistentStorage::verify_chain`)
+    pub fn verify_chain(&self, from: u64, to: u64) -> IntegrationResult<ChainVerifyReport> {
+        self.persistent.verify_chain(from, to)
+    }
+    
+    /// Whether a microblock is stored at `height` (dedup checks, no decode)
+    pub fn has_microblock(&self, height: u64) -> IntegrationResult<bool> {
+        self.persistent.has_microblock(height)
+    }
+    
     /// Delete a microblock at the specified height (for fork resolution)
     pub fn delete_microblock(&self, height: u64) -> IntegrationResult<()> {
         println!("[Storage] 🗑️ Deleting microblock at height {}", height);
@@ -2360,6 +3191,11 @@ impl Storage {
         self.persistent.get_macroblock_by_height(macroblock_index)
     }
     
+    /// Commit/reveal set that finalized macroblock `macroblock_index`, `None` if not stored yet
+    pub fn get_macroblock_consensus(&self, macroblock_index: u64) -> IntegrationResult<Option<qnet_state::ConsensusData>> {
+        self.persistent.get_macroblock_consensus(macroblock_index)
+    }
+    
     /// Save state snapshot for efficient storage
     pub async fn save_state_snapshot(&self, height: u64, state_root: [u8; 32], state_data: Vec<u8>) -> IntegrationResult<()> {
         // State snapshots are saved separately for efficient retrieval
@@ -2372,11 +3208,11 @@ impl Storage {
         let compressed = zstd::encode_all(&state_data[..], 15)
             .map_err(|e| IntegrationError::Other(format!("State compression error: {}", e)))?;
         
-        self.persistent.db.put_cf(&snapshots_cf, key.as_bytes(), &compressed)?;
+        self.persistent.put_cf(&snapshots_cf, key.as_bytes(), &compressed)?;
         
         // Store state root for verification
         let root_key = format!("state_root_{}", height);
-        self.persistent.db.put_cf(&snapshots_cf, root_key.as_bytes(), &state_root)?;
+        self.persistent.put_cf(&snapshots_cf, root_key.as_bytes(), &state_root)?;
         
         println!("[STATE] 💾 Saved state snapshot at height {} ({} KB compressed)", 
                 height, compressed.len() / 1024);
@@ -2391,7 +3227,7 @@ impl Storage {
             .map_err(|e| format!("Failed to serialize checkpoint: {}", e))?;
         
         let key = format!("checkpoint_{}", height);
-        self.persistent.db.put(key, serialized)
+        self.persistent.put(key, serialized)
             .map_err(|e| format!("Failed to save checkpoint: {}", e))?;
         
         println!("[STORAGE] 📍 Checkpoint saved at height {}", height);
@@ -2401,7 +3237,7 @@ impl Storage {
     /// Set a flag in storage (for emergency/critical markers)
     pub fn set_flag(&self, key: &str, value: bool) -> Result<(), String> {
         let flag_value = if value { vec![1u8] } else { vec![0u8] };
-        self.persistent.db.put(key, flag_value)
+        self.persistent.put(key, flag_value)
             .map_err(|e| format!("Failed to set flag {}: {}", key, e))
     }
     
@@ -2410,7 +3246,7 @@ impl Storage {
         let serialized = bincode::serialize(data)
             .map_err(|e| format!("Failed to serialize data: {}", e))?;
         
-        self.persistent.db.put(key, serialized)
+        self.persistent.put(key, serialized)
             .map_err(|e| format!("Failed to save data: {}", e))
     }
     
@@ -2485,45 +3321,29 @@ impl Storage {
         Ok(())
     }
     
-    /// Public wrapper for network size estimation (used by node configuration)
-    pub fn estimate_network_size_for_config(&self) -> usize {
-        Self::estimate_network_size_from_storage(&self.persistent)
+    /// Network size estimate used for mempool sizing, shard count and sharding auto-enable
+    pub fn network_size_estimate(&self) -> NetworkSizeEstimate {
+        self.network_size
     }
     
-    /// Estimate total network size for dynamic shard calculation
-    /// Uses multi-source detection: blockchain, environment, heuristics
-    fn estimate_network_size_from_storage(persistent: &PersistentStorage) -> usize {
-        // Priority 1: Explicit network size from monitoring/orchestration
-        if let Ok(size_str) = std::env::var("QNET_TOTAL_NETWORK_NODES") {
-            if let Ok(size) = size_str.parse::<usize>() {
-                println!("[Storage] 📊 Network size from monitoring: {} nodes", size);
-                return size;
-            }
-        }
-        
-        // Priority 2: Genesis phase detection (5 bootstrap nodes)
-        if std::env::var("QNET_BOOTSTRAP_ID").is_ok() {
-            println!("[Storage] 🌱 Genesis phase: 5 bootstrap nodes");
-            return 5;
-        }
+    /// Resolve the network size estimate (see `network_size` for the definition)
+    fn estimate_network_size_from_storage(persistent: &PersistentStorage) -> NetworkSizeEstimate {
+        // Activated nodes recorded in the blockchain registry
+        let registered_nodes = persistent.db.cf_handle("activations")
+            .map(|activations_cf| {
+                persistent.db.iterator_cf(activations_cf, rocksdb::IteratorMode::Start).count()
+            })
+            .unwrap_or(0);
         
-        // Priority 3: Read actual node activations from blockchain storage
-        if let Some(activations_cf) = persistent.db.cf_handle("activations") {
-            let mut count = 0;
-            let iter = persistent.db.iterator_cf(activations_cf, rocksdb::IteratorMode::Start);
-            for _ in iter {
-                count += 1;
-            }
-            
-            if count > 0 {
-                println!("[Storage] 🔗 Blockchain registry: {} activated nodes", count);
-                return count;
-            }
+        let estimate = NetworkSizeEstimate::from_env(registered_nodes);
+        match estimate.source {
+            NetworkSizeSource::Override => println!("[Storage] 📊 Network size from monitoring: {} nodes", estimate.nodes),
+            NetworkSizeSource::Genesis => println!("[Storage] 🌱 Genesis phase: {} bootstrap nodes", estimate.nodes),
+            NetworkSizeSource::Registry => println!("[Storage] 🔗 Blockchain registry: {} activated nodes", estimate.nodes),
+            NetworkSizeSource::Default => println!("[Storage] ⚠️ No network data found, using conservative default: {} nodes", estimate.nodes),
         }
-        
-        // Priority 4: Conservative default (small network assumption)
-        println!("[Storage] ⚠️ No network data found, using conservative default: 100 nodes");
-        100 // Conservative: assume small network to avoid over-sharding
+        estimate.publish();
+        estimate
     }
     
     /// Estimate Super node count in the network (conservative approximation)
@@ -2595,7 +3415,7 @@ impl Storage {
         }
         
         if pruned > 0 {
-            self.persistent.db.write(batch)?;
+            self.persistent.write(batch)?;
             println!("[PRUNING] ✅ Pruned {} microblocks (3 leader rotations finalized)", pruned);
         }
         
@@ -2644,6 +3464,41 @@ impl Storage {
         self.persistent.get_transactions_by_address(address, page, per_page).await
     }
     
+    /// Merkle proof of `tx_hash` against the `merkle_root` of the microblock that includes it
+    /// Returns `None` if the transaction is not in any stored block
+    pub fn transaction_inclusion_proof(&self, tx_hash: &str) -> IntegrationResult<Option<TransactionInclusionProof>> {
+        let tx_index_cf = self.persistent.db.cf_handle("tx_index")
+            .ok_or_else(|| IntegrationError::StorageError("tx_index column family not found".to_string()))?;
+        let height = match self.persistent.db.get_cf(&tx_index_cf, format!("tx_{}", tx_hash).as_bytes())? {
+            Some(data) if data.len() >= 8 => u64::from_be_bytes(data[0..8].try_into().unwrap_or([0u8; 8])),
+            _ => return Ok(None),
+        };
+        let microblock = match self.load_microblock_auto_format(height)? {
+            Some(microblock) => microblock,
+            None => return Ok(None),
+        };
+        
+        let leaves: Vec<[u8; 32]> = microblock.transactions.iter()
+            .map(|tx| qnet_state::merkle::transaction_leaf(&tx.hash))
+            .collect();
+        let index = match microblock.transactions.iter().position(|tx| tx.hash == tx_hash) {
+            Some(index) => index,
+            None => return Ok(None),
+        };
+        // Blocks produced before Merkle tree roots carry a flat hash that cannot be proven against
+        if qnet_state::merkle::merkle_root(&leaves) != microblock.merkle_root {
+            return Err(IntegrationError::ValidationError(format!(
+                "Microblock {} merkle_root is not a transaction Merkle tree root", height
+            )));
+        }
+        
+        Ok(qnet_state::MerkleProof::generate(&leaves, index).map(|proof| TransactionInclusionProof {
+            height,
+            merkle_root: microblock.merkle_root,
+            proof,
+        }))
+    }
+    
     /// Count transactions for an address
     pub async fn count_transactions_by_address(&self, address: &str) -> IntegrationResult<usize> {
         self.persistent.count_transactions_by_address(address).await
@@ -2845,7 +3700,7 @@ impl Storage {
         };
         
         // CRITICAL: Decompress if Zstd-compressed (magic bytes: 0x28 0xb5 0x2f 0xfd)
-        // Data is compressed in save_microblock_efficient via compress_block_adaptive
+        // Data is compressed in encode_microblock_efficient via compress_block_adaptive
         let microblock_data = if raw_data.len() >= 4 && raw_data[0..4] == [0x28, 0xb5, 0x2f, 0xfd] {
             zstd::decode_all(&raw_data[..])
                 .map_err(|e| IntegrationError::Other(format!("Zstd decompression failed: {}", e)))?
@@ -3699,7 +4554,7 @@ impl Storage {
             
             // Apply batch
             if !batch.is_empty() {
-                self.persistent.db.write(batch)?;
+                self.persistent.write(batch)?;
                 println!("[Storage] 📦 Recompressed batch {}-{}: {} blocks, saved {} KB",
                         batch_start, batch_end, recompressed_count, space_saved / 1024);
             }
@@ -3800,7 +4655,7 @@ impl Storage {
                         
                         // Apply batch every 1000 transactions
                         if recompressed_count % 1000 == 0 {
-                            self.persistent.db.write(batch)?;
+                            self.persistent.write(batch)?;
                             batch = WriteBatch::default();
                             // Brief pause to allow other operations (non-blocking)
                             std::thread::sleep(std::time::Duration::from_millis(1));
@@ -3817,7 +4672,7 @@ impl Storage {
         
         // Apply remaining batch
         if !batch.is_empty() {
-            self.persistent.db.write(batch)?;
+            self.persistent.write(batch)?;
         }
         
         // Compact to reclaim space
@@ -3881,10 +4736,34 @@ impl Storage {
         let data = bincode::serialize(reward)
             .map_err(|e| IntegrationError::SerializationError(e.to_string()))?;
         
-        self.persistent.db.put_cf(&rewards_cf, key.as_bytes(), &data)?;
+        self.persistent.put_cf(&rewards_cf, key.as_bytes(), &data)?;
         Ok(())
     }
     
+    /// Save reward window cursor (start of the next unprocessed reward window)
+    pub fn save_reward_window_cursor(&self, cursor: u64) -> IntegrationResult<()> {
+        let metadata_cf = self.persistent.db.cf_handle("metadata")
+            .ok_or_else(|| IntegrationError::StorageError("metadata column family not found".to_string()))?;
+        
+        self.persistent.put_cf(&metadata_cf, b"reward_window_cursor", &cursor.to_be_bytes())?;
+        Ok(())
+    }
+    
+    /// Load reward window cursor (None if no window was processed yet)
+    pub fn load_reward_window_cursor(&self) -> IntegrationResult<Option<u64>> {
+        let metadata_cf = self.persistent.db.cf_handle("metadata")
+            .ok_or_else(|| IntegrationError::StorageError("metadata column family not found".to_string()))?;
+        
+        match self.persistent.db.get_cf(&metadata_cf, b"reward_window_cursor")? {
+            Some(data) if data.len() == 8 => {
+                let bytes: [u8; 8] = data[0..8].try_into()
+                    .map_err(|_| IntegrationError::StorageError("Invalid reward window cursor".to_string()))?;
+                Ok(Some(u64::from_be_bytes(bytes)))
+            }
+            _ => Ok(None),
+        }
+    }
+    
     /// Load pending reward for a node
     pub fn load_pending_reward(&self, node_id: &str) -> IntegrationResult<Option<qnet_consensus::lazy_rewards::PhaseAwareReward>> {
         let rewards_cf = self.persistent.db.cf_handle("pending_rewards")
@@ -3907,7 +4786,7 @@ impl Storage {
             .ok_or_else(|| IntegrationError::StorageError("pending_rewards column family not found".to_string()))?;
         
         let key = format!("reward_{}", node_id);
-        self.persistent.db.delete_cf(&rewards_cf, key.as_bytes())?;
+        self.persistent.delete_cf(&rewards_cf, key.as_bytes())?;
         Ok(())
     }
     
@@ -3938,7 +4817,7 @@ impl Storage {
     // SCALABILITY: NODE REGISTRY IN ROCKSDB
     // ============================================
     
-    /// Save node registration information
+    /// Save node registration information, also indexed by owner wallet (`wallet_{address}` -> node_id)
     pub fn save_node_registration(&self, node_id: &str, node_type: &str, wallet: &str, reputation: f64) -> IntegrationResult<()> {
         let registry_cf = self.persistent.db.cf_handle("node_registry")
             .ok_or_else(|| IntegrationError::StorageError("node_registry column family not found".to_string()))?;
@@ -3951,12 +4830,63 @@ impl Storage {
             "timestamp": SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
         });
         
-        self.persistent.db.put_cf(&registry_cf, key.as_bytes(), data.to_string().as_bytes())?;
+        let mut batch = WriteBatch::default();
+        batch.put_cf(&registry_cf, key.as_bytes(), data.to_string().as_bytes());
+        
+        // A node moved to another wallet no longer answers for the old one
+        if let Some((_, old_wallet, _)) = self.read_node_registration(node_id)? {
+            if old_wallet != wallet && self.read_wallet_index(&old_wallet)?.as_deref() == Some(node_id) {
+                batch.delete_cf(&registry_cf, format!("wallet_{}", old_wallet).as_bytes());
+            }
+        }
+        if !wallet.is_empty() {
+            batch.put_cf(&registry_cf, format!("wallet_{}", wallet).as_bytes(), node_id.as_bytes());
+        }
+        
+        self.persistent.write(batch)?;
+        self.registration_cache.invalidate(node_id);
         Ok(())
     }
     
-    /// Load node registration
-    pub fn load_node_registration(&self, node_id: &str) -> IntegrationResult<Option<(String, String, f64)>> {
+    /// Node operated by `wallet` as `(node_id, node_type)`, from the wallet index
+    pub fn load_node_by_wallet(&self, wallet: &str) -> IntegrationResult<Option<(String, String)>> {
+        let Some(node_id) = self.read_wallet_index(wallet)? else {
+            return Ok(None);
+        };
+        match self.load_node_registration(&node_id)? {
+            Some((node_type, registered_wallet, _)) if registered_wallet == wallet => Ok(Some((node_id, node_type))),
+            _ => Ok(None),
+        }
+    }
+    
+    fn read_wallet_index(&self, wallet: &str) -> IntegrationResult<Option<String>> {
+        let registry_cf = self.persistent.db.cf_handle("node_registry")
+            .ok_or_else(|| IntegrationError::StorageError("node_registry column family not found".to_string()))?;
+        
+        match self.persistent.db.get_cf(&registry_cf, format!("wallet_{}", wallet).as_bytes())? {
+            Some(data) => String::from_utf8(data)
+                .map(Some)
+                .map_err(|e| IntegrationError::DeserializationError(e.to_string())),
+            None => Ok(None),
+        }
+    }
+    
+    /// Load node registration, served from the registration cache when possible
+    pub fn load_node_registration(&self, node_id: &str) -> IntegrationResult<Option<NodeRegistration>> {
+        if let Some(cached) = self.registration_cache.get(node_id) {
+            return Ok(cached);
+        }
+        let registration = self.read_node_registration(node_id)?;
+        self.registration_cache.insert(node_id, registration.clone());
+        Ok(registration)
+    }
+    
+    /// Hit rate and size of the node registration cache
+    pub fn node_registration_cache_stats(&self) -> RegistrationCacheStats {
+        self.registration_cache.stats()
+    }
+    
+    fn read_node_registration(&self, node_id: &str) -> IntegrationResult<Option<NodeRegistration>> {
         let registry_cf = self.persistent.db.cf_handle("node_registry")
             .ok_or_else(|| IntegrationError::StorageError("node_registry column family not found".to_string()))?;
         
@@ -3995,7 +4925,7 @@ impl Storage {
             "timestamp": timestamp
         });
         
-        self.persistent.db.put_cf(&ping_cf, key.as_bytes(), data.to_string().as_bytes())?;
+        self.persistent.put_cf(&ping_cf, key.as_bytes(), data.to_string().as_bytes())?;
         
         // Cleanup old pings (older than 24 hours)
         self.cleanup_old_pings(node_id, timestamp - 86400)?;
@@ -4059,7 +4989,7 @@ impl Storage {
         }
         
         if batch.len() > 0 {
-            self.persistent.db.write(batch)?;
+            self.persistent.write(batch)?;
         }
         
         Ok(())
@@ -4090,7 +5020,7 @@ impl Storage {
             "timestamp": timestamp
         });
         
-        self.persistent.db.put_cf(&rep_cf, key.as_bytes(), data.to_string().as_bytes())?;
+        self.persistent.put_cf(&rep_cf, key.as_bytes(), data.to_string().as_bytes())?;
         
         // Cleanup old history (keep only last 7 days)
         self.cleanup_old_reputation_history(node_id, timestamp - (7 * 86400))?;
@@ -4160,7 +5090,7 @@ impl Storage {
         }
         
         if batch.len() > 0 {
-            self.persistent.db.write(batch)?;
+            self.persistent.write(batch)?;
         }
         
         Ok(())
@@ -4184,7 +5114,7 @@ impl Storage {
             "timestamp": timestamp
         });
         
-        self.persistent.db.put_cf(&att_cf, key.as_bytes(), data.to_string().as_bytes())?;
+        self.persistent.put_cf(&att_cf, key.as_bytes(), data.to_string().as_bytes())?;
         Ok(())
     }
     
@@ -4233,7 +5163,7 @@ impl Storage {
         }
         
         if batch.len() > 0 {
-            self.persistent.db.write(batch)?;
+            self.persistent.write(batch)?;
         }
         
         Ok(removed)
@@ -4249,7 +5179,7 @@ impl Storage {
             .ok_or_else(|| IntegrationError::StorageError("heartbeats column family not found".to_string()))?;
         
         // Key: hb_{node_id}_{4h_window}_{index} for deduplication per window
-        let window = timestamp - (timestamp % (4 * 60 * 60));
+        let window = crate::network_config::get_chain_params().reward_window_start(timestamp);
         let key = format!("hb_{}_{}_{}", node_id, window, heartbeat_index);
         let data = json!({
             "node_id": node_id,
@@ -4259,7 +5189,7 @@ impl Storage {
             "window": window
         });
         
-        self.persistent.db.put_cf(&hb_cf, key.as_bytes(), data.to_string().as_bytes())?;
+        self.persistent.put_cf(&hb_cf, key.as_bytes(), data.to_string().as_bytes())?;
         Ok(())
     }
     
@@ -4310,7 +5240,7 @@ impl Storage {
         }
         
         if batch.len() > 0 {
-            self.persistent.db.write(batch)?;
+            self.persistent.write(batch)?;
         }
         
         Ok(removed)
@@ -4337,7 +5267,7 @@ impl Storage {
         let value = bincode::serialize(event)
             .map_err(|e| IntegrationError::StorageError(format!("Failed to serialize failover event: {}", e)))?;
         
-        self.persistent.db.put_cf(&failover_cf, key.as_bytes(), &value)?;
+        self.persistent.put_cf(&failover_cf, key.as_bytes(), &value)?;
         
         // Auto-cleanup old events based on time relevance, not node type
         // Keep ~30 days of history (assuming ~100 failovers per day worst case)
@@ -4440,7 +5370,7 @@ impl Storage {
         
         // Apply time-based cleanup
         if old_count > 0 {
-            self.persistent.db.write(batch)?;
+            self.persistent.write(batch)?;
             println!("[STORAGE] Cleaned up {} failover events older than 30 days", old_count);
         }
         
@@ -4455,7 +5385,7 @@ impl Storage {
                 batch.delete_cf(&failover_cf, &key);
             }
             
-            self.persistent.db.write(batch)?;
+            self.persistent.write(batch)?;
             println!("[STORAGE] Trimmed {} oldest failover events to maintain {} limit", to_delete, max_events);
         }
         
@@ -4547,7 +5477,7 @@ impl Storage {
         final_data.extend_from_slice(&(compressed.len() as u64).to_le_bytes());
         final_data.extend_from_slice(&compressed);
         
-        self.persistent.db.put_cf(&snapshots_cf, snapshot_key.as_bytes(), &final_data)?;
+        self.persistent.put_cf(&snapshots_cf, snapshot_key.as_bytes(), &final_data)?;
         
         let duration = start_time.elapsed();
         println!("[SNAPSHOT] ✅ Incremental snapshot created: {} bytes in {:.2}s (base: {})", 
@@ -4621,10 +5551,10 @@ impl Storage {
         final_data.extend_from_slice(&(compressed.len() as u64).to_le_bytes()); // 8 bytes size
         final_data.extend_from_slice(&compressed); // Compressed data
         
-        self.persistent.db.put_cf(&snapshots_cf, snapshot_key.as_bytes(), &final_data)?;
+        self.persistent.put_cf(&snapshots_cf, snapshot_key.as_bytes(), &final_data)?;
         
         // Update latest snapshot pointer
-        self.persistent.db.put_cf(&snapshots_cf, b"latest_snapshot", &height.to_le_bytes())?;
+        self.persistent.put_cf(&snapshots_cf, b"latest_snapshot", &height.to_le_bytes())?;
         
         let duration = start_time.elapsed();
         println!("[SNAPSHOT] ✅ Snapshot created: {} accounts, {} bytes compressed in {:.2}s", 
@@ -4702,7 +5632,7 @@ impl Storage {
             account_count += 1;
         }
         
-        self.persistent.db.write(batch)?;
+        self.persistent.write(batch)?;
         
         println!("[SNAPSHOT] ✅ Restored {} accounts from snapshot", account_count);
         
@@ -4741,7 +5671,7 @@ impl Storage {
                 batch.delete_cf(&snapshots_cf, key.as_bytes());
                 println!("[SNAPSHOT] 🗑️ Removing old snapshot at height {}", height);
             }
-            self.persistent.db.write(batch)?;
+            self.persistent.write(batch)?;
         }
         
         Ok(())
@@ -4811,7 +5741,7 @@ impl Storage {
                     let ipfs_key = format!("ipfs_{}", height);
                     let snapshots_cf = self.persistent.db.cf_handle("snapshots")
                         .ok_or_else(|| IntegrationError::StorageError("snapshots column family not found".to_string()))?;
-                    self.persistent.db.put_cf(&snapshots_cf, ipfs_key.as_bytes(), cid.as_bytes())?;
+                    self.persistent.put_cf(&snapshots_cf, ipfs_key.as_bytes(), cid.as_bytes())?;
                 } // cf_handle is dropped here
                 
                 println!("[IPFS] ✅ Snapshot uploaded to IPFS: {}", cid);
@@ -4932,11 +5862,11 @@ impl Storage {
         
         // Save snapshot locally
         let snapshot_key = format!("snapshot_{}", height);
-        self.persistent.db.put_cf(&snapshots_cf, snapshot_key.as_bytes(), &data)?;
+        self.persistent.put_cf(&snapshots_cf, snapshot_key.as_bytes(), &data)?;
         
         // Save IPFS reference
         let ipfs_key = format!("ipfs_{}", height);
-        self.persistent.db.put_cf(&snapshots_cf, ipfs_key.as_bytes(), cid.as_bytes())?;
+        self.persistent.put_cf(&snapshots_cf, ipfs_key.as_bytes(), cid.as_bytes())?;
         
         println!("[IPFS] ✅ Snapshot saved from IPFS (height: {})", height);
         
@@ -5042,7 +5972,7 @@ impl Storage {
                 
                 // Apply batch every 1000 blocks to avoid memory issues
                 if pruned_count % 1000 == 0 {
-                    self.persistent.db.write(batch)?;
+                    self.persistent.write(batch)?;
                     batch = WriteBatch::default();
                     println!("[PRUNING] Pruned {} blocks...", pruned_count);
             }
@@ -5050,7 +5980,7 @@ impl Storage {
         
         // Apply remaining batch
         if !batch.is_empty() {
-            self.persistent.db.write(batch)?;
+            self.persistent.write(batch)?;
         }
         
         // Force compaction to reclaim space
@@ -5071,7 +6001,7 @@ impl Storage {
         // Update metadata
         let metadata_cf = self.persistent.db.cf_handle("metadata")
             .ok_or_else(|| IntegrationError::StorageError("metadata column family not found".to_string()))?;
-        self.persistent.db.put_cf(&metadata_cf, b"oldest_block", &prune_before.to_le_bytes())?;
+        self.persistent.put_cf(&metadata_cf, b"oldest_block", &prune_before.to_le_bytes())?;
         
         Ok(())
     }
@@ -5119,7 +6049,7 @@ impl Storage {
             
             // Apply batch every 1000 transactions to avoid memory issues
             if pruned_count % 1000 == 0 {
-                self.persistent.db.write(batch)?;
+                self.persistent.write(batch)?;
                 batch = WriteBatch::default();
                 println!("[PRUNING] Pruned {} transactions...", pruned_count);
             }
@@ -5150,7 +6080,7 @@ impl Storage {
         
         // Apply remaining batch
         if !batch.is_empty() {
-            self.persistent.db.write(batch)?;
+            self.persistent.write(batch)?;
         }
         
         // Force compaction on transaction CFs to reclaim space
@@ -5189,13 +6119,13 @@ impl Storage {
             converted += 1;
             
             if converted % 100 == 0 {
-                self.persistent.db.write(batch)?;
+                self.persistent.write(batch)?;
                 batch = WriteBatch::default();
             }
         }
         
         if !batch.is_empty() {
-            self.persistent.db.write(batch)?;
+            self.persistent.write(batch)?;
         }
         
         println!("[PRUNING] ✅ Converted {} blocks to headers-only format", converted);
@@ -5369,7 +6299,7 @@ impl Storage {
             .ok_or_else(|| IntegrationError::StorageError("snapshots column family not found".to_string()))?;
         
         let snapshot_key = format!("snapshot_{}", height);
-        self.persistent.db.put_cf(&snapshots_cf, snapshot_key.as_bytes(), &data)?;
+        self.persistent.put_cf(&snapshots_cf, snapshot_key.as_bytes(), &data)?;
         
         // Load into state
         self.load_state_snapshot(height).await?;
@@ -5587,4 +6517,332 @@ pub struct StoredContractInfo {
     pub total_gas_used: u64,
     pub call_count: u64,
     pub is_active: bool,
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use qnet_state::{MicroBlock, Transaction, TransactionType};
+    use std::time::Duration;
+
+    fn synced_microblock(height: u64) -> MicroBlock {
+        let tx = Transaction::new(
+            format!("sender_{}", height), Some("receiver".to_string()), 1_000 + height, 1, 10, 10_000,
+            1_700_000_000 + height, None,
+            TransactionType::Transfer { from: format!("sender_{}", height), to: "receiver".to_string(), amount: 1_000 + height },
+            None,
+        );
+        MicroBlock {
+            height,
+            timestamp: 1_700_000_000 + height,
+            transactions: vec![tx],
+            producer: "producer_1".to_string(),
+            signature: vec![1; 64],
+            previous_hash: [height as u8; 32],
+            merkle_root: [0; 32],
+            poh_hash: vec![2; 64],
+            poh_count: height * 500_000,
+        }
+    }
+
+    #[test]
+    fn test_save_microblocks_batch() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::new(dir.path().to_str().unwrap()).unwrap();
+
+        let blocks: Vec<MicroBlock> = (1..=100).map(synced_microblock).collect();
+        let encoded: Vec<(u64, Vec<u8>)> = blocks.iter()
+            .map(|block| (block.height, bincode::serialize(block).unwrap()))
+            .collect();
+        storage.save_microblocks_batch(&encoded).unwrap();
+
+        assert_eq!(storage.get_chain_height().unwrap(), 100);
+        for block in &blocks {
+            let loaded = storage.load_microblock_auto_format(block.height).unwrap()
+                .unwrap_or_else(|| panic!("block #{} missing", block.height));
+            assert_eq!(&loaded, block);
+            assert_eq!(storage.load_poh_state(block.height).unwrap().unwrap().poh_count, block.poh_count);
+        }
+
+        // Raw persistent batch: empty chunk is a no-op, height follows the highest block
+        storage.persistent.save_microblocks_batch(&[]).unwrap();
+        storage.persistent.save_microblocks_batch(&[(102, vec![1]), (101, vec![2])]).unwrap();
+        assert_eq!(storage.get_chain_height().unwrap(), 102);
+        assert_eq!(storage.load_microblock(101).unwrap(), Some(vec![2]));
+        assert!(storage.has_microblock(101).unwrap());
+        assert!(!storage.has_microblock(103).unwrap());
+    }
+
+    #[test]
+    fn test_node_registration_served_from_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::new(dir.path().to_str().unwrap()).unwrap();
+        storage.save_node_registration("node_1", "full", "wallet_1", 70.0).unwrap();
+
+        let first = storage.load_node_registration("node_1").unwrap();
+        assert_eq!(first, Some(("full".to_string(), "wallet_1".to_string(), 70.0)));
+        let stats = storage.node_registration_cache_stats();
+        assert_eq!((stats.hits, stats.misses), (0, 1));
+
+        // Second lookup comes from memory; unknown nodes are cached too
+        assert_eq!(storage.load_node_registration("node_1").unwrap(), first);
+        assert_eq!(storage.load_node_registration("missing").unwrap(), None);
+        assert_eq!(storage.load_node_registration("missing").unwrap(), None);
+        let stats = storage.node_registration_cache_stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (2, 2, 2));
+        assert_eq!(stats.hit_rate(), 0.5);
+
+        // Updating a registration invalidates it
+        storage.save_node_registration("node_1", "super", "wallet_1", 80.0).unwrap();
+        assert_eq!(storage.load_node_registration("node_1").unwrap(),
+                   Some(("super".to_string(), "wallet_1".to_string(), 80.0)));
+        assert_eq!(storage.node_registration_cache_stats().misses, 3);
+    }
+
+    #[test]
+    fn test_node_looked_up_by_wallet() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::new(dir.path().to_str().unwrap()).unwrap();
+        storage.save_node_registration("node_1", "super", "wallet_1", 70.0).unwrap();
+        storage.save_node_registration("node_2", "light", "wallet_2", 70.0).unwrap();
+
+        assert_eq!(storage.load_node_by_wallet("wallet_1").unwrap(), Some(("node_1".to_string(), "super".to_string())));
+        assert_eq!(storage.load_node_by_wallet("wallet_2").unwrap(), Some(("node_2".to_string(), "light".to_string())));
+        assert_eq!(storage.load_node_by_wallet("wallet_3").unwrap(), None);
+
+        // Re-registered under a new owner: the old wallet no longer operates it
+        storage.save_node_registration("node_1", "super", "wallet_3", 70.0).unwrap();
+        assert_eq!(storage.load_node_by_wallet("wallet_1").unwrap(), None);
+        assert_eq!(storage.load_node_by_wallet("wallet_3").unwrap(), Some(("node_1".to_string(), "super".to_string())));
+    }
+
+    #[test]
+    fn test_node_registration_cache_is_bounded() {
+        let cache = NodeRegistrationCache::new(10);
+        for i in 0..10 {
+            cache.insert(&format!("node_{}", i), None);
+        }
+        // Touch node_0 so node_1 is the least recently used
+        assert!(cache.get("node_0").is_some());
+        cache.insert("node_10", None);
+        assert_eq!(cache.stats().entries, 10);
+        assert!(cache.get("node_0").is_some());
+        assert!(cache.get("node_1").is_none());
+        assert!(cache.get("node_10").is_some());
+    }
+
+    #[test]
+    fn test_transaction_inclusion_proof() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::new(dir.path().to_str().unwrap()).unwrap();
+
+        let mut block = synced_microblock(1);
+        block.transactions = (1..=3).flat_map(|h| synced_microblock(h).transactions).collect();
+        let leaves: Vec<[u8; 32]> = block.transactions.iter()
+            .map(|tx| qnet_state::merkle::transaction_leaf(&tx.hash))
+            .collect();
+        block.merkle_root = qnet_state::merkle::merkle_root(&leaves);
+        storage.save_microblock(1, &bincode::serialize(&block).unwrap()).unwrap();
+
+        let tx_hash = &block.transactions[1].hash;
+        let inclusion = storage.transaction_inclusion_proof(tx_hash).unwrap().unwrap();
+        assert_eq!((inclusion.height, inclusion.merkle_root), (1, block.merkle_root));
+        assert!(qnet_state::verify_transaction_inclusion(tx_hash, &inclusion.proof, &block.merkle_root));
+        assert!(!qnet_state::verify_transaction_inclusion(&block.transactions[0].hash, &inclusion.proof, &block.merkle_root));
+
+        // Unknown transaction: no proof
+        let outsider = synced_microblock(4).transactions[0].hash.clone();
+        assert_eq!(storage.transaction_inclusion_proof(&outsider).unwrap(), None);
+
+        // A block whose root is not a Merkle tree root cannot be proven against
+        let mut legacy = synced_microblock(5);
+        legacy.height = 2;
+        storage.save_microblock(2, &bincode::serialize(&legacy).unwrap()).unwrap();
+        assert!(storage.transaction_inclusion_proof(&legacy.transactions[0].hash).is_err());
+    }
+
+    #[test]
+    fn test_verify_chain_reports_corrupted_block() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::new(dir.path().to_str().unwrap()).unwrap();
+
+        // Linked chain #0..=#5, each block committing to the stored bytes below it
+        for height in 0..=5 {
+            let mut block = synced_microblock(height);
+            block.previous_hash = match height {
+                0 => [0; 32],
+                _ => stored_block_hash(&storage.load_microblock(height - 1).unwrap().unwrap()),
+            };
+            let leaves: Vec<[u8; 32]> = block.transactions.iter()
+                .map(|tx| qnet_state::merkle::transaction_leaf(&tx.hash))
+                .collect();
+            block.merkle_root = qnet_state::merkle::merkle_root(&leaves);
+            storage.save_microblock(height, &bincode::serialize(&block).unwrap()).unwrap();
+        }
+        let report = storage.verify_chain(0, 5).unwrap();
+        assert!(report.is_intact());
+        assert_eq!(report.verified, 6);
+
+        // Corrupt the merkle_root stored at #3
+        let stored = storage.load_microblock(3).unwrap().unwrap();
+        let stored = zstd::decode_all(&stored[..]).unwrap_or(stored);
+        let mut corrupted: qnet_state::EfficientMicroBlock = bincode::deserialize(&stored).unwrap();
+        corrupted.merkle_root[0] ^= 0xff;
+        storage.persistent.save_microblock(3, &bincode::serialize(&corrupted).unwrap()).unwrap();
+
+        let report = storage.verify_chain(0, 5).unwrap();
+        assert_eq!(report.verified, 3);
+        assert_eq!(report.first_break, Some(ChainBreak { height: 3, kind: ChainBreakKind::MerkleRootMismatch }));
+        // #4 no longer links to the rewritten #3
+        assert_eq!(storage.verify_chain(4, 5).unwrap().first_break,
+                   Some(ChainBreak { height: 4, kind: ChainBreakKind::PreviousHashMismatch }));
+        assert_eq!(storage.verify_chain(5, 6).unwrap().first_break,
+                   Some(ChainBreak { height: 6, kind: ChainBreakKind::Missing }));
+    }
+
+    #[tokio::test]
+    async fn test_unversioned_macroblocks_migrated_on_open() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().to_str().unwrap();
+        let consensus_data = qnet_state::ConsensusData {
+            commits: std::collections::HashMap::new(),
+            reveals: std::collections::HashMap::new(),
+            next_leader: "node_a".to_string(),
+        };
+        let legacy = qnet_state::MacroBlock::new(1, 1_700_000_090, [1; 32], vec![[2; 32]; 90], [3; 32], consensus_data);
+
+        // Database written before versioning: raw bincode, no format marker
+        {
+            let storage = PersistentStorage::new(path).unwrap();
+            let metadata_cf = storage.db.cf_handle("metadata").unwrap();
+            storage.db.delete_cf(&metadata_cf, b"macroblock_format_version").unwrap();
+            let microblocks_cf = storage.db.cf_handle("microblocks").unwrap();
+            storage.db.put_cf(&microblocks_cf, b"macroblock_1", bincode::serialize(&legacy).unwrap()).unwrap();
+        }
+
+        let storage = PersistentStorage::new(path).unwrap();
+        assert_eq!(storage.load_macroblock(1).unwrap(), Some(legacy.clone()));
+        let microblocks_cf = storage.db.cf_handle("microblocks").unwrap();
+        let raw = storage.db.get_cf(&microblocks_cf, b"macroblock_1").unwrap().unwrap();
+        assert_eq!(raw[0], qnet_state::block_format::CURRENT);
+
+        // Callers of the raw accessor still get plain bincode of the current layout
+        let bytes = storage.get_macroblock_by_height(1).unwrap().unwrap();
+        assert_eq!(bincode::deserialize::<qnet_state::MacroBlock>(&bytes).unwrap(), legacy);
+
+        let mut next = legacy.clone();
+        next.height = 2;
+        storage.save_macroblock(2, &next).await.unwrap();
+        assert_eq!(storage.load_macroblock(2).unwrap(), Some(next));
+        assert_eq!(storage.load_macroblock(3).unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_macroblock_consensus_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = PersistentStorage::new(dir.path().to_str().unwrap()).unwrap();
+        let consensus_data = qnet_state::ConsensusData {
+            commits: [("node_a", vec![1u8; 32]), ("node_b", vec![2u8; 32]), ("node_c", vec![3u8; 32])]
+                .into_iter().map(|(node, commit)| (node.to_string(), commit)).collect(),
+            reveals: [("node_a", vec![4u8; 2420]), ("node_b", vec![5u8; 2420])]
+                .into_iter().map(|(node, reveal)| (node.to_string(), reveal)).collect(),
+            next_leader: "node_b".to_string(),
+        };
+        let macroblock = qnet_state::MacroBlock::new(2, 1_700_000_180, [1; 32], vec![[2; 32]; 90], [3; 32], consensus_data.clone());
+        storage.save_macroblock(2, &macroblock).await.unwrap();
+
+        assert_eq!(storage.get_macroblock_consensus(2).unwrap(), Some(consensus_data));
+        assert_eq!(storage.get_macroblock_consensus(1).unwrap(), None);
+        assert_eq!(storage.get_macroblock_consensus(3).unwrap(), None);
+    }
+
+    #[test]
+    fn test_wal_synced_per_policy() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = PersistentStorage::open(dir.path().to_str().unwrap(), WalSyncPolicy::EveryN(3)).unwrap();
+        let opened = storage.wal_stats();
+        for i in 0..7 {
+            storage.save_raw(&format!("key_{}", i), b"value").unwrap();
+        }
+        let stats = storage.wal_stats();
+        assert_eq!(stats.writes - opened.writes, 7);
+        // Synced on every third write, including the format marker written on open
+        assert_eq!(stats.syncs, stats.writes / 3);
+
+        let dir = tempfile::tempdir().unwrap();
+        let storage = PersistentStorage::open(dir.path().to_str().unwrap(), WalSyncPolicy::Always).unwrap();
+        let opened = storage.wal_stats();
+        storage.save_raw("key", b"value").unwrap();
+        assert_eq!(storage.wal_stats().syncs - opened.syncs, 1);
+
+        let dir = tempfile::tempdir().unwrap();
+        let storage = PersistentStorage::open(dir.path().to_str().unwrap(), WalSyncPolicy::Periodic(Duration::from_millis(10))).unwrap();
+        storage.save_raw("key", b"value").unwrap();
+        std::thread::sleep(Duration::from_millis(100));
+        assert!(storage.wal_stats().syncs >= 1);
+
+        assert_eq!(WalSyncPolicy::parse("every:100"), Ok(WalSyncPolicy::EveryN(100)));
+        assert_eq!(WalSyncPolicy::parse("Periodic:250"), Ok(WalSyncPolicy::Periodic(Duration::from_millis(250))));
+        assert!(WalSyncPolicy::parse("every:0").is_err());
+        assert!(WalSyncPolicy::parse("never").is_err());
+    }
+
+    #[test]
+    fn test_clean_shutdown_flushes_buffered_wal() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().to_str().unwrap();
+        {
+            // Never synced by the policy: only the shutdown flush persists the write
+            let storage = PersistentStorage::open(path, WalSyncPolicy::EveryN(u64::MAX)).unwrap();
+            storage.save_raw("last_write", b"before_shutdown").unwrap();
+            assert_eq!(storage.wal_stats().syncs, 0);
+        }
+        let storage = PersistentStorage::open(path, WalSyncPolicy::Always).unwrap();
+        assert_eq!(storage.load_raw("last_write").unwrap(), Some(b"before_shutdown".to_vec()));
+    }
+
+    #[test]
+    fn test_hot_and_cold_data_on_separate_paths() {
+        let hot = tempfile::tempdir().unwrap();
+        let cold = tempfile::tempdir().unwrap();
+        let layout = StorageLayout { hot_dir: hot.path().to_path_buf(), cold_dir: cold.path().to_path_buf() };
+        let chunk = crate::archive_manager::ArchiveChunk {
+            chunk_id: [7; 32],
+            height_start: 1,
+            height_end: 100_000,
+            compressed_data: vec![1, 2, 3],
+            created_at: 1_700_000_000,
+            compression_ratio: 0.5,
+            verification_hash: [8; 32],
+        };
+        {
+            let storage = PersistentStorage::open_layout(&layout, WalSyncPolicy::Always).unwrap();
+            storage.save_microblock(42, b"block_42").unwrap();
+            storage.save_archive_chunk(&chunk).unwrap();
+            for i in 1..=HOT_POH_CHECKPOINTS as u64 + 2 {
+                storage.save_poh_checkpoint(&format!("poh_checkpoint_{}", i * 10), &i.to_le_bytes()).unwrap();
+            }
+            // Aged checkpoints are still found, now in cold storage
+            assert_eq!(storage.load_poh_checkpoint("poh_checkpoint_10").unwrap(), Some(1u64.to_le_bytes().to_vec()));
+            assert_eq!(storage.load_raw("poh_checkpoint_10").unwrap(), None);
+            assert_eq!(storage.load_raw("poh_checkpoint_30").unwrap(), Some(3u64.to_le_bytes().to_vec()));
+        }
+
+        // Blocks, chain height and recent checkpoints in the hot database only
+        let hot_db = DB::open_cf_for_read_only(&Options::default(), hot.path(), ["microblocks", "metadata"], false).unwrap();
+        assert_eq!(hot_db.get_cf(hot_db.cf_handle("microblocks").unwrap(), b"microblock_42").unwrap(), Some(b"block_42".to_vec()));
+        assert_eq!(hot_db.get_cf(hot_db.cf_handle("metadata").unwrap(), b"chain_height").unwrap(), Some(42u64.to_be_bytes().to_vec()));
+        assert!(!hot.path().join(COLD_DB_DIR).exists());
+
+        // Archive chunks and aged checkpoints in the cold database only
+        let cold_path = layout.cold_db_path();
+        assert!(DB::list_cf(&Options::default(), &cold_path).unwrap().iter().all(|cf| cf != "microblocks"));
+        let cold_db = DB::open_cf_for_read_only(&Options::default(), &cold_path, ["archive_chunks", "poh_checkpoints"], false).unwrap();
+        assert!(cold_db.get_cf(cold_db.cf_handle("archive_chunks").unwrap(), chunk.chunk_id).unwrap().is_some());
+        assert!(cold_db.get_cf(cold_db.cf_handle("poh_checkpoints").unwrap(), b"poh_checkpoint_20").unwrap().is_some());
+        assert!(cold_db.get_cf(cold_db.cf_handle("poh_checkpoints").unwrap(), b"poh_checkpoint_30").unwrap().is_none());
+        drop((hot_db, cold_db));
+
+        let storage = PersistentStorage::open_layout(&layout, WalSyncPolicy::Always).unwrap();
+        assert_eq!(storage.load_archive_chunk(&chunk.chunk_id).unwrap().unwrap().height_end, 100_000);
+    }
+}
@@ -0,0 +1,152 @@
+//! Retry of synced blocks that fail validation
+//!
+//! When a block received during sync fails validation, the same height is
+//! requested again from a different peer. Every peer that supplied an invalid
+//! block for a height is remembered and excluded from further attempts at that
+//! height; after a bounded number of distinct bad peers the height is given up
+//! on. A valid block at the height clears its entry.
+
+use std::collections::BTreeMap;
+
+/// Default number of distinct peers tried for one height before giving up
+pub const DEFAULT_MAX_SYNC_RETRY_PEERS: usize = 3;
+
+/// Cap on heights with outstanding retries (lowest height dropped first)
+const MAX_TRACKED_HEIGHTS: usize = 1000;
+
+/// What to do after a synced block failed validation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyncRetry {
+    /// Request the height again from a peer not in `excluded`
+    Retry { excluded: Vec<String> },
+    /// Every allowed peer supplied an invalid block, stop retrying this height
+    Exhausted { bad_peers: Vec<String> },
+}
+
+/// Peers that supplied invalid blocks, per height still being synced
+#[derive(Debug, Clone)]
+pub struct SyncRetryTracker {
+    max_peers: usize,
+    bad_peers: BTreeMap<u64, Vec<String>>,
+}
+
+impl Default for SyncRetryTracker {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_SYNC_RETRY_PEERS)
+    }
+}
+
+impl SyncRetryTracker {
+    pub fn new(max_peers: usize) -> Self {
+        Self {
+            max_peers: max_peers.max(1),
+            bad_peers: BTreeMap::new(),
+        }
+    }
+
+    /// Tracker with a `QNET_SYNC_RETRY_PEERS` override
+    pub fn from_env() -> Self {
+        let max_peers = std::env::var("QNET_SYNC_RETRY_PEERS")
+            .ok()
+            .and_then(|v| v.trim().parse::<usize>().ok())
+            .filter(|&v| v > 0)
+            .unwrap_or(DEFAULT_MAX_SYNC_RETRY_PEERS);
+        Self::new(max_peers)
+    }
+
+    pub fn max_peers(&self) -> usize {
+        self.max_peers
+    }
+
+    /// Record an invalid block at `height` from `peer` and decide whether to retry
+    pub fn record_invalid(&mut self, height: u64, peer: &str) -> SyncRetry {
+        if !self.bad_peers.contains_key(&height) && self.bad_peers.len() >= MAX_TRACKED_HEIGHTS {
+            self.bad_peers.pop_first();
+        }
+        let peers = self.bad_peers.entry(height).or_default();
+        if !peers.iter().any(|p| p == peer) {
+            peers.push(peer.to_string());
+        }
+        if peers.len() >= self.max_peers {
+            let bad_peers = self.bad_peers.remove(&height).unwrap_or_default();
+            SyncRetry::Exhausted { bad_peers }
+        } else {
+            SyncRetry::Retry { excluded: peers.clone() }
+        }
+    }
+
+    /// A valid block was stored at `height`, returning the peers that failed it
+    pub fn record_valid(&mut self, height: u64) -> Vec<String> {
+        self.bad_peers.remove(&height).unwrap_or_default()
+    }
+
+    /// Stop retrying `height` (e.g. no candidate peer is left)
+    pub fn abandon(&mut self, height: u64) -> Vec<String> {
+        self.bad_peers.remove(&height).unwrap_or_default()
+    }
+
+    /// Peers that supplied an invalid block at `height`
+    pub fn bad_peers(&self, height: u64) -> &[String] {
+        self.bad_peers.get(&height).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    pub fn len(&self) -> usize {
+        self.bad_peers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bad_peers.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sync one height from `peers` (best first), asking the next untried peer after each invalid block
+    fn sync_height(tracker: &mut SyncRetryTracker, peers: &[&str], height: u64, valid_from: &[&str]) -> Result<(String, Vec<String>), Vec<String>> {
+        let mut excluded: Vec<String> = Vec::new();
+        loop {
+            let Some(peer) = peers.iter().find(|p| !excluded.iter().any(|e| e == *p)) else {
+                return Err(tracker.abandon(height));
+            };
+            if valid_from.contains(peer) {
+                return Ok((peer.to_string(), tracker.record_valid(height)));
+            }
+            match tracker.record_invalid(height, peer) {
+                SyncRetry::Retry { excluded: next } => excluded = next,
+                SyncRetry::Exhausted { bad_peers } => return Err(bad_peers),
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid_block_retried_from_next_peer() {
+        let mut tracker = SyncRetryTracker::new(3);
+
+        // First peer serves an invalid block, the second serves the valid one
+        let (peer, bad_peers) = sync_height(&mut tracker, &["peer_a", "peer_b", "peer_c"], 42, &["peer_b"]).unwrap();
+        assert_eq!(peer, "peer_b");
+        assert_eq!(bad_peers, vec!["peer_a".to_string()]);
+        assert!(tracker.is_empty());
+    }
+
+    #[test]
+    fn test_gives_up_after_distinct_peers_exhausted() {
+        let mut tracker = SyncRetryTracker::new(2);
+
+        // Same bad peer twice counts once
+        assert!(matches!(tracker.record_invalid(7, "peer_a"), SyncRetry::Retry { .. }));
+        assert!(matches!(tracker.record_invalid(7, "peer_a"), SyncRetry::Retry { .. }));
+        assert_eq!(
+            tracker.record_invalid(7, "peer_b"),
+            SyncRetry::Exhausted { bad_peers: vec!["peer_a".to_string(), "peer_b".to_string()] }
+        );
+        assert!(tracker.is_empty());
+
+        // Running out of candidates also ends the attempt
+        let mut tracker = SyncRetryTracker::new(5);
+        assert_eq!(sync_height(&mut tracker, &["peer_a", "peer_b"], 9, &[]), Err(vec!["peer_a".to_string(), "peer_b".to_string()]));
+        assert!(tracker.is_empty());
+    }
+}
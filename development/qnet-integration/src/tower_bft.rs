@@ -2,9 +2,19 @@
 // Integrates with existing consensus mechanisms
 
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use std::collections::HashMap;
+use lazy_static::lazy_static;
+use prometheus::{register_gauge, Gauge};
+
+lazy_static! {
+    static ref TOWER_BFT_TIMEOUT_MS: Gauge = register_gauge!(
+        "qnet_tower_bft_timeout_ms",
+        "Most recently computed Tower BFT block timeout in milliseconds"
+    ).unwrap();
+}
 
 /// Tower BFT timeout configuration
 #[derive(Debug, Clone)]
@@ -33,10 +43,34 @@ impl Default for TowerBftConfig {
     }
 }
 
+impl TowerBftConfig {
+    /// Bounds must be ordered and backoff must grow timeouts, never shrink them
+    pub fn validate(&self) -> Result<(), String> {
+        if self.min_timeout_ms == 0 {
+            return Err("Minimum timeout must be positive".to_string());
+        }
+        if self.min_timeout_ms > self.base_timeout_ms || self.base_timeout_ms > self.max_timeout_ms {
+            return Err(format!(
+                "Timeouts must satisfy min <= base <= max, got {}ms / {}ms / {}ms",
+                self.min_timeout_ms, self.base_timeout_ms, self.max_timeout_ms
+            ));
+        }
+        if !(self.timeout_multiplier > 1.0 && self.timeout_multiplier.is_finite()) {
+            return Err(format!("Timeout multiplier must be greater than 1.0, got {}", self.timeout_multiplier));
+        }
+        if self.latency_window_size == 0 {
+            return Err("Latency window must hold at least one measurement".to_string());
+        }
+        Ok(())
+    }
+}
+
 /// Tower BFT adaptive timeout manager
 pub struct TowerBft {
     /// Configuration
     config: TowerBftConfig,
+    /// Most recently computed timeout (milliseconds)
+    current_timeout_ms: AtomicU64,
     /// Vote timeouts by height
     vote_timeouts: Arc<RwLock<HashMap<u64, Duration>>>,
     /// Network latency measurements
@@ -70,14 +104,34 @@ impl Default for NetworkState {
 }
 
 impl TowerBft {
-    /// Create new Tower BFT manager
-    pub fn new(config: TowerBftConfig) -> Self {
-        Self {
+    /// Create new Tower BFT manager, rejecting an invalid configuration
+    pub fn new(config: TowerBftConfig) -> Result<Self, String> {
+        config.validate()?;
+        Ok(Self {
+            current_timeout_ms: AtomicU64::new(config.base_timeout_ms),
             config,
             vote_timeouts: Arc::new(RwLock::new(HashMap::new())),
             latency_measurements: Arc::new(RwLock::new(Vec::new())),
             network_state: Arc::new(RwLock::new(NetworkState::default())),
-        }
+        })
+    }
+    
+    /// Active configuration
+    pub fn config(&self) -> &TowerBftConfig {
+        &self.config
+    }
+    
+    /// Most recently computed timeout (the base timeout before the first computation)
+    pub fn current_timeout(&self) -> Duration {
+        Duration::from_millis(self.current_timeout_ms.load(Ordering::Relaxed))
+    }
+    
+    /// Clamp to the configured bounds and record as the current timeout
+    fn bounded(&self, timeout_ms: u64) -> Duration {
+        let bounded_ms = timeout_ms.clamp(self.config.min_timeout_ms, self.config.max_timeout_ms);
+        self.current_timeout_ms.store(bounded_ms, Ordering::Relaxed);
+        TOWER_BFT_TIMEOUT_MS.set(bounded_ms as f64);
+        Duration::from_millis(bounded_ms)
     }
     
     /// Get adaptive timeout for block at height
@@ -88,10 +142,9 @@ impl TowerBft {
             if retry_count > 0 {
                 let multiplier = self.config.timeout_multiplier.powi(retry_count as i32);
                 let adjusted_ms = (timeout.as_millis() as f64 * multiplier) as u64;
-                let final_ms = adjusted_ms.min(self.config.max_timeout_ms).max(self.config.min_timeout_ms);
-                return Duration::from_millis(final_ms);
+                return self.bounded(adjusted_ms);
             }
-            return *timeout;
+            return self.bounded(timeout.as_millis() as u64);
         }
         
         // Calculate adaptive timeout based on QNet's existing logic
@@ -109,7 +162,7 @@ impl TowerBft {
             // CPU/Network contention from Dilithium signatures + commit/reveal phases
             // Producer needs extra buffer to avoid emergency failover
             5000  // 5 seconds for consensus period (balances safety vs. speed)
-        } else if height > 1 && ((height - 1) % 30) == 0 {
+        } else if crate::network_config::get_chain_params().is_rotation_start(height) {
             // CRITICAL: Rotation boundaries need slightly more time for producer switch
             // CRITICAL FIX: Increased from 3s to 4s to match normal block timeout
             // Rotation is when producer changes - needs same buffer as regular blocks
@@ -134,7 +187,7 @@ impl TowerBft {
                 _ => 5.0,   // Fourth+ attempt: 5x (capped)
             };
             let adjusted = (base_timeout as f64 * multiplier) as u64;
-            adjusted.min(10000).max(self.config.min_timeout_ms) // Max 10 seconds (was 60!)
+            adjusted.min(self.config.max_timeout_ms) // Max from config (10 seconds on nodes)
         } else {
             base_timeout
         };
@@ -151,7 +204,8 @@ impl TowerBft {
             timeout_ms
         };
         
-        let final_timeout = Duration::from_millis(network_adjusted);
+        // Never exceed the configured bounds, however bad the network
+        let final_timeout = self.bounded(network_adjusted);
         
         // Cache the timeout
         self.vote_timeouts.write().await.insert(height, final_timeout);
@@ -292,3 +346,55 @@ impl VoteState {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> TowerBftConfig {
+        TowerBftConfig {
+            base_timeout_ms: 3000,
+            timeout_multiplier: 1.5,
+            max_timeout_ms: 10000,
+            min_timeout_ms: 2000,
+            latency_window_size: 1,
+        }
+    }
+
+    #[test]
+    fn test_invalid_config_rejected() {
+        assert!(TowerBft::new(config()).is_ok());
+        assert!(TowerBftConfig::default().validate().is_ok());
+        for invalid in [
+            TowerBftConfig { min_timeout_ms: 4000, ..config() },
+            TowerBftConfig { max_timeout_ms: 2500, ..config() },
+            TowerBftConfig { min_timeout_ms: 0, ..config() },
+            TowerBftConfig { timeout_multiplier: 1.0, ..config() },
+            TowerBftConfig { timeout_multiplier: 0.5, ..config() },
+            TowerBftConfig { timeout_multiplier: f64::NAN, ..config() },
+            TowerBftConfig { latency_window_size: 0, ..config() },
+        ] {
+            assert!(TowerBft::new(invalid.clone()).is_err(), "{:?}", invalid);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_timeout_grows_with_latency_up_to_max() {
+        let tower = TowerBft::new(config()).unwrap();
+        assert_eq!(tower.current_timeout(), Duration::from_millis(3000));
+
+        // Normal blocks (not rotation starts or consensus periods), one height per sample
+        let mut previous = Duration::ZERO;
+        for (height, latency_ms) in (12..).zip([100, 1_000, 10_000, 100_000, 1_000_000]) {
+            tower.record_latency(Duration::from_millis(latency_ms)).await;
+            let timeout = tower.get_timeout(height, 0).await;
+            assert!(timeout >= previous, "timeout shrank at {}ms latency", latency_ms);
+            assert!(timeout <= Duration::from_millis(10000));
+            assert_eq!(tower.current_timeout(), timeout);
+            previous = timeout;
+        }
+        assert_eq!(previous, Duration::from_millis(10000));
+
+        // Retries on a cached height stay capped as well
+        assert_eq!(tower.get_timeout(12, 5).await, Duration::from_millis(10000));
+    }
+}
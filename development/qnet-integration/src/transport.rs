@@ -0,0 +1,411 @@
+//! Pluggable P2P transport
+//!
+//! [`SimplifiedP2P`](crate::unified_p2p::SimplifiedP2P) sends its messages as HTTP
+//! POSTs to `/api/v1/p2p/message` on the peer's API port. A [`Transport`] installed
+//! with `set_transport` takes over that traffic: outbound messages go through
+//! [`Transport::send`] and inbound ones are read from [`Transport::receive`] and fed
+//! to `handle_message` by `run_transport`.
+//!
+//! - [`TcpTransport`] speaks the production wire format over real sockets
+//! - [`MockNetwork`] hands out in-memory [`MockTransport`] endpoints, so several
+//!   nodes can run in one process (tests) without opening ports
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::sync::mpsc;
+
+use crate::unified_p2p::NetworkMessage;
+
+/// API port every node serves P2P messages on
+pub const DEFAULT_P2P_API_PORT: u16 = 8001;
+
+/// P2P message route, relative to the API root
+const P2P_MESSAGE_PATH: &str = "api/v1/p2p/message";
+
+const SEND_TIMEOUT: Duration = Duration::from_secs(2);
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Inbound message and the address it came from
+pub type Inbound = (String, NetworkMessage);
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TransportError {
+    #[error("peer {peer} unreachable: {reason}")]
+    Unreachable { peer: String, reason: String },
+
+    #[error("peer {peer} answered {status}")]
+    Rejected { peer: String, status: u16 },
+
+    #[error("cannot listen on {addr}: {reason}")]
+    Bind { addr: String, reason: String },
+
+    #[error("message encoding failed: {0}")]
+    Encoding(String),
+}
+
+/// Moves `NetworkMessage`s between nodes
+#[async_trait]
+pub trait Transport: Send + Sync {
+    /// Address peers use to reach this node
+    fn local_addr(&self) -> String;
+
+    /// Start accepting inbound messages on the local address
+    async fn accept(&self) -> Result<(), TransportError>;
+
+    /// Check that a peer is reachable
+    async fn connect(&self, peer_addr: &str) -> Result<(), TransportError>;
+
+    /// Deliver one message to a peer
+    async fn send(&self, peer_addr: &str, message: &NetworkMessage) -> Result<(), TransportError>;
+
+    /// Next inbound message, `None` once the transport is closed
+    async fn receive(&self) -> Option<Inbound>;
+}
+
+/// Production transport: JSON over HTTP on the peer's API port
+pub struct TcpTransport {
+    listen_addr: SocketAddr,
+    client: reqwest::Client,
+    inbox_tx: mpsc::UnboundedSender<Inbound>,
+    inbox: tokio::sync::Mutex<mpsc::UnboundedReceiver<Inbound>>,
+}
+
+impl Default for TcpTransport {
+    fn default() -> Self {
+        Self::new(SocketAddr::from(([0, 0, 0, 0], DEFAULT_P2P_API_PORT)))
+    }
+}
+
+impl TcpTransport {
+    pub fn new(listen_addr: SocketAddr) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(SEND_TIMEOUT)
+            .connect_timeout(CONNECT_TIMEOUT)
+            .user_agent("QNet-Node/1.0")
+            .tcp_nodelay(true)
+            .build()
+            .unwrap_or_default();
+        let (inbox_tx, inbox) = mpsc::unbounded_channel();
+        Self {
+            listen_addr,
+            client,
+            inbox_tx,
+            inbox: tokio::sync::Mutex::new(inbox),
+        }
+    }
+
+    /// Peers listen on the same API port, whatever port their address carries
+    fn peer_socket(&self, peer_addr: &str) -> String {
+        let peer_ip = peer_addr.split(':').next().unwrap_or(peer_addr);
+        format!("{}:{}", peer_ip, self.listen_addr.port())
+    }
+}
+
+#[async_trait]
+impl Transport for TcpTransport {
+    fn local_addr(&self) -> String {
+        self.listen_addr.to_string()
+    }
+
+    async fn accept(&self) -> Result<(), TransportError> {
+        use warp::Filter;
+
+        let inbox_tx = self.inbox_tx.clone();
+        let route = warp::path!("api" / "v1" / "p2p" / "message")
+            .and(warp::post())
+            .and(warp::addr::remote())
+            .and(warp::body::json())
+            .map(move |remote: Option<SocketAddr>, message: NetworkMessage| {
+                let from_peer = remote.map(|addr| addr.to_string()).unwrap_or_default();
+                let _ = inbox_tx.send((from_peer, message));
+                warp::reply::json(&serde_json::json!({ "status": "ok" }))
+            });
+
+        let (_, server) = warp::serve(route)
+            .try_bind_ephemeral(self.listen_addr)
+            .map_err(|e| TransportError::Bind { addr: self.listen_addr.to_string(), reason: e.to_string() })?;
+        tokio::spawn(server);
+        Ok(())
+    }
+
+    async fn connect(&self, peer_addr: &str) -> Result<(), TransportError> {
+        let socket = self.peer_socket(peer_addr);
+        match tokio::time::timeout(CONNECT_TIMEOUT, tokio::net::TcpStream::connect(&socket)).await {
+            Ok(Ok(_)) => Ok(()),
+            Ok(Err(e)) => Err(TransportError::Unreachable { peer: peer_addr.to_string(), reason: e.to_string() }),
+            Err(_) => Err(TransportError::Unreachable { peer: peer_addr.to_string(), reason: "connect timed out".to_string() }),
+        }
+    }
+
+    async fn send(&self, peer_addr: &str, message: &NetworkMessage) -> Result<(), TransportError> {
+        let url = format!("http://{}/{}", self.peer_socket(peer_addr), P2P_MESSAGE_PATH);
+        let response = self.client.post(&url)
+            .json(message)
+            .send()
+            .await
+            .map_err(|e| TransportError::Unreachable { peer: peer_addr.to_string(), reason: e.to_string() })?;
+        if !response.status().is_success() {
+            return Err(TransportError::Rejected { peer: peer_addr.to_string(), status: response.status().as_u16() });
+        }
+        Ok(())
+    }
+
+    async fn receive(&self) -> Option<Inbound> {
+        self.inbox.lock().await.recv().await
+    }
+}
+
+/// In-process network connecting [`MockTransport`] endpoints by address
+#[derive(Clone, Default)]
+pub struct MockNetwork {
+    listeners: Arc<Mutex<HashMap<String, mpsc::UnboundedSender<Inbound>>>>,
+}
+
+impl MockNetwork {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Endpoint for a node at `addr`; it is reachable once it accepts
+    pub fn endpoint(&self, addr: &str) -> MockTransport {
+        let (inbox_tx, inbox) = mpsc::unbounded_channel();
+        MockTransport {
+            addr: addr.to_string(),
+            network: self.clone(),
+            inbox_tx,
+            inbox: tokio::sync::Mutex::new(inbox),
+        }
+    }
+
+    /// Take a node off the network, as if its host went down
+    pub fn disconnect(&self, addr: &str) {
+        self.listeners.lock().unwrap().remove(addr);
+    }
+
+    fn listener(&self, addr: &str) -> Option<mpsc::UnboundedSender<Inbound>> {
+        self.listeners.lock().unwrap().get(addr).cloned()
+    }
+}
+
+/// In-memory transport endpoint on a [`MockNetwork`]
+pub struct MockTransport {
+    addr: String,
+    network: MockNetwork,
+    inbox_tx: mpsc::UnboundedSender<Inbound>,
+    inbox: tokio::sync::Mutex<mpsc::UnboundedReceiver<Inbound>>,
+}
+
+#[async_trait]
+impl Transport for MockTransport {
+    fn local_addr(&self) -> String {
+        self.addr.clone()
+    }
+
+    async fn accept(&self) -> Result<(), TransportError> {
+        let mut listeners = self.network.listeners.lock().unwrap();
+        if listeners.contains_key(&self.addr) {
+            return Err(TransportError::Bind { addr: self.addr.clone(), reason: "address in use".to_string() });
+        }
+        listeners.insert(self.addr.clone(), self.inbox_tx.clone());
+        Ok(())
+    }
+
+    async fn connect(&self, peer_addr: &str) -> Result<(), TransportError> {
+        match self.network.listener(peer_addr) {
+            Some(_) => Ok(()),
+            None => Err(TransportError::Unreachable { peer: peer_addr.to_string(), reason: "no listener".to_string() }),
+        }
+    }
+
+    async fn send(&self, peer_addr: &str, message: &NetworkMessage) -> Result<(), TransportError> {
+        let listener = self.network.listener(peer_addr)
+            .ok_or_else(|| TransportError::Unreachable { peer: peer_addr.to_string(), reason: "no listener".to_string() })?;
+
+        // Round-trip through JSON so messages cross the same encoding as on the wire
+        let encoded = serde_json::to_value(message).map_err(|e| TransportError::Encoding(e.to_string()))?;
+        let message = serde_json::from_value(encoded).map_err(|e| TransportError::Encoding(e.to_string()))?;
+
+        listener.send((self.addr.clone(), message))
+            .map_err(|_| TransportError::Unreachable { peer: peer_addr.to_string(), reason: "listener closed".to_string() })
+    }
+
+    async fn receive(&self) -> Option<Inbound> {
+        self.inbox.lock().await.recv().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::unified_p2p::{ConsensusMessage, NodeType, ReceivedBlock, Region, SimplifiedP2P};
+    use sha3::{Digest, Sha3_256};
+
+    const ROUND: u64 = 90;
+    const RECV_TIMEOUT: Duration = Duration::from_secs(5);
+
+    struct TestNode {
+        id: String,
+        addr: String,
+        p2p: Arc<SimplifiedP2P>,
+        consensus_rx: mpsc::UnboundedReceiver<ConsensusMessage>,
+        block_rx: mpsc::UnboundedReceiver<ReceivedBlock>,
+    }
+
+    async fn spawn_node(network: &MockNetwork, index: usize) -> TestNode {
+        let id = format!("mock_node_{}", index);
+        let addr = format!("10.0.0.{}:{}", index, DEFAULT_P2P_API_PORT);
+
+        let transport = network.endpoint(&addr);
+        transport.accept().await.expect("address free");
+
+        let mut p2p = SimplifiedP2P::new(id.clone(), NodeType::Super, Region::Europe, DEFAULT_P2P_API_PORT);
+        let (consensus_tx, consensus_rx) = mpsc::unbounded_channel();
+        let (block_tx, block_rx) = mpsc::unbounded_channel();
+        p2p.set_consensus_channel(consensus_tx);
+        p2p.set_block_channel(block_tx);
+        p2p.set_transport(Arc::new(transport));
+
+        let p2p = Arc::new(p2p);
+        let receiver = p2p.clone();
+        tokio::spawn(async move { receiver.run_transport().await });
+        TestNode { id, addr, p2p, consensus_rx, block_rx }
+    }
+
+    async fn next<T>(rx: &mut mpsc::UnboundedReceiver<T>) -> T {
+        tokio::time::timeout(RECV_TIMEOUT, rx.recv()).await
+            .expect("message delivered in time")
+            .expect("channel open")
+    }
+
+    fn reveal_of(id: &str) -> (String, String) {
+        (format!("reveal_{}", id), hex::encode(Sha3_256::digest(id.as_bytes())))
+    }
+
+    fn commit_of(reveal: &str, nonce: &str) -> String {
+        hex::encode(Sha3_256::digest([reveal.as_bytes(), nonce.as_bytes()].concat()))
+    }
+
+    #[tokio::test]
+    async fn test_mock_transport_rejects_unknown_peer() {
+        let network = MockNetwork::new();
+        let a = network.endpoint("10.0.0.1:8001");
+        a.accept().await.unwrap();
+        assert!(matches!(a.accept().await, Err(TransportError::Bind { .. })));
+
+        let message = NetworkMessage::Block { height: 1, data: vec![1], block_type: "micro".to_string() };
+        assert!(matches!(a.connect("10.0.0.2:8001").await, Err(TransportError::Unreachable { .. })));
+        assert!(matches!(a.send("10.0.0.2:8001", &message).await, Err(TransportError::Unreachable { .. })));
+
+        let b = network.endpoint("10.0.0.2:8001");
+        b.accept().await.unwrap();
+        a.send("10.0.0.2:8001", &message).await.unwrap();
+        let (from, NetworkMessage::Block { height, .. }) = b.receive().await.unwrap() else {
+            panic!("block not delivered");
+        };
+        assert_eq!((from.as_str(), height), ("10.0.0.1:8001", 1));
+
+        network.disconnect("10.0.0.2:8001");
+        assert!(a.connect("10.0.0.2:8001").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_four_mock_nodes_reach_consensus_and_agree_on_chain() {
+        let network = MockNetwork::new();
+        let mut nodes = Vec::new();
+        for index in 1..=4 {
+            nodes.push(spawn_node(&network, index).await);
+        }
+        let peers: Vec<(String, String)> = nodes.iter().map(|n| (n.id.clone(), n.addr.clone())).collect();
+
+        // Commit phase: every node commits to its reveal
+        for node in &nodes {
+            let (reveal, nonce) = reveal_of(&node.id);
+            for (_, addr) in peers.iter().filter(|(id, _)| *id != node.id) {
+                node.p2p.send_network_message(addr, NetworkMessage::ConsensusCommit {
+                    round_id: ROUND,
+                    node_id: node.id.clone(),
+                    commit_hash: commit_of(&reveal, &nonce),
+                    signature: format!("sig_{}", node.id),
+                    timestamp: 1,
+                });
+            }
+        }
+        let mut commits: Vec<HashMap<String, String>> = Vec::new();
+        for node in nodes.iter_mut() {
+            let mut seen = HashMap::new();
+            while seen.len() < peers.len() - 1 {
+                match next(&mut node.consensus_rx).await {
+                    ConsensusMessage::RemoteCommit { round_id, node_id, commit_hash, .. } => {
+                        assert_eq!(round_id, ROUND);
+                        seen.insert(node_id, commit_hash);
+                    }
+                    other => panic!("unexpected {:?}", other),
+                }
+            }
+            commits.push(seen);
+        }
+
+        // Reveal phase: every reveal must open the commit seen for that node
+        for node in &nodes {
+            let (reveal, nonce) = reveal_of(&node.id);
+            for (_, addr) in peers.iter().filter(|(id, _)| *id != node.id) {
+                node.p2p.send_network_message(addr, NetworkMessage::ConsensusReveal {
+                    round_id: ROUND,
+                    node_id: node.id.clone(),
+                    reveal_data: reveal.clone(),
+                    nonce: nonce.clone(),
+                    timestamp: 2,
+                });
+            }
+        }
+        let mut leaders = Vec::new();
+        for (node, commits) in nodes.iter_mut().zip(&commits) {
+            let mut reveals = vec![(node.id.clone(), reveal_of(&node.id).0)];
+            while reveals.len() < peers.len() {
+                match next(&mut node.consensus_rx).await {
+                    ConsensusMessage::RemoteReveal { node_id, reveal_data, nonce, .. } => {
+                        assert_eq!(commits[&node_id], commit_of(&reveal_data, &nonce));
+                        reveals.push((node_id, reveal_data));
+                    }
+                    other => panic!("unexpected {:?}", other),
+                }
+            }
+
+            // Deterministic leader from the combined reveals
+            reveals.sort();
+            let seed = Sha3_256::digest(reveals.iter().map(|(_, r)| r.as_str()).collect::<String>().as_bytes());
+            let index = u64::from_be_bytes(seed[..8].try_into().unwrap()) as usize % reveals.len();
+            leaders.push(reveals[index].0.clone());
+        }
+        assert!(leaders.iter().all(|leader| *leader == leaders[0]), "nodes disagree on leader: {:?}", leaders);
+
+        // The leader produces blocks; every node ends up with the same chain
+        let leader = nodes.iter().position(|n| n.id == leaders[0]).unwrap();
+        let chain: Vec<(u64, Vec<u8>)> = (1..=3)
+            .map(|height| (height, format!("block_{}_by_{}", height, leaders[0]).into_bytes()))
+            .collect();
+        for (height, data) in &chain {
+            for (_, addr) in peers.iter().filter(|(id, _)| *id != leaders[0]) {
+                nodes[leader].p2p.send_network_message(addr, NetworkMessage::Block {
+                    height: *height,
+                    data: data.clone(),
+                    block_type: "micro".to_string(),
+                });
+            }
+        }
+        for (i, node) in nodes.iter_mut().enumerate().filter(|(i, _)| *i != leader) {
+            let mut received = Vec::new();
+            while received.len() < chain.len() {
+                let block = next(&mut node.block_rx).await;
+                assert_eq!(block.from_peer, peers[leader].1);
+                received.push((block.height, block.data));
+            }
+            received.sort();
+            assert_eq!(received, chain, "node {} diverged", i + 1);
+        }
+    }
+}
@@ -21,6 +21,9 @@ use futures::{future, stream, StreamExt};
 // Import QNet consensus components for proper peer validation
 use qnet_consensus::reputation::{NodeReputation, ReputationConfig, MaliciousBehavior};
 use qnet_consensus::{commit_reveal::{Commit, Reveal}, ConsensusEngine};
+use crate::consensus_guard::{ConsensusGuard, ConsensusPhase, GuardVerdict};
+use crate::transport::Transport;
+use crate::dht_bootstrap::DiscoveryStatus;
 
 // ============================================================================
 // PRODUCTION CONSTANTS: Capacity limits for scalability
@@ -432,6 +435,156 @@ impl LoadBalancingConfig {
     }
 }
 
+/// SCALABILITY: Backoff hint returned to inbound peers rejected at capacity
+const INBOUND_CAPACITY_BACKOFF_SECS: u64 = 30;
+
+/// SYNC: Smoothing factor for per-peer latency EWMA (weight of the newest sample)
+const LATENCY_EWMA_ALPHA: f64 = 0.2;
+/// SYNC: Latency at or above this earns no sync score bonus
+const SYNC_LATENCY_CEILING_MS: f64 = 1000.0;
+/// SYNC: Maximum score bonus for latency (reputation spans 0-100, so it stays dominant)
+const SYNC_LATENCY_WEIGHT: f64 = 10.0;
+/// SYNC: Downloaded blocks committed per atomic storage write
+const SYNC_WRITE_BATCH_SIZE: usize = 50;
+
+/// Fold a latency sample into a peer's EWMA estimate
+fn update_latency_ewma(estimates: &DashMap<String, f64>, addr: &str, sample_ms: f64) {
+    estimates.entry(addr.to_string())
+        .and_modify(|ewma| *ewma = LATENCY_EWMA_ALPHA * sample_ms + (1.0 - LATENCY_EWMA_ALPHA) * *ewma)
+        .or_insert(sample_ms);
+}
+
+/// Default cap on blocks served for a single sync request
+pub const DEFAULT_MAX_SYNC_RANGE: u64 = 500;
+
+/// Default sync requests a catching-up peer may send per minute
+/// Catch-up clients pace chunks 100 ms apart, i.e. at most 600 per minute
+pub const DEFAULT_SYNC_REQUESTS_PER_MIN: usize = 600;
+
+/// Connection admission limits
+/// Genesis and Super nodes are the first contact for new nodes and must not be swamped
+#[derive(Debug, Clone)]
+pub struct P2PConfig {
+    pub max_inbound: usize,          // Maximum peers admitted via inbound discovery
+    pub max_outbound: usize,         // Maximum peers we dial ourselves
+    pub inbound_rate_per_sec: usize, // Inbound admissions allowed per second
+    pub max_sync_range: u64,         // Most blocks served for one sync request
+    pub sync_requests_per_min: usize, // Sync requests per peer per minute while catching up
+}
+
+impl Default for P2PConfig {
+    fn default() -> Self {
+        Self {
+            max_inbound: 1000,
+            max_outbound: 200,
+            inbound_rate_per_sec: 50,
+            max_sync_range: DEFAULT_MAX_SYNC_RANGE,
+            sync_requests_per_min: DEFAULT_SYNC_REQUESTS_PER_MIN,
+        }
+    }
+}
+
+impl P2PConfig {
+    /// Defaults with `QNET_MAX_SYNC_RANGE` / `QNET_SYNC_REQUESTS_PER_MIN` overrides
+    pub fn from_env() -> Self {
+        let env_value = |name: &str| std::env::var(name)
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .filter(|&v| v > 0);
+        let mut config = Self::default();
+        if let Some(range) = env_value("QNET_MAX_SYNC_RANGE") {
+            config.max_sync_range = range;
+        }
+        if let Some(per_min) = env_value("QNET_SYNC_REQUESTS_PER_MIN") {
+            config.sync_requests_per_min = per_min as usize;
+        }
+        config
+    }
+}
+
+/// Clamp a requested block range to `max_range` blocks
+/// Returns the last height to serve and, if clamped, the height to continue from;
+/// `None` for an inverted range
+pub fn clamp_sync_range(from_height: u64, to_height: u64, max_range: u64) -> Option<(u64, Option<u64>)> {
+    if to_height < from_height {
+        return None;
+    }
+    let last_allowed = from_height.saturating_add(max_range.max(1) - 1);
+    if to_height > last_allowed {
+        Some((last_allowed, Some(last_allowed + 1)))
+    } else {
+        Some((to_height, None))
+    }
+}
+
+/// Result of an inbound connection attempt
+#[derive(Debug, Clone, PartialEq)]
+pub enum InboundDecision {
+    /// Peer admitted into a free slot
+    Accepted,
+    /// Peer admitted by evicting a lower-reputation inbound peer
+    Replaced { evicted: String },
+    /// Peer rejected, should retry after the hint
+    Rejected { retry_after_secs: u64 },
+}
+
+/// First message on a new connection: who the peer is and what it speaks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PeerHandshake {
+    pub protocol_version: u32,
+    /// e.g. "qnet-mainnet-v1"; peers on another network are never routed to
+    pub network_id: String,
+    pub node_type: NodeType,
+    pub node_id: String,
+    /// Address ("ip:port") the peer accepts messages on
+    pub addr: String,
+    pub region: Region,
+}
+
+/// Why a peer's handshake was refused
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum HandshakeRejection {
+    #[error("protocol version {got} below minimum compatible {min}")]
+    VersionTooOld { got: u32, min: u32 },
+    #[error("network id {theirs} does not match ours ({ours})")]
+    NetworkMismatch { ours: String, theirs: String },
+    #[error("invalid peer address {0}")]
+    InvalidAddress(String),
+}
+
+/// Current connection counts by direction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectionCounts {
+    pub inbound: usize,
+    pub outbound: usize,
+}
+
+/// Connected peer as reported to operators (GET /api/v1/node/peers)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConnectedPeerInfo {
+    pub id: String,
+    pub addr: String,
+    pub node_type: NodeType,
+    pub region: Region,
+    /// Combined reputation (70% consensus, 30% network)
+    pub reputation: f64,
+    pub consensus_score: f64,
+    /// Smoothed round-trip latency, 0 if never measured
+    pub latency_ms: u32,
+    pub last_seen: u64,
+    /// Admitted via inbound discovery rather than dialed by us
+    pub inbound: bool,
+}
+
+/// Connected peer table with counts by region and node type
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PeerTableReport {
+    pub peers: Vec<ConnectedPeerInfo>,
+    pub total: usize,
+    pub by_region: std::collections::BTreeMap<String, usize>,
+    pub by_node_type: std::collections::BTreeMap<String, usize>,
+}
+
 /// QUANTUM SCALABILITY: Advanced P2P structure for millions of nodes
 /// Combines lock-free DashMap, dual indexing, and existing sharding
 pub struct SimplifiedP2P {
@@ -522,9 +675,8 @@ pub struct SimplifiedP2P {
     last_heartbeat_cleanup: Arc<Mutex<u64>>,
     
     /// PRODUCTION: Light Node attestations for reward eligibility
-    /// Key: "{light_node_id}:{slot}", Value: LightNodeAttestation
-    /// Dedupe ensures only one attestation per Light node per slot
-    light_node_attestations: Arc<RwLock<HashMap<String, LightNodeAttestation>>>,
+    /// Window-validated, at most one per Light node per reward window
+    light_node_attestations: Arc<RwLock<crate::light_attestation::LightAttestations>>,
     
     /// PRODUCTION: Active Full/Super nodes for pinger selection
     /// Updated via gossip, used for deterministic pinger assignment
@@ -538,6 +690,43 @@ pub struct SimplifiedP2P {
     /// PRODUCTION: Macroblock processing channel
     /// Received macroblocks are sent here for validation and storage
     macroblock_tx: Arc<Mutex<Option<tokio::sync::mpsc::UnboundedSender<ReceivedBlock>>>>,
+    
+    /// SCALABILITY: Inbound/outbound connection limits
+    p2p_config: P2PConfig,
+    
+    /// SCALABILITY: Peers admitted via inbound discovery (address -> combined reputation)
+    inbound_peers: Arc<DashMap<String, f64>>,
+    
+    /// SCALABILITY: Inbound admissions in the current second (second, count)
+    inbound_rate_window: Arc<Mutex<(u64, usize)>>,
+    
+    /// COMPATIBILITY: Accepted handshakes by peer address
+    peer_handshakes: Arc<DashMap<String, PeerHandshake>>,
+    
+    /// COMPATIBILITY: Addresses we already sent our handshake to
+    handshake_sent: Arc<DashSet<String>>,
+    
+    /// SYNC: EWMA round-trip latency per peer address (milliseconds)
+    peer_latency_ewma: Arc<DashMap<String, f64>>,
+    
+    /// SYNC: Address of the peer the last block sync request went to
+    last_sync_peer: Arc<RwLock<Option<String>>>,
+    
+    /// SECURITY: Decaying invalid-block score per producer (persisted across restarts)
+    invalid_block_scores: Arc<Mutex<crate::invalid_blocks::InvalidBlockTracker>>,
+    
+    /// SYNC: Peers that supplied invalid synced blocks, per height being retried
+    sync_retries: Arc<Mutex<crate::sync_retry::SyncRetryTracker>>,
+    
+    /// SECURITY: Dedup/replay guard for consensus commits and reveals, per (round, participant, phase)
+    /// Gossip re-delivers the same message many times; conflicting ones are equivocation
+    consensus_guard: Arc<Mutex<ConsensusGuard>>,
+    
+    /// NETWORK: Pluggable transport; None sends over HTTP directly
+    transport: Option<Arc<dyn Transport>>,
+    
+    /// DISCOVERY: Last DHT bootstrap watchdog report (regular nodes)
+    discovery_status: Arc<RwLock<DiscoveryStatus>>,
 }
 
 /// HYBRID: Simplified certificate manager for microblocks only
@@ -1097,7 +1286,9 @@ impl SimplifiedP2P {
             last_heartbeat_cleanup: Arc::new(Mutex::new(0)),
             
             // PRODUCTION: Light Node attestations for sharded ping system
-            light_node_attestations: Arc::new(RwLock::new(HashMap::new())),
+            light_node_attestations: Arc::new(RwLock::new(crate::light_attestation::LightAttestations::new(
+                crate::light_attestation::AttestationWindow::from_chain_params(),
+            ))),
             
             // PRODUCTION: Active Full/Super nodes map for pinger selection (gossip-synced)
             active_full_super_nodes: Arc::new(RwLock::new(HashMap::new())),
@@ -1105,6 +1296,20 @@ impl SimplifiedP2P {
             // PRODUCTION: Macroblock sync channels (v2.19.12)
             macroblock_sync_request_tx: None,
             macroblock_tx: Arc::new(Mutex::new(None)),
+            
+            // SCALABILITY: Connection admission control
+            p2p_config: P2PConfig::from_env(),
+            inbound_peers: Arc::new(DashMap::new()),
+            inbound_rate_window: Arc::new(Mutex::new((0, 0))),
+            peer_handshakes: Arc::new(DashMap::new()),
+            handshake_sent: Arc::new(DashSet::new()),
+            peer_latency_ewma: Arc::new(DashMap::new()),
+            last_sync_peer: Arc::new(RwLock::new(None)),
+            invalid_block_scores: Arc::new(Mutex::new(crate::invalid_blocks::InvalidBlockTracker::from_env())),
+            sync_retries: Arc::new(Mutex::new(crate::sync_retry::SyncRetryTracker::from_env())),
+            consensus_guard: Arc::new(Mutex::new(ConsensusGuard::from_env())),
+            transport: None,
+            discovery_status: Arc::new(RwLock::new(DiscoveryStatus::default())),
         }
     }
 
@@ -1114,6 +1319,23 @@ impl SimplifiedP2P {
         println!("[P2P] 🏛️ Consensus integration channel established");
     }
     
+    /// NETWORK: Route P2P messages through a pluggable transport instead of direct HTTP
+    /// Inbound messages are delivered by `run_transport`
+    pub fn set_transport(&mut self, transport: Arc<dyn Transport>) {
+        println!("[P2P] 🔌 Transport installed on {}", transport.local_addr());
+        self.transport = Some(transport);
+    }
+    
+    /// NETWORK: Feed messages arriving on the transport to `handle_message` until it closes
+    pub async fn run_transport(&self) {
+        let Some(transport) = self.transport.clone() else {
+            return;
+        };
+        while let Some((from_peer, message)) = transport.receive().await {
+            self.handle_message(&from_peer, message);
+        }
+    }
+    
     /// PRODUCTION: Set block processing channel for storage integration
     pub fn set_block_channel(&mut self, block_tx: tokio::sync::mpsc::UnboundedSender<ReceivedBlock>) {
         *self.block_tx.lock().unwrap() = Some(block_tx);
@@ -1386,9 +1608,9 @@ impl SimplifiedP2P {
     fn resolve_genesis_node_address(node_id: &str) -> Option<String> {
         if let Some(num) = node_id.strip_prefix("genesis_node_") {
             if let Ok(idx) = num.parse::<usize>() {
-                let genesis_ips = get_genesis_bootstrap_ips();
-                if idx > 0 && idx <= genesis_ips.len() {
-                    return Some(format!("{}:8001", genesis_ips[idx - 1]));
+                let genesis_addrs = get_genesis_bootstrap_addrs();
+                if idx > 0 && idx <= genesis_addrs.len() {
+                    return Some(genesis_addrs[idx - 1].clone());
                 }
             }
         }
@@ -1717,6 +1939,276 @@ impl SimplifiedP2P {
         }
     }
     
+    /// Replace connection admission limits
+    pub fn set_p2p_config(&mut self, config: P2PConfig) {
+        self.p2p_config = config;
+    }
+    
+    /// Current connection admission limits
+    pub fn p2p_config(&self) -> &P2PConfig {
+        &self.p2p_config
+    }
+    
+    /// Check whether a peer address is present in either peer index
+    fn is_connected_addr(&self, addr: &str) -> bool {
+        self.connected_peers_lockfree.contains_key(addr)
+            || self.connected_peer_addrs.read().map(|a| a.contains(addr)).unwrap_or(false)
+    }
+    
+    /// Current inbound/outbound connection counts
+    pub fn connection_counts(&self) -> ConnectionCounts {
+        // Drop inbound entries for peers removed elsewhere (cleanup, failures)
+        self.inbound_peers.retain(|addr, _| self.is_connected_addr(addr));
+        
+        let total = self.connected_peers_lockfree.len()
+            .max(self.connected_peer_addrs.read().map(|a| a.len()).unwrap_or(0));
+        let inbound = self.inbound_peers.len().min(total);
+        
+        ConnectionCounts {
+            inbound,
+            outbound: total - inbound,
+        }
+    }
+    
+    /// Check whether another outbound connection fits within the limit
+    pub fn can_open_outbound(&self) -> bool {
+        self.connection_counts().outbound < self.p2p_config.max_outbound
+    }
+    
+    /// SYNC: Record a measured round-trip time for a peer
+    pub fn record_peer_latency(&self, addr: &str, latency_ms: u32) {
+        update_latency_ewma(&self.peer_latency_ewma, addr, latency_ms as f64);
+    }
+    
+    /// SYNC: Smoothed latency estimate for a peer, if any sample was recorded
+    pub fn peer_latency_estimate(&self, addr: &str) -> Option<f64> {
+        self.peer_latency_ewma.get(addr).map(|ewma| *ewma)
+    }
+
+    /// Snapshot of the connected peer table for operators, sorted by address
+    pub fn peer_table_report(&self) -> PeerTableReport {
+        // Both peer indexes may hold entries depending on the storage mode in use
+        let mut by_addr: HashMap<String, PeerInfo> = self.connected_peers.read()
+            .map(|peers| peers.values().map(|p| (p.addr.clone(), p.clone())).collect())
+            .unwrap_or_default();
+        for entry in self.connected_peers_lockfree.iter() {
+            by_addr.insert(entry.key().clone(), entry.value().clone());
+        }
+
+        let mut peers: Vec<ConnectedPeerInfo> = by_addr.into_values()
+            .map(|peer| ConnectedPeerInfo {
+                latency_ms: self.peer_latency_estimate(&peer.addr)
+                    .map(|ms| ms.round() as u32)
+                    .unwrap_or(peer.latency_ms),
+                inbound: self.inbound_peers.contains_key(&peer.addr),
+                reputation: peer.combined_reputation(),
+                consensus_score: peer.consensus_score,
+                last_seen: peer.last_seen,
+                node_type: peer.node_type,
+                region: peer.region,
+                id: peer.id,
+                addr: peer.addr,
+            })
+            .collect();
+        peers.sort_by(|a, b| a.addr.cmp(&b.addr));
+
+        let mut by_region = std::collections::BTreeMap::new();
+        let mut by_node_type = std::collections::BTreeMap::new();
+        for peer in &peers {
+            *by_region.entry(format!("{:?}", peer.region)).or_insert(0) += 1;
+            *by_node_type.entry(format!("{:?}", peer.node_type)).or_insert(0) += 1;
+        }
+
+        PeerTableReport {
+            total: peers.len(),
+            peers,
+            by_region,
+            by_node_type,
+        }
+    }
+    
+    /// SYNC: Score a peer as a sync source - reputation first, latency as a bounded bonus
+    /// A fast but poorly reputed peer can never outrank a well reputed one by more than
+    /// SYNC_LATENCY_WEIGHT points, so latency only breaks near-ties
+    fn sync_peer_score(&self, peer: &PeerInfo) -> f64 {
+        let latency_ms = self.peer_latency_estimate(&peer.addr)
+            .or_else(|| (peer.latency_ms > 0).then_some(peer.latency_ms as f64))
+            .unwrap_or(SYNC_LATENCY_CEILING_MS / 2.0); // Unmeasured: neutral midpoint
+        let latency_bonus = (1.0 - (latency_ms / SYNC_LATENCY_CEILING_MS).min(1.0)) * SYNC_LATENCY_WEIGHT;
+        peer.combined_reputation() + latency_bonus
+    }
+    
+    /// SYNC: Highest scoring peer among candidates
+    fn select_sync_peer<'a>(&self, peers: impl IntoIterator<Item = &'a PeerInfo>) -> Option<&'a PeerInfo> {
+        peers.into_iter()
+            .map(|peer| (self.sync_peer_score(peer), peer))
+            .max_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(_, peer)| peer)
+    }
+    
+    /// SYNC: Best validated peer to sync from (reputation blended with measured latency)
+    pub fn best_sync_peer(&self) -> Option<PeerInfo> {
+        let peers = self.get_validated_active_peers();
+        self.select_sync_peer(&peers).cloned()
+    }
+    
+    /// SCALABILITY: Admission control for inbound peers
+    /// Enforces the per-second rate and inbound cap; at capacity a higher-reputation
+    /// newcomer displaces the weakest inbound peer, otherwise it gets a backoff hint
+    pub fn accept_inbound_peer(&self, peer_info: PeerInfo) -> InboundDecision {
+        if self.inbound_peers.contains_key(&peer_info.addr) && self.is_connected_addr(&peer_info.addr) {
+            return InboundDecision::Accepted;
+        }
+        
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        {
+            let mut window = self.inbound_rate_window.lock().unwrap_or_else(|e| e.into_inner());
+            if window.0 != now {
+                *window = (now, 0);
+            }
+            if window.1 >= self.p2p_config.inbound_rate_per_sec {
+                println!("[P2P] ⏳ Inbound rate limit reached, deferring {}", get_privacy_id_for_addr(&peer_info.addr));
+                return InboundDecision::Rejected { retry_after_secs: 1 };
+            }
+            window.1 += 1;
+        }
+        
+        let counts = self.connection_counts();
+        let reputation = peer_info.combined_reputation();
+        let mut evicted = None;
+        
+        if counts.inbound >= self.p2p_config.max_inbound {
+            // Keep high-reputation peers: only displace the weakest inbound peer
+            let weakest = self.inbound_peers.iter()
+                .map(|entry| (entry.key().clone(), *entry.value()))
+                .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+            
+            match weakest {
+                Some((weakest_addr, weakest_rep)) if reputation > weakest_rep => {
+                    self.inbound_peers.remove(&weakest_addr);
+                    if !self.remove_peer_lockfree(&weakest_addr) {
+                        if let Ok(mut peers) = self.connected_peers.write() {
+                            peers.remove(&weakest_addr);
+                        }
+                        if let Ok(mut addrs) = self.connected_peer_addrs.write() {
+                            addrs.remove(&weakest_addr);
+                        }
+                    }
+                    println!("[P2P] 🔄 Inbound at capacity: replaced {} (rep: {:.2}) with {} (rep: {:.2})",
+                             get_privacy_id_for_addr(&weakest_addr), weakest_rep,
+                             get_privacy_id_for_addr(&peer_info.addr), reputation);
+                    evicted = Some(weakest_addr);
+                }
+                _ => {
+                    println!("[P2P] 🚫 Inbound at capacity ({}), rejecting {} (rep: {:.2})",
+                             self.p2p_config.max_inbound, get_privacy_id_for_addr(&peer_info.addr), reputation);
+                    return InboundDecision::Rejected { retry_after_secs: INBOUND_CAPACITY_BACKOFF_SECS };
+                }
+            }
+        }
+        
+        let addr = peer_info.addr.clone();
+        if !self.add_peer_safe(peer_info) && !self.is_connected_addr(&addr) {
+            // Rejected by self-connection or K-bucket rules
+            return InboundDecision::Rejected { retry_after_secs: INBOUND_CAPACITY_BACKOFF_SECS };
+        }
+        self.inbound_peers.insert(addr, reputation);
+        
+        match evicted {
+            Some(evicted) => InboundDecision::Replaced { evicted },
+            None => InboundDecision::Accepted,
+        }
+    }
+    
+    /// COMPATIBILITY: Our handshake, or None until the external IP is known
+    pub fn local_handshake(&self) -> Option<PeerHandshake> {
+        let ip = self.external_ip.read().ok()?.clone()?;
+        Some(PeerHandshake {
+            protocol_version: crate::node::PROTOCOL_VERSION,
+            network_id: crate::network_config::get_network_config().network_id.clone(),
+            node_type: self.node_type.clone(),
+            node_id: self.node_id.clone(),
+            addr: format!("{}:{}", ip, self.port),
+            region: self.region.clone(),
+        })
+    }
+    
+    /// COMPATIBILITY: Send our handshake to `peer_addr` once per address
+    pub fn send_handshake(&self, peer_addr: &str) {
+        if !self.handshake_sent.insert(peer_addr.to_string()) {
+            return;
+        }
+        match self.local_handshake() {
+            Some(handshake) => self.send_network_message(peer_addr, NetworkMessage::Handshake { handshake }),
+            None => {
+                // Retry on the next connection attempt once our IP is known
+                self.handshake_sent.remove(peer_addr);
+                println!("[P2P] ⚠️ External IP unknown - handshake to {} postponed", get_privacy_id_for_addr(peer_addr));
+            }
+        }
+    }
+    
+    /// COMPATIBILITY: Version and network checks a handshake must pass
+    pub fn check_handshake(handshake: &PeerHandshake, network_id: &str) -> Result<(), HandshakeRejection> {
+        if handshake.protocol_version < crate::node::MIN_COMPATIBLE_VERSION {
+            return Err(HandshakeRejection::VersionTooOld {
+                got: handshake.protocol_version,
+                min: crate::node::MIN_COMPATIBLE_VERSION,
+            });
+        }
+        if handshake.network_id != network_id {
+            return Err(HandshakeRejection::NetworkMismatch {
+                ours: network_id.to_string(),
+                theirs: handshake.network_id.clone(),
+            });
+        }
+        Ok(())
+    }
+    
+    /// COMPATIBILITY: Admit a handshaking peer into the routing table, or drop it
+    /// if it is too old or on another network (also when we dialed it ourselves)
+    pub fn handle_handshake(&self, handshake: PeerHandshake) -> Result<InboundDecision, HandshakeRejection> {
+        let network_id = &crate::network_config::get_network_config().network_id;
+        let checked = Self::check_handshake(&handshake, network_id).and_then(|_| {
+            Self::parse_peer_address_static(&format!("{}@{}", handshake.node_id, handshake.addr))
+                .map_err(|_| HandshakeRejection::InvalidAddress(handshake.addr.clone()))
+        });
+        let mut peer_info = match checked {
+            Ok(peer_info) => peer_info,
+            Err(rejection) => {
+                println!("[P2P] 🚫 Handshake from {} rejected: {}", get_privacy_id_for_addr(&handshake.addr), rejection);
+                self.peer_handshakes.remove(&handshake.addr);
+                self.inbound_peers.remove(&handshake.addr);
+                if !self.remove_peer_lockfree(&handshake.addr) {
+                    if let Ok(mut peers) = self.connected_peers.write() {
+                        peers.remove(&handshake.addr);
+                    }
+                    if let Ok(mut addrs) = self.connected_peer_addrs.write() {
+                        addrs.remove(&handshake.addr);
+                    }
+                }
+                return Err(rejection);
+            }
+        };
+        peer_info.node_type = handshake.node_type.clone();
+        peer_info.region = handshake.region.clone();
+        
+        let decision = self.accept_inbound_peer(peer_info.clone());
+        if !matches!(decision, InboundDecision::Rejected { .. }) {
+            self.peer_handshakes.insert(handshake.addr.clone(), handshake);
+            self.add_peer_to_region(peer_info);
+        }
+        Ok(decision)
+    }
+    
+    /// COMPATIBILITY: Handshake accepted from this peer address
+    pub fn peer_handshake(&self, peer_addr: &str) -> Option<PeerHandshake> {
+        self.peer_handshakes.get(peer_addr).map(|h| h.clone())
+    }
+    
     /// QUANTUM OPTIMIZATION: Lock-free peer addition for millions of nodes
     /// Uses DashMap for concurrent operations without blocking
     pub fn add_peer_lockfree(&self, mut peer_info: PeerInfo) -> bool {
@@ -1985,6 +2477,13 @@ impl SimplifiedP2P {
         
         let mut new_connections = 0;
         for peer_addr in peer_addresses {
+            // SCALABILITY: Respect the outbound connection limit
+            if !self.can_open_outbound() {
+                println!("[P2P] ⚠️ Outbound limit ({}) reached, skipping remaining discovered peers",
+                         self.p2p_config.max_outbound);
+                break;
+            }
+            
             // CRITICAL: Filter out private/internal IPs before parsing
             let ip = peer_addr.split(':').next().unwrap_or("");
             if ip.starts_with("172.17.") || ip.starts_with("172.18.") 
@@ -2072,6 +2571,7 @@ impl SimplifiedP2P {
                             // CRITICAL FIX: Use centralized add_peer_safe to prevent duplicates
                             if self.add_peer_safe(peer_info.clone()) {
                     self.add_peer_to_region(peer_info.clone());
+                                self.send_handshake(&peer_info.addr);
                                 new_connections += 1;
                                 
                                 // CACHE FIX: Invalidate peer cache when topology changes
@@ -2258,7 +2758,7 @@ impl SimplifiedP2P {
     }
     
     /// Search for other QNet nodes on the internet with cryptographic peer verification
-    fn search_internet_peers(&self) {
+    pub(crate) fn search_internet_peers(&self) {
         let node_id = self.node_id.clone();
         let region = self.region.clone();
         let regional_peers = self.regional_peers.clone();
@@ -2947,6 +3447,22 @@ impl SimplifiedP2P {
                 let peer_latency = peer.latency_ms; // Copy latency before move
                 let block_data_clone = Arc::clone(&block_data);
                 
+                if let Some(transport) = &self.transport {
+                    let transport = Arc::clone(transport);
+                    let runtime = tokio::runtime::Handle::current();
+                    let handle = thread::spawn(move || {
+                        let block_msg = NetworkMessage::Block {
+                            height,
+                            data: (*block_data_clone).clone(),
+                            block_type: "micro".to_string(),
+                        };
+                        runtime.block_on(transport.send(&peer_addr, &block_msg))
+                            .map_err(|e| e.to_string())
+                    });
+                    handles.push((peer.addr.clone(), handle));
+                    continue;
+                }
+                
                 // Spawn thread for parallel sending
                 let handle = thread::spawn(move || {
                     use std::time::Duration;
@@ -3900,24 +4416,26 @@ impl SimplifiedP2P {
             .unwrap_or(false);
         
         // EXISTING: CORRECT Byzantine safety logic for consensus participation
-        // EXISTING: min_participants: 4 from consensus config (3f+1 where f=1)
+        // Same 3f+1 minimum as the consensus config
+        let byzantine = crate::network_config::get_byzantine_params();
         if is_genesis_bootstrap {
             // EXISTING: Use validated peers for consensus participation (real connectivity only)
             let validated_peers = self.get_validated_active_peers();
             let total_network_nodes = std::cmp::min(validated_peers.len() + 1, 5); // EXISTING: Add self, max 5 Genesis
             
-            if total_network_nodes >= 4 {
+            if total_network_nodes >= byzantine.min_participants() {
                 println!("🏛️ [CONSENSUS] Genesis node with {} total nodes - Byzantine consensus enabled", total_network_nodes);
                 // Continue to normal Byzantine checks below
             } else {
-                println!("⚠️ [CONSENSUS] Genesis bootstrap - insufficient nodes for Byzantine safety: {}/4", total_network_nodes);
+                println!("⚠️ [CONSENSUS] Genesis bootstrap - insufficient nodes for Byzantine safety: {}/{}",
+                         total_network_nodes, byzantine.min_participants());
                 println!("🔄 [CONSENSUS] Waiting for more Genesis nodes to join network...");
                 return false; // Even Genesis needs Byzantine safety
             }
         }
         
         // For non-genesis nodes: Strict Byzantine consensus requirement using validated peers
-        let min_nodes_for_consensus = 4; // EXISTING: Need 3f+1 nodes to tolerate f failures  
+        let min_nodes_for_consensus = byzantine.min_participants(); // EXISTING: Need 3f+1 nodes to tolerate f failures
         let validated_peers = self.get_validated_active_peers();
         let total_network_nodes = std::cmp::min(validated_peers.len() + 1, 1000); // EXISTING: Scale to network size
         
@@ -3942,7 +4460,7 @@ impl SimplifiedP2P {
         
         // Non-genesis nodes can participate if sufficient network diversity exists
         // In production: This would use reputation scores and validator selection algorithm (NO STAKE!)
-        validated_peers.len() >= 3 // Allow participation with sufficient peer diversity
+        byzantine.has_quorum(validated_peers.len()) // Allow participation with sufficient peer diversity
     }
     
     /// PRODUCTION: Cryptographic peer verification using post-quantum signatures
@@ -4611,6 +5129,15 @@ impl SimplifiedP2P {
         }
     }
     
+    /// DISCOVERY: DHT peer count and whether the bootstrap fallback is active
+    pub fn discovery_status(&self) -> DiscoveryStatus {
+        self.discovery_status.read().unwrap().clone()
+    }
+    
+    pub(crate) fn set_discovery_status(&self, status: DiscoveryStatus) {
+        *self.discovery_status.write().unwrap() = status;
+    }
+    
     /// PRODUCTION: Get discovery peers for DHT/API (Fast method for millions of nodes)  
     pub fn get_discovery_peers(&self) -> Vec<PeerInfo> {
         // ARCHITECTURE: Bootstrap nodes use deterministic Genesis peer list for consistent VRF
@@ -5931,6 +6458,10 @@ impl SimplifiedP2P {
     
     /// Update peer metrics
     pub fn update_peer_metrics(&self, peer_id: &str, latency_ms: u32, bandwidth_usage: u64) {
+        if let Some(addr_entry) = self.peer_id_to_addr.get(peer_id) {
+            self.record_peer_latency(addr_entry.value(), latency_ms);
+        }
+        
         // PRODUCTION: Use dual indexing for O(1) lookup by ID (already implemented)
         // First check if we should use lock-free mode
         if self.should_use_lockfree() {
@@ -6070,6 +6601,7 @@ impl SimplifiedP2P {
         let last_check = self.last_health_check.clone();
         let connected_peers = self.connected_peers.clone();
         let regional_metrics = self.regional_metrics.clone();
+        let peer_latency_ewma = self.peer_latency_ewma.clone();
         
         thread::spawn(move || {
             while *is_running.lock().unwrap() {
@@ -6085,6 +6617,7 @@ impl SimplifiedP2P {
                         // PRODUCTION: Query peer's /api/v1/node/health endpoint for real metrics
                         if let Ok(metrics) = Self::query_peer_metrics(&peer.addr) {
                             peer.latency_ms = metrics.latency_ms;
+                            update_latency_ewma(&peer_latency_ewma, &peer.addr, metrics.latency_ms as f64);
                         peer.last_seen = std::time::SystemTime::now()
                             .duration_since(std::time::UNIX_EPOCH)
                                 .unwrap_or_else(|_| {
@@ -6385,6 +6918,14 @@ impl SimplifiedP2P {
         use std::process::Command;
         use std::net::{SocketAddr, UdpSocket};
         
+        // Explicit advertise address (NAT/proxy setups) wins over detection
+        if let Some(addr) = std::env::var("QNET_ADVERTISE_ADDR").ok()
+            .and_then(|value| crate::listen_addr::parse_socket_addr(&value, 0).ok())
+            .filter(|addr| !addr.ip().is_unspecified())
+        {
+            return Ok(addr.ip().to_string());
+        }
+        
         // IMPROVED: Check if we're in Docker and need special handling
         if std::path::Path::new("/.dockerenv").exists() {
             println!("[P2P] 🐳 Docker environment detected, using enhanced NAT traversal");
@@ -6727,6 +7268,9 @@ impl SimplifiedP2P {
         let mut consecutive_failures = 0;
         const MAX_CONSECUTIVE_FAILURES: u32 = 20;  // CRITICAL FIX: Increased from 3 to 20 to handle async broadcast delays
         
+        // Downloaded blocks are committed in chunks with one RocksDB write each
+        let mut pending: Vec<(u64, Vec<u8>)> = Vec::with_capacity(SYNC_WRITE_BATCH_SIZE);
+        
         let mut height = start_height;
         while height <= end_height {
             // CRITICAL FIX: Check if block ACTUALLY exists (not just Ok())
@@ -6761,14 +7305,10 @@ impl SimplifiedP2P {
                             if let Ok(val) = response.json::<serde_json::Value>().await {
                                 if let Some(b64) = val.get("data").and_then(|v| v.as_str()) {
                                     if let Ok(bytes) = base64::engine::general_purpose::STANDARD.decode(b64) {
-                                        if storage.save_microblock(height, &bytes).is_ok() {
-                                            fetched = true;
-                                            consecutive_failures = 0;
-                                            
-                                            // CRITICAL FIX: Update LOCAL_BLOCKCHAIN_HEIGHT when syncing
-                                            LOCAL_BLOCKCHAIN_HEIGHT.store(height, Ordering::Relaxed);
-                                            break;
-                                        }
+                                        pending.push((height, bytes));
+                                        fetched = true;
+                                        consecutive_failures = 0;
+                                        break;
                                     }
                                 }
                             }
@@ -6782,6 +7322,9 @@ impl SimplifiedP2P {
             }
             
             if !fetched {
+                // Don't hold downloaded blocks while waiting on a missing one
+                Self::flush_synced_blocks(storage, &mut pending);
+                
                 consecutive_failures += 1;
                 if consecutive_failures >= MAX_CONSECUTIVE_FAILURES {
                     println!("[SYNC] ⚠️ Range {}-{} hit {} consecutive failures at block {} - waiting 3s for block propagation", 
@@ -6799,8 +7342,28 @@ impl SimplifiedP2P {
             } else {
                 // Successfully fetched block - move to next height
                 height += 1;
+                if pending.len() >= SYNC_WRITE_BATCH_SIZE {
+                    Self::flush_synced_blocks(storage, &mut pending);
+                }
             }
         }
+        Self::flush_synced_blocks(storage, &mut pending);
+    }
+    
+    /// Commit downloaded blocks in one atomic write
+    /// A failed chunk leaves gaps that the integrity check re-requests
+    fn flush_synced_blocks(storage: &crate::storage::Storage, pending: &mut Vec<(u64, Vec<u8>)>) {
+        let Some(&(last_height, _)) = pending.last() else {
+            return;
+        };
+        let first_height = pending[0].0;
+        
+        match storage.save_microblocks_batch(pending) {
+            // CRITICAL FIX: Update LOCAL_BLOCKCHAIN_HEIGHT when syncing
+            Ok(()) => LOCAL_BLOCKCHAIN_HEIGHT.store(last_height, Ordering::Relaxed),
+            Err(e) => println!("[SYNC] ❌ Failed to store blocks {}-{}: {}", first_height, last_height, e),
+        }
+        pending.clear();
     }
 }
 
@@ -6883,6 +7446,8 @@ pub struct LightNodeAttestation {
     pub pinger_id: String,            // Full/Super node that pinged
     pub slot: u64,                    // Time slot (4h window / 240 = 1 min slots)
     pub timestamp: u64,               // When attestation was created
+    #[serde(default)]
+    pub window_start: u64,            // Reward window the attestation counts for (0 from older peers)
     pub light_node_signature: String, // Light node's signature on challenge
     pub pinger_signature: String,     // Pinger's signature on attestation
     pub challenge: String,            // Original challenge (for verification)
@@ -6920,6 +7485,11 @@ pub enum NetworkMessage {
         requesting_node: PeerInfo,
     },
     
+    /// Protocol/network handshake, sent first on every new connection
+    Handshake {
+        handshake: PeerHandshake,
+    },
+    
     /// Simple health ping
     HealthPing {
         from: String,
@@ -6990,6 +7560,9 @@ pub enum NetworkMessage {
         from_height: u64,
         to_height: u64,
         sender_id: String,
+        /// Set when the request was clamped: first height still to be requested
+        #[serde(default)]
+        continue_from: Option<u64>,
     },
     
     /// Sync status query
@@ -7122,8 +7695,10 @@ pub enum NetworkMessage {
     LightNodeAttestation {
         light_node_id: String,        // Light node that was pinged
         pinger_id: String,            // Full/Super node that pinged
-        slot: u64,                    // Time slot for deduplication
+        slot: u64,                    // Time slot within the window
         timestamp: u64,               // When attestation was created
+        #[serde(default)]
+        window_start: u64,            // Reward window claimed (0 from older peers)
         light_node_signature: String, // Light node's signature on challenge
         pinger_signature: String,     // Pinger's signature on attestation
         challenge: String,            // Original challenge
@@ -7291,9 +7866,26 @@ impl SimplifiedP2P {
             NetworkMessage::PeerDiscovery { requesting_node } => {
                 println!("[P2P] ← Peer discovery from {} in {:?}", 
                          requesting_node.id, requesting_node.region);
+                // SCALABILITY: Inbound cap and rate limit before the peer is registered
+                if let InboundDecision::Rejected { retry_after_secs } = self.accept_inbound_peer(requesting_node.clone()) {
+                    println!("[P2P] ⏳ Peer discovery from {} deferred, retry after {}s", requesting_node.id, retry_after_secs);
+                    return;
+                }
                 self.add_peer_to_region(requesting_node);
             }
             
+            NetworkMessage::Handshake { handshake } => {
+                let peer_addr = handshake.addr.clone();
+                match self.handle_handshake(handshake) {
+                    Ok(InboundDecision::Rejected { retry_after_secs }) => {
+                        println!("[P2P] ⏳ Handshake from {} deferred, retry after {}s", get_privacy_id_for_addr(&peer_addr), retry_after_secs);
+                    }
+                    // Answer with ours so the dialing side can check us too
+                    Ok(_) => self.send_handshake(&peer_addr),
+                    Err(_) => {} // Logged in handle_handshake
+                }
+            }
+            
             NetworkMessage::HealthPing { from, timestamp: _ } => {
                 // Update last_seen for the peer who sent the ping
                 self.update_peer_last_seen(&from);
@@ -7433,10 +8025,13 @@ impl SimplifiedP2P {
                 self.handle_block_request(from_peer, from_height, to_height, requester_id);
             }
             
-            NetworkMessage::BlocksBatch { blocks, from_height, to_height, sender_id } => {
+            NetworkMessage::BlocksBatch { blocks, from_height, to_height, sender_id, continue_from } => {
                 // Handle batch of blocks for sync
                 println!("[SYNC] 📦 Received {} blocks from {} (heights {}-{})", 
                          blocks.len(), sender_id, from_height, to_height);
+                if let Some(next) = continue_from {
+                    println!("[SYNC] ↪️ Range clamped by {}, continue from {}", sender_id, next);
+                }
                 self.handle_blocks_batch(blocks, from_height, to_height, sender_id);
             }
             
@@ -8046,8 +8641,9 @@ impl SimplifiedP2P {
                     let heartbeats = self.heartbeat_history.read().unwrap();
                     if let Some(existing) = heartbeats.get(&heartbeat_key) {
                         // Same 4h window? Skip
-                        let current_4h_window = now - (now % (4 * 60 * 60));
-                        let existing_4h_window = existing.timestamp - (existing.timestamp % (4 * 60 * 60));
+                        let params = crate::network_config::get_chain_params();
+                        let current_4h_window = params.reward_window_start(now);
+                        let existing_4h_window = params.reward_window_start(existing.timestamp);
                         if current_4h_window == existing_4h_window {
                             return; // Already have this heartbeat for current window
                         }
@@ -8153,7 +8749,7 @@ impl SimplifiedP2P {
             
             // PRODUCTION: Light Node attestation - proof of ping response
             NetworkMessage::LightNodeAttestation {
-                light_node_id, pinger_id, slot, timestamp, 
+                light_node_id, pinger_id, slot, timestamp, window_start,
                 light_node_signature, pinger_signature, challenge, gossip_hop
             } => {
                 self.update_peer_last_seen(from_peer);
@@ -8163,15 +8759,21 @@ impl SimplifiedP2P {
                     return;
                 }
                 
-                // DEDUPE: Check if we already have attestation for this slot
-                let attestation_key = format!("{}:{}", light_node_id, slot);
-                {
+                // DEDUPE: One attestation per Light node per window
+                // WINDOW: Timestamp must fall inside the claimed window
+                let window_start = {
                     let attestations = self.light_node_attestations.read().unwrap();
-                    if attestations.contains_key(&attestation_key) {
-                        // Already have attestation for this Light node in this slot
+                    let window_start = attestations.claimed_window(window_start, timestamp);
+                    if attestations.contains(&light_node_id, window_start) {
+                        // Already have attestation for this Light node in this window
                         return;
                     }
-                }
+                    if let Err(e) = attestations.window().validate(window_start, slot, timestamp) {
+                        println!("[ATTESTATION] ❌ Rejected attestation for {}: {}", light_node_id, e);
+                        return;
+                    }
+                    window_start
+                };
                 
                 // TIMESTAMP VALIDATION: Must be within ±5 minutes
                 let now = std::time::SystemTime::now()
@@ -8217,23 +8819,26 @@ impl SimplifiedP2P {
                     // Capacity check: cleanup oldest if at limit
                     if attestations.len() >= MAX_ATTESTATIONS_SIZE {
                         let cutoff = timestamp.saturating_sub(RETENTION_PERIOD_SECS);
-                        let before = attestations.len();
-                        attestations.retain(|_, v| v.timestamp > cutoff);
-                        let removed = before - attestations.len();
+                        let removed = attestations.retain_newer_than(cutoff);
                         if removed > 0 {
                             println!("[ATTESTATION] 🧹 Cleaned up {} old attestations", removed);
                         }
                     }
                     
-                    attestations.insert(attestation_key.clone(), LightNodeAttestation {
+                    let inserted = attestations.insert(LightNodeAttestation {
                         light_node_id: light_node_id.clone(),
                         pinger_id: pinger_id.clone(),
                         slot,
                         timestamp,
+                        window_start,
                         light_node_signature: light_node_signature.clone(),
                         pinger_signature: pinger_signature.clone(),
                         challenge: challenge.clone(),
                     });
+                    // Another copy may have been stored while the signature was checked
+                    if inserted.is_err() {
+                        return;
+                    }
                 }
                 
                 // WHITEPAPER: Light nodes have FIXED reputation of 70
@@ -8248,6 +8853,7 @@ impl SimplifiedP2P {
                     pinger_id,
                     slot,
                     timestamp,
+                    window_start,
                     light_node_signature,
                     pinger_signature,
                     challenge,
@@ -8693,8 +9299,9 @@ impl SimplifiedP2P {
                         let already_sent = {
                             let history = p2p.heartbeat_history.read().unwrap();
                             if let Some(record) = history.get(&heartbeat_key) {
-                                let current_4h = now - (now % (4 * 60 * 60));
-                                let record_4h = record.timestamp - (record.timestamp % (4 * 60 * 60));
+                                let params = crate::network_config::get_chain_params();
+                                let current_4h = params.reward_window_start(now);
+                                let record_4h = params.reward_window_start(record.timestamp);
                                 current_4h == record_4h
                             } else {
                                 false
@@ -8917,7 +9524,8 @@ impl SimplifiedP2P {
             .unwrap()
             .as_secs();
         
-        let current_4h_window = now - (now % (4 * 60 * 60));
+        let params = crate::network_config::get_chain_params();
+        let current_4h_window = params.reward_window_start(now);
         
         // Count successful heartbeats in current 4h window
         let mut count = 0u8;
@@ -8926,7 +9534,7 @@ impl SimplifiedP2P {
             for i in 0..10 {
                 let key = format!("{}:{}", node_id, i);
                 if let Some(record) = history.get(&key) {
-                    let record_4h = record.timestamp - (record.timestamp % (4 * 60 * 60));
+                    let record_4h = params.reward_window_start(record.timestamp);
                     if record_4h == current_4h_window && record.verified {
                         count += 1;
                     }
@@ -8957,15 +9565,24 @@ impl SimplifiedP2P {
         hash[0]  // First byte = shard (0-255)
     }
     
-    /// Get current slot number (0-239 within 4h window, each slot = 1 minute)
+    /// Get current slot number (0-239 within a 4h window, each slot = 1 minute)
     pub fn get_current_slot() -> u64 {
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
             .as_secs();
-        let current_4h_window = now - (now % (4 * 60 * 60));
+        let current_4h_window = crate::network_config::get_chain_params().reward_window_start(now);
         let seconds_in_window = now - current_4h_window;
-        seconds_in_window / 60  // 0-239
+        seconds_in_window / crate::network_config::PING_SLOT_SECS  // 0-239
+    }
+    
+    /// Start of the current reward window
+    pub fn get_current_window_start() -> u64 {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        crate::network_config::get_chain_params().reward_window_start(now)
     }
     
     /// Get current 4-hour window number (for randomizing ping slots)
@@ -8974,7 +9591,7 @@ impl SimplifiedP2P {
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
             .as_secs();
-        now / (4 * 60 * 60)  // Window number since epoch
+        crate::network_config::get_chain_params().reward_window_number(now)  // Window number since epoch
     }
     
     /// Calculate ping slot for Light node with per-window randomization
@@ -8987,7 +9604,7 @@ impl SimplifiedP2P {
         light_node_id.hash(&mut hasher);
         window_number.hash(&mut hasher);  // Randomize per window!
         let hash = hasher.finish();
-        hash % 240  // 0-239 slots
+        hash % crate::network_config::get_chain_params().ping_slots_in_window()  // 0-239 slots
     }
     
     /// Get next ping time for a Light node (for polling fallback)
@@ -9003,18 +9620,19 @@ impl SimplifiedP2P {
         let node_slot = Self::calculate_randomized_slot(light_node_id, current_window);
         
         // Calculate window start timestamp
-        let window_start = current_window * 4 * 60 * 60;
+        let window_secs = crate::network_config::get_chain_params().reward_window_secs;
+        let window_start = current_window * window_secs;
         
         if node_slot > current_slot {
             // Ping is later in current window
-            let ping_time = window_start + (node_slot * 60);
+            let ping_time = window_start + (node_slot * crate::network_config::PING_SLOT_SECS);
             (ping_time, current_window)
         } else {
             // Ping already passed in current window, calculate for next window
             let next_window = current_window + 1;
             let next_slot = Self::calculate_randomized_slot(light_node_id, next_window);
-            let next_window_start = next_window * 4 * 60 * 60;
-            let ping_time = next_window_start + (next_slot * 60);
+            let next_window_start = next_window * window_secs;
+            let ping_time = next_window_start + (next_slot * crate::network_config::PING_SLOT_SECS);
             (ping_time, next_window)
         }
     }
@@ -9032,8 +9650,8 @@ impl SimplifiedP2P {
         let slot_diff = if current_slot >= node_slot {
             current_slot - node_slot
         } else {
-            // Handle wrap-around at slot 240
-            240 - node_slot + current_slot
+            // Handle wrap-around at the last slot (240 for a 4h window)
+            crate::network_config::get_chain_params().ping_slots_in_window() - node_slot + current_slot
         };
         
         slot_diff <= 2  // Primary slot (0) + 2 retry slots (1, 2)
@@ -9096,11 +9714,10 @@ impl SimplifiedP2P {
         }
     }
     
-    /// Check if attestation already exists for Light node in current slot
-    pub fn has_attestation(&self, light_node_id: &str, slot: u64) -> bool {
-        let key = format!("{}:{}", light_node_id, slot);
+    /// Check if Light node is already attested in the reward window starting at `window_start`
+    pub fn has_attestation(&self, light_node_id: &str, window_start: u64) -> bool {
         let attestations = self.light_node_attestations.read().unwrap();
-        attestations.contains_key(&key)
+        attestations.contains(light_node_id, window_start)
     }
     
     /// Get Light nodes in our shard (for this Full/Super node to ping)
@@ -9118,7 +9735,7 @@ impl SimplifiedP2P {
     /// CRITICAL: Only iterates over Light nodes in OUR SHARD for scalability
     /// OPTIMIZATION: Skips inactive nodes to reduce wasted pings
     pub fn get_light_nodes_to_ping(&self) -> Vec<(LightNodeRegistrationData, PingerRole)> {
-        let current_slot = Self::get_current_slot();
+        let current_window_start = Self::get_current_window_start();
         let our_shard = self.shard_id;
         let mut result = Vec::new();
         
@@ -9143,7 +9760,7 @@ impl SimplifiedP2P {
             }
             
             // Check if attestation already exists
-            if self.has_attestation(&node.node_id, current_slot) {
+            if self.has_attestation(&node.node_id, current_window_start) {
                 continue;
             }
             
@@ -9222,17 +9839,21 @@ impl SimplifiedP2P {
             pinger_id: attestation.pinger_id.clone(),
             slot: attestation.slot,
             timestamp: attestation.timestamp,
+            window_start: attestation.window_start,
             light_node_signature: attestation.light_node_signature.clone(),
             pinger_signature: attestation.pinger_signature.clone(),
             challenge: attestation.challenge.clone(),
             gossip_hop: 0,
         };
         
-        // Store locally first
-        let key = format!("{}:{}", attestation.light_node_id, attestation.slot);
+        // Store locally first (peers would reject what we can't store)
         {
+            let light_node_id = attestation.light_node_id.clone();
             let mut attestations = self.light_node_attestations.write().unwrap();
-            attestations.insert(key, attestation);
+            if let Err(e) = attestations.insert(attestation) {
+                println!("[ATTESTATION] ⚠️ Not gossiping attestation for {}: {}", light_node_id, e);
+                return;
+            }
         }
         
         // Gossip to peers
@@ -9461,10 +10082,7 @@ impl SimplifiedP2P {
         
         let cutoff = now - (24 * 60 * 60);  // 24 hours ago
         
-        let mut attestations = self.light_node_attestations.write().unwrap();
-        let before = attestations.len();
-        attestations.retain(|_, v| v.timestamp > cutoff);
-        let removed = before - attestations.len();
+        let removed = self.light_node_attestations.write().unwrap().retain_newer_than(cutoff);
         
         if removed > 0 {
             println!("[CLEANUP] 🧹 Removed {} old attestations (>24h)", removed);
@@ -9478,22 +10096,10 @@ impl SimplifiedP2P {
             .unwrap()
             .as_secs();
         
-        let current_4h_window = now - (now % (4 * 60 * 60));
-        let window_start_slot = 0u64;
-        let window_end_slot = 239u64;
+        let current_4h_window = crate::network_config::get_chain_params().reward_window_start(now);
         
-        // Count attestations in current 4h window
-        let mut count = 0u8;
-        {
-            let attestations = self.light_node_attestations.read().unwrap();
-            for slot in window_start_slot..=window_end_slot {
-                let key = format!("{}:{}", light_node_id, slot);
-                if attestations.contains_key(&key) {
-                    count += 1;
-                    break;  // Light nodes only need 1 ping
-                }
-            }
-        }
+        // Light nodes only need 1 ping, and keep at most one attestation per window
+        let count = u8::from(self.has_attestation(light_node_id, current_4h_window));
         
         (count, 1, count >= 1)
     }
@@ -9505,19 +10111,16 @@ impl SimplifiedP2P {
     /// Get all Light node attestations for a 4h window (for Merkle commitment)
     /// Returns Vec<(light_node_id, slot, pinger_id, timestamp)>
     pub fn get_attestations_for_window(&self, window_start_timestamp: u64) -> Vec<(String, u64, String, u64)> {
-        let window_end = window_start_timestamp + (4 * 60 * 60);
-        
         let attestations = self.light_node_attestations.read().unwrap();
-        attestations.values()
-            .filter(|a| a.timestamp >= window_start_timestamp && a.timestamp < window_end)
+        attestations.for_window(window_start_timestamp)
             .map(|a| (a.light_node_id.clone(), a.slot, a.pinger_id.clone(), a.timestamp))
             .collect()
     }
     
-    /// Get all Full/Super node heartbeats for a 4h window (for Merkle commitment)
+    /// Get all Full/Super node heartbeats for a reward window (for Merkle commitment)
     /// Returns Vec<(node_id, heartbeat_index, timestamp)>
     pub fn get_heartbeats_for_window(&self, window_start_timestamp: u64) -> Vec<(String, u8, u64)> {
-        let window_end = window_start_timestamp + (4 * 60 * 60);
+        let window_end = window_start_timestamp + crate::network_config::get_chain_params().reward_window_secs;
         
         let heartbeats = self.heartbeat_history.read().unwrap();
         heartbeats.values()
@@ -9609,23 +10212,25 @@ fn calculate_heartbeat_times_for_node(node_id: &str) -> Vec<u64> {
         .unwrap()
         .as_secs();
     
-    let current_4h_window = now - (now % (4 * 60 * 60));
+    let params = crate::network_config::get_chain_params();
+    let current_4h_window = params.reward_window_start(now);
+    let slots = params.ping_slots_in_window();
     
     // Deterministic base slot from node_id hash
     let mut hasher = DefaultHasher::new();
     node_id.hash(&mut hasher);
     let hash = hasher.finish();
-    let base_slot = (hash % 240) as u64; // 0-239 slots
+    let base_slot = hash % slots; // 0-239 slots for a 4h window
     
     // Offset within slot (0-59 seconds)
     let slot_offset = (node_id.len() % 60) as u64;
     
     let mut times = Vec::with_capacity(10);
     
-    // 10 heartbeats distributed evenly (every 24 minutes average)
+    // 10 heartbeats distributed evenly (every 24 minutes average in a 4h window)
     for i in 0..10u64 {
-        let slot = (base_slot + i * 24) % 240;
-        let time = current_4h_window + (slot * 60) + slot_offset;
+        let slot = (base_slot + i * slots / 10) % slots;
+        let time = current_4h_window + (slot * crate::network_config::PING_SLOT_SECS) + slot_offset;
         times.push(time);
     }
     
@@ -9640,84 +10245,32 @@ impl SimplifiedP2P {
         // Update last_seen for requesting peer
         self.update_peer_last_seen(from_peer);
         
-        // RATE LIMITING: Check if peer is making too many sync requests
         let current_time = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap_or_default()
             .as_secs();
         
-        // CRITICAL FIX: Adaptive rate limiting based on sync state
-        // If peer is far behind, allow unlimited sync requests for recovery
-        // ARCHITECTURE: Use REQUEST RANGE as proxy for how far behind the requester is
-        // This is MORE ACCURATE than trying to track each peer's height (which requires HTTP calls)
-        // If requester asks for blocks 1-100, they're clearly behind by at least 100 blocks
-        let blocks_behind = if to_height > from_height {
-            to_height - from_height  // Request range indicates how far behind
-        } else {
-            0
-        };
-        
-        // Check rate limit (adaptive based on sync state)
-        let rate_limited = {
-            // CRITICAL: No rate limit for nodes catching up (>5 blocks behind)
-            if blocks_behind > 5 {
-                println!("[SYNC] 🚀 PRIORITY SYNC: {} is {} blocks behind - no rate limit", 
-                         from_peer, blocks_behind);
-                false // No rate limit for catching up
-            } else {
-                // Normal rate limiting for synchronized nodes
-                // PRODUCTION: Lock-free DashMap access
-                let rate_key = format!("sync_{}", from_peer);
-                
-                let mut rate_limit = self.rate_limiter.entry(rate_key).or_insert_with(|| RateLimit {
-                    requests: Vec::new(),
-                    max_requests: 10,  // 10 sync requests per minute for normal operation
-                    window_seconds: 60,
-                    blocked_until: 0,
-                });
-                
-                // Check if currently blocked
-                if rate_limit.blocked_until > current_time {
-                    println!("[SYNC] ⛔ Rate limit: {} blocked for {} more seconds", 
-                             from_peer, rate_limit.blocked_until - current_time);
-                    return;
-                }
-                
-                // Clean old requests outside window
-                let window = rate_limit.window_seconds;
-                rate_limit.requests.retain(|&req_time| req_time > current_time - window);
-                
-                // Check if limit exceeded
-                if rate_limit.requests.len() >= rate_limit.max_requests {
-                    rate_limit.blocked_until = current_time + 60; // Block for 1 minute
-                    println!("[SYNC] ⛔ Rate limit exceeded for {} ({}+ requests/minute)", 
-                             from_peer, rate_limit.max_requests);
-                    true
-                } else {
-                    // Add this request
-                    rate_limit.requests.push(current_time);
-                    false
-                }
+        let (actual_to, continue_from) = match clamp_sync_range(from_height, to_height, self.p2p_config.max_sync_range) {
+            Some(range) => range,
+            None => {
+                println!("[SYNC] ⛔ Invalid sync range {}-{} from {}", from_height, to_height, from_peer);
+                return;
             }
         };
         
-        if rate_limited {
+        // RATE LIMITING: Peers catching up (range > 5 blocks) get a larger budget than
+        // synchronized peers, but no peer may request without limit
+        let catching_up = to_height - from_height > 5;
+        if !self.allow_sync_request(from_peer, catching_up, current_time) {
             return;
         }
         
-        // Validate request range (max 100 blocks per batch for performance)
-        let max_batch = 100;
-        let actual_to = if to_height - from_height > max_batch {
-            from_height + max_batch - 1
-        } else {
-            to_height
-        };
-        
         println!("[SYNC] 📤 Preparing blocks {}-{} for {}", from_height, actual_to, requester_id);
         
         // CRITICAL FIX: Send sync request to node.rs where storage is available
+        // The full requested range is forwarded, the node clamps it and adds the continue hint
         if let Some(ref sync_tx) = self.sync_request_tx {
-            if let Err(e) = sync_tx.send((from_height, actual_to, requester_id.clone())) {
+            if let Err(e) = sync_tx.send((from_height, to_height, requester_id.clone())) {
                 println!("[SYNC] ❌ Failed to send sync request to node: {}", e);
             } else {
                 println!("[SYNC] ✅ Sync request forwarded to node for processing");
@@ -9731,6 +10284,7 @@ impl SimplifiedP2P {
                 from_height,
                 to_height: actual_to,
                 sender_id: self.node_id.clone(),
+                continue_from,
             };
             
             // SCALABILITY FIX: Use O(1) lookup instead of O(n) find
@@ -9748,6 +10302,46 @@ impl SimplifiedP2P {
         }
     }
     
+    /// Count a block sync request from `peer` against its per-minute budget
+    /// Returns false (and blocks the peer for a minute) once the budget is spent
+    fn allow_sync_request(&self, peer: &str, catching_up: bool, current_time: u64) -> bool {
+        // PRODUCTION: Lock-free DashMap access
+        let (rate_key, max_requests) = if catching_up {
+            (format!("sync_bulk_{}", peer), self.p2p_config.sync_requests_per_min)
+        } else {
+            (format!("sync_{}", peer), 10) // 10 sync requests per minute for normal operation
+        };
+        
+        let mut rate_limit = self.rate_limiter.entry(rate_key).or_insert_with(|| RateLimit {
+            requests: Vec::new(),
+            max_requests,
+            window_seconds: 60,
+            blocked_until: 0,
+        });
+        
+        // Check if currently blocked
+        if rate_limit.blocked_until > current_time {
+            println!("[SYNC] ⛔ Rate limit: {} blocked for {} more seconds", 
+                     peer, rate_limit.blocked_until - current_time);
+            return false;
+        }
+        
+        // Clean old requests outside window
+        let window = rate_limit.window_seconds;
+        rate_limit.requests.retain(|&req_time| req_time > current_time.saturating_sub(window));
+        
+        // Check if limit exceeded
+        if rate_limit.requests.len() >= rate_limit.max_requests {
+            rate_limit.blocked_until = current_time + 60; // Block for 1 minute
+            println!("[SYNC] ⛔ Rate limit exceeded for {} ({}+ requests/minute)", 
+                     peer, rate_limit.max_requests);
+            return false;
+        }
+        
+        rate_limit.requests.push(current_time);
+        true
+    }
+    
     /// Handle blocks batch received for sync
     pub fn handle_blocks_batch(&self, blocks: Vec<(u64, Vec<u8>)>, from_height: u64, to_height: u64, sender_id: String) {
         println!("[SYNC] ✅ Processing {} blocks from {} (heights {}-{})", 
@@ -9933,9 +10527,8 @@ impl SimplifiedP2P {
             return Err("No Super/Full nodes available for macroblock sync".to_string());
         }
         
-        // Select best peer for sync (highest combined reputation)
-        let best_peer = eligible_peers.iter()
-            .max_by(|a, b| a.combined_reputation().partial_cmp(&b.combined_reputation()).unwrap())
+        // Select best peer for sync (reputation first, then measured latency)
+        let best_peer = self.select_sync_peer(eligible_peers.iter().copied())
             .ok_or("No valid peer for macroblock sync")?;
         
         println!("[MACROBLOCK-SYNC] 📡 Requesting macroblocks from peer {} (consensus: {:.1}%, network: {:.1}%)", 
@@ -10079,13 +10672,90 @@ impl SimplifiedP2P {
             return Err("No peers available for sync".to_string());
         }
         
-        // Select best peer for sync (highest combined reputation)
-        let best_peer = peers.iter()
-            .max_by(|a, b| a.combined_reputation().partial_cmp(&b.combined_reputation()).unwrap())
+        // Select best peer for sync (reputation first, then measured latency)
+        let best_peer = self.select_sync_peer(&peers)
             .ok_or("No valid peer for sync")?;
         
-        println!("[SYNC] 📡 Requesting blocks from peer {} (consensus: {:.1}%, network: {:.1}%)", 
-                 best_peer.id, best_peer.consensus_score, best_peer.network_score);
+        self.request_blocks_from(best_peer, from_height, to_height);
+        Ok(())
+    }
+    
+    /// SYNC: Restart a stalled block sync, avoiding the peer the stalled request went to
+    /// Falls back to the best available peer when no alternative exists
+    pub async fn resync_from_alternate_peer(&self, from_height: u64, to_height: u64) -> Result<String, String> {
+        let peers = self.get_validated_active_peers();
+        if peers.is_empty() {
+            return Err("No peers available for sync".to_string());
+        }
+        
+        let stalled_peer = self.last_sync_peer();
+        let peer = self.select_sync_peer(peers.iter().filter(|p| Some(&p.addr) != stalled_peer.as_ref()))
+            .or_else(|| {
+                println!("[SYNC] ⚠️ No alternative to stalled peer, retrying best available");
+                self.select_sync_peer(&peers)
+            })
+            .ok_or("No valid peer for sync")?;
+        
+        self.request_blocks_from(peer, from_height, to_height);
+        Ok(peer.id.clone())
+    }
+    
+    /// SYNC: Re-request a block that failed validation from a peer that has not sent a bad one
+    /// Returns the new peer's id; errors once the distinct-peer budget or the candidates run out
+    pub fn retry_invalid_sync_block(&self, height: u64, bad_peer: &str) -> Result<String, String> {
+        let decision = self.sync_retries.lock()
+            .map_err(|_| "Sync retry tracker poisoned".to_string())?
+            .record_invalid(height, bad_peer);
+        
+        let excluded = match decision {
+            crate::sync_retry::SyncRetry::Retry { excluded } => excluded,
+            crate::sync_retry::SyncRetry::Exhausted { bad_peers } => {
+                return Err(format!("Block #{} invalid from {} distinct peers: {}", 
+                                   height, bad_peers.len(), bad_peers.join(", ")));
+            }
+        };
+        
+        let peers = self.get_validated_active_peers();
+        let peer = match self.select_sync_peer(peers.iter().filter(|p| !excluded.contains(&p.id))) {
+            Some(peer) => peer,
+            None => {
+                let tried = self.sync_retries.lock()
+                    .map(|mut retries| retries.abandon(height))
+                    .unwrap_or(excluded);
+                return Err(format!("No untried peer left for block #{} (bad: {})", height, tried.join(", ")));
+            }
+        };
+        
+        println!("[SYNC] 🔁 Block #{} from {} failed validation, retrying from {}", height, bad_peer, peer.id);
+        self.request_blocks_from(peer, height, height);
+        Ok(peer.id.clone())
+    }
+    
+    /// SYNC: A valid block was stored at `height`, ending any retry for it
+    pub fn sync_block_accepted(&self, height: u64) {
+        let bad_peers = match self.sync_retries.lock() {
+            Ok(mut retries) => retries.record_valid(height),
+            Err(_) => return,
+        };
+        if !bad_peers.is_empty() {
+            println!("[SYNC] ✅ Block #{} recovered after invalid copies from {}", height, bad_peers.join(", "));
+        }
+    }
+    
+    /// SYNC: Address of the peer the last block sync request went to
+    pub fn last_sync_peer(&self) -> Option<String> {
+        self.last_sync_peer.read().ok().and_then(|p| p.clone())
+    }
+    
+    /// Send a RequestBlocks message to a peer and remember it as the current sync source
+    fn request_blocks_from(&self, peer: &PeerInfo, from_height: u64, to_height: u64) {
+        println!("[SYNC] 📡 Requesting blocks from peer {} (consensus: {:.1}%, network: {:.1}%, latency: {:.0}ms)", 
+                 peer.id, peer.consensus_score, peer.network_score,
+                 self.peer_latency_estimate(&peer.addr).unwrap_or(peer.latency_ms as f64));
+        
+        if let Ok(mut last) = self.last_sync_peer.write() {
+            *last = Some(peer.addr.clone());
+        }
         
         // Create request message
         let request = NetworkMessage::RequestBlocks {
@@ -10095,9 +10765,7 @@ impl SimplifiedP2P {
         };
         
         // Send request
-        self.send_network_message(&best_peer.addr, request);
-        
-        Ok(())
+        self.send_network_message(&peer.addr, request);
     }
     
     /// Batch sync for catch-up - request blocks in batches
@@ -10196,12 +10864,17 @@ pub fn get_privacy_id_for_addr(addr: &str) -> String {
 
 
 
-/// QUANTUM: Get Genesis bootstrap IPs using EXISTING genesis_constants
+/// Genesis bootstrap hosts from `QNET_BOOTSTRAP_PEERS` (Genesis IPs by default on mainnet)
 pub fn get_genesis_bootstrap_ips() -> Vec<String> {
-    // EXISTING: Use genesis_constants::GENESIS_NODE_IPS to avoid code duplication
-    use crate::genesis_constants::GENESIS_NODE_IPS;
-    GENESIS_NODE_IPS.iter()
-        .map(|(ip, _)| ip.to_string())
+    crate::network_config::get_bootstrap_peers().iter()
+        .map(|peer| peer.host.clone())
+        .collect()
+}
+
+/// Genesis bootstrap peers as dialable `host:port` addresses
+pub fn get_genesis_bootstrap_addrs() -> Vec<String> {
+    crate::network_config::get_bootstrap_peers().iter()
+        .map(|peer| peer.addr())
         .collect()
 }
 
@@ -11581,6 +12254,7 @@ impl SimplifiedP2P {
         };
         
         let total = peer_addresses.len();
+        let transport = self.transport.clone();
         tokio::spawn(async move {
             use futures::stream::{self, StreamExt};
             
@@ -11589,9 +12263,10 @@ impl SimplifiedP2P {
             let results = stream::iter(peer_addresses)
                 .map(|peer_addr| {
                     let msg = consensus_msg.clone();
+                    let transport = transport.clone();
                     async move {
                         for attempt in 1..=3 {
-                            if Self::send_consensus_message_with_retry(&peer_addr, &msg).await {
+                            if Self::send_consensus_message(transport.as_deref(), &peer_addr, &msg).await {
                                 return (peer_addr, true);
                             }
                             if attempt < 3 {
@@ -11672,6 +12347,7 @@ impl SimplifiedP2P {
         };
         
         let total = peer_addresses.len();
+        let transport = self.transport.clone();
         tokio::spawn(async move {
             use futures::stream::{self, StreamExt};
             
@@ -11680,9 +12356,10 @@ impl SimplifiedP2P {
             let results = stream::iter(peer_addresses)
                 .map(|peer_addr| {
                     let msg = consensus_msg.clone();
+                    let transport = transport.clone();
                     async move {
                         for attempt in 1..=3 {
-                            if Self::send_consensus_message_with_retry(&peer_addr, &msg).await {
+                            if Self::send_consensus_message(transport.as_deref(), &peer_addr, &msg).await {
                                 return (peer_addr, true);
                             }
                             if attempt < 3 {
@@ -11703,6 +12380,20 @@ impl SimplifiedP2P {
         Ok(())
     }
 
+    /// Send consensus message through the transport if one is installed, else over HTTP
+    async fn send_consensus_message(transport: Option<&dyn Transport>, peer_addr: &str, message: &NetworkMessage) -> bool {
+        match transport {
+            Some(transport) => match transport.send(peer_addr, message).await {
+                Ok(()) => true,
+                Err(e) => {
+                    println!("[P2P] ⚠️ Failed to send consensus to {}: {}", peer_addr, e);
+                    false
+                }
+            },
+            None => Self::send_consensus_message_with_retry(peer_addr, message).await,
+        }
+    }
+
     /// Send consensus message with retry (async for non-blocking)
     async fn send_consensus_message_with_retry(peer_addr: &str, message: &NetworkMessage) -> bool {
         use std::time::Duration;
@@ -11767,6 +12458,11 @@ impl SimplifiedP2P {
             return Ok(());
         }
         
+        if let Some(transport) = &self.transport {
+            return futures::executor::block_on(transport.send(peer_addr, &message))
+                .map_err(|e| e.to_string());
+        }
+        
         // Serialize message
         let message_json = serde_json::to_value(&message)
             .map_err(|e| format!("Failed to serialize: {}", e))?;
@@ -11834,6 +12530,15 @@ impl SimplifiedP2P {
             println!("[P2P] → Sending {} to {}", message_type, peer_addr);
         }
         
+        if let Some(transport) = self.transport.clone() {
+            tokio::spawn(async move {
+                if let Err(e) = transport.send(&peer_addr, &message).await {
+                    println!("[P2P] ❌ Transport send to {} failed: {}", peer_addr, e);
+                }
+            });
+            return;
+        }
+        
         let message_json = match serde_json::to_value(&message) {
             Ok(json) => {
                 // PRODUCTION DEBUG: Check serialization for blocks
@@ -11973,6 +12678,10 @@ impl SimplifiedP2P {
         
         println!("[CONSENSUS] ✅ Reputation check passed: {} ({:.1}%)", node_id, reputation_score * 100.0);
         
+        if !self.admit_consensus_message(round_id, &node_id, ConsensusPhase::Commit, commit_hash.as_bytes()) {
+            return;
+        }
+        
         // PRODUCTION: Send to consensus engine through channel
         if let Some(ref consensus_tx) = self.consensus_tx {
             let consensus_msg = ConsensusMessage::RemoteCommit {
@@ -12018,6 +12727,10 @@ impl SimplifiedP2P {
         
         println!("[CONSENSUS] ✅ Reputation check passed: {} ({:.1}%)", node_id, reputation_score * 100.0);
         
+        if !self.admit_consensus_message(round_id, &node_id, ConsensusPhase::Reveal, &[reveal_data.as_bytes(), b":", nonce.as_bytes()].concat()) {
+            return;
+        }
+        
         // PRODUCTION: Send to consensus engine through channel
         if let Some(ref consensus_tx) = self.consensus_tx {
             let consensus_msg = ConsensusMessage::RemoteReveal {
@@ -12041,6 +12754,31 @@ impl SimplifiedP2P {
         self.update_node_reputation(&node_id, ReputationEvent::ConsensusParticipation);
     }
     
+    /// Run a consensus message through the dedup/replay guard, returning true if it should be processed
+    /// Duplicates and out-of-window rounds are dropped; equivocation is dropped and penalized
+    fn admit_consensus_message(&self, round_id: u64, node_id: &str, phase: ConsensusPhase, payload: &[u8]) -> bool {
+        let height = LOCAL_BLOCKCHAIN_HEIGHT.load(Ordering::Relaxed);
+        let verdict = self.consensus_guard.lock().unwrap().check(round_id, node_id, phase, payload, height);
+        match verdict {
+            GuardVerdict::Accept => true,
+            GuardVerdict::Duplicate => {
+                println!("[CONSENSUS] 🔁 Ignoring duplicate {:?} from {} for round {}", phase, node_id, round_id);
+                false
+            }
+            GuardVerdict::OutOfWindow { round, current_round } => {
+                println!("[CONSENSUS] ❌ Rejecting {:?} from {} for round {} (current round {})",
+                         phase, node_id, round, current_round);
+                false
+            }
+            GuardVerdict::Equivocation(evidence) => {
+                println!("[CONSENSUS] 🚨 EQUIVOCATION: {} sent conflicting {:?} for round {} ({} vs {})",
+                         node_id, phase, evidence.round, evidence.first_digest, evidence.conflicting_digest);
+                self.update_node_reputation(node_id, ReputationEvent::MaliciousBehavior);
+                false
+            }
+        }
+    }
+    
     /// CRITICAL: Determine if consensus round is for macroblock (every 90 blocks)
     /// Microblocks use simple producer signatures, macroblocks use Byzantine consensus
     fn is_macroblock_consensus_round(&self, round_id: u64) -> bool {
@@ -13285,11 +14023,17 @@ impl SimplifiedP2P {
         let first_seen = entry.1;
         let elapsed = first_seen.elapsed();
         
-        println!("[SECURITY] ⚠️ Invalid block from {}: {} (count: {}, window: {}s)", 
-                 producer, reason, count, elapsed.as_secs());
+        // Decaying score survives restarts, the burst count above does not
+        let previous_score = self.invalid_block_score(producer);
+        let score = self.invalid_block_scores.lock()
+            .map(|mut scores| scores.record(producer, self.current_timestamp()))
+            .unwrap_or(previous_score);
+        
+        println!("[SECURITY] ⚠️ Invalid block from {}: {} (count: {}, window: {}s, score: {:.2})", 
+                 producer, reason, count, elapsed.as_secs(), score);
         
         // CRITICAL: Soft punishment with escalation
-        // 3 invalid blocks → warning + small penalty
+        // Score reaches 3 → warning + small penalty, reaches 5 → further penalty
         // 10 invalid blocks in 5 minutes → critical attack (1 year ban)
         
         if count >= 10 && elapsed < Duration::from_secs(300) {
@@ -13307,17 +14051,20 @@ impl SimplifiedP2P {
                 &format!("Repeated invalid signatures: {} blocks in {}s", count, elapsed.as_secs())
             );
             
-            // Clear tracker after ban
+            // Clear trackers after ban
             INVALID_BLOCKS_TRACKER.remove(producer);
+            if let Ok(mut scores) = self.invalid_block_scores.lock() {
+                scores.remove(producer);
+            }
             
-        } else if count == 3 {
-            // WARNING: 3 invalid blocks = possible bug or sync issue
-            println!("[SECURITY] ⚠️ WARNING: {} sent 3 invalid blocks - applying penalty", producer);
+        } else if previous_score < 3.0 && score >= 3.0 {
+            // WARNING: 3 recent invalid blocks = possible bug or sync issue
+            println!("[SECURITY] ⚠️ WARNING: {} reached invalid block score {:.2} - applying penalty", producer, score);
             self.update_node_reputation(producer, ReputationEvent::InvalidBlock);
             
-        } else if count == 5 {
-            // ESCALATION: 5 invalid blocks = suspicious behavior
-            println!("[SECURITY] ⚠️ ESCALATION: {} sent 5 invalid blocks - applying penalty", producer);
+        } else if previous_score < 5.0 && score >= 5.0 {
+            // ESCALATION: 5 recent invalid blocks = suspicious behavior
+            println!("[SECURITY] ⚠️ ESCALATION: {} reached invalid block score {:.2} - applying penalty", producer, score);
             self.update_node_reputation(producer, ReputationEvent::InvalidBlock);
         }
         
@@ -13336,6 +14083,36 @@ impl SimplifiedP2P {
         }
     }
     
+    /// Current decayed invalid-block score of a producer
+    pub fn invalid_block_score(&self, producer: &str) -> f64 {
+        self.invalid_block_scores.lock()
+            .map(|scores| scores.score(producer, self.current_timestamp()))
+            .unwrap_or(0.0)
+    }
+    
+    /// All tracked invalid-block scores, worst offender first (for monitoring)
+    pub fn get_invalid_block_scores(&self) -> (u64, Vec<(String, f64)>) {
+        match self.invalid_block_scores.lock() {
+            Ok(scores) => (scores.half_life_secs(), scores.scores(self.current_timestamp())),
+            Err(_) => (0, Vec::new()),
+        }
+    }
+    
+    /// PERSISTENCE: Serialize invalid-block scores for storage (called on shutdown)
+    pub fn export_invalid_block_scores(&self) -> Result<Vec<u8>, String> {
+        self.invalid_block_scores.lock()
+            .map_err(|_| "Invalid block scores lock poisoned".to_string())?
+            .to_bytes(self.current_timestamp())
+    }
+    
+    /// PERSISTENCE: Restore invalid-block scores saved by `export_invalid_block_scores`
+    pub fn restore_invalid_block_scores(&self, data: &[u8]) -> Result<usize, String> {
+        let mut scores = self.invalid_block_scores.lock()
+            .map_err(|_| "Invalid block scores lock poisoned".to_string())?;
+        scores.restore(data, self.current_timestamp())?;
+        Ok(scores.len())
+    }
+    
     /// Check if emergency failover is already in progress for a specific block
     /// CRITICAL: Prevents race condition where multiple nodes trigger failover simultaneously
     pub fn check_emergency_in_progress(&self, failover_key: &str) -> bool {
@@ -13630,4 +14407,185 @@ impl SimplifiedP2P {
             println!("[BLACKLIST] 🧹 Cleaned up {} expired blacklist entries", removed);
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_p2p(config: P2PConfig) -> SimplifiedP2P {
+        let mut p2p = SimplifiedP2P::new("test_node_admission".to_string(), NodeType::Super, Region::Europe, 19876);
+        p2p.set_p2p_config(config);
+        p2p
+    }
+
+    fn test_peer(index: u8, consensus_score: f64) -> PeerInfo {
+        let mut peer = SimplifiedP2P::parse_peer_address_static(&format!("peer_{}@203.0.113.{}:9876", index, index))
+            .expect("valid peer address");
+        peer.consensus_score = consensus_score;
+        peer
+    }
+
+    #[test]
+    fn test_inbound_cap_rejects_excess_and_keeps_high_reputation_peer() {
+        let p2p = test_p2p(P2PConfig { max_inbound: 3, max_outbound: 10, inbound_rate_per_sec: 100, ..P2PConfig::default() });
+
+        // High-reputation peer first, then fill the remaining slots
+        assert_eq!(p2p.accept_inbound_peer(test_peer(1, 95.0)), InboundDecision::Accepted);
+        assert_eq!(p2p.accept_inbound_peer(test_peer(2, 70.0)), InboundDecision::Accepted);
+        assert_eq!(p2p.accept_inbound_peer(test_peer(3, 70.0)), InboundDecision::Accepted);
+        assert_eq!(p2p.connection_counts(), ConnectionCounts { inbound: 3, outbound: 0 });
+
+        // Excess peers with no better reputation are rejected with a backoff hint
+        for index in 4..8 {
+            assert_eq!(
+                p2p.accept_inbound_peer(test_peer(index, 60.0)),
+                InboundDecision::Rejected { retry_after_secs: INBOUND_CAPACITY_BACKOFF_SECS }
+            );
+        }
+        assert_eq!(p2p.connection_counts().inbound, 3);
+        assert!(p2p.is_connected_addr(&test_peer(1, 95.0).addr));
+
+        // A stronger newcomer displaces a weak peer, never the high-reputation one
+        match p2p.accept_inbound_peer(test_peer(8, 90.0)) {
+            InboundDecision::Replaced { evicted } => assert_ne!(evicted, test_peer(1, 95.0).addr),
+            other => panic!("expected replacement, got {:?}", other),
+        }
+        assert_eq!(p2p.connection_counts().inbound, 3);
+        assert!(p2p.is_connected_addr(&test_peer(1, 95.0).addr));
+        assert!(p2p.is_connected_addr(&test_peer(8, 90.0).addr));
+    }
+
+    fn handshake(index: u8, protocol_version: u32, network_id: &str) -> PeerHandshake {
+        PeerHandshake {
+            protocol_version,
+            network_id: network_id.to_string(),
+            node_type: NodeType::Full,
+            node_id: format!("peer_{}", index),
+            addr: format!("203.0.113.{}:9876", index),
+            region: Region::Asia,
+        }
+    }
+
+    #[test]
+    fn test_handshake_admits_compatible_peer_and_rejects_others() {
+        let p2p = test_p2p(P2PConfig::default());
+        let network_id = crate::network_config::get_network_config().network_id.clone();
+
+        let accepted = handshake(1, crate::node::PROTOCOL_VERSION, &network_id);
+        assert_eq!(p2p.handle_handshake(accepted.clone()), Ok(InboundDecision::Accepted));
+        assert!(p2p.is_connected_addr(&accepted.addr));
+        assert_eq!(p2p.peer_handshake(&accepted.addr), Some(accepted));
+
+        let too_old = handshake(2, crate::node::MIN_COMPATIBLE_VERSION - 1, &network_id);
+        assert_eq!(
+            p2p.handle_handshake(too_old.clone()),
+            Err(HandshakeRejection::VersionTooOld { got: 0, min: crate::node::MIN_COMPATIBLE_VERSION })
+        );
+        assert!(!p2p.is_connected_addr(&too_old.addr));
+        assert!(p2p.peer_handshake(&too_old.addr).is_none());
+
+        // A peer we dialed ourselves is dropped once its handshake shows another network
+        let other_network = handshake(3, crate::node::PROTOCOL_VERSION, "qnet-other-network");
+        assert!(p2p.add_peer_safe(test_peer(3, 70.0)));
+        assert!(p2p.is_connected_addr(&other_network.addr));
+        assert!(matches!(
+            p2p.handle_handshake(other_network.clone()),
+            Err(HandshakeRejection::NetworkMismatch { .. })
+        ));
+        assert!(!p2p.is_connected_addr(&other_network.addr));
+        assert_eq!(p2p.connection_counts().inbound, 1);
+    }
+
+    #[test]
+    fn test_best_sync_peer_prefers_lower_latency_at_equal_reputation() {
+        let p2p = test_p2p(P2PConfig::default());
+        let near = test_peer(1, 90.0);
+        let far = test_peer(2, 90.0);
+
+        for _ in 0..5 {
+            p2p.record_peer_latency(&near.addr, 40);
+            p2p.record_peer_latency(&far.addr, 450);
+        }
+        // One slow outlier barely moves the smoothed estimate
+        p2p.record_peer_latency(&near.addr, 400);
+        assert!(p2p.peer_latency_estimate(&near.addr).unwrap() < 150.0);
+
+        let peers = vec![far.clone(), near.clone()];
+        assert_eq!(p2p.select_sync_peer(&peers).unwrap().addr, near.addr);
+
+        // Reputation stays dominant: a fast but less reputed peer loses
+        let fast_low_rep = test_peer(3, 75.0);
+        p2p.record_peer_latency(&fast_low_rep.addr, 1);
+        let peers = vec![fast_low_rep, far.clone()];
+        assert_eq!(p2p.select_sync_peer(&peers).unwrap().addr, far.addr);
+    }
+
+    #[test]
+    fn test_sync_requests_clamped_and_throttled() {
+        // Oversized requests are served partially with a continue hint
+        assert_eq!(clamp_sync_range(1, 5_000_000, DEFAULT_MAX_SYNC_RANGE), Some((500, Some(501))));
+        assert_eq!(clamp_sync_range(1_000, 1_499, 500), Some((1_499, None)));
+        assert_eq!(clamp_sync_range(1_000, 1_500, 500), Some((1_499, Some(1_500))));
+        assert_eq!(clamp_sync_range(10, 9, 500), None);
+        assert_eq!(clamp_sync_range(u64::MAX - 1, u64::MAX, 500), Some((u64::MAX, None)));
+
+        let p2p = test_p2p(P2PConfig { sync_requests_per_min: 3, ..P2PConfig::default() });
+        let now = 1_700_000_000;
+        for _ in 0..3 {
+            assert!(p2p.allow_sync_request("203.0.113.1:9876", true, now));
+        }
+        // Fourth catch-up request within the minute is refused and the peer stays blocked
+        assert!(!p2p.allow_sync_request("203.0.113.1:9876", true, now + 1));
+        assert!(!p2p.allow_sync_request("203.0.113.1:9876", true, now + 30));
+        assert!(p2p.allow_sync_request("203.0.113.1:9876", true, now + 62));
+
+        // Other peers keep their own budget
+        assert!(p2p.allow_sync_request("203.0.113.2:9876", true, now + 1));
+    }
+
+    #[test]
+    fn test_peer_table_report_fields_and_summary() {
+        let p2p = test_p2p(P2PConfig::default());
+
+        let mut dialed = test_peer(1, 90.0);
+        dialed.node_type = NodeType::Super;
+        dialed.region = Region::Europe;
+        dialed.network_score = 80.0;
+        dialed.last_seen = 1_700_000_000;
+        assert!(p2p.add_peer_safe(dialed.clone()));
+        p2p.record_peer_latency(&dialed.addr, 42);
+
+        let mut inbound = test_peer(2, 70.0);
+        inbound.node_type = NodeType::Full;
+        inbound.region = Region::Asia;
+        assert_eq!(p2p.accept_inbound_peer(inbound.clone()), InboundDecision::Accepted);
+
+        let report = p2p.peer_table_report();
+        assert_eq!(report.total, 2);
+        assert_eq!(report.peers[0], ConnectedPeerInfo {
+            id: dialed.id.clone(),
+            addr: dialed.addr.clone(),
+            node_type: NodeType::Super,
+            region: Region::Europe,
+            reputation: dialed.combined_reputation(),
+            consensus_score: 90.0,
+            latency_ms: 42,
+            last_seen: 1_700_000_000,
+            inbound: false,
+        });
+        assert_eq!(report.peers[1].addr, inbound.addr);
+        assert!(report.peers[1].inbound);
+        assert_eq!(report.by_region.get("Europe"), Some(&1));
+        assert_eq!(report.by_region.get("Asia"), Some(&1));
+        assert_eq!(report.by_node_type.get("Super"), Some(&1));
+        assert_eq!(report.by_node_type.get("Full"), Some(&1));
+
+        // Wire format served by the endpoint
+        let json = serde_json::to_value(&report).unwrap();
+        for field in ["id", "addr", "node_type", "region", "reputation", "consensus_score", "latency_ms", "last_seen", "inbound"] {
+            assert!(json["peers"][0].get(field).is_some(), "missing field {}", field);
+        }
+        assert_eq!(json["peers"][1]["region"], "Asia");
+    }
+}
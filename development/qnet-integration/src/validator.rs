@@ -4,6 +4,7 @@ use std::sync::Arc;
 use crate::errors::{IntegrationError, IntegrationResult};
 use crate::storage::PersistentStorage;
 use qnet_state::{Block, Transaction, TransactionType};
+use qnet_core::crypto::SignatureEnvelope;
 use sha3::{Sha3_256, Digest};
 
 /// Block and transaction validator
@@ -63,7 +64,7 @@ impl BlockValidator {
         // Skip general amount check for NodeActivation (validated in validate_transaction_type)
         let is_node_activation = matches!(tx.tx_type, TransactionType::NodeActivation { .. });
         
-        if tx.amount == 0 && !is_node_activation {
+        if tx.amount == 0 && !is_node_activation && !tx.is_cancellation() {
             return Err(IntegrationError::ValidationError("Transaction amount cannot be zero".to_string()));
         }
         
@@ -104,7 +105,10 @@ impl BlockValidator {
     /// Verify transaction signature (Ed25519 or Hybrid)
     fn verify_ed25519_signature(&self, tx: &Transaction, signature_hex: &str) -> IntegrationResult<bool> {
         // PRODUCTION: Support multiple signature formats
-        if signature_hex.starts_with("hybrid:") {
+        if SignatureEnvelope::is_enveloped(signature_hex) {
+            // Self-describing envelope - algorithm id selects the verifier
+            self.verify_enveloped_signature(tx, signature_hex)
+        } else if signature_hex.starts_with("hybrid:") {
             // Node hybrid signature (with certificate) - for consensus messages
             self.verify_hybrid_signature(tx, signature_hex)
         } else if signature_hex.starts_with("dilithium_sig_") {
@@ -116,6 +120,32 @@ impl BlockValidator {
         }
     }
     
+    /// Verify enveloped signature with the algorithm named by its tag
+    fn verify_enveloped_signature(&self, tx: &Transaction, signature: &str) -> IntegrationResult<bool> {
+        let envelope = SignatureEnvelope::parse(signature)
+            .map_err(|e| IntegrationError::ValidationError(format!("Invalid signature envelope: {}", e.message)))?;
+        
+        let pubkey_hex = tx.public_key.as_ref().ok_or_else(|| {
+            IntegrationError::ValidationError("Missing public_key in transaction - required for signature verification".to_string())
+        })?;
+        let pubkey_bytes = hex::decode(pubkey_hex)
+            .map_err(|e| IntegrationError::ValidationError(format!("Invalid public key hex: {}", e)))?;
+        
+        let message = self.create_client_signing_message(tx)?;
+        
+        match envelope.verify(&message, &pubkey_bytes) {
+            Ok(true) => {
+                println!("[VALIDATOR] ✅ {} signature verified for transaction from {}", envelope.algorithm, tx.from);
+                Ok(true)
+            }
+            Ok(false) => {
+                println!("[VALIDATOR] ❌ Invalid {} signature from {}", envelope.algorithm, tx.from);
+                Ok(false)
+            }
+            Err(e) => Err(IntegrationError::ValidationError(format!("{} verification failed: {}", envelope.algorithm, e.message))),
+        }
+    }
+    
     /// PRODUCTION: Verify Ed25519 signature with public key from transaction
     fn verify_ed25519_with_pubkey(&self, tx: &Transaction, signature_hex: &str) -> IntegrationResult<bool> {
         use ed25519_dalek::{Signature, Verifier, VerifyingKey};
@@ -334,7 +364,8 @@ impl BlockValidator {
                 if to.is_empty() {
                     return Err(IntegrationError::ValidationError("Transfer to address cannot be empty".to_string()));
                 }
-                if *amount == 0 {
+                // Zero-value self-transfers are cancellations, checked by Transaction::validate
+                if *amount == 0 && from != to {
                     return Err(IntegrationError::ValidationError("Transfer amount cannot be zero".to_string()));
                 }
                 // Check address format for testnet